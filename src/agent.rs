@@ -7,14 +7,128 @@
 use anyhow::{Context, Result};
 use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
 use std::io::{BufRead, BufReader, Read, Write};
-use std::path::PathBuf;
+use std::collections::VecDeque;
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
 
 use cctakt::stream_parser::{StreamEvent, StreamParser};
-use cctakt::debug;
+use cctakt::config::DEFAULT_MAX_OUTPUT_BYTES;
+use cctakt::{debug, AgentConfig, Issue};
+
+#[cfg(test)]
+use mockall::automock;
+
+/// Name of the Claude Code CLI binary
+pub const CLAUDE_BIN: &str = "claude";
+
+/// Error returned when the `claude` binary cannot be found
+///
+/// Spawn failures caused by a missing CLI are reported with this type so
+/// callers can tell them apart (`err.is::<ClaudeNotFound>()`) and show a
+/// single actionable message instead of one failure per task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClaudeNotFound;
+
+impl fmt::Display for ClaudeNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "claude CLI not found on PATH — install it with `npm install -g @anthropic-ai/claude-code` and run `cctakt status` to check your setup"
+        )
+    }
+}
+
+impl std::error::Error for ClaudeNotFound {}
+
+/// Find an executable named `name` in a PATH-style list of directories
+fn find_in_path(name: &str, path: &std::ffi::OsStr) -> Option<PathBuf> {
+    std::env::split_paths(path)
+        .map(|dir| dir.join(name))
+        .find(|candidate| is_executable(candidate))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file() || path.with_extension("exe").is_file() || path.with_extension("cmd").is_file()
+}
+
+/// Locate the `claude` binary on PATH
+pub fn find_claude() -> Option<PathBuf> {
+    std::env::var_os("PATH").and_then(|path| find_in_path(CLAUDE_BIN, &path))
+}
+
+/// Fail with `ClaudeNotFound` unless the `claude` binary is on PATH
+pub fn ensure_claude_available() -> Result<()> {
+    if find_claude().is_some() {
+        Ok(())
+    } else {
+        Err(ClaudeNotFound.into())
+    }
+}
+
+/// Whether an error was caused by a missing `claude` binary
+pub fn is_claude_not_found(err: &anyhow::Error) -> bool {
+    err.is::<ClaudeNotFound>()
+}
+
+/// Retry policy for spawning plan workers
+///
+/// The TUI retries on later ticks instead of sleeping (`App::pending_spawns`);
+/// other agents are started once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpawnRetry {
+    /// Extra attempts after the first failure
+    pub retries: u32,
+    /// Delay before the first retry (doubles on each further attempt)
+    pub backoff: Duration,
+}
+
+impl SpawnRetry {
+    /// Policy from the `[agent]` config section
+    pub fn from_config(config: &AgentConfig) -> Self {
+        Self {
+            retries: config.spawn_retries,
+            backoff: Duration::from_millis(config.spawn_retry_backoff_ms),
+        }
+    }
+
+    /// Delay before retry number `attempt` (0-based)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.backoff.saturating_mul(1 << attempt.min(16))
+    }
+}
+
+/// Whether a spawn error is transient (EAGAIN, EINTR, busy resource)
+///
+/// Permanent failures such as a missing `claude` binary are never retried.
+pub fn is_retryable_spawn_error(err: &anyhow::Error) -> bool {
+    if is_claude_not_found(err) {
+        return false;
+    }
+    err.chain()
+        .filter_map(|cause| cause.downcast_ref::<std::io::Error>())
+        .any(|io_err| {
+            matches!(
+                io_err.kind(),
+                std::io::ErrorKind::WouldBlock
+                    | std::io::ErrorKind::Interrupted
+                    | std::io::ErrorKind::ResourceBusy
+            )
+        })
+}
 
 /// Agent execution mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -58,15 +172,139 @@ struct InteractiveState {
 struct NonInteractiveState {
     parser: Arc<Mutex<StreamParser>>,
     child: Option<Child>,
-    output_buffer: Arc<Mutex<String>>,
+    output_buffer: Arc<Mutex<OutputBuffer>>,
+    stderr: StderrTail,
+}
+
+/// Lines of stderr kept to explain a failed worker
+pub const STDERR_TAIL_LINES: usize = 20;
+
+/// The last lines a worker wrote to stderr
+///
+/// `capture` drains the pipe on its own thread, so a worker writing a lot
+/// to stderr never blocks on a full pipe while stdout is being read.
+#[derive(Debug, Clone, Default)]
+pub struct StderrTail {
+    lines: Arc<Mutex<VecDeque<String>>>,
+    closed: Arc<AtomicBool>,
+}
+
+impl StderrTail {
+    /// Start reading `stderr` in the background; lines are also logged
+    /// under the worker's name
+    pub fn capture(stderr: impl Read + Send + 'static, name: String) -> Self {
+        let tail = Self::default();
+        let reader_tail = tail.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                debug::log_worker(&name, "stderr", &line);
+                reader_tail.push(line);
+            }
+            reader_tail.closed.store(true, Ordering::SeqCst);
+        });
+        tail
+    }
+
+    fn push(&self, line: String) {
+        let mut lines = self.lines.lock().unwrap();
+        if lines.len() == STDERR_TAIL_LINES {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// Wait up to `timeout` for the pipe to close, so lines written just
+    /// before the process exited are in
+    pub fn wait_closed(&self, timeout: Duration) {
+        let deadline = Instant::now() + timeout;
+        while !self.closed.load(Ordering::SeqCst) && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    /// `message` followed by the captured lines, if there are any
+    pub fn annotate(&self, message: &str) -> String {
+        let lines = self.lines.lock().unwrap();
+        let lines: Vec<&str> = lines.iter().map(String::as_str).filter(|l| !l.trim().is_empty()).collect();
+        if lines.is_empty() {
+            message.to_string()
+        } else {
+            format!("{message}\n{}", lines.join("\n"))
+        }
+    }
+}
+
+/// Worker output kept in memory, trimmed from the front a line at a time
+///
+/// Offsets count every byte ever written, so readers can tell what they
+/// have already seen after older output was discarded.
+#[derive(Debug)]
+struct OutputBuffer {
+    text: String,
+    /// Bytes discarded from the front so far
+    discarded: usize,
+    max_bytes: usize,
+}
+
+impl OutputBuffer {
+    fn new(max_bytes: usize) -> Self {
+        Self {
+            text: String::new(),
+            discarded: 0,
+            max_bytes,
+        }
+    }
+
+    fn push_line(&mut self, line: &str) {
+        self.text.push_str(line);
+        self.text.push('\n');
+        self.trim();
+    }
+
+    fn set_max_bytes(&mut self, max_bytes: usize) {
+        self.max_bytes = max_bytes;
+        self.trim();
+    }
+
+    /// Drop whole lines from the front until the text fits, always keeping
+    /// the newest line so a JSON event is never cut in half
+    fn trim(&mut self) {
+        let excess = self.text.len().saturating_sub(self.max_bytes);
+        if excess == 0 {
+            return;
+        }
+        let last_line = self.text[..self.text.len() - 1]
+            .rfind('\n')
+            .map_or(0, |pos| pos + 1);
+        // Search bytes: `excess - 1` may fall inside a multibyte character,
+        // but a newline byte is always on a char boundary
+        let cut = self.text.as_bytes()[excess - 1..]
+            .iter()
+            .position(|&byte| byte == b'\n')
+            .map_or(last_line, |pos| excess + pos)
+            .min(last_line);
+        self.text.drain(..cut);
+        self.discarded += cut;
+    }
+
+    /// Offset of the oldest byte still kept
+    fn start(&self) -> usize {
+        self.discarded
+    }
+
+    /// Text after `offset`, or all of it if that was already discarded,
+    /// with the offset it starts at
+    fn since(&self, offset: usize) -> (usize, &str) {
+        let start = offset.max(self.discarded);
+        let text = self.text.get(start - self.discarded..).unwrap_or_default();
+        (start, text)
+    }
 }
 
 /// Represents a single Claude Code session
 pub struct Agent {
-    #[allow(dead_code)]
-    pub id: usize,
+    pub id: u64,
     pub name: String,
-    #[allow(dead_code)]
     pub working_dir: PathBuf,
     pub status: AgentStatus,
     pub work_state: WorkState,
@@ -74,6 +312,12 @@ pub struct Agent {
     pub mode: AgentMode,
     /// Branch name associated with this agent (for workers)
     pub branch: Option<String>,
+    /// Issue this agent is working on
+    pub issue: Option<Issue>,
+    /// Worktree the agent works in, if it has its own
+    pub worktree_path: Option<PathBuf>,
+    /// Plan task this agent is running
+    pub task_id: Option<String>,
     /// Error message if failed (non-interactive only)
     pub error: Option<String>,
     /// Result text if completed (non-interactive only)
@@ -94,7 +338,10 @@ pub struct Agent {
 
 impl Agent {
     /// Create a new agent in interactive (PTY) mode
-    pub fn spawn(id: usize, name: String, working_dir: PathBuf, rows: u16, cols: u16) -> Result<Self> {
+    pub fn spawn(id: u64, name: String, working_dir: PathBuf, rows: u16, cols: u16) -> Result<Self> {
+        // portable-pty reports a missing binary as a generic error, so check up front
+        ensure_claude_available()?;
+
         let parser = Arc::new(Mutex::new(vt100::Parser::new(rows, cols, 1000)));
 
         // Setup PTY
@@ -109,7 +356,7 @@ impl Agent {
             .context("Failed to open pty")?;
 
         // Spawn Claude Code in the specified working directory (orchestrator mode)
-        let mut cmd = CommandBuilder::new("claude");
+        let mut cmd = CommandBuilder::new(CLAUDE_BIN);
         cmd.arg("--dangerously-skip-permissions");
         cmd.arg("--append-system-prompt");
         cmd.arg(
@@ -164,6 +411,9 @@ impl Agent {
             task_sent: false,
             mode: AgentMode::Interactive,
             branch: None,
+            issue: None,
+            worktree_path: None,
+            task_id: None,
             error: None,
             result: None,
             cost_usd: None,
@@ -183,7 +433,7 @@ impl Agent {
 
     /// Create a new agent in non-interactive mode
     pub fn spawn_non_interactive(
-        id: usize,
+        id: u64,
         name: String,
         working_dir: PathBuf,
         task_description: &str,
@@ -191,10 +441,10 @@ impl Agent {
         branch: Option<String>,
     ) -> Result<Self> {
         let parser = Arc::new(Mutex::new(StreamParser::new()));
-        let output_buffer = Arc::new(Mutex::new(String::new()));
+        let output_buffer = Arc::new(Mutex::new(OutputBuffer::new(DEFAULT_MAX_OUTPUT_BYTES)));
 
         // Build command
-        let mut cmd = Command::new("claude");
+        let mut cmd = Command::new(CLAUDE_BIN);
         cmd.arg("-p")
             .arg(task_description)
             .arg("--output-format")
@@ -210,7 +460,13 @@ impl Agent {
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
-        let mut child = cmd.spawn().context("Failed to spawn claude process")?;
+        let mut child = cmd.spawn().map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                anyhow::Error::new(ClaudeNotFound)
+            } else {
+                anyhow::Error::new(e).context("Failed to spawn claude process")
+            }
+        })?;
 
         let stdout = child.stdout.take().context("Failed to capture stdout")?;
         let stderr = child.stderr.take().context("Failed to capture stderr")?;
@@ -230,21 +486,16 @@ impl Agent {
                         p.feed(&format!("{}\n", line));
                     }
                     if let Ok(mut buf) = output_buffer_clone.lock() {
-                        buf.push_str(&line);
-                        buf.push('\n');
+                        buf.push_line(&line);
                     }
                 }
             }
             debug::log_worker(&worker_name, "EOF", "stdout closed");
         });
 
-        // Spawn stderr reading thread
-        std::thread::spawn(move || {
-            let reader = BufReader::new(stderr);
-            for line in reader.lines().map_while(Result::ok) {
-                eprintln!("[agent stderr] {line}");
-            }
-        });
+        // Keep the end of stderr to explain a failure (printing it would
+        // scribble over the TUI)
+        let stderr = StderrTail::capture(stderr, name.clone());
 
         Ok(Self {
             id,
@@ -255,6 +506,9 @@ impl Agent {
             task_sent: true,
             mode: AgentMode::NonInteractive,
             branch,
+            issue: None,
+            worktree_path: None,
+            task_id: None,
             error: None,
             result: None,
             cost_usd: None,
@@ -265,6 +519,7 @@ impl Agent {
                 parser,
                 child: Some(child),
                 output_buffer,
+                stderr,
             }),
             _output_thread: Some(output_thread),
         })
@@ -280,11 +535,19 @@ impl Agent {
         }
     }
 
-    /// Resize the PTY (interactive mode only)
+    /// Resize the PTY and its vt100 parser together (interactive mode only)
     pub fn resize(&mut self, cols: u16, rows: u16) {
+        // A tiny terminal can leave a pane with no room at all; vt100 needs
+        // at least one cell
+        let (cols, rows) = (cols.max(1), rows.max(1));
         if let Some(ref mut state) = self.interactive {
             {
+                // The PTY and the parser always change together, so an
+                // unchanged parser means there's nothing to do
                 let mut parser = state.parser.lock().unwrap();
+                if parser.screen().size() == (rows, cols) {
+                    return;
+                }
                 parser.set_size(rows, cols);
             }
             if let Some(ref master) = state.pty_master {
@@ -324,7 +587,10 @@ impl Agent {
                                     }
                                 }
                                 if !exit_status.success() && self.error.is_none() {
-                                    self.error = Some(format!("Process exited with status: {exit_status}"));
+                                    state.stderr.wait_closed(Duration::from_millis(200));
+                                    self.error = Some(state.stderr.annotate(&format!(
+                                        "Process exited with status: {exit_status}"
+                                    )));
                                 }
                             }
                         }
@@ -366,7 +632,7 @@ impl Agent {
             AgentMode::NonInteractive => {
                 if let Some(ref state) = self.non_interactive {
                     if let Ok(buf) = state.output_buffer.lock() {
-                        return buf.clone();
+                        return buf.text.clone();
                     }
                 }
                 String::new()
@@ -374,6 +640,40 @@ impl Agent {
         }
     }
 
+    /// Raw output (non-interactive mode) written after the first `offset`
+    /// bytes, with the offset it starts at
+    ///
+    /// Copies only the new part, so callers following a streaming worker
+    /// don't pay for the whole buffer each time. Starts later than `offset`
+    /// if that output was already discarded.
+    pub fn output_since(&self, offset: usize) -> (usize, String) {
+        self.non_interactive
+            .as_ref()
+            .and_then(|state| state.output_buffer.lock().ok())
+            .map(|buf| {
+                let (start, text) = buf.since(offset);
+                (start, text.to_string())
+            })
+            .unwrap_or((offset, String::new()))
+    }
+
+    /// Offset of the oldest output byte still kept (see `output_since`)
+    pub fn output_start(&self) -> usize {
+        self.non_interactive
+            .as_ref()
+            .and_then(|state| state.output_buffer.lock().ok())
+            .map_or(0, |buf| buf.start())
+    }
+
+    /// Cap the output kept in memory (non-interactive mode)
+    pub fn set_max_output_bytes(&mut self, max_bytes: usize) {
+        if let Some(ref state) = self.non_interactive
+            && let Ok(mut buf) = state.output_buffer.lock()
+        {
+            buf.set_max_bytes(max_bytes);
+        }
+    }
+
     /// Check work state and update based on activity
     /// Returns true if state changed to Completed
     pub fn update_work_state(&mut self, idle_threshold: Duration) -> bool {
@@ -496,29 +796,140 @@ impl Agent {
     pub fn is_error(&self) -> bool {
         self.work_state == WorkState::Completed && self.error.is_some()
     }
+
+    /// Create an agent with no backing process (for mock spawners)
+    ///
+    /// The agent stays `Running` until a test sets `status`/`error` itself.
+    #[cfg(test)]
+    pub fn detached(
+        id: u64,
+        name: String,
+        working_dir: PathBuf,
+        mode: AgentMode,
+        branch: Option<String>,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            working_dir,
+            status: AgentStatus::Running,
+            work_state: WorkState::Working,
+            task_sent: mode == AgentMode::NonInteractive,
+            mode,
+            branch,
+            issue: None,
+            worktree_path: None,
+            task_id: None,
+            error: None,
+            result: None,
+            cost_usd: None,
+            duration_ms: None,
+            num_turns: None,
+            interactive: None,
+            non_interactive: None,
+            _output_thread: None,
+        }
+    }
+}
+
+/// Trait for starting agent processes (allows mocking)
+#[cfg_attr(test, automock)]
+pub trait Spawner {
+    /// Start an interactive (PTY) agent
+    fn spawn_interactive(
+        &self,
+        id: u64,
+        name: String,
+        working_dir: PathBuf,
+        rows: u16,
+        cols: u16,
+    ) -> Result<Agent>;
+
+    /// Start a non-interactive (stream-json) worker
+    fn spawn_non_interactive(
+        &self,
+        id: u64,
+        name: String,
+        working_dir: PathBuf,
+        task_description: &str,
+        max_turns: Option<u32>,
+        branch: Option<String>,
+    ) -> Result<Agent>;
+}
+
+/// Real spawner running the `claude` CLI
+pub struct ClaudeSpawner;
+
+impl Spawner for ClaudeSpawner {
+    fn spawn_interactive(
+        &self,
+        id: u64,
+        name: String,
+        working_dir: PathBuf,
+        rows: u16,
+        cols: u16,
+    ) -> Result<Agent> {
+        Agent::spawn(id, name, working_dir, rows, cols)
+    }
+
+    fn spawn_non_interactive(
+        &self,
+        id: u64,
+        name: String,
+        working_dir: PathBuf,
+        task_description: &str,
+        max_turns: Option<u32>,
+        branch: Option<String>,
+    ) -> Result<Agent> {
+        Agent::spawn_non_interactive(id, name, working_dir, task_description, max_turns, branch)
+    }
+}
+
+/// Where an agent works, for reviewing its branch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentWorktree {
+    /// Id of the agent (stable across closing other tabs, unlike its index)
+    pub agent_id: u64,
+    pub path: PathBuf,
+    /// Branch the agent was started on, if known
+    pub branch: Option<String>,
 }
 
 /// Manages multiple agents
 pub struct AgentManager {
     agents: Vec<Agent>,
     active_index: usize,
-    next_id: usize,
+    next_id: u64,
+    max_output_bytes: usize,
+    spawner: Box<dyn Spawner>,
 }
 
 impl AgentManager {
-    /// Create a new empty AgentManager
+    /// Create a new empty AgentManager that runs the `claude` CLI
     pub fn new() -> Self {
+        Self::with_spawner(Box::new(ClaudeSpawner))
+    }
+
+    /// Create a new empty AgentManager with a custom spawner
+    pub fn with_spawner(spawner: Box<dyn Spawner>) -> Self {
         Self {
             agents: Vec::new(),
             active_index: 0,
             next_id: 1,
+            max_output_bytes: DEFAULT_MAX_OUTPUT_BYTES,
+            spawner,
         }
     }
 
+    /// Set how much output each worker keeps in memory
+    pub fn set_max_output_bytes(&mut self, max_bytes: usize) {
+        self.max_output_bytes = max_bytes;
+    }
+
     /// Add a new agent in interactive (PTY) mode
-    pub fn add(&mut self, name: String, working_dir: PathBuf, rows: u16, cols: u16) -> Result<usize> {
+    pub fn add(&mut self, name: String, working_dir: PathBuf, rows: u16, cols: u16) -> Result<u64> {
         let id = self.next_id;
-        let agent = Agent::spawn(id, name, working_dir, rows, cols)?;
+        let agent = self.spawner.spawn_interactive(id, name, working_dir, rows, cols)?;
         self.agents.push(agent);
         self.next_id += 1;
         self.active_index = self.agents.len() - 1;
@@ -533,9 +944,17 @@ impl AgentManager {
         task_description: &str,
         max_turns: Option<u32>,
         branch: Option<String>,
-    ) -> Result<usize> {
+    ) -> Result<u64> {
         let id = self.next_id;
-        let agent = Agent::spawn_non_interactive(id, name, working_dir, task_description, max_turns, branch)?;
+        let mut agent = self.spawner.spawn_non_interactive(
+            id,
+            name,
+            working_dir,
+            task_description,
+            max_turns,
+            branch,
+        )?;
+        agent.set_max_output_bytes(self.max_output_bytes);
         self.agents.push(agent);
         self.next_id += 1;
         self.active_index = self.agents.len() - 1;
@@ -547,11 +966,46 @@ impl AgentManager {
         self.agents.get(self.active_index)
     }
 
+    /// Worktree and branch of the agent at `index`
+    ///
+    /// None if the index is out of range or the agent has no worktree of
+    /// its own (the orchestrator).
+    pub fn worktree_of(&self, index: usize) -> Option<AgentWorktree> {
+        let agent = self.agents.get(index)?;
+        Some(AgentWorktree {
+            agent_id: agent.id,
+            path: agent.worktree_path.clone()?,
+            branch: agent.branch.clone(),
+        })
+    }
+
+    /// Worktree and branch of the active agent (see `worktree_of`)
+    pub fn active_worktree(&self) -> Option<AgentWorktree> {
+        self.worktree_of(self.active_index)
+    }
+
     /// Get the active agent mutably
     pub fn active_mut(&mut self) -> Option<&mut Agent> {
         self.agents.get_mut(self.active_index)
     }
 
+    /// Position of the agent with a given id
+    pub fn index_of(&self, id: u64) -> Option<usize> {
+        self.agents.iter().position(|agent| agent.id == id)
+    }
+
+    /// Get an agent by id
+    pub fn get_by_id(&self, id: u64) -> Option<&Agent> {
+        self.agents.iter().find(|agent| agent.id == id)
+    }
+
+    /// Close the agent with a given id (no-op if it's already gone)
+    pub fn close_by_id(&mut self, id: u64) {
+        if let Some(index) = self.index_of(id) {
+            self.close(index);
+        }
+    }
+
     /// Get an agent by index
     pub fn get(&self, index: usize) -> Option<&Agent> {
         self.agents.get(index)
@@ -592,6 +1046,19 @@ impl AgentManager {
         }
     }
 
+    /// Swap the agents at two indices; the active agent stays active
+    pub fn swap(&mut self, a: usize, b: usize) {
+        if a >= self.agents.len() || b >= self.agents.len() {
+            return;
+        }
+        self.agents.swap(a, b);
+        if self.active_index == a {
+            self.active_index = b;
+        } else if self.active_index == b {
+            self.active_index = a;
+        }
+    }
+
     /// Close an agent by index
     pub fn close(&mut self, index: usize) {
         if index < self.agents.len() {
@@ -611,6 +1078,18 @@ impl AgentManager {
         &self.agents
     }
 
+    /// Index of the agent running plan task `task_id`
+    pub fn find_by_task(&self, task_id: &str) -> Option<usize> {
+        self.agents
+            .iter()
+            .position(|agent| agent.task_id.as_deref() == Some(task_id))
+    }
+
+    /// The most recently added agent
+    pub fn last_mut(&mut self) -> Option<&mut Agent> {
+        self.agents.last_mut()
+    }
+
     /// Get the current active index
     pub fn active_index(&self) -> usize {
         self.active_index
@@ -706,7 +1185,6 @@ impl AgentManager {
     }
 
     /// Get the index of the active NonInteractive agent within workers list
-    #[allow(dead_code)]
     pub fn active_worker_index(&self) -> Option<usize> {
         let workers: Vec<_> = self.agents.iter()
             .enumerate()
@@ -724,7 +1202,6 @@ impl AgentManager {
     }
 
     /// Switch to a worker by its index within the workers list
-    #[allow(dead_code)]
     pub fn switch_to_worker(&mut self, worker_index: usize) {
         let workers: Vec<_> = self.agents.iter()
             .enumerate()
@@ -804,8 +1281,9 @@ impl AgentManager {
             .context("Failed to get current directory for orchestrator restart")?;
 
         // Spawn new orchestrator
-        let agent = Agent::spawn(
-            self.next_id,
+        let id = self.next_id;
+        let agent = self.spawner.spawn_interactive(
+            id,
             "Orchestrator".to_string(),
             working_dir,
             rows,
@@ -831,6 +1309,150 @@ impl Default for AgentManager {
 mod tests {
     use super::*;
 
+    // ==================== ClaudeNotFound tests ====================
+
+    #[test]
+    fn test_claude_not_found_is_detectable() {
+        let err: anyhow::Error = ClaudeNotFound.into();
+        assert!(is_claude_not_found(&err));
+        assert!(err.to_string().contains("cctakt status"));
+
+        let other = anyhow::anyhow!("Failed to open pty");
+        assert!(!is_claude_not_found(&other));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_in_path_requires_executable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let empty = tempfile::tempdir().unwrap();
+        let bin = tempfile::tempdir().unwrap();
+        let path = std::env::join_paths([empty.path(), bin.path()]).unwrap();
+        assert_eq!(find_in_path("claude", &path), None);
+
+        let claude = bin.path().join("claude");
+        std::fs::write(&claude, "#!/bin/sh\n").unwrap();
+        assert_eq!(find_in_path("claude", &path), None);
+
+        std::fs::set_permissions(&claude, std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(find_in_path("claude", &path), Some(claude));
+    }
+
+    // ==================== SpawnRetry tests ====================
+
+    fn eagain() -> anyhow::Error {
+        anyhow::Error::new(std::io::Error::from(std::io::ErrorKind::WouldBlock))
+            .context("Failed to open pty")
+    }
+
+    #[test]
+    fn test_retryable_spawn_errors() {
+        assert!(is_retryable_spawn_error(&eagain()));
+        assert!(!is_retryable_spawn_error(&ClaudeNotFound.into()));
+        assert!(!is_retryable_spawn_error(&anyhow::anyhow!("permission denied")));
+    }
+
+    #[test]
+    fn test_spawn_retry_backoff_doubles() {
+        let policy = SpawnRetry {
+            retries: 3,
+            backoff: Duration::from_millis(100),
+        };
+        assert_eq!(policy.delay(0), Duration::from_millis(100));
+        assert_eq!(policy.delay(1), Duration::from_millis(200));
+        assert_eq!(policy.delay(2), Duration::from_millis(400));
+    }
+
+    // ==================== Spawner tests ====================
+
+    #[test]
+    fn test_agent_manager_uses_injected_spawner() {
+        let mut spawner = MockSpawner::new();
+        spawner
+            .expect_spawn_non_interactive()
+            .withf(|_, name, _, task, turns, _| name == "w" && task == "do it" && *turns == Some(3))
+            .times(1)
+            .returning(|id, name, dir, _, _, branch| {
+                Ok(Agent::detached(id, name, dir, AgentMode::NonInteractive, branch))
+            });
+        let mut manager = AgentManager::with_spawner(Box::new(spawner));
+
+        let id = manager
+            .add_non_interactive("w".to_string(), PathBuf::from("."), "do it", Some(3), Some("b".to_string()))
+            .unwrap();
+        assert_eq!(id, 1);
+        assert_eq!(manager.len(), 1);
+        assert_eq!(manager.get(0).unwrap().branch.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn test_agent_manager_spawn_failure_adds_nothing() {
+        let mut spawner = MockSpawner::new();
+        spawner
+            .expect_spawn_interactive()
+            .returning(|_, _, _, _, _| Err(ClaudeNotFound.into()));
+        let mut manager = AgentManager::with_spawner(Box::new(spawner));
+
+        let err = manager.add("o".to_string(), PathBuf::from("."), 24, 80).unwrap_err();
+        assert!(is_claude_not_found(&err));
+        assert!(manager.is_empty());
+    }
+
+    #[test]
+    fn test_agent_manager_ids_survive_closing() {
+        let mut spawner = MockSpawner::new();
+        spawner.expect_spawn_non_interactive().returning(|id, name, dir, _, _, branch| {
+            Ok(Agent::detached(id, name, dir, AgentMode::NonInteractive, branch))
+        });
+        let mut manager = AgentManager::with_spawner(Box::new(spawner));
+        let ids: Vec<u64> = ["a", "b", "c"]
+            .into_iter()
+            .map(|name| {
+                manager
+                    .add_non_interactive(name.to_string(), PathBuf::from("."), "task", None, None)
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(ids, [1, 2, 3]);
+
+        manager.close_by_id(ids[0]);
+        assert_eq!(manager.index_of(ids[2]), Some(1));
+        assert_eq!(manager.get_by_id(ids[2]).unwrap().name, "c");
+        assert!(manager.get_by_id(ids[0]).is_none());
+
+        manager.close_by_id(ids[0]);
+        assert_eq!(manager.len(), 2);
+
+        let id = manager
+            .add_non_interactive("d".to_string(), PathBuf::from("."), "task", None, None)
+            .unwrap();
+        assert_eq!(id, 4);
+    }
+
+    #[test]
+    fn test_agent_manager_swap_keeps_active_agent() {
+        let mut spawner = MockSpawner::new();
+        spawner.expect_spawn_non_interactive().returning(|id, name, dir, _, _, branch| {
+            Ok(Agent::detached(id, name, dir, AgentMode::NonInteractive, branch))
+        });
+        let mut manager = AgentManager::with_spawner(Box::new(spawner));
+        for name in ["a", "b", "c"] {
+            manager
+                .add_non_interactive(name.to_string(), PathBuf::from("."), "task", None, None)
+                .unwrap();
+        }
+        manager.switch_to(0);
+
+        manager.swap(0, 2);
+        let names: Vec<_> = manager.list().iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["c", "b", "a"]);
+        assert_eq!(manager.active().unwrap().name, "a");
+
+        manager.swap(1, 3);
+        assert_eq!(manager.get(1).unwrap().name, "b");
+    }
+
     // ==================== AgentMode tests ====================
 
     #[test]
@@ -918,6 +1540,39 @@ mod tests {
         assert!(manager.active().is_none());
     }
 
+    #[test]
+    fn test_agent_manager_active_worktree() {
+        let mut spawner = MockSpawner::new();
+        spawner.expect_spawn_non_interactive().returning(|id, name, dir, _, _, branch| {
+            Ok(Agent::detached(id, name, dir, AgentMode::NonInteractive, branch))
+        });
+        let mut manager = AgentManager::with_spawner(Box::new(spawner));
+        assert!(manager.active_worktree().is_none());
+
+        // No worktree of its own, like the orchestrator
+        manager
+            .add_non_interactive("main".to_string(), PathBuf::from("."), "task", None, None)
+            .unwrap();
+        assert!(manager.active_worktree().is_none());
+
+        let id = manager
+            .add_non_interactive("w".to_string(), PathBuf::from("/wt/a"), "task", None, Some("feat/a".to_string()))
+            .unwrap();
+        manager.last_mut().unwrap().worktree_path = Some(PathBuf::from("/wt/a"));
+        let expected = AgentWorktree {
+            agent_id: id,
+            path: PathBuf::from("/wt/a"),
+            branch: Some("feat/a".to_string()),
+        };
+        assert_eq!(manager.active_worktree(), Some(expected.clone()));
+        assert_eq!(manager.worktree_of(1), Some(expected));
+
+        // Closing the worker leaves the orchestrator active, and its old index empty
+        manager.close(1);
+        assert!(manager.active_worktree().is_none());
+        assert!(manager.worktree_of(1).is_none());
+    }
+
     #[test]
     fn test_agent_manager_active_mut_empty() {
         let mut manager = AgentManager::new();
@@ -988,4 +1643,73 @@ mod tests {
         manager.resize_all(80, 24);
         assert!(manager.is_empty());
     }
+
+    #[test]
+    fn test_stderr_tail_keeps_last_lines() {
+        let text: String = (1..=30).map(|n| format!("line {n}\n")).collect();
+        let tail = StderrTail::capture(std::io::Cursor::new(text), "w1".to_string());
+        tail.wait_closed(Duration::from_secs(5));
+        let annotated = tail.annotate("Process exited with status: 1");
+        let lines: Vec<&str> = annotated.lines().collect();
+        assert_eq!(lines.len(), 1 + STDERR_TAIL_LINES);
+        assert_eq!(lines[0], "Process exited with status: 1");
+        assert_eq!(lines[1], "line 11");
+        assert_eq!(lines[STDERR_TAIL_LINES], "line 30");
+    }
+
+    #[test]
+    fn test_stderr_tail_without_output_is_just_the_message() {
+        let tail = StderrTail::capture(std::io::Cursor::new("\n  \n"), "w1".to_string());
+        tail.wait_closed(Duration::from_secs(5));
+        assert_eq!(tail.annotate("Process exited"), "Process exited");
+    }
+
+    #[test]
+    fn test_output_buffer_drops_whole_lines() {
+        let mut buf = OutputBuffer::new(10);
+        buf.push_line("aaaa");
+        buf.push_line("bbbb");
+        assert_eq!(buf.text, "aaaa\nbbbb\n");
+
+        buf.push_line("cc");
+        assert_eq!(buf.text, "bbbb\ncc\n");
+        assert_eq!(buf.start(), 5);
+    }
+
+    #[test]
+    fn test_output_buffer_keeps_an_oversized_newest_line() {
+        let mut buf = OutputBuffer::new(10);
+        buf.push_line("aa");
+        buf.push_line(r#"{"type":"assistant","message":{}}"#);
+        assert_eq!(buf.text, "{\"type\":\"assistant\",\"message\":{}}\n");
+        assert_eq!(buf.start(), 3);
+    }
+
+    #[test]
+    fn test_output_buffer_trims_multibyte_lines() {
+        let mut buf = OutputBuffer::new(8);
+        buf.push_line("ああ");
+        buf.push_line("ああ");
+        assert_eq!(buf.text, "ああ\n");
+        assert_eq!(buf.start(), 7);
+
+        let mut buf = OutputBuffer::new(6);
+        buf.push_line("🚀x");
+        buf.push_line("日本");
+        buf.push_line("🎉");
+        assert_eq!(buf.text, "🎉\n");
+    }
+
+    #[test]
+    fn test_output_buffer_since_skips_discarded_output() {
+        let mut buf = OutputBuffer::new(1024);
+        buf.push_line("one");
+        buf.push_line("two");
+        assert_eq!(buf.since(4), (4, "two\n"));
+        assert_eq!(buf.since(8), (8, ""));
+
+        buf.set_max_bytes(4);
+        buf.push_line("six");
+        assert_eq!(buf.since(4), (8, "six\n"));
+    }
 }
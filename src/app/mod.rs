@@ -2,30 +2,65 @@
 
 pub mod types;
 
-pub use types::{AppMode, FocusedPane, InputMode, MergeQueue, MergeTask, Notification, ReviewFocus, ReviewState};
+pub use types::{
+    AppMode, AttentionTarget, CommitsView, FocusedPane, InputMode, MergeQueue, MergeTask, Notification, PendingSpawn,
+    ReviewComment, ReviewDiff, ReviewFocus, ReviewQueue, ReviewState, RunningCommand, ScreenRegions, build_task_description,
+    display_path, editor_command, EditorCommand, pull_request_text, quit_warning, review_feedback, worktree_branch_problem,
+};
 
-use crate::agent::{AgentManager, AgentStatus};
-use crate::git_utils::{detect_github_repo, get_commit_log, get_worker_commits};
+use crate::agent::{
+    is_claude_not_found, is_retryable_spawn_error, AgentManager, AgentMode, AgentStatus, SpawnRetry, WorkState,
+};
+use crate::git_utils::{
+    detect_github_repo, get_commit_details, get_commit_log, get_commits_since, get_worker_commits,
+    nonconforming_commits,
+};
+use crate::tui::keymap::Keymap;
+use crate::tui::render::{grid_cells, grid_dims, split_pane_content_sizes, GRID_CAPACITY};
+use crate::tui::log_viewer::LogViewer;
+use crate::tui::output::{OutputCache, OutputFilter, OutputLine};
 use anyhow::{Context, Result};
+use cctakt::browser::OpenUrl;
+use cctakt::github::{PullRequest, ReviewEvent, ReviewLineComment, ReviewSide};
+use cctakt::config::{MAX_SPLIT_RATIO, MIN_SPLIT_RATIO};
+use cctakt::control::{ControlCommand, ControlServer};
+use cctakt::events::{Event, EventRecord};
+use cctakt::messages::{self, Locale, Msg};
 use cctakt::{
-    available_themes, create_theme, current_theme_id, debug, render_task, set_theme,
-    Config, DiffView, GitHubClient, Issue, IssuePicker, MergeManager, Plan, PlanManager,
-    suggest_branch_name, TaskAction, TaskResult, TaskStatus, WorktreeManager,
+    available_themes, create_theme, current_theme_id, debug, fill_placeholders, render_task, set_theme,
+    BranchProtection, Config, DiffView, GateStatus, GitHubClient, DiffSide, InputDialog, Issue, IssuePicker, LineLocation, MergeManager,
+    MergeMode, UpdateOutcome, UpdateStrategy,
+    Plan, PlanManager,
+    issue_plan, issue_subtasks, load_pull_request_template, pull_request_body, suggest_branch_name_unique, agent_name, RemoveOutcome, Task, TaskAction, TaskResult, TaskStatus,
+    WorkerPrompt, WorktreeManager,
 };
+use std::collections::{HashMap, HashSet};
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+/// How long the border stays red after an error notification
+const ERROR_FLASH_DURATION: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Quiet period after the last terminal resize before PTYs are resized
+pub const RESIZE_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(100);
+
 /// Application state
 pub struct App {
     pub agent_manager: AgentManager,
     pub should_quit: bool,
+    /// Ask before quitting while work is in flight (disabled by --no-confirm)
+    pub confirm_quit: bool,
+    /// Pending quit confirmation message (shown as a dialog while set)
+    pub quit_prompt: Option<String>,
     pub content_rows: u16,
     pub content_cols: u16,
     /// Current application mode
     pub mode: AppMode,
     /// Focused pane in split view
     pub focused_pane: FocusedPane,
+    /// Tile workers in a grid instead of showing the active one alone
+    pub worker_grid: bool,
     /// Input mode (Navigation or Input)
     pub input_mode: InputMode,
     /// Configuration
@@ -34,46 +69,99 @@ pub struct App {
     pub worktree_manager: Option<WorktreeManager>,
     /// GitHub client
     pub github_client: Option<GitHubClient>,
+    /// Protection of branches on GitHub, looked up once per session
+    pub branch_protection: HashMap<String, BranchProtection>,
+    /// Branch whose protection is being looked up in the background
+    pub branch_protection_check: Option<(String, std::sync::mpsc::Receiver<BranchProtection>)>,
     /// Issue picker UI
     pub issue_picker: IssuePicker,
-    /// Current issue being worked on (per agent)
-    pub agent_issues: Vec<Option<Issue>>,
-    /// Worktree paths per agent
-    pub agent_worktrees: Vec<Option<PathBuf>>,
     /// Review state for merge review mode
     pub review_state: Option<ReviewState>,
+    /// Agents whose review was cancelled, so it isn't reopened when they end
+    pub review_dismissed: HashSet<u64>,
     /// Plan manager for orchestrator communication
     pub plan_manager: PlanManager,
     /// Current plan being executed
     pub current_plan: Option<Plan>,
-    /// Task ID to agent index mapping
-    pub task_agents: std::collections::HashMap<String, usize>,
     /// Notifications to display
     pub notifications: Vec<Notification>,
     /// Pending prompt to send to agent after it initializes (unused in non-interactive mode)
     pub pending_agent_prompt: Option<String>,
     /// Frame counter for delayed prompt sending (unused in non-interactive mode)
     pub prompt_delay_frames: u32,
+    /// Main-loop iterations since startup; drives spinner animation
+    pub frame: u64,
+    /// Parsed output of each worker, by agent ID
+    pub output_caches: HashMap<u64, OutputCache>,
+    /// Latest terminal resize (content cols, rows, when) not yet applied
+    pub pending_resize: Option<(u16, u16, std::time::Instant)>,
     /// Pending review task ID to mark as completed after merge
     pub pending_review_task_id: Option<String>,
     /// Merge queue for sequential merge processing
     pub merge_queue: MergeQueue,
+    /// Finished workers left to review in "review all pending"
+    pub review_queue: ReviewQueue,
     /// Theme picker: show picker modal
     pub show_theme_picker: bool,
     /// Theme picker: currently selected index
     pub theme_picker_index: usize,
-    /// BuildWorker agent index (None if not spawned)
-    pub build_worker_index: Option<usize>,
+    /// BuildWorker agent id (None if not spawned)
+    pub build_worker_id: Option<u64>,
     /// Branch name associated with the current build worker
     pub build_worker_branch: Option<String>,
     /// Command buffer for :command mode
     pub command_buffer: String,
+    /// RunCommand tasks currently executing
+    pub running_commands: Vec<RunningCommand>,
+    /// Plan workers waiting to retry a failed spawn
+    pub pending_spawns: Vec<PendingSpawn>,
+    /// Background check for a newer release (`check_updates`)
+    pub update_check: Option<std::sync::mpsc::Receiver<Option<String>>>,
+    /// Control socket for other tools (`control_socket`)
+    pub control: Option<ControlServer>,
+    /// Where progress events are appended (`event_log`)
+    pub event_log: Option<PathBuf>,
+    /// Event-type filter and search for the worker output view
+    pub output_filter: OutputFilter,
+    /// Screen regions from the last draw (for mouse handling)
+    pub regions: ScreenRegions,
+    /// Debug log viewer overlay state
+    pub log_viewer: LogViewer,
+    /// Global key bindings (with config overrides)
+    pub keymap: Keymap,
+    /// Mode to return to when the help overlay closes
+    pub help_return_mode: AppMode,
+    /// Help overlay scroll offset
+    pub help_scroll: u16,
+    /// Scroll offset of the plan overlay
+    pub plan_view_scroll: u16,
+    /// Index of the selected task in the plan overlay
+    pub plan_view_selected: usize,
+    /// Whether the missing `claude` CLI has already been reported
+    claude_missing_reported: bool,
+    /// Whether the user was told that worktrees are created outside the repository
+    worktree_dir_warned: bool,
+    /// Whether completion of the current plan has already been handled
+    plan_completion_handled: bool,
+    /// Ring the terminal bell after the next draw
+    pending_bell: bool,
+    /// Command line to run with the TUI suspended (a text browser or editor)
+    foreground_command: Option<Vec<String>>,
+    /// Flash the main area border until this instant
+    error_flash_until: Option<std::time::Instant>,
+    /// Receives the outcome of the running `on_plan_complete` hook
+    plan_hook: Option<std::sync::mpsc::Receiver<Result<cctakt::plan::CommandOutcome>>>,
 }
 
 impl App {
     pub fn new(rows: u16, cols: u16, config: Config) -> Self {
+        let output_filter = OutputFilter::with_tools(config.ui.show_tool_use);
+        let keymap = Keymap::from_config(&config.keybindings);
+
         // Initialize worktree manager
-        let worktree_manager = WorktreeManager::from_current_dir().ok();
+        let worktree_manager = WorktreeManager::from_current_dir()
+            .ok()
+            .map(|manager| manager.with_naming(config.worktree_naming));
 
         // Initialize GitHub client if repository is configured
         let github_client = config
@@ -82,47 +170,74 @@ impl App {
             .as_ref()
             .and_then(|repo| GitHubClient::new(repo).ok());
 
+        let mut agent_manager = AgentManager::new();
+        agent_manager.set_max_output_bytes(config.agent.max_output_bytes);
+
+        let event_log = config
+            .event_log
+            .then(|| PathBuf::from(cctakt::events::EVENTS_FILE));
+
         Self {
-            agent_manager: AgentManager::new(),
+            agent_manager,
             should_quit: false,
+            confirm_quit: true,
+            quit_prompt: None,
             content_rows: rows,
             content_cols: cols,
             mode: AppMode::Normal,
             focused_pane: FocusedPane::Right, // Default to worker pane
+            worker_grid: config.ui.worker_grid,
             input_mode: InputMode::Input,     // Default to input mode
             config,
             worktree_manager,
             github_client,
+            branch_protection: HashMap::new(),
+            branch_protection_check: None,
             issue_picker: IssuePicker::new(),
-            agent_issues: Vec::new(),
-            agent_worktrees: Vec::new(),
             review_state: None,
             plan_manager: PlanManager::current_dir(),
             current_plan: None,
-            task_agents: std::collections::HashMap::new(),
             notifications: Vec::new(),
             pending_agent_prompt: None,
             prompt_delay_frames: 0,
+            frame: 0,
+            output_caches: HashMap::new(),
+            pending_resize: None,
             pending_review_task_id: None,
+            review_dismissed: HashSet::new(),
             merge_queue: MergeQueue::new(),
+            review_queue: ReviewQueue::default(),
             show_theme_picker: false,
             theme_picker_index: 0,
-            build_worker_index: None,
+            build_worker_id: None,
             build_worker_branch: None,
             command_buffer: String::new(),
+            running_commands: Vec::new(),
+            pending_spawns: Vec::new(),
+            update_check: None,
+            control: None,
+            event_log,
+            output_filter,
+            regions: ScreenRegions::default(),
+            log_viewer: LogViewer::new(),
+            keymap,
+            help_return_mode: AppMode::Normal,
+            help_scroll: 0,
+            plan_view_scroll: 0,
+            plan_view_selected: 0,
+            claude_missing_reported: false,
+            worktree_dir_warned: false,
+            plan_completion_handled: false,
+            pending_bell: false,
+            foreground_command: None,
+            error_flash_until: None,
+            plan_hook: None,
         }
     }
 
     /// Open issue picker and fetch issues
     pub fn open_issue_picker(&mut self) {
-        if self.github_client.is_none() {
-            // Try to detect repository from git remote
-            if let Some(repo) = detect_github_repo() {
-                self.github_client = GitHubClient::new(&repo).ok();
-            }
-        }
-
-        if self.github_client.is_some() {
+        if self.ensure_github_client() {
             self.mode = AppMode::IssuePicker;
             self.add_notification(
                 "Opening issue picker...".to_string(),
@@ -137,6 +252,16 @@ impl App {
         }
     }
 
+    /// Set up the GitHub client from the git remote if it isn't configured
+    fn ensure_github_client(&mut self) -> bool {
+        if self.github_client.is_none()
+            && let Some(repo) = detect_github_repo()
+        {
+            self.github_client = GitHubClient::new(&repo).ok();
+        }
+        self.github_client.is_some()
+    }
+
     /// Open theme picker
     pub fn open_theme_picker(&mut self) {
         // Set index to current theme
@@ -150,7 +275,247 @@ impl App {
         self.mode = AppMode::ThemePicker;
     }
 
-    /// Apply selected theme and save to config
+    /// Language for worker prompts (English unless configured)
+    fn prompt_locale(&self) -> Locale {
+        Locale::for_prompts(self.config.language.as_deref())
+    }
+
+    /// Open the key binding help overlay over the current mode
+    pub fn open_help(&mut self) {
+        if self.mode != AppMode::Help {
+            self.help_return_mode = self.mode.clone();
+        }
+        self.help_scroll = 0;
+        self.mode = AppMode::Help;
+    }
+
+    /// Close the help overlay and return to the previous mode
+    pub fn close_help(&mut self) {
+        self.mode = self.help_return_mode.clone();
+    }
+
+    /// Show the current plan's tasks
+    pub fn open_plan_view(&mut self) {
+        if self.current_plan.as_ref().is_none_or(|plan| plan.tasks.is_empty()) {
+            self.add_notification("No plan loaded".to_string(), cctakt::plan::NotifyLevel::Info);
+            return;
+        }
+        self.plan_view_scroll = 0;
+        self.plan_view_selected = 0;
+        self.mode = AppMode::PlanView;
+    }
+
+    /// The first thing in an error state, most urgent first
+    ///
+    /// Failed plan tasks come first, then agents that ended with an error,
+    /// then finished workers whose branch would conflict with the base.
+    pub fn attention_target(&self) -> Option<AttentionTarget> {
+        if let Some(index) = self
+            .current_plan
+            .as_ref()
+            .and_then(|plan| plan.tasks.iter().position(|task| task.status == TaskStatus::Failed))
+        {
+            return Some(AttentionTarget::FailedTask(index));
+        }
+        let agents = self.agent_manager.list();
+        if let Some(index) = agents
+            .iter()
+            .position(|agent| agent.status == AgentStatus::Ended && agent.error.is_some())
+        {
+            return Some(AttentionTarget::FailedAgent(index));
+        }
+        (0..agents.len())
+            .filter(|&index| agents[index].status == AgentStatus::Ended)
+            .find(|&index| {
+                let Some(worktree) = self.agent_manager.worktree_of(index) else {
+                    return false;
+                };
+                let Some(branch) = worktree.branch else {
+                    return false;
+                };
+                let merger = MergeManager::new(&worktree.path);
+                merger
+                    .preview(&branch, &merger.default_branch())
+                    .is_ok_and(|preview| !preview.conflicts.is_empty())
+            })
+            .map(AttentionTarget::ConflictingBranch)
+    }
+
+    /// Jump to whatever needs attention first (see `attention_target`)
+    ///
+    /// Opens the plan overlay on a failed task, switches to an agent that
+    /// failed, or opens the review of a conflicting branch.
+    pub fn jump_to_attention(&mut self) {
+        match self.attention_target() {
+            Some(AttentionTarget::FailedTask(index)) => {
+                self.open_plan_view();
+                self.plan_view_selected = index;
+            }
+            Some(AttentionTarget::FailedAgent(index)) => {
+                let is_worker = self
+                    .agent_manager
+                    .get(index)
+                    .is_some_and(|agent| agent.mode == AgentMode::NonInteractive);
+                self.focused_pane = if is_worker { FocusedPane::Right } else { FocusedPane::Left };
+                self.agent_manager.switch_to(index);
+            }
+            Some(AttentionTarget::ConflictingBranch(index)) => {
+                self.agent_manager.switch_to(index);
+                self.focused_pane = FocusedPane::Right;
+                self.start_review(index);
+            }
+            None => self.add_notification(
+                "Nothing needs attention".to_string(),
+                cctakt::plan::NotifyLevel::Info,
+            ),
+        }
+    }
+
+    /// Id of the task selected in the plan overlay
+    pub fn selected_plan_task(&self) -> Option<String> {
+        let plan = self.current_plan.as_ref()?;
+        plan.tasks.get(self.plan_view_selected).map(|task| task.id.clone())
+    }
+
+    /// Move the plan overlay selection, staying within the task list
+    pub fn move_plan_selection(&mut self, delta: isize) {
+        let count = self.current_plan.as_ref().map_or(0, |plan| plan.tasks.len());
+        self.plan_view_selected = self
+            .plan_view_selected
+            .saturating_add_signed(delta)
+            .min(count.saturating_sub(1));
+    }
+
+    /// Put a failed task back to pending so `process_plan` runs it again
+    ///
+    /// For a worker, its ended agent, worktree and branch are removed first
+    /// so the retry starts over on the same branch name (otherwise it would
+    /// get a `-2` branch that later review and merge tasks don't know).
+    pub fn retry_task(&mut self, task_id: &str) {
+        let Some(task) = self.current_plan.as_ref().and_then(|p| p.get_task(task_id)).cloned() else {
+            return;
+        };
+        if task.status != TaskStatus::Failed {
+            self.add_notification(
+                format!("{task_id} has not failed; only failed tasks can be retried"),
+                cctakt::plan::NotifyLevel::Warning,
+            );
+            return;
+        }
+
+        if let TaskAction::CreateWorker { ref branch, .. } = task.action
+            && let Err(e) = self.discard_worker_attempt(branch)
+        {
+            self.add_notification(
+                format!("Cannot retry {task_id}: {e:#}"),
+                cctakt::plan::NotifyLevel::Error,
+            );
+            return;
+        }
+
+        if let Some(ref mut plan) = self.current_plan {
+            plan.reset_task(task_id);
+        }
+        self.save_plan();
+        debug::log_task(task_id, "failed", "pending");
+        self.add_notification(format!("Retrying {task_id}"), cctakt::plan::NotifyLevel::Info);
+    }
+
+    /// Close ended workers on `branch` and remove its worktree and branch
+    fn discard_worker_attempt(&mut self, branch: &str) -> Result<()> {
+        let ended: Vec<usize> = self
+            .agent_manager
+            .list()
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| a.status == AgentStatus::Ended && a.branch.as_deref() == Some(branch))
+            .map(|(index, _)| index)
+            .collect();
+        for index in ended.into_iter().rev() {
+            self.agent_manager.close(index);
+        }
+        self.update_agent_sizes();
+
+        let Some(ref wt_manager) = self.worktree_manager else {
+            return Ok(());
+        };
+        if let Some(path) = wt_manager.path_for_branch(branch)? {
+            if wt_manager.is_main_worktree(&path) {
+                // The worker ran in the main checkout; leave the branch first
+                let merger = MergeManager::new(&path);
+                merger.checkout(&merger.default_branch())?;
+            } else {
+                wt_manager.remove(&path, true)?;
+                debug::log_worktree("removed", &path);
+            }
+        }
+        if wt_manager.branch_exists(branch)? {
+            wt_manager.delete_branch(branch)?;
+        }
+        Ok(())
+    }
+
+    /// Mark a failed or pending task as skipped, along with pending tasks
+    /// waiting for it, so the rest of the plan can go on
+    pub fn skip_task(&mut self, task_id: &str) {
+        let Some(status) = self
+            .current_plan
+            .as_ref()
+            .and_then(|p| p.get_task(task_id))
+            .map(|t| t.status.clone())
+        else {
+            return;
+        };
+        if !matches!(status, TaskStatus::Failed | TaskStatus::Pending) {
+            self.add_notification(
+                format!("{task_id} is running or finished; only failed or pending tasks can be skipped"),
+                cctakt::plan::NotifyLevel::Warning,
+            );
+            return;
+        }
+
+        let skipped = self
+            .current_plan
+            .as_mut()
+            .map(|plan| plan.skip_with_dependents(task_id))
+            .unwrap_or_default();
+        self.save_plan();
+        let message = match skipped.len() {
+            0 | 1 => format!("Skipped {task_id}"),
+            n => format!("Skipped {task_id} and {} task(s) waiting for it", n - 1),
+        };
+        self.add_notification(message, cctakt::plan::NotifyLevel::Info);
+    }
+
+    /// Quit, or ask for confirmation first if workers, merges or plan tasks are in flight
+    pub fn request_quit(&mut self) {
+        if !self.confirm_quit {
+            self.should_quit = true;
+            return;
+        }
+
+        let workers = self
+            .agent_manager
+            .get_non_interactive_agents()
+            .iter()
+            .filter(|a| {
+                a.status == AgentStatus::Running
+                    && matches!(a.work_state, WorkState::Starting | WorkState::Working)
+            })
+            .count();
+        let merges = self.merge_queue.pending_count();
+        let pending_tasks = self.current_plan.as_ref().map_or(0, |plan| {
+            let (pending, running, _, _) = plan.count_by_status();
+            pending + running
+        });
+
+        match quit_warning(workers, merges, pending_tasks) {
+            Some(message) => self.quit_prompt = Some(message),
+            None => self.should_quit = true,
+        }
+    }
+
+    /// Apply selected theme and save to the per-user config
     pub fn apply_theme(&mut self, theme_id: &str) {
         // Set the theme
         set_theme(create_theme(theme_id));
@@ -158,8 +523,10 @@ impl App {
         // Update config
         self.config.theme = theme_id.to_string();
 
-        // Save config to file
-        if let Err(e) = self.config.save() {
+        // Save to the user file so the project config isn't touched
+        if let Err(e) =
+            Config::save_user_preference("theme", toml::Value::String(theme_id.to_string()))
+        {
             self.add_notification(
                 format!("Failed to save theme: {e}"),
                 cctakt::plan::NotifyLevel::Warning,
@@ -214,14 +581,54 @@ impl App {
         }
     }
 
+    /// Switch the main checkout to a new branch for a worker (`use_worktrees = false`)
+    ///
+    /// Refused while another worker is still running there, since switching
+    /// branches would pull its files out from under it.
+    fn checkout_in_main_repo(&self, branch: &str) -> Result<PathBuf> {
+        let wt_manager = self.worktree_manager.as_ref().context("Not in a git repository")?;
+        if let Some(agent) = self.agent_manager.list().iter().find(|agent| {
+            agent.status != AgentStatus::Ended
+                && agent.worktree_path.as_deref().is_some_and(|path| wt_manager.is_main_worktree(path))
+        }) {
+            anyhow::bail!("{} is still working in the main checkout", agent.name);
+        }
+        wt_manager.create_in_place(branch)
+    }
+
+    /// Whether `path` is the main checkout rather than a worktree
+    fn is_main_checkout(&self, path: &Path) -> bool {
+        self.worktree_manager
+            .as_ref()
+            .is_some_and(|wt_manager| wt_manager.is_main_worktree(path))
+    }
+
     /// Add a new agent from a selected issue
     pub fn add_agent_from_issue(&mut self, issue: Issue) -> Result<()> {
-        let branch_name = suggest_branch_name(&issue, &self.config.branch_prefix);
+        // Avoid colliding with a branch left over from an earlier run on this issue
+        // (same suffixing as `WorktreeManager::create`, so both agree on the name)
+        let existing_branches = self
+            .worktree_manager
+            .as_ref()
+            .and_then(|wt| wt.list_branches().ok())
+            .unwrap_or_default();
+        let branch_name = suggest_branch_name_unique(
+            &issue,
+            self.config.branch_prefix_for(&issue),
+            &self.config.branch_slug,
+            &existing_branches,
+        );
 
         // Create worktree if available
-        let (working_dir, worktree_path) = if let Some(ref wt_manager) = self.worktree_manager {
+        let (working_dir, worktree_path) = if !self.config.use_worktrees {
+            let path = self.checkout_in_main_repo(&branch_name)?;
+            (path.clone(), Some(path))
+        } else if let Some(ref wt_manager) = self.worktree_manager {
             match wt_manager.create(&branch_name, &self.config.worktree_dir) {
-                Ok(path) => (path.clone(), Some(path)),
+                Ok(path) => {
+                    self.warn_if_worktree_outside_repo(&path);
+                    (path.clone(), Some(path))
+                }
                 Err(_) => (
                     env::current_dir().context("Failed to get current directory")?,
                     None,
@@ -237,12 +644,20 @@ impl App {
         // Generate task prompt from issue
         let task_prompt = render_task(&issue);
 
-        let name = format!("#{}", issue.number);
-        self.agent_manager
+        let name = agent_name(
+            self.config.agent_name_style,
+            Some(&issue),
+            &branch_name,
+            &self.config.branch_slug,
+        );
+        let agent_id = self
+            .agent_manager
             .add_non_interactive(name, working_dir, &task_prompt, None, Some(branch_name))?;
-
-        self.agent_issues.push(Some(issue));
-        self.agent_worktrees.push(worktree_path);
+        if let Some(agent) = self.agent_manager.last_mut() {
+            agent.issue = Some(issue);
+            agent.worktree_path = worktree_path;
+        }
+        self.emit_agent_started(agent_id);
 
         // Update PTY sizes for pane split
         self.update_agent_sizes();
@@ -250,6 +665,77 @@ impl App {
         Ok(())
     }
 
+    /// Split an issue's checklist into plan tasks, one worker per unchecked item
+    ///
+    /// The tasks are added to the current plan (or a new one) and saved so
+    /// they can be inspected and edited in the plan file. Issues without a
+    /// checklist get a single worker as with `add_agent_from_issue`.
+    pub fn add_plan_from_issue(&mut self, issue: Issue) -> Result<()> {
+        let tasks = issue_subtasks(&issue, self.config.branch_prefix_for(&issue), &self.config.branch_slug);
+        if tasks.is_empty() {
+            return self.add_agent_from_issue(issue);
+        }
+
+        let count = tasks.len();
+        let plan = match self.current_plan.take() {
+            Some(plan) if !plan.is_complete() => plan,
+            _ => Plan::with_description(format!("Issue #{}: {}", issue.number, issue.title)),
+        };
+        let plan = self.current_plan.insert(plan);
+        for mut task in tasks {
+            if plan.get_task(&task.id).is_some() {
+                task.id = plan.next_task_id(&task.id);
+            }
+            plan.add_task(task);
+        }
+        self.save_plan();
+        self.add_notification(
+            format!("Issue #{} split into {count} plan tasks", issue.number),
+            cctakt::plan::NotifyLevel::Info,
+        );
+        Ok(())
+    }
+
+    /// Plan the issue's whole lifecycle (worker → review → merge → cleanup)
+    ///
+    /// The tasks are appended to an unfinished plan, or start a new one, and
+    /// run like any other plan. Refused if the plan already has the issue's tasks.
+    pub fn add_full_plan_from_issue(&mut self, issue: Issue) {
+        let issue_tasks = issue_plan(
+            std::slice::from_ref(&issue),
+            &self.config.branch_prefix,
+            &self.config.label_branch_prefixes,
+            &self.config.branch_slug,
+            self.config.worktree_naming,
+        )
+        .tasks;
+        if let Some(ref plan) = self.current_plan
+            && !plan.is_complete()
+            && issue_tasks.iter().any(|task| plan.get_task(&task.id).is_some())
+        {
+            self.add_notification(
+                format!("Issue #{} is already in the plan", issue.number),
+                cctakt::plan::NotifyLevel::Warning,
+            );
+            return;
+        }
+
+        let count = issue_tasks.len();
+        let plan = match self.current_plan.take() {
+            Some(plan) if !plan.is_complete() => plan,
+            _ => Plan::with_description(format!("Issue #{}: {}", issue.number, issue.title)),
+        };
+        let plan = self.current_plan.insert(plan);
+        for task in issue_tasks {
+            plan.add_task(task);
+        }
+        self.save_plan();
+        self.add_notification(
+            format!("Issue #{} planned: worker → review → merge → cleanup ({count} tasks)", issue.number),
+            cctakt::plan::NotifyLevel::Info,
+        );
+    }
+
     /// Add a new agent with the current directory (interactive mode for orchestrator)
     pub fn add_agent(&mut self) -> Result<()> {
         let working_dir = env::current_dir().context("Failed to get current directory")?;
@@ -269,8 +755,6 @@ impl App {
         // Use interactive mode (PTY) for manual agent creation
         self.agent_manager
             .add(display_name, working_dir, self.content_rows, self.content_cols)?;
-        self.agent_issues.push(None);
-        self.agent_worktrees.push(None);
         Ok(())
     }
 
@@ -278,16 +762,23 @@ impl App {
     pub fn close_active_agent(&mut self) {
         let index = self.agent_manager.active_index();
         self.agent_manager.close(index);
-        if index < self.agent_issues.len() {
-            self.agent_issues.remove(index);
-        }
-        if index < self.agent_worktrees.len() {
-            self.agent_worktrees.remove(index);
-        }
         // Update PTY sizes after closing (e.g., restore full width)
         self.update_agent_sizes();
     }
 
+    /// Move the active tab one position left (`-1`) or right (`1`)
+    pub fn move_active_agent(&mut self, delta: isize) {
+        let from = self.agent_manager.active_index();
+        let Some(to) = from
+            .checked_add_signed(delta)
+            .filter(|&to| to < self.agent_manager.len())
+        else {
+            return;
+        };
+
+        self.agent_manager.swap(from, to);
+    }
+
     /// Check all agents for completion and auto-transition to review mode
     pub fn check_agent_completion(&mut self) {
         use std::time::Duration;
@@ -312,6 +803,14 @@ impl App {
 
         // Second pass: handle completion (separate borrow)
         if let Some((index, name)) = completed_agent {
+            // Without a worktree (the orchestrator) there is nothing to review
+            if self.agent_manager.worktree_of(index).is_none() {
+                self.add_notification(
+                    format!("Agent '{name}' completed work"),
+                    cctakt::plan::NotifyLevel::Success,
+                );
+                return;
+            }
             self.add_notification(
                 format!("Agent '{name}' completed work. Starting review..."),
                 cctakt::plan::NotifyLevel::Success,
@@ -324,130 +823,504 @@ impl App {
     }
 
     /// Start review mode for the agent at given index
+    ///
+    /// Does nothing if there is no such agent or it has no worktree of its
+    /// own (the orchestrator).
     pub fn start_review(&mut self, agent_index: usize) {
-        // Get worktree path for this agent
-        let worktree_path = if agent_index < self.agent_worktrees.len() {
-            self.agent_worktrees[agent_index].clone()
-        } else {
-            None
-        };
-
-        let Some(worktree_path) = worktree_path else {
-            // No worktree, can't review
+        let Some(worktree) = self.agent_manager.worktree_of(agent_index) else {
             return;
         };
-
-        // Get actual branch name from git (not directory name which has / replaced with -)
-        let branch = Command::new("git")
-            .current_dir(&worktree_path)
-            .args(["branch", "--show-current"])
-            .output()
-            .ok()
-            .and_then(|o| String::from_utf8(o.stdout).ok())
-            .map(|s| s.trim().to_string())
+        let worktree_path = worktree.path;
+
+        // Look up the branch recorded for this worktree, falling back to the
+        // branch the agent was started on, then to asking git (the directory
+        // name need not match the branch)
+        let current = WorktreeManager::current_branch(&worktree_path);
+        let expected = self
+            .worktree_manager
+            .as_ref()
+            .and_then(|wt| wt.branch_for_path(&worktree_path).ok().flatten())
+            .or(worktree.branch);
+        let branch = expected
+            .clone()
+            .or_else(|| current.as_ref().ok().cloned().flatten())
             .unwrap_or_else(|| "unknown".to_string());
 
-        // Get main repo path
-        let repo_path = env::current_dir().unwrap_or_default();
-        let merger = MergeManager::new(&repo_path);
-
-        // Get diff
-        let diff = merger.diff(&branch).unwrap_or_default();
+        // A worker that committed on the base branch or left the worktree
+        // detached would get the wrong thing merged
+        if let (Some(expected), Ok(current)) = (expected, &current) {
+            let merger = MergeManager::new(&worktree_path);
+            let base = merger.default_branch();
+            let ahead = merger.commits_ahead(&expected, &base).ok();
+            if let Some(problem) = worktree_branch_problem(&expected, current.as_deref(), &base, ahead) {
+                let home = env::var_os("HOME").map(PathBuf::from);
+                let shown = display_path(&worktree_path, home.as_deref());
+                self.add_notification(
+                    format!("Not reviewing {shown}: {problem}"),
+                    cctakt::plan::NotifyLevel::Warning,
+                );
+                // Don't retry every frame; the message says how to review it later
+                self.review_dismissed.insert(worktree.agent_id);
+                return;
+            }
+        }
 
-        // Get commit log
-        let commit_log = get_commit_log(&worktree_path);
+        self.start_review_for_branch(&branch, &worktree_path, None);
+        if let Some(ref mut state) = self.review_state {
+            state.agent_id = Some(worktree.agent_id);
+        }
+    }
 
-        // Get merge preview
-        let preview = merger.preview(&branch).ok();
-        let (files_changed, insertions, deletions, conflicts) = match preview {
-            Some(p) => (p.files_changed, p.insertions, p.deletions, p.conflicts),
-            None => (0, 0, 0, vec![]),
+    /// Open review when the active agent has ended in its own worktree
+    ///
+    /// A review the user cancelled is not reopened for the same agent.
+    pub fn review_ended_active_agent(&mut self) {
+        if self.mode != AppMode::Normal {
+            return;
+        }
+        let ended = self
+            .agent_manager
+            .active()
+            .is_some_and(|agent| agent.status == AgentStatus::Ended);
+        let Some(worktree) = self.agent_manager.active_worktree() else {
+            return;
         };
-
-        // Create diff view
-        let diff_view = DiffView::new(diff).with_title(format!("{branch} → main"));
-
-        self.review_state = Some(ReviewState {
-            agent_index,
-            branch,
-            worktree_path,
-            diff_view,
-            commit_log,
-            files_changed,
-            insertions,
-            deletions,
-            conflicts,
-            focus: ReviewFocus::default(),
-            summary_scroll: 0,
-        });
-
-        self.mode = AppMode::ReviewMerge;
+        if ended && !self.review_dismissed.contains(&worktree.agent_id) {
+            self.start_review(self.agent_manager.active_index());
+        }
     }
 
-    /// Enqueue merge task and start MergeWorker if needed
-    pub fn enqueue_merge(&mut self) {
-        let review = self.review_state.take();
-        let Some(review) = review else {
-            self.mode = AppMode::Normal;
+    /// Review every finished worker one after another
+    ///
+    /// Each review is resolved as usual (approve, reject or skip with `q`);
+    /// approved branches are queued for merging and the next review opens
+    /// right away.
+    pub fn review_all_pending(&mut self) {
+        let ids: Vec<u64> = (0..self.agent_manager.list().len())
+            .filter(|&index| self.agent_manager.list()[index].status == AgentStatus::Ended)
+            .filter_map(|index| self.agent_manager.worktree_of(index))
+            .map(|worktree| worktree.agent_id)
+            .collect();
+        if ids.is_empty() {
+            self.add_notification(
+                "No finished workers are waiting for review".to_string(),
+                cctakt::plan::NotifyLevel::Info,
+            );
             return;
-        };
+        }
+        self.review_queue = ReviewQueue::new(ids);
+        self.start_next_queued_review();
+    }
 
-        // Close the worker agent (the implementation tab disappears)
-        if review.agent_index != usize::MAX {
-            self.agent_manager.close(review.agent_index);
-            if review.agent_index < self.agent_issues.len() {
-                self.agent_issues.remove(review.agent_index);
-            }
-            if review.agent_index < self.agent_worktrees.len() {
-                self.agent_worktrees.remove(review.agent_index);
+    /// Open the next review in the queue, or report the outcome once it's empty
+    fn start_next_queued_review(&mut self) {
+        while let Some(agent_id) = self.review_queue.next() {
+            // The worker may have been closed since the queue was built
+            let Some(index) = self.agent_manager.index_of(agent_id) else {
+                continue;
+            };
+            self.agent_manager.switch_to(index);
+            self.start_review(index);
+            if self.review_state.is_some() {
+                return;
             }
-            // Update PTY sizes after closing worker
-            self.update_agent_sizes();
         }
+        if self.review_queue.is_active() {
+            let ReviewQueue { total, approved, .. } = std::mem::take(&mut self.review_queue);
+            self.add_notification(
+                format!("Reviewed {total} worker(s): {approved} approved"),
+                cctakt::plan::NotifyLevel::Info,
+            );
+        }
+    }
 
-        let task = MergeTask {
-            branch: review.branch.clone(),
-            worktree_path: review.worktree_path.clone(),
-            task_id: self.pending_review_task_id.take(),
-        };
+    /// Move on to the next queued review once the current one is resolved
+    fn continue_review_queue(&mut self) {
+        if self.review_queue.is_active() && self.review_state.is_none() {
+            self.start_next_queued_review();
+        }
+    }
 
-        let pending_count = self.merge_queue.pending_count();
-        self.merge_queue.enqueue(task);
+    /// Cancel the current review and the rest of the review queue
+    pub fn stop_review_queue(&mut self) {
+        self.review_queue = ReviewQueue::default();
+        self.cancel_review();
+    }
 
+    /// Re-check the reviewed worktree for uncommitted changes and warn if any
+    ///
+    /// Merging would silently drop them when the worktree is cleaned up, so
+    /// callers refuse to merge while this returns true.
+    fn review_has_uncommitted_changes(&mut self) -> bool {
+        let Some(ref mut review) = self.review_state else {
+            return false;
+        };
+        if let Ok(files) = WorktreeManager::dirty_files(&review.worktree_path) {
+            review.uncommitted = files;
+        }
+        let count = review.uncommitted.len();
+        if count == 0 {
+            return false;
+        }
+        let path = review.worktree_path.display().to_string();
         self.add_notification(
-            format!(
-                "Merge queued: {} (pending: {})",
-                review.branch,
-                pending_count + 1
-            ),
-            cctakt::plan::NotifyLevel::Info,
+            format!("{count} uncommitted change(s) in {path} — commit them before merging"),
+            cctakt::plan::NotifyLevel::Warning,
         );
+        true
+    }
 
-        self.mode = AppMode::Normal;
+    /// Whether merging is refused because commits break the commit convention
+    ///
+    /// Only when `enforce_commit_convention` is set; otherwise the review
+    /// header just warns about them.
+    fn review_violates_commit_convention(&mut self) -> bool {
+        if !self.config.enforce_commit_convention {
+            return false;
+        }
+        let count = self
+            .review_state
+            .as_ref()
+            .map_or(0, |review| review.nonconforming_commits.len());
+        if count == 0 {
+            return false;
+        }
+        self.add_notification(
+            format!("{count} commit(s) don't follow the commit convention — reword them before merging"),
+            cctakt::plan::NotifyLevel::Warning,
+        );
+        true
+    }
 
-        // Start processing if not already busy
-        self.process_merge_queue();
+    /// Whether the branch under review can't be merged yet (warns why)
+    fn review_blocks_merge(&mut self) -> bool {
+        self.review_has_uncommitted_changes() || self.review_violates_commit_convention()
     }
 
-    /// Process the next merge task in queue
+    /// Commits the worktree's branch has on top of `base` whose subject breaks
+    /// `commit_subject_pattern`
+    fn nonconforming_commits(&self, worktree_path: &Path, base: &str) -> Vec<String> {
+        // An invalid pattern is reported by `Config::validate`
+        let Ok(pattern) = regex::Regex::new(&self.config.commit_subject_pattern) else {
+            return Vec::new();
+        };
+        nonconforming_commits(&get_commits_since(worktree_path, base), &pattern)
+    }
+
+    /// Approve the branch under review according to `merge_mode`
+    pub fn approve_review(&mut self) {
+        if self.config.merge_mode == MergeMode::PullRequest {
+            if self.ensure_github_client() {
+                self.open_review_pull_request();
+                return;
+            }
+            self.add_notification(
+                "GitHub repository not configured; merging locally instead of opening a PR"
+                    .to_string(),
+                cctakt::plan::NotifyLevel::Warning,
+            );
+        } else if let Some(base) = self.review_state.as_ref().map(|review| review.base.clone()) {
+            match self.review_base_requires_pull_request(&base) {
+                None => {
+                    self.add_notification(
+                        format!("Still checking whether {base} is protected; try again in a moment"),
+                        cctakt::plan::NotifyLevel::Info,
+                    );
+                    return;
+                }
+                Some(true) => {
+                    self.add_notification(
+                        format!("{base} only takes changes through pull requests; opening one instead of merging"),
+                        cctakt::plan::NotifyLevel::Warning,
+                    );
+                    self.open_review_pull_request();
+                    return;
+                }
+                Some(false) => {}
+            }
+        }
+        self.enqueue_merge();
+    }
+
+    /// Whether the review base only takes pull requests, without waiting on GitHub
+    ///
+    /// None while the lookup started when the review opened hasn't answered.
+    fn review_base_requires_pull_request(&mut self, base: &str) -> Option<bool> {
+        self.check_branch_protection();
+        if let Some(protection) = self.branch_protection.get(base) {
+            return Some(protection.requires_pull_request);
+        }
+        self.start_branch_protection_check(base);
+        self.branch_protection_check.is_none().then_some(false)
+    }
+
+    /// Look up `branch`'s protection in the background unless it is known
+    ///
+    /// Without a GitHub repository nothing is looked up and the branch counts
+    /// as unprotected.
+    fn start_branch_protection_check(&mut self, branch: &str) {
+        if self.branch_protection.contains_key(branch)
+            || self
+                .branch_protection_check
+                .as_ref()
+                .is_some_and(|(checking, _)| checking == branch)
+        {
+            return;
+        }
+        let Some(client) = self.github_client.clone() else {
+            return;
+        };
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let lookup = branch.to_string();
+        std::thread::spawn(move || {
+            let protection = client.get_branch_protection(&lookup).unwrap_or_else(|e| {
+                debug::log_fields(
+                    debug::Level::Warn,
+                    "github",
+                    "failed to check branch protection",
+                    &[("branch", &lookup), ("error", &e)],
+                );
+                BranchProtection::default()
+            });
+            let _ = sender.send(protection);
+        });
+        self.branch_protection_check = Some((branch.to_string(), receiver));
+    }
+
+    /// Cache the answer of the background branch protection lookup, if any
+    pub fn check_branch_protection(&mut self) {
+        let Some((ref branch, ref receiver)) = self.branch_protection_check else {
+            return;
+        };
+        match receiver.try_recv() {
+            Ok(protection) => {
+                self.branch_protection.insert(branch.clone(), protection);
+                self.branch_protection_check = None;
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => {}
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                self.branch_protection.insert(branch.clone(), BranchProtection::default());
+                self.branch_protection_check = None;
+            }
+        }
+    }
+
+    /// Whether branch protection on GitHub only lets `branch` change through pull requests
+    ///
+    /// Looked up once per branch. Without a GitHub repository, or when the
+    /// lookup fails, the branch counts as unprotected and merging goes ahead.
+    fn requires_pull_request(&mut self, branch: &str) -> bool {
+        if let Some(protection) = self.branch_protection.get(branch) {
+            return protection.requires_pull_request;
+        }
+        let Some(ref client) = self.github_client else {
+            return false;
+        };
+        let protection = client.get_branch_protection(branch).unwrap_or_else(|e| {
+            debug::log_fields(
+                debug::Level::Warn,
+                "github",
+                "failed to check branch protection",
+                &[("branch", &branch), ("error", &e)],
+            );
+            BranchProtection::default()
+        });
+        let required = protection.requires_pull_request;
+        self.branch_protection.insert(branch.to_string(), protection);
+        required
+    }
+
+    /// Close the worker agent whose branch was reviewed (its tab disappears)
+    fn close_reviewed_agent(&mut self, agent_id: Option<u64>) {
+        let Some(agent_id) = agent_id else {
+            return;
+        };
+        self.agent_manager.close_by_id(agent_id);
+        // Update PTY sizes after closing worker
+        self.update_agent_sizes();
+    }
+
+    /// Push the branch under review and open a pull request for it
+    ///
+    /// Stays in review mode if the push or the PR fails.
+    fn open_review_pull_request(&mut self) {
+        if self.review_blocks_merge() {
+            return;
+        }
+        let Some(review) = self.review_state.as_ref() else {
+            return;
+        };
+        let branch = review.branch.clone();
+        let base = review.base.clone();
+        let draft = review.draft_pr;
+        let commits = get_commit_details(&review.worktree_path, &base);
+
+        if let Err(e) = self.push_for_pull_request(&branch) {
+            self.add_notification(format!("{e:#}"), cctakt::plan::NotifyLevel::Error);
+            return;
+        }
+
+        let (title, generated) = pull_request_text(&branch, &commits);
+        let body = self.pull_request_body_for(&branch, None, generated.as_deref());
+        let pr = match self.create_pull_request(&branch, &title, body.as_deref(), &base, draft) {
+            Ok(pr) => pr,
+            Err(e) => {
+                self.add_notification(
+                    format!("Failed to create PR: {e}"),
+                    cctakt::plan::NotifyLevel::Error,
+                );
+                return;
+            }
+        };
+
+        if let Some(review) = self.review_state.take() {
+            self.close_reviewed_agent(review.agent_id);
+        }
+        if let Some(task_id) = self.pending_review_task_id.take() {
+            let result = TaskResult {
+                commits: Vec::new(),
+                pr_number: Some(pr.number),
+                pr_url: Some(pr.html_url),
+                output: None,
+            };
+            self.complete_task(&task_id, Some(result));
+        }
+        self.mode = AppMode::Normal;
+        self.review_queue.approved += 1;
+        self.continue_review_queue();
+    }
+
+    /// Enqueue merge task and start MergeWorker if needed
+    pub fn enqueue_merge(&mut self) {
+        if self.review_blocks_merge() {
+            return;
+        }
+
+        // A worker without a worktree leaves its branch checked out in the
+        // main checkout; the merge happens on the review base
+        if let Some((path, base)) = self
+            .review_state
+            .as_ref()
+            .map(|review| (review.worktree_path.clone(), review.base.clone()))
+            && self.is_main_checkout(&path)
+            && let Err(e) = MergeManager::new(&path).checkout(&base)
+        {
+            self.add_notification(
+                format!("Cannot switch the main checkout back to {base}: {e:#}"),
+                cctakt::plan::NotifyLevel::Error,
+            );
+            return;
+        }
+
+        let review = self.review_state.take();
+        let Some(review) = review else {
+            self.mode = AppMode::Normal;
+            return;
+        };
+
+        self.close_reviewed_agent(review.agent_id);
+
+        let task = MergeTask {
+            branch: review.branch.clone(),
+            target: review.base.clone(),
+            worktree_path: review.worktree_path.clone(),
+            task_id: self.pending_review_task_id.take(),
+            target_head_before: None,
+        };
+
+        let pending_count = self.merge_queue.pending_count();
+        self.merge_queue.enqueue(task);
+
+        self.add_notification(
+            format!(
+                "Merge queued: {} (pending: {})",
+                review.branch,
+                pending_count + 1
+            ),
+            cctakt::plan::NotifyLevel::Info,
+        );
+
+        self.mode = AppMode::Normal;
+
+        // Start processing if not already busy
+        self.process_merge_queue();
+
+        self.review_queue.approved += 1;
+        self.continue_review_queue();
+    }
+
+    /// Process the next merge task in queue
     pub fn process_merge_queue(&mut self) {
         // Skip if already processing
         if self.merge_queue.is_busy() {
             return;
         }
 
-        // Get next task and clone branch name to avoid borrow issues
-        let branch = match self.merge_queue.start_next() {
-            Some(task) => task.branch.clone(),
-            None => return,
-        };
+        // Clean merges finish immediately, so keep going until a MergeWorker is needed
+        while let Some((branch, target)) = self
+            .merge_queue
+            .start_next()
+            .map(|task| (task.branch.clone(), task.target.clone()))
+        {
+            if self.config.auto_merge_clean
+                && let Ok(repo_path) = env::current_dir()
+                && self.merge_in_process(&repo_path, &branch, &target)
+            {
+                continue;
+            }
+
+            // Spawn MergeWorker
+            self.spawn_merge_worker(&branch, &target);
+            return;
+        }
+    }
+
+    /// Merge `branch` into `target` directly when the preview shows no
+    /// potential conflicts
+    ///
+    /// Returns true if the merge landed and the current task was completed;
+    /// false means a MergeWorker should take over.
+    fn merge_in_process(&mut self, repo_path: &Path, branch: &str, target: &str) -> bool {
+        let merger = MergeManager::new(repo_path).with_main_branch(target);
+
+        // `git merge` merges into whatever is checked out
+        if merger.current_branch().ok().as_deref() != Some(target) {
+            return false;
+        }
+        match merger.preview(branch, target) {
+            Ok(preview) if preview.conflicts.is_empty() => {}
+            _ => return false,
+        }
+
+        // Signing could prompt for a passphrase and stall the TUI
+        if merger.signing_enabled() {
+            self.add_notification(
+                format!("Commit signing is enabled; merging {branch} with a MergeWorker"),
+                cctakt::plan::NotifyLevel::Info,
+            );
+            return false;
+        }
+
+        if let Err(e) = merger.merge_no_ff(branch, None) {
+            debug::log_fields(
+                debug::Level::Warn,
+                "merge",
+                "in-process merge failed, falling back to MergeWorker",
+                &[("branch", &branch), ("error", &e)],
+            );
+            let _ = merger.abort();
+            return false;
+        }
 
-        // Spawn MergeWorker
-        self.spawn_merge_worker(&branch);
+        debug::log_fields(debug::Level::Info, "merge", "merged in-process", &[("branch", &branch)]);
+        if let Some(task) = self.merge_queue.current.take() {
+            self.handle_merge_success(&task);
+        }
+        true
     }
 
     /// Spawn MergeWorker to execute merge
-    fn spawn_merge_worker(&mut self, branch: &str) {
+    fn spawn_merge_worker(&mut self, branch: &str, target: &str) {
         let repo_path = match env::current_dir() {
             Ok(p) => p,
             Err(e) => {
@@ -460,16 +1333,26 @@ impl App {
             }
         };
 
-        let task_description = format!(
-            "mainブランチに {} をマージしてください。\n\n\
-             手順:\n\
-             1. git checkout main\n\
-             2. git pull origin main (最新を取得)\n\
-             3. git merge --no-ff {}\n\
-             4. コンフリクトがあれば解決してコミット\n\n\
-             重要: マージコミットを必ず作成してください。",
-            branch, branch
+        // Remember where the target was, to tell "merged" from "nothing happened"
+        let target_head = MergeManager::new(&repo_path).rev_parse(target).ok();
+        if let Some(ref mut task) = self.merge_queue.current {
+            task.target_head_before = target_head;
+        }
+
+        let custom = WorkerPrompt::Merge.load(
+            self.config.prompts.merge_worker.as_deref(),
+            &repo_path.join(".cctakt"),
         );
+        let task_description = match custom {
+            Some(template) => {
+                fill_placeholders(&template, &[("branch", branch), ("main_branch", target)])
+            }
+            None => messages::format(
+                self.prompt_locale(),
+                Msg::MergeWorkerTask,
+                &[("branch", branch), ("target", target)],
+            ),
+        };
 
         match self.agent_manager.add_non_interactive(
             "merge-worker".to_string(),
@@ -479,9 +1362,8 @@ impl App {
             Some(branch.to_string()),
         ) {
             Ok(agent_id) => {
-                // Find the agent index (it's the last one added)
-                let agent_index = self.agent_manager.len() - 1;
-                self.merge_queue.worker_agent_index = Some(agent_index);
+                self.merge_queue.worker_agent_id = Some(agent_id);
+                self.emit_agent_started(agent_id);
                 // Update PTY sizes for pane split
                 self.update_agent_sizes();
                 self.add_notification(
@@ -490,10 +1372,7 @@ impl App {
                 );
             }
             Err(e) => {
-                self.add_notification(
-                    format!("Failed to start MergeWorker: {e}"),
-                    cctakt::plan::NotifyLevel::Error,
-                );
+                self.notify_spawn_error("MergeWorker", &e);
                 self.merge_queue.complete_current();
             }
         }
@@ -501,11 +1380,11 @@ impl App {
 
     /// Check MergeWorker completion and handle result
     pub fn check_merge_worker_completion(&mut self) {
-        let Some(worker_idx) = self.merge_queue.worker_agent_index else {
+        let Some(worker_id) = self.merge_queue.worker_agent_id else {
             return;
         };
 
-        let Some(agent) = self.agent_manager.get(worker_idx) else {
+        let Some(agent) = self.agent_manager.get_by_id(worker_id) else {
             return;
         };
 
@@ -519,40 +1398,48 @@ impl App {
             None => return,
         };
 
-        // Check merge result by looking at git log
+        // Check merge result: the branch tip must now be reachable from the target
         let repo_path = match env::current_dir() {
             Ok(p) => p,
             Err(_) => {
-                self.handle_merge_failure(&task);
-                self.merge_queue.worker_agent_index = None;
+                self.handle_merge_failure(&task, "could not determine repository path");
+                self.merge_queue.worker_agent_id = None;
                 self.process_merge_queue();
                 return;
             }
         };
 
-        // Check if branch was merged by looking for the branch in git log
-        let merged = std::process::Command::new("git")
-            .args([
-                "log",
-                "--oneline",
-                "-1",
-                "--grep",
-                &format!("Merge branch '{}'", task.branch),
-            ])
-            .current_dir(&repo_path)
-            .output()
-            .map(|o| !o.stdout.is_empty())
-            .unwrap_or(false);
+        let merger = MergeManager::new(&repo_path).with_main_branch(&task.target);
+        let target_head_after = merger.rev_parse(&task.target).ok();
+        debug::log_fields(
+            debug::Level::Info,
+            "merge",
+            "MergeWorker ended",
+            &[
+                ("branch", &task.branch),
+                ("target", &task.target),
+                ("target_before", &task.target_head_before.as_deref().unwrap_or("?")),
+                ("target_after", &target_head_after.as_deref().unwrap_or("?")),
+            ],
+        );
 
-        if merged {
-            self.handle_merge_success(&task);
-        } else {
-            self.handle_merge_failure(&task);
+        match merger.is_merged(&task.branch) {
+            Ok(true) => self.handle_merge_success(&task),
+            Ok(false) if task.target_head_before.is_some()
+                && task.target_head_before == target_head_after =>
+            {
+                self.handle_merge_failure(&task, &format!("{} did not change", task.target));
+            }
+            Ok(false) => self.handle_merge_failure(
+                &task,
+                &format!("branch is not reachable from {}", task.target),
+            ),
+            Err(e) => self.handle_merge_failure(&task, &e.to_string()),
         }
 
         // Close MergeWorker agent
-        self.agent_manager.close(worker_idx);
-        self.merge_queue.worker_agent_index = None;
+        self.agent_manager.close_by_id(worker_id);
+        self.merge_queue.worker_agent_id = None;
         // Update PTY sizes after closing worker
         self.update_agent_sizes();
 
@@ -563,45 +1450,77 @@ impl App {
     /// Handle successful merge
     fn handle_merge_success(&mut self, task: &MergeTask) {
         self.add_notification(
-            format!("Merged: {} → main", task.branch),
+            format!("Merged: {} → {}", task.branch, task.target),
             cctakt::plan::NotifyLevel::Success,
         );
 
-        // Remove worktree
-        if let Some(ref wt_manager) = self.worktree_manager {
-            let _ = wt_manager.remove(&task.worktree_path);
+        if self.config.push.after_merge {
+            self.push_merge_target(&task.target);
+        }
+
+        // Remove worktree (the branch is merged, so leftover changes can go)
+        if self.is_main_checkout(&task.worktree_path) {
+            // The worker ran in the main checkout; there is no worktree to remove
+        } else if !self.config.cleanup_after_merge {
+            self.add_notification(
+                format!(
+                    "Worktree kept at {} (`cctakt worktree remove {}` removes it)",
+                    task.worktree_path.display(),
+                    task.branch
+                ),
+                cctakt::plan::NotifyLevel::Info,
+            );
+        } else if let Some(ref wt_manager) = self.worktree_manager
+            && let Err(e) = wt_manager.remove(&task.worktree_path, true)
+        {
+            debug::log_fields(
+                debug::Level::Warn,
+                "worktree",
+                "failed to remove worktree after merge",
+                &[("path", &task.worktree_path.display()), ("error", &e)],
+            );
         }
 
         // Note: Worker agent is already closed in enqueue_merge()
 
         // Mark task as completed
         if let Some(ref task_id) = task.task_id {
-            if let Some(ref mut plan) = self.current_plan {
-                plan.update_status(task_id, TaskStatus::Completed);
-                let _ = self.plan_manager.save(plan);
-            }
+            self.complete_task(task_id, None);
         }
+        self.emit_event(Event::MergeSucceeded {
+            branch: task.branch.clone(),
+            target: task.target.clone(),
+            task_id: task.task_id.clone(),
+        });
 
         // Automatically run build (no confirmation dialog)
         self.spawn_build_worker(task.branch.clone());
     }
 
+    /// Push the branch a local merge landed on to origin
+    fn push_merge_target(&mut self, target: &str) {
+        let pushed = env::current_dir()
+            .map_err(anyhow::Error::from)
+            .and_then(|repo_path| MergeManager::new(repo_path).push_branch(target, false));
+        match pushed {
+            Ok(()) => self.add_notification(
+                format!("Pushed {target} to origin"),
+                cctakt::plan::NotifyLevel::Info,
+            ),
+            Err(e) => self.add_notification(format!("{e:#}"), cctakt::plan::NotifyLevel::Error),
+        }
+    }
+
     /// Handle failed merge
-    fn handle_merge_failure(&mut self, task: &MergeTask) {
+    fn handle_merge_failure(&mut self, task: &MergeTask, reason: &str) {
         self.add_notification(
-            format!(
-                "Merge failed: {} (MergeWorker could not complete)",
-                task.branch
-            ),
+            format!("Merge failed: {} ({reason})", task.branch),
             cctakt::plan::NotifyLevel::Error,
         );
 
         // Mark task as failed
         if let Some(ref task_id) = task.task_id {
-            if let Some(ref mut plan) = self.current_plan {
-                plan.mark_failed(task_id, "MergeWorker could not complete merge");
-                let _ = self.plan_manager.save(plan);
-            }
+            self.record_task_failure(task_id, &format!("MergeWorker could not complete merge: {reason}"));
         }
     }
 
@@ -618,13 +1537,30 @@ impl App {
             }
         };
 
-        let task_description = "マージ後のビルドチェックを実行してください。\n\n\
-             手順:\n\
-             1. cargo build を実行\n\
-             2. エラーがあれば修正してコミット\n\
-             3. cargo test を実行（オプション）\n\n\
-             ビルドが成功したら完了です。"
-            .to_string();
+        let commands = self.config.build_commands(&repo_path);
+        let custom = WorkerPrompt::Build.load(
+            self.config.prompts.build_worker.as_deref(),
+            &repo_path.join(".cctakt"),
+        );
+        let Some(task_description) =
+            build_task_description(&commands, &branch, self.prompt_locale(), custom.as_deref())
+        else {
+            self.add_notification(
+                "No build command configured or detected; skipping post-merge build".to_string(),
+                cctakt::plan::NotifyLevel::Info,
+            );
+            return;
+        };
+        let command_summary = [commands.build.as_deref(), commands.test.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" && ");
+        let command_summary = if custom.is_some() || command_summary.is_empty() {
+            "custom prompt".to_string()
+        } else {
+            command_summary
+        };
 
         match self.agent_manager.add_non_interactive(
             "build-worker".to_string(),
@@ -634,32 +1570,27 @@ impl App {
             Some(branch.clone()),
         ) {
             Ok(agent_id) => {
-                let agent_index = self.agent_manager.len() - 1;
-                self.build_worker_index = Some(agent_index);
+                self.build_worker_id = Some(agent_id);
                 self.build_worker_branch = Some(branch);
+                self.emit_agent_started(agent_id);
                 // Update PTY sizes for pane split
                 self.update_agent_sizes();
                 self.add_notification(
-                    format!("BuildWorker started (agent {})", agent_id),
+                    format!("BuildWorker started (agent {agent_id}): {command_summary}"),
                     cctakt::plan::NotifyLevel::Info,
                 );
             }
-            Err(e) => {
-                self.add_notification(
-                    format!("Failed to start BuildWorker: {e}"),
-                    cctakt::plan::NotifyLevel::Error,
-                );
-            }
+            Err(e) => self.notify_spawn_error("BuildWorker", &e),
         }
     }
 
     /// Check BuildWorker completion and show notification (no popup)
     pub fn check_build_worker_completion(&mut self) {
-        let Some(worker_idx) = self.build_worker_index else {
+        let Some(worker_id) = self.build_worker_id else {
             return;
         };
 
-        let Some(agent) = self.agent_manager.get(worker_idx) else {
+        let Some(agent) = self.agent_manager.get_by_id(worker_id) else {
             return;
         };
 
@@ -674,8 +1605,8 @@ impl App {
         let branch = self.build_worker_branch.take().unwrap_or_else(|| "unknown".to_string());
 
         // Close BuildWorker agent
-        self.agent_manager.close(worker_idx);
-        self.build_worker_index = None;
+        self.agent_manager.close_by_id(worker_id);
+        self.build_worker_id = None;
         // Update PTY sizes after closing worker
         self.update_agent_sizes();
 
@@ -693,10 +1624,35 @@ impl App {
         }
     }
 
+    /// Open the full commit details overlay for the branch under review
+    pub fn open_review_commits(&mut self) {
+        if let Some(ref mut state) = self.review_state {
+            let commits = get_commit_details(&state.worktree_path, &state.base);
+            state.commits_view = Some(CommitsView { commits, scroll: 0 });
+        }
+    }
+
+    /// Close the commit details overlay and return to the diff
+    pub fn close_review_commits(&mut self) {
+        if let Some(ref mut state) = self.review_state {
+            state.commits_view = None;
+        }
+    }
+
+    /// Whether the commit details overlay is open
+    pub fn review_commits_open(&self) -> bool {
+        self.review_state
+            .as_ref()
+            .is_some_and(|state| state.commits_view.is_some())
+    }
+
     /// Cancel review and return to normal mode
     pub fn cancel_review(&mut self) {
-        self.review_state = None;
+        if let Some(agent_id) = self.review_state.take().and_then(|review| review.agent_id) {
+            self.review_dismissed.insert(agent_id);
+        }
         self.mode = AppMode::Normal;
+        self.continue_review_queue();
     }
 
     /// Check for plan file changes and load
@@ -704,9 +1660,22 @@ impl App {
         if self.plan_manager.has_changes() {
             match self.plan_manager.load() {
                 Ok(Some(plan)) => {
-                    // 完了済みプランはクリア（通知なし）
+                    // 完了済みプランはクリア（設定に応じてアーカイブ、通知なし）
                     if plan.is_complete() {
                         self.current_plan = None;
+                        if self.config.archive_completed_plans
+                            && let Err(e) = self.plan_manager.archive()
+                        {
+                            debug::log_fields(
+                                debug::Level::Warn,
+                                "plan",
+                                "failed to archive completed plan",
+                                &[("error", &e)],
+                            );
+                        }
+                    } else if !self.accept_loaded_plan(&plan) {
+                        // Stop running the old plan; it no longer matches the file
+                        self.current_plan = None;
                     } else {
                         if let Some(desc) = &plan.description {
                             self.add_notification(
@@ -730,9 +1699,35 @@ impl App {
         }
     }
 
+    /// Report `Plan::validate` problems in a loaded plan; false if it has
+    /// errors and must not run
+    fn accept_loaded_plan(&mut self, plan: &Plan) -> bool {
+        let Err(problems) = plan.validate() else {
+            return true;
+        };
+        let (warnings, errors): (Vec<_>, Vec<_>) = problems.iter().partition(|p| p.is_warning());
+        for warning in warnings {
+            self.add_notification(format!("Plan: {warning}"), cctakt::plan::NotifyLevel::Warning);
+        }
+        let Some(first) = errors.first() else {
+            return true;
+        };
+        let more = match errors.len() {
+            1 => String::new(),
+            n => format!(" (and {} more; see `cctakt plan validate`)", n - 1),
+        };
+        self.add_notification(
+            format!("Plan not loaded: {first}{more}"),
+            cctakt::plan::NotifyLevel::Error,
+        );
+        false
+    }
+
     /// Process pending tasks in the current plan
     pub fn process_plan(&mut self) {
-        // First, recover orphaned running tasks (no corresponding agent)
+        self.retry_pending_spawns();
+
+        // Then recover orphaned running tasks (no corresponding agent)
         self.recover_orphaned_tasks();
 
         // Get next pending task (clone to avoid borrow issues)
@@ -752,6 +1747,111 @@ impl App {
         }
     }
 
+    /// Notify and run the `on_plan_complete` hook when the current plan
+    /// finishes
+    ///
+    /// Fires once on the transition to complete; a plan that is already
+    /// complete when loaded is cleared by `check_plan` and never fires.
+    pub fn check_plan_completion(&mut self) {
+        self.check_plan_hook();
+
+        let Some(ref plan) = self.current_plan else {
+            self.plan_completion_handled = false;
+            return;
+        };
+        if plan.tasks.is_empty() || !plan.is_complete() {
+            self.plan_completion_handled = false;
+            return;
+        }
+        if self.plan_completion_handled {
+            return;
+        }
+        self.plan_completion_handled = true;
+
+        let (_, _, completed, failed) = plan.count_by_status();
+        let env = plan.completion_env();
+        let level = if failed > 0 {
+            cctakt::plan::NotifyLevel::Error
+        } else {
+            cctakt::plan::NotifyLevel::Success
+        };
+        self.add_notification(
+            format!("Plan complete: {completed} completed, {failed} failed"),
+            level,
+        );
+        self.emit_event(Event::PlanComplete { completed, failed });
+        // A plan with failures stays loaded so its tasks can be retried
+        if self.config.archive_completed_plans && failed == 0 {
+            self.archive_plan();
+            self.current_plan = None;
+        }
+
+        let Some(command) = self.config.on_plan_complete.clone() else {
+            return;
+        };
+        let dir = match env::current_dir() {
+            Ok(dir) => dir,
+            Err(e) => {
+                self.add_notification(
+                    format!("on_plan_complete not run: {e}"),
+                    cctakt::plan::NotifyLevel::Error,
+                );
+                return;
+            }
+        };
+        debug::log(&format!("Running on_plan_complete: {command}"));
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = sender.send(cctakt::plan::run_command_with_env(&dir, &command, &env));
+        });
+        self.plan_hook = Some(receiver);
+    }
+
+    /// Move the finished plan file aside so the next plan starts fresh
+    fn archive_plan(&mut self) {
+        match self.plan_manager.archive() {
+            Ok(Some(path)) => self.add_notification(
+                format!("Plan archived to {}", path.display()),
+                cctakt::plan::NotifyLevel::Info,
+            ),
+            Ok(None) => {}
+            Err(e) => self.add_notification(
+                format!("Failed to archive plan: {e:#}"),
+                cctakt::plan::NotifyLevel::Warning,
+            ),
+        }
+    }
+
+    /// Report a failed `on_plan_complete` hook once it exits
+    fn check_plan_hook(&mut self) {
+        let Some(ref receiver) = self.plan_hook else {
+            return;
+        };
+        let error = match receiver.try_recv() {
+            Ok(Ok(outcome)) if outcome.success => None,
+            Ok(Ok(outcome)) => Some(format!(
+                "exited with {}: {}",
+                outcome
+                    .exit_code
+                    .map_or_else(|| "signal".to_string(), |code| code.to_string()),
+                cctakt::plan::output_tail(&outcome.output, 1)
+            )),
+            Ok(Err(e)) => Some(e.to_string()),
+            Err(std::sync::mpsc::TryRecvError::Empty) => return,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                Some("hook thread exited unexpectedly".to_string())
+            }
+        };
+        self.plan_hook = None;
+        if let Some(error) = error {
+            debug::log(&format!("on_plan_complete failed: {error}"));
+            self.add_notification(
+                format!("on_plan_complete failed: {error}"),
+                cctakt::plan::NotifyLevel::Warning,
+            );
+        }
+    }
+
     /// Recover orphaned running tasks (tasks marked running but no agent exists)
     fn recover_orphaned_tasks(&mut self) {
         // Find running tasks without corresponding agents
@@ -762,7 +1862,9 @@ impl App {
                 plan.tasks
                     .iter()
                     .filter(|t| t.status == TaskStatus::Running)
-                    .filter(|t| !self.task_agents.contains_key(&t.id))
+                    .filter(|t| self.agent_manager.find_by_task(&t.id).is_none())
+                    .filter(|t| !self.running_commands.iter().any(|c| c.task_id == t.id))
+                    .filter(|t| !self.pending_spawns.iter().any(|s| s.task_id == t.id))
                     .map(|t| t.id.clone())
                     .collect()
             })
@@ -826,6 +1928,9 @@ impl App {
                 base,
                 draft,
             } => {
+                if !self.check_gate(task_id, &branch) {
+                    return;
+                }
                 self.execute_create_pr(
                     task_id,
                     &branch,
@@ -836,23 +1941,66 @@ impl App {
                 );
             }
             TaskAction::MergeBranch { branch, target } => {
+                if !self.check_gate(task_id, &branch) {
+                    return;
+                }
                 self.execute_merge_branch(task_id, &branch, target.as_deref());
             }
             TaskAction::CleanupWorktree { worktree } => {
                 self.execute_cleanup_worktree(task_id, &worktree);
             }
-            TaskAction::RunCommand { worktree, command } => {
+            TaskAction::RunCommand {
+                worktree, command, ..
+            } => {
                 self.execute_run_command(task_id, &worktree, &command);
             }
             TaskAction::Notify { message, level } => {
                 self.add_notification(message, level);
+                self.complete_task(task_id, None);
+            }
+            TaskAction::RequestReview { branch, after_task } => {
+                if !self.check_gate(task_id, &branch) {
+                    return;
+                }
+                self.execute_request_review(task_id, &branch, after_task.as_deref());
+            }
+        }
+    }
+
+    /// Check RunCommand gates for a branch before running a dependent task
+    ///
+    /// Returns true if the task may proceed. While a gating command is still
+    /// pending/running the task is reset to pending; if one failed the task is skipped.
+    fn check_gate(&mut self, task_id: &str, branch: &str) -> bool {
+        let status = self
+            .current_plan
+            .as_ref()
+            .map(|p| p.gate_status(branch, task_id))
+            .unwrap_or(GateStatus::Clear);
+
+        match status {
+            GateStatus::Clear => true,
+            GateStatus::Waiting(_) => {
                 if let Some(ref mut plan) = self.current_plan {
-                    plan.update_status(task_id, TaskStatus::Completed);
+                    plan.update_status(task_id, TaskStatus::Pending);
                 }
                 self.save_plan();
+                false
             }
-            TaskAction::RequestReview { branch, after_task } => {
-                self.execute_request_review(task_id, &branch, after_task.as_deref());
+            GateStatus::Blocked(gate_id) => {
+                let error = format!("Blocked by failed command task '{gate_id}'");
+                self.add_notification(
+                    format!("Task {task_id} skipped: {error}"),
+                    cctakt::plan::NotifyLevel::Error,
+                );
+                if let Some(ref mut plan) = self.current_plan {
+                    plan.update_status(task_id, TaskStatus::Skipped);
+                    if let Some(task) = plan.get_task_mut(task_id) {
+                        task.error = Some(error);
+                    }
+                }
+                self.save_plan();
+                false
             }
         }
     }
@@ -879,333 +2027,1514 @@ impl App {
             }
         }
 
-        // Find the agent index for this branch
-        let agent_index = self.agent_worktrees.iter().position(|wt| {
-            wt.as_ref()
-                .and_then(|p| p.file_name())
-                .and_then(|n| n.to_str())
-                .map(|n| n == branch)
-                .unwrap_or(false)
-        });
+        let Some(worktree_path) = self
+            .worktree_manager
+            .as_ref()
+            .and_then(|wt| wt.path_for_branch(branch).ok().flatten())
+        else {
+            self.mark_task_failed(task_id, &format!("Branch '{branch}' not found"));
+            return;
+        };
+
+        // Store the task_id in review state for later completion marking
+        self.pending_review_task_id = Some(task_id.to_string());
 
+        // Prefer the agent that worked in this worktree so it is closed on merge
+        let agent_index = (0..self.agent_manager.len()).find(|&index| {
+            self.agent_manager
+                .worktree_of(index)
+                .is_some_and(|worktree| worktree.path == worktree_path)
+        });
         if let Some(index) = agent_index {
-            // Store the task_id in review state for later completion marking
-            self.pending_review_task_id = Some(task_id.to_string());
             self.start_review(index);
         } else {
-            // Try to start review directly from branch name (worktree might be in worktree_dir)
-            let worktree_path = self.config.worktree_dir.join(branch);
-            if worktree_path.exists() {
-                self.pending_review_task_id = Some(task_id.to_string());
-                self.start_review_for_branch(branch, &worktree_path);
-            } else {
-                self.mark_task_failed(task_id, &format!("Branch '{branch}' not found"));
-            }
+            self.start_review_for_branch(branch, &worktree_path, None);
         }
     }
 
     /// Start review mode for a specific branch and worktree path
-    pub fn start_review_for_branch(&mut self, branch: &str, worktree_path: &PathBuf) {
+    ///
+    /// `base` is the branch to compare against; defaults to the repository's
+    /// default branch.
+    pub fn start_review_for_branch(
+        &mut self,
+        branch: &str,
+        worktree_path: &PathBuf,
+        base: Option<&str>,
+    ) {
         // Get main repo path
         let repo_path = env::current_dir().unwrap_or_default();
         let merger = MergeManager::new(&repo_path);
+        let base = base.map_or_else(|| merger.default_branch(), str::to_string);
 
         // Get diff
-        let diff = merger.diff(branch).unwrap_or_default();
+        let diff = merger.diff(branch, &base).unwrap_or_default();
 
         // Get commit log
         let commit_log = get_commit_log(worktree_path);
 
+        // Changes the worker left uncommitted would be lost on cleanup
+        let uncommitted = WorktreeManager::dirty_files(worktree_path).unwrap_or_default();
+        let nonconforming_commits = self.nonconforming_commits(worktree_path, &base);
+
         // Get merge preview
-        let preview = merger.preview(branch).ok();
-        let (files_changed, insertions, deletions, conflicts) = match preview {
-            Some(p) => (p.files_changed, p.insertions, p.deletions, p.conflicts),
-            None => (0, 0, 0, vec![]),
+        let preview = merger.preview(branch, &base).ok();
+        let (files_changed, insertions, deletions, conflicts, ahead_by, behind_by) = match preview {
+            Some(p) => (p.files_changed, p.insertions, p.deletions, p.conflicts, p.ahead_by, p.behind_by),
+            None => (0, 0, 0, vec![], 0, 0),
         };
 
         // Create diff view
-        let diff_view = DiffView::new(diff).with_title(format!("{branch} → main"));
+        let diff_view = DiffView::new(diff).with_title(format!("{branch} → {base}"));
+
+        // Approving checks the base's protection; ask GitHub now, not then
+        if self.config.merge_mode != MergeMode::PullRequest {
+            self.start_branch_protection_check(&base);
+        }
 
         self.review_state = Some(ReviewState {
-            agent_index: usize::MAX, // No agent associated
+            agent_id: None,
             branch: branch.to_string(),
+            base,
             worktree_path: worktree_path.clone(),
             diff_view,
             commit_log,
             files_changed,
             insertions,
             deletions,
+            ahead_by,
+            behind_by,
             conflicts,
+            uncommitted,
+            nonconforming_commits,
             focus: ReviewFocus::default(),
             summary_scroll: 0,
+            commits_view: None,
+            base_dialog: None,
+            search_input: None,
+            reject_dialog: None,
+            comments: Vec::new(),
+            comment_dialog: None,
+            post_review_pr: None,
+            draft_pr: false,
+            diff_kind: ReviewDiff::Branch,
         });
 
         self.mode = AppMode::ReviewMerge;
     }
 
-    /// Execute CreateWorker task
-    fn execute_create_worker(
-        &mut self,
-        task_id: &str,
-        branch: &str,
-        task_description: &str,
-        _base_branch: Option<&str>,
-    ) {
-        // Create worktree
-        let (working_dir, worktree_path) = if let Some(ref wt_manager) = self.worktree_manager {
-            match wt_manager.create(branch, &self.config.worktree_dir) {
+    /// Review an existing branch that this session isn't tracking
+    ///
+    /// Reuses the branch's worktree (creating one if it has none) and opens
+    /// review mode; reports a notification instead if the branch doesn't
+    /// exist or has no changes against the base.
+    pub fn attach_branch(&mut self, branch: &str) {
+        let Some(ref wt_manager) = self.worktree_manager else {
+            self.add_notification(
+                "Cannot attach: not in a git repository".to_string(),
+                cctakt::plan::NotifyLevel::Error,
+            );
+            return;
+        };
+
+        if !wt_manager.branch_exists(branch).unwrap_or(false) {
+            self.add_notification(
+                format!("Branch '{branch}' does not exist"),
+                cctakt::plan::NotifyLevel::Error,
+            );
+            return;
+        }
+
+        let merger = MergeManager::new(wt_manager.repo_path());
+        let base = merger.default_branch();
+        if merger.diff(branch, &base).is_ok_and(|diff| diff.trim().is_empty()) {
+            self.add_notification(
+                format!("Nothing to review: {branch} has no changes against {base}"),
+                cctakt::plan::NotifyLevel::Info,
+            );
+            return;
+        }
+
+        let worktree_path = match wt_manager.path_for_branch(branch) {
+            Ok(Some(path)) => path,
+            _ => match wt_manager.checkout(branch, &self.config.worktree_dir) {
                 Ok(path) => {
                     debug::log_worktree("created", &path);
-                    (path.clone(), Some(path))
-                }
-                Err(e) => {
-                    self.mark_task_failed(task_id, &format!("Failed to create worktree: {e}"));
-                    return;
+                    self.warn_if_worktree_outside_repo(&path);
+                    path
                 }
-            }
-        } else {
-            match env::current_dir() {
-                Ok(dir) => (dir, None),
                 Err(e) => {
-                    self.mark_task_failed(task_id, &format!("Failed to get current directory: {e}"));
+                    self.add_notification(
+                        format!("Failed to create worktree for {branch}: {e}"),
+                        cctakt::plan::NotifyLevel::Error,
+                    );
                     return;
                 }
-            }
+            },
         };
 
-        // Create agent in non-interactive mode
-        let name = branch.to_string();
-        let full_prompt = format!(
-            "{}\n\n\
-            重要: 作業完了後は必ず git add と git commit を実行してコミットしてください。\n\
-            コミットせずに終了すると変更が失われます。",
-            task_description
-        );
-        match self.agent_manager.add_non_interactive(
-            name.clone(),
-            working_dir,
-            &full_prompt,
-            None, // No turn limit for plan-based workers
-            Some(branch.to_string()),
-        ) {
-            Ok(_) => {
-                let agent_index = self.agent_manager.list().len() - 1;
-                self.agent_issues.push(None);
-                self.agent_worktrees.push(worktree_path);
-                self.task_agents.insert(task_id.to_string(), agent_index);
-
-                // Update PTY sizes for pane split
-                self.update_agent_sizes();
+        self.start_review_for_branch(branch, &worktree_path, Some(&base));
+    }
 
-                debug::log_task(task_id, "pending", "running");
-                self.add_notification(
-                    format!("Worker started: {name}"),
-                    cctakt::plan::NotifyLevel::Success,
-                );
-            }
-            Err(e) => {
-                self.mark_task_failed(task_id, &format!("Failed to create agent: {e}"));
-            }
+    /// Prompt for a different base branch to review against
+    pub fn open_review_base_prompt(&mut self) {
+        if let Some(ref mut state) = self.review_state {
+            let mut dialog = InputDialog::new(
+                "Review Base",
+                format!("Compare {} against (current: {}):", state.branch, state.base),
+            );
+            dialog.show();
+            state.base_dialog = Some(dialog);
         }
     }
 
-    /// Execute CreatePr task
-    fn execute_create_pr(
-        &mut self,
-        task_id: &str,
-        branch: &str,
-        title: &str,
-        body: Option<&str>,
-        base: Option<&str>,
-        draft: bool,
-    ) {
-        let Some(ref client) = self.github_client else {
-            self.mark_task_failed(task_id, "GitHub client not configured");
+    /// Re-run the current review against `base`
+    pub fn change_review_base(&mut self, base: &str) {
+        if self.review_state.is_none() {
             return;
-        };
+        }
+        let repo_path = env::current_dir().unwrap_or_default();
+        if !MergeManager::new(&repo_path).branch_exists(base) {
+            self.add_notification(
+                format!("Unknown base branch: {base}"),
+                cctakt::plan::NotifyLevel::Error,
+            );
+            return;
+        }
+        self.reload_review(base);
+    }
 
-        let create_req = cctakt::github::CreatePullRequest {
-            title: title.to_string(),
-            body: body.map(String::from),
-            head: branch.to_string(),
-            base: base.unwrap_or("main").to_string(),
-            draft,
+    /// Re-run the current review against `base`, keeping the reviewer's
+    /// agent, overflow mode and comments
+    fn reload_review(&mut self, base: &str) {
+        let Some(state) = self.review_state.as_ref() else {
+            return;
         };
-
-        match client.create_pull_request(&create_req) {
-            Ok(pr) => {
-                self.add_notification(
-                    format!("PR created: #{} - {}", pr.number, pr.title),
-                    cctakt::plan::NotifyLevel::Success,
-                );
-                let result = TaskResult {
-                    commits: Vec::new(),
-                    pr_number: Some(pr.number),
-                    pr_url: Some(pr.html_url),
-                };
-                if let Some(ref mut plan) = self.current_plan {
-                    plan.mark_completed(task_id, result);
-                    if let Err(e) = self.plan_manager.save(plan) {
-                        debug::log(&format!("Failed to save plan: {e}"));
-                    }
-                }
-            }
-            Err(e) => {
-                self.mark_task_failed(task_id, &format!("Failed to create PR: {e}"));
-            }
+        let agent_id = state.agent_id;
+        let overflow = state.diff_view.overflow();
+        let comments = state.comments.clone();
+        let branch = state.branch.clone();
+        let worktree_path = state.worktree_path.clone();
+        self.start_review_for_branch(&branch, &worktree_path, Some(base));
+        if let Some(ref mut state) = self.review_state {
+            state.agent_id = agent_id;
+            state.diff_view.set_overflow(overflow);
+            state.comments = comments;
+            state
+                .diff_view
+                .set_comment_marks(state.comments.iter().map(|c| &c.location));
         }
     }
 
-    /// Execute MergeBranch task
-    fn execute_merge_branch(&mut self, task_id: &str, branch: &str, target: Option<&str>) {
-        let repo_path = match env::current_dir() {
-            Ok(p) => p,
+    /// Switch the review diff between the branch's changes and the net merge result
+    ///
+    /// The merge result is what the base would look like after the merge,
+    /// so changes the base already has drop out and conflicts show up with
+    /// their markers. Nothing is checked out or merged to compute it.
+    pub fn toggle_review_merge_result(&mut self) {
+        let Some(state) = self.review_state.as_ref() else {
+            return;
+        };
+        let (branch, base) = (state.branch.clone(), state.base.clone());
+        let merger = MergeManager::new(&state.worktree_path);
+        let (kind, diff, title) = match state.diff_kind {
+            ReviewDiff::Branch => (
+                ReviewDiff::MergeResult,
+                merger.merge_result_diff(&branch, &base),
+                format!("{base} after merging {branch}"),
+            ),
+            ReviewDiff::MergeResult => (
+                ReviewDiff::Branch,
+                merger.diff(&branch, &base),
+                format!("{branch} → {base}"),
+            ),
+        };
+        let diff = match diff {
+            Ok(diff) => diff,
             Err(e) => {
-                self.mark_task_failed(task_id, &format!("Failed to get current directory: {e}"));
+                self.add_notification(format!("{e:#}"), cctakt::plan::NotifyLevel::Error);
                 return;
             }
         };
 
-        let merger = MergeManager::new(&repo_path);
-        let merger = if let Some(t) = target {
-            merger.with_main_branch(t)
-        } else {
-            merger
+        let Some(ref mut state) = self.review_state else {
+            return;
         };
+        let overflow = state.diff_view.overflow();
+        state.diff_view = DiffView::new(diff).with_title(title);
+        state.diff_view.set_overflow(overflow);
+        // Comment locations refer to the branch's diff
+        if kind == ReviewDiff::Branch {
+            state
+                .diff_view
+                .set_comment_marks(state.comments.iter().map(|c| &c.location));
+        }
+        state.diff_kind = kind;
+    }
 
-        match merger.merge_no_ff(branch, None) {
-            Ok(()) => {
+    /// Bring the branch under review up to date with its base
+    ///
+    /// Merges or rebases per `update_strategy` inside the review worktree,
+    /// then refreshes the review. Conflicts are left in the worktree with
+    /// their markers, so the review shows them as uncommitted changes and
+    /// refuses to merge until they're resolved.
+    pub fn update_review_branch(&mut self) {
+        let Some(state) = self.review_state.as_ref() else {
+            return;
+        };
+        let base = state.base.clone();
+        let worktree_path = state.worktree_path.clone();
+        let dirty = WorktreeManager::dirty_files(&worktree_path).unwrap_or_default();
+        if !dirty.is_empty() {
+            self.add_notification(
+                format!(
+                    "{} uncommitted change(s) in {} — commit them before updating",
+                    dirty.len(),
+                    worktree_path.display()
+                ),
+                cctakt::plan::NotifyLevel::Warning,
+            );
+            return;
+        }
+        let merger = MergeManager::new(&worktree_path);
+        let strategy = self.config.update_strategy;
+        // Signing the merge commit or the rebased commits would prompt for a
+        // passphrase behind the TUI
+        if merger.signing_enabled() {
+            let action = match strategy {
+                UpdateStrategy::Merge => "merge",
+                UpdateStrategy::Rebase => "rebase onto",
+            };
+            self.add_notification(
+                format!("Commit signing is enabled — {action} {base} in {} yourself", worktree_path.display()),
+                cctakt::plan::NotifyLevel::Warning,
+            );
+            return;
+        }
+        match merger.update_from_base(&base, strategy) {
+            Ok(UpdateOutcome::Updated) => {
+                let verb = match strategy {
+                    UpdateStrategy::Merge => "Merged",
+                    UpdateStrategy::Rebase => "Rebased onto",
+                };
                 self.add_notification(
-                    format!("Merged: {} → {}", branch, target.unwrap_or("main")),
+                    format!("{verb} {base}"),
                     cctakt::plan::NotifyLevel::Success,
                 );
-                if let Some(ref mut plan) = self.current_plan {
-                    plan.update_status(task_id, TaskStatus::Completed);
-                }
-                self.save_plan();
+            }
+            Ok(UpdateOutcome::Conflicts(files)) => {
+                let abort = match strategy {
+                    UpdateStrategy::Merge => "git merge --abort",
+                    UpdateStrategy::Rebase => "git rebase --abort",
+                };
+                self.add_notification(
+                    format!(
+                        "Updating from {base} conflicts in {} — resolve them in {} or run `{abort}`",
+                        files.join(", "),
+                        worktree_path.display()
+                    ),
+                    cctakt::plan::NotifyLevel::Warning,
+                );
             }
             Err(e) => {
-                self.mark_task_failed(task_id, &format!("Failed to merge: {e}"));
+                self.add_notification(
+                    format!("Update failed: {e:#}"),
+                    cctakt::plan::NotifyLevel::Error,
+                );
+                return;
             }
         }
+        self.reload_review(&base);
     }
 
-    /// Execute CleanupWorktree task
-    fn execute_cleanup_worktree(&mut self, task_id: &str, worktree: &str) {
-        if let Some(ref wt_manager) = self.worktree_manager {
-            let worktree_path = self.config.worktree_dir.join(worktree);
-            match wt_manager.remove(&worktree_path) {
-                Ok(()) => {
-                    self.add_notification(
-                        format!("Worktree cleaned up: {worktree}"),
-                        cctakt::plan::NotifyLevel::Info,
-                    );
-                    if let Some(ref mut plan) = self.current_plan {
-                        plan.update_status(task_id, TaskStatus::Completed);
-                    }
-                    self.save_plan();
-                }
-                Err(e) => {
-                    self.mark_task_failed(task_id, &format!("Failed to cleanup worktree: {e}"));
-                }
-            }
-        } else {
-            self.mark_task_failed(task_id, "Worktree manager not available");
-        }
+    /// Whether the base branch prompt is open
+    pub fn review_base_prompt_open(&self) -> bool {
+        self.review_state
+            .as_ref()
+            .is_some_and(|state| state.base_dialog.is_some())
     }
 
-    /// Execute RunCommand task (not implemented yet - just marks complete)
-    fn execute_run_command(&mut self, task_id: &str, worktree: &str, command: &str) {
-        self.add_notification(
-            format!("RunCommand not implemented: {command} in {worktree}"),
-            cctakt::plan::NotifyLevel::Warning,
-        );
-        if let Some(ref mut plan) = self.current_plan {
-            plan.update_status(task_id, TaskStatus::Skipped);
+    /// Prompt for review feedback to send back to the branch's worker
+    pub fn open_review_reject_prompt(&mut self) {
+        if let Some(ref mut state) = self.review_state {
+            let mut dialog = InputDialog::new(
+                "Request Changes",
+                format!("Feedback for the worker on {}:", state.branch),
+            );
+            dialog.show();
+            state.reject_dialog = Some(dialog);
         }
-        self.save_plan();
     }
 
-    /// Mark a task as failed
-    pub fn mark_task_failed(&mut self, task_id: &str, error: &str) {
-        self.add_notification(
-            format!("Task failed: {error}"),
-            cctakt::plan::NotifyLevel::Error,
-        );
-        if let Some(ref mut plan) = self.current_plan {
-            plan.mark_failed(task_id, error);
-            if let Err(e) = self.plan_manager.save(plan) {
-                debug::log(&format!("Failed to save plan: {e}"));
-            }
-        }
+    /// Whether the reject feedback prompt is open
+    pub fn review_reject_prompt_open(&self) -> bool {
+        self.review_state
+            .as_ref()
+            .is_some_and(|state| state.reject_dialog.is_some())
     }
 
-    /// Add a notification
-    pub fn add_notification(&mut self, message: String, level: cctakt::plan::NotifyLevel) {
-        self.notifications.push(Notification {
-            message,
-            level,
-            created_at: std::time::Instant::now(),
-        });
+    /// Reject the branch under review and send `feedback` to its worker
+    ///
+    /// Line comments made during the review are appended to the feedback.
+    /// A running interactive agent gets the feedback as input; otherwise the
+    /// finished worker is replaced by a fresh one on the same worktree, which
+    /// comes back to review when it ends.
+    pub fn reject_review(&mut self, feedback: &str) {
+        self.request_review_changes(feedback);
+        self.continue_review_queue();
     }
 
-    /// Save current plan to file (persist status changes across restarts)
-    pub fn save_plan(&mut self) {
-        if let Some(ref plan) = self.current_plan {
-            if let Err(e) = self.plan_manager.save(plan) {
-                debug::log(&format!("Failed to save plan: {e}"));
-            }
-        }
-    }
+    /// Send the rejection feedback to the reviewed worker, restarting it if needed
+    fn request_review_changes(&mut self, feedback: &str) {
+        let Some(review) = self.review_state.take() else {
+            return;
+        };
+        // The plan's review task must not be completed by the next merge
+        self.pending_review_task_id = None;
+        self.mode = AppMode::Normal;
 
-    /// Clean up old notifications (older than 5 seconds)
-    pub fn cleanup_notifications(&mut self) {
-        let now = std::time::Instant::now();
-        self.notifications
-            .retain(|n| now.duration_since(n.created_at).as_secs() < 5);
-    }
+        let prompt = format!(
+            "{}\n\n{}",
+            messages::format(
+                self.prompt_locale(),
+                Msg::ReviewFeedbackTask,
+                &[
+                    ("branch", &review.branch),
+                    ("feedback", &review_feedback(self.prompt_locale(), feedback, &review.comments)),
+                ],
+            ),
+            messages::text(self.prompt_locale(), Msg::WorkerCommitReminder)
+        );
 
-    /// Check if any agent completed its task and update plan
-    pub fn check_agent_task_completions(&mut self) {
-        // Collect ended agents with their task info
-        let ended: Vec<(String, usize, Option<String>)> = self
-            .task_agents
-            .iter()
-            .filter_map(|(task_id, &agent_index)| {
-                self.agent_manager
-                    .get(agent_index)
-                    .filter(|a| a.status == AgentStatus::Ended)
-                    .map(|a| (task_id.clone(), agent_index, a.error.clone()))
+        let existing = review.agent_id.and_then(|id| self.agent_manager.index_of(id));
+        if let Some(index) = existing
+            && let Some(agent) = self.agent_manager.get_mut(index)
+            && agent.mode == AgentMode::Interactive
+            && agent.status == AgentStatus::Running
+        {
+            agent.send_bytes(prompt.as_bytes());
+            agent.send_bytes(b"\r");
+            let name = agent.name.clone();
+            self.agent_manager.switch_to(index);
+            self.add_notification(
+                format!("Review feedback sent to {name}"),
+                cctakt::plan::NotifyLevel::Info,
+            );
+            return;
+        }
+
+        // Start a fresh worker on the same worktree, keeping the old one's metadata
+        let (name, issue, task_id) = existing
+            .and_then(|index| self.agent_manager.get(index))
+            .map(|agent| (agent.name.clone(), agent.issue.clone(), agent.task_id.clone()))
+            .unwrap_or_else(|| (review.branch.clone(), None, None));
+        if let Some(index) = existing {
+            self.agent_manager.close(index);
+        }
+
+        match self.agent_manager.add_non_interactive(
+            name,
+            review.worktree_path.clone(),
+            &prompt,
+            None,
+            Some(review.branch.clone()),
+        ) {
+            Ok(id) => {
+                if let Some(agent) = self.agent_manager.last_mut() {
+                    agent.issue = issue;
+                    agent.worktree_path = Some(review.worktree_path);
+                    agent.task_id = task_id;
+                }
+                self.emit_agent_started(id);
+                if let Some(index) = self.agent_manager.index_of(id) {
+                    self.agent_manager.switch_to(index);
+                }
+                self.update_agent_sizes();
+                self.add_notification(
+                    format!("Changes requested: worker restarted on {}", review.branch),
+                    cctakt::plan::NotifyLevel::Info,
+                );
+            }
+            Err(e) if is_claude_not_found(&e) => self.notify_spawn_error("worker", &e),
+            Err(e) => self.add_notification(
+                format!("Failed to restart worker for {}: {e}", review.branch),
+                cctakt::plan::NotifyLevel::Error,
+            ),
+        }
+    }
+
+    /// Prompt for a comment on the diff line at the top of the view
+    ///
+    /// An existing comment on that line is loaded for editing.
+    pub fn open_review_comment_prompt(&mut self) {
+        let Some(ref mut state) = self.review_state else {
+            return;
+        };
+        if state.diff_kind == ReviewDiff::MergeResult {
+            self.add_notification(
+                "Comments go on the branch's changes — press v to show them".to_string(),
+                cctakt::plan::NotifyLevel::Warning,
+            );
+            return;
+        }
+        let Some(location) = state.diff_view.current_location().cloned() else {
+            self.add_notification(
+                "No diff line to comment on here".to_string(),
+                cctakt::plan::NotifyLevel::Warning,
+            );
+            return;
+        };
+        let existing = state
+            .comments
+            .iter()
+            .find(|comment| comment.location == location)
+            .map(|comment| comment.text.clone())
+            .unwrap_or_default();
+        let mut dialog =
+            InputDialog::new("Comment", format!("Comment on {location}:")).with_value(existing);
+        dialog.show();
+        state.comment_dialog = Some((location, dialog));
+    }
+
+    /// Whether the line comment prompt is open
+    pub fn review_comment_prompt_open(&self) -> bool {
+        self.review_state
+            .as_ref()
+            .is_some_and(|state| state.comment_dialog.is_some())
+    }
+
+    /// Set the review comment on `location`; empty text removes it
+    pub fn set_review_comment(&mut self, location: LineLocation, text: &str) {
+        let Some(ref mut state) = self.review_state else {
+            return;
+        };
+        let text = text.trim();
+        let existing = state.comments.iter().position(|c| c.location == location);
+        match existing {
+            Some(index) if text.is_empty() => {
+                state.comments.remove(index);
+            }
+            Some(index) => state.comments[index].text = text.to_string(),
+            None if text.is_empty() => {}
+            None => state.comments.push(ReviewComment {
+                location,
+                text: text.to_string(),
+            }),
+        }
+        state
+            .diff_view
+            .set_comment_marks(state.comments.iter().map(|c| &c.location));
+    }
+
+    /// Ask for a verdict to post the review on the branch's open pull request
+    ///
+    /// Needs an authenticated GitHub client and an open PR whose head is the
+    /// reviewed branch.
+    pub fn open_review_post_prompt(&mut self) {
+        let Some(branch) = self.review_state.as_ref().map(|state| state.branch.clone()) else {
+            return;
+        };
+        if !self.ensure_github_client() {
+            self.add_notification(
+                "GitHub repository not configured; cannot post a review".to_string(),
+                cctakt::plan::NotifyLevel::Error,
+            );
+            return;
+        }
+        let Some(ref client) = self.github_client else {
+            return;
+        };
+        if !client.has_auth() {
+            self.add_notification(
+                "Posting a review needs a GitHub token (GITHUB_TOKEN or gh auth login)".to_string(),
+                cctakt::plan::NotifyLevel::Error,
+            );
+            return;
+        }
+        match client.find_open_pull_request(&branch) {
+            Ok(Some(pr)) => {
+                if let Some(ref mut state) = self.review_state {
+                    state.post_review_pr = Some(pr.number);
+                }
+            }
+            Ok(None) => self.add_notification(
+                format!("No open PR for {branch}"),
+                cctakt::plan::NotifyLevel::Warning,
+            ),
+            Err(e) => self.add_notification(
+                format!("Failed to look up the PR for {branch}: {e}"),
+                cctakt::plan::NotifyLevel::Error,
+            ),
+        }
+    }
+
+    /// Whether the review verdict prompt is open
+    pub fn review_post_prompt_open(&self) -> bool {
+        self.review_state
+            .as_ref()
+            .is_some_and(|state| state.post_review_pr.is_some())
+    }
+
+    /// Close the review verdict prompt without posting
+    pub fn cancel_review_post(&mut self) {
+        if let Some(ref mut state) = self.review_state {
+            state.post_review_pr = None;
+        }
+    }
+
+    /// Post the line comments as a review with `event` on the pull request
+    /// chosen by `open_review_post_prompt`
+    ///
+    /// Comments are sent as line comments; if GitHub rejects them (e.g. a
+    /// line outside the PR's hunks), they are listed in the review body
+    /// instead. Posted comments are cleared.
+    pub fn post_review(&mut self, event: ReviewEvent) {
+        let locale = self.prompt_locale();
+        let Some(state) = self.review_state.as_mut() else {
+            return;
+        };
+        let Some(number) = state.post_review_pr.take() else {
+            return;
+        };
+        if event != ReviewEvent::Approve && state.comments.is_empty() {
+            self.add_notification(
+                "Add line comments with C before commenting or requesting changes".to_string(),
+                cctakt::plan::NotifyLevel::Warning,
+            );
+            return;
+        }
+        let line_comments: Vec<ReviewLineComment> = state
+            .comments
+            .iter()
+            .map(|comment| ReviewLineComment {
+                path: comment.location.path.clone(),
+                line: comment.location.line,
+                side: match comment.location.side {
+                    DiffSide::Old => ReviewSide::Left,
+                    DiffSide::New => ReviewSide::Right,
+                },
+                body: comment.text.clone(),
             })
             .collect();
+        let fallback_body = review_feedback(locale, "", &state.comments);
+        let Some(ref client) = self.github_client else {
+            return;
+        };
+
+        let result = client
+            .create_review(number, event, None, &line_comments)
+            .map(|()| false)
+            .or_else(|e| {
+                if line_comments.is_empty() {
+                    return Err(e);
+                }
+                debug::log(&format!("Line comments rejected on PR #{number}: {e}"));
+                client
+                    .create_review(number, event, Some(&fallback_body), &[])
+                    .map(|()| true)
+            });
+        match result {
+            Ok(folded) => {
+                if let Some(ref mut state) = self.review_state {
+                    state.comments.clear();
+                    state.diff_view.set_comment_marks([]);
+                }
+                let note = if folded { "; comments added to the review body" } else { "" };
+                self.add_notification(
+                    format!("Review posted on PR #{number} ({}){note}", event.label()),
+                    cctakt::plan::NotifyLevel::Success,
+                );
+            }
+            Err(e) => self.add_notification(
+                format!("Failed to post review on PR #{number}: {e}"),
+                cctakt::plan::NotifyLevel::Error,
+            ),
+        }
+    }
+
+    /// Open the `/` search prompt for the review diff
+    pub fn open_review_search(&mut self) {
+        if let Some(ref mut state) = self.review_state {
+            state.search_input = Some(String::new());
+        }
+    }
+
+    /// Whether the review search prompt is open
+    pub fn review_search_open(&self) -> bool {
+        self.review_state
+            .as_ref()
+            .is_some_and(|state| state.search_input.is_some())
+    }
+
+    /// Run the typed search query against the review diff and close the prompt
+    pub fn run_review_search(&mut self) {
+        let Some(ref mut state) = self.review_state else {
+            return;
+        };
+        let query = state.search_input.take().unwrap_or_default();
+        if query.is_empty() {
+            state.diff_view.clear_search();
+            return;
+        }
+        state.focus = ReviewFocus::Diff;
+        if state.diff_view.search(&query).is_empty() {
+            self.add_notification(
+                format!("No matches for '{query}'"),
+                cctakt::plan::NotifyLevel::Info,
+            );
+        }
+    }
+
+    /// Jump to the next (or previous) search match in the review diff
+    pub fn review_search_step(&mut self, forward: bool) {
+        let Some(ref mut state) = self.review_state else {
+            return;
+        };
+        let view = &mut state.diff_view;
+        if view.search_query().is_empty() {
+            return;
+        }
+        let moved = if forward { view.next_match() } else { view.prev_match() };
+        if moved.is_none() {
+            let query = view.search_query().to_string();
+            self.add_notification(
+                format!("No matches for '{query}'"),
+                cctakt::plan::NotifyLevel::Info,
+            );
+        }
+    }
+
+    /// Close the search prompt and clear any active search, staying in review
+    pub fn clear_review_search(&mut self) {
+        if let Some(ref mut state) = self.review_state {
+            state.search_input = None;
+            state.diff_view.clear_search();
+        }
+    }
+
+    /// Execute CreateWorker task
+    fn execute_create_worker(
+        &mut self,
+        task_id: &str,
+        branch: &str,
+        task_description: &str,
+        _base_branch: Option<&str>,
+    ) {
+        // Create worktree, or switch the main checkout to the branch
+        let (working_dir, worktree_path) = if !self.config.use_worktrees {
+            match self.checkout_in_main_repo(branch) {
+                Ok(path) => (path.clone(), Some(path)),
+                Err(e) => {
+                    self.mark_task_failed(task_id, &format!("Failed to check out {branch}: {e:#}"));
+                    return;
+                }
+            }
+        } else if let Some(ref wt_manager) = self.worktree_manager {
+            match wt_manager.create(branch, &self.config.worktree_dir) {
+                Ok(path) => {
+                    debug::log_worktree("created", &path);
+                    self.warn_if_worktree_outside_repo(&path);
+                    (path.clone(), Some(path))
+                }
+                Err(e) => {
+                    self.mark_task_failed(task_id, &format!("Failed to create worktree: {e}"));
+                    return;
+                }
+            }
+        } else {
+            match env::current_dir() {
+                Ok(dir) => (dir, None),
+                Err(e) => {
+                    self.mark_task_failed(task_id, &format!("Failed to get current directory: {e}"));
+                    return;
+                }
+            }
+        };
+
+        // Create agent in non-interactive mode
+        let name = agent_name(self.config.agent_name_style, None, branch, &self.config.branch_slug);
+        let full_prompt = format!(
+            "{}\n\n{}",
+            task_description,
+            messages::text(self.prompt_locale(), Msg::WorkerCommitReminder)
+        );
+        self.spawn_plan_worker(PendingSpawn {
+            task_id: task_id.to_string(),
+            name,
+            working_dir,
+            worktree_path,
+            prompt: full_prompt,
+            branch: branch.to_string(),
+            attempt: 0,
+            retry_at: std::time::Instant::now(),
+        });
+    }
+
+    /// Start a plan worker's agent
+    ///
+    /// A transient failure is queued in `pending_spawns` with exponential
+    /// backoff (`[agent]` retry settings) rather than slept on here.
+    fn spawn_plan_worker(&mut self, spawn: PendingSpawn) {
+        let task_id = spawn.task_id.as_str();
+        match self.agent_manager.add_non_interactive(
+            spawn.name.clone(),
+            spawn.working_dir.clone(),
+            &spawn.prompt,
+            None, // No turn limit for plan-based workers
+            Some(spawn.branch.clone()),
+        ) {
+            Ok(agent_id) => {
+                if let Some(agent) = self.agent_manager.last_mut() {
+                    agent.worktree_path = spawn.worktree_path.clone();
+                    agent.task_id = Some(task_id.to_string());
+                }
+                self.emit_agent_started(agent_id);
+
+                // Update PTY sizes for pane split
+                self.update_agent_sizes();
+
+                debug::log_task(task_id, "pending", "running");
+                self.add_notification(
+                    format!("Worker started: {}", spawn.name),
+                    cctakt::plan::NotifyLevel::Success,
+                );
+            }
+            Err(e) if is_claude_not_found(&e) => {
+                self.notify_spawn_error("worker", &e);
+                self.record_task_failure(task_id, &e.to_string());
+            }
+            Err(e) if is_retryable_spawn_error(&e) && spawn.attempt < self.config.agent.spawn_retries => {
+                let delay = SpawnRetry::from_config(&self.config.agent).delay(spawn.attempt);
+                debug::log_fields(
+                    debug::Level::Warn,
+                    "agent",
+                    "spawn failed, retrying",
+                    &[
+                        ("agent", &spawn.name),
+                        ("attempt", &(spawn.attempt + 1)),
+                        ("delay_ms", &delay.as_millis()),
+                        ("error", &e),
+                    ],
+                );
+                self.pending_spawns.push(PendingSpawn {
+                    attempt: spawn.attempt + 1,
+                    retry_at: std::time::Instant::now() + delay,
+                    ..spawn
+                });
+            }
+            Err(e) => {
+                self.mark_task_failed(task_id, &format!("Failed to create agent: {e}"));
+            }
+        }
+    }
+
+    /// Start the plan workers whose spawn retry is due
+    fn retry_pending_spawns(&mut self) {
+        let now = std::time::Instant::now();
+        let (due, waiting) = std::mem::take(&mut self.pending_spawns)
+            .into_iter()
+            .partition(|spawn| spawn.retry_at <= now);
+        self.pending_spawns = waiting;
+        for spawn in due {
+            self.spawn_plan_worker(spawn);
+        }
+    }
+
+    /// Execute CreatePr task
+    fn execute_create_pr(
+        &mut self,
+        task_id: &str,
+        branch: &str,
+        title: &str,
+        body: Option<&str>,
+        base: Option<&str>,
+        draft: bool,
+    ) {
+        if self.github_client.is_none() {
+            self.mark_task_failed(task_id, "GitHub client not configured");
+            return;
+        }
+        if let Err(e) = self.push_for_pull_request(branch) {
+            self.mark_task_failed(task_id, &format!("{e:#}"));
+            return;
+        }
+
+        let body = self.pull_request_body_for(branch, body, None);
+        match self.create_pull_request(branch, title, body.as_deref(), base.unwrap_or("main"), draft) {
+            Ok(pr) => {
+                let result = TaskResult {
+                    commits: Vec::new(),
+                    pr_number: Some(pr.number),
+                    pr_url: Some(pr.html_url),
+                    output: None,
+                };
+                self.complete_task(task_id, Some(result));
+            }
+            Err(e) => {
+                self.mark_task_failed(task_id, &format!("Failed to create PR: {e}"));
+            }
+        }
+    }
+
+    /// Make sure origin has `branch` as it is locally before a PR is opened from it
+    ///
+    /// Always pushes, so commits made since an earlier push make it into the
+    /// PR; the push is never forced and fails if origin has diverged.
+    fn push_for_pull_request(&self, branch: &str) -> Result<()> {
+        if !self.config.push.before_pr {
+            return Ok(());
+        }
+        MergeManager::new(env::current_dir()?).push_branch(branch, true)
+    }
+
+    /// Body for a pull request from `branch`
+    ///
+    /// An explicit body wins over the repository's PR template, which wins
+    /// over `generated`; `Closes #N` is added when an agent worked on an issue
+    /// for the branch.
+    fn pull_request_body_for(
+        &self,
+        branch: &str,
+        explicit: Option<&str>,
+        generated: Option<&str>,
+    ) -> Option<String> {
+        let repo_path = env::current_dir().unwrap_or_default();
+        let template = load_pull_request_template(&repo_path);
+        let issue = self
+            .agent_manager
+            .list()
+            .iter()
+            .filter(|agent| agent.branch.as_deref() == Some(branch))
+            .find_map(|agent| agent.issue.as_ref().map(|issue| issue.number));
+        pull_request_body(explicit, template.as_deref().or(generated), issue)
+    }
+
+    /// Toggle opening the reviewed branch's pull request as a draft
+    pub fn toggle_review_draft(&mut self) {
+        if self.config.merge_mode != MergeMode::PullRequest {
+            self.add_notification(
+                "Draft PRs only apply with merge_mode = \"pull_request\"".to_string(),
+                cctakt::plan::NotifyLevel::Warning,
+            );
+            return;
+        }
+        let Some(ref mut review) = self.review_state else {
+            return;
+        };
+        review.draft_pr = !review.draft_pr;
+        let message = if review.draft_pr {
+            "The pull request will be opened as a draft"
+        } else {
+            "The pull request will be opened ready for review"
+        };
+        self.add_notification(message.to_string(), cctakt::plan::NotifyLevel::Info);
+    }
+
+    fn create_pull_request(
+        &mut self,
+        branch: &str,
+        title: &str,
+        body: Option<&str>,
+        base: &str,
+        draft: bool,
+    ) -> Result<PullRequest> {
+        let Some(ref client) = self.github_client else {
+            anyhow::bail!("GitHub client not configured");
+        };
+
+        let create_req = cctakt::github::CreatePullRequest {
+            title: title.to_string(),
+            body: body.map(String::from),
+            head: branch.to_string(),
+            base: base.to_string(),
+            draft,
+        };
+        let pr = client.create_pull_request(&create_req)?;
+        self.add_notification(
+            format!("PR created: #{} - {}", pr.number, pr.title),
+            cctakt::plan::NotifyLevel::Success,
+        );
+        Ok(pr)
+    }
+
+    /// Execute MergeBranch task
+    fn execute_merge_branch(&mut self, task_id: &str, branch: &str, target: Option<&str>) {
+        let repo_path = match self.worktree_manager.as_ref() {
+            Some(wt_manager) => wt_manager.repo_path().to_path_buf(),
+            None => match env::current_dir() {
+                Ok(p) => p,
+                Err(e) => {
+                    self.mark_task_failed(task_id, &format!("Failed to get current directory: {e}"));
+                    return;
+                }
+            },
+        };
+
+        let merger = MergeManager::new(&repo_path);
+        let target = target.map_or_else(|| merger.default_branch(), str::to_string);
+        let merger = merger.with_main_branch(&target);
+
+        if self.requires_pull_request(&target) {
+            self.add_notification(
+                format!("{target} only takes changes through pull requests; opening one for {branch}"),
+                cctakt::plan::NotifyLevel::Warning,
+            );
+            self.execute_create_pr(task_id, branch, branch, None, Some(&target), false);
+            return;
+        }
+
+        // `git merge` merges into whatever is checked out (a worker without a
+        // worktree also leaves its branch checked out here)
+        if merger.current_branch().ok().as_deref() != Some(target.as_str())
+            && let Err(e) = merger.checkout(&target)
+        {
+            self.mark_task_failed(task_id, &format!("Failed to check out {target}: {e:#}"));
+            return;
+        }
+
+        match merger.merge_no_ff(branch, None) {
+            Ok(()) => {
+                self.add_notification(
+                    format!("Merged: {} → {}", branch, merger.main_branch()),
+                    cctakt::plan::NotifyLevel::Success,
+                );
+                self.complete_task(task_id, None);
+                self.emit_event(Event::MergeSucceeded {
+                    branch: branch.to_string(),
+                    target: merger.main_branch().to_string(),
+                    task_id: Some(task_id.to_string()),
+                });
+            }
+            Err(e) => {
+                self.mark_task_failed(task_id, &format!("Failed to merge: {e}"));
+            }
+        }
+    }
+
+    /// Execute CleanupWorktree task
+    fn execute_cleanup_worktree(&mut self, task_id: &str, worktree: &str) {
+        if let Some(ref wt_manager) = self.worktree_manager {
+            let worktree_path = self.config.worktree_dir.join(worktree);
+            match wt_manager.remove(&worktree_path, false) {
+                Ok(outcome @ (RemoveOutcome::Removed | RemoveOutcome::AlreadyGone)) => {
+                    let message = if outcome == RemoveOutcome::AlreadyGone {
+                        format!("Worktree already removed: {worktree}")
+                    } else {
+                        format!("Worktree cleaned up: {worktree}")
+                    };
+                    self.add_notification(message, cctakt::plan::NotifyLevel::Info);
+                    self.complete_task(task_id, None);
+                }
+                Ok(RemoveOutcome::RefusedDirty(files)) => {
+                    self.mark_task_failed(
+                        task_id,
+                        &format!(
+                            "Worktree {worktree} has {} uncommitted change(s); not removing it",
+                            files.len()
+                        ),
+                    );
+                }
+                Err(e) => {
+                    self.mark_task_failed(task_id, &format!("Failed to cleanup worktree: {e}"));
+                }
+            }
+        } else {
+            self.mark_task_failed(task_id, "Worktree manager not available");
+        }
+    }
+
+    /// Execute RunCommand task on a background thread
+    ///
+    /// Completion is picked up by `check_command_completions`.
+    fn execute_run_command(&mut self, task_id: &str, worktree: &str, command: &str) {
+        let Some(dir) = self.resolve_worktree_dir(worktree) else {
+            self.mark_task_failed(task_id, &format!("Worktree '{worktree}' not found"));
+            return;
+        };
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let thread_command = command.to_string();
+        std::thread::spawn(move || {
+            let _ = sender.send(cctakt::plan::run_command_in(&dir, &thread_command));
+        });
+
+        self.running_commands.push(RunningCommand {
+            task_id: task_id.to_string(),
+            command: command.to_string(),
+            receiver,
+        });
+        debug::log_task(task_id, "pending", "running");
+        self.add_notification(
+            format!("Running: {command} ({worktree})"),
+            cctakt::plan::NotifyLevel::Info,
+        );
+    }
+
+    /// Resolve a RunCommand `worktree` field (path or branch name) to a directory
+    fn resolve_worktree_dir(&self, worktree: &str) -> Option<PathBuf> {
+        if let Some(path) = self
+            .worktree_manager
+            .as_ref()
+            .and_then(|wt| wt.path_for_branch(worktree).ok().flatten())
+        {
+            return Some(path);
+        }
+        [
+            PathBuf::from(worktree),
+            self.config.worktree_dir.join(worktree),
+            self.config.worktree_dir.join(worktree.replace('/', "-")),
+        ]
+        .into_iter()
+        .find(|p| p.is_dir())
+    }
+
+    /// Collect finished RunCommand tasks and record their outcome in the plan
+    /// Start looking for a newer release in the background if
+    /// `check_updates` is on
+    pub fn start_update_check(&mut self) {
+        if self.config.check_updates {
+            self.update_check = Some(cctakt::update_check::spawn());
+        }
+    }
+
+    /// Mention a newer release once the background check has answered
+    pub fn check_update_notice(&mut self) {
+        let Some(ref receiver) = self.update_check else {
+            return;
+        };
+        match receiver.try_recv() {
+            Ok(newer) => {
+                self.update_check = None;
+                if let Some(version) = newer {
+                    self.add_notification(
+                        format!(
+                            "cctakt {version} is available (running {}); update with `cargo install cctakt`",
+                            env!("CARGO_PKG_VERSION")
+                        ),
+                        cctakt::plan::NotifyLevel::Info,
+                    );
+                }
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => {}
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => self.update_check = None,
+        }
+    }
+
+    /// Start listening on the control socket if `control_socket` is on
+    pub fn start_control_server(&mut self) {
+        if !self.config.control_socket {
+            return;
+        }
+        match ControlServer::bind(Path::new(cctakt::control::SOCKET_PATH)) {
+            Ok(server) => self.control = Some(server),
+            Err(e) => self.add_notification(
+                format!("Control socket disabled: {e:#}"),
+                cctakt::plan::NotifyLevel::Warning,
+            ),
+        }
+    }
+
+    /// Handle requests that arrived on the control socket
+    pub fn process_control_requests(&mut self) {
+        while let Some(request) = self.control.as_ref().and_then(ControlServer::try_recv) {
+            let result = self.handle_control_command(request.command.clone());
+            if let Some(ref control) = self.control {
+                control.respond(&request, result);
+            }
+        }
+    }
+
+    fn handle_control_command(&mut self, command: ControlCommand) -> Result<serde_json::Value, String> {
+        match command {
+            ControlCommand::AddTask { task } => {
+                let plan = match self.current_plan.take() {
+                    Some(plan) if !plan.is_complete() => plan,
+                    _ => Plan::with_description("Tasks added over the control socket"),
+                };
+                let plan = self.current_plan.insert(plan);
+                if plan.get_task(&task.id).is_some() {
+                    return Err(format!("Task '{}' already exists", task.id));
+                }
+                let task_id = task.id.clone();
+                plan.add_task(*task);
+                self.save_plan();
+                debug::log(&format!("Task added over control socket: {task_id}"));
+                Ok(serde_json::json!({ "task_id": task_id }))
+            }
+            ControlCommand::Review { branch } => {
+                self.attach_branch(&branch);
+                if self.review_state.as_ref().is_some_and(|s| s.branch == branch) {
+                    Ok(serde_json::json!({ "branch": branch }))
+                } else {
+                    // attach_branch reports why in a notification
+                    Err(self
+                        .notifications
+                        .last()
+                        .map(|n| n.message.clone())
+                        .unwrap_or_else(|| format!("Could not review {branch}")))
+                }
+            }
+            ControlCommand::Status => {
+                let agents: Vec<serde_json::Value> = self
+                    .agent_manager
+                    .list()
+                    .iter()
+                    .map(|agent| {
+                        serde_json::json!({
+                            "id": agent.id,
+                            "name": agent.name,
+                            "branch": agent.branch,
+                            "task_id": agent.task_id,
+                            "ended": agent.status == AgentStatus::Ended,
+                            "error": agent.error,
+                        })
+                    })
+                    .collect();
+                Ok(serde_json::json!({ "plan": self.current_plan, "agents": agents }))
+            }
+        }
+    }
+
+    /// Record a progress event in the event log and send it to control
+    /// socket clients
+    pub fn emit_event(&self, event: Event) {
+        let record = EventRecord::now(event);
+        if let Some(ref path) = self.event_log
+            && let Err(e) = cctakt::events::append(path, &record)
+        {
+            debug::log(&format!("Failed to write event log: {e:#}"));
+        }
+        if let Some(ref control) = self.control {
+            control.broadcast(&record);
+        }
+    }
+
+    pub fn check_command_completions(&mut self) {
+        let mut finished = Vec::new();
+        self.running_commands.retain(|running| match running.receiver.try_recv() {
+            Ok(result) => {
+                finished.push((running.task_id.clone(), running.command.clone(), Some(result)));
+                false
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => true,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                finished.push((running.task_id.clone(), running.command.clone(), None));
+                false
+            }
+        });
+
+        for (task_id, command, result) in finished {
+            let outcome = match result {
+                Some(Ok(outcome)) => outcome,
+                Some(Err(e)) => {
+                    self.mark_task_failed(&task_id, &format!("{e}"));
+                    continue;
+                }
+                None => {
+                    self.mark_task_failed(&task_id, "Command thread exited unexpectedly");
+                    continue;
+                }
+            };
+
+            if let Some(ref mut plan) = self.current_plan {
+                plan.complete_run_command(&task_id, &outcome);
+            }
+            self.save_plan();
+            self.emit_task_outcome(&task_id);
+
+            let status = self
+                .current_plan
+                .as_ref()
+                .and_then(|p| p.get_task(&task_id))
+                .map(|t| t.status.clone());
+            let tail = cctakt::plan::output_tail(&outcome.output, 3);
+            match (outcome.success, status) {
+                (true, _) => {
+                    self.add_notification(
+                        format!("Command succeeded: {command}"),
+                        cctakt::plan::NotifyLevel::Success,
+                    );
+                }
+                (false, Some(TaskStatus::Completed)) => {
+                    self.add_notification(
+                        format!("Command failed (allowed): {command}: {tail}"),
+                        cctakt::plan::NotifyLevel::Warning,
+                    );
+                }
+                (false, _) => {
+                    self.add_notification(
+                        format!("Command failed: {command}: {tail}"),
+                        cctakt::plan::NotifyLevel::Error,
+                    );
+                }
+            }
+            debug::log(&format!(
+                "RunCommand {task_id} finished (success={}):\n{}",
+                outcome.success, outcome.output
+            ));
+        }
+    }
+
+    /// Mark a task as failed
+    pub fn mark_task_failed(&mut self, task_id: &str, error: &str) {
+        self.add_notification(
+            format!("Task failed: {error}"),
+            cctakt::plan::NotifyLevel::Error,
+        );
+        self.record_task_failure(task_id, error);
+    }
+
+    /// Mark a task as failed in the plan without a notification
+    fn record_task_failure(&mut self, task_id: &str, error: &str) {
+        let Some(ref mut plan) = self.current_plan else {
+            return;
+        };
+        if !plan.mark_failed(task_id, error) {
+            return;
+        }
+        self.save_plan();
+        self.emit_event(Event::TaskFailed {
+            task_id: task_id.to_string(),
+            agent_id: self.task_agent_id(task_id),
+            error: error.to_string(),
+        });
+    }
+
+    /// Mark a task as completed in the plan (recording `result` if given)
+    fn complete_task(&mut self, task_id: &str, result: Option<TaskResult>) {
+        let Some(ref mut plan) = self.current_plan else {
+            return;
+        };
+        let found = match result {
+            Some(result) => plan.mark_completed(task_id, result),
+            None => plan.update_status(task_id, TaskStatus::Completed),
+        };
+        if !found {
+            return;
+        }
+        self.save_plan();
+        self.emit_event(Event::TaskCompleted {
+            task_id: task_id.to_string(),
+            agent_id: self.task_agent_id(task_id),
+        });
+    }
+
+    /// Emit completion or failure for a task whose status was set elsewhere
+    fn emit_task_outcome(&self, task_id: &str) {
+        let Some(task) = self.current_plan.as_ref().and_then(|p| p.get_task(task_id)) else {
+            return;
+        };
+        let agent_id = self.task_agent_id(task_id);
+        match task.status {
+            TaskStatus::Completed => self.emit_event(Event::TaskCompleted {
+                task_id: task_id.to_string(),
+                agent_id,
+            }),
+            TaskStatus::Failed => self.emit_event(Event::TaskFailed {
+                task_id: task_id.to_string(),
+                agent_id,
+                error: task.error.clone().unwrap_or_default(),
+            }),
+            _ => {}
+        }
+    }
+
+    /// Id of the agent working on a task
+    fn task_agent_id(&self, task_id: &str) -> Option<u64> {
+        let index = self.agent_manager.find_by_task(task_id)?;
+        self.agent_manager.get(index).map(|agent| agent.id)
+    }
+
+    /// Emit `AgentStarted` for a newly added agent
+    fn emit_agent_started(&self, agent_id: u64) {
+        let Some(agent) = self
+            .agent_manager
+            .index_of(agent_id)
+            .and_then(|index| self.agent_manager.get(index))
+        else {
+            return;
+        };
+        self.emit_event(Event::AgentStarted {
+            agent_id: agent.id,
+            name: agent.name.clone(),
+            task_id: agent.task_id.clone(),
+            branch: agent.branch.clone(),
+        });
+    }
+
+    /// Notify that an agent could not be started
+    ///
+    /// A missing `claude` CLI is reported once per session instead of once
+    /// per worker, since every later spawn fails the same way.
+    fn notify_spawn_error(&mut self, what: &str, e: &anyhow::Error) {
+        if is_claude_not_found(e) {
+            if !self.claude_missing_reported {
+                self.claude_missing_reported = true;
+                self.add_notification(e.to_string(), cctakt::plan::NotifyLevel::Error);
+            }
+        } else {
+            self.add_notification(
+                format!("Failed to start {what}: {e}"),
+                cctakt::plan::NotifyLevel::Error,
+            );
+        }
+    }
+
+    /// Take the pending terminal bell request
+    pub fn take_pending_bell(&mut self) -> bool {
+        std::mem::take(&mut self.pending_bell)
+    }
+
+    /// Take the command line waiting to run in the foreground
+    pub fn take_foreground_command(&mut self) -> Option<Vec<String>> {
+        self.foreground_command.take()
+    }
+
+    /// Report how a foreground command went, if it didn't go well
+    pub fn foreground_command_finished(
+        &mut self,
+        command: &[String],
+        status: std::io::Result<std::process::ExitStatus>,
+    ) {
+        let message = match status {
+            Ok(status) if status.success() => return,
+            Ok(status) => format!("'{}' exited with {status}", command[0]),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                format!("'{}' not found", command[0])
+            }
+            Err(e) => format!("Failed to run '{}': {e}", command[0]),
+        };
+        self.add_notification(message, cctakt::plan::NotifyLevel::Error);
+    }
+
+    /// Whether the error flash is currently showing
+    pub fn error_flash_active(&self) -> bool {
+        self.error_flash_until
+            .is_some_and(|until| std::time::Instant::now() < until)
+    }
+
+    /// Add a notification
+    pub fn add_notification(&mut self, message: String, level: cctakt::plan::NotifyLevel) {
+        if matches!(level, cctakt::plan::NotifyLevel::Error) {
+            self.pending_bell |= self.config.error_bell.rings();
+            if self.config.error_bell.flashes() {
+                self.error_flash_until = Some(std::time::Instant::now() + ERROR_FLASH_DURATION);
+            }
+        }
+        if self.config.desktop_notifications
+            && matches!(
+                level,
+                cctakt::plan::NotifyLevel::Success | cctakt::plan::NotifyLevel::Error
+            )
+        {
+            cctakt::desktop::notify("cctakt", &message);
+        }
+        self.notifications.push(Notification {
+            message,
+            level,
+            created_at: std::time::Instant::now(),
+        });
+    }
+
+    /// Save current plan to file (persist status changes across restarts)
+    pub fn save_plan(&mut self) {
+        if let Some(ref plan) = self.current_plan {
+            if let Err(e) = self.plan_manager.save(plan) {
+                debug::log(&format!("Failed to save plan: {e}"));
+            }
+        }
+    }
+
+    /// Append a task to the current plan, creating the plan if needed
+    ///
+    /// The task gets a `tui-N` id so it can't clash with orchestrator-assigned ids.
+    /// Returns the new task id.
+    pub fn append_task(&mut self, action: TaskAction) -> String {
+        let plan = self
+            .current_plan
+            .get_or_insert_with(|| Plan::with_description("Tasks added from cctakt"));
+        let task_id = plan.next_task_id("tui");
+        plan.add_task(Task::new(task_id.clone(), action));
+        self.save_plan();
+        debug::log(&format!("Task appended to plan: {task_id}"));
+        task_id
+    }
+
+    /// Queue a MergeBranch task for the given branch
+    pub fn queue_merge_task(&mut self, branch: &str) {
+        let task_id = self.append_task(TaskAction::MergeBranch {
+            branch: branch.to_string(),
+            target: None,
+        });
+        self.add_notification(
+            format!("Queued merge of {branch} ({task_id})"),
+            cctakt::plan::NotifyLevel::Info,
+        );
+    }
+
+    /// Queue a CleanupWorktree task for the given worktree
+    pub fn queue_cleanup_task(&mut self, worktree: &str) {
+        let task_id = self.append_task(TaskAction::CleanupWorktree {
+            worktree: worktree.to_string(),
+        });
+        self.add_notification(
+            format!("Queued cleanup of {worktree} ({task_id})"),
+            cctakt::plan::NotifyLevel::Info,
+        );
+    }
+
+    /// Turn the branch under review into a MergeBranch plan task and leave review mode
+    pub fn queue_review_as_task(&mut self) {
+        if self.review_blocks_merge() {
+            return;
+        }
+        let Some(branch) = self.review_state.as_ref().map(|s| s.branch.clone()) else {
+            return;
+        };
+        self.queue_merge_task(&branch);
+        self.review_queue.approved += 1;
+        self.cancel_review();
+    }
+
+    /// Clean up old notifications (older than 5 seconds)
+    pub fn cleanup_notifications(&mut self) {
+        let now = std::time::Instant::now();
+        self.notifications
+            .retain(|n| now.duration_since(n.created_at).as_secs() < 5);
+    }
+
+    /// Check if any agent completed its task and update plan
+    pub fn check_agent_task_completions(&mut self) {
+        // Collect ended agents with their task info
+        let ended: Vec<(String, usize, Option<String>)> = self
+            .agent_manager
+            .list()
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| a.status == AgentStatus::Ended)
+            .filter_map(|(index, a)| Some((a.task_id.clone()?, index, a.error.clone())))
+            .collect();
 
         // Process ended agents
         for (task_id, agent_index, error) in ended {
             if let Some(error_msg) = error {
-                // Agent ended with error - mark task as failed
+                // Agent ended with error - mark task as failed. The stderr
+                // lines after the first are kept in the task (see the plan view)
                 debug::log_task(&task_id, "running", "failed");
+                let summary = error_msg.lines().next().unwrap_or_default();
                 self.add_notification(
-                    format!("Worker failed: {error_msg}"),
+                    format!("Worker failed: {summary}"),
                     cctakt::plan::NotifyLevel::Error,
                 );
-                if let Some(ref mut plan) = self.current_plan {
-                    plan.mark_failed(&task_id, &error_msg);
-                    // Persist plan to file so status survives restart
-                    if let Err(e) = self.plan_manager.save(plan) {
-                        debug::log(&format!("Failed to save plan: {e}"));
-                    }
-                }
+                self.record_task_failure(&task_id, &error_msg);
             } else {
                 // Agent ended successfully - get commits and mark completed
-                let commits = if agent_index < self.agent_worktrees.len() {
-                    if let Some(ref worktree_path) = self.agent_worktrees[agent_index] {
-                        get_worker_commits(worktree_path)
-                    } else {
-                        Vec::new()
-                    }
-                } else {
-                    Vec::new()
-                };
+                let commits = self
+                    .agent_manager
+                    .get(agent_index)
+                    .and_then(|a| a.worktree_path.as_ref())
+                    .map(get_worker_commits)
+                    .unwrap_or_default();
 
                 // Warn if no commits
                 if commits.is_empty() {
@@ -1219,53 +3548,349 @@ impl App {
                     commits,
                     pr_number: None,
                     pr_url: None,
+                    output: None,
                 };
 
-                if let Some(ref mut plan) = self.current_plan {
-                    plan.mark_completed(&task_id, result);
-                    // Persist plan to file so status survives restart
-                    if let Err(e) = self.plan_manager.save(plan) {
-                        debug::log(&format!("Failed to save plan: {e}"));
-                    }
-                }
+                self.complete_task(&task_id, Some(result));
                 debug::log_task(&task_id, "running", "completed");
             }
-            self.task_agents.remove(&task_id);
+            if let Some(agent) = self.agent_manager.get_mut(agent_index) {
+                agent.task_id = None;
+            }
         }
     }
 
     /// Resize all agents
     pub fn resize(&mut self, cols: u16, rows: u16) {
+        self.pending_resize = None;
         self.content_cols = cols;
         self.content_rows = rows;
         self.update_agent_sizes();
     }
 
+    /// Record a terminal resize to apply once resizing has settled
+    ///
+    /// Tiling window managers can send a burst of resize events; resizing
+    /// the PTYs for each one floods the agents with SIGWINCH.
+    pub fn queue_resize(&mut self, cols: u16, rows: u16, now: std::time::Instant) {
+        self.pending_resize = Some((cols, rows, now));
+    }
+
+    /// Apply the latest queued resize if none arrived for `RESIZE_DEBOUNCE`
+    ///
+    /// Returns true if a resize was applied.
+    pub fn apply_pending_resize(&mut self, now: std::time::Instant) -> bool {
+        match self.pending_resize {
+            Some((cols, rows, at)) if now.duration_since(at) >= RESIZE_DEBOUNCE => {
+                self.resize(cols, rows);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Grow (positive) or shrink the orchestrator's share of the split view
+    ///
+    /// Lasts for this session; `ui.split_ratio` sets the starting value.
+    pub fn nudge_split(&mut self, delta: f32) {
+        let ratio = (self.config.ui.split_ratio + delta).clamp(MIN_SPLIT_RATIO, MAX_SPLIT_RATIO);
+        // Keep steps landing on round values despite float drift
+        self.config.ui.split_ratio = (ratio * 100.0).round() / 100.0;
+        self.update_agent_sizes();
+    }
+
+    /// Parse output the workers wrote since the last call
+    ///
+    /// Lines whose output the agent has discarded are dropped, as are the
+    /// caches of agents that were closed.
+    pub fn refresh_output_caches(&mut self) {
+        let agents = self.agent_manager.list();
+        self.output_caches
+            .retain(|id, _| agents.iter().any(|agent| agent.id == *id));
+        for agent in agents.iter().filter(|a| a.mode == AgentMode::NonInteractive) {
+            let cache = self.output_caches.entry(agent.id).or_default();
+            let (start, new_output) = agent.output_since(cache.consumed());
+            cache.extend(start, &new_output);
+            cache.discard_before(agent.output_start());
+        }
+    }
+
+    /// Parsed output of a worker (empty if none yet)
+    pub fn output_lines(&self, agent_id: u64) -> &[OutputLine] {
+        self.output_caches
+            .get(&agent_id)
+            .map(OutputCache::lines)
+            .unwrap_or_default()
+    }
+
+    /// Switch between one worker at a time and the worker grid
+    pub fn toggle_worker_grid(&mut self) {
+        self.worker_grid = !self.worker_grid;
+        self.update_agent_sizes();
+    }
+
+    /// Agent indices of the workers tiled in the grid
+    ///
+    /// The grid shows the page of up to `GRID_CAPACITY` workers holding the
+    /// active one; the rest stay reachable through the tabs. Empty when the
+    /// grid is off.
+    pub fn grid_workers(&self) -> Vec<usize> {
+        if !self.worker_grid {
+            return Vec::new();
+        }
+        let workers = self.worker_indices();
+        let active = self.agent_manager.active_worker_index().unwrap_or(0);
+        let start = active / GRID_CAPACITY * GRID_CAPACITY;
+        workers.into_iter().skip(start).take(GRID_CAPACITY).collect()
+    }
+
+    /// Agent indices of all workers, in tab order
+    fn worker_indices(&self) -> Vec<usize> {
+        self.agent_manager
+            .list()
+            .iter()
+            .enumerate()
+            .filter(|(_, agent)| agent.mode == AgentMode::NonInteractive)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Move grid focus by `dx` columns or `dy` rows
+    ///
+    /// Moving left from the first column focuses the orchestrator pane.
+    /// Moving past the bottom (top) row goes to the next (previous) page of
+    /// workers, wrapping around.
+    pub fn move_grid_focus(&mut self, dx: isize, dy: isize) {
+        let workers = self.worker_indices();
+        if workers.is_empty() {
+            return;
+        }
+        let active = self.agent_manager.active_worker_index().unwrap_or(0);
+        let start = active / GRID_CAPACITY * GRID_CAPACITY;
+        let visible = (workers.len() - start).min(GRID_CAPACITY);
+        let (cols, rows) = grid_dims(visible);
+        let (row, col) = ((active - start) / cols, (active - start) % cols);
+
+        let target = if dx != 0 {
+            let col = col as isize + dx;
+            if col < 0 {
+                self.focused_pane = FocusedPane::Left;
+                return;
+            }
+            let cell = row * cols + col as usize;
+            if col as usize >= cols || cell >= visible {
+                return;
+            }
+            start + cell
+        } else {
+            let row = row as isize + dy;
+            if row < 0 {
+                // Last worker of the previous page
+                let page = if start == 0 {
+                    (workers.len() - 1) / GRID_CAPACITY * GRID_CAPACITY
+                } else {
+                    start - GRID_CAPACITY
+                };
+                (page + GRID_CAPACITY).min(workers.len()) - 1
+            } else if row as usize >= rows {
+                // First worker of the next page
+                let next = start + GRID_CAPACITY;
+                if next < workers.len() { next } else { 0 }
+            } else {
+                start + (row as usize * cols + col).min(visible - 1)
+            }
+        };
+
+        self.focused_pane = FocusedPane::Right;
+        self.agent_manager.switch_to_worker(target);
+        self.update_agent_sizes();
+    }
+
     /// Update PTY sizes based on current pane layout
     pub fn update_agent_sizes(&mut self) {
         let has_workers = self.agent_manager.has_non_interactive();
 
         if has_workers {
-            // Split view: 50% each (minus separator)
-            let pane_width = (self.content_cols.saturating_sub(1)) / 2;
+            // Split view at the configured ratio and direction
+            let ((left_cols, left_rows), (right_cols, right_rows)) = split_pane_content_sizes(
+                self.content_cols,
+                self.content_rows,
+                self.config.ui.split_ratio,
+                self.config.ui.split_direction,
+            );
 
             // Resize interactive agent (left pane)
             if let Some(agent) = self.agent_manager.get_interactive_mut() {
-                agent.resize(pane_width, self.content_rows);
+                agent.resize(left_cols, left_rows);
             }
 
             // Resize non-interactive agents (right pane)
             for agent in self.agent_manager.get_all_non_interactive_mut() {
-                agent.resize(pane_width, self.content_rows);
+                agent.resize(right_cols, right_rows);
             }
+            self.resize_grid_workers(right_cols, right_rows);
         } else {
             // Full width for single agent
             self.agent_manager.resize_all(self.content_cols, self.content_rows);
         }
     }
 
+    /// Size the workers shown in the grid to their cells inside a worker
+    /// pane of `cols` x `rows`
+    fn resize_grid_workers(&mut self, cols: u16, rows: u16) {
+        let workers = self.grid_workers();
+        let pane = ratatui::layout::Rect::new(0, 0, cols.saturating_add(2), rows.saturating_add(2));
+        for (index, cell) in workers.iter().zip(grid_cells(pane, workers.len())) {
+            if let Some(agent) = self.agent_manager.get_mut(*index) {
+                agent.resize(cell.width.saturating_sub(2), cell.height.saturating_sub(2));
+            }
+        }
+    }
+
+    /// Working directory of an agent: its worktree, else where it was started
+    pub fn agent_dir(&self, index: usize) -> Option<PathBuf> {
+        let agent = self.agent_manager.get(index)?;
+        Some(agent.worktree_path.clone().unwrap_or_else(|| agent.working_dir.clone()))
+    }
+
+    /// Index of the agent shown in the focused pane
+    fn focused_agent_index(&self) -> Option<usize> {
+        let agents = self.agent_manager.list();
+        let is_mode = |index: usize, mode: AgentMode| agents.get(index).is_some_and(|a| a.mode == mode);
+        let wanted = match self.focused_pane {
+            FocusedPane::Left => AgentMode::Interactive,
+            FocusedPane::Right => AgentMode::NonInteractive,
+        };
+        let active = self.agent_manager.active_index();
+        if is_mode(active, wanted) {
+            Some(active)
+        } else {
+            (0..agents.len()).find(|&i| is_mode(i, wanted))
+        }
+    }
+
+    /// Open the focused agent's working directory in the editor (detached)
+    pub fn open_in_editor(&mut self) {
+        let Some(dir) = self.focused_agent_index().and_then(|i| self.agent_dir(i)) else {
+            self.add_notification(
+                "No agent to open".to_string(),
+                cctakt::plan::NotifyLevel::Warning,
+            );
+            return;
+        };
+        let Some(EditorCommand { words, terminal }) = editor_command(
+            self.config.editor.as_deref(),
+            env::var("VISUAL").ok().as_deref(),
+            env::var("EDITOR").ok().as_deref(),
+        ) else {
+            self.add_notification(
+                "No editor configured: set `editor` in .cctakt.toml, $VISUAL or $EDITOR".to_string(),
+                cctakt::plan::NotifyLevel::Error,
+            );
+            return;
+        };
+
+        if terminal {
+            let mut command = words;
+            command.push(dir.to_string_lossy().into_owned());
+            self.foreground_command = Some(command);
+            return;
+        }
+
+        let result = Command::new(&words[0])
+            .args(&words[1..])
+            .arg(&dir)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn();
+        let home = env::var_os("HOME").map(PathBuf::from);
+        let shown = display_path(&dir, home.as_deref());
+        match result {
+            Ok(mut child) => {
+                // Reaped in the background so it doesn't linger as a zombie
+                std::thread::spawn(move || child.wait());
+                self.add_notification(
+                    format!("Opened {shown} in {}", words[0]),
+                    cctakt::plan::NotifyLevel::Info,
+                );
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => self.add_notification(
+                format!("Editor '{}' not found", words[0]),
+                cctakt::plan::NotifyLevel::Error,
+            ),
+            Err(e) => self.add_notification(
+                format!("Failed to open editor '{}': {e}", words[0]),
+                cctakt::plan::NotifyLevel::Error,
+            ),
+        }
+    }
+
+    /// Tell the user once per session when worktrees land outside the repository
+    ///
+    /// That is a valid setup (e.g. `worktree_dir = "../worktrees"`), but an
+    /// unintended one is easy to miss.
+    fn warn_if_worktree_outside_repo(&mut self, path: &Path) {
+        if self.worktree_dir_warned
+            || !self.worktree_manager.as_ref().is_some_and(|wt| wt.is_outside_repo(path))
+        {
+            return;
+        }
+        self.worktree_dir_warned = true;
+        self.add_notification(
+            format!("Worktree created outside the repository: {}", path.display()),
+            cctakt::plan::NotifyLevel::Warning,
+        );
+    }
+
+    /// Web page for an agent: the pull request opened for its branch, else its issue
+    pub fn agent_web_url(&self, index: usize) -> Option<String> {
+        let agent = self.agent_manager.get(index)?;
+        let pr_url = agent.branch.as_deref().and_then(|branch| {
+            self.current_plan.as_ref()?.tasks.iter().rev().find_map(|task| {
+                let url = task.result.as_ref()?.pr_url.clone()?;
+                task.action.branch_names().first().is_some_and(|b| *b == branch).then_some(url)
+            })
+        });
+        pr_url.or_else(|| agent.issue.as_ref().map(|issue| issue.html_url.clone()))
+    }
+
+    /// Open the focused agent's pull request or issue in the browser
+    pub fn open_agent_in_browser(&mut self) {
+        match self.focused_agent_index().and_then(|i| self.agent_web_url(i)) {
+            Some(url) => self.open_in_browser(&url),
+            None => self.add_notification(
+                "The focused agent has no issue or pull request".to_string(),
+                cctakt::plan::NotifyLevel::Warning,
+            ),
+        }
+    }
+
+    /// Open a URL in the browser; without one, copy it to the clipboard and show it
+    pub fn open_in_browser(&mut self, url: &str) {
+        match cctakt::browser::open_url(url) {
+            Ok(OpenUrl::Opened) => self.add_notification(
+                format!("Opened {url}"),
+                cctakt::plan::NotifyLevel::Info,
+            ),
+            Ok(OpenUrl::Terminal(command)) => self.foreground_command = Some(command),
+            Err(e) => {
+                let copied = cctakt::browser::copy_to_clipboard(url).is_ok();
+                let message = if copied {
+                    format!("{e:#}; copied {url} to the clipboard")
+                } else {
+                    format!("{e:#}: {url}")
+                };
+                self.add_notification(message, cctakt::plan::NotifyLevel::Warning);
+            }
+        }
+    }
+
     /// Restart the conductor (orchestrator) agent
     pub fn restart_conductor(&mut self) -> Result<()> {
         self.agent_manager.restart_interactive(self.content_rows, self.content_cols)
     }
 }
+
+#[cfg(test)]
+mod tests;
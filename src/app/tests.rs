@@ -0,0 +1,2193 @@
+//! Orchestration tests driven through a mock agent spawner
+
+use super::*;
+use crate::agent::{Agent, AgentMode, ClaudeNotFound, MockSpawner};
+use cctakt::plan::NotifyLevel;
+use cctakt::SplitDirection;
+use tempfile::TempDir;
+
+/// Spawner whose workers never run a process; tests end them by hand
+fn detached_spawner() -> MockSpawner {
+    let mut spawner = MockSpawner::new();
+    spawner
+        .expect_spawn_non_interactive()
+        .returning(|id, name, working_dir, _, _, branch| {
+            Ok(Agent::detached(id, name, working_dir, AgentMode::NonInteractive, branch))
+        });
+    spawner
+}
+
+/// App using `spawner`, with no worktrees and the plan stored in a temp dir
+fn test_app(spawner: MockSpawner) -> (App, TempDir) {
+    let dir = TempDir::new().unwrap();
+    let mut app = App::new(24, 80, Config::default());
+    app.agent_manager = AgentManager::with_spawner(Box::new(spawner));
+    app.worktree_manager = None;
+    app.plan_manager = PlanManager::new(dir.path());
+    (app, dir)
+}
+
+fn end_agent(app: &mut App, index: usize, error: Option<&str>) {
+    let agent = app.agent_manager.get_mut(index).unwrap();
+    agent.status = AgentStatus::Ended;
+    agent.error = error.map(str::to_string);
+}
+
+fn has_notification(app: &App, level: NotifyLevel, text: &str) -> bool {
+    app.notifications
+        .iter()
+        .any(|n| n.level == level && n.message.contains(text))
+}
+
+fn plan_with_worker(id: &str) -> Plan {
+    let mut plan = Plan::new();
+    plan.add_task(Task::create_worker(id, "feat/x", "Implement x"));
+    plan
+}
+
+fn task_status(app: &App, id: &str) -> TaskStatus {
+    app.current_plan
+        .as_ref()
+        .and_then(|plan| plan.get_task(id))
+        .map(|task| task.status.clone())
+        .unwrap()
+}
+
+// ==================== Plan worker tests ====================
+
+#[test]
+fn test_plan_worker_success_completes_task() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.current_plan = Some(plan_with_worker("w1"));
+
+    app.process_plan();
+    assert_eq!(task_status(&app, "w1"), TaskStatus::Running);
+    let index = app.agent_manager.find_by_task("w1").unwrap();
+
+    app.check_agent_task_completions();
+    assert_eq!(task_status(&app, "w1"), TaskStatus::Running);
+
+    end_agent(&mut app, index, None);
+    app.check_agent_task_completions();
+    assert_eq!(task_status(&app, "w1"), TaskStatus::Completed);
+    assert_eq!(app.agent_manager.find_by_task("w1"), None);
+    assert!(has_notification(&app, NotifyLevel::Warning, "completed with no commits"));
+}
+
+#[test]
+fn test_plan_worker_error_fails_task() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.current_plan = Some(plan_with_worker("w1"));
+
+    app.process_plan();
+    let index = app.agent_manager.find_by_task("w1").unwrap();
+    end_agent(&mut app, index, Some("max turns reached"));
+    app.check_agent_task_completions();
+
+    assert_eq!(task_status(&app, "w1"), TaskStatus::Failed);
+    assert!(has_notification(&app, NotifyLevel::Error, "Worker failed: max turns reached"));
+}
+
+#[test]
+fn test_plan_worker_error_keeps_stderr_in_task() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.current_plan = Some(plan_with_worker("w1"));
+
+    app.process_plan();
+    let index = app.agent_manager.find_by_task("w1").unwrap();
+    end_agent(&mut app, index, Some("Process exited with status: 1\nError: invalid API key"));
+    app.check_agent_task_completions();
+
+    let task = app.current_plan.as_ref().unwrap().get_task("w1").unwrap();
+    assert_eq!(task.error.as_deref(), Some("Process exited with status: 1\nError: invalid API key"));
+    let failure = app.notifications.iter().find(|n| n.message.starts_with("Worker failed")).unwrap();
+    assert_eq!(failure.message, "Worker failed: Process exited with status: 1");
+}
+
+#[test]
+fn test_missing_claude_is_reported_once() {
+    let mut spawner = MockSpawner::new();
+    spawner
+        .expect_spawn_non_interactive()
+        .returning(|_, _, _, _, _, _| Err(ClaudeNotFound.into()));
+    let (mut app, _dir) = test_app(spawner);
+    let mut plan = plan_with_worker("w1");
+    plan.add_task(Task::create_worker("w2", "feat/y", "Implement y"));
+    app.current_plan = Some(plan);
+
+    app.process_plan();
+    app.process_plan();
+
+    assert_eq!(task_status(&app, "w1"), TaskStatus::Failed);
+    assert_eq!(task_status(&app, "w2"), TaskStatus::Failed);
+    let reports = app
+        .notifications
+        .iter()
+        .filter(|n| n.message.contains("claude CLI not found"))
+        .count();
+    assert_eq!(reports, 1);
+}
+
+#[test]
+fn test_transient_spawn_failure_is_retried_next_frame() {
+    let mut spawner = MockSpawner::new();
+    let mut calls = 0;
+    spawner
+        .expect_spawn_non_interactive()
+        .returning(move |id, name, working_dir, _, _, branch| {
+            calls += 1;
+            if calls == 1 {
+                return Err(std::io::Error::from(std::io::ErrorKind::WouldBlock).into());
+            }
+            Ok(Agent::detached(id, name, working_dir, AgentMode::NonInteractive, branch))
+        });
+    let (mut app, _dir) = test_app(spawner);
+    app.config.agent.spawn_retry_backoff_ms = 0;
+    app.current_plan = Some(plan_with_worker("w1"));
+
+    app.process_plan();
+    assert!(app.agent_manager.is_empty());
+    assert_eq!(app.pending_spawns.len(), 1);
+    assert_eq!(task_status(&app, "w1"), TaskStatus::Running);
+
+    app.process_plan();
+    assert!(app.pending_spawns.is_empty());
+    assert_eq!(app.agent_manager.len(), 1);
+    assert_eq!(task_status(&app, "w1"), TaskStatus::Running);
+    assert!(has_notification(&app, NotifyLevel::Success, "Worker started"));
+}
+
+// ==================== BuildWorker tests ====================
+
+#[test]
+fn test_build_worker_failure_is_reported() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.config.build_command = Some("make".to_string());
+
+    app.spawn_build_worker("feat/x".to_string());
+    let index = app.agent_manager.index_of(app.build_worker_id.unwrap()).unwrap();
+    end_agent(&mut app, index, Some("build failed"));
+    app.check_build_worker_completion();
+
+    assert!(app.build_worker_id.is_none());
+    assert!(app.agent_manager.is_empty());
+    assert!(has_notification(&app, NotifyLevel::Error, "Build failed: feat/x"));
+}
+
+#[test]
+fn test_build_worker_success_is_reported() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.config.build_command = Some("make".to_string());
+
+    app.spawn_build_worker("feat/x".to_string());
+    let index = app.agent_manager.index_of(app.build_worker_id.unwrap()).unwrap();
+    end_agent(&mut app, index, None);
+    app.check_build_worker_completion();
+
+    assert!(has_notification(&app, NotifyLevel::Success, "Build succeeded: feat/x"));
+}
+
+// ==================== Plan completion tests ====================
+
+#[test]
+fn test_plan_completion_fires_once() {
+    let (mut app, _dir) = test_app(MockSpawner::new());
+    let mut plan = Plan::new();
+    plan.add_task(Task::notify("n1", "hello"));
+    plan.add_task(Task::notify("n2", "hello"));
+    app.current_plan = Some(plan);
+
+    app.check_plan_completion();
+    assert!(!has_notification(&app, NotifyLevel::Success, "Plan complete"));
+
+    let plan = app.current_plan.as_mut().unwrap();
+    plan.update_status("n1", TaskStatus::Completed);
+    plan.mark_failed("n2", "boom");
+    app.check_plan_completion();
+    app.check_plan_completion();
+
+    let reports = app
+        .notifications
+        .iter()
+        .filter(|n| n.message == "Plan complete: 1 completed, 1 failed")
+        .count();
+    assert_eq!(reports, 1);
+}
+
+#[test]
+fn test_plan_completion_runs_hook_with_summary() {
+    let (mut app, dir) = test_app(MockSpawner::new());
+    let marker = dir.path().join("summary.txt");
+    app.config.on_plan_complete = Some(format!(
+        "echo \"$CCTAKT_PLAN_COMPLETED/$CCTAKT_PLAN_TOTAL\" > '{}'",
+        marker.display()
+    ));
+    let mut plan = Plan::new();
+    plan.add_task(Task::notify("n1", "hello"));
+    plan.update_status("n1", TaskStatus::Completed);
+    app.current_plan = Some(plan);
+
+    app.check_plan_completion();
+    while app.plan_hook.is_some() {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        app.check_plan_completion();
+    }
+
+    assert_eq!(std::fs::read_to_string(marker).unwrap().trim(), "1/1");
+    assert!(!has_notification(&app, NotifyLevel::Warning, "on_plan_complete failed"));
+}
+
+/// Plan with one notify task in `status`, saved to the app's plan file
+fn finished_plan(app: &mut App, status: TaskStatus) {
+    let mut plan = Plan::new();
+    plan.add_task(Task::notify("n1", "hello"));
+    plan.update_status("n1", status);
+    app.plan_manager.save(&plan).unwrap();
+    app.current_plan = Some(plan);
+}
+
+#[test]
+fn test_completed_plan_is_archived() {
+    let (mut app, _dir) = test_app(MockSpawner::new());
+    finished_plan(&mut app, TaskStatus::Completed);
+
+    app.check_plan_completion();
+    assert!(!app.plan_manager.plan_file().exists());
+    assert!(app.current_plan.is_none());
+    assert!(has_notification(&app, NotifyLevel::Info, "Plan archived to"));
+}
+
+#[test]
+fn test_plan_with_failures_stays_for_retry() {
+    let (mut app, _dir) = test_app(MockSpawner::new());
+    finished_plan(&mut app, TaskStatus::Failed);
+
+    app.check_plan_completion();
+    assert!(app.plan_manager.plan_file().exists());
+    assert!(app.current_plan.is_some());
+}
+
+#[test]
+fn test_plan_archiving_can_be_disabled() {
+    let (mut app, _dir) = test_app(MockSpawner::new());
+    app.config.archive_completed_plans = false;
+    finished_plan(&mut app, TaskStatus::Completed);
+
+    app.check_plan_completion();
+    assert!(app.plan_manager.plan_file().exists());
+    assert!(app.current_plan.is_some());
+}
+
+#[test]
+fn test_loaded_completed_plan_is_archived() {
+    let (mut app, dir) = test_app(MockSpawner::new());
+    finished_plan(&mut app, TaskStatus::Completed);
+    // A later session finds the finished plan
+    app.current_plan = None;
+    app.plan_manager = PlanManager::new(dir.path());
+
+    app.check_plan();
+    assert!(app.current_plan.is_none());
+    assert!(!app.plan_manager.plan_file().exists());
+}
+
+// ==================== Error bell tests ====================
+
+#[test]
+fn test_error_bell_off_by_default() {
+    let (mut app, _dir) = test_app(MockSpawner::new());
+    app.add_notification("boom".to_string(), NotifyLevel::Error);
+    assert!(!app.take_pending_bell());
+    assert!(!app.error_flash_active());
+}
+
+#[test]
+fn test_error_bell_rings_and_flashes_on_errors_only() {
+    let (mut app, _dir) = test_app(MockSpawner::new());
+    app.config.error_bell = cctakt::ErrorBell::Both;
+
+    app.add_notification("ok".to_string(), NotifyLevel::Success);
+    assert!(!app.take_pending_bell());
+    assert!(!app.error_flash_active());
+
+    app.add_notification("boom".to_string(), NotifyLevel::Error);
+    assert!(app.take_pending_bell());
+    assert!(!app.take_pending_bell());
+    assert!(app.error_flash_active());
+}
+
+// ==================== Agent directory tests ====================
+
+#[test]
+fn test_agent_dir_prefers_worktree() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.agent_manager
+        .add_non_interactive("w".to_string(), PathBuf::from("/repo"), "task", None, None)
+        .unwrap();
+    assert_eq!(app.agent_dir(0), Some(PathBuf::from("/repo")));
+
+    app.agent_manager.get_mut(0).unwrap().worktree_path = Some(PathBuf::from("/repo/.worktrees/w"));
+    assert_eq!(app.agent_dir(0), Some(PathBuf::from("/repo/.worktrees/w")));
+    assert_eq!(app.agent_dir(1), None);
+}
+
+// ==================== Auto review tests ====================
+
+/// Add a worker on `branch` working in `worktree`, ended
+fn add_ended_worker(app: &mut App, branch: &str, worktree: Option<PathBuf>) -> u64 {
+    let id = app
+        .agent_manager
+        .add_non_interactive(branch.to_string(), PathBuf::from("."), "task", None, Some(branch.to_string()))
+        .unwrap();
+    let agent = app.agent_manager.last_mut().unwrap();
+    agent.worktree_path = worktree;
+    agent.status = AgentStatus::Ended;
+    id
+}
+
+/// Repo on main with a worktree per branch, each branch one commit ahead
+fn worker_worktrees(branches: &[&str]) -> (TempDir, Vec<PathBuf>) {
+    let temp = TempDir::new().unwrap();
+    let repo = temp.path().join("repo");
+    std::fs::create_dir(&repo).unwrap();
+    git(&repo, &["init", "-b", "main"]);
+    git(&repo, &["config", "user.email", "test@test.com"]);
+    git(&repo, &["config", "user.name", "Test User"]);
+    git(&repo, &["commit", "--allow-empty", "-m", "init", "--no-gpg-sign"]);
+    let paths = branches
+        .iter()
+        .enumerate()
+        .map(|(i, branch)| {
+            let path = temp.path().join(format!("wt{i}"));
+            git(&repo, &["worktree", "add", "-b", branch, path.to_str().unwrap()]);
+            git(&path, &["commit", "--allow-empty", "-m", "work", "--no-gpg-sign"]);
+            path
+        })
+        .collect();
+    (temp, paths)
+}
+
+#[test]
+fn test_agent_web_url_prefers_pull_request() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    add_ended_worker(&mut app, "feat/x", None);
+    let index = app.agent_manager.list().len() - 1;
+    assert_eq!(app.agent_web_url(index), None);
+
+    app.agent_manager.last_mut().unwrap().issue = Some(checklist_issue(""));
+    assert_eq!(
+        app.agent_web_url(index).as_deref(),
+        Some("https://github.com/test/repo/issues/12")
+    );
+
+    let mut plan = plan_with_worker("w1");
+    plan.add_task(Task::create_pr("w1-pr", "feat/x", "Title"));
+    plan.get_task_mut("w1-pr").unwrap().result = Some(TaskResult {
+        commits: Vec::new(),
+        pr_number: Some(7),
+        pr_url: Some("https://github.com/o/r/pull/7".to_string()),
+        output: None,
+    });
+    app.current_plan = Some(plan);
+    assert_eq!(app.agent_web_url(index).as_deref(), Some("https://github.com/o/r/pull/7"));
+}
+
+#[test]
+fn test_ended_agent_without_worktree_is_not_reviewed() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    add_ended_worker(&mut app, "main", None);
+
+    app.review_ended_active_agent();
+    assert_eq!(app.mode, AppMode::Normal);
+    assert!(app.review_state.is_none());
+}
+
+#[test]
+fn test_start_review_index_out_of_range_after_close() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let cwd = std::env::current_dir().unwrap();
+    add_ended_worker(&mut app, "feat/a", Some(cwd.clone()));
+    add_ended_worker(&mut app, "feat/b", Some(cwd));
+    app.agent_manager.close(1);
+
+    app.start_review(1);
+    assert_eq!(app.mode, AppMode::Normal);
+    assert!(app.review_state.is_none());
+}
+
+#[test]
+fn test_review_uses_agent_branch_and_id() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let (_repo, worktrees) = worker_worktrees(&["feat/a", "feat/b"]);
+    add_ended_worker(&mut app, "feat/a", Some(worktrees[0].clone()));
+    let id = add_ended_worker(&mut app, "feat/b", Some(worktrees[1].clone()));
+
+    app.review_ended_active_agent();
+    assert_eq!(app.mode, AppMode::ReviewMerge);
+    let review = app.review_state.as_ref().unwrap();
+    assert_eq!(review.branch, "feat/b");
+    assert_eq!(review.agent_id, Some(id));
+}
+
+#[test]
+fn test_cancelled_review_is_not_reopened() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let (_repo, worktrees) = worker_worktrees(&["feat/a"]);
+    add_ended_worker(&mut app, "feat/a", Some(worktrees[0].clone()));
+
+    app.review_ended_active_agent();
+    assert_eq!(app.mode, AppMode::ReviewMerge);
+    app.cancel_review();
+
+    app.review_ended_active_agent();
+    assert_eq!(app.mode, AppMode::Normal);
+    assert!(app.review_state.is_none());
+}
+
+#[test]
+fn test_worktree_on_another_branch_is_not_reviewed() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let (_repo, worktrees) = worker_worktrees(&["feat/a"]);
+    let worktree = &worktrees[0];
+    // The worker switched its worktree to a branch off main and committed there
+    git(worktree, &["checkout", "-b", "other", "main"]);
+    add_ended_worker(&mut app, "feat/a", Some(worktree.clone()));
+
+    app.review_ended_active_agent();
+    assert_eq!(app.mode, AppMode::Normal);
+    assert!(has_notification(&app, NotifyLevel::Warning, "is on other instead of feat/a"));
+
+    // Not retried on the next frame
+    let count = app.notifications.len();
+    app.review_ended_active_agent();
+    assert_eq!(app.notifications.len(), count);
+}
+
+#[test]
+fn test_detached_worktree_is_not_reviewed() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let (_repo, worktrees) = worker_worktrees(&["feat/a"]);
+    git(&worktrees[0], &["checkout", "--detach"]);
+    add_ended_worker(&mut app, "feat/a", Some(worktrees[0].clone()));
+
+    app.review_ended_active_agent();
+    assert!(app.review_state.is_none());
+    assert!(has_notification(&app, NotifyLevel::Warning, "detached HEAD"));
+}
+
+#[test]
+fn test_branch_without_commits_is_not_reviewed() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let (_repo, worktrees) = worker_worktrees(&["feat/a"]);
+    git(&worktrees[0], &["reset", "--hard", "main"]);
+    add_ended_worker(&mut app, "feat/a", Some(worktrees[0].clone()));
+
+    app.review_ended_active_agent();
+    assert!(app.review_state.is_none());
+    assert!(has_notification(&app, NotifyLevel::Warning, "no commits ahead of main"));
+}
+
+// ==================== Review commits tests ====================
+
+#[test]
+fn test_review_commits_overlay_opens_and_closes() {
+    let (mut app, _dir) = test_app(MockSpawner::new());
+    app.review_state = Some(ReviewState {
+        agent_id: None,
+        branch: "feat/x".to_string(),
+        base: "main".to_string(),
+        worktree_path: std::env::current_dir().unwrap(),
+        diff_view: cctakt::DiffView::new(String::new()),
+        commit_log: String::new(),
+        files_changed: 0,
+        insertions: 0,
+        deletions: 0,
+        ahead_by: 0,
+        behind_by: 0,
+        conflicts: vec![],
+        uncommitted: vec![],
+        nonconforming_commits: vec![],
+        focus: ReviewFocus::default(),
+        summary_scroll: 0,
+        commits_view: None,
+        base_dialog: None,
+        search_input: None,
+        reject_dialog: None,
+        comments: Vec::new(),
+        comment_dialog: None,
+        post_review_pr: None,
+        draft_pr: false,
+        diff_kind: ReviewDiff::Branch,
+    });
+    assert!(!app.review_commits_open());
+
+    app.open_review_commits();
+    assert!(app.review_commits_open());
+
+    app.close_review_commits();
+    assert!(!app.review_commits_open());
+    assert!(app.review_state.is_some());
+}
+
+#[test]
+fn test_review_unknown_base_is_rejected() {
+    let (mut app, _dir) = test_app(MockSpawner::new());
+    let worktree_path = std::env::current_dir().unwrap();
+    app.start_review_for_branch("HEAD", &worktree_path, Some("main"));
+    app.open_review_base_prompt();
+    assert!(app.review_base_prompt_open());
+
+    app.change_review_base("no-such-base-branch");
+    let state = app.review_state.as_ref().unwrap();
+    assert_eq!(state.base, "main");
+    assert!(has_notification(&app, NotifyLevel::Error, "Unknown base branch"));
+}
+
+// ==================== In-process merge tests ====================
+
+fn git(dir: &std::path::Path, args: &[&str]) {
+    let output = std::process::Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {args:?}: {output:?}");
+}
+
+/// Repo on main with a `feat` branch; both sides edit `file` when `conflicting`
+fn merge_repo(conflicting: bool) -> TempDir {
+    let temp = TempDir::new().unwrap();
+    let dir = temp.path();
+    git(dir, &["init", "-b", "main"]);
+    git(dir, &["config", "user.email", "test@test.com"]);
+    git(dir, &["config", "user.name", "Test User"]);
+    git(dir, &["config", "commit.gpgsign", "false"]);
+    std::fs::write(dir.join("file"), "base\n").unwrap();
+    git(dir, &["add", "file"]);
+    git(dir, &["commit", "-m", "init"]);
+    git(dir, &["checkout", "-b", "feat"]);
+    std::fs::write(dir.join("feature"), "x\n").unwrap();
+    std::fs::write(dir.join("file"), "feat\n").unwrap();
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-m", "feat"]);
+    git(dir, &["checkout", "main"]);
+    if conflicting {
+        std::fs::write(dir.join("file"), "main\n").unwrap();
+        git(dir, &["commit", "-am", "main change"]);
+    }
+    temp
+}
+
+fn start_merge_task(app: &mut App, branch: &str, target: &str) {
+    app.merge_queue.enqueue(MergeTask {
+        branch: branch.to_string(),
+        target: target.to_string(),
+        worktree_path: PathBuf::from("/nonexistent"),
+        task_id: None,
+        target_head_before: None,
+    });
+    app.merge_queue.start_next();
+}
+
+#[test]
+fn test_clean_branch_merges_in_process() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let repo = merge_repo(false);
+    start_merge_task(&mut app, "feat", "main");
+
+    assert!(app.merge_in_process(repo.path(), "feat", "main"));
+    assert!(!app.merge_queue.is_busy());
+    assert!(MergeManager::new(repo.path()).is_merged("feat").unwrap());
+    assert!(has_notification(&app, NotifyLevel::Success, "Merged: feat → main"));
+}
+
+#[test]
+fn test_clean_branch_merges_into_review_base() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let repo = merge_repo(false);
+    git(repo.path(), &["checkout", "-b", "release"]);
+    start_merge_task(&mut app, "feat", "release");
+
+    // Checked out on release, so merging into main must not happen here
+    assert!(!app.merge_in_process(repo.path(), "feat", "main"));
+    assert!(app.merge_in_process(repo.path(), "feat", "release"));
+    assert!(has_notification(&app, NotifyLevel::Success, "Merged: feat → release"));
+    let release = MergeManager::new(repo.path()).with_main_branch("release");
+    assert!(release.is_merged("feat").unwrap());
+    assert!(!MergeManager::new(repo.path()).is_merged("feat").unwrap());
+}
+
+#[test]
+fn test_conflicting_branch_is_left_to_merge_worker() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let repo = merge_repo(true);
+    start_merge_task(&mut app, "feat", "main");
+
+    assert!(!app.merge_in_process(repo.path(), "feat", "main"));
+    assert!(app.merge_queue.is_busy());
+    assert!(!MergeManager::new(repo.path()).is_merged("feat").unwrap());
+}
+
+#[test]
+fn test_signed_repo_is_left_to_merge_worker() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let repo = merge_repo(false);
+    git(repo.path(), &["config", "commit.gpgsign", "true"]);
+    start_merge_task(&mut app, "feat", "main");
+
+    assert!(!app.merge_in_process(repo.path(), "feat", "main"));
+    assert!(app.merge_queue.is_busy());
+    assert!(has_notification(&app, NotifyLevel::Info, "Commit signing is enabled"));
+}
+
+#[test]
+fn test_worktree_kept_after_merge_when_cleanup_disabled() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.config.cleanup_after_merge = false;
+    let repo = merge_repo(false);
+    start_merge_task(&mut app, "feat", "main");
+
+    assert!(app.merge_in_process(repo.path(), "feat", "main"));
+    assert!(has_notification(&app, NotifyLevel::Info, "Worktree kept at /nonexistent"));
+}
+
+// ==================== Tab reordering tests ====================
+
+#[test]
+fn test_move_active_agent_keeps_metadata_with_agents() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    for name in ["a", "b", "c"] {
+        app.agent_manager
+            .add_non_interactive(name.to_string(), PathBuf::from("."), "task", None, None)
+            .unwrap();
+        let agent = app.agent_manager.last_mut().unwrap();
+        agent.worktree_path = Some(PathBuf::from(name));
+        agent.task_id = Some(format!("task-{name}"));
+    }
+    app.build_worker_id = Some(app.agent_manager.get(1).unwrap().id);
+    app.agent_manager.switch_to(2);
+
+    app.move_active_agent(-1);
+    app.move_active_agent(-1);
+    app.move_active_agent(-1);
+
+    let names: Vec<_> = app.agent_manager.list().iter().map(|a| a.name.clone()).collect();
+    assert_eq!(names, ["c", "a", "b"]);
+    assert_eq!(app.agent_manager.active_index(), 0);
+    assert_eq!(app.agent_manager.find_by_task("task-c"), Some(0));
+    assert_eq!(app.agent_manager.get_by_id(app.build_worker_id.unwrap()).unwrap().name, "b");
+
+    app.move_active_agent(1);
+    assert_eq!(app.agent_dir(1), Some(PathBuf::from("c")));
+    assert_eq!(app.agent_manager.find_by_task("task-c"), Some(1));
+}
+
+#[test]
+fn test_closing_agent_keeps_metadata_with_remaining_agents() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let mut plan = plan_with_worker("w1");
+    plan.add_task(Task::create_worker("w2", "feat/y", "Implement y"));
+    app.current_plan = Some(plan);
+    app.process_plan();
+    app.process_plan();
+    app.agent_manager.get_mut(1).unwrap().worktree_path = Some(PathBuf::from("/wt/feat-y"));
+
+    app.agent_manager.switch_to(0);
+    app.close_active_agent();
+
+    assert_eq!(app.agent_manager.find_by_task("w2"), Some(0));
+    assert_eq!(app.agent_dir(0), Some(PathBuf::from("/wt/feat-y")));
+}
+
+#[test]
+fn test_build_worker_is_tracked_by_id_across_closes() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.config.build_command = Some("make".to_string());
+    app.agent_manager
+        .add_non_interactive("other".to_string(), PathBuf::from("."), "task", None, None)
+        .unwrap();
+    app.spawn_build_worker("feat/x".to_string());
+
+    // Closing an earlier tab shifts positions but not ids
+    app.agent_manager.close(0);
+    end_agent(&mut app, 0, None);
+    app.check_build_worker_completion();
+
+    assert!(app.build_worker_id.is_none());
+    assert!(app.agent_manager.is_empty());
+    assert!(has_notification(&app, NotifyLevel::Success, "Build succeeded: feat/x"));
+}
+
+// ==================== Attach tests ====================
+
+#[test]
+fn test_attach_unknown_branch_reports_error() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let repo = merge_repo(false);
+    app.worktree_manager = Some(WorktreeManager::new(repo.path()).unwrap());
+
+    app.attach_branch("missing");
+
+    assert_eq!(app.mode, AppMode::Normal);
+    let last = app.notifications.last().unwrap();
+    assert_eq!(last.level, NotifyLevel::Error);
+    assert!(last.message.contains("'missing' does not exist"));
+}
+
+#[test]
+fn test_attach_branch_without_changes_is_not_reviewed() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let repo = merge_repo(false);
+    git(repo.path(), &["branch", "same"]);
+    app.worktree_manager = Some(WorktreeManager::new(repo.path()).unwrap());
+
+    app.attach_branch("same");
+
+    assert_eq!(app.mode, AppMode::Normal);
+    assert!(app.notifications.last().unwrap().message.starts_with("Nothing to review"));
+    assert!(app.review_state.is_none());
+}
+
+#[test]
+fn test_attach_creates_worktree_and_starts_review() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let repo = merge_repo(false);
+    app.worktree_manager = Some(WorktreeManager::new(repo.path()).unwrap());
+    app.config.worktree_dir = repo.path().join(".worktrees");
+
+    app.attach_branch("feat");
+
+    assert_eq!(app.mode, AppMode::ReviewMerge);
+    let state = app.review_state.as_ref().unwrap();
+    assert_eq!(state.branch, "feat");
+    assert_eq!(state.base, "main");
+    assert_eq!(state.worktree_path, repo.path().join(".worktrees").join("feat"));
+    assert!(state.worktree_path.is_dir());
+}
+
+// ==================== Draft PR tests ====================
+
+#[test]
+fn test_toggle_review_draft_needs_pull_request_mode() {
+    let (mut app, _dir) = review_app("+x");
+    app.toggle_review_draft();
+    assert!(!app.review_state.as_ref().unwrap().draft_pr);
+    assert!(has_notification(&app, NotifyLevel::Warning, "Draft PRs only apply"));
+
+    app.config.merge_mode = MergeMode::PullRequest;
+    app.toggle_review_draft();
+    assert!(app.review_state.as_ref().unwrap().draft_pr);
+    app.toggle_review_draft();
+    assert!(!app.review_state.as_ref().unwrap().draft_pr);
+}
+
+// ==================== Review queue tests ====================
+
+#[test]
+fn test_review_all_pending_cycles_through_finished_workers() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.config.auto_merge_clean = false;
+    let (_repo, worktrees) = worker_worktrees(&["feat/a", "feat/b"]);
+    let first = add_ended_worker(&mut app, "feat/a", Some(worktrees[0].clone()));
+    add_ended_worker(&mut app, "feat/orchestrated", None);
+    add_ended_worker(&mut app, "feat/b", Some(worktrees[1].clone()));
+
+    app.review_all_pending();
+    let review = app.review_state.as_ref().unwrap();
+    assert_eq!((review.branch.as_str(), review.agent_id), ("feat/a", Some(first)));
+    assert_eq!(app.review_queue.progress(), Some((1, 2)));
+
+    // Approving queues the merge and opens the next review
+    app.approve_review();
+    assert_eq!(app.merge_queue.pending_count(), 1);
+    assert_eq!(app.mode, AppMode::ReviewMerge);
+    assert_eq!(app.review_state.as_ref().unwrap().branch, "feat/b");
+    assert_eq!(app.review_queue.progress(), Some((2, 2)));
+
+    // Skipping the last one ends the queue
+    app.cancel_review();
+    assert_eq!(app.mode, AppMode::Normal);
+    assert!(!app.review_queue.is_active());
+    assert!(has_notification(&app, NotifyLevel::Info, "Reviewed 2 worker(s): 1 approved"));
+}
+
+#[test]
+fn test_stop_review_queue() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let (_repo, worktrees) = worker_worktrees(&["feat/a", "feat/b"]);
+    add_ended_worker(&mut app, "feat/a", Some(worktrees[0].clone()));
+    add_ended_worker(&mut app, "feat/b", Some(worktrees[1].clone()));
+
+    app.review_all_pending();
+    app.stop_review_queue();
+    assert_eq!(app.mode, AppMode::Normal);
+    assert!(app.review_state.is_none());
+    assert!(!app.review_queue.is_active());
+}
+
+#[test]
+fn test_review_all_pending_without_finished_workers() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.review_all_pending();
+    assert_eq!(app.mode, AppMode::Normal);
+    assert!(has_notification(&app, NotifyLevel::Info, "No finished workers"));
+}
+
+// ==================== Update from base tests ====================
+
+/// Review of `feat` in `repo`, checked out there
+fn update_review_app(repo: &TempDir) -> (App, TempDir) {
+    git(repo.path(), &["checkout", "feat"]);
+    let (mut app, dir) = review_app("+x");
+    app.review_state.as_mut().unwrap().worktree_path = repo.path().to_path_buf();
+    app.review_state.as_mut().unwrap().agent_id = Some(7);
+    (app, dir)
+}
+
+#[test]
+fn test_update_review_branch_merges_base() {
+    let repo = merge_repo(false);
+    std::fs::write(repo.path().join("other"), "main\n").unwrap();
+    git(repo.path(), &["add", "other"]);
+    git(repo.path(), &["commit", "-m", "main work"]);
+    let (mut app, _dir) = update_review_app(&repo);
+
+    app.update_review_branch();
+
+    assert!(has_notification(&app, NotifyLevel::Success, "Merged main"));
+    let merger = MergeManager::new(repo.path());
+    assert_eq!(merger.commits_ahead("main", "feat").unwrap(), 0);
+    // The review is refreshed for the same reviewer
+    assert_eq!(app.review_state.as_ref().unwrap().agent_id, Some(7));
+}
+
+#[test]
+fn test_update_review_branch_leaves_conflicts_in_worktree() {
+    let repo = merge_repo(true);
+    let (mut app, _dir) = update_review_app(&repo);
+    app.config.update_strategy = UpdateStrategy::Rebase;
+
+    app.update_review_branch();
+
+    assert!(has_notification(&app, NotifyLevel::Warning, "conflicts in file"));
+    assert!(has_notification(&app, NotifyLevel::Warning, "git rebase --abort"));
+    let content = std::fs::read_to_string(repo.path().join("file")).unwrap();
+    assert!(content.contains("<<<<<<<"));
+    // Merging stays blocked until the conflict is resolved
+    assert!(!app.review_state.as_ref().unwrap().uncommitted.is_empty());
+}
+
+#[test]
+fn test_update_review_branch_refuses_signed_rebase() {
+    let repo = merge_repo(false);
+    git(repo.path(), &["config", "commit.gpgsign", "true"]);
+    let (mut app, _dir) = update_review_app(&repo);
+    app.config.update_strategy = UpdateStrategy::Rebase;
+    let merger = MergeManager::new(repo.path());
+    let before = merger.rev_parse("feat").unwrap();
+
+    app.update_review_branch();
+
+    assert!(has_notification(&app, NotifyLevel::Warning, "Commit signing is enabled — rebase onto main"));
+    assert_eq!(merger.rev_parse("feat").unwrap(), before);
+}
+
+#[test]
+fn test_update_review_branch_refuses_uncommitted_changes() {
+    let repo = merge_repo(true);
+    let (mut app, _dir) = update_review_app(&repo);
+    std::fs::write(repo.path().join("feature"), "edited\n").unwrap();
+
+    app.update_review_branch();
+
+    assert!(has_notification(&app, NotifyLevel::Warning, "commit them before updating"));
+    let merger = MergeManager::new(repo.path());
+    assert_eq!(merger.commits_ahead("main", "feat").unwrap(), 1);
+}
+
+#[test]
+fn test_merge_refused_with_uncommitted_changes() {
+    let repo = merge_repo(false);
+    git(repo.path(), &["checkout", "feat"]);
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.start_review_for_branch("feat", &repo.path().to_path_buf(), Some("main"));
+    assert_eq!(app.mode, AppMode::ReviewMerge);
+    std::fs::write(repo.path().join("feature"), "edited\n").unwrap();
+
+    app.enqueue_merge();
+    assert_eq!(app.merge_queue.pending_count(), 0);
+    assert!(app.merge_queue.current.is_none());
+    assert!(has_notification(&app, NotifyLevel::Warning, "1 uncommitted change(s)"));
+
+    app.queue_review_as_task();
+    assert!(app.current_plan.is_none());
+    assert_eq!(app.mode, AppMode::ReviewMerge);
+    assert_eq!(app.review_state.as_ref().unwrap().uncommitted.len(), 1);
+}
+
+// ==================== Commit convention tests ====================
+
+#[test]
+fn test_review_lists_nonconforming_commits() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let repo = merge_repo(false);
+    git(repo.path(), &["checkout", "feat"]);
+
+    app.start_review_for_branch("feat", &repo.path().to_path_buf(), Some("main"));
+    let state = app.review_state.as_ref().unwrap();
+    assert_eq!(state.nonconforming_commits.len(), 1);
+    assert!(state.nonconforming_commits[0].ends_with(" feat"));
+}
+
+#[test]
+fn test_commit_convention_is_advisory_by_default() {
+    let (mut app, _dir) = review_app("+x");
+    app.review_state.as_mut().unwrap().nonconforming_commits = vec!["abc1234 wip".to_string()];
+
+    app.queue_review_as_task();
+    assert!(app.review_state.is_none());
+    assert_eq!(app.current_plan.as_ref().unwrap().tasks.len(), 1);
+}
+
+#[test]
+fn test_enforced_commit_convention_blocks_merge() {
+    let (mut app, _dir) = review_app("+x");
+    app.config.enforce_commit_convention = true;
+    app.review_state.as_mut().unwrap().nonconforming_commits = vec!["abc1234 wip".to_string()];
+
+    app.queue_review_as_task();
+    app.enqueue_merge();
+    assert!(app.review_state.is_some());
+    assert!(app.current_plan.is_none());
+    assert_eq!(app.merge_queue.pending_count(), 0);
+    assert!(has_notification(&app, NotifyLevel::Warning, "1 commit(s) don't follow the commit convention"));
+
+    app.review_state.as_mut().unwrap().nonconforming_commits.clear();
+    app.queue_review_as_task();
+    assert!(app.review_state.is_none());
+}
+
+// ==================== Review search tests ====================
+
+fn review_state(diff: &str) -> ReviewState {
+    ReviewState {
+        agent_id: None,
+        branch: "feat".to_string(),
+        base: "main".to_string(),
+        worktree_path: PathBuf::from("/nonexistent"),
+        diff_view: DiffView::new(diff.to_string()),
+        commit_log: String::new(),
+        files_changed: 0,
+        insertions: 0,
+        deletions: 0,
+        ahead_by: 0,
+        behind_by: 0,
+        conflicts: vec![],
+        uncommitted: vec![],
+        nonconforming_commits: vec![],
+        focus: ReviewFocus::Summary,
+        summary_scroll: 0,
+        commits_view: None,
+        base_dialog: None,
+        search_input: None,
+        reject_dialog: None,
+        comments: Vec::new(),
+        comment_dialog: None,
+        post_review_pr: None,
+        draft_pr: false,
+        diff_kind: ReviewDiff::Branch,
+    }
+}
+
+fn review_app(diff: &str) -> (App, TempDir) {
+    let (mut app, dir) = test_app(detached_spawner());
+    app.review_state = Some(review_state(diff));
+    app.mode = AppMode::ReviewMerge;
+    (app, dir)
+}
+
+#[test]
+fn test_review_search_prompt_flow() {
+    let (mut app, _dir) = review_app("+alpha\n beta\n+Alpha again");
+
+    app.open_review_search();
+    assert!(app.review_search_open());
+    app.review_state.as_mut().unwrap().search_input = Some("alpha".to_string());
+    app.run_review_search();
+
+    assert!(!app.review_search_open());
+    let state = app.review_state.as_ref().unwrap();
+    assert_eq!(state.focus, ReviewFocus::Diff);
+    assert_eq!(state.diff_view.search_matches(), &[0, 2]);
+
+    app.review_search_step(true);
+    assert_eq!(app.review_state.as_ref().unwrap().diff_view.scroll_position(), 2);
+
+    // Esc clears the search but stays in review
+    app.clear_review_search();
+    assert_eq!(app.mode, AppMode::ReviewMerge);
+    assert!(app.review_state.as_ref().unwrap().diff_view.search_query().is_empty());
+}
+
+#[test]
+fn test_review_search_without_matches_notifies() {
+    let (mut app, _dir) = review_app("+alpha");
+    app.open_review_search();
+    app.review_state.as_mut().unwrap().search_input = Some("zeta".to_string());
+    app.run_review_search();
+
+    assert_eq!(app.notifications.last().unwrap().message, "No matches for 'zeta'");
+}
+
+// ==================== Review reject tests ====================
+
+#[test]
+fn test_reject_review_restarts_worker_with_feedback() {
+    let mut spawner = MockSpawner::new();
+    spawner
+        .expect_spawn_non_interactive()
+        .times(2)
+        .returning(|id, name, working_dir, task, _, branch| {
+            assert!(id == 1 || task.contains("Review feedback:\nhandle the empty case"));
+            Ok(Agent::detached(id, name, working_dir, AgentMode::NonInteractive, branch))
+        });
+    let (mut app, _dir) = test_app(spawner);
+    let worktree = PathBuf::from("/repo/.worktrees/feat");
+    let old_id = app
+        .agent_manager
+        .add_non_interactive("#7".to_string(), worktree.clone(), "task", None, Some("feat".to_string()))
+        .unwrap();
+    if let Some(agent) = app.agent_manager.last_mut() {
+        agent.worktree_path = Some(worktree.clone());
+        agent.task_id = Some("t1".to_string());
+    }
+    end_agent(&mut app, 0, None);
+
+    let mut state = review_state("+x");
+    state.agent_id = Some(old_id);
+    state.worktree_path = worktree.clone();
+    app.review_state = Some(state);
+    app.pending_review_task_id = Some("review-t1".to_string());
+    app.mode = AppMode::ReviewMerge;
+
+    app.open_review_reject_prompt();
+    assert!(app.review_reject_prompt_open());
+    app.reject_review("handle the empty case");
+
+    assert_eq!(app.mode, AppMode::Normal);
+    assert!(app.review_state.is_none());
+    assert!(app.pending_review_task_id.is_none());
+    assert!(app.agent_manager.get_by_id(old_id).is_none());
+    let agent = app.agent_manager.active().unwrap();
+    assert_ne!(agent.id, old_id);
+    assert_eq!(agent.name, "#7");
+    assert_eq!(agent.worktree_path.as_ref(), Some(&worktree));
+    assert_eq!(agent.task_id.as_deref(), Some("t1"));
+    assert_eq!(agent.branch.as_deref(), Some("feat"));
+}
+
+// ==================== Review comment tests ====================
+
+const COMMENT_DIFF: &str = "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -10,2 +10,2 @@\n fn a() {}\n-old\n+new\n";
+
+#[test]
+fn test_review_comment_add_edit_remove() {
+    let (mut app, _dir) = review_app(COMMENT_DIFF);
+    app.review_state.as_mut().unwrap().diff_view.scroll_down(6);
+
+    app.open_review_comment_prompt();
+    assert!(app.review_comment_prompt_open());
+    let (location, _) = app.review_state.as_mut().unwrap().comment_dialog.take().unwrap();
+    assert_eq!(location.to_string(), "src/lib.rs:11");
+
+    app.set_review_comment(location.clone(), "  unwrap can panic ");
+    let state = app.review_state.as_ref().unwrap();
+    assert_eq!(state.comments.len(), 1);
+    assert_eq!(state.comments[0].text, "unwrap can panic");
+    assert!(state.diff_view.is_commented(6));
+
+    // Reopening loads the existing text; submitting replaces it
+    app.open_review_comment_prompt();
+    let (_, dialog) = app.review_state.as_ref().unwrap().comment_dialog.as_ref().unwrap();
+    assert_eq!(dialog.value(), "unwrap can panic");
+    app.set_review_comment(location.clone(), "use ?");
+    assert_eq!(app.review_state.as_ref().unwrap().comments[0].text, "use ?");
+
+    app.set_review_comment(location, "");
+    let state = app.review_state.as_ref().unwrap();
+    assert!(state.comments.is_empty());
+    assert!(!state.diff_view.is_commented(6));
+}
+
+#[test]
+fn test_review_comment_needs_a_code_line() {
+    let (mut app, _dir) = review_app("");
+    app.open_review_comment_prompt();
+    assert!(!app.review_comment_prompt_open());
+    assert!(has_notification(&app, NotifyLevel::Warning, "No diff line"));
+}
+
+#[test]
+fn test_reject_review_sends_line_comments() {
+    let mut spawner = MockSpawner::new();
+    spawner
+        .expect_spawn_non_interactive()
+        .times(1)
+        .returning(|id, name, working_dir, task, _, branch| {
+            assert!(task.contains("Comments on specific lines:\n- src/lib.rs:10: rename this"));
+            Ok(Agent::detached(id, name, working_dir, AgentMode::NonInteractive, branch))
+        });
+    let (mut app, _dir) = test_app(spawner);
+    app.review_state = Some(review_state(COMMENT_DIFF));
+    app.mode = AppMode::ReviewMerge;
+
+    let location = app.review_state.as_ref().unwrap().diff_view.location(4).cloned().unwrap();
+    app.set_review_comment(location, "rename this");
+    app.reject_review("");
+
+    assert!(app.review_state.is_none());
+    assert_eq!(app.agent_manager.list().len(), 1);
+}
+
+#[test]
+fn test_post_review_requires_auth() {
+    let (mut app, _dir) = review_app(COMMENT_DIFF);
+    app.github_client = Some(GitHubClient::with_token("owner/repo", None));
+
+    app.open_review_post_prompt();
+
+    assert!(!app.review_post_prompt_open());
+    assert!(has_notification(&app, NotifyLevel::Error, "needs a GitHub token"));
+}
+
+#[test]
+fn test_post_review_without_comments_only_approves() {
+    let (mut app, _dir) = review_app(COMMENT_DIFF);
+    app.review_state.as_mut().unwrap().post_review_pr = Some(5);
+
+    app.post_review(ReviewEvent::RequestChanges);
+
+    assert!(!app.review_post_prompt_open());
+    assert!(has_notification(&app, NotifyLevel::Warning, "Add line comments"));
+}
+
+// ==================== Issue sub-task plan tests ====================
+
+fn checklist_issue(body: &str) -> Issue {
+    Issue {
+        number: 12,
+        title: "Split me".to_string(),
+        body: Some(body.to_string()),
+        labels: vec![],
+        state: "open".to_string(),
+        html_url: "https://github.com/test/repo/issues/12".to_string(),
+        comments: 0,
+        created_at: None,
+        updated_at: None,
+    }
+}
+
+#[test]
+fn test_add_plan_from_issue_writes_one_task_per_item() {
+    let (mut app, _dir) = test_app(MockSpawner::new());
+
+    app.add_plan_from_issue(checklist_issue("- [ ] first\n- [x] done\n- [ ] second"))
+        .unwrap();
+
+    let plan = app.current_plan.as_ref().unwrap();
+    assert_eq!(plan.description.as_deref(), Some("Issue #12: Split me"));
+    let ids: Vec<&str> = plan.tasks.iter().map(|t| t.id.as_str()).collect();
+    assert_eq!(ids, ["issue-12-1", "issue-12-2"]);
+    assert!(app.plan_manager.load().unwrap().is_some());
+    assert!(app.agent_manager.is_empty());
+    assert!(has_notification(&app, NotifyLevel::Info, "split into 2 plan tasks"));
+}
+
+#[test]
+fn test_add_plan_from_issue_appends_to_running_plan() {
+    let (mut app, _dir) = test_app(MockSpawner::new());
+    app.current_plan = Some(plan_with_worker("w1"));
+    let issue = checklist_issue("- [ ] only");
+
+    app.add_plan_from_issue(issue.clone()).unwrap();
+    app.add_plan_from_issue(issue).unwrap();
+
+    let ids: Vec<&str> = app
+        .current_plan
+        .as_ref()
+        .unwrap()
+        .tasks
+        .iter()
+        .map(|t| t.id.as_str())
+        .collect();
+    assert_eq!(ids, ["w1", "issue-12-1", "issue-12-1-1"]);
+}
+
+#[test]
+fn test_add_plan_from_issue_without_checklist_starts_worker() {
+    let mut spawner = MockSpawner::new();
+    spawner
+        .expect_spawn_non_interactive()
+        .times(1)
+        .returning(|id, name, working_dir, _, _, branch| {
+            Ok(Agent::detached(id, name, working_dir, AgentMode::NonInteractive, branch))
+        });
+    let (mut app, _dir) = test_app(spawner);
+
+    app.add_plan_from_issue(checklist_issue("Just prose")).unwrap();
+
+    assert!(app.current_plan.is_none());
+    assert_eq!(app.agent_manager.list()[0].name, "#12");
+}
+
+#[test]
+fn test_agent_from_issue_uses_name_style() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.config.agent_name_style = cctakt::AgentNameStyle::NumberSlug;
+    app.add_agent_from_issue(checklist_issue("Just prose")).unwrap();
+    assert_eq!(app.agent_manager.list()[0].name, "#12 split-me");
+
+    app.config.agent_name_style = cctakt::AgentNameStyle::Branch;
+    app.add_agent_from_issue(checklist_issue("Just prose")).unwrap();
+    let agent = &app.agent_manager.list()[1];
+    assert_eq!(Some(agent.name.as_str()), agent.branch.as_deref());
+}
+
+#[test]
+fn test_add_full_plan_from_issue_plans_lifecycle() {
+    let (mut app, _dir) = test_app(MockSpawner::new());
+    app.current_plan = Some(plan_with_worker("w1"));
+
+    app.add_full_plan_from_issue(checklist_issue("- [ ] ignored"));
+    let ids: Vec<&str> = app
+        .current_plan
+        .as_ref()
+        .unwrap()
+        .tasks
+        .iter()
+        .map(|t| t.id.as_str())
+        .collect();
+    assert_eq!(ids, ["w1", "issue-12", "issue-12-review", "issue-12-merge", "issue-12-cleanup"]);
+    assert!(app.plan_manager.load().unwrap().is_some());
+    assert!(has_notification(&app, NotifyLevel::Info, "Issue #12 planned"));
+
+    // Picking the same issue again doesn't duplicate its tasks
+    app.add_full_plan_from_issue(checklist_issue(""));
+    assert_eq!(app.current_plan.as_ref().unwrap().tasks.len(), 5);
+    assert!(has_notification(&app, NotifyLevel::Warning, "already in the plan"));
+}
+
+// ==================== Resize tests ====================
+
+#[test]
+fn test_split_pane_widths_fill_odd_and_even_terminals() {
+    for content_cols in [38, 78, 79, 80, 119, 120] {
+        let ((left, left_rows), (right, right_rows)) =
+            split_pane_content_sizes(content_cols, 20, 0.5, SplitDirection::Horizontal);
+        // Both panes' borders plus the separator take five columns
+        assert_eq!(left + right + 5, content_cols + 2, "width {content_cols}");
+        assert!(left.abs_diff(right) <= 1, "width {content_cols}");
+        assert_eq!((left_rows, right_rows), (20, 20));
+    }
+}
+
+#[test]
+fn test_split_pane_sizes_follow_ratio_and_direction() {
+    // 100 content columns: 102 wide, 101 after the separator
+    let ((left, _), (right, _)) = split_pane_content_sizes(100, 20, 0.6, SplitDirection::Horizontal);
+    assert_eq!((left, right), (59, 38));
+
+    // Stacked: 22 rows, 21 after the separator, split 11 / 10
+    let (top, bottom) = split_pane_content_sizes(100, 20, 0.5, SplitDirection::Vertical);
+    assert_eq!(top, (100, 9));
+    assert_eq!(bottom, (100, 8));
+}
+
+#[test]
+fn test_split_keeps_a_minimum_pane_in_tiny_terminals() {
+    let ((left, _), (right, _)) = split_pane_content_sizes(10, 5, 0.9, SplitDirection::Horizontal);
+    assert_eq!((left, right), (6, 1));
+
+    // Too small for borders on both sides: nothing underflows
+    let (top, bottom) = split_pane_content_sizes(4, 0, 0.1, SplitDirection::Vertical);
+    assert_eq!((top, bottom), ((4, 0), (4, 0)));
+}
+
+#[test]
+fn test_nudge_split_clamps_ratio() {
+    let (mut app, _dir) = test_app(detached_spawner());
+
+    app.nudge_split(0.05);
+    assert_eq!(app.config.ui.split_ratio, 0.55);
+
+    for _ in 0..20 {
+        app.nudge_split(0.05);
+    }
+    assert_eq!(app.config.ui.split_ratio, 0.9);
+
+    for _ in 0..20 {
+        app.nudge_split(-0.05);
+    }
+    assert_eq!(app.config.ui.split_ratio, 0.1);
+}
+
+#[test]
+fn test_pane_content_size_excludes_header_footer_and_border() {
+    assert_eq!(crate::tui::render::pane_content_size(80, 24), (78, 19));
+    assert_eq!(crate::tui::render::pane_content_size(1, 3), (0, 0));
+}
+
+#[test]
+fn test_queued_resize_waits_for_debounce() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let start = std::time::Instant::now();
+
+    app.queue_resize(100, 30, start);
+    assert!(!app.apply_pending_resize(start + RESIZE_DEBOUNCE / 2));
+    assert_eq!((app.content_cols, app.content_rows), (80, 24));
+
+    assert!(app.apply_pending_resize(start + RESIZE_DEBOUNCE));
+    assert_eq!((app.content_cols, app.content_rows), (100, 30));
+    assert!(app.pending_resize.is_none());
+}
+
+#[test]
+fn test_rapid_resizes_apply_only_the_last_size() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let start = std::time::Instant::now();
+    let step = RESIZE_DEBOUNCE / 4;
+
+    for (i, cols) in [90, 110, 70, 120].into_iter().enumerate() {
+        let at = start + step * i as u32;
+        app.queue_resize(cols, 40, at);
+        assert!(!app.apply_pending_resize(at + step));
+    }
+
+    assert!(app.apply_pending_resize(start + step * 3 + RESIZE_DEBOUNCE));
+    assert_eq!((app.content_cols, app.content_rows), (120, 40));
+    assert!(!app.apply_pending_resize(start + step * 3 + RESIZE_DEBOUNCE * 2));
+}
+
+// ==================== Worker grid tests ====================
+
+/// App with `count` detached workers and the worker grid on
+fn grid_app(count: usize) -> (App, TempDir) {
+    let (mut app, dir) = test_app(detached_spawner());
+    for i in 0..count {
+        app.agent_manager
+            .add_non_interactive(format!("w{i}"), PathBuf::from("/tmp"), "task", None, None)
+            .unwrap();
+    }
+    app.worker_grid = true;
+    app.agent_manager.switch_to_worker(0);
+    (app, dir)
+}
+
+fn active_worker(app: &App) -> usize {
+    app.agent_manager.active_worker_index().unwrap()
+}
+
+#[test]
+fn test_grid_dims_stack_before_widening() {
+    assert_eq!(crate::tui::render::grid_dims(1), (1, 1));
+    assert_eq!(crate::tui::render::grid_dims(2), (1, 2));
+    assert_eq!(crate::tui::render::grid_dims(3), (2, 2));
+    assert_eq!(crate::tui::render::grid_dims(4), (2, 2));
+}
+
+#[test]
+fn test_grid_workers_show_the_active_page() {
+    let (mut app, _dir) = grid_app(6);
+    assert_eq!(app.grid_workers(), [0, 1, 2, 3]);
+
+    app.agent_manager.switch_to_worker(5);
+    assert_eq!(app.grid_workers(), [4, 5]);
+
+    app.toggle_worker_grid();
+    assert!(app.grid_workers().is_empty());
+}
+
+#[test]
+fn test_move_grid_focus_between_cells() {
+    let (mut app, _dir) = grid_app(4);
+
+    app.move_grid_focus(1, 0);
+    assert_eq!(active_worker(&app), 1);
+    app.move_grid_focus(1, 0);
+    assert_eq!(active_worker(&app), 1, "no cell to the right");
+    app.move_grid_focus(0, 1);
+    assert_eq!(active_worker(&app), 3);
+    app.move_grid_focus(-1, 0);
+    assert_eq!(active_worker(&app), 2);
+
+    app.move_grid_focus(-1, 0);
+    assert_eq!(app.focused_pane, FocusedPane::Left);
+    assert_eq!(active_worker(&app), 2);
+}
+
+#[test]
+fn test_move_grid_focus_pages_past_the_edges() {
+    let (mut app, _dir) = grid_app(6);
+
+    app.agent_manager.switch_to_worker(2);
+    app.move_grid_focus(0, 1);
+    assert_eq!(active_worker(&app), 4);
+
+    app.move_grid_focus(0, -1);
+    assert_eq!(active_worker(&app), 3);
+
+    app.agent_manager.switch_to_worker(0);
+    app.move_grid_focus(0, -1);
+    assert_eq!(active_worker(&app), 5, "wraps to the last page");
+}
+
+// ==================== Output cache tests ====================
+
+#[test]
+fn test_refresh_output_caches_tracks_live_workers() {
+    let (mut app, _dir) = grid_app(2);
+    app.output_caches.insert(99, OutputCache::default());
+
+    app.refresh_output_caches();
+
+    let mut ids: Vec<u64> = app.output_caches.keys().copied().collect();
+    ids.sort();
+    let workers: Vec<u64> = app.agent_manager.list().iter().map(|a| a.id).collect();
+    assert_eq!(ids, workers);
+    assert!(app.output_lines(99).is_empty());
+}
+
+// ==================== Update check tests ====================
+
+#[test]
+fn test_update_check_is_off_by_default() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.start_update_check();
+    assert!(app.update_check.is_none());
+}
+
+#[test]
+fn test_newer_release_is_an_info_notification() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let (sender, receiver) = std::sync::mpsc::channel();
+    app.update_check = Some(receiver);
+
+    app.check_update_notice();
+    assert!(app.update_check.is_some(), "still waiting");
+
+    sender.send(Some("9.9.9".to_string())).unwrap();
+    app.check_update_notice();
+    assert!(app.update_check.is_none());
+    assert!(has_notification(&app, NotifyLevel::Info, "cctakt 9.9.9 is available"));
+}
+
+#[test]
+fn test_failed_update_check_is_silent() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let (sender, receiver) = std::sync::mpsc::channel::<Option<String>>();
+    app.update_check = Some(receiver);
+    drop(sender);
+
+    app.check_update_notice();
+    assert!(app.update_check.is_none());
+    assert!(app.notifications.is_empty());
+}
+
+// ==================== Control socket tests ====================
+
+fn control_add_task(app: &mut App, task: Task) -> Result<serde_json::Value, String> {
+    app.handle_control_command(ControlCommand::AddTask { task: Box::new(task) })
+}
+
+#[test]
+fn test_control_add_task_starts_plan_and_saves_it() {
+    let (mut app, _dir) = test_app(detached_spawner());
+
+    let result = control_add_task(&mut app, Task::create_worker("w1", "feat/x", "Implement x"));
+    assert_eq!(result.unwrap()["task_id"], "w1");
+    assert_eq!(task_status(&app, "w1"), TaskStatus::Pending);
+    let saved = app.plan_manager.load().unwrap().unwrap();
+    assert!(saved.get_task("w1").is_some());
+
+    app.process_plan();
+    assert_eq!(task_status(&app, "w1"), TaskStatus::Running);
+}
+
+#[test]
+fn test_control_add_task_rejects_duplicate_id() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.current_plan = Some(plan_with_worker("w1"));
+
+    let result = control_add_task(&mut app, Task::create_worker("w1", "feat/y", "Implement y"));
+    assert_eq!(result.unwrap_err(), "Task 'w1' already exists");
+    assert_eq!(app.current_plan.as_ref().unwrap().tasks.len(), 1);
+}
+
+#[test]
+fn test_control_add_task_after_complete_plan_starts_new_one() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let mut plan = plan_with_worker("w1");
+    plan.update_status("w1", TaskStatus::Completed);
+    app.current_plan = Some(plan);
+
+    control_add_task(&mut app, Task::create_worker("w1", "feat/y", "Implement y")).unwrap();
+    let plan = app.current_plan.as_ref().unwrap();
+    assert_eq!(plan.tasks.len(), 1);
+    assert_eq!(task_status(&app, "w1"), TaskStatus::Pending);
+}
+
+#[test]
+fn test_control_status_lists_plan_and_agents() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.current_plan = Some(plan_with_worker("w1"));
+    app.process_plan();
+
+    let status = app.handle_control_command(ControlCommand::Status).unwrap();
+    assert_eq!(status["plan"]["tasks"][0]["id"], "w1");
+    assert_eq!(status["agents"][0]["task_id"], "w1");
+    assert_eq!(status["agents"][0]["ended"], false);
+}
+
+#[test]
+fn test_control_review_reports_failure() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let result = app.handle_control_command(ControlCommand::Review {
+        branch: "feat/x".to_string(),
+    });
+    assert!(result.unwrap_err().contains("not in a git repository"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_task_events_reach_control_clients() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    let (mut app, dir) = test_app(detached_spawner());
+    let path = dir.path().join("control.sock");
+    app.control = Some(ControlServer::bind(&path).unwrap());
+    let mut client = UnixStream::connect(&path).unwrap();
+    client
+        .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+        .unwrap();
+    let mut reader = BufReader::new(client.try_clone().unwrap());
+
+    // Once a request has come through, the client is registered for events
+    client.write_all(b"{\"command\":\"status\"}\n").unwrap();
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    while app.control.as_ref().unwrap().try_recv().is_none() {
+        assert!(std::time::Instant::now() < deadline, "request never arrived");
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+
+    let mut plan = plan_with_worker("w1");
+    plan.add_task(Task::create_worker("w2", "feat/y", "Implement y"));
+    app.current_plan = Some(plan);
+    app.process_plan();
+    app.process_plan();
+    let w1 = app.agent_manager.find_by_task("w1").unwrap();
+    let w2 = app.agent_manager.find_by_task("w2").unwrap();
+    end_agent(&mut app, w1, None);
+    end_agent(&mut app, w2, Some("max turns reached"));
+    app.check_agent_task_completions();
+
+    // Two agent_started events, then the outcomes
+    let mut events = Vec::new();
+    for _ in 0..4 {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        events.push(serde_json::from_str::<EventRecord>(&line).unwrap().event);
+    }
+    assert!(matches!(events[0], Event::AgentStarted { .. }));
+    assert!(events.contains(&Event::TaskCompleted {
+        task_id: "w1".to_string(),
+        agent_id: Some(app.agent_manager.get(w1).unwrap().id),
+    }));
+    assert!(events.contains(&Event::TaskFailed {
+        task_id: "w2".to_string(),
+        agent_id: Some(app.agent_manager.get(w2).unwrap().id),
+        error: "max turns reached".to_string(),
+    }));
+}
+
+// ==================== Plan validation tests ====================
+
+#[test]
+fn test_invalid_plan_is_not_loaded() {
+    let (mut app, dir) = test_app(detached_spawner());
+    let mut plan = plan_with_worker("w1");
+    plan.add_task(Task::request_review("r1", "feat/x", Some("w9".to_string())));
+    plan.add_task(Task::create_worker("w1", "feat/y", "Implement y"));
+    PlanManager::new(dir.path()).save(&plan).unwrap();
+
+    app.current_plan = Some(plan_with_worker("old"));
+    app.check_plan();
+    assert!(app.current_plan.is_none());
+    assert!(has_notification(
+        &app,
+        NotifyLevel::Error,
+        "Plan not loaded: duplicate task id 'w1' (and 1 more"
+    ));
+}
+
+#[test]
+fn test_plan_warnings_still_load() {
+    let (mut app, dir) = test_app(detached_spawner());
+    let mut plan = plan_with_worker("w1");
+    plan.add_task(Task::merge_branch("m1", "feat/old", None));
+    PlanManager::new(dir.path()).save(&plan).unwrap();
+
+    app.check_plan();
+    assert!(app.current_plan.is_some());
+    assert!(has_notification(&app, NotifyLevel::Warning, "which no create_worker task creates"));
+}
+
+// ==================== Event log tests ====================
+
+fn logged_events(path: &std::path::Path) -> Vec<Event> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .map(|line| serde_json::from_str::<EventRecord>(line).unwrap().event)
+        .collect()
+}
+
+#[test]
+fn test_event_log_is_off_by_default() {
+    let (app, _dir) = test_app(detached_spawner());
+    assert!(app.event_log.is_none());
+}
+
+#[test]
+fn test_worker_lifecycle_is_logged() {
+    let (mut app, dir) = test_app(detached_spawner());
+    let path = dir.path().join("events.jsonl");
+    app.event_log = Some(path.clone());
+    app.current_plan = Some(plan_with_worker("w1"));
+
+    app.process_plan();
+    let index = app.agent_manager.find_by_task("w1").unwrap();
+    let agent_id = app.agent_manager.get(index).unwrap().id;
+    end_agent(&mut app, index, None);
+    app.check_agent_task_completions();
+    app.check_plan_completion();
+
+    assert_eq!(
+        logged_events(&path),
+        vec![
+            Event::AgentStarted {
+                agent_id,
+                name: "feat/x".to_string(),
+                task_id: Some("w1".to_string()),
+                branch: Some("feat/x".to_string()),
+            },
+            Event::TaskCompleted {
+                task_id: "w1".to_string(),
+                agent_id: Some(agent_id),
+            },
+            Event::PlanComplete { completed: 1, failed: 0 },
+        ]
+    );
+}
+
+#[test]
+fn test_failed_task_is_logged_once() {
+    let (mut app, dir) = test_app(detached_spawner());
+    let path = dir.path().join("events.jsonl");
+    app.event_log = Some(path.clone());
+    app.current_plan = Some(plan_with_worker("w1"));
+
+    app.mark_task_failed("w1", "boom");
+    app.mark_task_failed("missing", "not in the plan");
+
+    assert_eq!(
+        logged_events(&path),
+        vec![Event::TaskFailed {
+            task_id: "w1".to_string(),
+            agent_id: None,
+            error: "boom".to_string(),
+        }]
+    );
+}
+
+// ==================== Plan view tests ====================
+
+#[test]
+fn test_plan_view_needs_a_plan() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.open_plan_view();
+    assert_eq!(app.mode, AppMode::Normal);
+    assert!(has_notification(&app, NotifyLevel::Info, "No plan loaded"));
+
+    app.current_plan = Some(plan_with_worker("w1"));
+    app.plan_view_scroll = 5;
+    app.open_plan_view();
+    assert_eq!(app.mode, AppMode::PlanView);
+    assert_eq!(app.plan_view_scroll, 0);
+}
+
+#[test]
+fn test_plan_view_selection_stays_in_range() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let mut plan = plan_with_worker("w1");
+    plan.add_task(Task::create_worker("w2", "feat/y", "Implement y"));
+    app.current_plan = Some(plan);
+    app.open_plan_view();
+
+    app.move_plan_selection(-1);
+    assert_eq!(app.selected_plan_task().as_deref(), Some("w1"));
+    app.move_plan_selection(isize::MAX);
+    assert_eq!(app.selected_plan_task().as_deref(), Some("w2"));
+    app.move_plan_selection(isize::MIN);
+    assert_eq!(app.selected_plan_task().as_deref(), Some("w1"));
+}
+
+#[test]
+fn test_retry_failed_worker_starts_it_again() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.current_plan = Some(plan_with_worker("w1"));
+
+    app.process_plan();
+    let index = app.agent_manager.find_by_task("w1").unwrap();
+    end_agent(&mut app, index, Some("max turns reached"));
+    app.check_agent_task_completions();
+    assert_eq!(task_status(&app, "w1"), TaskStatus::Failed);
+
+    app.retry_task("w1");
+    assert_eq!(task_status(&app, "w1"), TaskStatus::Pending);
+    assert!(app.agent_manager.is_empty());
+    let task = app.current_plan.as_ref().unwrap().get_task("w1").unwrap();
+    assert!(task.error.is_none());
+
+    app.process_plan();
+    assert_eq!(task_status(&app, "w1"), TaskStatus::Running);
+    assert!(app.agent_manager.find_by_task("w1").is_some());
+}
+
+#[test]
+fn test_retry_only_failed_tasks() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.current_plan = Some(plan_with_worker("w1"));
+
+    app.retry_task("w1");
+    assert_eq!(task_status(&app, "w1"), TaskStatus::Pending);
+    assert!(has_notification(&app, NotifyLevel::Warning, "only failed tasks can be retried"));
+}
+
+#[test]
+fn test_skip_failed_worker_skips_its_review() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let mut plan = plan_with_worker("w1");
+    plan.add_task(Task::request_review("w1-review", "feat/x", Some("w1".to_string())));
+    plan.add_task(Task::create_worker("w2", "feat/y", "Implement y"));
+    app.current_plan = Some(plan);
+
+    app.process_plan();
+    let index = app.agent_manager.find_by_task("w1").unwrap();
+    end_agent(&mut app, index, Some("boom"));
+    app.check_agent_task_completions();
+
+    app.skip_task("w1");
+    assert_eq!(task_status(&app, "w1"), TaskStatus::Skipped);
+    assert_eq!(task_status(&app, "w1-review"), TaskStatus::Skipped);
+    assert_eq!(task_status(&app, "w2"), TaskStatus::Pending);
+    assert!(has_notification(&app, NotifyLevel::Info, "Skipped w1 and 1 task(s) waiting for it"));
+
+    app.process_plan();
+    assert_eq!(task_status(&app, "w2"), TaskStatus::Running);
+}
+
+// ==================== Attention tests ====================
+
+#[test]
+fn test_nothing_needs_attention() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    add_ended_worker(&mut app, "feat/a", None);
+    app.jump_to_attention();
+    assert_eq!(app.mode, AppMode::Normal);
+    assert!(has_notification(&app, NotifyLevel::Info, "Nothing needs attention"));
+}
+
+#[test]
+fn test_failed_task_comes_before_failed_agent() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    add_ended_worker(&mut app, "feat/a", None);
+    add_ended_worker(&mut app, "feat/b", None);
+    app.agent_manager.get_mut(1).unwrap().error = Some("max turns reached".to_string());
+    assert_eq!(app.attention_target(), Some(AttentionTarget::FailedAgent(1)));
+
+    app.jump_to_attention();
+    assert_eq!(app.agent_manager.active_index(), 1);
+    assert_eq!(app.focused_pane, FocusedPane::Right);
+
+    let mut plan = plan_with_worker("w1");
+    plan.add_task(Task::create_worker("w2", "feat/y", "Implement y"));
+    plan.update_status("w2", TaskStatus::Failed);
+    app.current_plan = Some(plan);
+
+    app.jump_to_attention();
+    assert_eq!(app.mode, AppMode::PlanView);
+    assert_eq!(app.selected_plan_task().as_deref(), Some("w2"));
+}
+
+#[test]
+fn test_jump_to_conflicting_branch_opens_review() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let repo = merge_repo(true);
+    git(repo.path(), &["checkout", "feat"]);
+    add_ended_worker(&mut app, "feat", Some(repo.path().to_path_buf()));
+    app.agent_manager.switch_to(0);
+
+    assert_eq!(app.attention_target(), Some(AttentionTarget::ConflictingBranch(0)));
+    app.jump_to_attention();
+    assert_eq!(app.mode, AppMode::ReviewMerge);
+    assert_eq!(app.review_state.as_ref().unwrap().branch, "feat");
+}
+
+// ==================== No-worktree mode tests ====================
+
+/// App that runs workers in `repo`'s main checkout (`use_worktrees = false`)
+fn in_place_app(repo: &std::path::Path) -> (App, TempDir) {
+    let (mut app, dir) = test_app(detached_spawner());
+    app.config.use_worktrees = false;
+    app.worktree_manager = Some(WorktreeManager::new(repo).unwrap());
+    (app, dir)
+}
+
+#[test]
+fn test_worker_runs_in_main_checkout() {
+    let repo = merge_repo(false);
+    let (mut app, _dir) = in_place_app(repo.path());
+    let mut plan = Plan::new();
+    plan.add_task(Task::create_worker("w1", "task/x", "Implement x"));
+    app.current_plan = Some(plan);
+
+    app.process_plan();
+    assert_eq!(task_status(&app, "w1"), TaskStatus::Running);
+    let index = app.agent_manager.find_by_task("w1").unwrap();
+    let worktree = app.agent_manager.get(index).unwrap().worktree_path.clone().unwrap();
+    assert!(app.is_main_checkout(&worktree));
+    assert_eq!(
+        WorktreeManager::current_branch(repo.path()).unwrap().as_deref(),
+        Some("task/x")
+    );
+    assert_eq!(app.worktree_manager.as_ref().unwrap().list().unwrap().len(), 1);
+}
+
+#[test]
+fn test_second_worker_refused_in_main_checkout() {
+    let repo = merge_repo(false);
+    let (mut app, _dir) = in_place_app(repo.path());
+    let mut plan = Plan::new();
+    plan.add_task(Task::create_worker("w1", "task/x", "Implement x"));
+    app.current_plan = Some(plan);
+    app.process_plan();
+
+    let err = app.checkout_in_main_repo("task/y").unwrap_err();
+    assert!(err.to_string().contains("task/x is still working in the main checkout"), "{err}");
+    assert_eq!(
+        WorktreeManager::current_branch(repo.path()).unwrap().as_deref(),
+        Some("task/x")
+    );
+}
+
+#[test]
+fn test_merge_from_main_checkout_switches_back() {
+    let repo = merge_repo(false);
+    git(repo.path(), &["checkout", "feat"]);
+    let (mut app, _dir) = in_place_app(repo.path());
+    app.config.auto_merge_clean = false;
+    add_ended_worker(&mut app, "feat", Some(repo.path().to_path_buf()));
+
+    app.start_review(0);
+    assert_eq!(app.mode, AppMode::ReviewMerge);
+    // The base is resolved from the test process's cwd, not the temp repo
+    app.review_state.as_mut().unwrap().base = "main".to_string();
+    app.enqueue_merge();
+    assert_eq!(
+        WorktreeManager::current_branch(repo.path()).unwrap().as_deref(),
+        Some("main")
+    );
+
+    let task = MergeTask {
+        branch: "feat".to_string(),
+        target: "main".to_string(),
+        worktree_path: repo.path().to_path_buf(),
+        task_id: None,
+        target_head_before: None,
+    };
+    app.handle_merge_success(&task);
+    assert!(repo.path().join("file").exists());
+}
+
+// ==================== Branch protection tests ====================
+
+/// App whose GitHub repository protects main with required pull requests
+fn protected_main_app(app: &mut App) {
+    app.github_client = Some(GitHubClient::with_token("o/r", None));
+    app.config.push.before_pr = false;
+    app.branch_protection.insert(
+        "main".to_string(),
+        BranchProtection { requires_pull_request: true, required_approvals: 1 },
+    );
+}
+
+#[test]
+fn test_approve_opens_pull_request_for_protected_main() {
+    let (mut app, _dir) = review_app("+alpha");
+    protected_main_app(&mut app);
+
+    app.approve_review();
+    assert!(has_notification(&app, NotifyLevel::Warning, "main only takes changes through pull requests"));
+    // No token, so creating the PR fails and the review stays open
+    assert!(has_notification(&app, NotifyLevel::Error, "Failed to create PR"));
+    assert_eq!(app.merge_queue.pending_count(), 0);
+    assert_eq!(app.mode, AppMode::ReviewMerge);
+}
+
+#[test]
+fn test_approve_merges_when_main_is_unprotected() {
+    let (mut app, _dir) = review_app("+alpha");
+    app.config.auto_merge_clean = false;
+    app.github_client = Some(GitHubClient::with_token("o/r", None));
+    app.branch_protection.insert("main".to_string(), BranchProtection::default());
+
+    app.approve_review();
+    assert!(has_notification(&app, NotifyLevel::Info, "Merge queued"));
+}
+
+#[test]
+fn test_approve_waits_for_branch_protection_lookup() {
+    let (mut app, _dir) = review_app("+alpha");
+    app.config.auto_merge_clean = false;
+    let (sender, receiver) = std::sync::mpsc::channel();
+    app.branch_protection_check = Some(("main".to_string(), receiver));
+
+    app.approve_review();
+    assert!(has_notification(&app, NotifyLevel::Info, "Still checking whether main is protected"));
+    assert_eq!(app.merge_queue.pending_count(), 0);
+    assert_eq!(app.mode, AppMode::ReviewMerge);
+
+    sender.send(BranchProtection::default()).unwrap();
+    app.check_branch_protection();
+    assert!(app.branch_protection_check.is_none());
+    app.approve_review();
+    assert!(has_notification(&app, NotifyLevel::Info, "Merge queued"));
+}
+
+#[test]
+fn test_approve_uses_review_base_protection() {
+    let (mut app, _dir) = review_app("+alpha");
+    protected_main_app(&mut app);
+    app.config.auto_merge_clean = false;
+    app.review_state.as_mut().unwrap().base = "release".to_string();
+    app.branch_protection.insert("release".to_string(), BranchProtection::default());
+
+    app.approve_review();
+    assert!(has_notification(&app, NotifyLevel::Info, "Merge queued"));
+}
+
+#[test]
+fn test_merge_task_on_protected_target_opens_pull_request() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    protected_main_app(&mut app);
+    let mut plan = Plan::new();
+    plan.add_task(Task::merge_branch("m1", "feat/x", Some("main".to_string())));
+    app.current_plan = Some(plan);
+
+    app.process_plan();
+    assert!(has_notification(&app, NotifyLevel::Warning, "opening one for feat/x"));
+    assert_eq!(task_status(&app, "m1"), TaskStatus::Failed);
+    let error = app.current_plan.as_ref().unwrap().get_task("m1").unwrap().error.clone();
+    assert!(error.unwrap().contains("Failed to create PR"));
+}
+
+#[test]
+fn test_merge_task_checks_out_its_target() {
+    let repo = merge_repo(false);
+    git(repo.path(), &["branch", "release"]);
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.worktree_manager = Some(WorktreeManager::new(repo.path()).unwrap());
+    let mut plan = Plan::new();
+    plan.add_task(Task::merge_branch("m1", "feat", Some("release".to_string())));
+    app.current_plan = Some(plan);
+
+    app.process_plan();
+    assert_eq!(task_status(&app, "m1"), TaskStatus::Completed);
+    assert!(has_notification(&app, NotifyLevel::Success, "Merged: feat → release"));
+    let merger = MergeManager::new(repo.path());
+    assert_eq!(merger.current_branch().unwrap(), "release");
+    assert_eq!(merger.commits_ahead("feat", "release").unwrap(), 0);
+    // main, which was checked out before, is untouched
+    assert_eq!(merger.commits_ahead("feat", "main").unwrap(), 1);
+}
+
+// ==================== Merge result diff tests ====================
+
+#[test]
+fn test_toggle_review_merge_result() {
+    let repo = merge_repo(true);
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.start_review_for_branch("feat", &repo.path().to_path_buf(), Some("main"));
+    app.review_state.as_mut().unwrap().worktree_path = repo.path().to_path_buf();
+
+    app.toggle_review_merge_result();
+    let state = app.review_state.as_ref().unwrap();
+    assert_eq!(state.diff_kind, ReviewDiff::MergeResult);
+    assert!(state.diff_view.content().contains("+<<<<<<< main"));
+
+    // Comments only attach to the branch's changes
+    app.open_review_comment_prompt();
+    assert!(has_notification(&app, NotifyLevel::Warning, "press v"));
+
+    app.toggle_review_merge_result();
+    let state = app.review_state.as_ref().unwrap();
+    assert_eq!(state.diff_kind, ReviewDiff::Branch);
+    assert!(!state.diff_view.content().contains("<<<<<<<"));
+    assert!(state.diff_view.content().contains("+feat"));
+}
+
+// ==================== Foreground command tests ====================
+
+#[cfg(unix)]
+#[test]
+fn test_foreground_command_failure_is_reported() {
+    let (mut app, _dir) = test_app(detached_spawner());
+    let command = vec!["w3m".to_string(), "https://example.com".to_string()];
+
+    let ok = std::process::Command::new("true").status();
+    app.foreground_command_finished(&command, ok);
+    assert!(app.notifications.is_empty());
+
+    let failed = std::process::Command::new("false").status();
+    app.foreground_command_finished(&command, failed);
+    assert!(has_notification(&app, NotifyLevel::Error, "'w3m' exited with"));
+
+    let missing = std::process::Command::new("no-such-browser-cctakt").status();
+    app.foreground_command_finished(&command, missing);
+    assert!(has_notification(&app, NotifyLevel::Error, "'w3m' not found"));
+}
+
+// ==================== Worktree directory tests ====================
+
+#[test]
+fn test_worktree_outside_repo_warned_once() {
+    let repo = merge_repo(false);
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.worktree_manager = Some(WorktreeManager::new(repo.path()).unwrap());
+
+    app.warn_if_worktree_outside_repo(&repo.path().join(".worktrees/feat"));
+    assert!(app.notifications.is_empty());
+
+    let outside = TempDir::new().unwrap();
+    app.warn_if_worktree_outside_repo(outside.path());
+    app.warn_if_worktree_outside_repo(outside.path());
+    let warnings = app.notifications.iter().filter(|n| n.level == NotifyLevel::Warning).count();
+    assert_eq!(warnings, 1);
+    assert!(has_notification(&app, NotifyLevel::Warning, "outside the repository"));
+}
+
+#[test]
+fn test_worktree_dir_outside_repo_loads_and_warns_once() {
+    let parent = TempDir::new().unwrap();
+    let repo = parent.path().join("repo");
+    std::fs::create_dir(&repo).unwrap();
+    git(&repo, &["init", "-b", "main"]);
+    git(&repo, &["-c", "user.email=t@t", "-c", "user.name=T", "commit", "--allow-empty", "-m", "init"]);
+    std::fs::write(repo.join(".cctakt.toml"), "worktree_dir = \"../wt\"\n").unwrap();
+
+    let config = Config::load_layered(&repo.join(".cctakt.toml"), None).unwrap();
+    config.validate().unwrap();
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.config = config;
+    app.worktree_manager = Some(WorktreeManager::new(&repo).unwrap());
+
+    app.add_agent_from_issue(checklist_issue("Just prose")).unwrap();
+    app.add_agent_from_issue(checklist_issue("Just prose")).unwrap();
+
+    assert_eq!(app.agent_manager.len(), 2);
+    assert!(parent.path().join("wt").is_dir());
+    let warnings = app.notifications.iter().filter(|n| n.level == NotifyLevel::Warning).count();
+    assert_eq!(warnings, 1);
+    assert!(has_notification(&app, NotifyLevel::Warning, "outside the repository"));
+}
+
+// ==================== Review key tests ====================
+
+/// Key codes named in a help table entry such as "j / k / ↓ / ↑"
+fn help_keys(keys: &str) -> Vec<crossterm::event::KeyCode> {
+    use crossterm::event::KeyCode;
+    keys.split(" / ")
+        .map(|key| match key {
+            "↓" => KeyCode::Down,
+            "↑" => KeyCode::Up,
+            "←" => KeyCode::Left,
+            "→" => KeyCode::Right,
+            "PgUp" => KeyCode::PageUp,
+            "PgDn" => KeyCode::PageDown,
+            "Home" => KeyCode::Home,
+            "End" => KeyCode::End,
+            "Tab" => KeyCode::Tab,
+            "Enter" => KeyCode::Enter,
+            "Esc" => KeyCode::Esc,
+            _ => {
+                let mut chars = key.chars();
+                let c = chars.next().unwrap();
+                assert!(chars.next().is_none(), "unknown key name in review help: {key}");
+                KeyCode::Char(c)
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_review_help_keys_are_handled() {
+    for mode in [InputMode::Navigation, InputMode::Input] {
+        for (keys, action) in crate::tui::keymap::REVIEW_HELP {
+            for code in help_keys(keys) {
+                let (mut app, _dir) = review_app(COMMENT_DIFF);
+                app.input_mode = mode;
+                assert!(
+                    crate::tui::handle_review_input(&mut app, code),
+                    "{code:?} ({action}) is not handled in {mode:?} mode"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_review_page_keys_scroll_the_diff() {
+    use crossterm::event::KeyCode;
+    let diff = (0..100).map(|i| format!("+line {i}")).collect::<Vec<_>>().join("\n");
+    let (mut app, _dir) = review_app(&diff);
+    app.review_state.as_mut().unwrap().focus = ReviewFocus::Diff;
+    let position = |app: &App| app.review_state.as_ref().unwrap().diff_view.scroll_position();
+
+    crate::tui::handle_review_input(&mut app, KeyCode::Char('G'));
+    let bottom = position(&app);
+    assert!(bottom > 0);
+    crate::tui::handle_review_input(&mut app, KeyCode::Char('u'));
+    assert!(position(&app) < bottom);
+    crate::tui::handle_review_input(&mut app, KeyCode::Char('g'));
+    assert_eq!(position(&app), 0);
+    crate::tui::handle_review_input(&mut app, KeyCode::Char('d'));
+    assert!(position(&app) > 0);
+}
+
+// ==================== Quit confirmation tests ====================
+
+#[test]
+fn test_quit_confirm_accepts_configured_quit_key() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    let (mut app, _dir) = test_app(detached_spawner());
+    app.config.keybindings.quit = "ctrl+x".to_string();
+    app.keymap = Keymap::from_config(&app.config.keybindings);
+
+    app.quit_prompt = Some("Quit?".to_string());
+    crate::tui::handle_quit_confirm(&mut app, KeyModifiers::CONTROL, KeyCode::Char('q'));
+    assert!(!app.should_quit);
+    crate::tui::handle_quit_confirm(&mut app, KeyModifiers::CONTROL, KeyCode::Char('x'));
+    assert!(app.should_quit);
+    assert!(app.quit_prompt.is_none());
+}
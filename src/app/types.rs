@@ -1,7 +1,13 @@
 //! Application types and state structures
 
-use cctakt::DiffView;
-use std::path::PathBuf;
+use cctakt::plan::CommandOutcome;
+use cctakt::messages::{self, Locale, Msg};
+use cctakt::{fill_placeholders, BuildCommands};
+use cctakt::{DiffView, InputDialog, LineLocation};
+use crate::git_utils::CommitDetail;
+use ratatui::layout::Rect;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
 
 /// Application mode
 #[derive(Debug, Clone, PartialEq)]
@@ -14,6 +20,12 @@ pub enum AppMode {
     ReviewMerge,
     /// Theme picker mode
     ThemePicker,
+    /// Debug log viewer overlay
+    LogViewer,
+    /// Key binding help overlay
+    Help,
+    /// Plan task list overlay
+    PlanView,
 }
 
 /// Focused pane in split view
@@ -46,12 +58,24 @@ pub enum ReviewFocus {
     Diff,
 }
 
+/// Which diff review mode shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReviewDiff {
+    /// What the branch changes since it forked from the base
+    #[default]
+    Branch,
+    /// What merging the branch would change on the base as it is now
+    MergeResult,
+}
+
 /// Review state for a completed agent
 pub struct ReviewState {
-    /// Agent index being reviewed
-    pub agent_index: usize,
+    /// Agent whose branch is being reviewed (None when reviewing a plain branch)
+    pub agent_id: Option<u64>,
     /// Branch name
     pub branch: String,
+    /// Branch the review compares against
+    pub base: String,
     /// Working directory (worktree path)
     pub worktree_path: PathBuf,
     /// Diff view
@@ -62,22 +86,104 @@ pub struct ReviewState {
     pub files_changed: usize,
     pub insertions: usize,
     pub deletions: usize,
+    /// Commits the branch has on top of the base / the base has that the branch lacks
+    pub ahead_by: usize,
+    pub behind_by: usize,
     /// Potential conflicts
     pub conflicts: Vec<String>,
+    /// Uncommitted changes in the worktree (`git status --porcelain` lines)
+    pub uncommitted: Vec<String>,
+    /// Commits whose subject breaks `commit_subject_pattern` (`<short hash> <subject>`)
+    pub nonconforming_commits: Vec<String>,
     /// Current focus in review split pane
     pub focus: ReviewFocus,
     /// Scroll position for summary/commit log pane
     pub summary_scroll: u16,
+    /// Full commit details overlay, if open
+    pub commits_view: Option<CommitsView>,
+    /// Base branch prompt, if open
+    pub base_dialog: Option<InputDialog>,
+    /// Diff search query being typed, if the `/` prompt is open
+    pub search_input: Option<String>,
+    /// Review feedback prompt for rejecting the branch, if open
+    pub reject_dialog: Option<InputDialog>,
+    /// Comments attached to diff lines, in the order they were added
+    pub comments: Vec<ReviewComment>,
+    /// Comment prompt and the line it annotates, if open
+    pub comment_dialog: Option<(LineLocation, InputDialog)>,
+    /// Pull request awaiting a review verdict key, if the post prompt is open
+    pub post_review_pr: Option<u64>,
+    /// Open the pull request as a draft (`merge_mode = "pull_request"`)
+    pub draft_pr: bool,
+    /// Whether the diff shows the branch's changes or the net merge result
+    pub diff_kind: ReviewDiff,
+}
+
+/// A review comment on one diff line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReviewComment {
+    pub location: LineLocation,
+    pub text: String,
+}
+
+/// Commit details overlay in review mode
+pub struct CommitsView {
+    /// Commits on the reviewed branch, newest first
+    pub commits: Vec<CommitDetail>,
+    /// Scroll position
+    pub scroll: u16,
+}
+
+/// Screen areas from the last draw, used to map mouse clicks to UI elements
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScreenRegions {
+    /// Header line with agent tabs
+    pub header: Rect,
+    /// Left pane (orchestrator), if shown
+    pub left_pane: Option<Rect>,
+    /// Right pane (worker or review), if shown
+    pub right_pane: Option<Rect>,
+}
+
+/// A RunCommand task executing on a background thread
+pub struct RunningCommand {
+    /// Plan task ID
+    pub task_id: String,
+    /// Command being run
+    pub command: String,
+    /// Receives the outcome when the command exits
+    pub receiver: Receiver<anyhow::Result<CommandOutcome>>,
+}
+
+/// A plan worker whose spawn failed transiently, started again on a later tick
+pub struct PendingSpawn {
+    /// Plan task ID
+    pub task_id: String,
+    /// Agent name
+    pub name: String,
+    pub working_dir: PathBuf,
+    pub worktree_path: Option<PathBuf>,
+    /// Full prompt for the worker
+    pub prompt: String,
+    pub branch: String,
+    /// Retries made so far
+    pub attempt: u32,
+    /// Not started again before this
+    pub retry_at: std::time::Instant,
 }
 
 /// Merge task for the queue
 pub struct MergeTask {
     /// Branch name to merge
     pub branch: String,
+    /// Branch to merge into (the review base)
+    pub target: String,
     /// Worktree path (for cleanup after merge)
     pub worktree_path: PathBuf,
     /// Task ID (for plan update)
     pub task_id: Option<String>,
+    /// The target's HEAD when the MergeWorker was spawned
+    pub target_head_before: Option<String>,
 }
 
 /// Merge queue for sequential merge processing
@@ -86,8 +192,8 @@ pub struct MergeQueue {
     pub queue: std::collections::VecDeque<MergeTask>,
     /// Currently processing task
     pub current: Option<MergeTask>,
-    /// MergeWorker agent index (None if not spawned)
-    pub worker_agent_index: Option<usize>,
+    /// MergeWorker agent id (None if not spawned)
+    pub worker_agent_id: Option<u64>,
 }
 
 impl MergeQueue {
@@ -95,7 +201,7 @@ impl MergeQueue {
         Self {
             queue: std::collections::VecDeque::new(),
             current: None,
-            worker_agent_index: None,
+            worker_agent_id: None,
         }
     }
 
@@ -129,6 +235,56 @@ impl Default for MergeQueue {
     }
 }
 
+/// The most urgent thing needing attention (see `App::attention_target`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttentionTarget {
+    /// Index of a failed plan task
+    FailedTask(usize),
+    /// Index of an agent that ended with an error
+    FailedAgent(usize),
+    /// Index of a finished worker whose branch conflicts with the base
+    ConflictingBranch(usize),
+}
+
+/// Finished workers reviewed one after another ("review all pending")
+///
+/// Only the review decisions are queued; approved branches go through the
+/// `MergeQueue` as usual.
+#[derive(Debug, Default)]
+pub struct ReviewQueue {
+    /// Agent ids still to review, in order
+    pub pending: std::collections::VecDeque<u64>,
+    /// Number of agents the queue started with (0 when inactive)
+    pub total: usize,
+    /// Reviews approved so far
+    pub approved: usize,
+}
+
+impl ReviewQueue {
+    pub fn new(agent_ids: Vec<u64>) -> Self {
+        Self {
+            total: agent_ids.len(),
+            pending: agent_ids.into(),
+            approved: 0,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.total > 0
+    }
+
+    /// Take the next agent to review
+    pub fn next(&mut self) -> Option<u64> {
+        self.pending.pop_front()
+    }
+
+    /// Position of the review in progress and the total, as in "reviewing 2/4"
+    pub fn progress(&self) -> Option<(usize, usize)> {
+        self.is_active()
+            .then(|| (self.total - self.pending.len(), self.total))
+    }
+}
+
 /// Notification message
 pub struct Notification {
     pub message: String,
@@ -136,10 +292,366 @@ pub struct Notification {
     pub created_at: std::time::Instant,
 }
 
+/// Describe work that would be abandoned by quitting, or None if there is none
+///
+/// e.g. "2 workers running, 1 merge queued — quit anyway? (y/n)"
+pub fn quit_warning(workers: usize, merges: usize, pending_tasks: usize) -> Option<String> {
+    let plural = |n: usize, word: &str| {
+        if n == 1 {
+            format!("{n} {word}")
+        } else {
+            format!("{n} {word}s")
+        }
+    };
+
+    let mut parts = Vec::new();
+    if workers > 0 {
+        parts.push(format!("{} running", plural(workers, "worker")));
+    }
+    if merges > 0 {
+        parts.push(format!("{} queued", plural(merges, "merge")));
+    }
+    if pending_tasks > 0 {
+        parts.push(format!("{} pending", plural(pending_tasks, "plan task")));
+    }
+
+    if parts.is_empty() {
+        None
+    } else {
+        Some(format!("{} — quit anyway? (y/n)", parts.join(", ")))
+    }
+}
+
+/// BuildWorker instructions for the given commands
+///
+/// Renders `custom` (see `WorkerPrompt::Build`) if given, otherwise the
+/// built-in prompt. Returns None if there's nothing to run and no custom prompt.
+pub fn build_task_description(
+    commands: &BuildCommands,
+    branch: &str,
+    locale: Locale,
+    custom: Option<&str>,
+) -> Option<String> {
+    let mut steps = Vec::new();
+    if let Some(ref build) = commands.build {
+        steps.push(messages::format(locale, Msg::BuildStepRun, &[("command", build)]));
+        steps.push(messages::text(locale, Msg::BuildStepFix).to_string());
+    }
+    if let Some(ref test) = commands.test {
+        steps.push(messages::format(locale, Msg::TestStepRun, &[("command", test)]));
+        steps.push(messages::text(locale, Msg::TestStepFix).to_string());
+    }
+    let steps = steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| format!("{}. {step}", i + 1))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    if let Some(template) = custom {
+        return Some(fill_placeholders(
+            template,
+            &[
+                ("branch", branch),
+                ("build_command", commands.build.as_deref().unwrap_or("")),
+                ("test_command", commands.test.as_deref().unwrap_or("")),
+                ("steps", &steps),
+            ],
+        ));
+    }
+    if commands.is_empty() {
+        return None;
+    }
+    Some(messages::format(locale, Msg::BuildWorkerTask, &[("steps", &steps)]))
+}
+
+/// Command line that opens a worktree in an editor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditorCommand {
+    pub words: Vec<String>,
+    /// Taken from `$VISUAL` / `$EDITOR`, which usually name a terminal
+    /// editor, so it runs in the foreground with the TUI suspended
+    pub terminal: bool,
+}
+
+/// Editor command line: the configured editor, else `$VISUAL`, else `$EDITOR`
+///
+/// The value is split on whitespace so options like `code -n` work.
+pub fn editor_command(
+    configured: Option<&str>,
+    visual: Option<&str>,
+    editor: Option<&str>,
+) -> Option<EditorCommand> {
+    [(configured, false), (visual, true), (editor, true)]
+        .into_iter()
+        .filter_map(|(value, terminal)| Some((value?, terminal)))
+        .map(|(value, terminal)| EditorCommand {
+            words: value.split_whitespace().map(str::to_string).collect(),
+            terminal,
+        })
+        .find(|command| !command.words.is_empty())
+}
+
+/// Why a worker's worktree can't be reviewed, if it can't
+///
+/// `expected` is the branch the worker was given, `current` the branch its
+/// worktree has checked out (None when detached) and `ahead` the number of
+/// commits `expected` has on top of `base`, if known.
+pub fn worktree_branch_problem(
+    expected: &str,
+    current: Option<&str>,
+    base: &str,
+    ahead: Option<usize>,
+) -> Option<String> {
+    let retry = format!("then review it with :attach {expected}");
+    match current {
+        None => Some(format!(
+            "The worktree is in detached HEAD state instead of on {expected}; \
+             check out {expected} (keeping the worker's commits), {retry}"
+        )),
+        Some(current) if current == base && expected != base => Some(format!(
+            "The worker committed on {base} instead of {expected}; \
+             move the commits to {expected} before merging, {retry}"
+        )),
+        Some(current) if current != expected => Some(format!(
+            "The worktree is on {current} instead of {expected}; switch back to {expected}, {retry}"
+        )),
+        Some(_) if ahead == Some(0) => {
+            Some(format!("{expected} has no commits ahead of {base}; nothing to review"))
+        }
+        Some(_) => None,
+    }
+}
+
+/// Path for display, with the home directory shortened to `~`
+pub fn display_path(path: &Path, home: Option<&Path>) -> String {
+    match home.and_then(|home| path.strip_prefix(home).ok()) {
+        Some(rest) if rest.as_os_str().is_empty() => "~".to_string(),
+        Some(rest) => format!("~/{}", rest.display()),
+        None => path.display().to_string(),
+    }
+}
+
+/// Pull request title and body for a reviewed branch
+///
+/// A single commit supplies both; otherwise the branch name is the title and
+/// the body lists the commit subjects, oldest first (`commits` is newest first).
+pub fn pull_request_text(branch: &str, commits: &[CommitDetail]) -> (String, Option<String>) {
+    match commits {
+        [commit] => {
+            let body = Some(commit.body.trim().to_string()).filter(|body| !body.is_empty());
+            (commit.subject.clone(), body)
+        }
+        [] => (branch.to_string(), None),
+        _ => {
+            let list = commits
+                .iter()
+                .rev()
+                .map(|commit| format!("- {}", commit.subject))
+                .collect::<Vec<_>>()
+                .join("\n");
+            (branch.to_string(), Some(list))
+        }
+    }
+}
+
+/// Feedback text for a rejected review: the general feedback followed by
+/// the line comments
+pub fn review_feedback(locale: Locale, feedback: &str, comments: &[ReviewComment]) -> String {
+    if comments.is_empty() {
+        return feedback.to_string();
+    }
+    let list = comments
+        .iter()
+        .map(|comment| format!("- {}: {}", comment.location, comment.text))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let heading = messages::text(locale, Msg::ReviewLineComments);
+    if feedback.is_empty() {
+        format!("{heading}\n{list}")
+    } else {
+        format!("{feedback}\n\n{heading}\n{list}")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // ==================== review_feedback tests ====================
+
+    fn comment(path: &str, line: usize, text: &str) -> ReviewComment {
+        ReviewComment {
+            location: LineLocation {
+                path: path.to_string(),
+                line,
+                side: cctakt::DiffSide::New,
+            },
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_review_feedback_lists_comments() {
+        let comments = [comment("src/a.rs", 12, "unwrap can panic"), comment("b.rs", 3, "typo")];
+        assert_eq!(
+            review_feedback(Locale::En, "Needs tests", &comments),
+            "Needs tests\n\nComments on specific lines:\n- src/a.rs:12: unwrap can panic\n- b.rs:3: typo"
+        );
+        assert_eq!(
+            review_feedback(Locale::En, "", &comments[1..]),
+            "Comments on specific lines:\n- b.rs:3: typo"
+        );
+        assert_eq!(review_feedback(Locale::En, "Needs tests", &[]), "Needs tests");
+    }
+
+    // ==================== pull_request_text tests ====================
+
+    fn commit(subject: &str, body: &str) -> CommitDetail {
+        CommitDetail {
+            subject: subject.to_string(),
+            body: body.to_string(),
+            ..CommitDetail::default()
+        }
+    }
+
+    #[test]
+    fn test_pull_request_text_single_commit() {
+        let (title, body) = pull_request_text("feat/x", &[commit("Add x", "Details\n")]);
+        assert_eq!(title, "Add x");
+        assert_eq!(body.as_deref(), Some("Details"));
+
+        let (_, body) = pull_request_text("feat/x", &[commit("Add x", "")]);
+        assert_eq!(body, None);
+    }
+
+    #[test]
+    fn test_pull_request_text_lists_commits_oldest_first() {
+        let commits = [commit("Second", ""), commit("First", "")];
+        let (title, body) = pull_request_text("feat/x", &commits);
+        assert_eq!(title, "feat/x");
+        assert_eq!(body.as_deref(), Some("- First\n- Second"));
+        assert_eq!(pull_request_text("feat/x", &[]), ("feat/x".to_string(), None));
+    }
+
+    // ==================== worktree_branch_problem tests ====================
+
+    #[test]
+    fn test_worktree_branch_problem() {
+        assert_eq!(worktree_branch_problem("feat/x", Some("feat/x"), "main", Some(2)), None);
+        // Unknown commit count doesn't block
+        assert_eq!(worktree_branch_problem("feat/x", Some("feat/x"), "main", None), None);
+
+        let detached = worktree_branch_problem("feat/x", None, "main", Some(2)).unwrap();
+        assert!(detached.starts_with("The worktree is in detached HEAD state"), "{detached}");
+        assert!(detached.ends_with(":attach feat/x"), "{detached}");
+
+        let on_base = worktree_branch_problem("feat/x", Some("main"), "main", Some(0)).unwrap();
+        assert!(on_base.starts_with("The worker committed on main instead of feat/x"), "{on_base}");
+
+        let other = worktree_branch_problem("feat/x", Some("feat/y"), "main", Some(1)).unwrap();
+        assert!(other.starts_with("The worktree is on feat/y instead of feat/x"), "{other}");
+
+        assert_eq!(
+            worktree_branch_problem("feat/x", Some("feat/x"), "main", Some(0)).as_deref(),
+            Some("feat/x has no commits ahead of main; nothing to review")
+        );
+    }
+
+    // ==================== editor_command tests ====================
+
+    #[test]
+    fn test_editor_command_precedence() {
+        assert_eq!(
+            editor_command(Some("code -n"), Some("gvim"), Some("vi")),
+            Some(EditorCommand { words: vec!["code".to_string(), "-n".to_string()], terminal: false })
+        );
+        assert_eq!(
+            editor_command(None, Some("gvim"), Some("vi")),
+            Some(EditorCommand { words: vec!["gvim".to_string()], terminal: true })
+        );
+        assert_eq!(
+            editor_command(Some("  "), None, Some("vi")),
+            Some(EditorCommand { words: vec!["vi".to_string()], terminal: true })
+        );
+        assert_eq!(editor_command(None, None, None), None);
+    }
+
+    // ==================== display_path tests ====================
+
+    #[test]
+    fn test_display_path_shortens_home() {
+        let home = Path::new("/home/me");
+        assert_eq!(
+            display_path(Path::new("/home/me/src/app/.worktrees/x"), Some(home)),
+            "~/src/app/.worktrees/x"
+        );
+        assert_eq!(display_path(Path::new("/home/me"), Some(home)), "~");
+        assert_eq!(display_path(Path::new("/home/meg/x"), Some(home)), "/home/meg/x");
+        assert_eq!(display_path(Path::new("/srv/x"), None), "/srv/x");
+    }
+
+    // ==================== quit_warning tests ====================
+
+    #[test]
+    fn test_quit_warning_nothing_in_flight() {
+        assert_eq!(quit_warning(0, 0, 0), None);
+    }
+
+    #[test]
+    fn test_quit_warning_message() {
+        assert_eq!(
+            quit_warning(2, 1, 0).unwrap(),
+            "2 workers running, 1 merge queued — quit anyway? (y/n)"
+        );
+        assert_eq!(
+            quit_warning(0, 0, 3).unwrap(),
+            "3 plan tasks pending — quit anyway? (y/n)"
+        );
+    }
+
+    // ==================== build_task_description tests ====================
+
+    #[test]
+    fn test_build_task_description_uses_commands() {
+        let commands = BuildCommands {
+            build: Some("npm run build".to_string()),
+            test: Some("npm test".to_string()),
+        };
+        let description = build_task_description(&commands, "feat", Locale::En, None).unwrap();
+        assert!(description.contains("1. Run npm run build"));
+        assert!(description.contains("3. Run npm test"));
+        assert!(!description.contains("cargo"));
+
+        let description = build_task_description(&commands, "feat", Locale::Ja, None).unwrap();
+        assert!(description.contains("1. npm run build を実行"));
+    }
+
+    #[test]
+    fn test_build_task_description_empty() {
+        let commands = BuildCommands {
+            build: None,
+            test: None,
+        };
+        assert!(build_task_description(&commands, "feat", Locale::En, None).is_none());
+    }
+
+    #[test]
+    fn test_build_task_description_custom_template() {
+        let commands = BuildCommands {
+            build: Some("make".to_string()),
+            test: None,
+        };
+        let description = build_task_description(
+            &commands,
+            "feat/x",
+            Locale::En,
+            Some("Build {{branch}} with {{build_command}}, run clippy, keep {{other}}"),
+        )
+        .unwrap();
+        assert_eq!(description, "Build feat/x with make, run clippy, keep {{other}}");
+    }
+
     #[test]
     fn test_app_mode_equality() {
         assert_eq!(AppMode::Normal, AppMode::Normal);
@@ -164,20 +676,34 @@ mod tests {
     #[test]
     fn test_review_state_creation() {
         let state = ReviewState {
-            agent_index: 0,
+            agent_id: Some(0),
             branch: "feature/test".to_string(),
+            base: "main".to_string(),
             worktree_path: PathBuf::from("/tmp/worktree"),
             diff_view: DiffView::new("+ added line\n- removed line".to_string()),
             commit_log: "abc1234 Initial commit".to_string(),
             files_changed: 5,
             insertions: 100,
             deletions: 20,
+            ahead_by: 0,
+            behind_by: 0,
             conflicts: vec!["src/main.rs".to_string()],
+            uncommitted: vec![],
+            nonconforming_commits: vec![],
             focus: ReviewFocus::default(),
             summary_scroll: 0,
+            commits_view: None,
+            base_dialog: None,
+            search_input: None,
+            reject_dialog: None,
+            comments: Vec::new(),
+            comment_dialog: None,
+            post_review_pr: None,
+            draft_pr: false,
+            diff_kind: ReviewDiff::Branch,
         };
 
-        assert_eq!(state.agent_index, 0);
+        assert_eq!(state.agent_id, Some(0));
         assert_eq!(state.branch, "feature/test");
         assert_eq!(state.files_changed, 5);
         assert_eq!(state.insertions, 100);
@@ -189,17 +715,31 @@ mod tests {
     #[test]
     fn test_review_state_empty_conflicts() {
         let state = ReviewState {
-            agent_index: 0,
+            agent_id: Some(0),
             branch: "test".to_string(),
+            base: "main".to_string(),
             worktree_path: PathBuf::from("/tmp"),
             diff_view: DiffView::new(String::new()),
             commit_log: String::new(),
             files_changed: 0,
             insertions: 0,
             deletions: 0,
+            ahead_by: 0,
+            behind_by: 0,
             conflicts: vec![],
+            uncommitted: vec![],
+            nonconforming_commits: vec![],
             focus: ReviewFocus::default(),
             summary_scroll: 0,
+            commits_view: None,
+            base_dialog: None,
+            search_input: None,
+            reject_dialog: None,
+            comments: Vec::new(),
+            comment_dialog: None,
+            post_review_pr: None,
+            draft_pr: false,
+            diff_kind: ReviewDiff::Branch,
         };
 
         assert!(state.conflicts.is_empty());
@@ -209,21 +749,35 @@ mod tests {
     #[test]
     fn test_review_state_multiple_conflicts() {
         let state = ReviewState {
-            agent_index: 1,
+            agent_id: Some(1),
             branch: "feature".to_string(),
+            base: "main".to_string(),
             worktree_path: PathBuf::from("/worktree"),
             diff_view: DiffView::new("diff".to_string()),
             commit_log: "log".to_string(),
             files_changed: 10,
             insertions: 500,
             deletions: 100,
+            ahead_by: 0,
+            behind_by: 0,
             conflicts: vec![
                 "file1.rs".to_string(),
                 "file2.rs".to_string(),
                 "file3.rs".to_string(),
             ],
+            uncommitted: vec![],
+            nonconforming_commits: vec![],
             focus: ReviewFocus::Diff,
             summary_scroll: 0,
+            commits_view: None,
+            base_dialog: None,
+            search_input: None,
+            reject_dialog: None,
+            comments: Vec::new(),
+            comment_dialog: None,
+            post_review_pr: None,
+            draft_pr: false,
+            diff_kind: ReviewDiff::Branch,
         };
 
         assert_eq!(state.conflicts.len(), 3);
@@ -259,4 +813,18 @@ mod tests {
             let _ = notification.message;
         }
     }
+
+    #[test]
+    fn test_review_queue_progress() {
+        assert!(!ReviewQueue::default().is_active());
+        assert_eq!(ReviewQueue::default().progress(), None);
+
+        let mut queue = ReviewQueue::new(vec![3, 5]);
+        assert_eq!(queue.next(), Some(3));
+        assert_eq!(queue.progress(), Some((1, 2)));
+        assert_eq!(queue.next(), Some(5));
+        assert_eq!(queue.progress(), Some((2, 2)));
+        assert_eq!(queue.next(), None);
+        assert!(queue.is_active());
+    }
 }
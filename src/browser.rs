@@ -0,0 +1,206 @@
+//! Opening URLs in the user's web browser
+//!
+//! Uses `$BROWSER` if set, then the platform opener (`open`, `start` or
+//! `xdg-open`). Without a graphical session `$BROWSER` is taken to be a
+//! terminal browser, which the TUI has to run in the foreground; with
+//! nothing to open, callers fall back to `copy_to_clipboard`.
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use std::io::Write;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+/// How long a GUI opener gets to fail before it counts as launched
+///
+/// Openers like `xdg-open` exit as soon as they've handed the URL over; a
+/// browser run directly keeps going and is reaped in the background.
+const LAUNCH_WAIT: Duration = Duration::from_millis(500);
+
+/// A command line that can open a URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrowserCommand {
+    pub words: Vec<String>,
+    /// Needs the terminal (a text browser without a graphical session)
+    pub terminal: bool,
+}
+
+/// Command lines to try, in order, to open a URL
+///
+/// `browser` is `$BROWSER`: a `:`-separated list of commands, each split on
+/// whitespace (so `firefox --new-tab` works). `os` is `std::env::consts::OS`
+/// and `has_display` tells whether a graphical session is available (only
+/// checked on Linux and the BSDs). Empty if no browser can be used.
+pub fn browser_commands(browser: Option<&str>, os: &str, has_display: bool) -> Vec<BrowserCommand> {
+    let graphical = has_display || matches!(os, "macos" | "windows");
+    let mut commands: Vec<BrowserCommand> = browser
+        .unwrap_or_default()
+        .split(':')
+        .map(|value| value.split_whitespace().map(str::to_string).collect::<Vec<_>>())
+        .filter(|words| !words.is_empty())
+        .map(|words| BrowserCommand { words, terminal: !graphical })
+        .collect();
+    let opener: &[&str] = match os {
+        "macos" => &["open"],
+        "windows" => &["cmd", "/C", "start", ""],
+        _ if has_display => &["xdg-open"],
+        _ => &[],
+    };
+    if !opener.is_empty() {
+        commands.push(BrowserCommand {
+            words: opener.iter().map(|word| word.to_string()).collect(),
+            terminal: false,
+        });
+    }
+    commands
+}
+
+/// Whether a graphical session is available (X11 or Wayland)
+fn has_display() -> bool {
+    ["DISPLAY", "WAYLAND_DISPLAY"]
+        .iter()
+        .any(|var| std::env::var_os(var).is_some_and(|value| !value.is_empty()))
+}
+
+/// What `open_url` did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpenUrl {
+    /// A GUI browser has the URL
+    Opened,
+    /// This terminal browser command line (URL included) has to run in the
+    /// foreground, with the TUI suspended
+    Terminal(Vec<String>),
+}
+
+/// Open a URL in the browser
+///
+/// Tries each of `browser_commands` until one launches, waiting up to
+/// `LAUNCH_WAIT` for each to fail. Fails with the last error if none works.
+pub fn open_url(url: &str) -> Result<OpenUrl> {
+    let browser = std::env::var("BROWSER").ok();
+    let commands = browser_commands(browser.as_deref(), std::env::consts::OS, has_display());
+    let mut last_error = None;
+    for command in commands {
+        if command.terminal {
+            let mut words = command.words;
+            words.push(url.to_string());
+            return Ok(OpenUrl::Terminal(words));
+        }
+        match launch(&command.words, url) {
+            Ok(()) => return Ok(OpenUrl::Opened),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No browser available")))
+}
+
+/// Start a GUI opener detached from the terminal and check it didn't fail
+fn launch(words: &[String], url: &str) -> Result<()> {
+    let child = Command::new(&words[0])
+        .args(&words[1..])
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run '{}'", words[0]))?;
+    match wait_briefly(child, LAUNCH_WAIT)? {
+        Some(status) if !status.success() => anyhow::bail!("'{}' exited with {status}", words[0]),
+        _ => Ok(()),
+    }
+}
+
+/// Exit status of `child` if it exits within `timeout`
+///
+/// A child still running afterwards is waited on by a background thread, so
+/// it never lingers as a zombie.
+fn wait_briefly(mut child: Child, timeout: Duration) -> Result<Option<ExitStatus>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if Instant::now() >= deadline {
+            std::thread::spawn(move || child.wait());
+            return Ok(None);
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
+/// OSC 52 escape sequence that asks the terminal to put `text` on the clipboard
+pub fn osc52_sequence(text: &str) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    format!("\x1b]52;c;{encoded}\x07")
+}
+
+/// Copy text to the clipboard through the terminal (OSC 52)
+///
+/// Works over SSH, but terminals that don't support OSC 52 silently ignore it.
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    stdout.write_all(osc52_sequence(text).as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(commands: &[BrowserCommand]) -> Vec<Vec<&str>> {
+        commands
+            .iter()
+            .map(|command| command.words.iter().map(String::as_str).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_browser_commands_prefer_browser_env() {
+        let commands = browser_commands(Some("firefox --new-tab:chromium"), "linux", true);
+        assert_eq!(words(&commands), [vec!["firefox", "--new-tab"], vec!["chromium"], vec!["xdg-open"]]);
+        assert!(commands.iter().all(|command| !command.terminal));
+        // A blank $BROWSER falls back to the platform opener
+        assert_eq!(words(&browser_commands(Some("  "), "macos", false)), [vec!["open"]]);
+    }
+
+    #[test]
+    fn test_browser_commands_per_platform() {
+        assert_eq!(words(&browser_commands(None, "macos", false)), [vec!["open"]]);
+        assert_eq!(words(&browser_commands(None, "windows", false)), [vec!["cmd", "/C", "start", ""]]);
+        assert_eq!(words(&browser_commands(None, "linux", true)), [vec!["xdg-open"]]);
+        assert_eq!(words(&browser_commands(None, "freebsd", true)), [vec!["xdg-open"]]);
+    }
+
+    #[test]
+    fn test_browser_commands_headless() {
+        assert!(browser_commands(None, "linux", false).is_empty());
+        // Without a display $BROWSER must be a text browser
+        let commands = browser_commands(Some("w3m"), "linux", false);
+        assert_eq!(commands, [BrowserCommand { words: vec!["w3m".to_string()], terminal: true }]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_launch_reports_failing_opener() {
+        let err = launch(&["false".to_string()], "https://example.com").unwrap_err();
+        assert!(err.to_string().contains("'false' exited with"), "{err}");
+        assert!(launch(&["true".to_string()], "https://example.com").is_ok());
+        assert!(launch(&["no-such-opener-cctakt".to_string()], "https://example.com").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_wait_briefly_leaves_long_runner_to_background() {
+        let child = Command::new("sleep").arg("5").spawn().unwrap();
+        assert!(wait_briefly(child, Duration::from_millis(50)).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(
+            osc52_sequence("https://github.com/o/r/issues/1"),
+            "\x1b]52;c;aHR0cHM6Ly9naXRodWIuY29tL28vci9pc3N1ZXMvMQ==\x07"
+        );
+    }
+}
@@ -9,6 +9,15 @@ use std::path::PathBuf;
 pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Commands>,
+
+    /// Quit the TUI without asking, even if workers or merges are in flight
+    #[arg(long)]
+    pub no_confirm: bool,
+
+    /// GitHub repository (owner/name) to use instead of config or git remote
+    /// (also settable via CCTAKT_REPO)
+    #[arg(long, global = true, value_name = "OWNER/NAME")]
+    pub repo: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -32,10 +41,123 @@ pub enum Commands {
     },
     /// Run workers from a plan file (CLI mode, no TUI)
     Run {
-        /// Path to plan.json file (default: .cctakt/plan.json)
+        /// Path to plan.json file, or `-` to read it from stdin and print the
+        /// updated plan to stdout (default: .cctakt/plan.json)
         #[arg(default_value = ".cctakt/plan.json")]
         plan: PathBuf,
+        /// Only print task results and errors
+        #[arg(short, long, conflicts_with = "verbose")]
+        quiet: bool,
+        /// Print the worker's full event stream
+        #[arg(short, long)]
+        verbose: bool,
+        /// Kill a worker still running after this many seconds and fail its
+        /// task (overrides `agent.task_timeout_secs`)
+        #[arg(long, value_name = "SECS")]
+        timeout: Option<u64>,
     },
     /// Run as MCP server (for orchestrator integration)
     Mcp,
+    /// Show or change configuration (prints the effective config if no action given)
+    Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
+    },
+    /// Print a conventional-commit message suggested from a GitHub issue
+    SuggestCommit {
+        /// Issue number
+        #[arg(long)]
+        issue: u64,
+    },
+    /// Open the TUI reviewing an existing branch (creating its worktree if needed)
+    Attach {
+        /// Branch to review
+        branch: String,
+    },
+    /// Manage git worktrees (list, add, remove, prune)
+    Worktree {
+        #[command(subcommand)]
+        action: WorktreeAction,
+    },
+    /// Generate plan files
+    Plan {
+        #[command(subcommand)]
+        action: PlanAction,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PlanAction {
+    /// Write a plan taking issues through worker, review, merge and cleanup
+    New {
+        /// Issue numbers (comma-separated), one worker each
+        #[arg(
+            long,
+            value_delimiter = ',',
+            required_unless_present = "from_checklist",
+            conflicts_with = "from_checklist"
+        )]
+        issues: Vec<u64>,
+        /// Issue whose unchecked checklist items become one worker each
+        #[arg(long, value_name = "ISSUE")]
+        from_checklist: Option<u64>,
+        /// Replace a plan that still has unfinished tasks
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Check a plan for duplicate ids, broken dependencies and bad branch names
+    Validate {
+        /// Path to the plan file
+        #[arg(default_value = ".cctakt/plan.json")]
+        plan: PathBuf,
+    },
+    /// List archived plans newest first, or show one plan's tasks and results
+    History {
+        /// Plan to show, as numbered in the list (1 = newest)
+        number: Option<usize>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Print the effective value of a key (e.g. keybindings.quit)
+    Get {
+        /// Dotted key path
+        key: String,
+    },
+    /// Set a key in the user config file (or the project file with --project)
+    Set {
+        /// Dotted key path
+        key: String,
+        /// New value (parsed as TOML, otherwise taken as a string)
+        value: String,
+        /// Write to the project config instead of the user config
+        #[arg(long)]
+        project: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum WorktreeAction {
+    /// List worktrees (branch, path, HEAD)
+    List {
+        /// Print as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Create a worktree on a new branch under worktree_dir
+    Add {
+        /// Branch name to create
+        branch: String,
+    },
+    /// Remove the worktree that has a branch checked out
+    Remove {
+        /// Branch checked out in the worktree
+        branch: String,
+        /// Remove even if the worktree has uncommitted changes
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Clean up records of worktrees whose directories are gone
+    Prune,
 }
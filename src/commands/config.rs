@@ -0,0 +1,72 @@
+//! Config command implementation
+
+use crate::cli::ConfigAction;
+use anyhow::{Context, Result};
+use cctakt::config::{project_config_path, user_config_path};
+use cctakt::Config;
+
+/// Show or change configuration
+pub fn run_config(action: Option<ConfigAction>) -> Result<()> {
+    match action {
+        None => print_effective(),
+        Some(ConfigAction::Get { key }) => get(&key),
+        Some(ConfigAction::Set {
+            key,
+            value,
+            project,
+        }) => set(&key, &value, project),
+    }
+}
+
+/// Print every effective setting with where it came from
+fn print_effective() -> Result<()> {
+    let user_path = user_config_path();
+    let entries = Config::effective_entries(&project_config_path(), user_path.as_deref())?;
+
+    let lines: Vec<(String, String)> = entries
+        .iter()
+        .map(|e| (format!("{} = {}", e.key, e.value), e.source.to_string()))
+        .collect();
+    let width = lines.iter().map(|(l, _)| l.len()).max().unwrap_or(0);
+
+    for (line, source) in lines {
+        println!("{line:<width$}  # {source}");
+    }
+    Ok(())
+}
+
+/// Print the effective value of one key
+fn get(key: &str) -> Result<()> {
+    let config = Config::load()?;
+    match config.get_value(key)? {
+        Some(toml::Value::String(s)) => println!("{s}"),
+        Some(value) => println!("{value}"),
+        None => anyhow::bail!("Unknown or unset key: {key}"),
+    }
+    Ok(())
+}
+
+/// Set a key in the user file (default) or the project file
+fn set(key: &str, value: &str, project: bool) -> Result<()> {
+    let project_path = project_config_path();
+    let user_path = user_config_path();
+
+    let target = if project {
+        project_path.clone()
+    } else {
+        if !Config::is_user_preference(key) {
+            anyhow::bail!(
+                "'{key}' is a project setting and can't be set per user. Use --project to write it to {}",
+                project_path.display()
+            );
+        }
+        user_path
+            .clone()
+            .context("Could not determine user config directory")?
+    };
+
+    Config::set_value_in(&target, &project_path, user_path.as_deref(), key, value)?;
+
+    println!("Set {key} in {}", target.display());
+    Ok(())
+}
@@ -80,7 +80,13 @@ pub fn run_init(force: bool) -> Result<()> {
 
     // 6. Update .gitignore
     let gitignore_path = PathBuf::from(".gitignore");
-    let gitignore_entries = [".cctakt/plan_*.json"];
+    let gitignore_entries = [
+        ".cctakt/plan_*.json",
+        ".cctakt/debug.log",
+        ".cctakt/events.jsonl",
+        ".cctakt/control.sock",
+        ".cctakt/update_check.json",
+    ];
 
     let existing_gitignore = fs::read_to_string(&gitignore_path).unwrap_or_default();
     let mut added_entries = Vec::new();
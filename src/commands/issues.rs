@@ -1,24 +1,15 @@
 //! Issues command implementation
 
-use crate::git_utils::detect_github_repo;
+use crate::git_utils::resolve_github_repo;
 use anyhow::Result;
 use cctakt::{Config, GitHubClient};
 
 /// List GitHub issues
-pub fn run_issues(labels: Option<String>, state: String) -> Result<()> {
+pub fn run_issues(repo: Option<String>, labels: Option<String>, state: String) -> Result<()> {
     let config = Config::load()?;
 
-    // Get repository from config or detect from git
-    let repo = config
-        .github
-        .repository
-        .clone()
-        .or_else(detect_github_repo)
-        .ok_or_else(|| {
-            anyhow::anyhow!(
-                "No repository configured. Set 'repository' in cctakt.toml or add a git remote."
-            )
-        })?;
+    // Get repository from --repo / CCTAKT_REPO, then config, then git remote
+    let repo = resolve_github_repo(repo, config.github.repository.as_deref())?;
 
     let client = GitHubClient::new(&repo)?;
 
@@ -1,15 +1,23 @@
 //! Command implementations
 
+pub mod config;
 pub mod init;
 pub mod issues;
 pub mod mcp;
+pub mod plan;
 pub mod run;
 pub mod status;
+pub mod suggest_commit;
 pub mod tui;
+pub mod worktree;
 
+pub use config::run_config;
 pub use init::run_init;
 pub use issues::run_issues;
 pub use mcp::run_mcp;
-pub use run::run_plan;
+pub use plan::run_plan_command;
+pub use run::{run_plan, Verbosity};
 pub use status::run_status;
+pub use suggest_commit::run_suggest_commit;
 pub use tui::run_tui;
+pub use worktree::run_worktree;
@@ -0,0 +1,318 @@
+//! Plan command implementation
+
+use crate::cli::PlanAction;
+use crate::git_utils::resolve_github_repo;
+use anyhow::{Context, Result};
+use cctakt::{
+    checklist_plan, issue_plan, ArchivedPlan, Config, GitHubClient, Plan, PlanManager, TaskAction,
+    TaskStatus,
+};
+use std::path::Path;
+
+/// Generate plan files
+pub fn run_plan_command(repo: Option<String>, action: PlanAction) -> Result<()> {
+    match action {
+        PlanAction::New {
+            issues,
+            from_checklist,
+            force,
+        } => new_plan(repo, &issues, from_checklist, force),
+        PlanAction::Validate { plan } => validate_plan_file(&plan),
+        PlanAction::History { number } => plan_history(number),
+    }
+}
+
+/// List archived plans, or print the tasks of the `number`th newest
+fn plan_history(number: Option<usize>) -> Result<()> {
+    let history = PlanManager::current_dir().history()?;
+    let Some(number) = number else {
+        if history.is_empty() {
+            println!("No archived plans in .cctakt");
+        }
+        for (index, archived) in history.iter().enumerate() {
+            println!("{}", history_line(index + 1, archived));
+        }
+        return Ok(());
+    };
+    let archived = number
+        .checked_sub(1)
+        .and_then(|index| history.get(index))
+        .with_context(|| format!("No archived plan #{number} ({} archived)", history.len()))?;
+    let plan = archived
+        .plan
+        .as_ref()
+        .map_err(|e| anyhow::anyhow!("{}: {e:#}", archived.path.display()))?;
+    print!("{}", plan_details(archived, plan));
+    Ok(())
+}
+
+/// Local time of a Unix timestamp, to the minute
+fn format_timestamp(secs: u64) -> String {
+    chrono::DateTime::from_timestamp(secs as i64, 0)
+        .map(|time| time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| secs.to_string())
+}
+
+/// Outcome mark and task counts, e.g. `✗ 2/4 completed, 1 failed, 1 skipped`
+fn plan_outcome(plan: &Plan) -> String {
+    let count = |status| plan.tasks.iter().filter(|task| task.status == status).count();
+    let (completed, failed, skipped) = (
+        count(TaskStatus::Completed),
+        count(TaskStatus::Failed),
+        count(TaskStatus::Skipped),
+    );
+    let mark = if failed > 0 {
+        "✗"
+    } else if plan.is_complete() {
+        "✓"
+    } else {
+        "…"
+    };
+    let mut outcome = format!("{mark} {completed}/{} completed", plan.tasks.len());
+    if failed > 0 {
+        outcome.push_str(&format!(", {failed} failed"));
+    }
+    if skipped > 0 {
+        outcome.push_str(&format!(", {skipped} skipped"));
+    }
+    outcome
+}
+
+/// One line of `cctakt plan history`
+fn history_line(number: usize, archived: &ArchivedPlan) -> String {
+    let time = format_timestamp(archived.archived_at);
+    match &archived.plan {
+        Ok(plan) => format!(
+            "{number:>3}  {time}  {:<28}  {}",
+            plan_outcome(plan),
+            plan.description.as_deref().unwrap_or("(no description)")
+        ),
+        Err(e) => format!("{number:>3}  {time}  unreadable: {e:#}"),
+    }
+}
+
+/// Tasks of an archived plan with their errors, commits and PRs
+fn plan_details(archived: &ArchivedPlan, plan: &Plan) -> String {
+    let mut out = format!("{}\n", archived.path.display());
+    if let Some(description) = &plan.description {
+        out.push_str(&format!("{description}\n"));
+    }
+    out.push_str(&format!(
+        "Archived {}: {}\n\n",
+        format_timestamp(archived.archived_at),
+        plan_outcome(plan)
+    ));
+    for task in &plan.tasks {
+        let icon = match task.status {
+            TaskStatus::Pending => "○",
+            TaskStatus::Running => "●",
+            TaskStatus::Completed => "✓",
+            TaskStatus::Failed => "✗",
+            TaskStatus::Skipped => "-",
+        };
+        out.push_str(&format!("{icon} {:<20} {}\n", task.id, task.action.summary()));
+        if let Some(error) = &task.error {
+            out.push_str(&format!("    error: {error}\n"));
+        }
+        if let Some(result) = &task.result {
+            for commit in &result.commits {
+                out.push_str(&format!("    {commit}\n"));
+            }
+            if let Some(url) = &result.pr_url {
+                out.push_str(&format!("    PR: {url}\n"));
+            }
+        }
+    }
+    out
+}
+
+/// Print every problem in the plan file, failing if any is an error
+fn validate_plan_file(path: &Path) -> Result<()> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read plan file: {}", path.display()))?;
+    let plan = cctakt::plan::parse_plan(&content)?;
+    match plan.validate() {
+        Ok(()) => {
+            println!("{}: OK ({} tasks)", path.display(), plan.tasks.len());
+            Ok(())
+        }
+        Err(problems) => {
+            for problem in &problems {
+                let level = if problem.is_warning() { "warning" } else { "error" };
+                println!("{level}: {problem}");
+            }
+            let errors = problems.iter().filter(|problem| !problem.is_warning()).count();
+            if errors > 0 {
+                anyhow::bail!("{}: {errors} error(s)", path.display());
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Fetch the issues, build the plan and write it to `.cctakt/plan.json`
+fn new_plan(repo: Option<String>, issues: &[u64], from_checklist: Option<u64>, force: bool) -> Result<()> {
+    let config = Config::load()?;
+    let mut manager = PlanManager::current_dir();
+    if !force
+        && let Some(existing) = manager.load()?
+        && !existing.is_complete()
+    {
+        anyhow::bail!(
+            "{} has unfinished tasks; use --force to replace it",
+            manager.plan_file().display()
+        );
+    }
+
+    let repo = resolve_github_repo(repo, config.github.repository.as_deref())?;
+    let client = GitHubClient::new(&repo)?;
+    let fetch = |number: u64| {
+        client
+            .get_issue(number)
+            .with_context(|| format!("Failed to fetch issue #{number} from {repo}"))
+    };
+
+    let plan = match from_checklist {
+        Some(number) => {
+            let issue = fetch(number)?;
+            let plan = checklist_plan(
+                &issue,
+                config.branch_prefix_for(&issue),
+                &config.branch_slug,
+                config.worktree_naming,
+            );
+            if plan.tasks.is_empty() {
+                anyhow::bail!("Issue #{number} has no unchecked checklist items");
+            }
+            plan
+        }
+        None => {
+            let issues = issues.iter().map(|&n| fetch(n)).collect::<Result<Vec<_>>>()?;
+            issue_plan(
+                &issues,
+                &config.branch_prefix,
+                &config.label_branch_prefixes,
+                &config.branch_slug,
+                config.worktree_naming,
+            )
+        }
+    };
+
+    check(&plan)?;
+    manager.save(&plan)?;
+
+    println!(
+        "Wrote {} with {} tasks:",
+        manager.plan_file().display(),
+        plan.tasks.len()
+    );
+    for task in &plan.tasks {
+        if let TaskAction::CreateWorker { branch, .. } = &task.action {
+            println!("  {:<20} {branch}", task.id);
+        }
+    }
+    println!("Edit it if needed, then start cctakt to run it.");
+    Ok(())
+}
+
+/// Fail with every error `Plan::validate` finds (warnings don't count)
+fn check(plan: &Plan) -> Result<()> {
+    let errors: Vec<String> = match plan.validate() {
+        Ok(()) => return Ok(()),
+        Err(problems) => problems
+            .iter()
+            .filter(|problem| !problem.is_warning())
+            .map(ToString::to_string)
+            .collect(),
+    };
+    match errors.len() {
+        0 => Ok(()),
+        1 => anyhow::bail!("Invalid plan: {}", errors[0]),
+        n => anyhow::bail!("Invalid plan ({n} errors):\n  {}", errors.join("\n  ")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cctakt::{Task, TaskResult};
+    use std::path::PathBuf;
+
+    fn archived(plan: Plan) -> ArchivedPlan {
+        ArchivedPlan {
+            path: PathBuf::from(".cctakt/plan_100.json"),
+            archived_at: 100,
+            plan: Ok(plan),
+        }
+    }
+
+    #[test]
+    fn test_plan_outcome() {
+        let mut plan = Plan::new();
+        plan.add_task(Task::notify("n1", "hi"));
+        plan.add_task(Task::notify("n2", "hi"));
+        assert_eq!(plan_outcome(&plan), "… 0/2 completed");
+
+        plan.update_status("n1", TaskStatus::Completed);
+        plan.update_status("n2", TaskStatus::Skipped);
+        assert_eq!(plan_outcome(&plan), "✓ 1/2 completed, 1 skipped");
+
+        plan.mark_failed("n2", "boom");
+        assert_eq!(plan_outcome(&plan), "✗ 1/2 completed, 1 failed");
+    }
+
+    #[test]
+    fn test_history_line() {
+        let line = history_line(1, &archived(Plan::with_description("Fix login")));
+        assert!(line.starts_with("  1  "), "{line}");
+        assert!(line.contains("✓ 0/0 completed"), "{line}");
+        assert!(line.ends_with("  Fix login"), "{line}");
+
+        let unreadable = ArchivedPlan {
+            plan: Err(anyhow::anyhow!("Failed to parse plan file")),
+            ..archived(Plan::new())
+        };
+        assert!(history_line(2, &unreadable).ends_with("unreadable: Failed to parse plan file"));
+    }
+
+    #[test]
+    fn test_plan_details_lists_results() {
+        let mut plan = Plan::with_description("Fix login");
+        plan.add_task(Task::create_worker("w1", "feat/a", "Do a"));
+        plan.add_task(Task::create_worker("w2", "feat/b", "Do b"));
+        plan.update_status("w1", TaskStatus::Completed);
+        plan.tasks[0].result = Some(TaskResult {
+            commits: vec!["abc1234 feat: a".to_string()],
+            pr_number: Some(7),
+            pr_url: Some("https://github.com/o/r/pull/7".to_string()),
+            output: None,
+        });
+        plan.mark_failed("w2", "max turns reached");
+
+        let details = plan_details(&archived(plan.clone()), &plan);
+        assert!(details.starts_with(".cctakt/plan_100.json\nFix login\nArchived "), "{details}");
+        assert!(details.contains("✓ w1                   worker feat/a\n    abc1234 feat: a\n    PR: https://github.com/o/r/pull/7\n"), "{details}");
+        assert!(details.contains("✗ w2                   worker feat/b\n    error: max turns reached\n"), "{details}");
+    }
+
+    #[test]
+    fn test_check() {
+        let mut plan = Plan::new();
+        plan.add_task(Task::create_worker("w1", "feat/a", "Do a"));
+        plan.add_task(Task::request_review("w1-review", "feat/a", Some("w1".to_string())));
+        assert!(check(&plan).is_ok());
+
+        // Reviewing a branch no worker creates is only a warning
+        plan.add_task(Task::request_review("old-review", "feat/old", None));
+        assert!(check(&plan).is_ok());
+
+        plan.add_task(Task::request_review("w2-review", "feat/b", Some("w2".to_string())));
+        let err = check(&plan).unwrap_err().to_string();
+        assert_eq!(err, "Invalid plan: task 'w2-review' waits for unknown task 'w2'");
+
+        plan.add_task(Task::create_worker("w1", "feat/c", "Do c"));
+        let err = check(&plan).unwrap_err().to_string();
+        assert!(err.starts_with("Invalid plan (2 errors):"), "{err}");
+        assert!(err.contains("duplicate task id 'w1'"), "{err}");
+    }
+}
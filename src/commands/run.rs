@@ -1,39 +1,279 @@
 //! Run command implementation (CLI mode)
 
-use crate::git_utils::get_worker_commits;
+use crate::agent::StderrTail;
+use crate::git_utils::{get_worker_commits, CommitKind, OnelineCommit};
 use anyhow::{Context, Result};
 use cctakt::{Config, Plan, TaskAction, TaskResult, TaskStatus, WorktreeManager};
+use std::ffi::OsString;
 use std::fs;
-use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
-use std::process::{Command, Stdio};
+use std::io::{self, BufRead, BufReader, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Plan path that means "read the plan from stdin"
+const STDIN_PATH: &str = "-";
+
+/// How often the worker's output wait wakes up to check the timeout and Ctrl+C
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How a worker process ended
+#[derive(Debug)]
+enum WorkerExit {
+    Finished(ExitStatus),
+    /// Killed after running past the timeout
+    TimedOut,
+    /// Killed because of Ctrl+C
+    Interrupted,
+}
+
+/// Feed the child's stdout to `on_line` until it exits, killing it once
+/// `deadline` passes or `interrupted` is set
+///
+/// Lines are read on a separate thread so a silent, hung worker can't keep
+/// the run from noticing either.
+fn wait_for_worker(
+    child: &mut Child,
+    deadline: Option<Instant>,
+    interrupted: &AtomicBool,
+    mut on_line: impl FnMut(&str) -> io::Result<()>,
+) -> Result<WorkerExit> {
+    let (sender, lines) = mpsc::channel();
+    if let Some(stdout) = child.stdout.take() {
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+    } else {
+        drop(sender);
+    }
+
+    let mut stdout_open = true;
+    loop {
+        let killed = if interrupted.load(Ordering::SeqCst) {
+            Some(WorkerExit::Interrupted)
+        } else if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            Some(WorkerExit::TimedOut)
+        } else {
+            None
+        };
+        if let Some(exit) = killed {
+            // It may have exited on its own in the meantime
+            let _ = child.kill();
+            child.wait()?;
+            return Ok(exit);
+        }
+
+        if stdout_open {
+            match lines.recv_timeout(POLL_INTERVAL) {
+                Ok(line) => on_line(&line)?,
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => stdout_open = false,
+            }
+        } else if let Some(status) = child.try_wait()? {
+            return Ok(WorkerExit::Finished(status));
+        } else {
+            // stdout is closed but the process hasn't exited yet
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+/// How much `cctakt run` prints
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verbosity {
+    /// Only task results and errors
+    Quiet,
+    /// Progress plus a preview of what each worker says
+    Normal,
+    /// Every event of the worker's stream, untruncated
+    Verbose,
+}
+
+impl Verbosity {
+    /// From the `--quiet` / `--verbose` flags (clap keeps them exclusive)
+    pub fn from_flags(quiet: bool, verbose: bool) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Self::Quiet,
+            (_, true) => Self::Verbose,
+            _ => Self::Normal,
+        }
+    }
+}
+
+/// Whether to color output: off when `NO_COLOR` is set (and non-empty) or
+/// the output is not a terminal
+fn use_color(no_color: Option<OsString>, is_terminal: bool) -> bool {
+    is_terminal && no_color.is_none_or(|value| value.is_empty())
+}
+
+/// Progress output of a run, filtered by verbosity
+struct RunOutput<W: Write> {
+    writer: W,
+    verbosity: Verbosity,
+    color: bool,
+}
+
+impl<W: Write> RunOutput<W> {
+    /// Progress, hidden with `--quiet`
+    fn info(&mut self, message: &str) -> io::Result<()> {
+        if self.verbosity == Verbosity::Quiet {
+            return Ok(());
+        }
+        writeln!(self.writer, "{message}")
+    }
+
+    /// Worker stream details, shown only with `--verbose`
+    fn detail(&mut self, message: &str) -> io::Result<()> {
+        if self.verbosity != Verbosity::Verbose {
+            return Ok(());
+        }
+        writeln!(self.writer, "{message}")
+    }
+
+    /// The outcome of a task, always shown (green or red)
+    fn result(&mut self, task_id: &str, success: bool, message: &str) -> io::Result<()> {
+        let (label, code) = if success { ("OK", "32") } else { ("FAILED", "31") };
+        let label = self.paint(label, code);
+        writeln!(self.writer, "[{task_id}] {label} {message}")
+    }
+
+    /// An error, always shown
+    fn error(&mut self, message: &str) -> io::Result<()> {
+        let label = self.paint("error:", "31");
+        writeln!(self.writer, "{label} {message}")
+    }
+
+    /// A `git log --oneline` line: dim hash, colored type prefix (progress)
+    fn commit(&mut self, line: &str) -> io::Result<()> {
+        let commit = OnelineCommit::parse(line);
+        let hash = self.paint(commit.hash, "2");
+        let prefix = match (commit.prefix, commit.kind()) {
+            (Some(prefix), Some(kind)) => {
+                let code = match kind {
+                    CommitKind::Feature => "32",
+                    CommitKind::Fix => "33",
+                    CommitKind::Other => "36",
+                };
+                format!("{} ", self.paint(prefix, code))
+            }
+            _ => String::new(),
+        };
+        self.info(&format!("  - {hash} {prefix}{}", commit.subject))
+    }
+
+    fn paint(&self, text: &str, code: &str) -> String {
+        if self.color {
+            format!("\x1b[{code}m{text}\x1b[0m")
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Print one line of the worker's stream-json output
+    fn stream_event(&mut self, json: &serde_json::Value) -> io::Result<()> {
+        let str_field = |value: &serde_json::Value, key: &str| {
+            value.get(key).and_then(|v| v.as_str()).unwrap_or("").to_string()
+        };
+        let event_type = str_field(json, "type");
+        match event_type.as_str() {
+            "system" => self.info(&format!("[SYS] {}", str_field(json, "subtype"))),
+            "assistant" => {
+                // Text is previewed; tool use only shows up with --verbose
+                let content = json
+                    .get("message")
+                    .and_then(|m| m.get("content"))
+                    .and_then(|c| c.as_array())
+                    .cloned()
+                    .unwrap_or_default();
+                for block in &content {
+                    match str_field(block, "type").as_str() {
+                        "text" => {
+                            let text = str_field(block, "text");
+                            if self.verbosity == Verbosity::Verbose {
+                                self.detail(&format!("[AI] {text}"))?;
+                                continue;
+                            }
+                            let preview: String = text.chars().take(100).collect();
+                            if !preview.trim().is_empty() {
+                                self.info(&format!("[AI] {}...", preview.replace('\n', " ")))?;
+                            }
+                        }
+                        "tool_use" => self.detail(&format!("[TOOL] {}", str_field(block, "name")))?,
+                        _ => {}
+                    }
+                }
+                Ok(())
+            }
+            "result" => self.info(&format!("[RESULT] {}", str_field(json, "subtype"))),
+            other => self.detail(&format!("[{}]", other.to_uppercase())),
+        }
+    }
+}
 
 /// Run workers from a plan file (CLI mode)
-pub fn run_plan(plan_path: PathBuf) -> Result<()> {
-    println!("Loading plan from: {}", plan_path.display());
+///
+/// With `-` as the path the plan is read from stdin and the updated plan is
+/// printed to stdout, so progress goes to stderr to keep stdout parseable.
+///
+/// A worker running past `timeout` seconds (or `agent.task_timeout_secs`)
+/// is killed and its task failed. Ctrl+C kills the current worker and
+/// saves the plan before exiting.
+pub fn run_plan(plan_path: PathBuf, verbosity: Verbosity, timeout: Option<u64>) -> Result<()> {
+    let from_stdin = plan_path == Path::new(STDIN_PATH);
+    let (writer, is_terminal): (Box<dyn Write>, bool) = if from_stdin {
+        (Box::new(io::stderr()), io::stderr().is_terminal())
+    } else {
+        (Box::new(io::stdout()), io::stdout().is_terminal())
+    };
+    let mut out = RunOutput {
+        writer,
+        verbosity,
+        color: use_color(std::env::var_os("NO_COLOR"), is_terminal),
+    };
 
-    // Load plan
-    let plan_content = fs::read_to_string(&plan_path)
-        .with_context(|| format!("Failed to read plan file: {}", plan_path.display()))?;
-    let mut plan: Plan =
-        serde_json::from_str(&plan_content).with_context(|| "Failed to parse plan JSON")?;
+    if from_stdin {
+        out.info("Loading plan from stdin")?;
+    } else {
+        out.info(&format!("Loading plan from: {}", plan_path.display()))?;
+    }
+    let mut plan = load_plan(&plan_path, io::stdin().lock())?;
 
-    println!(
+    out.info(&format!(
         "Plan: {}",
         plan.description.as_deref().unwrap_or("(no description)")
-    );
-    println!("Tasks: {}", plan.tasks.len());
-    println!();
+    ))?;
+    out.info(&format!("Tasks: {}", plan.tasks.len()))?;
+    out.info("")?;
 
     // Load config for worktree settings
-    let config = Config::load().unwrap_or_default();
-    let worktree_manager =
-        WorktreeManager::from_current_dir().context("Failed to initialize worktree manager")?;
+    let config = Config::load()?;
+    cctakt::debug::init_with(config.log_level.as_deref());
+    let worktree_manager = WorktreeManager::from_current_dir()
+        .context("Failed to initialize worktree manager")?
+        .with_naming(config.worktree_naming);
+    let timeout = timeout
+        .or(config.agent.task_timeout_secs)
+        .map(Duration::from_secs);
+
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let handler_flag = Arc::clone(&interrupted);
+    ctrlc::set_handler(move || handler_flag.store(true, Ordering::SeqCst))
+        .context("Failed to install the Ctrl+C handler")?;
 
     // Process pending create_worker tasks
     for task in &mut plan.tasks {
+        if interrupted.load(Ordering::SeqCst) {
+            break;
+        }
         if task.status != TaskStatus::Pending {
-            println!("[{}] Skipping (status: {:?})", task.id, task.status);
+            out.info(&format!("[{}] Skipping (status: {:?})", task.id, task.status))?;
             continue;
         }
 
@@ -43,26 +283,27 @@ pub fn run_plan(plan_path: PathBuf) -> Result<()> {
             base_branch: _,
         } = &task.action
         else {
-            println!("[{}] Skipping (not a create_worker task)", task.id);
+            out.info(&format!("[{}] Skipping (not a create_worker task)", task.id))?;
             continue;
         };
 
-        println!("========================================");
-        println!("[{}] Starting worker", task.id);
-        println!("Branch: {branch}");
-        println!("Task: {}", task_description.lines().next().unwrap_or(""));
-        println!("========================================");
+        out.info("========================================")?;
+        out.info(&format!("[{}] Starting worker", task.id))?;
+        out.info(&format!("Branch: {branch}"))?;
+        out.info(&format!("Task: {}", task_description.lines().next().unwrap_or("")))?;
+        out.info("========================================")?;
 
         // Create worktree
         let worktree_path = match worktree_manager.create(branch, &config.worktree_dir) {
             Ok(path) => {
-                println!("Created worktree: {}", path.display());
+                out.info(&format!("Created worktree: {}", path.display()))?;
                 path
             }
             Err(e) => {
-                println!("Failed to create worktree: {e}");
+                let error = format!("Failed to create worktree: {e}");
+                out.result(&task.id, false, &error)?;
                 task.status = TaskStatus::Failed;
-                task.error = Some(format!("Failed to create worktree: {e}"));
+                task.error = Some(error);
                 continue;
             }
         };
@@ -83,90 +324,267 @@ pub fn run_plan(plan_path: PathBuf) -> Result<()> {
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
-        println!("\n--- Worker output ---\n");
+        out.info("\n--- Worker output ---\n")?;
 
         // Spawn process
-        let mut child = cmd.spawn().context("Failed to spawn claude")?;
-
-        // Read stdout
-        if let Some(stdout) = child.stdout.take() {
-            let reader = BufReader::new(stdout);
-            for line in reader.lines().map_while(Result::ok) {
-                // Parse and display JSON events
-                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&line) {
-                    let event_type = json.get("type").and_then(|v| v.as_str()).unwrap_or("");
-                    match event_type {
-                        "system" => {
-                            let subtype =
-                                json.get("subtype").and_then(|v| v.as_str()).unwrap_or("");
-                            println!("[SYS] {subtype}");
-                        }
-                        "assistant" => {
-                            // Extract only text content (skip tool_use)
-                            if let Some(content) = json
-                                .get("message")
-                                .and_then(|m| m.get("content"))
-                                .and_then(|c| c.as_array())
-                            {
-                                for block in content {
-                                    if block.get("type").and_then(|t| t.as_str()) == Some("text") {
-                                        if let Some(text) =
-                                            block.get("text").and_then(|t| t.as_str())
-                                        {
-                                            let preview: String = text.chars().take(100).collect();
-                                            if !preview.trim().is_empty() {
-                                                println!(
-                                                    "[AI] {}...",
-                                                    preview.replace('\n', " ")
-                                                );
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        "result" => {
-                            let subtype =
-                                json.get("subtype").and_then(|v| v.as_str()).unwrap_or("");
-                            println!("[RESULT] {subtype}");
-                        }
-                        _ => {}
-                    }
-                }
+        let mut child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                out.error(&format!("Failed to spawn claude: {e}"))?;
+                return Err(e).context("Failed to spawn claude");
             }
-        }
+        };
+
+        // stderr is drained alongside so neither pipe can fill up and stall
+        // the worker; its last lines explain a failure
+        let stderr = child
+            .stderr
+            .take()
+            .map(|stderr| StderrTail::capture(stderr, task.id.clone()))
+            .unwrap_or_default();
 
-        // Wait for process to finish
-        let status = child.wait()?;
-        println!("\n--- Worker finished (exit: {status}) ---\n");
+        // Read stdout until the worker exits, times out or Ctrl+C is pressed
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let exit = wait_for_worker(&mut child, deadline, &interrupted, |line| {
+            match serde_json::from_str::<serde_json::Value>(line) {
+                Ok(json) => out.stream_event(&json),
+                Err(_) => Ok(()),
+            }
+        })?;
+        let status = match exit {
+            WorkerExit::Finished(status) => status,
+            WorkerExit::TimedOut => {
+                let error = stderr.annotate(&format!(
+                    "Timed out after {}s",
+                    timeout.unwrap_or_default().as_secs()
+                ));
+                out.result(&task.id, false, &error)?;
+                task.status = TaskStatus::Failed;
+                task.error = Some(error);
+                continue;
+            }
+            WorkerExit::Interrupted => {
+                out.result(&task.id, false, "Interrupted")?;
+                task.status = TaskStatus::Failed;
+                task.error = Some("Interrupted".to_string());
+                break;
+            }
+        };
+        out.info(&format!("\n--- Worker finished (exit: {status}) ---\n"))?;
 
         // Get commits
         let commits = get_worker_commits(&worktree_path);
-        println!("Commits: {}", commits.len());
         for commit in &commits {
-            println!("  - {commit}");
+            out.commit(commit)?;
         }
 
         // Update task
         if status.success() {
+            let count = out.paint(&format!("{} commit(s)", commits.len()), "1");
+            out.result(&task.id, true, &format!("{count} on {branch}"))?;
             task.status = TaskStatus::Completed;
             task.result = Some(TaskResult {
                 commits,
                 pr_number: None,
                 pr_url: None,
+                output: None,
             });
         } else {
+            stderr.wait_closed(Duration::from_secs(1));
+            let error = stderr.annotate(&format!("Process exited with: {status}"));
+            out.result(&task.id, false, &error)?;
             task.status = TaskStatus::Failed;
-            task.error = Some(format!("Process exited with: {status}"));
+            task.error = Some(error);
         }
 
-        println!();
+        out.info("")?;
     }
 
-    // Save updated plan
+    // Save updated plan (or hand it back on stdout when it came from stdin)
     let updated_plan = serde_json::to_string_pretty(&plan)?;
-    fs::write(&plan_path, updated_plan)?;
-    println!("Plan saved to: {}", plan_path.display());
+    if from_stdin {
+        println!("{updated_plan}");
+    } else {
+        fs::write(&plan_path, updated_plan)?;
+        out.info(&format!("Plan saved to: {}", plan_path.display()))?;
+    }
 
+    if interrupted.load(Ordering::SeqCst) {
+        anyhow::bail!("Interrupted");
+    }
     Ok(())
 }
+
+/// Read and parse the plan at `plan_path`, or from `stdin` when the path is `-`
+fn load_plan(plan_path: &Path, mut stdin: impl Read) -> Result<Plan> {
+    let plan_content = if plan_path == Path::new(STDIN_PATH) {
+        let mut content = String::new();
+        stdin
+            .read_to_string(&mut content)
+            .context("Failed to read plan from stdin")?;
+        content
+    } else {
+        fs::read_to_string(plan_path)
+            .with_context(|| format!("Failed to read plan file: {}", plan_path.display()))?
+    };
+    cctakt::plan::parse_plan(&plan_content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(verbosity: Verbosity, color: bool) -> RunOutput<Vec<u8>> {
+        RunOutput {
+            writer: Vec::new(),
+            verbosity,
+            color,
+        }
+    }
+
+    fn text(out: &RunOutput<Vec<u8>>) -> String {
+        String::from_utf8(out.writer.clone()).unwrap()
+    }
+
+    #[test]
+    fn test_load_plan_from_stdin() {
+        let json = r#"{"version":1,"created_at":0,"tasks":[{"id":"w1","action":{"type":"create_worker","branch":"feat/a","task_description":"Do a"}}]}"#;
+        let plan = load_plan(Path::new("-"), json.as_bytes()).unwrap();
+        assert_eq!(plan.tasks.len(), 1);
+        assert_eq!(plan.tasks[0].id, "w1");
+
+        assert!(load_plan(Path::new("-"), "not json".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_load_plan_from_file_ignores_stdin() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("plan.json");
+        fs::write(&path, r#"{"version":1,"created_at":0,"tasks":[]}"#).unwrap();
+        let plan = load_plan(&path, "not json".as_bytes()).unwrap();
+        assert!(plan.tasks.is_empty());
+
+        let err = load_plan(&dir.path().join("missing.json"), io::empty()).unwrap_err();
+        assert!(err.to_string().contains("Failed to read plan file"));
+    }
+
+    #[test]
+    fn test_use_color() {
+        assert!(use_color(None, true));
+        assert!(!use_color(None, false));
+        assert!(!use_color(Some("1".into()), true));
+        // An empty NO_COLOR does not count as set
+        assert!(use_color(Some("".into()), true));
+    }
+
+    #[test]
+    fn test_quiet_prints_only_results_and_errors() {
+        let mut out = output(Verbosity::Quiet, false);
+        out.info("Loading plan").unwrap();
+        out.stream_event(&serde_json::json!({"type": "system", "subtype": "init"})).unwrap();
+        out.result("w1", true, "2 commit(s) on feat/a").unwrap();
+        out.result("w2", false, "Process exited with: 1").unwrap();
+        out.error("Failed to spawn claude").unwrap();
+        assert_eq!(
+            text(&out),
+            "[w1] OK 2 commit(s) on feat/a\n[w2] FAILED Process exited with: 1\nerror: Failed to spawn claude\n"
+        );
+    }
+
+    #[test]
+    fn test_color_wraps_labels_only() {
+        let mut out = output(Verbosity::Normal, true);
+        out.result("w1", false, "boom").unwrap();
+        assert_eq!(text(&out), "[w1] \x1b[31mFAILED\x1b[0m boom\n");
+    }
+
+    #[test]
+    fn test_commit_listing() {
+        let mut out = output(Verbosity::Normal, false);
+        out.commit("abc1234 feat(ui): add footer").unwrap();
+        out.commit("def5678 wip").unwrap();
+        assert_eq!(text(&out), "  - abc1234 feat(ui): add footer\n  - def5678 wip\n");
+
+        let mut out = output(Verbosity::Normal, true);
+        out.commit("abc1234 fix: typo").unwrap();
+        assert_eq!(text(&out), "  - \x1b[2mabc1234\x1b[0m \x1b[33mfix:\x1b[0m typo\n");
+
+        let mut out = output(Verbosity::Quiet, true);
+        out.commit("abc1234 fix: typo").unwrap();
+        assert_eq!(text(&out), "");
+    }
+
+    #[test]
+    fn test_verbose_shows_full_stream() {
+        let event = serde_json::json!({
+            "type": "assistant",
+            "message": {"content": [
+                {"type": "text", "text": format!("{}\nsecond line", "a".repeat(150))},
+                {"type": "tool_use", "name": "Edit"}
+            ]}
+        });
+
+        let mut normal = output(Verbosity::Normal, false);
+        normal.stream_event(&event).unwrap();
+        normal.stream_event(&serde_json::json!({"type": "user"})).unwrap();
+        assert_eq!(text(&normal), format!("[AI] {}...\n", "a".repeat(100)));
+
+        let mut verbose = output(Verbosity::Verbose, false);
+        verbose.stream_event(&event).unwrap();
+        verbose.stream_event(&serde_json::json!({"type": "user"})).unwrap();
+        assert_eq!(
+            text(&verbose),
+            format!("[AI] {}\nsecond line\n[TOOL] Edit\n[USER]\n", "a".repeat(150))
+        );
+    }
+
+    #[cfg(unix)]
+    fn spawn_sh(script: &str) -> Child {
+        Command::new("sh")
+            .arg("-c")
+            .arg(script)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_wait_for_worker_reads_output_until_exit() {
+        let mut child = spawn_sh("echo one; echo two; exit 3");
+        let mut lines = Vec::new();
+        let exit = wait_for_worker(&mut child, None, &AtomicBool::new(false), |line| {
+            lines.push(line.to_string());
+            Ok(())
+        })
+        .unwrap();
+        assert!(matches!(exit, WorkerExit::Finished(status) if status.code() == Some(3)));
+        assert_eq!(lines, ["one", "two"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_wait_for_worker_kills_hung_worker() {
+        // Prints once, then hangs without closing stdout
+        let mut child = spawn_sh("echo started; exec sleep 30");
+        let deadline = Instant::now() + Duration::from_millis(300);
+        let mut lines = Vec::new();
+        let exit = wait_for_worker(&mut child, Some(deadline), &AtomicBool::new(false), |line| {
+            lines.push(line.to_string());
+            Ok(())
+        })
+        .unwrap();
+        assert!(matches!(exit, WorkerExit::TimedOut));
+        assert_eq!(lines, ["started"]);
+        assert!(child.try_wait().unwrap().is_some());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_wait_for_worker_stops_on_interrupt() {
+        let mut child = spawn_sh("exec sleep 30");
+        let exit = wait_for_worker(&mut child, None, &AtomicBool::new(true), |_| Ok(())).unwrap();
+        assert!(matches!(exit, WorkerExit::Interrupted));
+        assert!(child.try_wait().unwrap().is_some());
+    }
+}
@@ -0,0 +1,19 @@
+//! Suggest-commit command implementation
+
+use crate::git_utils::resolve_github_repo;
+use anyhow::{Context, Result};
+use cctakt::{suggest_commit_message, Config, GitHubClient};
+
+/// Print a suggested commit message for a GitHub issue
+pub fn run_suggest_commit(repo: Option<String>, issue: u64) -> Result<()> {
+    let config = Config::load()?;
+    let repo = resolve_github_repo(repo, config.github.repository.as_deref())?;
+
+    let client = GitHubClient::new(&repo)?;
+    let issue = client
+        .get_issue(issue)
+        .with_context(|| format!("Failed to fetch issue #{issue} from {repo}"))?;
+
+    println!("{}", suggest_commit_message(&issue));
+    Ok(())
+}
@@ -1,43 +1,75 @@
 //! TUI command implementation
 
-use crate::agent::{AgentStatus, WorkState};
-use crate::app::{App, AppMode, FocusedPane, InputMode, ReviewFocus};
-use crate::tui::{handle_command_mode, handle_keybinding, handle_navigation_mode, handle_theme_picker_input, ui};
+use crate::agent::{ensure_claude_available, AgentStatus, WorkState};
+use crate::app::{App, AppMode, FocusedPane, InputMode};
+use crate::git_utils::repo_override;
+use crate::tui::render::pane_content_size;
+use crate::tui::{
+    handle_command_mode, handle_help_input, handle_keybinding, handle_log_viewer_input, handle_mouse, handle_navigation_mode,
+    handle_plan_view_input,
+    handle_output_search_input, handle_quit_confirm, handle_review_base_input, handle_review_comment_input, handle_review_commits_input, handle_review_input, handle_review_post_input, handle_review_reject_input, handle_review_search_input, handle_theme_picker_input, ui,
+};
 use anyhow::{Context, Result};
-use cctakt::{create_theme, debug, set_theme, Config, IssuePickerResult, LockFile};
+use cctakt::messages::{self, Locale};
+use cctakt::{create_theme, debug, set_color_depth, set_theme, ColorDepth, Config, IssuePickerResult, LockFile, WorktreeManager};
 use crossterm::{
     cursor::Hide,
-    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind,
+        KeyModifiers,
+    },
     execute,
     terminal::{
         self, disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
     },
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
-use std::io;
-use std::time::Duration;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
 
 /// Run the TUI application
-pub fn run_tui() -> Result<()> {
+///
+/// With `no_confirm`, quitting never asks for confirmation. With `attach`,
+/// the TUI opens straight into review of that branch.
+pub fn run_tui(no_confirm: bool, repo: Option<String>, attach: Option<String>) -> Result<()> {
+    // Fail before taking over the terminal if the branch to attach is unknown
+    if let Some(ref branch) = attach
+        && !WorktreeManager::from_current_dir()?.branch_exists(branch)?
+    {
+        anyhow::bail!("Branch '{branch}' does not exist");
+    }
+
     // Acquire lock to prevent duplicate instances
     // The lock is automatically released when _lock goes out of scope
     let _lock = LockFile::acquire()?;
 
-    // Load configuration
-    let config = Config::load().unwrap_or_default();
+    // Load configuration (--repo / CCTAKT_REPO take precedence over the config)
+    let mut config = Config::load()?;
+    if let Some(repo) = repo_override(repo)? {
+        config.github.repository = Some(repo);
+    }
+    debug::init_with(config.log_level.as_deref());
 
-    // Initialize theme from config
+    // Initialize theme and UI language from config
     set_theme(create_theme(&config.theme));
+    set_color_depth(config.force_color.unwrap_or_else(ColorDepth::from_env));
+    messages::set_ui_locale(Locale::for_ui(config.language.as_deref()));
+
+    // Refuse to start without the claude CLI rather than failing inside the TUI
+    ensure_claude_available()?;
 
     // Get terminal size
     let (cols, rows) = terminal::size().context("Failed to get terminal size")?;
-    let content_rows = rows.saturating_sub(3); // Header 1 line + border 2 lines
-    let content_cols = cols.saturating_sub(2); // Border 2 columns
+    let (content_cols, content_rows) = pane_content_size(cols, rows);
 
     // Setup terminal
+    let mouse_enabled = config.ui.mouse;
     enable_raw_mode().context("Failed to enable raw mode")?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, Hide)?;
+    if mouse_enabled {
+        execute!(stdout, EnableMouseCapture)?;
+    }
     execute!(
         stdout,
         crossterm::terminal::SetTitle("cctakt - Claude Code Orchestrator")
@@ -48,11 +80,15 @@ pub fn run_tui() -> Result<()> {
 
     // Initialize app
     let mut app = App::new(content_rows, content_cols, config);
+    app.confirm_quit = !no_confirm;
 
     // Add initial agent
     if let Err(e) = app.add_agent() {
         // Cleanup and return error
         disable_raw_mode()?;
+        if mouse_enabled {
+            execute!(terminal.backend_mut(), DisableMouseCapture)?;
+        }
         execute!(
             terminal.backend_mut(),
             crossterm::cursor::Show,
@@ -61,10 +97,27 @@ pub fn run_tui() -> Result<()> {
         return Err(e);
     }
 
+    if let Some(branch) = attach {
+        app.attach_branch(&branch);
+    }
+    app.start_update_check();
+    app.start_control_server();
+
     // Main loop
     loop {
         // Draw
+        app.frame = app.frame.wrapping_add(1);
+        app.refresh_output_caches();
         terminal.draw(|f| ui(f, &mut app))?;
+        if app.take_pending_bell() {
+            let backend = terminal.backend_mut();
+            backend.write_all(b"\x07")?;
+            backend.flush()?;
+        }
+        if let Some(command) = app.take_foreground_command() {
+            let status = run_in_foreground(&mut terminal, mouse_enabled, &command)?;
+            app.foreground_command_finished(&command, status);
+        }
 
         // Handle pending agent prompt (wait ~1 second for agent to initialize)
         if app.pending_agent_prompt.is_some() {
@@ -90,6 +143,9 @@ pub fn run_tui() -> Result<()> {
         // Poll events (16ms ≈ 60fps)
         if event::poll(Duration::from_millis(16))? {
             match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press && app.quit_prompt.is_some() => {
+                    handle_quit_confirm(&mut app, key.modifiers, key.code);
+                }
                 Event::Key(key) if key.kind == KeyEventKind::Press => {
                     // Debug: log every key event received
                     debug::log(&format!(
@@ -97,279 +153,52 @@ pub fn run_tui() -> Result<()> {
                         key.code, app.mode, app.input_mode
                     ));
                     match app.mode {
+                        AppMode::ReviewMerge if app.review_base_prompt_open() => {
+                            handle_review_base_input(&mut app, key.code);
+                        }
+                        AppMode::ReviewMerge if app.review_commits_open() => {
+                            handle_review_commits_input(&mut app, key.code);
+                        }
+                        AppMode::ReviewMerge if app.review_reject_prompt_open() => {
+                            handle_review_reject_input(&mut app, key.code);
+                        }
+                        AppMode::ReviewMerge if app.review_comment_prompt_open() => {
+                            handle_review_comment_input(&mut app, key.code);
+                        }
+                        AppMode::ReviewMerge if app.review_post_prompt_open() => {
+                            handle_review_post_input(&mut app, key.code);
+                        }
+                        AppMode::ReviewMerge if app.review_search_open() => {
+                            handle_review_search_input(&mut app, key.code);
+                        }
                         AppMode::ReviewMerge => {
-                            // Handle review mode input with split pane
-                            // Use InputMode for vim-style navigation
-                            match app.input_mode {
-                                InputMode::Navigation => {
-                                    // NAV mode: hjkl for scroll/focus, q to quit, i/Enter to enter Input mode
-                                    match key.code {
-                                        KeyCode::Char('i') | KeyCode::Enter => {
-                                            app.input_mode = InputMode::Input;
-                                        }
-                                        KeyCode::Char('q') | KeyCode::Char('Q') => {
-                                            // Cancel review
-                                            app.cancel_review();
-                                        }
-                                        KeyCode::Char('m') | KeyCode::Char('M') => {
-                                            // Enqueue merge (handled by MergeWorker)
-                                            app.enqueue_merge();
-                                        }
-                                        // Scroll focused pane with j/k
-                                        KeyCode::Char('k') | KeyCode::Up => {
-                                            if let Some(ref mut state) = app.review_state {
-                                                match state.focus {
-                                                    ReviewFocus::Summary => {
-                                                        state.summary_scroll =
-                                                            state.summary_scroll.saturating_sub(1);
-                                                    }
-                                                    ReviewFocus::Diff => {
-                                                        state.diff_view.scroll_up(1);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        KeyCode::Char('j') | KeyCode::Down => {
-                                            if let Some(ref mut state) = app.review_state {
-                                                match state.focus {
-                                                    ReviewFocus::Summary => {
-                                                        let max_scroll = state
-                                                            .commit_log
-                                                            .lines()
-                                                            .count()
-                                                            .saturating_sub(1)
-                                                            as u16;
-                                                        state.summary_scroll =
-                                                            (state.summary_scroll + 1).min(max_scroll);
-                                                    }
-                                                    ReviewFocus::Diff => {
-                                                        state.diff_view.scroll_down(1);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        // Pane navigation with h/l
-                                        KeyCode::Char('h') => {
-                                            app.focused_pane = FocusedPane::Left;
-                                        }
-                                        KeyCode::Char('l') => {
-                                            app.focused_pane = FocusedPane::Right;
-                                        }
-                                        // Focus switching between Summary/Diff with Tab
-                                        KeyCode::Tab => {
-                                            if let Some(ref mut state) = app.review_state {
-                                                state.focus = match state.focus {
-                                                    ReviewFocus::Summary => ReviewFocus::Diff,
-                                                    ReviewFocus::Diff => ReviewFocus::Summary,
-                                                };
-                                            }
-                                        }
-                                        KeyCode::PageUp => {
-                                            if let Some(ref mut state) = app.review_state {
-                                                match state.focus {
-                                                    ReviewFocus::Summary => {
-                                                        state.summary_scroll =
-                                                            state.summary_scroll.saturating_sub(10);
-                                                    }
-                                                    ReviewFocus::Diff => {
-                                                        state.diff_view.page_up(20);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        KeyCode::PageDown => {
-                                            if let Some(ref mut state) = app.review_state {
-                                                match state.focus {
-                                                    ReviewFocus::Summary => {
-                                                        let max_scroll = state
-                                                            .commit_log
-                                                            .lines()
-                                                            .count()
-                                                            .saturating_sub(1)
-                                                            as u16;
-                                                        state.summary_scroll =
-                                                            (state.summary_scroll + 10).min(max_scroll);
-                                                    }
-                                                    ReviewFocus::Diff => {
-                                                        state.diff_view.page_down(20);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        KeyCode::Home => {
-                                            if let Some(ref mut state) = app.review_state {
-                                                match state.focus {
-                                                    ReviewFocus::Summary => {
-                                                        state.summary_scroll = 0;
-                                                    }
-                                                    ReviewFocus::Diff => {
-                                                        state.diff_view.scroll_to_top();
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        KeyCode::End => {
-                                            if let Some(ref mut state) = app.review_state {
-                                                match state.focus {
-                                                    ReviewFocus::Summary => {
-                                                        state.summary_scroll = state
-                                                            .commit_log
-                                                            .lines()
-                                                            .count()
-                                                            .saturating_sub(1)
-                                                            as u16;
-                                                    }
-                                                    ReviewFocus::Diff => {
-                                                        state.diff_view.scroll_to_bottom();
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                                InputMode::Input => {
-                                    // Input mode: Esc to enter NAV mode, other keys for review actions
-                                    match key.code {
-                                        KeyCode::Esc => {
-                                            app.input_mode = InputMode::Navigation;
-                                        }
-                                        KeyCode::Char('m') | KeyCode::Char('M') => {
-                                            // Enqueue merge (handled by MergeWorker)
-                                            app.enqueue_merge();
-                                        }
-                                        KeyCode::Char('c') | KeyCode::Char('C') => {
-                                            // Cancel review
-                                            app.cancel_review();
-                                        }
-                                        // Focus switching between Summary/Diff with Tab
-                                        KeyCode::Tab => {
-                                            if let Some(ref mut state) = app.review_state {
-                                                state.focus = match state.focus {
-                                                    ReviewFocus::Summary => ReviewFocus::Diff,
-                                                    ReviewFocus::Diff => ReviewFocus::Summary,
-                                                };
-                                            }
-                                        }
-                                        // Scroll focused pane with arrow keys
-                                        KeyCode::Up => {
-                                            if let Some(ref mut state) = app.review_state {
-                                                match state.focus {
-                                                    ReviewFocus::Summary => {
-                                                        state.summary_scroll =
-                                                            state.summary_scroll.saturating_sub(1);
-                                                    }
-                                                    ReviewFocus::Diff => {
-                                                        state.diff_view.scroll_up(1);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        KeyCode::Down => {
-                                            if let Some(ref mut state) = app.review_state {
-                                                match state.focus {
-                                                    ReviewFocus::Summary => {
-                                                        let max_scroll = state
-                                                            .commit_log
-                                                            .lines()
-                                                            .count()
-                                                            .saturating_sub(1)
-                                                            as u16;
-                                                        state.summary_scroll =
-                                                            (state.summary_scroll + 1).min(max_scroll);
-                                                    }
-                                                    ReviewFocus::Diff => {
-                                                        state.diff_view.scroll_down(1);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        KeyCode::PageUp => {
-                                            if let Some(ref mut state) = app.review_state {
-                                                match state.focus {
-                                                    ReviewFocus::Summary => {
-                                                        state.summary_scroll =
-                                                            state.summary_scroll.saturating_sub(10);
-                                                    }
-                                                    ReviewFocus::Diff => {
-                                                        state.diff_view.page_up(20);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        KeyCode::PageDown => {
-                                            if let Some(ref mut state) = app.review_state {
-                                                match state.focus {
-                                                    ReviewFocus::Summary => {
-                                                        let max_scroll = state
-                                                            .commit_log
-                                                            .lines()
-                                                            .count()
-                                                            .saturating_sub(1)
-                                                            as u16;
-                                                        state.summary_scroll =
-                                                            (state.summary_scroll + 10).min(max_scroll);
-                                                    }
-                                                    ReviewFocus::Diff => {
-                                                        state.diff_view.page_down(20);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        KeyCode::Home => {
-                                            if let Some(ref mut state) = app.review_state {
-                                                match state.focus {
-                                                    ReviewFocus::Summary => {
-                                                        state.summary_scroll = 0;
-                                                    }
-                                                    ReviewFocus::Diff => {
-                                                        state.diff_view.scroll_to_top();
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        KeyCode::End => {
-                                            if let Some(ref mut state) = app.review_state {
-                                                match state.focus {
-                                                    ReviewFocus::Summary => {
-                                                        state.summary_scroll = state
-                                                            .commit_log
-                                                            .lines()
-                                                            .count()
-                                                            .saturating_sub(1)
-                                                            as u16;
-                                                    }
-                                                    ReviewFocus::Diff => {
-                                                        state.diff_view.scroll_to_bottom();
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                                InputMode::Command => {
-                                    // Command mode not used in review, treat as Input
-                                    if key.code == KeyCode::Esc {
-                                        app.input_mode = InputMode::Navigation;
-                                    }
-                                }
-                            }
+                            handle_review_input(&mut app, key.code);
                         }
                         AppMode::IssuePicker => {
                             // Handle issue picker input
-                            if let Some(result) = app.issue_picker.handle_key(key.code) {
+                            if let Some(result) = app.issue_picker.handle_key_event(key) {
                                 match result {
                                     IssuePickerResult::Selected(issue) => {
                                         app.mode = AppMode::Normal;
                                         let _ = app.add_agent_from_issue(issue);
                                     }
+                                    IssuePickerResult::SelectedAsPlan(issue) => {
+                                        app.mode = AppMode::Normal;
+                                        let _ = app.add_plan_from_issue(issue);
+                                    }
+                                    IssuePickerResult::SelectedAsFullPlan(issue) => {
+                                        app.mode = AppMode::Normal;
+                                        app.add_full_plan_from_issue(issue);
+                                    }
                                     IssuePickerResult::Cancel => {
                                         app.mode = AppMode::Normal;
                                     }
                                     IssuePickerResult::Refresh => {
                                         app.fetch_issues();
                                     }
+                                    IssuePickerResult::OpenInBrowser(url) => {
+                                        app.open_in_browser(&url);
+                                    }
                                 }
                             }
                         }
@@ -379,6 +208,9 @@ pub fn run_tui() -> Result<()> {
                                 // No agents - orchestrator was closed, quit app
                                 debug::log("agent_manager.is_empty() = true, quitting");
                                 app.should_quit = true;
+                            } else if app.output_filter.editing_search {
+                                // Typing a worker output search query
+                                handle_output_search_input(&mut app, key.code);
                             } else {
                                 // Always handle global keybindings (Ctrl+Q, Ctrl+T, etc)
                                 let handled = handle_keybinding(&mut app, key.modifiers, key.code);
@@ -517,24 +349,43 @@ pub fn run_tui() -> Result<()> {
                             // Handle theme picker input
                             handle_theme_picker_input(&mut app, key.code);
                         }
+                        AppMode::LogViewer => {
+                            handle_log_viewer_input(&mut app, key.code);
+                        }
+                        AppMode::Help => {
+                            handle_help_input(&mut app, key.code);
+                        }
+                        AppMode::PlanView => {
+                            handle_plan_view_input(&mut app, key.code);
+                        }
                     }
                 }
+                Event::Mouse(mouse) => {
+                    handle_mouse(&mut app, mouse);
+                }
+                // Applied by `apply_pending_resize` once resizing settles
                 Event::Resize(new_cols, new_rows) => {
-                    let content_rows = new_rows.saturating_sub(3);
-                    let content_cols = new_cols.saturating_sub(2);
-                    app.resize(content_cols, content_rows);
+                    let (content_cols, content_rows) = pane_content_size(new_cols, new_rows);
+                    app.queue_resize(content_cols, content_rows, Instant::now());
                 }
                 _ => {}
             }
         }
 
+        app.apply_pending_resize(Instant::now());
+
         // Check all agents' status
         app.agent_manager.check_all_status();
 
         // Plan processing
         app.check_plan();
         app.check_agent_task_completions();
+        app.check_command_completions();
+        app.check_update_notice();
+        app.check_branch_protection();
+        app.process_control_requests();
         app.process_plan();
+        app.check_plan_completion();
 
         // Check MergeWorker completion
         app.check_merge_worker_completion();
@@ -545,19 +396,7 @@ pub fn run_tui() -> Result<()> {
         app.cleanup_notifications();
 
         // Check if active agent just ended and has a worktree (for review)
-        if app.mode == AppMode::Normal {
-            let active_index = app.agent_manager.active_index();
-            if let Some(agent) = app.agent_manager.active() {
-                if agent.status == AgentStatus::Ended {
-                    // Check if this agent has a worktree
-                    let has_worktree = active_index < app.agent_worktrees.len()
-                        && app.agent_worktrees[active_index].is_some();
-                    if has_worktree {
-                        app.start_review(active_index);
-                    }
-                }
-            }
-        }
+        app.review_ended_active_agent();
 
         if app.should_quit {
             break;
@@ -566,6 +405,9 @@ pub fn run_tui() -> Result<()> {
 
     // Cleanup
     disable_raw_mode()?;
+    if mouse_enabled {
+        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+    }
     execute!(
         terminal.backend_mut(),
         crossterm::cursor::Show,
@@ -574,3 +416,30 @@ pub fn run_tui() -> Result<()> {
 
     Ok(())
 }
+
+/// Run a command that needs the terminal (a text browser or editor)
+///
+/// Leaves raw mode and the alternate screen while it runs, then restores
+/// them and redraws from scratch. The outer error is about the terminal,
+/// the inner one about the command.
+fn run_in_foreground(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    mouse_enabled: bool,
+    command: &[String],
+) -> Result<io::Result<std::process::ExitStatus>> {
+    disable_raw_mode()?;
+    if mouse_enabled {
+        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+    }
+    execute!(terminal.backend_mut(), crossterm::cursor::Show, LeaveAlternateScreen)?;
+
+    let status = std::process::Command::new(&command[0]).args(&command[1..]).status();
+
+    enable_raw_mode()?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen, Hide)?;
+    if mouse_enabled {
+        execute!(terminal.backend_mut(), EnableMouseCapture)?;
+    }
+    terminal.clear()?;
+    Ok(status)
+}
@@ -0,0 +1,146 @@
+//! Worktree command implementation
+
+use crate::cli::WorktreeAction;
+use anyhow::{Context, Result};
+use cctakt::{Config, RemoveOutcome, WorktreeInfo, WorktreeManager};
+
+/// Manage git worktrees outside the TUI
+pub fn run_worktree(action: WorktreeAction) -> Result<()> {
+    let manager = WorktreeManager::from_current_dir()?;
+    match action {
+        WorktreeAction::List { json } => list(&manager, json),
+        WorktreeAction::Add { branch } => add(manager, &branch),
+        WorktreeAction::Remove { branch, force } => remove(&manager, &branch, force),
+        WorktreeAction::Prune => prune(&manager),
+    }
+}
+
+/// Print worktrees as a table (or JSON)
+fn list(manager: &WorktreeManager, json: bool) -> Result<()> {
+    let worktrees = manager.list()?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&worktrees)?);
+        return Ok(());
+    }
+
+    print!("{}", format_table(&worktrees));
+    Ok(())
+}
+
+/// Format worktrees as aligned BRANCH / PATH / HEAD columns
+fn format_table(worktrees: &[WorktreeInfo]) -> String {
+    let rows: Vec<(String, String, String)> = worktrees
+        .iter()
+        .map(|wt| {
+            let branch = if wt.branch.is_empty() {
+                "(detached)".to_string()
+            } else if wt.is_main {
+                format!("{} *", wt.branch)
+            } else {
+                wt.branch.clone()
+            };
+            let head: String = wt.head.chars().take(7).collect();
+            (branch, wt.path.display().to_string(), head)
+        })
+        .collect();
+
+    let branch_width = rows.iter().map(|r| r.0.len()).max().unwrap_or(0).max(6);
+    let path_width = rows.iter().map(|r| r.1.len()).max().unwrap_or(0).max(4);
+
+    let mut out = format!("{:<branch_width$}  {:<path_width$}  HEAD\n", "BRANCH", "PATH");
+    for (branch, path, head) in rows {
+        out.push_str(&format!("{branch:<branch_width$}  {path:<path_width$}  {head}\n"));
+    }
+    out
+}
+
+/// Create a worktree for a new branch under the configured worktree_dir,
+/// named according to worktree_naming
+fn add(manager: WorktreeManager, branch: &str) -> Result<()> {
+    let config = Config::load()?;
+    let manager = manager.with_naming(config.worktree_naming);
+    let path = manager.create(branch, &config.worktree_dir)?;
+    let created = manager
+        .list()?
+        .into_iter()
+        .find(|wt| wt.path == path)
+        .map(|wt| wt.branch)
+        .unwrap_or_else(|| branch.to_string());
+
+    println!("Created worktree for '{created}' at {}", path.display());
+    Ok(())
+}
+
+/// Remove the worktree checked out on `branch`
+fn remove(manager: &WorktreeManager, branch: &str, force: bool) -> Result<()> {
+    let worktree = manager
+        .find_by_branch(branch)?
+        .with_context(|| format!("No worktree has branch '{branch}' checked out"))?;
+
+    if worktree.is_main {
+        anyhow::bail!("'{branch}' is checked out in the main worktree; refusing to remove it");
+    }
+
+    match manager.remove(&worktree.path, force)? {
+        RemoveOutcome::Removed => println!("Removed worktree {}", worktree.path.display()),
+        RemoveOutcome::AlreadyGone => println!(
+            "Worktree {} was already gone; pruned its metadata",
+            worktree.path.display()
+        ),
+        RemoveOutcome::RefusedDirty(_) => anyhow::bail!(
+            "Worktree {} has uncommitted changes. Commit or stash them, or pass --force",
+            worktree.path.display()
+        ),
+    }
+    Ok(())
+}
+
+/// Clean up administrative data for worktrees that no longer exist
+fn prune(manager: &WorktreeManager) -> Result<()> {
+    let pruned = manager.prune()?;
+    if pruned.is_empty() {
+        println!("Nothing to prune.");
+    } else {
+        for line in pruned {
+            println!("{line}");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_format_table() {
+        let worktrees = vec![
+            WorktreeInfo {
+                path: PathBuf::from("/repo"),
+                branch: "main".to_string(),
+                head: "abc1234567".to_string(),
+                is_main: true,
+                locked: false,
+            },
+            WorktreeInfo {
+                path: PathBuf::from("/repo/.worktrees/cctakt-feature"),
+                branch: "cctakt/feature".to_string(),
+                head: "def4567890".to_string(),
+                is_main: false,
+                locked: false,
+            },
+        ];
+
+        let table = format_table(&worktrees);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("BRANCH"));
+        assert!(lines[1].starts_with("main *"));
+        assert!(lines[1].ends_with("abc1234"));
+        assert!(lines[2].contains("/repo/.worktrees/cctakt-feature"));
+        assert!(lines[2].ends_with("def4567"));
+        // Columns line up
+        assert_eq!(lines[1].find("/repo"), lines[2].find("/repo"));
+    }
+}
@@ -1,30 +1,134 @@
 //! Configuration file support for cctakt
 //!
 //! Handles `.cctakt.toml` configuration file loading and saving.
-
+//!
+//! Personal preferences (theme, keybindings, UI) can also be set in a
+//! per-user file (`~/.config/cctakt/config.toml`), which takes precedence
+//! over the project file for those keys only.
+
+use crate::github::Issue;
+use crate::template::{branch_prefix_for, AgentNameStyle, SLUG_SEPARATORS, SlugOptions};
+use crate::theme::ColorDepth;
+use crate::worktree::WorktreeNaming;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 /// Default configuration file name
 const CONFIG_FILE_NAME: &str = ".cctakt.toml";
 
+/// Keys the per-user config file may set; everything else is project-only
+const USER_PREFERENCE_KEYS: &[&str] = &[
+    "theme",
+    "force_color",
+    "agent_name_style",
+    "keybindings",
+    "ui",
+    "log_level",
+    "language",
+    "desktop_notifications",
+    "error_bell",
+    "editor",
+    "check_updates",
+];
+
+/// Path of the project config file (in the current directory)
+pub fn project_config_path() -> PathBuf {
+    PathBuf::from(CONFIG_FILE_NAME)
+}
+
+/// Where an effective config value came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// Built-in default
+    Default,
+    /// Per-user preferences file
+    User(PathBuf),
+    /// Project config file
+    Project(PathBuf),
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::User(path) | ConfigSource::Project(path) => {
+                write!(f, "{}", path.display())
+            }
+        }
+    }
+}
+
+/// A single effective setting and its provenance
+#[derive(Debug, Clone)]
+pub struct ConfigEntry {
+    /// Dotted key path, e.g. `keybindings.quit`
+    pub key: String,
+    /// Effective value
+    pub value: toml::Value,
+    /// Layer the value came from
+    pub source: ConfigSource,
+}
+
+/// Path of the per-user preferences file
+///
+/// `$XDG_CONFIG_HOME/cctakt/config.toml`, falling back to
+/// `~/.config/cctakt/config.toml`. Returns None if neither variable is set.
+pub fn user_config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("cctakt").join("config.toml"))
+}
+
 /// Main configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
     /// Base directory for creating worktrees
     #[serde(default = "default_worktree_dir")]
     pub worktree_dir: PathBuf,
 
+    /// How worktree directories are named: "flat", "nested" or "hash"
+    #[serde(default)]
+    pub worktree_naming: WorktreeNaming,
+
+    /// Give each worker its own worktree; when false, workers run in the
+    /// main checkout on a new branch, one at a time
+    #[serde(default = "default_use_worktrees")]
+    pub use_worktrees: bool,
+
     /// Default branch prefix
     #[serde(default = "default_branch_prefix")]
     pub branch_prefix: String,
 
+    /// Branch prefix per issue label (e.g. `bug = "fix"`), used instead of
+    /// `branch_prefix` for issues with a mapped label; the issue's first
+    /// mapped label wins
+    #[serde(default)]
+    pub label_branch_prefixes: HashMap<String, String>,
+
+    /// How issue titles become branch name slugs
+    #[serde(default)]
+    pub branch_slug: SlugOptions,
+
+    /// How agents are named in tabs: "number" (`#42`), "number_slug"
+    /// (`#42 add-auth`) or "branch"; plan workers always use their branch
+    #[serde(default)]
+    pub agent_name_style: AgentNameStyle,
+
     /// Color theme name: "cyberpunk", "monokai", "dracula", "nord", "minimal"
     #[serde(default = "default_theme")]
     pub theme: String,
 
+    /// Color depth to render with ("truecolor", "256" or "16") instead of
+    /// detecting it from the terminal
+    #[serde(default)]
+    pub force_color: Option<ColorDepth>,
+
     /// GitHub configuration
     #[serde(default)]
     pub github: GitHubConfig,
@@ -36,27 +140,159 @@ pub struct Config {
     /// Keybinding configuration
     #[serde(default)]
     pub keybindings: KeyBindings,
+
+    /// UI display configuration
+    #[serde(default)]
+    pub ui: UiConfig,
+
+    /// Debug log level: "trace", "debug", "info", "warn" or "off"
+    /// (overridden by the CCTAKT_LOG env var)
+    #[serde(default)]
+    pub log_level: Option<String>,
+
+    /// Language for worker prompts and UI text: "en" or "ja"
+    /// (prompts default to English; UI text falls back to LANG)
+    #[serde(default)]
+    pub language: Option<String>,
+
+    /// Custom MergeWorker/BuildWorker prompt templates
+    #[serde(default)]
+    pub prompts: PromptsConfig,
+
+    /// Agent process settings
+    #[serde(default)]
+    pub agent: AgentConfig,
+
+    /// When to push to origin
+    #[serde(default)]
+    pub push: PushConfig,
+
+    /// Command the BuildWorker runs after a merge (auto-detected if unset)
+    #[serde(default)]
+    pub build_command: Option<String>,
+
+    /// Test command the BuildWorker runs after building (auto-detected if unset)
+    #[serde(default)]
+    pub test_command: Option<String>,
+
+    /// Merge reviewed branches with no potential conflicts directly, only
+    /// spawning a MergeWorker when conflicts need resolving
+    #[serde(default = "default_auto_merge_clean")]
+    pub auto_merge_clean: bool,
+
+    /// Remove a branch's worktree once it has been merged
+    #[serde(default = "default_cleanup_after_merge")]
+    pub cleanup_after_merge: bool,
+
+    /// Approving a review merges locally ("local") or opens a PR ("pull_request")
+    #[serde(default)]
+    pub merge_mode: MergeMode,
+
+    /// How review's update action brings a branch up to date with its base:
+    /// "merge" or "rebase"
+    #[serde(default)]
+    pub update_strategy: UpdateStrategy,
+
+    /// Also show success/error notifications as desktop notifications
+    #[serde(default)]
+    pub desktop_notifications: bool,
+
+    /// Check crates.io for a newer cctakt at startup (at most once a day)
+    #[serde(default)]
+    pub check_updates: bool,
+
+    /// Listen on `.cctakt/control.sock` for commands from other tools (Unix only)
+    #[serde(default)]
+    pub control_socket: bool,
+
+    /// Append progress events to `.cctakt/events.jsonl`
+    #[serde(default)]
+    pub event_log: bool,
+
+    /// Command used to open a worktree (default: `$VISUAL`, then `$EDITOR`)
+    #[serde(default)]
+    pub editor: Option<String>,
+
+    /// Draw attention to error notifications: "off", "bell", "flash" or "both"
+    #[serde(default)]
+    pub error_bell: ErrorBell,
+
+    /// Shell command run once when every task in the plan has finished
+    /// (task counts are passed as `CCTAKT_PLAN_*` env vars)
+    #[serde(default)]
+    pub on_plan_complete: Option<String>,
+
+    /// Move a finished plan to `.cctakt/plan_<timestamp>.json`
+    #[serde(default = "default_archive_completed_plans")]
+    pub archive_completed_plans: bool,
+
+    /// Regex commit subjects are checked against in review
+    /// (default: Conventional Commits)
+    #[serde(default = "default_commit_subject_pattern")]
+    pub commit_subject_pattern: String,
+
+    /// Refuse to merge a branch whose commits don't match
+    /// `commit_subject_pattern` (otherwise it's only a warning)
+    #[serde(default)]
+    pub enforce_commit_convention: bool,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             worktree_dir: default_worktree_dir(),
+            use_worktrees: default_use_worktrees(),
+            worktree_naming: WorktreeNaming::default(),
             branch_prefix: default_branch_prefix(),
+            label_branch_prefixes: HashMap::new(),
+            branch_slug: SlugOptions::default(),
+            agent_name_style: AgentNameStyle::default(),
             theme: default_theme(),
+            force_color: None,
             github: GitHubConfig::default(),
             anthropic: AnthropicConfig::default(),
             keybindings: KeyBindings::default(),
+            ui: UiConfig::default(),
+            log_level: None,
+            language: None,
+            prompts: PromptsConfig::default(),
+            agent: AgentConfig::default(),
+            push: PushConfig::default(),
+            build_command: None,
+            test_command: None,
+            auto_merge_clean: default_auto_merge_clean(),
+            cleanup_after_merge: default_cleanup_after_merge(),
+            merge_mode: MergeMode::default(),
+            update_strategy: UpdateStrategy::default(),
+            desktop_notifications: false,
+            check_updates: false,
+            control_socket: false,
+            event_log: false,
+            error_bell: ErrorBell::default(),
+            editor: None,
+            on_plan_complete: None,
+            archive_completed_plans: default_archive_completed_plans(),
+            commit_subject_pattern: default_commit_subject_pattern(),
+            enforce_commit_convention: false,
         }
     }
 }
 
+/// Conventional Commits (`type(scope)!: subject`); merge and revert commits
+/// git writes itself are let through
+pub const DEFAULT_COMMIT_SUBJECT_PATTERN: &str = r#"^((build|chore|ci|docs|feat|fix|perf|refactor|revert|style|test)(\([^()]+\))?!?: \S|Merge |Revert ")"#;
+
+fn default_commit_subject_pattern() -> String {
+    DEFAULT_COMMIT_SUBJECT_PATTERN.to_string()
+}
+
 fn default_theme() -> String {
     "cyberpunk".to_string()
 }
 
 /// GitHub-related configuration
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
 pub struct GitHubConfig {
     /// Whether to automatically fetch issues
     #[serde(default)]
@@ -71,8 +307,123 @@ pub struct GitHubConfig {
     pub labels: Vec<String>,
 }
 
+/// UI display configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UiConfig {
+    /// Show tool use lines (e.g. `[BASH] cargo build`) in worker output
+    #[serde(default)]
+    pub show_tool_use: bool,
+
+    /// Capture the mouse for clicking tabs/panes and wheel scrolling
+    /// (disables the terminal's native text selection)
+    #[serde(default)]
+    pub mouse: bool,
+
+    /// Show the active agent's working directory in the header
+    #[serde(default)]
+    pub show_worktree_path: bool,
+
+    /// Share of the split view given to the orchestrator pane
+    /// (`MIN_SPLIT_RATIO` to `MAX_SPLIT_RATIO`)
+    #[serde(default = "default_split_ratio")]
+    pub split_ratio: f32,
+
+    /// How the orchestrator and worker panes are arranged
+    #[serde(default)]
+    pub split_direction: SplitDirection,
+
+    /// Start with workers tiled in a grid instead of one at a time
+    #[serde(default)]
+    pub worker_grid: bool,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            show_tool_use: false,
+            mouse: false,
+            show_worktree_path: false,
+            split_ratio: default_split_ratio(),
+            split_direction: SplitDirection::default(),
+            worker_grid: false,
+        }
+    }
+}
+
+/// Smallest `ui.split_ratio`
+pub const MIN_SPLIT_RATIO: f32 = 0.1;
+
+/// Largest `ui.split_ratio`
+pub const MAX_SPLIT_RATIO: f32 = 0.9;
+
+fn default_split_ratio() -> f32 {
+    0.5
+}
+
+/// Arrangement of the orchestrator and worker panes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitDirection {
+    /// Side by side: orchestrator on the left, workers on the right
+    #[default]
+    Horizontal,
+    /// Stacked: orchestrator on top, workers below
+    Vertical,
+}
+
+/// How error notifications get attention
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorBell {
+    /// Nothing beyond the notification itself
+    #[default]
+    Off,
+    /// Ring the terminal bell
+    Bell,
+    /// Briefly flash the main area border
+    Flash,
+    /// Bell and flash
+    Both,
+}
+
+impl ErrorBell {
+    /// Whether to ring the terminal bell
+    pub fn rings(self) -> bool {
+        matches!(self, ErrorBell::Bell | ErrorBell::Both)
+    }
+
+    /// Whether to flash the border
+    pub fn flashes(self) -> bool {
+        matches!(self, ErrorBell::Flash | ErrorBell::Both)
+    }
+}
+
+/// What approving a review does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeMode {
+    /// Merge the branch into main locally
+    #[default]
+    Local,
+    /// Push the branch and open a pull request
+    PullRequest,
+}
+
+/// How a branch is brought up to date with its base
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateStrategy {
+    /// Merge the base into the branch
+    #[default]
+    Merge,
+    /// Rebase the branch onto the base
+    Rebase,
+}
+
 /// Anthropic API configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct AnthropicConfig {
     /// API key (can also be set via ANTHROPIC_API_KEY env var)
     #[serde(default)]
@@ -116,8 +467,9 @@ impl Default for AnthropicConfig {
 
 /// Keybinding configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct KeyBindings {
-    /// Key to create new agent (default: "ctrl+t")
+    /// Key to create new agent (default: "ctrl+t"; reserved, not bound yet)
     #[serde(default = "default_new_agent")]
     pub new_agent: String,
 
@@ -125,11 +477,11 @@ pub struct KeyBindings {
     #[serde(default = "default_close_agent")]
     pub close_agent: String,
 
-    /// Key to switch to next tab (default: "tab")
+    /// Key to switch to next tab (default: "ctrl+n")
     #[serde(default = "default_next_tab")]
     pub next_tab: String,
 
-    /// Key to switch to previous tab (default: "shift+tab")
+    /// Key to switch to previous tab (default: "ctrl+p")
     #[serde(default = "default_prev_tab")]
     pub prev_tab: String,
 
@@ -138,6 +490,160 @@ pub struct KeyBindings {
     pub quit: String,
 }
 
+/// Worker prompt templates (`.cctakt/*_worker_prompt.md` files take precedence)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct PromptsConfig {
+    /// MergeWorker prompt; placeholders `{{branch}}`, `{{main_branch}}`
+    #[serde(default)]
+    pub merge_worker: Option<String>,
+
+    /// BuildWorker prompt; placeholders `{{branch}}`, `{{build_command}}`,
+    /// `{{test_command}}`, `{{steps}}`
+    #[serde(default)]
+    pub build_worker: Option<String>,
+}
+
+/// Agent process configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AgentConfig {
+    /// Extra attempts when spawning a plan worker fails transiently (e.g.
+    /// EAGAIN), at most `MAX_SPAWN_RETRIES`; other agents are started once
+    #[serde(default = "default_spawn_retries")]
+    pub spawn_retries: u32,
+
+    /// Delay before the first retry in milliseconds (doubles per attempt),
+    /// at most `MAX_SPAWN_RETRY_BACKOFF_MS`
+    #[serde(default = "default_spawn_retry_backoff_ms")]
+    pub spawn_retry_backoff_ms: u64,
+
+    /// Worker output kept in memory per agent; the oldest lines are
+    /// dropped beyond this
+    #[serde(default = "default_max_output_bytes")]
+    pub max_output_bytes: usize,
+
+    /// Kill a `cctakt run` worker still running after this many seconds and
+    /// fail its task (no limit if unset)
+    #[serde(default)]
+    pub task_timeout_secs: Option<u64>,
+}
+
+impl Default for AgentConfig {
+    fn default() -> Self {
+        Self {
+            spawn_retries: default_spawn_retries(),
+            spawn_retry_backoff_ms: default_spawn_retry_backoff_ms(),
+            max_output_bytes: default_max_output_bytes(),
+            task_timeout_secs: None,
+        }
+    }
+}
+
+/// Upper bound for `agent.spawn_retries`
+pub const MAX_SPAWN_RETRIES: u32 = 5;
+
+/// Upper bound for `agent.spawn_retry_backoff_ms`
+pub const MAX_SPAWN_RETRY_BACKOFF_MS: u64 = 5_000;
+
+/// Default `agent.max_output_bytes` (4 MiB)
+pub const DEFAULT_MAX_OUTPUT_BYTES: usize = 4 * 1024 * 1024;
+
+fn default_max_output_bytes() -> usize {
+    DEFAULT_MAX_OUTPUT_BYTES
+}
+
+fn default_spawn_retries() -> u32 {
+    2
+}
+
+fn default_spawn_retry_backoff_ms() -> u64 {
+    200
+}
+
+/// Pushing branches to `origin`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PushConfig {
+    /// Push the branch before opening a PR
+    #[serde(default = "default_push_before_pr")]
+    pub before_pr: bool,
+
+    /// Push the merge target after a successful local merge
+    #[serde(default)]
+    pub after_merge: bool,
+}
+
+impl Default for PushConfig {
+    fn default() -> Self {
+        Self {
+            before_pr: default_push_before_pr(),
+            after_merge: false,
+        }
+    }
+}
+
+fn default_push_before_pr() -> bool {
+    true
+}
+
+/// Build and test commands for the post-merge BuildWorker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildCommands {
+    pub build: Option<String>,
+    pub test: Option<String>,
+}
+
+impl BuildCommands {
+    fn new(build: &str, test: &str) -> Self {
+        Self {
+            build: Some(build.to_string()),
+            test: Some(test.to_string()),
+        }
+    }
+
+    /// Whether there is anything to run
+    pub fn is_empty(&self) -> bool {
+        self.build.is_none() && self.test.is_none()
+    }
+}
+
+/// Guess build and test commands from the marker files in `dir`
+pub fn detect_build_commands(dir: &Path) -> Option<BuildCommands> {
+    let has = |name: &str| dir.join(name).exists();
+
+    if has("Cargo.toml") {
+        Some(BuildCommands::new("cargo build", "cargo test"))
+    } else if has("package.json") {
+        let runner = if has("pnpm-lock.yaml") {
+            "pnpm"
+        } else if has("yarn.lock") {
+            "yarn"
+        } else {
+            "npm"
+        };
+        Some(BuildCommands::new(
+            &format!("{runner} run build"),
+            &format!("{runner} test"),
+        ))
+    } else if has("go.mod") {
+        Some(BuildCommands::new("go build ./...", "go test ./..."))
+    } else if has("pom.xml") {
+        Some(BuildCommands::new("mvn -q compile", "mvn -q test"))
+    } else if has("gradlew") {
+        Some(BuildCommands::new("./gradlew build", "./gradlew test"))
+    } else if has("pyproject.toml") || has("setup.py") {
+        Some(BuildCommands {
+            build: None,
+            test: Some("pytest".to_string()),
+        })
+    } else if has("Makefile") {
+        Some(BuildCommands::new("make", "make test"))
+    } else {
+        None
+    }
+}
+
 // Default value functions
 fn default_worktree_dir() -> PathBuf {
     PathBuf::from(".worktrees")
@@ -147,6 +653,22 @@ fn default_branch_prefix() -> String {
     "cctakt".to_string()
 }
 
+fn default_use_worktrees() -> bool {
+    true
+}
+
+fn default_auto_merge_clean() -> bool {
+    true
+}
+
+fn default_cleanup_after_merge() -> bool {
+    true
+}
+
+fn default_archive_completed_plans() -> bool {
+    true
+}
+
 fn default_new_agent() -> String {
     "ctrl+t".to_string()
 }
@@ -156,11 +678,11 @@ fn default_close_agent() -> String {
 }
 
 fn default_next_tab() -> String {
-    "tab".to_string()
+    "ctrl+n".to_string()
 }
 
 fn default_prev_tab() -> String {
-    "shift+tab".to_string()
+    "ctrl+p".to_string()
 }
 
 fn default_quit() -> String {
@@ -180,17 +702,157 @@ impl Default for KeyBindings {
 }
 
 impl Config {
+    /// Branch prefix for an issue, from its labels or `branch_prefix`
+    pub fn branch_prefix_for(&self, issue: &Issue) -> &str {
+        branch_prefix_for(issue, &self.branch_prefix, &self.label_branch_prefixes)
+    }
+
+    /// Build/test commands for the BuildWorker: configured values first,
+    /// then whatever `detect_build_commands` finds in `repo_root`
+    pub fn build_commands(&self, repo_root: &Path) -> BuildCommands {
+        let detected = detect_build_commands(repo_root);
+        let detected_build = detected.as_ref().and_then(|d| d.build.clone());
+        let detected_test = detected.and_then(|d| d.test);
+        BuildCommands {
+            build: self.build_command.clone().or(detected_build),
+            test: self.test_command.clone().or(detected_test),
+        }
+    }
+
     /// Load configuration file (returns default if not found)
     ///
     /// Searches for `.cctakt.toml` in the current directory.
+    /// Preferences from the per-user file are layered on top. A file that
+    /// exists but can't be parsed, or values that fail `validate`, are errors.
     pub fn load() -> Result<Self> {
-        let config_path = PathBuf::from(CONFIG_FILE_NAME);
+        let config = Self::load_layered(&project_config_path(), user_config_path().as_deref())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Load the project config and layer user preferences over it
+    ///
+    /// Missing files are treated as empty. Only `USER_PREFERENCE_KEYS`
+    /// are taken from the user file.
+    pub fn load_layered(project_path: &Path, user_path: Option<&Path>) -> Result<Self> {
+        let project = read_toml_table(project_path)?.unwrap_or_default();
+        let user = match user_path {
+            Some(path) => read_toml_table(path)?,
+            None => None,
+        };
+        Self::from_layers(project, user)
+    }
 
-        if config_path.exists() {
-            Self::load_from(&config_path)
+    /// Build a config from an already-read project table and optional user table
+    fn from_layers(mut merged: toml::Table, user: Option<toml::Table>) -> Result<Self> {
+        for (key, value) in user.unwrap_or_default() {
+            if !USER_PREFERENCE_KEYS.contains(&key.as_str()) {
+                continue;
+            }
+            match (merged.get_mut(&key), value) {
+                (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => {
+                    merge_tables(base, overlay);
+                }
+                (_, value) => {
+                    merged.insert(key, value);
+                }
+            }
+        }
+
+        toml::Value::Table(merged)
+            .try_into()
+            .context("Failed to parse configuration")
+    }
+
+    /// List every effective setting with the file it came from
+    ///
+    /// Keys are dotted paths (e.g. `keybindings.quit`). Unset optional
+    /// values are omitted.
+    pub fn effective_entries(
+        project_path: &Path,
+        user_path: Option<&Path>,
+    ) -> Result<Vec<ConfigEntry>> {
+        let project = read_toml_table(project_path)?.unwrap_or_default();
+        let user = match user_path {
+            Some(path) => read_toml_table(path)?,
+            None => None,
+        };
+        let config = Self::from_layers(project.clone(), user.clone())?;
+
+        let mut entries = Vec::new();
+        for (key, value) in flatten_table(&config.to_table()?) {
+            let root = key.split('.').next().unwrap_or_default();
+            let from_user = USER_PREFERENCE_KEYS.contains(&root)
+                && user.as_ref().is_some_and(|u| lookup_path(u, &key).is_some());
+            let source = if from_user {
+                ConfigSource::User(user_path.map(Path::to_path_buf).unwrap_or_default())
+            } else if lookup_path(&project, &key).is_some() {
+                ConfigSource::Project(project_path.to_path_buf())
+            } else {
+                ConfigSource::Default
+            };
+            entries.push(ConfigEntry { key, value, source });
+        }
+        Ok(entries)
+    }
+
+    /// Look up a setting by dotted key path (e.g. `github.repository`)
+    pub fn get_value(&self, key: &str) -> Result<Option<toml::Value>> {
+        Ok(lookup_path(&self.to_table()?, key).cloned())
+    }
+
+    /// Set a dotted key in a config file, checking the result before writing
+    ///
+    /// `raw` is parsed as a TOML value (`true`, `1024`, `["a"]`), falling
+    /// back to a plain string. The file being written must still match the
+    /// schema, and the layered result must pass `validate`.
+    pub fn set_value_in(
+        target: &Path,
+        project_path: &Path,
+        user_path: Option<&Path>,
+        key: &str,
+        raw: &str,
+    ) -> Result<()> {
+        let value = parse_raw_value(raw);
+        let mut table = read_toml_table(target)?.unwrap_or_default();
+        set_path(&mut table, key, value)?;
+
+        toml::Value::Table(table.clone())
+            .try_into::<Config>()
+            .with_context(|| format!("Invalid value for '{key}'"))?;
+
+        let (project, user) = if user_path == Some(target) {
+            (read_toml_table(project_path)?.unwrap_or_default(), Some(table.clone()))
         } else {
-            Ok(Self::default())
+            let user = match user_path {
+                Some(path) => read_toml_table(path)?,
+                None => None,
+            };
+            (table.clone(), user)
+        };
+        Self::from_layers(project, user)?.validate()?;
+
+        if let Some(parent) = target.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
         }
+        let content = toml::to_string_pretty(&table).context("Failed to serialize configuration")?;
+        fs::write(target, content)
+            .with_context(|| format!("Failed to write configuration file: {}", target.display()))?;
+        Ok(())
+    }
+
+    /// Whether a dotted key may be set in the per-user file
+    pub fn is_user_preference(key: &str) -> bool {
+        let root = key.split('.').next().unwrap_or_default();
+        USER_PREFERENCE_KEYS.contains(&root)
+    }
+
+    /// Serialize to a TOML table
+    fn to_table(&self) -> Result<toml::Table> {
+        toml::Table::try_from(self).context("Failed to serialize configuration")
     }
 
     /// Load configuration from specified path
@@ -223,6 +885,131 @@ impl Config {
         Ok(())
     }
 
+    /// Check values that parse fine but can't work
+    ///
+    /// A `worktree_dir` outside the repository is allowed; the TUI warns when
+    /// it creates a worktree there.
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+
+        if self.worktree_dir.as_os_str().is_empty() {
+            problems.push("worktree_dir must not be empty".to_string());
+        }
+
+        if let Err(reason) = check_ref_prefix(&self.branch_prefix) {
+            problems.push(format!(
+                "branch_prefix '{}' is not a valid git ref component: {reason}",
+                self.branch_prefix
+            ));
+        }
+        let mut label_prefixes: Vec<_> = self.label_branch_prefixes.iter().collect();
+        label_prefixes.sort();
+        for (label, prefix) in label_prefixes {
+            if let Err(reason) = check_ref_prefix(prefix) {
+                problems.push(format!(
+                    "label_branch_prefixes.{label} '{prefix}' is not a valid git ref component: {reason}"
+                ));
+            }
+        }
+
+        if let Err(e) = regex::Regex::new(&self.commit_subject_pattern) {
+            problems.push(format!(
+                "commit_subject_pattern is not a valid regex: {}",
+                e.to_string().lines().last().unwrap_or_default().trim()
+            ));
+        }
+
+        if let Some(ref language) = self.language
+            && crate::messages::Locale::parse(language).is_none()
+        {
+            problems.push(format!("unknown language '{language}' (expected one of: en, ja)"));
+        }
+
+        if !SLUG_SEPARATORS.contains(&self.branch_slug.separator.as_str()) {
+            problems.push(format!(
+                "branch_slug.separator '{}' is not supported (expected one of: {})",
+                self.branch_slug.separator,
+                SLUG_SEPARATORS.join(", ")
+            ));
+        }
+
+        if !crate::theme::available_themes()
+            .iter()
+            .any(|(id, _, _)| *id == self.theme)
+        {
+            let ids: Vec<&str> = crate::theme::available_themes()
+                .iter()
+                .map(|(id, _, _)| *id)
+                .collect();
+            problems.push(format!(
+                "unknown theme '{}' (expected one of: {})",
+                self.theme,
+                ids.join(", ")
+            ));
+        }
+
+        if let Some(ref level) = self.log_level
+            && !crate::debug::Level::is_valid_name(level)
+        {
+            problems.push(format!(
+                "unknown log_level '{level}' (expected one of: trace, debug, info, warn, off)"
+            ));
+        }
+
+        if !(MIN_SPLIT_RATIO..=MAX_SPLIT_RATIO).contains(&self.ui.split_ratio) {
+            problems.push(format!(
+                "ui.split_ratio {} is out of range (expected {MIN_SPLIT_RATIO} to {MAX_SPLIT_RATIO})",
+                self.ui.split_ratio
+            ));
+        }
+
+        if self.agent.spawn_retries > MAX_SPAWN_RETRIES {
+            problems.push(format!(
+                "agent.spawn_retries {} is too high (at most {MAX_SPAWN_RETRIES})",
+                self.agent.spawn_retries
+            ));
+        }
+        if self.agent.spawn_retry_backoff_ms > MAX_SPAWN_RETRY_BACKOFF_MS {
+            problems.push(format!(
+                "agent.spawn_retry_backoff_ms {} is too high (at most {MAX_SPAWN_RETRY_BACKOFF_MS})",
+                self.agent.spawn_retry_backoff_ms
+            ));
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            anyhow::bail!("Invalid configuration:\n  - {}", problems.join("\n  - "))
+        }
+    }
+
+    /// Save a single preference key to the per-user file
+    ///
+    /// Other keys already in the user file are preserved. Returns the path
+    /// written to.
+    pub fn save_user_preference(key: &str, value: toml::Value) -> Result<PathBuf> {
+        let path = user_config_path().context("Could not determine user config directory")?;
+        Self::save_user_preference_to(&path, key, value)?;
+        Ok(path)
+    }
+
+    /// Save a single preference key to the given user file
+    pub fn save_user_preference_to(path: &Path, key: &str, value: toml::Value) -> Result<()> {
+        let mut table = read_toml_table(path)?.unwrap_or_default();
+        table.insert(key.to_string(), value);
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        let content = toml::to_string_pretty(&table)
+            .context("Failed to serialize configuration")?;
+        fs::write(path, content)
+            .with_context(|| format!("Failed to write configuration file: {}", path.display()))?;
+
+        Ok(())
+    }
+
     /// Generate default configuration file
     pub fn generate_default(path: &Path) -> Result<()> {
         let config = Config::default();
@@ -230,6 +1017,121 @@ impl Config {
     }
 }
 
+/// Read a TOML file as a table, or None if it doesn't exist
+///
+/// The file is also checked against the `Config` schema here, so unknown
+/// keys and type mismatches are reported with their line in that file.
+fn read_toml_table(path: &Path) -> Result<Option<toml::Table>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read configuration file: {}", path.display()))?;
+    toml::from_str::<Config>(&content)
+        .with_context(|| format!("Invalid configuration file: {}", path.display()))?;
+    let table = content
+        .parse::<toml::Table>()
+        .with_context(|| format!("Failed to parse configuration file: {}", path.display()))?;
+    Ok(Some(table))
+}
+
+/// Flatten nested tables into (dotted key, leaf value) pairs
+fn flatten_table(table: &toml::Table) -> Vec<(String, toml::Value)> {
+    let mut out = Vec::new();
+    for (key, value) in table {
+        match value {
+            toml::Value::Table(inner) => {
+                for (sub, leaf) in flatten_table(inner) {
+                    out.push((format!("{key}.{sub}"), leaf));
+                }
+            }
+            _ => out.push((key.clone(), value.clone())),
+        }
+    }
+    out
+}
+
+/// Look up a dotted key path in a table
+fn lookup_path<'a>(table: &'a toml::Table, key: &str) -> Option<&'a toml::Value> {
+    let mut parts = key.split('.');
+    let mut value = table.get(parts.next()?)?;
+    for part in parts {
+        value = value.as_table()?.get(part)?;
+    }
+    Some(value)
+}
+
+/// Set a dotted key path in a table, creating intermediate tables
+fn set_path(table: &mut toml::Table, key: &str, value: toml::Value) -> Result<()> {
+    let (parents, leaf) = match key.rsplit_once('.') {
+        Some((parents, leaf)) => (Some(parents), leaf),
+        None => (None, key),
+    };
+    if leaf.is_empty() {
+        anyhow::bail!("Invalid key: '{key}'");
+    }
+
+    let mut current = table;
+    for part in parents.into_iter().flat_map(|p| p.split('.')) {
+        current = current
+            .entry(part.to_string())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .with_context(|| format!("'{part}' is not a table in key '{key}'"))?;
+    }
+    current.insert(leaf.to_string(), value);
+    Ok(())
+}
+
+/// Parse a command-line value as TOML, falling back to a plain string
+fn parse_raw_value(raw: &str) -> toml::Value {
+    format!("v = {raw}")
+        .parse::<toml::Table>()
+        .ok()
+        .and_then(|mut t| t.remove("v"))
+        .unwrap_or_else(|| toml::Value::String(raw.to_string()))
+}
+
+/// Check that a branch prefix is usable as the leading part of a git ref
+///
+/// Follows the rules of `git check-ref-format` that apply to a prefix.
+pub(crate) fn check_ref_prefix(prefix: &str) -> std::result::Result<(), &'static str> {
+    if prefix.is_empty() {
+        return Err("must not be empty");
+    }
+    if prefix.starts_with('/') || prefix.ends_with('/') || prefix.contains("//") {
+        return Err("must not start or end with '/' or contain '//'");
+    }
+    if prefix.contains("..") || prefix.contains("@{") {
+        return Err("must not contain '..' or '@{'");
+    }
+    if prefix
+        .chars()
+        .any(|c| c.is_ascii_control() || " ~^:?*[\\".contains(c))
+    {
+        return Err("must not contain spaces, control characters or any of ~^:?*[\\");
+    }
+    if prefix
+        .split('/')
+        .any(|part| part.starts_with('.') || part.ends_with(".lock") || part.ends_with('.'))
+    {
+        return Err("components must not start or end with '.' or end with '.lock'");
+    }
+    Ok(())
+}
+
+/// Recursively merge `overlay` into `base`, overlay values winning
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(b)), toml::Value::Table(o)) => merge_tables(b, o),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -253,6 +1155,499 @@ mod tests {
         assert_eq!(config.anthropic.model, "claude-sonnet-4-20250514");
         assert_eq!(config.anthropic.max_tokens, 1024);
         assert!(config.anthropic.auto_generate_pr_description);
+        // UI defaults
+        assert!(!config.ui.show_tool_use);
+        assert!(!config.ui.mouse);
+        // Agent defaults
+        assert_eq!(config.agent.spawn_retries, 2);
+        assert_eq!(config.agent.spawn_retry_backoff_ms, 200);
+        assert_eq!(config.agent.max_output_bytes, 4 * 1024 * 1024);
+        assert_eq!(config.agent.task_timeout_secs, None);
+        assert!(!config.desktop_notifications);
+        assert!(!config.check_updates);
+        assert!(!config.control_socket);
+        assert!(!config.event_log);
+        assert_eq!(config.error_bell, ErrorBell::Off);
+        assert!(config.auto_merge_clean);
+        assert!(config.cleanup_after_merge);
+        assert!(config.use_worktrees);
+        assert_eq!(config.agent_name_style, AgentNameStyle::Number);
+        assert_eq!(config.force_color, None);
+        assert!(config.archive_completed_plans);
+        assert_eq!(config.merge_mode, MergeMode::Local);
+        assert!(config.push.before_pr);
+        assert!(!config.push.after_merge);
+    }
+
+    #[test]
+    fn test_worktree_naming_parse() {
+        assert_eq!(Config::default().worktree_naming, WorktreeNaming::Flat);
+        let config: Config = toml::from_str(r#"worktree_naming = "nested""#).unwrap();
+        assert_eq!(config.worktree_naming, WorktreeNaming::Nested);
+        assert!(toml::from_str::<Config>(r#"worktree_naming = "random""#).is_err());
+    }
+
+    #[test]
+    fn test_force_color_parse() {
+        let config: Config = toml::from_str(r#"force_color = "256""#).unwrap();
+        assert_eq!(config.force_color, Some(ColorDepth::Ansi256));
+        assert!(toml::from_str::<Config>(r#"force_color = "88""#).is_err());
+    }
+
+    #[test]
+    fn test_agent_name_style_parse() {
+        let config: Config = toml::from_str(r#"agent_name_style = "number_slug""#).unwrap();
+        assert_eq!(config.agent_name_style, AgentNameStyle::NumberSlug);
+        assert!(toml::from_str::<Config>(r#"agent_name_style = "title""#).is_err());
+    }
+
+    #[test]
+    fn test_merge_mode_parse() {
+        let config: Config = toml::from_str(r#"merge_mode = "pull_request""#).unwrap();
+        assert_eq!(config.merge_mode, MergeMode::PullRequest);
+        assert!(toml::from_str::<Config>(r#"merge_mode = "rebase""#).is_err());
+    }
+
+    #[test]
+    fn test_error_bell_parse() {
+        let config: Config = toml::from_str(r#"error_bell = "both""#).unwrap();
+        assert_eq!(config.error_bell, ErrorBell::Both);
+        assert!(config.error_bell.rings() && config.error_bell.flashes());
+
+        let config: Config = toml::from_str(r#"error_bell = "flash""#).unwrap();
+        assert!(!config.error_bell.rings() && config.error_bell.flashes());
+
+        assert!(toml::from_str::<Config>(r#"error_bell = "loud""#).is_err());
+    }
+
+    #[test]
+    fn test_load_ui_config() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(
+            temp_file,
+            r#"
+[ui]
+show_tool_use = true
+"#
+        )
+        .unwrap();
+
+        let config = Config::load_from(temp_file.path()).unwrap();
+        assert!(config.ui.show_tool_use);
+        assert_eq!(config.ui.split_ratio, 0.5);
+        assert_eq!(config.ui.split_direction, SplitDirection::Horizontal);
+    }
+
+    #[test]
+    fn test_split_layout_config() {
+        let config: Config =
+            toml::from_str("[ui]\nsplit_ratio = 0.6\nsplit_direction = \"vertical\"\n").unwrap();
+        assert_eq!(config.ui.split_ratio, 0.6);
+        assert_eq!(config.ui.split_direction, SplitDirection::Vertical);
+
+        assert!(toml::from_str::<Config>("[ui]\nsplit_direction = \"diagonal\"\n").is_err());
+    }
+
+    #[test]
+    fn test_load_layered_user_preferences() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = dir.path().join(".cctakt.toml");
+        let user = dir.path().join("user.toml");
+        fs::write(
+            &project,
+            r#"
+theme = "cyberpunk"
+branch_prefix = "team"
+
+[keybindings]
+new_agent = "ctrl+n"
+"#,
+        )
+        .unwrap();
+        fs::write(
+            &user,
+            r#"
+theme = "nord"
+branch_prefix = "mine"
+desktop_notifications = true
+
+[keybindings]
+quit = "ctrl+c"
+
+[github]
+repository = "me/fork"
+"#,
+        )
+        .unwrap();
+
+        let config = Config::load_layered(&project, Some(&user)).unwrap();
+        // Preferences come from the user file
+        assert_eq!(config.theme, "nord");
+        assert_eq!(config.keybindings.quit, "ctrl+c");
+        assert!(config.desktop_notifications);
+        // Nested tables are merged, not replaced
+        assert_eq!(config.keybindings.new_agent, "ctrl+n");
+        // Repo-specific settings stay with the project
+        assert_eq!(config.branch_prefix, "team");
+        assert!(config.github.repository.is_none());
+    }
+
+    #[test]
+    fn test_load_layered_missing_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = dir.path().join(".cctakt.toml");
+        let user = dir.path().join("user.toml");
+
+        let config = Config::load_layered(&project, Some(&user)).unwrap();
+        assert_eq!(config.theme, "cyberpunk");
+
+        let config = Config::load_layered(&project, None).unwrap();
+        assert_eq!(config.branch_prefix, "cctakt");
+    }
+
+    #[test]
+    fn test_save_user_preference_preserves_other_keys() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let user = dir.path().join("cctakt").join("config.toml");
+        fs::create_dir_all(user.parent().unwrap()).unwrap();
+        fs::write(&user, "[ui]\nshow_tool_use = true\n").unwrap();
+
+        Config::save_user_preference_to(&user, "theme", toml::Value::String("dracula".into()))
+            .unwrap();
+
+        let project = dir.path().join(".cctakt.toml");
+        let config = Config::load_layered(&project, Some(&user)).unwrap();
+        assert_eq!(config.theme, "dracula");
+        assert!(config.ui.show_tool_use);
+    }
+
+    #[test]
+    fn test_load_rejects_unknown_key() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = dir.path().join(".cctakt.toml");
+        fs::write(&project, "branch_prefix = \"x\"\n\n[github]\nrepo = \"a/b\"\n").unwrap();
+
+        let err = format!("{:#}", Config::load_layered(&project, None).unwrap_err());
+        assert!(err.contains("unknown field `repo`"), "{err}");
+        assert!(err.contains("line 4"), "{err}");
+    }
+
+    #[test]
+    fn test_load_rejects_type_mismatch() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = dir.path().join(".cctakt.toml");
+        fs::write(&project, "[anthropic]\nmax_tokens = \"lots\"\n").unwrap();
+
+        let err = format!("{:#}", Config::load_layered(&project, None).unwrap_err());
+        assert!(err.contains("line 2"), "{err}");
+        assert!(err.contains("max_tokens"), "{err}");
+    }
+
+    #[test]
+    fn test_validate_default_config() {
+        assert!(Config::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_worktree_dir() {
+        let mut config = Config {
+            worktree_dir: PathBuf::new(),
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+
+        // Outside the repository is allowed (warned about when used)
+        for dir in ["../elsewhere", "a/../../b", "/tmp/worktrees", "build/../.worktrees"] {
+            config.worktree_dir = PathBuf::from(dir);
+            assert!(config.validate().is_ok(), "{dir}");
+        }
+    }
+
+    #[test]
+    fn test_validate_branch_prefix() {
+        let mut config = Config::default();
+
+        for valid in ["cctakt", "team/agents", "feat-x"] {
+            config.branch_prefix = valid.to_string();
+            assert!(config.validate().is_ok(), "{valid}");
+        }
+        for invalid in ["", "has space", "a..b", "/lead", "trail/", ".hidden", "x.lock", "q?"] {
+            config.branch_prefix = invalid.to_string();
+            assert!(config.validate().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_label_branch_prefixes() {
+        let config: Config = toml::from_str(
+            "branch_prefix = \"cctakt\"\n\n[label_branch_prefixes]\nbug = \"fix\"\nenhancement = \"feat\"\n",
+        )
+        .unwrap();
+        let issue = |labels: &[&str]| Issue {
+            number: 1,
+            title: "Title".to_string(),
+            body: None,
+            labels: labels
+                .iter()
+                .map(|name| crate::github::Label {
+                    name: name.to_string(),
+                    color: String::new(),
+                })
+                .collect(),
+            state: "open".to_string(),
+            html_url: String::new(),
+            comments: 0,
+            created_at: None,
+            updated_at: None,
+        };
+        assert_eq!(config.branch_prefix_for(&issue(&["enhancement", "bug"])), "feat");
+        assert_eq!(config.branch_prefix_for(&issue(&["docs"])), "cctakt");
+        assert!(config.validate().is_ok());
+
+        let mut config = config;
+        config.label_branch_prefixes.insert("wip".to_string(), "w i p".to_string());
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("label_branch_prefixes.wip 'w i p'"), "{err}");
+    }
+
+    #[test]
+    fn test_commit_subject_pattern() {
+        let config = Config::default();
+        assert!(!config.enforce_commit_convention);
+        let pattern = regex::Regex::new(&config.commit_subject_pattern).unwrap();
+        for ok in [
+            "feat: add login",
+            "fix(auth): handle expired tokens",
+            "refactor!: drop the v1 API",
+            "Merge branch 'main' into feat/x",
+            "Revert \"feat: add login\"",
+        ] {
+            assert!(pattern.is_match(ok), "{ok}");
+        }
+        for bad in ["Add login", "feat add login", "feat:", "feature: add login", "fix(): x"] {
+            assert!(!pattern.is_match(bad), "{bad}");
+        }
+
+        let config: Config = toml::from_str(
+            "commit_subject_pattern = \"^[A-Z]+-[0-9]+ \"\nenforce_commit_convention = true\n",
+        )
+        .unwrap();
+        assert!(config.enforce_commit_convention);
+        assert!(config.validate().is_ok());
+
+        let config = Config {
+            commit_subject_pattern: "(unclosed".to_string(),
+            ..Default::default()
+        };
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("commit_subject_pattern is not a valid regex"), "{err}");
+    }
+
+    #[test]
+    fn test_validate_log_level() {
+        let mut config = Config {
+            log_level: Some("trace".to_string()),
+            ..Config::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.log_level = Some("chatty".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_detect_build_commands() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert_eq!(detect_build_commands(dir.path()), None);
+
+        fs::write(dir.path().join("package.json"), "{}").unwrap();
+        fs::write(dir.path().join("yarn.lock"), "").unwrap();
+        assert_eq!(
+            detect_build_commands(dir.path()),
+            Some(BuildCommands::new("yarn run build", "yarn test"))
+        );
+
+        fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        assert_eq!(
+            detect_build_commands(dir.path()),
+            Some(BuildCommands::new("cargo build", "cargo test"))
+        );
+    }
+
+    #[test]
+    fn test_build_commands_config_overrides_detection() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::write(dir.path().join("go.mod"), "").unwrap();
+
+        let config = Config {
+            build_command: Some("make all".to_string()),
+            ..Config::default()
+        };
+        let commands = config.build_commands(dir.path());
+        assert_eq!(commands.build.as_deref(), Some("make all"));
+        assert_eq!(commands.test.as_deref(), Some("go test ./..."));
+
+        let empty = tempfile::TempDir::new().unwrap();
+        assert!(Config::default().build_commands(empty.path()).is_empty());
+    }
+
+    #[test]
+    fn test_branch_slug_config() {
+        let config: Config = toml::from_str(
+            r#"
+[branch_slug]
+preserve_case = true
+separator = "_"
+"#,
+        )
+        .unwrap();
+        assert!(config.branch_slug.preserve_case);
+        assert_eq!(config.branch_slug.separator, "_");
+        assert!(!config.branch_slug.keep_ticket_prefix);
+        assert!(config.validate().is_ok());
+
+        let config = Config {
+            branch_slug: SlugOptions {
+                separator: "/".to_string(),
+                ..SlugOptions::default()
+            },
+            ..Config::default()
+        };
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("branch_slug.separator"));
+    }
+
+    #[test]
+    fn test_validate_language() {
+        let mut config = Config {
+            language: Some("ja".to_string()),
+            ..Config::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.language = Some("klingon".to_string());
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("unknown language 'klingon'"));
+    }
+
+    #[test]
+    fn test_validate_unknown_theme() {
+        let config = Config {
+            theme: "solarized".to_string(),
+            ..Config::default()
+        };
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("unknown theme 'solarized'"));
+    }
+
+    #[test]
+    fn test_validate_split_ratio() {
+        let mut config = Config::default();
+        config.ui.split_ratio = MAX_SPLIT_RATIO;
+        assert!(config.validate().is_ok());
+
+        config.ui.split_ratio = 0.95;
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("ui.split_ratio 0.95 is out of range"));
+    }
+
+    #[test]
+    fn test_validate_spawn_retry_limits() {
+        let mut config = Config::default();
+        config.agent.spawn_retries = MAX_SPAWN_RETRIES;
+        config.agent.spawn_retry_backoff_ms = MAX_SPAWN_RETRY_BACKOFF_MS;
+        assert!(config.validate().is_ok());
+
+        config.agent.spawn_retries = 100;
+        config.agent.spawn_retry_backoff_ms = 60_000;
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("agent.spawn_retries 100 is too high"), "{err}");
+        assert!(err.contains("agent.spawn_retry_backoff_ms 60000 is too high"), "{err}");
+    }
+
+    #[test]
+    fn test_effective_entries_provenance() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = dir.path().join(".cctakt.toml");
+        let user = dir.path().join("user.toml");
+        fs::write(&project, "branch_prefix = \"team\"\ntheme = \"monokai\"\n").unwrap();
+        fs::write(&user, "theme = \"nord\"\n").unwrap();
+
+        let entries = Config::effective_entries(&project, Some(&user)).unwrap();
+        let find = |key: &str| entries.iter().find(|e| e.key == key).unwrap();
+
+        assert_eq!(find("theme").value.as_str(), Some("nord"));
+        assert_eq!(find("theme").source, ConfigSource::User(user.clone()));
+        assert_eq!(find("branch_prefix").source, ConfigSource::Project(project.clone()));
+        assert_eq!(find("keybindings.quit").source, ConfigSource::Default);
+        assert_eq!(find("keybindings.quit").value.as_str(), Some("ctrl+q"));
+    }
+
+    #[test]
+    fn test_get_value() {
+        let config = Config::default();
+        assert_eq!(
+            config.get_value("anthropic.max_tokens").unwrap(),
+            Some(toml::Value::Integer(1024))
+        );
+        assert_eq!(config.get_value("theme").unwrap().unwrap().as_str(), Some("cyberpunk"));
+        assert!(config.get_value("nope.missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_set_value_in_user_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = dir.path().join(".cctakt.toml");
+        let user = dir.path().join("cctakt").join("config.toml");
+
+        Config::set_value_in(&user, &project, Some(&user), "ui.show_tool_use", "true")
+            .unwrap();
+        Config::set_value_in(&user, &project, Some(&user), "keybindings.quit", "ctrl+x")
+            .unwrap();
+
+        let config = Config::load_layered(&project, Some(&user)).unwrap();
+        assert!(config.ui.show_tool_use);
+        assert_eq!(config.keybindings.quit, "ctrl+x");
+    }
+
+    #[test]
+    fn test_set_value_rejects_invalid() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = dir.path().join(".cctakt.toml");
+        let user = dir.path().join("user.toml");
+
+        // Unknown key
+        assert!(
+            Config::set_value_in(&user, &project, Some(&user), "ui.nope", "1").is_err()
+        );
+        // Type mismatch
+        assert!(Config::set_value_in(
+            &project,
+            &project,
+            Some(&user),
+            "anthropic.max_tokens",
+            "many"
+        )
+        .is_err());
+        // Fails validation
+        assert!(
+            Config::set_value_in(&user, &project, Some(&user), "theme", "solarized")
+                .is_err()
+        );
+        assert!(!user.exists());
+        assert!(!project.exists());
+    }
+
+    #[test]
+    fn test_parse_raw_value() {
+        assert_eq!(parse_raw_value("true"), toml::Value::Boolean(true));
+        assert_eq!(parse_raw_value("42"), toml::Value::Integer(42));
+        assert_eq!(parse_raw_value("ctrl+t"), toml::Value::String("ctrl+t".to_string()));
+        assert_eq!(parse_raw_value("\"quoted\""), toml::Value::String("quoted".to_string()));
+        assert_eq!(
+            parse_raw_value(r#"["a", "b"]"#),
+            toml::Value::Array(vec!["a".into(), "b".into()])
+        );
     }
 
     #[test]
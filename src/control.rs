@@ -0,0 +1,377 @@
+//! Control socket for driving cctakt from other tools
+//!
+//! With `control_socket = true` in `cctakt.toml`, cctakt listens on
+//! `.cctakt/control.sock` (Unix only). Clients write one JSON request per
+//! line and read JSON lines back: a response for each request, plus events
+//! (see `events`) as they happen. Requests are handled by the main loop, so they see the
+//! same state as the TUI, and `.cctakt/plan.json` keeps working alongside.
+//!
+//! ```text
+//! → {"id": 1, "command": "add_task", "task": {"id": "w1", "action": {...}}}
+//! ← {"id": 1, "ok": true, "result": {"task_id": "w1"}}
+//! → {"id": 2, "command": "review", "branch": "feat/login"}
+//! ← {"id": 2, "ok": true, "result": {"branch": "feat/login"}}
+//! ← {"timestamp": 1700000420, "event": "task_completed", "task_id": "w1", "agent_id": 3}
+//! ```
+
+use crate::events::EventRecord;
+use crate::plan::Task;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+
+/// Socket path, relative to the repository root
+pub const SOCKET_PATH: &str = ".cctakt/control.sock";
+
+/// Lines queued for a client before it counts as lagging and is dropped
+const CLIENT_BACKLOG: usize = 256;
+
+/// A command sent by a client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ControlCommand {
+    /// Add a task to the current plan (starting a new plan if there is none)
+    AddTask { task: Box<Task> },
+    /// Open review mode for a branch
+    Review { branch: String },
+    /// Current plan and agents
+    Status,
+}
+
+/// A request read from a client, waiting for the main loop
+#[derive(Debug)]
+pub struct ControlRequest {
+    /// Connection the response goes back to
+    pub client: u64,
+    /// Echoed back in the response so clients can match them up
+    pub id: Option<Value>,
+    pub command: ControlCommand,
+}
+
+#[derive(Deserialize)]
+struct RawRequest {
+    #[serde(default)]
+    id: Option<Value>,
+    #[serde(flatten)]
+    command: ControlCommand,
+}
+
+/// Response to a single request
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ControlResponse {
+    pub id: Option<Value>,
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ControlResponse {
+    pub fn from_result(id: Option<Value>, result: Result<Value, String>) -> Self {
+        match result {
+            Ok(value) => Self { id, ok: true, result: Some(value), error: None },
+            Err(error) => Self { id, ok: false, result: None, error: Some(error) },
+        }
+    }
+}
+
+/// Parse one request line into its id and command
+pub fn parse_request(line: &str) -> Result<(Option<Value>, ControlCommand)> {
+    let raw: RawRequest = serde_json::from_str(line).context("invalid request")?;
+    Ok((raw.id, raw.command))
+}
+
+/// Outgoing line queue of each connected client
+type Clients = Arc<Mutex<HashMap<u64, SyncSender<String>>>>;
+
+/// Listening control socket
+///
+/// Accepting, reading and writing happen on background threads; the main
+/// loop drains requests with `try_recv` and answers with `respond`, which
+/// only queue lines and never wait on a client. The socket file is removed
+/// when the server is dropped.
+pub struct ControlServer {
+    path: PathBuf,
+    requests: Receiver<ControlRequest>,
+    clients: Clients,
+}
+
+impl ControlServer {
+    /// Listen on `path`, replacing a stale socket left by a crashed run
+    #[cfg(unix)]
+    pub fn bind(path: &Path) -> Result<Self> {
+        use std::io::{BufRead, BufReader, Write};
+        use std::net::Shutdown;
+        use std::os::unix::net::{UnixListener, UnixStream};
+
+        if path.exists() {
+            if UnixStream::connect(path).is_ok() {
+                anyhow::bail!("Another cctakt is already listening on {}", path.display());
+            }
+            std::fs::remove_file(path)
+                .with_context(|| format!("Failed to remove stale socket {}", path.display()))?;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let listener = UnixListener::bind(path)
+            .with_context(|| format!("Failed to listen on {}", path.display()))?;
+
+        let (sender, requests) = std::sync::mpsc::channel();
+        let clients: Clients = Arc::new(Mutex::new(HashMap::new()));
+        let accept_clients = Arc::clone(&clients);
+
+        std::thread::spawn(move || {
+            for (client, stream) in (0u64..).zip(listener.incoming()) {
+                let Ok(stream) = stream else { continue };
+                let Ok(mut writer) = stream.try_clone() else { continue };
+                let (lines, outgoing) = std::sync::mpsc::sync_channel::<String>(CLIENT_BACKLOG);
+                accept_clients.lock().unwrap().insert(client, lines);
+
+                // Runs until the client is forgotten or stops accepting data;
+                // shutting the socket down also ends its reader
+                std::thread::spawn(move || {
+                    for line in outgoing {
+                        if writer.write_all(line.as_bytes()).and_then(|_| writer.flush()).is_err() {
+                            break;
+                        }
+                    }
+                    let _ = writer.shutdown(Shutdown::Both);
+                });
+
+                let sender = sender.clone();
+                let clients = Arc::clone(&accept_clients);
+                std::thread::spawn(move || {
+                    for line in BufReader::new(stream).lines() {
+                        let Ok(line) = line else { break };
+                        if line.trim().is_empty() {
+                            continue;
+                        }
+                        match parse_request(&line) {
+                            Ok((id, command)) => {
+                                let request = ControlRequest { client, id, command };
+                                if sender.send(request).is_err() {
+                                    break;
+                                }
+                            }
+                            Err(e) => {
+                                let response =
+                                    ControlResponse::from_result(None, Err(format!("{e:#}")));
+                                send_line(&clients, client, &response);
+                            }
+                        }
+                    }
+                    clients.lock().unwrap().remove(&client);
+                });
+            }
+        });
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            requests,
+            clients,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn bind(_path: &Path) -> Result<Self> {
+        anyhow::bail!("The control socket is only available on Unix")
+    }
+
+    /// Next pending request, if any
+    pub fn try_recv(&self) -> Option<ControlRequest> {
+        self.requests.try_recv().ok()
+    }
+
+    /// Answer a request
+    pub fn respond(&self, request: &ControlRequest, result: Result<Value, String>) {
+        let response = ControlResponse::from_result(request.id.clone(), result);
+        send_line(&self.clients, request.client, &response);
+    }
+
+    /// Send an event to every connected client
+    pub fn broadcast(&self, event: &EventRecord) {
+        let ids: Vec<u64> = self.clients.lock().unwrap().keys().copied().collect();
+        for client in ids {
+            send_line(&self.clients, client, event);
+        }
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Queue one JSON line for a client without blocking
+///
+/// A client that is gone, or so far behind that its queue is full, is
+/// forgotten; its writer thread then closes the connection.
+fn send_line(clients: &Clients, client: u64, message: &impl Serialize) {
+    let Ok(mut line) = serde_json::to_string(message) else { return };
+    line.push('\n');
+    let mut clients = clients.lock().unwrap();
+    let dropped = match clients.get(&client) {
+        Some(lines) => matches!(
+            lines.try_send(line),
+            Err(TrySendError::Full(_) | TrySendError::Disconnected(_))
+        ),
+        None => false,
+    };
+    if dropped {
+        clients.remove(&client);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_add_task() {
+        let line = r#"{"id":1,"command":"add_task","task":{"id":"w1","action":{"type":"create_worker","branch":"feat/a","task_description":"Do it"}}}"#;
+        let (id, command) = parse_request(line).unwrap();
+        assert_eq!(id, Some(serde_json::json!(1)));
+        match command {
+            ControlCommand::AddTask { task } => assert_eq!(task.id, "w1"),
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_without_id() {
+        let (id, command) = parse_request(r#"{"command":"status"}"#).unwrap();
+        assert_eq!(id, None);
+        assert!(matches!(command, ControlCommand::Status));
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_command() {
+        assert!(parse_request(r#"{"command":"explode"}"#).is_err());
+        assert!(parse_request("not json").is_err());
+    }
+
+    #[test]
+    fn test_response_omits_missing_fields() {
+        let ok = ControlResponse::from_result(Some(serde_json::json!(3)), Ok(serde_json::json!({})));
+        assert_eq!(serde_json::to_string(&ok).unwrap(), r#"{"id":3,"ok":true,"result":{}}"#);
+        let err = ControlResponse::from_result(None, Err("nope".to_string()));
+        assert_eq!(serde_json::to_string(&err).unwrap(), r#"{"id":null,"ok":false,"error":"nope"}"#);
+    }
+
+    #[test]
+    fn test_lagging_client_is_dropped() {
+        let (lines, _outgoing) = std::sync::mpsc::sync_channel(CLIENT_BACKLOG);
+        let clients: Clients = Arc::new(Mutex::new(HashMap::from([(1, lines)])));
+        let response = ControlResponse::from_result(None, Ok(Value::Null));
+        for _ in 0..CLIENT_BACKLOG {
+            send_line(&clients, 1, &response);
+        }
+        assert!(clients.lock().unwrap().contains_key(&1));
+        // Nobody drained the queue, so one more line drops the client
+        send_line(&clients, 1, &response);
+        assert!(clients.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_disconnected_client_is_dropped() {
+        let (lines, outgoing) = std::sync::mpsc::sync_channel(CLIENT_BACKLOG);
+        drop(outgoing);
+        let clients: Clients = Arc::new(Mutex::new(HashMap::from([(1, lines)])));
+        send_line(&clients, 1, &ControlResponse::from_result(None, Ok(Value::Null)));
+        assert!(clients.lock().unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    mod socket {
+        use super::super::*;
+        use crate::events::Event;
+        use std::io::{BufRead, BufReader, Write};
+        use std::os::unix::net::UnixStream;
+        use std::time::{Duration, Instant};
+        use tempfile::TempDir;
+
+        fn wait_for_request(server: &ControlServer) -> ControlRequest {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            loop {
+                if let Some(request) = server.try_recv() {
+                    return request;
+                }
+                assert!(Instant::now() < deadline, "no request arrived");
+                std::thread::sleep(Duration::from_millis(5));
+            }
+        }
+
+        fn read_line(reader: &mut BufReader<UnixStream>) -> Value {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            serde_json::from_str(&line).unwrap()
+        }
+
+        #[test]
+        fn test_request_response_and_events() {
+            let dir = TempDir::new().unwrap();
+            let path = dir.path().join(".cctakt/control.sock");
+            let server = ControlServer::bind(&path).unwrap();
+
+            let mut client = UnixStream::connect(&path).unwrap();
+            client.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+            let mut reader = BufReader::new(client.try_clone().unwrap());
+
+            client.write_all(b"{\"id\":7,\"command\":\"status\"}\n").unwrap();
+            let request = wait_for_request(&server);
+            assert!(matches!(request.command, ControlCommand::Status));
+            server.respond(&request, Ok(serde_json::json!({"agents": []})));
+            let response = read_line(&mut reader);
+            assert_eq!(response["id"], 7);
+            assert_eq!(response["ok"], true);
+
+            server.broadcast(&EventRecord::now(Event::MergeSucceeded {
+                branch: "feat/a".to_string(),
+                target: "main".to_string(),
+                task_id: None,
+            }));
+            let event = read_line(&mut reader);
+            assert_eq!(event["event"], "merge_succeeded");
+            assert_eq!(event["branch"], "feat/a");
+        }
+
+        #[test]
+        fn test_malformed_request_gets_error() {
+            let dir = TempDir::new().unwrap();
+            let path = dir.path().join("control.sock");
+            let _server = ControlServer::bind(&path).unwrap();
+
+            let mut client = UnixStream::connect(&path).unwrap();
+            client.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+            let mut reader = BufReader::new(client.try_clone().unwrap());
+            client.write_all(b"{\"command\":\"explode\"}\n").unwrap();
+
+            let response = read_line(&mut reader);
+            assert_eq!(response["ok"], false);
+            assert!(response["error"].as_str().unwrap().contains("invalid request"));
+        }
+
+        #[test]
+        fn test_socket_removed_on_drop_and_stale_socket_replaced() {
+            let dir = TempDir::new().unwrap();
+            let path = dir.path().join("control.sock");
+
+            let server = ControlServer::bind(&path).unwrap();
+            assert!(path.exists());
+            assert!(ControlServer::bind(&path).is_err());
+            drop(server);
+            assert!(!path.exists());
+
+            // A leftover file nobody listens on is replaced
+            std::fs::write(&path, "").unwrap();
+            let _server = ControlServer::bind(&path).unwrap();
+        }
+    }
+}
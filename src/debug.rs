@@ -1,97 +1,308 @@
 //! Debug logging utilities
 //!
-//! Provides debug logging that only activates in debug builds.
-//! In release builds, all debug_log! calls are no-ops.
+//! Leveled, structured logging to `.cctakt/debug.log`. The level comes from
+//! the `CCTAKT_LOG` env var (`trace`, `debug`, `info`, `warn`, `off`), then
+//! the `log_level` config key, and defaults to `debug` in debug builds and
+//! `off` in release builds. When logging is off, every call returns after a
+//! single atomic load.
 
+use std::fmt::Display;
 use std::fs::OpenOptions;
 use std::io::Write;
-use std::sync::Mutex;
 use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Environment variable that sets the log level
+pub const LOG_ENV_VAR: &str = "CCTAKT_LOG";
+
+/// Log file location (relative to the repository root)
+const LOG_FILE: &str = ".cctakt/debug.log";
+
+/// Level value meaning logging is disabled
+const OFF: u8 = u8::MAX;
 
 static DEBUG_FILE: Mutex<Option<std::fs::File>> = Mutex::new(None);
+static LEVEL: AtomicU8 = AtomicU8::new(OFF);
+/// Level to return to when verbose logging is toggled off
+static BASE_LEVEL: AtomicU8 = AtomicU8::new(OFF);
 
-/// Initialize debug logging (only in debug builds)
-#[cfg(debug_assertions)]
-pub fn init() {
-    let mut file_guard = DEBUG_FILE.lock().unwrap();
-    if file_guard.is_none() {
-        if let Ok(file) = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open("debug.log")
-        {
-            *file_guard = Some(file);
-            drop(file_guard);
-            log("=== Debug session started ===");
+/// Log level, from most to least verbose
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Trace = 0,
+    Debug = 1,
+    Info = 2,
+    Warn = 3,
+}
+
+impl Level {
+    /// Parse a level name; `None` for "off" or unrecognized names
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "trace" => Some(Level::Trace),
+            "debug" => Some(Level::Debug),
+            "info" => Some(Level::Info),
+            "warn" | "warning" => Some(Level::Warn),
+            _ => None,
+        }
+    }
+
+    /// Whether `name` is a valid level setting (including "off")
+    pub fn is_valid_name(name: &str) -> bool {
+        Self::parse(name).is_some() || name.trim().eq_ignore_ascii_case("off")
+    }
+
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Level::Trace),
+            1 => Some(Level::Debug),
+            2 => Some(Level::Info),
+            3 => Some(Level::Warn),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Level::Trace => "TRACE",
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
         }
     }
 }
 
-#[cfg(not(debug_assertions))]
-pub fn init() {}
+/// Path of the debug log file (relative to the repository root)
+pub fn log_file_path() -> &'static Path {
+    Path::new(LOG_FILE)
+}
 
-/// Log a message to debug.log (only in debug builds)
-#[cfg(debug_assertions)]
-pub fn log(message: &str) {
-    let timestamp = chrono::Local::now().format("%H:%M:%S%.3f");
-    let line = format!("[{}] {}\n", timestamp, message);
+/// Default level when neither env var nor config sets one
+fn default_level() -> Option<Level> {
+    if cfg!(debug_assertions) {
+        Some(Level::Debug)
+    } else {
+        None
+    }
+}
+
+/// Initialize logging from `CCTAKT_LOG` (or the build default)
+pub fn init() {
+    init_with(None);
+}
+
+/// Initialize logging, using `config_level` if `CCTAKT_LOG` isn't set
+///
+/// Can be called again once the config is loaded.
+pub fn init_with(config_level: Option<&str>) {
+    let setting = std::env::var(LOG_ENV_VAR)
+        .ok()
+        .or_else(|| config_level.map(str::to_string));
+    let level = match setting {
+        Some(name) => Level::parse(&name),
+        None => default_level(),
+    };
+    BASE_LEVEL.store(level.map_or(OFF, |l| l as u8), Ordering::Relaxed);
+    set_level(level);
+}
+
+/// Set the active level (None turns logging off)
+pub fn set_level(level: Option<Level>) {
+    if level.is_some() {
+        open_log_file();
+    }
+    let was_off = LEVEL.swap(level.map_or(OFF, |l| l as u8), Ordering::Relaxed) == OFF;
+    if was_off && level.is_some() {
+        log_at(Level::Info, "debug", "=== Debug session started ===");
+    }
+}
+
+/// Current level (None if logging is off)
+pub fn level() -> Option<Level> {
+    Level::from_u8(LEVEL.load(Ordering::Relaxed))
+}
+
+/// Toggle trace-level logging on and off at runtime, returning the new level
+pub fn toggle_verbose() -> Option<Level> {
+    let next = if level() == Some(Level::Trace) {
+        Level::from_u8(BASE_LEVEL.load(Ordering::Relaxed))
+    } else {
+        Some(Level::Trace)
+    };
+    set_level(next);
+    next
+}
+
+/// Whether messages at `level` are currently written
+#[inline]
+pub fn enabled(level: Level) -> bool {
+    let current = LEVEL.load(Ordering::Relaxed);
+    current != OFF && level as u8 >= current
+}
+
+fn open_log_file() {
+    let Ok(mut file_guard) = DEBUG_FILE.lock() else {
+        return;
+    };
+    if file_guard.is_some() {
+        return;
+    }
+    if let Some(parent) = Path::new(LOG_FILE).parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Ok(file) = OpenOptions::new().create(true).append(true).open(LOG_FILE) {
+        *file_guard = Some(file);
+    }
+}
 
-    if let Ok(mut file_guard) = DEBUG_FILE.lock() {
-        if let Some(ref mut file) = *file_guard {
-            let _ = file.write_all(line.as_bytes());
-            let _ = file.flush();
+/// Format one log line: `[time] LEVEL subsystem: message key=value ...`
+fn format_line(
+    timestamp: &str,
+    level: Level,
+    subsystem: &str,
+    message: &str,
+    fields: &[(&str, &dyn Display)],
+) -> String {
+    let mut line = format!("[{timestamp}] {:<5} {subsystem}: {message}", level.label());
+    for (key, value) in fields {
+        let value = value.to_string();
+        if value.is_empty() || value.contains(char::is_whitespace) {
+            line.push_str(&format!(" {key}={value:?}"));
+        } else {
+            line.push_str(&format!(" {key}={value}"));
         }
     }
+    line.push('\n');
+    line
 }
 
-#[cfg(not(debug_assertions))]
-pub fn log(_message: &str) {}
+/// Log a message with structured fields
+pub fn log_fields(level: Level, subsystem: &str, message: &str, fields: &[(&str, &dyn Display)]) {
+    if !enabled(level) {
+        return;
+    }
+    let timestamp = chrono::Local::now().format("%H:%M:%S%.3f").to_string();
+    let line = format_line(&timestamp, level, subsystem, message, fields);
+
+    if let Ok(mut file_guard) = DEBUG_FILE.lock()
+        && let Some(ref mut file) = *file_guard
+    {
+        let _ = file.write_all(line.as_bytes());
+        let _ = file.flush();
+    }
+}
+
+/// Log a message at a level for a subsystem
+pub fn log_at(level: Level, subsystem: &str, message: &str) {
+    log_fields(level, subsystem, message, &[]);
+}
+
+/// Log a debug message from the app
+pub fn log(message: &str) {
+    log_at(Level::Debug, "app", message);
+}
 
 /// Log a message with a category prefix
-#[cfg(debug_assertions)]
 pub fn log_category(category: &str, message: &str) {
-    log(&format!("[{}] {}", category, message));
+    log_at(Level::Debug, category, message);
 }
 
-#[cfg(not(debug_assertions))]
-pub fn log_category(_category: &str, _message: &str) {}
-
 /// Log worker stream-json output
-#[cfg(debug_assertions)]
 pub fn log_worker(worker_id: &str, event_type: &str, content: &str) {
-    let truncated = if content.len() > 200 {
+    if !enabled(Level::Trace) {
+        return;
+    }
+    let truncated = if content.chars().count() > 200 {
         format!("{}...", content.chars().take(200).collect::<String>())
     } else {
         content.to_string()
     };
-    log(&format!("[WORKER:{}] {} | {}", worker_id, event_type, truncated));
+    log_fields(
+        Level::Trace,
+        "worker",
+        &truncated,
+        &[("worker", &worker_id), ("event", &event_type)],
+    );
 }
 
-#[cfg(not(debug_assertions))]
-pub fn log_worker(_worker_id: &str, _event_type: &str, _content: &str) {}
-
 /// Log task state changes
-#[cfg(debug_assertions)]
 pub fn log_task(task_id: &str, old_status: &str, new_status: &str) {
-    log(&format!("[TASK:{}] {} -> {}", task_id, old_status, new_status));
+    log_fields(
+        Level::Info,
+        "task",
+        "status changed",
+        &[("task", &task_id), ("from", &old_status), ("to", &new_status)],
+    );
 }
 
-#[cfg(not(debug_assertions))]
-pub fn log_task(_task_id: &str, _old_status: &str, _new_status: &str) {}
-
 /// Log worktree operations
-#[cfg(debug_assertions)]
 pub fn log_worktree(operation: &str, path: &Path) {
-    log(&format!("[WORKTREE] {} {}", operation, path.display()));
+    log_fields(
+        Level::Info,
+        "worktree",
+        operation,
+        &[("path", &path.display())],
+    );
 }
 
-#[cfg(not(debug_assertions))]
-pub fn log_worktree(_operation: &str, _path: &Path) {}
-
-/// Macro for convenient debug logging
+/// Macro for convenient debug logging (skips formatting when disabled)
 #[macro_export]
 macro_rules! debug_log {
     ($($arg:tt)*) => {
-        $crate::debug::log(&format!($($arg)*))
+        if $crate::debug::enabled($crate::debug::Level::Debug) {
+            $crate::debug::log(&format!($($arg)*))
+        }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_parse() {
+        assert_eq!(Level::parse("trace"), Some(Level::Trace));
+        assert_eq!(Level::parse("DEBUG"), Some(Level::Debug));
+        assert_eq!(Level::parse(" info "), Some(Level::Info));
+        assert_eq!(Level::parse("warning"), Some(Level::Warn));
+        assert_eq!(Level::parse("off"), None);
+        assert_eq!(Level::parse("loud"), None);
+        assert!(Level::is_valid_name("off"));
+        assert!(!Level::is_valid_name("loud"));
+    }
+
+    #[test]
+    fn test_level_ordering() {
+        assert!(Level::Trace < Level::Debug);
+        assert!(Level::Info < Level::Warn);
+    }
+
+    #[test]
+    fn test_format_line_with_fields() {
+        let line = format_line(
+            "12:00:00.000",
+            Level::Info,
+            "task",
+            "status changed",
+            &[("task", &"t-1"), ("from", &"pending"), ("to", &"running")],
+        );
+        assert_eq!(
+            line,
+            "[12:00:00.000] INFO  task: status changed task=t-1 from=pending to=running\n"
+        );
+    }
+
+    #[test]
+    fn test_format_line_quotes_values_with_spaces() {
+        let line = format_line(
+            "12:00:00.000",
+            Level::Warn,
+            "worktree",
+            "removed",
+            &[("path", &"/tmp/my dir"), ("note", &"")],
+        );
+        assert!(line.contains(r#"path="/tmp/my dir""#));
+        assert!(line.contains(r#"note="""#));
+    }
+}
@@ -0,0 +1,30 @@
+//! Desktop (OS) notifications
+//!
+//! Sending is best-effort: when no notification service is available the
+//! failure is written to the debug log and otherwise ignored.
+
+use crate::debug;
+
+/// Application name shown by the notification service
+const APP_NAME: &str = "cctakt";
+
+/// Show a desktop notification without blocking the caller
+pub fn notify(summary: &str, body: &str) {
+    let summary = summary.to_string();
+    let body = body.to_string();
+    std::thread::spawn(move || {
+        let result = notify_rust::Notification::new()
+            .appname(APP_NAME)
+            .summary(&summary)
+            .body(&body)
+            .show();
+        if let Err(e) = result {
+            debug::log_fields(
+                debug::Level::Debug,
+                "desktop",
+                "desktop notification unavailable",
+                &[("summary", &summary), ("error", &e)],
+            );
+        }
+    });
+}
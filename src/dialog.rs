@@ -3,6 +3,7 @@
 //! Provides a generic input dialog widget for user input operations
 //! such as adding agents, entering task descriptions, etc.
 
+use crate::overlay::{centered_modal, is_degenerate};
 use crate::theme::theme;
 use crossterm::event::KeyCode;
 use ratatui::{
@@ -56,6 +57,13 @@ impl InputDialog {
         }
     }
 
+    /// Pre-fill the input, with the cursor at the end
+    pub fn with_value(mut self, value: impl Into<String>) -> Self {
+        self.input = value.into();
+        self.cursor_position = self.input.len();
+        self
+    }
+
     /// Show the dialog
     pub fn show(&mut self) {
         self.visible = true;
@@ -160,7 +168,10 @@ impl InputDialog {
         let dialog_height = 9;
 
         // Center the dialog
-        let dialog_area = centered_rect(dialog_width, dialog_height, area);
+        let dialog_area = centered_modal(area, dialog_width, dialog_height);
+        if is_degenerate(dialog_area) {
+            return;
+        }
 
         // Clear the area behind the dialog
         f.render_widget(Clear, dialog_area);
@@ -239,13 +250,6 @@ impl InputDialog {
     }
 }
 
-/// Create a centered rectangle with given width and height
-fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
-    let x = area.x + (area.width.saturating_sub(width)) / 2;
-    let y = area.y + (area.height.saturating_sub(height)) / 2;
-    Rect::new(x, y, width.min(area.width), height.min(area.height))
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -371,6 +375,16 @@ mod tests {
         assert!(dialog.value().is_empty());
     }
 
+    #[test]
+    fn test_dialog_with_value() {
+        let mut dialog = InputDialog::new("Title", "Prompt").with_value("abc");
+        dialog.show();
+        assert_eq!(dialog.value(), "abc");
+
+        dialog.handle_key(KeyCode::Char('d'));
+        assert_eq!(dialog.value(), "abcd");
+    }
+
     #[test]
     fn test_dialog_not_visible_ignores_input() {
         let mut dialog = InputDialog::new("Test", "Prompt");
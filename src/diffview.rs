@@ -3,20 +3,40 @@
 //! Provides a scrollable diff viewer widget for reviewing changes
 //! before merging branches.
 
+use anyhow::{Context, Result};
+use crate::overlay::is_degenerate;
+use crate::text::display_width;
 use crate::theme::theme;
+use similar::{ChangeTag, TextDiff};
 use ratatui::{
     layout::{Alignment, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
+    widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap},
     Frame,
 };
+use std::cell::Cell;
+use std::path::Path;
+use unicode_width::UnicodeWidthChar;
+
+/// Diffs larger than this (in lines) start with word-level highlighting disabled
+const WORD_DIFF_MAX_LINES: usize = 5000;
+
+/// Lines longer than this (in bytes) are never word-diffed
+const WORD_DIFF_MAX_LINE_LEN: usize = 500;
+
+/// Minimum ratio of unchanged text for a removed/added pair to be word-diffed.
+/// Below this the lines are unrelated and whole-line coloring reads better.
+const WORD_DIFF_MIN_SIMILARITY: f32 = 0.4;
+
+/// Appended to lines that carry a review comment
+const COMMENT_MARKER: &str = " \u{25c6}";
 
 /// A scrollable diff viewer widget
 ///
 /// # Example
 /// ```ignore
-/// let diff_content = merger.diff("feat/auth")?;
+/// let diff_content = merger.diff("feat/auth", "main")?;
 /// let mut diffview = DiffView::new(diff_content);
 ///
 /// // Handle scroll
@@ -36,6 +56,165 @@ pub struct DiffView {
     syntax_highlight: bool,
     /// Title for the diff view (e.g., "feat/auth -> main")
     title: Option<String>,
+    /// Per-file change summary, sorted by churn (most changed first)
+    file_stats: Vec<FileStat>,
+    /// Whether the per-file summary is shown instead of the full diff
+    show_summary: bool,
+    /// Selected row in the per-file summary
+    summary_selected: usize,
+    /// Whether word-level intra-line highlighting is enabled
+    word_diff: bool,
+    /// Cached word-level segments per line (computed when word diff is enabled)
+    word_segments: Vec<Option<Vec<WordSegment>>>,
+    /// How lines wider than the view are shown
+    overflow: LineOverflow,
+    /// First visible column in `LineOverflow::Scroll` mode
+    h_scroll: u16,
+    /// Content width at the last render (0 until rendered), for wrap math
+    view_width: Cell<u16>,
+    /// Active search query (empty when not searching)
+    search_query: String,
+    /// Whether the search matches case exactly
+    search_case_sensitive: bool,
+    /// Indices of lines matching the search query
+    search_matches: Vec<usize>,
+    /// Position in `search_matches` of the current match
+    search_current: Option<usize>,
+    /// File and line each diff line refers to (`None` for headers)
+    locations: Vec<Option<LineLocation>>,
+    /// Old and new line number of each diff line (`None` where it has none)
+    line_numbers: Vec<(Option<usize>, Option<usize>)>,
+    /// Whether the old/new line number gutter is shown
+    show_line_numbers: bool,
+    /// Indices of lines that carry a review comment
+    comment_marks: Vec<usize>,
+}
+
+/// Side of the diff a line number refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffSide {
+    /// Line number in the old file (removed lines)
+    Old,
+    /// Line number in the new file (added and context lines)
+    New,
+}
+
+/// File and line number that a diff line refers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineLocation {
+    /// File path (new side of the diff)
+    pub path: String,
+    /// 1-based line number on `side`
+    pub line: usize,
+    pub side: DiffSide,
+}
+
+impl std::fmt::Display for LineLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.side {
+            DiffSide::New => write!(f, "{}:{}", self.path, self.line),
+            DiffSide::Old => write!(f, "{}:{} (removed)", self.path, self.line),
+        }
+    }
+}
+
+/// How diff lines wider than the view are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineOverflow {
+    /// Cut off at the pane edge
+    #[default]
+    Truncate,
+    /// Soft-wrap onto the following rows
+    Wrap,
+    /// Cut off, with a column window moved by `scroll_left`/`scroll_right`
+    Scroll,
+}
+
+impl LineOverflow {
+    /// The mode after this one (truncate → wrap → scroll → truncate)
+    pub fn next(self) -> Self {
+        match self {
+            LineOverflow::Truncate => LineOverflow::Wrap,
+            LineOverflow::Wrap => LineOverflow::Scroll,
+            LineOverflow::Scroll => LineOverflow::Truncate,
+        }
+    }
+
+    /// Short label, e.g. "wrap"
+    pub fn label(self) -> &'static str {
+        match self {
+            LineOverflow::Truncate => "truncate",
+            LineOverflow::Wrap => "wrap",
+            LineOverflow::Scroll => "scroll",
+        }
+    }
+}
+
+/// A span of a diff line, marked as changed or unchanged relative to its pair
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct WordSegment {
+    text: String,
+    changed: bool,
+}
+
+/// Per-file change statistics derived from the diff text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileStat {
+    /// File path (new side of the diff)
+    pub path: String,
+    /// Number of added lines
+    pub additions: usize,
+    /// Number of removed lines
+    pub deletions: usize,
+    /// Index of the `diff --git` header line in the full diff
+    pub line_index: usize,
+    /// How the file changed
+    pub change: FileChange,
+    /// Whether git reported the file as binary
+    pub binary: bool,
+}
+
+/// Kind of change made to a file in the diff
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum FileChange {
+    #[default]
+    Modified,
+    Added,
+    Deleted,
+    /// Renamed (possibly with edits) from the given path
+    Renamed { from: String },
+}
+
+impl FileChange {
+    /// Short label, e.g. "added"
+    pub fn label(&self) -> &'static str {
+        match self {
+            FileChange::Modified => "modified",
+            FileChange::Added => "added",
+            FileChange::Deleted => "deleted",
+            FileChange::Renamed { .. } => "renamed",
+        }
+    }
+}
+
+impl FileStat {
+    /// Total number of changed lines
+    pub fn churn(&self) -> usize {
+        self.additions + self.deletions
+    }
+
+    /// Path as shown in the summary (`old → new` for renames)
+    pub fn display_path(&self) -> String {
+        let path = match &self.change {
+            FileChange::Renamed { from } => format!("{from} \u{2192} {}", self.path),
+            _ => self.path.clone(),
+        };
+        if self.binary {
+            format!("{path} [binary]")
+        } else {
+            path
+        }
+    }
 }
 
 /// A parsed diff line with its type
@@ -58,6 +237,10 @@ enum DiffLineType {
     HunkHeader,
     /// File header (diff --git, ---, +++)
     FileHeader,
+    /// Rename summary (collapsed `rename from` / `rename to`)
+    Rename,
+    /// Binary file summary (replaces `Binary files differ` / `GIT binary patch`)
+    Binary,
     /// Empty line
     Empty,
 }
@@ -71,7 +254,8 @@ impl DiffLineType {
             DiffLineType::Addition => t.diff_addition(),
             DiffLineType::Deletion => t.diff_deletion(),
             DiffLineType::HunkHeader => t.diff_hunk_header(),
-            DiffLineType::FileHeader => t.diff_file_header(),
+            DiffLineType::FileHeader | DiffLineType::Rename => t.diff_file_header(),
+            DiffLineType::Binary => t.diff_hunk_header(),
             DiffLineType::Empty => t.text_primary(),
         }
     }
@@ -91,13 +275,51 @@ impl DiffView {
     /// Create a new diff view with the given content
     pub fn new(diff: String) -> Self {
         let lines = parse_diff(&diff);
-        Self {
+        let file_stats = compute_file_stats(&lines);
+        let line_numbers = compute_line_numbers(&lines);
+        let locations = compute_locations(&lines, &line_numbers);
+        let mut view = Self {
             diff_content: diff,
             lines,
             scroll: 0,
             syntax_highlight: true,
             title: None,
-        }
+            file_stats,
+            show_summary: false,
+            summary_selected: 0,
+            word_diff: false,
+            word_segments: Vec::new(),
+            overflow: LineOverflow::default(),
+            h_scroll: 0,
+            view_width: Cell::new(0),
+            search_query: String::new(),
+            search_case_sensitive: false,
+            search_matches: Vec::new(),
+            search_current: None,
+            locations,
+            line_numbers,
+            show_line_numbers: false,
+            comment_marks: Vec::new(),
+        };
+        view.set_word_diff(view.lines.len() <= WORD_DIFF_MAX_LINES);
+        view
+    }
+
+    /// Create a diff view comparing two texts, titled `label`
+    ///
+    /// The texts are diffed line by line into unified-diff output under a
+    /// `diff --git` header for `label`, so it renders and navigates like
+    /// `git diff` output. Identical texts give an empty view.
+    pub fn from_texts(old: &str, new: &str, label: &str) -> Self {
+        Self::new(unified_diff(old, new, label)).with_title(label)
+    }
+
+    /// Create a diff view comparing two files, labeled with the new file's path
+    pub fn from_files(old: &Path, new: &Path) -> Result<Self> {
+        let read = |path: &Path| {
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+        };
+        Ok(Self::from_texts(&read(old)?, &read(new)?, &new.display().to_string()))
     }
 
     /// Set the title for the diff view
@@ -111,6 +333,128 @@ impl DiffView {
         self.syntax_highlight = enabled;
     }
 
+    /// Enable or disable word-level intra-line highlighting
+    pub fn set_word_diff(&mut self, enabled: bool) {
+        self.word_diff = enabled;
+        if enabled && self.word_segments.is_empty() && !self.lines.is_empty() {
+            self.word_segments = compute_word_segments(&self.lines);
+        }
+    }
+
+    /// Toggle word-level intra-line highlighting
+    pub fn toggle_word_diff(&mut self) {
+        self.set_word_diff(!self.word_diff);
+    }
+
+    /// Whether word-level intra-line highlighting is enabled
+    pub fn word_diff_enabled(&self) -> bool {
+        self.word_diff
+    }
+
+    /// Show or hide the old/new line number gutter
+    pub fn set_line_numbers(&mut self, enabled: bool) {
+        self.show_line_numbers = enabled;
+    }
+
+    /// Toggle the old/new line number gutter
+    pub fn toggle_line_numbers(&mut self) {
+        self.show_line_numbers = !self.show_line_numbers;
+    }
+
+    /// Whether the old/new line number gutter is shown
+    pub fn line_numbers_enabled(&self) -> bool {
+        self.show_line_numbers
+    }
+
+    /// Old and new line number of the diff line at `index`
+    ///
+    /// Context lines have both, added lines only a new and removed lines
+    /// only an old number; headers have neither.
+    pub fn line_numbers(&self, index: usize) -> (Option<usize>, Option<usize>) {
+        self.line_numbers.get(index).copied().unwrap_or((None, None))
+    }
+
+    /// Digits per number in the gutter (0 when hidden)
+    fn gutter_digits(&self) -> usize {
+        if !self.show_line_numbers {
+            return 0;
+        }
+        let max = self
+            .line_numbers
+            .iter()
+            .flat_map(|&(old, new)| old.into_iter().chain(new))
+            .max()
+            .unwrap_or(0);
+        max.to_string().len()
+    }
+
+    /// Columns taken by the gutter: "old new │ " (0 when hidden)
+    fn gutter_width(&self) -> u16 {
+        match self.gutter_digits() {
+            0 => 0,
+            digits => (digits * 2 + 4) as u16,
+        }
+    }
+
+    /// Gutter span for the diff line at `index`
+    fn gutter(&self, index: usize, digits: usize) -> Span<'static> {
+        let number = |n: Option<usize>| n.map_or_else(|| " ".repeat(digits), |n| format!("{n:>digits$}"));
+        let (old, new) = self.line_numbers(index);
+        Span::styled(
+            format!("{} {} \u{2502} ", number(old), number(new)),
+            Style::default().fg(theme().text_muted()),
+        )
+    }
+
+    /// How lines wider than the view are shown
+    pub fn overflow(&self) -> LineOverflow {
+        self.overflow
+    }
+
+    /// Set how lines wider than the view are shown
+    ///
+    /// Leaving horizontal-scroll mode resets the column offset.
+    pub fn set_overflow(&mut self, overflow: LineOverflow) {
+        self.overflow = overflow;
+        if overflow != LineOverflow::Scroll {
+            self.h_scroll = 0;
+        }
+    }
+
+    /// Switch to the next overflow mode (truncate → wrap → scroll)
+    pub fn cycle_overflow(&mut self) {
+        self.set_overflow(self.overflow.next());
+    }
+
+    /// First visible column in horizontal-scroll mode
+    pub fn column_offset(&self) -> u16 {
+        self.h_scroll
+    }
+
+    /// Shift the visible columns left (horizontal-scroll mode only)
+    pub fn scroll_left(&mut self, columns: u16) {
+        if self.overflow == LineOverflow::Scroll {
+            self.h_scroll = self.h_scroll.saturating_sub(columns);
+        }
+    }
+
+    /// Shift the visible columns right (horizontal-scroll mode only)
+    ///
+    /// Stops once the end of the longest line is in view.
+    pub fn scroll_right(&mut self, columns: u16) {
+        if self.overflow != LineOverflow::Scroll {
+            return;
+        }
+        let longest = self
+            .lines
+            .iter()
+            .map(|line| display_width(&line.content))
+            .max()
+            .unwrap_or(0);
+        let max = longest.saturating_sub(self.view_width.get().max(1) as usize);
+        self.h_scroll = (self.h_scroll as usize + columns as usize).min(max) as u16;
+    }
+
     /// Get the current scroll position
     pub fn scroll_position(&self) -> u16 {
         self.scroll
@@ -122,34 +466,259 @@ impl DiffView {
     }
 
     /// Scroll up by the specified number of lines
+    ///
+    /// In summary mode this moves the file selection instead.
     pub fn scroll_up(&mut self, lines: u16) {
+        if self.show_summary {
+            self.summary_selected = self.summary_selected.saturating_sub(lines as usize);
+            return;
+        }
         self.scroll = self.scroll.saturating_sub(lines);
     }
 
     /// Scroll down by the specified number of lines
+    ///
+    /// In summary mode this moves the file selection instead.
     pub fn scroll_down(&mut self, lines: u16) {
+        if self.show_summary {
+            let max = self.file_stats.len().saturating_sub(1);
+            self.summary_selected = (self.summary_selected + lines as usize).min(max);
+            return;
+        }
         let max_scroll = self.lines.len().saturating_sub(1) as u16;
         self.scroll = (self.scroll + lines).min(max_scroll);
     }
 
     /// Scroll to the top
     pub fn scroll_to_top(&mut self) {
+        if self.show_summary {
+            self.summary_selected = 0;
+            return;
+        }
         self.scroll = 0;
     }
 
     /// Scroll to the bottom
     pub fn scroll_to_bottom(&mut self) {
+        if self.show_summary {
+            self.summary_selected = self.file_stats.len().saturating_sub(1);
+            return;
+        }
         self.scroll = self.lines.len().saturating_sub(1) as u16;
     }
 
     /// Page up (scroll by viewport height)
+    ///
+    /// In wrap mode this moves by as many lines as fill the viewport rows.
     pub fn page_up(&mut self, viewport_height: u16) {
-        self.scroll_up(viewport_height.saturating_sub(2));
+        let rows = viewport_height.saturating_sub(2) as usize;
+        let lines = match self.wrap_width() {
+            Some(width) if !self.show_summary => {
+                let mut used = 0;
+                let fit = (0..self.scroll as usize)
+                    .rev()
+                    .take_while(|&i| {
+                        used += wrapped_rows(&self.lines[i].content, width);
+                        used <= rows
+                    })
+                    .count();
+                fit.max(1)
+            }
+            _ => rows.max(1),
+        };
+        self.scroll_up(lines as u16);
     }
 
     /// Page down (scroll by viewport height)
+    ///
+    /// In wrap mode this moves by as many lines as fill the viewport rows.
     pub fn page_down(&mut self, viewport_height: u16) {
-        self.scroll_down(viewport_height.saturating_sub(2));
+        let rows = viewport_height.saturating_sub(2) as usize;
+        let lines = match self.wrap_width() {
+            Some(width) if !self.show_summary => {
+                let mut used = 0;
+                let fit = self.lines[(self.scroll as usize).min(self.lines.len())..]
+                    .iter()
+                    .take_while(|line| {
+                        used += wrapped_rows(&line.content, width);
+                        used <= rows
+                    })
+                    .count();
+                fit.max(1)
+            }
+            _ => rows.max(1),
+        };
+        self.scroll_down(lines as u16);
+    }
+
+    /// Content width when wrapping is active and the view has been rendered
+    fn wrap_width(&self) -> Option<usize> {
+        let width = self.view_width.get() as usize;
+        (self.overflow == LineOverflow::Wrap && width > 0).then_some(width)
+    }
+
+    /// Per-file change statistics, sorted by churn
+    pub fn file_stats(&self) -> &[FileStat] {
+        &self.file_stats
+    }
+
+    /// Whether the per-file summary is currently shown
+    pub fn is_summary(&self) -> bool {
+        self.show_summary
+    }
+
+    /// Toggle between the full diff and the per-file summary
+    pub fn toggle_summary(&mut self) {
+        self.show_summary = !self.show_summary;
+    }
+
+    /// Index of the selected file in the summary
+    pub fn summary_selected(&self) -> usize {
+        self.summary_selected
+    }
+
+    /// Jump the full diff to the file selected in the summary
+    ///
+    /// Switches back to full-diff mode. Does nothing if there are no files.
+    pub fn open_selected_file(&mut self) {
+        if let Some(stat) = self.file_stats.get(self.summary_selected) {
+            self.scroll = stat.line_index as u16;
+            self.show_summary = false;
+        }
+    }
+
+    /// Search the diff for `query` and jump to the first match at or below
+    /// the current scroll position (wrapping to the top)
+    ///
+    /// Returns the indices of matching lines; an empty query clears the search.
+    pub fn search(&mut self, query: &str) -> &[usize] {
+        self.search_query = query.to_string();
+        self.search_matches = if query.is_empty() {
+            Vec::new()
+        } else {
+            self.lines
+                .iter()
+                .enumerate()
+                .filter(|(_, line)| {
+                    !find_matches(&line.content, query, self.search_case_sensitive).is_empty()
+                })
+                .map(|(i, _)| i)
+                .collect()
+        };
+        self.search_current = None;
+        let from = self.scroll as usize;
+        let first = self
+            .search_matches
+            .iter()
+            .position(|&line| line >= from)
+            .or((!self.search_matches.is_empty()).then_some(0));
+        if let Some(index) = first {
+            self.scroll_to_match(index);
+        }
+        &self.search_matches
+    }
+
+    /// Scroll so the `index`-th match is at the top and make it current
+    pub fn scroll_to_match(&mut self, index: usize) {
+        if let Some(&line) = self.search_matches.get(index) {
+            self.search_current = Some(index);
+            self.show_summary = false;
+            self.scroll = line as u16;
+        }
+    }
+
+    /// Jump to the next match (wrapping); returns its line index
+    pub fn next_match(&mut self) -> Option<usize> {
+        let count = self.search_matches.len();
+        if count == 0 {
+            return None;
+        }
+        let index = self.search_current.map_or(0, |i| (i + 1) % count);
+        self.scroll_to_match(index);
+        Some(self.search_matches[index])
+    }
+
+    /// Jump to the previous match (wrapping); returns its line index
+    pub fn prev_match(&mut self) -> Option<usize> {
+        let count = self.search_matches.len();
+        if count == 0 {
+            return None;
+        }
+        let index = self.search_current.map_or(count - 1, |i| (i + count - 1) % count);
+        self.scroll_to_match(index);
+        Some(self.search_matches[index])
+    }
+
+    /// Clear the search query and highlights
+    pub fn clear_search(&mut self) {
+        self.search_query.clear();
+        self.search_matches.clear();
+        self.search_current = None;
+    }
+
+    /// Active search query (empty when not searching)
+    pub fn search_query(&self) -> &str {
+        &self.search_query
+    }
+
+    /// Line indices of the current search matches
+    pub fn search_matches(&self) -> &[usize] {
+        &self.search_matches
+    }
+
+    /// Position of the current match within `search_matches`
+    pub fn current_match(&self) -> Option<usize> {
+        self.search_current
+    }
+
+    /// Whether the search matches case exactly (default: case-insensitive)
+    pub fn search_case_sensitive(&self) -> bool {
+        self.search_case_sensitive
+    }
+
+    /// Toggle case-sensitive search, re-running the active query
+    pub fn toggle_search_case(&mut self) {
+        self.search_case_sensitive = !self.search_case_sensitive;
+        if !self.search_query.is_empty() {
+            let query = self.search_query.clone();
+            self.search(&query);
+        }
+    }
+
+    /// File and line that the diff line at `index` refers to
+    pub fn location(&self, index: usize) -> Option<&LineLocation> {
+        self.locations.get(index).and_then(Option::as_ref)
+    }
+
+    /// Location of the first code line at or below the top of the view
+    ///
+    /// Headers have no location, so a hunk header at the top resolves to
+    /// the first line of that hunk.
+    pub fn current_location(&self) -> Option<&LineLocation> {
+        if self.show_summary {
+            return None;
+        }
+        self.locations
+            .iter()
+            .skip(self.scroll as usize)
+            .find_map(Option::as_ref)
+    }
+
+    /// Mark the lines at `locations` as commented
+    pub fn set_comment_marks<'a>(&mut self, locations: impl IntoIterator<Item = &'a LineLocation>) {
+        let locations: Vec<&LineLocation> = locations.into_iter().collect();
+        self.comment_marks = self
+            .locations
+            .iter()
+            .enumerate()
+            .filter(|(_, loc)| loc.as_ref().is_some_and(|loc| locations.contains(&loc)))
+            .map(|(i, _)| i)
+            .collect();
+    }
+
+    /// Whether the line at `index` carries a review comment
+    pub fn is_commented(&self, index: usize) -> bool {
+        self.comment_marks.contains(&index)
     }
 
     /// Get the raw diff content
@@ -164,6 +733,10 @@ impl DiffView {
 
     /// Render the diff view
     pub fn render(&self, f: &mut Frame, area: Rect) {
+        // Borders, help line and at least one row of diff
+        if area.width < 5 || area.height < 5 {
+            return;
+        }
         let t = theme();
 
         // Calculate content area (excluding borders)
@@ -177,22 +750,12 @@ impl DiffView {
             .border_style(Style::default().fg(t.border_primary()));
 
         // Build styled lines
-        let visible_lines: Vec<Line> = self
-            .lines
-            .iter()
-            .skip(self.scroll as usize)
-            .take(content_height)
-            .map(|line| {
-                if self.syntax_highlight {
-                    style_diff_line(line)
-                } else {
-                    Line::from(line.content.clone())
-                }
-            })
-            .collect();
-
-        // Add empty lines if needed
-        let all_lines = visible_lines;
+        self.view_width.set(area.width.saturating_sub(2).saturating_sub(self.gutter_width()));
+        let all_lines: Vec<Line> = if self.show_summary {
+            self.summary_lines(area.width.saturating_sub(2), content_height)
+        } else {
+            self.visible_lines(content_height)
+        };
 
         // Add help line at the bottom
         let help_line = Line::from(vec![
@@ -227,9 +790,12 @@ impl DiffView {
         ]);
 
         // Render main content
-        let diff_widget = Paragraph::new(all_lines)
+        let mut diff_widget = Paragraph::new(all_lines)
             .block(block)
             .alignment(Alignment::Left);
+        if self.overflow == LineOverflow::Wrap && !self.show_summary {
+            diff_widget = diff_widget.wrap(Wrap { trim: false });
+        }
 
         f.render_widget(diff_widget, area);
 
@@ -244,13 +810,24 @@ impl DiffView {
         f.render_widget(help_widget, help_area);
 
         // Render scrollbar if needed
-        if self.lines.len() > content_height {
+        if !self.show_summary && self.lines.len() > content_height {
             let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
                 .begin_symbol(Some("\u{25b2}")) // ▲
                 .end_symbol(Some("\u{25bc}"));   // ▼
 
-            let mut scrollbar_state = ScrollbarState::new(self.lines.len())
-                .position(self.scroll as usize);
+            // Measured in rows, so wrapped lines count once per row
+            let (total, position) = match self.wrap_width() {
+                Some(width) => {
+                    let rows: Vec<usize> = self
+                        .lines
+                        .iter()
+                        .map(|line| wrapped_rows(&line.content, width))
+                        .collect();
+                    (rows.iter().sum(), rows[..self.scroll as usize].iter().sum())
+                }
+                None => (self.lines.len(), self.scroll as usize),
+            };
+            let mut scrollbar_state = ScrollbarState::new(total).position(position);
 
             let scrollbar_area = Rect::new(
                 area.x + area.width.saturating_sub(1),
@@ -265,43 +842,655 @@ impl DiffView {
 
     /// Render with a custom block (for embedding in other widgets)
     pub fn render_with_block(&self, f: &mut Frame, area: Rect, block: Block) {
+        if is_degenerate(area) {
+            return;
+        }
         let inner = block.inner(area);
         f.render_widget(block, area);
 
         let content_height = inner.height as usize;
 
-        let visible_lines: Vec<Line> = self
-            .lines
+        if self.show_summary {
+            let summary = Paragraph::new(self.summary_lines(inner.width, content_height));
+            f.render_widget(summary, inner);
+            return;
+        }
+
+        self.view_width.set(inner.width.saturating_sub(self.gutter_width()));
+        let mut diff_widget = Paragraph::new(self.visible_lines(content_height));
+        if self.overflow == LineOverflow::Wrap {
+            diff_widget = diff_widget.wrap(Wrap { trim: false });
+        }
+        f.render_widget(diff_widget, inner);
+    }
+
+    /// Styled lines from the scroll position that fill `height` rows
+    ///
+    /// Wrapped lines may take several rows, so fewer lines are returned; in
+    /// horizontal-scroll mode each line starts at the column offset. The line
+    /// number gutter stays in place when the content scrolls sideways.
+    fn visible_lines(&self, height: usize) -> Vec<Line<'static>> {
+        let start = self.scroll as usize;
+        let digits = self.gutter_digits();
+        let count = match self.wrap_width() {
+            Some(width) => {
+                let mut used = 0;
+                self.lines
+                    .iter()
+                    .skip(start)
+                    .take_while(|line| {
+                        let fits = used < height;
+                        used += wrapped_rows(&line.content, width);
+                        fits
+                    })
+                    .count()
+            }
+            None => height,
+        };
+
+        self.lines
             .iter()
-            .skip(self.scroll as usize)
-            .take(content_height)
-            .map(|line| {
-                if self.syntax_highlight {
-                    style_diff_line(line)
-                } else {
-                    Line::from(line.content.clone())
+            .skip(start)
+            .take(count)
+            .enumerate()
+            .map(|(i, line)| {
+                let mut styled = self.styled_line(start + i, line);
+                if self.is_commented(start + i) {
+                    styled.spans.push(Span::styled(
+                        COMMENT_MARKER,
+                        Style::default().fg(theme().neon_yellow()).add_modifier(Modifier::BOLD),
+                    ));
+                }
+                if self.overflow == LineOverflow::Scroll && self.h_scroll > 0 {
+                    styled = skip_columns(styled, self.h_scroll as usize);
                 }
+                if digits > 0 {
+                    styled.spans.insert(0, self.gutter(start + i, digits));
+                }
+                styled
             })
-            .collect();
+            .collect()
+    }
 
-        let diff_widget = Paragraph::new(visible_lines);
-        f.render_widget(diff_widget, inner);
+    /// Style a single line, using word-level segments when available and
+    /// highlighting search matches
+    fn styled_line(&self, index: usize, line: &DiffLine) -> Line<'static> {
+        let styled = if !self.syntax_highlight {
+            Line::from(line.content.clone())
+        } else if self.word_diff
+            && let Some(Some(segments)) = self.word_segments.get(index)
+        {
+            style_word_diff_line(line, segments)
+        } else {
+            style_diff_line(line)
+        };
+
+        if self.search_query.is_empty() {
+            return styled;
+        }
+        let ranges = find_matches(&line.content, &self.search_query, self.search_case_sensitive);
+        if ranges.is_empty() {
+            return styled;
+        }
+        let t = theme();
+        let current = self
+            .search_current
+            .and_then(|i| self.search_matches.get(i))
+            .is_some_and(|&line_index| line_index == index);
+        let highlight = if current {
+            Style::default().fg(Color::Black).bg(t.neon_yellow()).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Black).bg(t.neon_cyan())
+        };
+        highlight_ranges(styled, &ranges, highlight)
+    }
+
+    /// Build the per-file summary lines (`git diff --stat` style with bars)
+    fn summary_lines(&self, width: u16, height: usize) -> Vec<Line<'static>> {
+        let t = theme();
+
+        if self.file_stats.is_empty() {
+            return vec![Line::from(Span::styled(
+                "No file changes",
+                Style::default().fg(t.text_muted()),
+            ))];
+        }
+
+        let name_width = self
+            .file_stats
+            .iter()
+            .map(|s| s.display_path().chars().count())
+            .max()
+            .unwrap_or(0)
+            .min(width as usize / 2);
+        let count_width = self
+            .file_stats
+            .iter()
+            .map(|s| s.churn().to_string().len())
+            .max()
+            .unwrap_or(1);
+        // "> " + name + " | " + count + " "
+        let bar_width = (width as usize).saturating_sub(name_width + count_width + 6);
+        let max_churn = self.file_stats.iter().map(FileStat::churn).max().unwrap_or(0);
+
+        // Keep the selection visible
+        let start = self
+            .summary_selected
+            .saturating_sub(height.saturating_sub(1));
+
+        self.file_stats
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(height)
+            .map(|(i, stat)| {
+                let (adds, dels) = scale_bar(stat.additions, stat.deletions, max_churn, bar_width);
+                let selected = i == self.summary_selected;
+                let name_style = if selected {
+                    t.style_selected()
+                } else {
+                    Style::default().fg(t.diff_file_header())
+                };
+                let display = stat.display_path();
+                let path: String = if display.chars().count() > name_width {
+                    let skip = display.chars().count() - name_width;
+                    display.chars().skip(skip).collect()
+                } else {
+                    display
+                };
+
+                Line::from(vec![
+                    Span::styled(
+                        if selected { "> " } else { "  " },
+                        Style::default().fg(t.neon_cyan()),
+                    ),
+                    Span::styled(format!("{path:<name_width$}"), name_style),
+                    Span::styled(" | ", Style::default().fg(t.text_muted())),
+                    Span::styled(
+                        format!("{:>count_width$} ", stat.churn()),
+                        Style::default().fg(t.text_primary()),
+                    ),
+                    Span::styled("+".repeat(adds), Style::default().fg(t.diff_addition())),
+                    Span::styled("-".repeat(dels), Style::default().fg(t.diff_deletion())),
+                ])
+            })
+            .collect()
     }
 }
 
-/// Parse diff content into typed lines
-fn parse_diff(diff: &str) -> Vec<DiffLine> {
-    diff.lines()
+/// Unified diff of two texts in `git diff` format, or "" if they are equal
+fn unified_diff(old: &str, new: &str, label: &str) -> String {
+    if old == new {
+        return String::new();
+    }
+    let hunks = TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{label}"), &format!("b/{label}"))
+        .to_string();
+    format!("diff --git a/{label} b/{label}\n{hunks}")
+}
+
+/// Build per-file statistics from parsed diff lines, sorted by churn (descending)
+fn compute_file_stats(lines: &[DiffLine]) -> Vec<FileStat> {
+    let mut stats: Vec<FileStat> = Vec::new();
+    let mut in_hunk = false;
+
+    for (index, line) in lines.iter().enumerate() {
+        if let Some(rest) = line.content.strip_prefix("diff --git ") {
+            // "a/path b/path" -> take the new-side path
+            let path = rest
+                .rsplit_once(" b/")
+                .map(|(_, p)| p.to_string())
+                .unwrap_or_else(|| rest.to_string());
+            stats.push(FileStat {
+                path,
+                additions: 0,
+                deletions: 0,
+                line_index: index,
+                change: FileChange::Modified,
+                binary: false,
+            });
+            in_hunk = false;
+            continue;
+        }
+
+        let Some(current) = stats.last_mut() else {
+            continue;
+        };
+
+        match line.line_type {
+            DiffLineType::FileHeader if !in_hunk && line.content.starts_with("new file mode") => {
+                current.change = FileChange::Added;
+            }
+            DiffLineType::FileHeader if !in_hunk && line.content.starts_with("deleted file mode") => {
+                current.change = FileChange::Deleted;
+            }
+            DiffLineType::Rename => {
+                if let Some((from, _)) = line
+                    .content
+                    .strip_prefix(RENAME_PREFIX)
+                    .and_then(|rest| rest.split_once(" \u{2192} "))
+                {
+                    current.change = FileChange::Renamed {
+                        from: from.to_string(),
+                    };
+                }
+            }
+            DiffLineType::Binary => current.binary = true,
+            DiffLineType::HunkHeader => in_hunk = true,
+            DiffLineType::Addition if in_hunk => current.additions += 1,
+            DiffLineType::Deletion if in_hunk => current.deletions += 1,
+            // "+++"/"---" inside a hunk are real content lines, not headers
+            DiffLineType::FileHeader if in_hunk => {
+                if line.content.starts_with("+++") {
+                    current.additions += 1;
+                } else if line.content.starts_with("---") {
+                    current.deletions += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    stats.sort_by(|a, b| b.churn().cmp(&a.churn()).then_with(|| a.path.cmp(&b.path)));
+    stats
+}
+
+/// Map each diff line to the file and line number it refers to
+///
+/// Line numbers come from the hunk headers: removed lines count on the old
+/// side, added and context lines on the new side. Headers map to `None`.
+fn compute_locations(
+    lines: &[DiffLine],
+    line_numbers: &[(Option<usize>, Option<usize>)],
+) -> Vec<Option<LineLocation>> {
+    let mut path = String::new();
+    lines
+        .iter()
+        .zip(line_numbers)
+        .map(|(line, &numbers)| {
+            if let Some(rest) = line.content.strip_prefix("diff --git ") {
+                path = rest.rsplit_once(" b/").map_or(rest, |(_, p)| p).to_string();
+            }
+            let (side, number) = match numbers {
+                (Some(old), None) => (DiffSide::Old, old),
+                (_, Some(new)) => (DiffSide::New, new),
+                (None, None) => return None,
+            };
+            Some(LineLocation {
+                path: path.clone(),
+                line: number,
+                side,
+            })
+        })
+        .collect()
+}
+
+/// Old and new line numbers of each diff line, counted from the hunk headers
+///
+/// Removed lines only have an old number, added lines only a new one;
+/// headers and "\ No newline at end of file" markers have neither.
+fn compute_line_numbers(lines: &[DiffLine]) -> Vec<(Option<usize>, Option<usize>)> {
+    let (mut old_line, mut new_line) = (0, 0);
+    let mut in_hunk = false;
+
+    lines
+        .iter()
         .map(|line| {
-            let line_type = classify_diff_line(line);
-            DiffLine {
-                content: line.to_string(),
-                line_type,
+            if line.content.starts_with("diff --git ") {
+                in_hunk = false;
+                return (None, None);
+            }
+            if line.line_type == DiffLineType::HunkHeader {
+                if let Some((old, new)) = parse_hunk_header(&line.content) {
+                    (old_line, new_line) = (old, new);
+                    in_hunk = true;
+                }
+                return (None, None);
+            }
+            if !in_hunk {
+                return (None, None);
+            }
+            // "+++"/"---" inside a hunk are real content lines, not headers
+            let header = line.line_type == DiffLineType::FileHeader;
+            let added = line.line_type == DiffLineType::Addition
+                || (header && line.content.starts_with("+++"));
+            let removed = line.line_type == DiffLineType::Deletion
+                || (header && line.content.starts_with("---"));
+            if added {
+                new_line += 1;
+                (None, Some(new_line - 1))
+            } else if removed {
+                old_line += 1;
+                (Some(old_line - 1), None)
+            } else if matches!(line.line_type, DiffLineType::Context | DiffLineType::Empty)
+                && !line.content.starts_with('\\')
+            {
+                old_line += 1;
+                new_line += 1;
+                (Some(old_line - 1), Some(new_line - 1))
+            } else {
+                (None, None)
             }
         })
         .collect()
 }
 
+/// Starting old and new line numbers from a `@@ -a,b +c,d @@` header
+fn parse_hunk_header(header: &str) -> Option<(usize, usize)> {
+    let mut parts = header.split_whitespace().skip(1);
+    let start = |part: Option<&str>, sign: char| -> Option<usize> {
+        part?.strip_prefix(sign)?.split(',').next()?.parse().ok()
+    };
+    let old = start(parts.next(), '-')?;
+    let new = start(parts.next(), '+')?;
+    Some((old, new))
+}
+
+/// Byte ranges of `query` in `text` (ASCII case-insensitive unless `case_sensitive`)
+fn find_matches(text: &str, query: &str, case_sensitive: bool) -> Vec<(usize, usize)> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    // ASCII lowercasing keeps byte offsets valid for the original text
+    let (haystack, needle) = if case_sensitive {
+        (text.to_string(), query.to_string())
+    } else {
+        (text.to_ascii_lowercase(), query.to_ascii_lowercase())
+    };
+    haystack
+        .match_indices(&needle)
+        .map(|(start, m)| (start, start + m.len()))
+        .collect()
+}
+
+/// Restyle the byte `ranges` of a line's text with `highlight`, splitting spans as needed
+fn highlight_ranges(line: Line<'static>, ranges: &[(usize, usize)], highlight: Style) -> Line<'static> {
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    for span in line.spans {
+        let text = span.content.as_ref();
+        let (start, end) = (offset, offset + text.len());
+        let mut cursor = start;
+        for &(m_start, m_end) in ranges {
+            let (from, to) = (m_start.max(start), m_end.min(end));
+            if from >= to {
+                continue;
+            }
+            if from > cursor {
+                spans.push(Span::styled(text[cursor - start..from - start].to_string(), span.style));
+            }
+            spans.push(Span::styled(
+                text[from - start..to - start].to_string(),
+                span.style.patch(highlight),
+            ));
+            cursor = to;
+        }
+        if cursor < end {
+            spans.push(Span::styled(text[cursor - start..].to_string(), span.style));
+        }
+        offset = end;
+    }
+    Line::from(spans)
+}
+
+/// Rows a line occupies when soft-wrapped at `width` cells
+fn wrapped_rows(content: &str, width: usize) -> usize {
+    display_width(content).div_ceil(width.max(1)).max(1)
+}
+
+/// Drop the first `columns` cells of a styled line, keeping span styles
+///
+/// A wide character cut in half leaves a space for its visible cell.
+fn skip_columns(line: Line<'static>, columns: usize) -> Line<'static> {
+    let mut remaining = columns;
+    let spans: Vec<Span<'static>> = line
+        .spans
+        .into_iter()
+        .filter_map(|span| {
+            let len = display_width(&span.content);
+            if remaining >= len {
+                remaining -= len;
+                return None;
+            }
+            let mut text = String::new();
+            for c in span.content.chars() {
+                let width = c.width().unwrap_or(0);
+                if remaining == 0 {
+                    text.push(c);
+                } else if width > remaining {
+                    text.push_str(&" ".repeat(width - remaining));
+                    remaining = 0;
+                } else {
+                    remaining -= width;
+                }
+            }
+            Some(Span::styled(text, span.style))
+        })
+        .collect();
+    Line::from(spans)
+}
+
+/// Scale addition/deletion counts to fit a bar of `width` characters
+fn scale_bar(additions: usize, deletions: usize, max_churn: usize, width: usize) -> (usize, usize) {
+    let churn = additions + deletions;
+    if churn == 0 || max_churn == 0 || width == 0 {
+        return (0, 0);
+    }
+    if max_churn <= width {
+        return (additions, deletions);
+    }
+    let total = (churn * width).div_ceil(max_churn).max(1);
+    let adds = (additions * total).div_ceil(churn).min(total);
+    (adds, total - adds)
+}
+
+/// Pair removed/added lines within each hunk and compute word-level segments
+///
+/// A run of deletions immediately followed by a run of additions is paired
+/// line by line. Lines without a counterpart, overly long lines, and pairs
+/// that share too little text are left as `None` (whole-line styling).
+fn compute_word_segments(lines: &[DiffLine]) -> Vec<Option<Vec<WordSegment>>> {
+    let mut segments = vec![None; lines.len()];
+    let mut i = 0;
+
+    while i < lines.len() {
+        if lines[i].line_type != DiffLineType::Deletion {
+            i += 1;
+            continue;
+        }
+
+        let del_start = i;
+        while i < lines.len() && lines[i].line_type == DiffLineType::Deletion {
+            i += 1;
+        }
+        let add_start = i;
+        while i < lines.len() && lines[i].line_type == DiffLineType::Addition {
+            i += 1;
+        }
+
+        let pairs = (add_start - del_start).min(i - add_start);
+        for k in 0..pairs {
+            let (del_idx, add_idx) = (del_start + k, add_start + k);
+            if let Some((old, new)) =
+                word_diff_pair(&lines[del_idx].content[1..], &lines[add_idx].content[1..])
+            {
+                segments[del_idx] = Some(old);
+                segments[add_idx] = Some(new);
+            }
+        }
+    }
+
+    segments
+}
+
+/// Word-diff a removed/added pair (without the leading `-`/`+`)
+///
+/// Returns the segments for the old and new line, or `None` when the pair
+/// should fall back to whole-line styling.
+fn word_diff_pair(old: &str, new: &str) -> Option<(Vec<WordSegment>, Vec<WordSegment>)> {
+    if old.len() > WORD_DIFF_MAX_LINE_LEN || new.len() > WORD_DIFF_MAX_LINE_LEN {
+        return None;
+    }
+
+    let diff = TextDiff::from_words(old, new);
+
+    let mut old_segments: Vec<WordSegment> = Vec::new();
+    let mut new_segments: Vec<WordSegment> = Vec::new();
+    for change in diff.iter_all_changes() {
+        let text = change.value();
+        match change.tag() {
+            ChangeTag::Equal => {
+                push_segment(&mut old_segments, text, false);
+                push_segment(&mut new_segments, text, false);
+            }
+            ChangeTag::Delete => push_segment(&mut old_segments, text, true),
+            ChangeTag::Insert => push_segment(&mut new_segments, text, true),
+        }
+    }
+
+    // Whitespace always matches, so measure similarity on visible text only
+    let visible = |segs: &[WordSegment], changed: bool| -> usize {
+        segs.iter()
+            .filter(|s| s.changed == changed)
+            .map(|s| s.text.chars().filter(|c| !c.is_whitespace()).count())
+            .sum()
+    };
+    let unchanged = visible(&new_segments, false);
+    let total = unchanged + visible(&old_segments, true).max(visible(&new_segments, true));
+    if total == 0 || (unchanged as f32) / (total as f32) < WORD_DIFF_MIN_SIMILARITY {
+        return None;
+    }
+
+    Some((old_segments, new_segments))
+}
+
+/// Append text to the segment list, merging with the previous segment if it has the same kind
+fn push_segment(segments: &mut Vec<WordSegment>, text: &str, changed: bool) {
+    match segments.last_mut() {
+        Some(last) if last.changed == changed => last.text.push_str(text),
+        _ => segments.push(WordSegment {
+            text: text.to_string(),
+            changed,
+        }),
+    }
+}
+
+/// Brighten a background color for highlighting changed words
+fn intensify(color: Color) -> Color {
+    match color {
+        Color::Rgb(r, g, b) => Color::Rgb(
+            r.saturating_mul(2).saturating_add(30),
+            g.saturating_mul(2).saturating_add(30),
+            b.saturating_mul(2).saturating_add(30),
+        ),
+        other => other,
+    }
+}
+
+/// Style a removed/added line with its changed words highlighted
+fn style_word_diff_line(line: &DiffLine, segments: &[WordSegment]) -> Line<'static> {
+    let fg = line.line_type.color();
+    let bg = line.line_type.bg_color();
+    let base = bg.map_or(Style::default().fg(fg), |bg| Style::default().fg(fg).bg(bg));
+    // Palette colors (below truecolor) can't be brightened, so reverse them instead
+    let changed = match bg.map(intensify) {
+        Some(bright) if Some(bright) != bg => Style::default()
+            .fg(fg)
+            .bg(bright)
+            .add_modifier(Modifier::BOLD),
+        _ => base.add_modifier(Modifier::REVERSED),
+    };
+
+    let prefix = line.content[..1].to_string();
+    let mut spans = vec![Span::styled(prefix, base)];
+    spans.extend(segments.iter().map(|seg| {
+        Span::styled(seg.text.clone(), if seg.changed { changed } else { base })
+    }));
+    Line::from(spans)
+}
+
+/// Parse diff content into typed lines
+///
+/// `rename from`/`rename to` pairs become one `renamed: old → new` line and
+/// binary entries (`Binary files … differ`, or a `GIT binary patch` with its
+/// encoded data) become one `[binary] path (kind)` line. Every parsed line is
+/// a displayed line, so scroll offsets and file indices stay aligned.
+fn parse_diff(diff: &str) -> Vec<DiffLine> {
+    let raw: Vec<&str> = diff.lines().collect();
+    let mut lines = Vec::with_capacity(raw.len());
+    // Paths and change kind of the current file, for the binary summary
+    let mut path = String::new();
+    let mut change = "modified";
+    let mut in_hunk = false;
+    let mut i = 0;
+
+    while i < raw.len() {
+        let line = raw[i];
+        i += 1;
+
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            path = rest
+                .rsplit_once(" b/")
+                .map_or(rest, |(_, new)| new)
+                .to_string();
+            change = "modified";
+            in_hunk = false;
+        } else if line.starts_with("@@") {
+            in_hunk = true;
+        } else if !in_hunk {
+            if line.starts_with("new file mode") {
+                change = "added";
+            } else if line.starts_with("deleted file mode") {
+                change = "deleted";
+            } else if let Some(from) = line.strip_prefix("rename from ")
+                && let Some(to) = raw.get(i).and_then(|next| next.strip_prefix("rename to "))
+            {
+                lines.push(DiffLine {
+                    content: format!("{RENAME_PREFIX}{from} \u{2192} {to}"),
+                    line_type: DiffLineType::Rename,
+                });
+                i += 1;
+                continue;
+            } else if line.starts_with("Binary files ") && line.ends_with(" differ") {
+                if line.ends_with(" and /dev/null differ") {
+                    change = "deleted";
+                } else if line.starts_with("Binary files /dev/null ") {
+                    change = "added";
+                }
+                lines.push(binary_line(&path, change));
+                continue;
+            } else if line == "GIT binary patch" {
+                lines.push(binary_line(&path, change));
+                // Skip the encoded literal/delta data up to the next file
+                while i < raw.len() && !raw[i].starts_with("diff --git ") {
+                    i += 1;
+                }
+                continue;
+            }
+        }
+
+        lines.push(DiffLine {
+            content: line.to_string(),
+            line_type: classify_diff_line(line),
+        });
+    }
+
+    lines
+}
+
+/// Prefix of collapsed rename lines
+const RENAME_PREFIX: &str = "renamed: ";
+
+/// Summary line for a binary file entry
+fn binary_line(path: &str, change: &str) -> DiffLine {
+    DiffLine {
+        content: format!("[binary] {path} ({change})"),
+        line_type: DiffLineType::Binary,
+    }
+}
+
 /// Classify a diff line by its prefix
 fn classify_diff_line(line: &str) -> DiffLineType {
     if line.is_empty() {
@@ -310,6 +1499,9 @@ fn classify_diff_line(line: &str) -> DiffLineType {
         || line.starts_with("index ")
         || line.starts_with("---")
         || line.starts_with("+++")
+        || line.starts_with("new file mode")
+        || line.starts_with("deleted file mode")
+        || line.starts_with("similarity index")
     {
         DiffLineType::FileHeader
     } else if line.starts_with("@@") {
@@ -334,7 +1526,10 @@ fn style_diff_line(line: &DiffLine) -> Line<'static> {
     // Add bold for headers
     if matches!(
         line.line_type,
-        DiffLineType::FileHeader | DiffLineType::HunkHeader
+        DiffLineType::FileHeader
+            | DiffLineType::HunkHeader
+            | DiffLineType::Rename
+            | DiffLineType::Binary
     ) {
         style = style.add_modifier(Modifier::BOLD);
     }
@@ -361,6 +1556,48 @@ mod tests {
         assert_eq!(view.title, Some("feat/auth -> main".to_string()));
     }
 
+    #[test]
+    fn test_diffview_from_texts() {
+        let old = "one\ntwo\nthree\n";
+        let new = "one\n2\nthree\nfour\n";
+        let view = DiffView::from_texts(old, new, "out/report.txt");
+        assert_eq!(view.title.as_deref(), Some("out/report.txt"));
+
+        let content = view.content();
+        assert!(content.starts_with(
+            "diff --git a/out/report.txt b/out/report.txt\n--- a/out/report.txt\n+++ b/out/report.txt\n@@ -1,3 +1,4 @@\n"
+        ));
+        assert!(content.contains("\n-two\n+2\n three\n+four\n"));
+
+        // Parsed like git output: one file, same per-line types
+        let stats = view.file_stats();
+        assert_eq!(stats.len(), 1);
+        assert_eq!((stats[0].path.as_str(), stats[0].additions, stats[0].deletions), ("out/report.txt", 2, 1));
+        assert!(view.lines.iter().any(|line| line.line_type == DiffLineType::Addition && line.content == "+four"));
+    }
+
+    #[test]
+    fn test_diffview_from_texts_identical() {
+        let view = DiffView::from_texts("same\n", "same\n", "x");
+        assert!(view.is_empty());
+        assert!(view.file_stats().is_empty());
+    }
+
+    #[test]
+    fn test_diffview_from_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let (old, new) = (dir.path().join("expected.md"), dir.path().join("actual.md"));
+        std::fs::write(&old, "# Title\n").unwrap();
+        std::fs::write(&new, "# Title\nbody\n").unwrap();
+
+        let view = DiffView::from_files(&old, &new).unwrap();
+        assert_eq!(view.file_stats()[0].additions, 1);
+        assert!(view.title.unwrap().ends_with("actual.md"));
+
+        let missing = dir.path().join("missing.md");
+        assert!(DiffView::from_files(&missing, &new).is_err());
+    }
+
     #[test]
     fn test_diffview_scroll_down() {
         let diff = (0..100).map(|i| format!("line {i}")).collect::<Vec<_>>().join("\n");
@@ -455,5 +1692,447 @@ mod tests {
 
         view.page_up(20);
         assert_eq!(view.scroll_position(), 0);
+
+        // A viewport with no rows left still moves by a line
+        view.page_down(1);
+        assert_eq!(view.scroll_position(), 1);
+        view.page_up(0);
+        assert_eq!(view.scroll_position(), 0);
+    }
+
+    const TWO_FILE_DIFF: &str = "\
+diff --git a/src/small.rs b/src/small.rs
+index 111..222 100644
+--- a/src/small.rs
++++ b/src/small.rs
+@@ -1,2 +1,2 @@
+-old
++new
+diff --git a/src/big.rs b/src/big.rs
+index 333..444 100644
+--- a/src/big.rs
++++ b/src/big.rs
+@@ -1,1 +1,4 @@
+ context
++one
++two
++++three
+---four";
+
+    #[test]
+    fn test_file_stats_sorted_by_churn() {
+        let view = DiffView::new(TWO_FILE_DIFF.to_string());
+        let stats = view.file_stats();
+
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].path, "src/big.rs");
+        assert_eq!(stats[0].additions, 3);
+        assert_eq!(stats[0].deletions, 1);
+        assert_eq!(stats[0].line_index, 7);
+        assert_eq!(stats[1].path, "src/small.rs");
+        assert_eq!(stats[1].additions, 1);
+        assert_eq!(stats[1].deletions, 1);
+        assert_eq!(stats[1].line_index, 0);
+    }
+
+    #[test]
+    fn test_summary_toggle_and_jump() {
+        let mut view = DiffView::new(TWO_FILE_DIFF.to_string());
+        assert!(!view.is_summary());
+
+        view.toggle_summary();
+        assert!(view.is_summary());
+
+        // Scrolling moves the file selection in summary mode
+        view.scroll_down(1);
+        assert_eq!(view.summary_selected(), 1);
+        assert_eq!(view.scroll_position(), 0);
+        view.scroll_down(5);
+        assert_eq!(view.summary_selected(), 1);
+
+        view.open_selected_file();
+        assert!(!view.is_summary());
+        assert_eq!(view.scroll_position(), 0); // src/small.rs header
+
+        view.toggle_summary();
+        view.scroll_to_top();
+        view.open_selected_file();
+        assert_eq!(view.scroll_position(), 7); // src/big.rs header
+    }
+
+    #[test]
+    fn test_file_stats_empty_diff() {
+        let mut view = DiffView::new(String::new());
+        assert!(view.file_stats().is_empty());
+
+        view.toggle_summary();
+        view.open_selected_file();
+        assert!(view.is_summary());
+    }
+
+    #[test]
+    fn test_scale_bar() {
+        assert_eq!(scale_bar(3, 1, 4, 40), (3, 1));
+        assert_eq!(scale_bar(0, 0, 10, 40), (0, 0));
+        let (adds, dels) = scale_bar(100, 100, 200, 20);
+        assert_eq!(adds + dels, 20);
+        assert_eq!(adds, 10);
+        // Tiny changes still get at least one character
+        let (adds, dels) = scale_bar(1, 0, 1000, 20);
+        assert_eq!((adds, dels), (1, 0));
+    }
+
+    const SPECIAL_DIFF: &str = "\
+diff --git a/assets/logo.png b/assets/logo.png
+new file mode 100644
+index 0000000..abc1234
+Binary files /dev/null and b/assets/logo.png differ
+diff --git a/src/old_name.rs b/src/new_name.rs
+similarity index 90%
+rename from src/old_name.rs
+rename to src/new_name.rs
+index 111..222 100644
+--- a/src/old_name.rs
++++ b/src/new_name.rs
+@@ -1 +1 @@
+-old
++new
+diff --git a/icon.ico b/icon.ico
+index 333..444 100644
+GIT binary patch
+literal 12
+TcmZQzU}RumU}j)oU;qFD
+
+literal 10
+RcmZQzU}Ruma8O`k
+
+diff --git a/gone.bin b/gone.bin
+deleted file mode 100644
+index 555..0000000
+Binary files a/gone.bin and /dev/null differ";
+
+    #[test]
+    fn test_binary_and_renamed_entries() {
+        let view = DiffView::new(SPECIAL_DIFF.to_string());
+        let contents: Vec<&str> = view.lines.iter().map(|l| l.content.as_str()).collect();
+
+        assert_eq!(contents[3], "[binary] assets/logo.png (added)");
+        assert_eq!(view.lines[3].line_type, DiffLineType::Binary);
+        assert_eq!(contents[6], "renamed: src/old_name.rs \u{2192} src/new_name.rs");
+        assert_eq!(view.lines[6].line_type, DiffLineType::Rename);
+        assert!(!contents.iter().any(|l| l.starts_with("rename to")));
+        assert_eq!(contents[15], "[binary] icon.ico (modified)");
+        // Encoded patch data is not shown; the next file follows directly
+        assert!(contents[16].starts_with("diff --git a/gone.bin"));
+        assert_eq!(contents[19], "[binary] gone.bin (deleted)");
+        assert_eq!(view.line_count(), 20);
+    }
+
+    #[test]
+    fn test_file_stats_classify_changes() {
+        let mut view = DiffView::new(SPECIAL_DIFF.to_string());
+        let stat = |path: &str| view.file_stats().iter().find(|s| s.path == path).unwrap().clone();
+
+        let logo = stat("assets/logo.png");
+        assert_eq!(logo.change, FileChange::Added);
+        assert!(logo.binary);
+        assert_eq!(logo.display_path(), "assets/logo.png [binary]");
+
+        let renamed = stat("src/new_name.rs");
+        assert_eq!(
+            renamed.change,
+            FileChange::Renamed { from: "src/old_name.rs".to_string() }
+        );
+        assert_eq!((renamed.additions, renamed.deletions), (1, 1));
+        assert_eq!(renamed.display_path(), "src/old_name.rs \u{2192} src/new_name.rs");
+
+        assert_eq!(stat("gone.bin").change, FileChange::Deleted);
+        assert_eq!(stat("icon.ico").change, FileChange::Modified);
+
+        // Jumping to a file lands on its header even after collapsed entries
+        let gone = stat("gone.bin");
+        view.toggle_summary();
+        let selected = view.file_stats().iter().position(|s| s.path == "gone.bin").unwrap();
+        view.scroll_down(selected as u16);
+        view.open_selected_file();
+        assert_eq!(view.scroll_position() as usize, gone.line_index);
+        assert!(view.lines[gone.line_index].content.starts_with("diff --git a/gone.bin"));
+    }
+
+    #[test]
+    fn test_overflow_cycle_and_horizontal_scroll() {
+        let long = format!("+{}", "x".repeat(100));
+        let mut view = DiffView::new(format!("{long}\n short"));
+        view.view_width.set(40);
+        assert_eq!(view.overflow(), LineOverflow::Truncate);
+
+        // Horizontal scrolling only applies in scroll mode
+        view.scroll_right(10);
+        assert_eq!(view.column_offset(), 0);
+
+        view.cycle_overflow();
+        assert_eq!(view.overflow(), LineOverflow::Wrap);
+        view.cycle_overflow();
+        assert_eq!(view.overflow(), LineOverflow::Scroll);
+
+        view.scroll_right(10);
+        assert_eq!(view.column_offset(), 10);
+        view.scroll_right(1000);
+        assert_eq!(view.column_offset(), 61); // 101 chars - 40 columns
+        view.scroll_left(1);
+        assert_eq!(view.column_offset(), 60);
+
+        let shifted = view.visible_lines(10);
+        let text: String = shifted[0].spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text.chars().count(), 41);
+        // Lines shorter than the offset become empty
+        assert!(shifted[1].spans.is_empty());
+
+        view.cycle_overflow();
+        assert_eq!(view.overflow(), LineOverflow::Truncate);
+        assert_eq!(view.column_offset(), 0);
+    }
+
+    #[test]
+    fn test_wrap_mode_counts_rows() {
+        // Each long line wraps to 3 rows at width 10
+        let diff = (0..10).map(|i| format!("+{i}{}", "y".repeat(20))).collect::<Vec<_>>().join("\n");
+        let mut view = DiffView::new(diff);
+        view.view_width.set(10);
+        view.set_overflow(LineOverflow::Wrap);
+
+        assert_eq!(view.visible_lines(6).len(), 2);
+        assert_eq!(view.visible_lines(7).len(), 3);
+
+        // 8 rows of content fit two whole wrapped lines
+        view.page_down(10);
+        assert_eq!(view.scroll_position(), 2);
+        view.page_up(10);
+        assert_eq!(view.scroll_position(), 0);
+    }
+
+    #[test]
+    fn test_wide_characters_are_measured_in_cells() {
+        // 12 CJK characters are 24 cells: 3 rows at width 10
+        assert_eq!(wrapped_rows(&"漢".repeat(12), 10), 3);
+
+        let line = Line::from(vec![Span::raw("ab"), Span::raw("漢字x")]);
+        let text = |line: Line<'static>| line.spans.iter().map(|s| s.content.to_string()).collect::<String>();
+        assert_eq!(text(skip_columns(line.clone(), 4)), "字x");
+        // Half of 漢 is skipped; its right cell stays blank
+        assert_eq!(text(skip_columns(line, 3)), " 字x");
+    }
+
+    #[test]
+    fn test_overflow_persists_across_file_jumps() {
+        let mut view = DiffView::new(TWO_FILE_DIFF.to_string());
+        view.set_overflow(LineOverflow::Wrap);
+
+        view.toggle_summary();
+        view.scroll_down(1);
+        view.open_selected_file();
+        assert_eq!(view.overflow(), LineOverflow::Wrap);
+    }
+
+    #[test]
+    fn test_search_and_cycle_matches() {
+        let mut view = DiffView::new(TWO_FILE_DIFF.to_string());
+
+        assert_eq!(view.search("BIG.rs"), &[7, 9, 10]);
+        assert_eq!(view.scroll_position(), 7);
+        assert_eq!(view.current_match(), Some(0));
+
+        assert_eq!(view.next_match(), Some(9));
+        assert_eq!(view.next_match(), Some(10));
+        assert_eq!(view.next_match(), Some(7)); // wraps
+        assert_eq!(view.prev_match(), Some(10));
+        assert_eq!(view.scroll_position(), 10);
+
+        view.toggle_search_case();
+        assert!(view.search_matches().is_empty());
+        view.toggle_search_case();
+        assert_eq!(view.search_matches().len(), 3);
+
+        assert!(view.search("nothing here").is_empty());
+        assert_eq!(view.next_match(), None);
+
+        view.clear_search();
+        assert_eq!(view.search_query(), "");
+    }
+
+    #[test]
+    fn test_search_starts_from_scroll_position() {
+        let mut view = DiffView::new(TWO_FILE_DIFF.to_string());
+        view.scroll_down(5);
+        assert_eq!(view.search("diff --git"), &[0, 7]);
+        assert_eq!(view.scroll_position(), 7);
+
+        // Nothing at or below the viewport: wrap to the first match
+        view.scroll_down(5);
+        view.search("src/small.rs");
+        assert_eq!(view.scroll_position(), 0);
+    }
+
+    #[test]
+    fn test_search_highlights_matches_across_spans() {
+        let mut view = DiffView::new("@@ -1 +1 @@\n-let x = foo(1);\n+let x = bar(1);".to_string());
+        view.search("X = ");
+        let line = view.styled_line(2, &view.lines[2]);
+        let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, "+let x = bar(1);");
+        let highlighted: Vec<&str> = line
+            .spans
+            .iter()
+            .filter(|s| s.style.bg == Some(theme().neon_cyan()))
+            .map(|s| s.content.as_ref())
+            .collect();
+        assert_eq!(highlighted.concat(), "x = ");
+        assert_eq!(find_matches("AbcABC", "abc", false), vec![(0, 3), (3, 6)]);
+        assert_eq!(find_matches("AbcABC", "abc", true), Vec::<(usize, usize)>::new());
+    }
+
+    #[test]
+    fn test_word_segments_pairs_changed_words() {
+        let diff = "@@ -1 +1 @@\n-let x = foo(1);\n+let x = bar(1);".to_string();
+        let view = DiffView::new(diff);
+        assert!(view.word_diff_enabled());
+
+        let old = view.word_segments[1].as_ref().unwrap();
+        let new = view.word_segments[2].as_ref().unwrap();
+
+        let changed_old: Vec<&str> = old.iter().filter(|s| s.changed).map(|s| s.text.as_str()).collect();
+        let changed_new: Vec<&str> = new.iter().filter(|s| s.changed).map(|s| s.text.as_str()).collect();
+        assert_eq!(changed_old, vec!["foo(1);"]);
+        assert_eq!(changed_new, vec!["bar(1);"]);
+
+        // Segments reassemble the original text
+        let joined: String = new.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(joined, "let x = bar(1);");
+    }
+
+    #[test]
+    fn test_word_segments_unpaired_and_unrelated_lines() {
+        let diff = "@@ -1,2 +1,1 @@\n-alpha beta gamma\n-removed only\n+zzz yyy xxx".to_string();
+        let view = DiffView::new(diff);
+
+        // Unrelated pair falls back to whole-line styling
+        assert!(view.word_segments[1].is_none());
+        assert!(view.word_segments[3].is_none());
+        // Deletion without a counterpart
+        assert!(view.word_segments[2].is_none());
+    }
+
+    #[test]
+    fn test_word_diff_toggle() {
+        let mut view = DiffView::new("-a b\n+a c".to_string());
+        assert!(view.word_diff_enabled());
+
+        view.toggle_word_diff();
+        assert!(!view.word_diff_enabled());
+
+        view.toggle_word_diff();
+        assert!(view.word_diff_enabled());
+        assert!(view.word_segments[0].is_some());
+    }
+
+    #[test]
+    fn test_word_diff_disabled_for_huge_diffs() {
+        let diff = (0..=WORD_DIFF_MAX_LINES).map(|i| format!("+line {i}")).collect::<Vec<_>>().join("\n");
+        let view = DiffView::new(diff);
+        assert!(!view.word_diff_enabled());
+        assert!(view.word_segments.is_empty());
+    }
+
+    #[test]
+    fn test_line_locations_follow_hunk_headers() {
+        let diff = "diff --git a/src/a.rs b/src/a.rs\n--- a/src/a.rs\n+++ b/src/a.rs\n\
+                    @@ -3,3 +3,3 @@ fn main()\n ctx\n-old\n+new\n\\ No newline at end of file\n\
+                    @@ -20 +20,2 @@\n+--- looks like a header\n ctx";
+        let view = DiffView::new(diff.to_string());
+        let at = |index| view.location(index).map(ToString::to_string);
+
+        assert_eq!(at(0), None);
+        assert_eq!(at(3), None);
+        assert_eq!(at(4).as_deref(), Some("src/a.rs:3"));
+        assert_eq!(at(5).as_deref(), Some("src/a.rs:4 (removed)"));
+        assert_eq!(at(6).as_deref(), Some("src/a.rs:4"));
+        assert_eq!(at(7), None);
+        assert_eq!(at(9).as_deref(), Some("src/a.rs:20"));
+        assert_eq!(at(10).as_deref(), Some("src/a.rs:21"));
+        assert_eq!(view.location(5).unwrap().side, DiffSide::Old);
+    }
+
+    #[test]
+    fn test_line_numbers_across_hunks_and_files() {
+        let view = DiffView::new(TWO_FILE_DIFF.to_string());
+        assert_eq!(view.line_numbers(0), (None, None));
+        assert_eq!(view.line_numbers(4), (None, None));
+        assert_eq!(view.line_numbers(5), (Some(1), None));
+        assert_eq!(view.line_numbers(6), (None, Some(1)));
+        // Second file starts counting again from its own hunk header
+        assert_eq!(view.line_numbers(12), (Some(1), Some(1)));
+        assert_eq!(view.line_numbers(15), (None, Some(4)));
+        assert_eq!(view.line_numbers(16), (Some(2), None));
+        assert_eq!(view.line_numbers(99), (None, None));
+
+        let diff = "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n\
+                    @@ -3,2 +3,2 @@\n ctx\n-old\n+new\n\\ No newline at end of file\n\
+                    @@ -120 +120,2 @@\n ctx\n+added";
+        let view = DiffView::new(diff.to_string());
+        assert_eq!(view.line_numbers(4), (Some(3), Some(3)));
+        assert_eq!(view.line_numbers(7), (None, None));
+        assert_eq!(view.line_numbers(9), (Some(120), Some(120)));
+        assert_eq!(view.line_numbers(10), (None, Some(121)));
+    }
+
+    #[test]
+    fn test_line_number_gutter() {
+        let diff = "diff --git a/a.rs b/a.rs\n@@ -9,2 +9,2 @@\n ctx\n-a long removed line\n+new";
+        let mut view = DiffView::new(diff.to_string());
+        assert!(!view.line_numbers_enabled());
+        assert_eq!(view.visible_lines(5)[2].spans[0].content, " ctx");
+
+        view.toggle_line_numbers();
+        assert_eq!(view.gutter_width(), 8);
+        let lines = view.visible_lines(5);
+        assert_eq!(lines[0].spans[0].content, "      \u{2502} ");
+        assert_eq!(lines[2].spans[0].content, " 9  9 \u{2502} ");
+        assert_eq!(lines[3].spans[0].content, "10    \u{2502} ");
+        assert_eq!(lines[4].spans[0].content, "   10 \u{2502} ");
+
+        // Scrolling sideways moves the content, not the gutter
+        view.set_overflow(LineOverflow::Scroll);
+        view.view_width.set(4);
+        view.scroll_right(2);
+        let lines = view.visible_lines(5);
+        assert_eq!(lines[3].spans[0].content, "10    \u{2502} ");
+        let text: String = lines[3].spans[1..].iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(text, " long removed line");
+    }
+
+    #[test]
+    fn test_current_location_skips_headers() {
+        let mut view = DiffView::new(TWO_FILE_DIFF.to_string());
+        let first = view.location(5).cloned();
+        assert!(first.is_some());
+        assert_eq!(view.current_location().cloned(), first);
+
+        view.toggle_summary();
+        assert!(view.current_location().is_none());
+    }
+
+    #[test]
+    fn test_comment_marks() {
+        let mut view = DiffView::new(TWO_FILE_DIFF.to_string());
+        let location = view.location(5).cloned().unwrap();
+        view.set_comment_marks([&location]);
+        assert!(view.is_commented(5));
+        assert!(!view.is_commented(6));
+
+        let lines = view.visible_lines(10);
+        assert!(lines[5].spans.last().unwrap().content.contains('\u{25c6}'));
+
+        view.set_comment_marks([]);
+        assert!(!view.is_commented(5));
     }
 }
@@ -0,0 +1,135 @@
+//! Structured progress events for external dashboards
+//!
+//! The TUI emits an `Event` whenever an agent starts, a task finishes, a
+//! merge lands or the plan completes. Each one is stamped with the time and
+//! sent to control socket clients and, with `event_log = true`, appended as
+//! a JSON line to `.cctakt/events.jsonl`:
+//!
+//! ```text
+//! {"timestamp":1700000000,"event":"agent_started","agent_id":3,"name":"w1","task_id":"w1","branch":"feat/a"}
+//! {"timestamp":1700000420,"event":"task_completed","task_id":"w1","agent_id":3}
+//! ```
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Event log path, relative to the repository root
+pub const EVENTS_FILE: &str = ".cctakt/events.jsonl";
+
+/// Something that happened during a session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// A worker (or merge/build worker) was spawned
+    AgentStarted {
+        agent_id: u64,
+        name: String,
+        task_id: Option<String>,
+        branch: Option<String>,
+    },
+    /// A plan task finished successfully
+    TaskCompleted {
+        task_id: String,
+        agent_id: Option<u64>,
+    },
+    /// A plan task failed
+    TaskFailed {
+        task_id: String,
+        agent_id: Option<u64>,
+        error: String,
+    },
+    /// A branch was merged
+    MergeSucceeded {
+        branch: String,
+        target: String,
+        task_id: Option<String>,
+    },
+    /// Every task in the plan has finished
+    PlanComplete { completed: usize, failed: usize },
+}
+
+/// An event with the Unix time it happened
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventRecord {
+    pub timestamp: u64,
+    #[serde(flatten)]
+    pub event: Event,
+}
+
+impl EventRecord {
+    /// Stamp an event with the current time
+    pub fn now(event: Event) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self { timestamp, event }
+    }
+}
+
+/// Append a record to the JSON lines file at `path`
+pub fn append(path: &Path, record: &EventRecord) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(record)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_serialization_is_flat() {
+        let record = EventRecord {
+            timestamp: 1_700_000_000,
+            event: Event::TaskCompleted {
+                task_id: "w1".to_string(),
+                agent_id: Some(3),
+            },
+        };
+        assert_eq!(
+            serde_json::to_string(&record).unwrap(),
+            r#"{"timestamp":1700000000,"event":"task_completed","task_id":"w1","agent_id":3}"#
+        );
+        let parsed: EventRecord = serde_json::from_str(&serde_json::to_string(&record).unwrap()).unwrap();
+        assert_eq!(parsed, record);
+    }
+
+    #[test]
+    fn test_append_writes_one_line_per_event() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(".cctakt/events.jsonl");
+
+        append(&path, &EventRecord::now(Event::PlanComplete { completed: 2, failed: 0 })).unwrap();
+        append(
+            &path,
+            &EventRecord::now(Event::MergeSucceeded {
+                branch: "feat/a".to_string(),
+                target: "main".to_string(),
+                task_id: None,
+            }),
+        )
+        .unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let events: Vec<Event> = content
+            .lines()
+            .map(|line| serde_json::from_str::<EventRecord>(line).unwrap().event)
+            .collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0], Event::PlanComplete { completed: 2, failed: 0 });
+        assert!(matches!(events[1], Event::MergeSucceeded { .. }));
+    }
+}
@@ -1,6 +1,8 @@
 //! Git utility functions
 
-use std::path::PathBuf;
+use anyhow::{anyhow, Result};
+use cctakt::validate_repository;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 /// Get commit log from worktree
@@ -53,6 +55,193 @@ pub fn get_worker_commits(worktree_path: &PathBuf) -> Vec<String> {
     }
 }
 
+/// A commit with its full message and changed files
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitDetail {
+    pub hash: String,
+    /// `Name <email>`
+    pub author: String,
+    pub date: String,
+    pub subject: String,
+    /// Message body (may be empty)
+    pub body: String,
+    pub files: Vec<String>,
+}
+
+/// `git log` format: each commit starts with 0x1e, fields end with 0x1f,
+/// and `--name-only` appends the file list after the last field
+const COMMIT_DETAIL_FORMAT: &str = "--format=%x1e%H%x1f%an <%ae>%x1f%ad%x1f%s%x1f%b%x1f";
+
+/// Get full details of the commits on a worktree's branch
+///
+/// Lists commits ahead of `base`; falls back to the 20 most recent commits
+/// if `base` doesn't exist.
+pub fn get_commit_details(worktree_path: &Path, base: &str) -> Vec<CommitDetail> {
+    log_commit_details(worktree_path, &[&format!("{base}..HEAD")])
+        .or_else(|| log_commit_details(worktree_path, &["-n", "20"]))
+        .unwrap_or_default()
+}
+
+/// Commits a worktree's branch has on top of `base`, and nothing else
+///
+/// Unlike [`get_commit_details`] there is no fallback: an unknown `base`
+/// yields no commits rather than history that is already on it.
+pub fn get_commits_since(worktree_path: &Path, base: &str) -> Vec<CommitDetail> {
+    log_commit_details(worktree_path, &[&format!("{base}..HEAD")]).unwrap_or_default()
+}
+
+/// Run `git log` with `COMMIT_DETAIL_FORMAT` over `range`
+fn log_commit_details(worktree_path: &Path, range: &[&str]) -> Option<Vec<CommitDetail>> {
+    Command::new("git")
+        .current_dir(worktree_path)
+        .args(["log", COMMIT_DETAIL_FORMAT, "--date=format:%Y-%m-%d %H:%M", "--name-only"])
+        .args(range)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| parse_commit_details(&String::from_utf8_lossy(&o.stdout)))
+}
+
+/// Parse `git log` output produced with `COMMIT_DETAIL_FORMAT`
+pub fn parse_commit_details(output: &str) -> Vec<CommitDetail> {
+    output
+        .split('\x1e')
+        .filter(|record| !record.trim().is_empty())
+        .filter_map(|record| {
+            let mut fields = record.split('\x1f');
+            let mut next = || fields.next().map(|f| f.trim().to_string());
+            let hash = next()?;
+            let author = next()?;
+            let date = next()?;
+            let subject = next()?;
+            let body = next()?;
+            let files = next()
+                .unwrap_or_default()
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect();
+            Some(CommitDetail {
+                hash,
+                author,
+                date,
+                subject,
+                body,
+                files,
+            })
+        })
+        .collect()
+}
+
+/// Commits whose subject doesn't match `pattern`, as `<short hash> <subject>`
+pub fn nonconforming_commits(commits: &[CommitDetail], pattern: &regex::Regex) -> Vec<String> {
+    commits
+        .iter()
+        .filter(|commit| !pattern.is_match(&commit.subject))
+        .map(|commit| {
+            let short = commit.hash.get(..7).unwrap_or(&commit.hash);
+            format!("{short} {}", commit.subject)
+        })
+        .collect()
+}
+
+/// Conventional Commits type of a commit, for highlighting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitKind {
+    /// `feat`
+    Feature,
+    /// `fix`
+    Fix,
+    /// Any other type (`docs`, `refactor`, ...)
+    Other,
+}
+
+/// A `git log --oneline` line split into the parts shown in different styles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OnelineCommit<'a> {
+    pub hash: &'a str,
+    /// Conventional Commits prefix including scope and colon (`feat(ui)!:`)
+    pub prefix: Option<&'a str>,
+    /// The rest of the subject
+    pub subject: &'a str,
+}
+
+impl<'a> OnelineCommit<'a> {
+    pub fn parse(line: &'a str) -> Self {
+        let (hash, subject) = line.split_once(' ').unwrap_or((line, ""));
+        match conventional_prefix_len(subject) {
+            Some(len) => Self {
+                hash,
+                prefix: Some(&subject[..len]),
+                subject: subject[len..].trim_start(),
+            },
+            None => Self { hash, prefix: None, subject },
+        }
+    }
+
+    /// Type named by the prefix, if the subject has one
+    pub fn kind(&self) -> Option<CommitKind> {
+        let prefix = self.prefix?;
+        Some(if prefix.starts_with("feat") {
+            CommitKind::Feature
+        } else if prefix.starts_with("fix") {
+            CommitKind::Fix
+        } else {
+            CommitKind::Other
+        })
+    }
+}
+
+/// Length of a `type(scope)!:` prefix followed by a space, if `subject` starts with one
+fn conventional_prefix_len(subject: &str) -> Option<usize> {
+    let type_len = subject.bytes().take_while(u8::is_ascii_lowercase).count();
+    if type_len == 0 {
+        return None;
+    }
+    let mut rest = &subject[type_len..];
+    if let Some(scoped) = rest.strip_prefix('(') {
+        rest = &scoped[scoped.find(')')? + 1..];
+    }
+    rest = rest.strip_prefix('!').unwrap_or(rest);
+    rest.strip_prefix(": ")?;
+    Some(subject.len() - rest.len() + 1)
+}
+
+/// Environment variable that overrides the GitHub repository
+pub const REPO_ENV_VAR: &str = "CCTAKT_REPO";
+
+/// Pick the repository override: `--repo` flag, then `CCTAKT_REPO`
+///
+/// Returns None if neither is set, so callers fall back to config and then
+/// the git remote. A malformed override is an error rather than ignored.
+pub fn repo_override(flag: Option<String>) -> Result<Option<String>> {
+    let env = std::env::var(REPO_ENV_VAR).ok().filter(|v| !v.is_empty());
+    choose_repo_override(flag, env)
+}
+
+/// Resolve the repository: `--repo`/`CCTAKT_REPO`, then config, then git remote
+pub fn resolve_github_repo(flag: Option<String>, configured: Option<&str>) -> Result<String> {
+    repo_override(flag)?
+        .or_else(|| configured.map(str::to_string))
+        .or_else(detect_github_repo)
+        .ok_or_else(|| {
+            anyhow!(
+                "No repository configured. Pass --repo, set 'repository' in cctakt.toml or add a git remote."
+            )
+        })
+}
+
+fn choose_repo_override(flag: Option<String>, env: Option<String>) -> Result<Option<String>> {
+    let (source, repo) = match (flag, env) {
+        (Some(repo), _) => ("--repo", repo),
+        (None, Some(repo)) => (REPO_ENV_VAR, repo),
+        (None, None) => return Ok(None),
+    };
+    validate_repository(&repo).map_err(|e| anyhow!("{source}: {e}"))?;
+    Ok(Some(repo))
+}
+
 /// Detect GitHub repository from git remote
 pub fn detect_github_repo() -> Option<String> {
     let output = Command::new("git")
@@ -96,6 +285,48 @@ pub fn parse_github_url(url: &str) -> Option<String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_nonconforming_commits() {
+        let commit = |hash: &str, subject: &str| CommitDetail {
+            hash: hash.to_string(),
+            subject: subject.to_string(),
+            ..Default::default()
+        };
+        let commits = [
+            commit("0123456789abcdef", "feat(ui): add footer"),
+            commit("fedcba9876543210", "wip"),
+            commit("abc", "Fix typo"),
+        ];
+        let pattern = regex::Regex::new(cctakt::config::DEFAULT_COMMIT_SUBJECT_PATTERN).unwrap();
+        assert_eq!(
+            nonconforming_commits(&commits, &pattern),
+            vec!["fedcba9 wip".to_string(), "abc Fix typo".to_string()]
+        );
+
+        let anything = regex::Regex::new("").unwrap();
+        assert!(nonconforming_commits(&commits, &anything).is_empty());
+    }
+
+    #[test]
+    fn test_oneline_commit_parse() {
+        let commit = OnelineCommit::parse("abc1234 feat(ui)!: add footer");
+        assert_eq!(commit.hash, "abc1234");
+        assert_eq!(commit.prefix, Some("feat(ui)!:"));
+        assert_eq!(commit.subject, "add footer");
+        assert_eq!(commit.kind(), Some(CommitKind::Feature));
+
+        assert_eq!(OnelineCommit::parse("abc1234 fix: typo").kind(), Some(CommitKind::Fix));
+        assert_eq!(OnelineCommit::parse("abc1234 docs: readme").kind(), Some(CommitKind::Other));
+
+        // Not a conventional prefix: uppercase type, no space after the colon
+        for line in ["abc1234 Fix: typo", "abc1234 note:x", "abc1234 wip"] {
+            let commit = OnelineCommit::parse(line);
+            assert_eq!(commit.prefix, None, "{line}");
+            assert_eq!(commit.subject, &line[8..]);
+        }
+        assert_eq!(OnelineCommit::parse("abc1234").subject, "");
+    }
+
     #[test]
     fn test_parse_github_url_https() {
         let url = "https://github.com/owner/repo.git";
@@ -162,6 +393,34 @@ mod tests {
         assert!(!log.is_empty());
     }
 
+    #[test]
+    fn test_parse_commit_details() {
+        let output = "\x1eabc123\x1fAda <ada@example.com>\x1f2024-05-01 10:00\x1fAdd parser\x1fLonger explanation.\n\nCloses #4\n\x1f\n\nsrc/a.rs\nsrc/b.rs\n\
+                      \x1edef456\x1fBob <bob@example.com>\x1f2024-05-02 11:30\x1fFix typo\x1f\x1f\n\nREADME.md\n";
+        let commits = parse_commit_details(output);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].hash, "abc123");
+        assert_eq!(commits[0].author, "Ada <ada@example.com>");
+        assert_eq!(commits[0].subject, "Add parser");
+        assert_eq!(commits[0].body, "Longer explanation.\n\nCloses #4");
+        assert_eq!(commits[0].files, vec!["src/a.rs", "src/b.rs"]);
+        assert_eq!(commits[1].body, "");
+        assert_eq!(commits[1].files, vec!["README.md"]);
+        assert!(parse_commit_details("").is_empty());
+    }
+
+    #[test]
+    fn test_get_commit_details_current_repo() {
+        let commits = get_commit_details(Path::new("."), "HEAD~1");
+        assert!(commits.iter().all(|c| !c.hash.is_empty()));
+    }
+
+    #[test]
+    fn test_get_commits_since_unknown_base() {
+        assert!(get_commits_since(Path::new("."), "no-such-base-branch").is_empty());
+        assert!(get_commits_since(Path::new("."), "HEAD").is_empty());
+    }
+
     #[test]
     fn test_get_worker_commits_current_repo() {
         let commits = get_worker_commits(&PathBuf::from("."));
@@ -182,4 +441,32 @@ mod tests {
             assert!(first.len() >= 7, "Commit should have hash: {first}");
         }
     }
+
+    #[test]
+    fn test_repo_override_precedence() {
+        let flag = Some("flag/repo".to_string());
+        let env = Some("env/repo".to_string());
+        assert_eq!(
+            choose_repo_override(flag, env.clone()).unwrap(),
+            Some("flag/repo".to_string())
+        );
+        assert_eq!(
+            choose_repo_override(None, env).unwrap(),
+            Some("env/repo".to_string())
+        );
+        assert_eq!(choose_repo_override(None, None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_repo_override_rejects_malformed() {
+        let err = choose_repo_override(Some("just-a-name".to_string()), None)
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("--repo:"), "{err}");
+
+        let err = choose_repo_override(None, Some("a/b/c".to_string()))
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with(REPO_ENV_VAR), "{err}");
+    }
 }
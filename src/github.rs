@@ -3,6 +3,7 @@
 //! Provides functionality to fetch issues from GitHub and use them as agent tasks.
 
 use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::process::Command;
 
@@ -29,6 +30,18 @@ pub struct Issue {
 
     /// URL to the issue on GitHub
     pub html_url: String,
+
+    /// Number of comments
+    #[serde(default)]
+    pub comments: u64,
+
+    /// When the issue was opened
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+
+    /// When the issue was last updated (comments and label changes count)
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 /// GitHub Label representation
@@ -100,6 +113,69 @@ pub struct CreatePullRequest {
     pub draft: bool,
 }
 
+/// Verdict of a pull request review
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReviewEvent {
+    Approve,
+    RequestChanges,
+    Comment,
+}
+
+impl ReviewEvent {
+    /// Short label, e.g. "changes requested"
+    pub fn label(self) -> &'static str {
+        match self {
+            ReviewEvent::Approve => "approved",
+            ReviewEvent::RequestChanges => "changes requested",
+            ReviewEvent::Comment => "commented",
+        }
+    }
+}
+
+/// Side of the pull request diff a review comment's line is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ReviewSide {
+    /// Old file (removed lines)
+    Left,
+    /// New file (added and context lines)
+    Right,
+}
+
+/// A line comment in a pull request review
+///
+/// Addressed by file line number and side rather than diff `position`, so
+/// the line must fall inside one of the pull request's diff hunks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReviewLineComment {
+    /// File path
+    pub path: String,
+    /// 1-based line number on `side`
+    pub line: usize,
+    pub side: ReviewSide,
+    /// Comment text
+    pub body: String,
+}
+
+/// What a branch's protection asks of changes to it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BranchProtection {
+    /// Changes must go through a pull request
+    pub requires_pull_request: bool,
+
+    /// Approving reviews a pull request needs before it can be merged
+    pub required_approvals: u64,
+}
+
+impl BranchProtection {
+    /// Fold in one pull request requirement (classic protection or a ruleset)
+    fn require_pull_request(&mut self, approvals: Option<u64>) {
+        self.requires_pull_request = true;
+        self.required_approvals = self.required_approvals.max(approvals.unwrap_or(0));
+    }
+}
+
 /// HTTP response abstraction for testing
 #[derive(Debug, Clone)]
 pub struct HttpResponse {
@@ -124,6 +200,7 @@ pub trait HttpClient: Send + Sync {
 }
 
 /// Real HTTP client using ureq
+#[derive(Clone)]
 pub struct UreqHttpClient {
     agent: ureq::Agent,
 }
@@ -194,7 +271,26 @@ impl HttpClient for UreqHttpClient {
     }
 }
 
+/// Check that `repo` is a GitHub repository in `owner/name` format
+pub fn validate_repository(repo: &str) -> Result<()> {
+    let valid_part = |part: &str| {
+        !part.is_empty()
+            && part != "."
+            && part != ".."
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    match repo.split_once('/') {
+        Some((owner, name)) if valid_part(owner) && valid_part(name) => Ok(()),
+        _ => Err(anyhow!(
+            "Invalid repository '{repo}': expected owner/name (e.g. taiki3/cctakt)"
+        )),
+    }
+}
+
 /// GitHub API client
+#[derive(Clone)]
 pub struct GitHubClient<H: HttpClient = UreqHttpClient> {
     /// Repository in "owner/repo" format
     repository: String,
@@ -490,6 +586,104 @@ impl<H: HttpClient> GitHubClient<H> {
         Ok(prs)
     }
 
+    /// The open pull request whose head is `branch`, if any
+    pub fn find_open_pull_request(&self, branch: &str) -> Result<Option<PullRequest>> {
+        let owner = self.repository.split('/').next().unwrap_or_default();
+        let prs = self.list_pull_requests("open", Some(&format!("{owner}:{branch}")), None)?;
+        Ok(prs.into_iter().find(|pr| pr.head.branch == branch))
+    }
+
+    /// Submit a review on pull request `number`
+    ///
+    /// GitHub requires a `body` for `RequestChanges` and `Comment` reviews
+    /// that have no line comments.
+    pub fn create_review(
+        &self,
+        number: u64,
+        event: ReviewEvent,
+        body: Option<&str>,
+        comments: &[ReviewLineComment],
+    ) -> Result<()> {
+        let url = format!(
+            "https://api.github.com/repos/{}/pulls/{}/reviews",
+            self.repository, number
+        );
+
+        self.token.as_ref()
+            .ok_or_else(|| anyhow!("Authentication required to submit reviews"))?;
+
+        let mut json_body = serde_json::json!({
+            "event": event,
+            "comments": comments,
+        });
+        if let Some(body) = body {
+            json_body["body"] = serde_json::Value::String(body.to_string());
+        }
+
+        let mut headers = self.build_headers();
+        headers.push(("Content-Type".to_string(), "application/json".to_string()));
+
+        let response = self.http.post(&url, headers, json_body.to_string())
+            .with_context(|| format!("Failed to submit review on PR #{number}"))?;
+
+        if response.status != 200 {
+            return Err(anyhow!(
+                "Failed to submit review: HTTP {}",
+                response.status
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Protection rules that apply to `branch`
+    ///
+    /// Combines classic branch protection with repository rulesets. Reading
+    /// classic protection needs admin rights, so it is skipped when GitHub
+    /// refuses; rulesets are readable by anyone who can read the repository.
+    pub fn get_branch_protection(&self, branch: &str) -> Result<BranchProtection> {
+        let mut protection = BranchProtection::default();
+
+        let url = format!(
+            "https://api.github.com/repos/{}/branches/{}/protection",
+            self.repository, branch
+        );
+        // 404 (not protected) and 403 (no admin rights) leave only the rulesets
+        if let Ok(response) = self.http.get(&url, self.build_headers())
+            && response.status == 200
+        {
+            let classic: serde_json::Value = serde_json::from_str(&response.body)
+                .context("Failed to parse branch protection response")?;
+            if let Some(reviews) = classic.get("required_pull_request_reviews") {
+                protection.require_pull_request(
+                    reviews.get("required_approving_review_count").and_then(|n| n.as_u64()),
+                );
+            }
+        }
+
+        let url = format!(
+            "https://api.github.com/repos/{}/rules/branches/{}",
+            self.repository, branch
+        );
+        let response = self.http.get(&url, self.build_headers())
+            .with_context(|| format!("Failed to fetch the rules for {branch}"))?;
+        if response.status != 200 {
+            return Err(anyhow!(
+                "Failed to fetch the rules for {branch}: HTTP {}",
+                response.status
+            ));
+        }
+        let rules: Vec<serde_json::Value> = serde_json::from_str(&response.body)
+            .context("Failed to parse branch rules response")?;
+        for rule in rules.iter().filter(|rule| rule["type"] == "pull_request") {
+            protection.require_pull_request(
+                rule["parameters"]["required_approving_review_count"].as_u64(),
+            );
+        }
+
+        Ok(protection)
+    }
+
     /// Check if client has authentication
     pub fn has_auth(&self) -> bool {
         self.token.is_some()
@@ -520,6 +714,34 @@ impl Issue {
     pub fn has_label(&self, name: &str) -> bool {
         self.labels.iter().any(|l| l.name == name)
     }
+
+    /// How long ago the issue was opened ("3d ago"), if known
+    pub fn age(&self, now: DateTime<Utc>) -> Option<String> {
+        self.created_at.map(|at| format_age(at, now))
+    }
+}
+
+/// Format the time between `at` and `now` as a short relative age
+///
+/// "just now", "5m ago", "3h ago", "12d ago", "4mo ago" or "2y ago".
+/// Timestamps in the future (clock skew) count as "just now".
+pub fn format_age(at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let minutes = (now - at).num_minutes();
+    let hours = minutes / 60;
+    let days = hours / 24;
+    if minutes < 1 {
+        "just now".to_string()
+    } else if hours < 1 {
+        format!("{minutes}m ago")
+    } else if days < 1 {
+        format!("{hours}h ago")
+    } else if days < 30 {
+        format!("{days}d ago")
+    } else if days < 365 {
+        format!("{}mo ago", days / 30)
+    } else {
+        format!("{}y ago", days / 365)
+    }
 }
 
 #[cfg(test)]
@@ -535,6 +757,9 @@ mod tests {
             labels: vec![],
             state: "open".to_string(),
             html_url: "https://github.com/test/repo/issues/123".to_string(),
+            comments: 0,
+            created_at: None,
+            updated_at: None,
         };
 
         assert_eq!(issue.short_description(), "#123: Test issue");
@@ -558,6 +783,9 @@ mod tests {
             ],
             state: "open".to_string(),
             html_url: "https://github.com/test/repo/issues/1".to_string(),
+            comments: 0,
+            created_at: None,
+            updated_at: None,
         };
 
         assert_eq!(issue.label_names(), "bug, enhancement");
@@ -575,12 +803,45 @@ mod tests {
             }],
             state: "open".to_string(),
             html_url: "https://github.com/test/repo/issues/1".to_string(),
+            comments: 0,
+            created_at: None,
+            updated_at: None,
         };
 
         assert!(issue.has_label("bug"));
         assert!(!issue.has_label("enhancement"));
     }
 
+    #[test]
+    fn test_format_age() {
+        let now: DateTime<Utc> = "2024-06-15T12:00:00Z".parse().unwrap();
+        let age = |at: &str| format_age(at.parse().unwrap(), now);
+        assert_eq!(age("2024-06-15T12:00:00Z"), "just now");
+        assert_eq!(age("2024-06-15T11:59:30Z"), "just now");
+        assert_eq!(age("2024-06-15T13:00:00Z"), "just now");
+        assert_eq!(age("2024-06-15T11:55:00Z"), "5m ago");
+        assert_eq!(age("2024-06-15T09:00:00Z"), "3h ago");
+        assert_eq!(age("2024-06-12T11:00:00Z"), "3d ago");
+        assert_eq!(age("2024-05-17T12:00:00Z"), "29d ago");
+        assert_eq!(age("2024-05-16T12:00:00Z"), "1mo ago");
+        assert_eq!(age("2024-03-01T00:00:00Z"), "3mo ago");
+        assert_eq!(age("2022-01-01T00:00:00Z"), "2y ago");
+    }
+
+    #[test]
+    fn test_issue_age() {
+        let now: DateTime<Utc> = "2024-06-15T12:00:00Z".parse().unwrap();
+        let mut issue: Issue = serde_json::from_str(
+            r#"{"number": 1, "title": "T", "body": null, "labels": [], "state": "open", "html_url": ""}"#,
+        )
+        .unwrap();
+        assert_eq!(issue.comments, 0);
+        assert_eq!(issue.age(now), None);
+
+        issue.created_at = Some("2024-06-13T12:00:00Z".parse().unwrap());
+        assert_eq!(issue.age(now).as_deref(), Some("2d ago"));
+    }
+
     #[test]
     fn test_github_client_with_token() {
         let client = GitHubClient::with_token("owner/repo", Some("test-token".to_string()));
@@ -731,7 +992,10 @@ mod mock_tests {
             "body": "Issue body",
             "labels": [{"name": "bug", "color": "d73a4a"}],
             "state": "open",
-            "html_url": "https://github.com/test/repo/issues/42"
+            "html_url": "https://github.com/test/repo/issues/42",
+            "comments": 3,
+            "created_at": "2024-06-01T09:30:00Z",
+            "updated_at": "2024-06-10T18:00:00Z"
         }"#.to_string()
     }
 
@@ -806,6 +1070,9 @@ mod mock_tests {
         assert_eq!(issue.number, 42);
         assert_eq!(issue.title, "Test issue");
         assert!(issue.has_label("bug"));
+        assert_eq!(issue.comments, 3);
+        assert_eq!(issue.created_at, Some("2024-06-01T09:30:00Z".parse().unwrap()));
+        assert_eq!(issue.updated_at, Some("2024-06-10T18:00:00Z".parse().unwrap()));
     }
 
     #[test]
@@ -1106,4 +1373,132 @@ mod mock_tests {
         assert_eq!(response.status, 200);
         assert_eq!(response.body, "test body");
     }
+
+    #[test]
+    fn test_validate_repository() {
+        assert!(validate_repository("owner/repo").is_ok());
+        assert!(validate_repository("my-org/my_repo.rs").is_ok());
+
+        for bad in ["", "owner", "owner/", "/repo", "a/b/c", "owner/re po", "../repo"] {
+            let err = validate_repository(bad).unwrap_err().to_string();
+            assert!(err.contains("owner/name"), "{bad}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_create_review_with_mock() {
+        let mut mock = MockHttpClient::new();
+        mock.expect_post()
+            .withf(|url: &str, _: &Headers, body: &String| {
+                let json: serde_json::Value = serde_json::from_str(body).unwrap();
+                url.ends_with("/pulls/7/reviews")
+                    && json["event"] == "REQUEST_CHANGES"
+                    && json["comments"][0]["path"] == "src/a.rs"
+                    && json["comments"][0]["line"] == 12
+                    && json["comments"][0]["side"] == "LEFT"
+                    && json.get("body").is_none()
+            })
+            .returning(|_, _, _| Ok(HttpResponse {
+                status: 200,
+                body: "{}".to_string(),
+            }));
+
+        let client = GitHubClient::with_http_client(
+            "test/repo",
+            Some("test-token".to_string()),
+            mock,
+        );
+        let comments = [ReviewLineComment {
+            path: "src/a.rs".to_string(),
+            line: 12,
+            side: ReviewSide::Left,
+            body: "Keep this".to_string(),
+        }];
+        let result = client.create_review(7, ReviewEvent::RequestChanges, None, &comments);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_create_review_requires_auth() {
+        let mock = MockHttpClient::new();
+        let client = GitHubClient::with_http_client("test/repo", None, mock);
+        let result = client.create_review(7, ReviewEvent::Approve, None, &[]);
+
+        assert!(result.unwrap_err().to_string().contains("Authentication required"));
+    }
+
+    #[test]
+    fn test_find_open_pull_request_filters_by_owner_and_branch() {
+        let mut mock = MockHttpClient::new();
+        mock.expect_get()
+            .withf(|url: &str, _: &Headers| url.contains("state=open&head=test:feat/x"))
+            .returning(|_, _| Ok(HttpResponse {
+                status: 200,
+                body: r#"[{"number": 5, "title": "X", "body": null, "state": "open",
+                    "html_url": "https://github.com/test/repo/pull/5",
+                    "head": {"ref": "feat/x", "sha": "abc"},
+                    "base": {"ref": "main", "sha": "def"}}]"#.to_string(),
+            }));
+
+        let client = GitHubClient::with_http_client("test/repo", None, mock);
+        let pr = client.find_open_pull_request("feat/x").unwrap();
+
+        assert_eq!(pr.map(|pr| pr.number), Some(5));
+    }
+
+    #[test]
+    fn test_branch_protection_from_classic_and_rulesets() {
+        let mut mock = MockHttpClient::new();
+        mock.expect_get()
+            .withf(|url: &str, _: &Headers| url.ends_with("/branches/main/protection"))
+            .returning(|_, _| Ok(HttpResponse {
+                status: 200,
+                body: r#"{"required_pull_request_reviews": {"required_approving_review_count": 1}}"#.to_string(),
+            }));
+        mock.expect_get()
+            .withf(|url: &str, _: &Headers| url.ends_with("/rules/branches/main"))
+            .returning(|_, _| Ok(HttpResponse {
+                status: 200,
+                body: r#"[{"type": "deletion"}, {"type": "pull_request", "parameters": {"required_approving_review_count": 2}}]"#.to_string(),
+            }));
+
+        let client = GitHubClient::with_http_client("test/repo", None, mock);
+        let protection = client.get_branch_protection("main").unwrap();
+
+        assert_eq!(
+            protection,
+            BranchProtection { requires_pull_request: true, required_approvals: 2 }
+        );
+    }
+
+    #[test]
+    fn test_unprotected_branch() {
+        let mut mock = MockHttpClient::new();
+        mock.expect_get()
+            .withf(|url: &str, _: &Headers| url.ends_with("/protection"))
+            .returning(|_, _| Ok(HttpResponse {
+                status: 404,
+                body: r#"{"message": "Branch not protected"}"#.to_string(),
+            }));
+        mock.expect_get()
+            .withf(|url: &str, _: &Headers| url.contains("/rules/branches/"))
+            .returning(|_, _| Ok(HttpResponse { status: 200, body: "[]".to_string() }));
+
+        let client = GitHubClient::with_http_client("test/repo", None, mock);
+
+        assert_eq!(client.get_branch_protection("main").unwrap(), BranchProtection::default());
+    }
+
+    #[test]
+    fn test_branch_protection_rules_error() {
+        let mut mock = MockHttpClient::new();
+        mock.expect_get()
+            .returning(|_, _| Ok(HttpResponse { status: 500, body: String::new() }));
+
+        let client = GitHubClient::with_http_client("test/repo", None, mock);
+        let err = client.get_branch_protection("main").unwrap_err();
+
+        assert!(err.to_string().contains("HTTP 500"), "{err}");
+    }
 }
@@ -2,26 +2,84 @@
 //!
 //! Provides a TUI component for selecting GitHub issues.
 
-use crate::github::Issue;
-use crate::theme::theme;
-use crossterm::event::KeyCode;
+use crate::github::{format_age, Issue};
+use crate::overlay::is_degenerate;
+use crate::template::{issue_subtask_items, normalize_issue_body};
+use crate::theme::{adapt_color, theme};
+use chrono::{DateTime, Utc};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
-    layout::Rect,
-    style::Style,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
 };
 
+/// Minimum inner width for placing the detail pane beside the list.
+/// Narrower pickers stack the detail pane below the list instead.
+const SIDE_BY_SIDE_MIN_WIDTH: u16 = 100;
+
 /// Result of issue picker interaction
 #[derive(Debug, Clone)]
 pub enum IssuePickerResult {
     /// User selected an issue
     Selected(Issue),
+    /// User selected an issue to split into one worker per sub-task
+    SelectedAsPlan(Issue),
+    /// User selected an issue to take through worker → review → merge → cleanup
+    SelectedAsFullPlan(Issue),
     /// User cancelled the picker
     Cancel,
     /// User requested refresh
     Refresh,
+    /// User asked to open the highlighted issue (its URL) in the browser
+    OpenInBrowser(String),
+}
+
+/// Order of the issue list, cycled with `s`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IssueSort {
+    /// Ascending issue number
+    #[default]
+    Number,
+    /// Most recently updated first
+    Recent,
+    /// Most comments first
+    Comments,
+}
+
+impl IssueSort {
+    /// The next order in the cycle
+    pub fn next(self) -> Self {
+        match self {
+            Self::Number => Self::Recent,
+            Self::Recent => Self::Comments,
+            Self::Comments => Self::Number,
+        }
+    }
+
+    /// Short name shown in the help line
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Number => "number",
+            Self::Recent => "recent",
+            Self::Comments => "comments",
+        }
+    }
+
+    /// Sort issues in this order (ties put the newer issue first)
+    pub fn sort(self, issues: &mut [Issue]) {
+        issues.sort_by(|a, b| match self {
+            Self::Number => a.number.cmp(&b.number),
+            Self::Recent => b
+                .updated_at
+                .or(b.created_at)
+                .cmp(&a.updated_at.or(a.created_at))
+                .then(b.number.cmp(&a.number)),
+            Self::Comments => b.comments.cmp(&a.comments).then(b.number.cmp(&a.number)),
+        });
+    }
 }
 
 /// Issue picker UI component
@@ -43,6 +101,12 @@ pub struct IssuePicker {
 
     /// List state for ratatui
     list_state: ListState,
+
+    /// Scroll offset of the detail pane
+    detail_scroll: u16,
+
+    /// Order of the issue list
+    sort: IssueSort,
 }
 
 impl IssuePicker {
@@ -58,15 +122,19 @@ impl IssuePicker {
             loading: false,
             error: None,
             list_state,
+            detail_scroll: 0,
+            sort: IssueSort::default(),
         }
     }
 
-    /// Set the list of issues
-    pub fn set_issues(&mut self, issues: Vec<Issue>) {
+    /// Set the list of issues (sorted in the current order)
+    pub fn set_issues(&mut self, mut issues: Vec<Issue>) {
+        self.sort.sort(&mut issues);
         self.issues = issues;
         self.selected_index = 0;
         self.scroll_offset = 0;
         self.list_state.select(Some(0));
+        self.detail_scroll = 0;
         self.error = None;
     }
 
@@ -89,11 +157,44 @@ impl IssuePicker {
         self.issues.get(self.selected_index)
     }
 
+    /// Current order of the issue list
+    pub fn sort(&self) -> IssueSort {
+        self.sort
+    }
+
+    /// Switch to the next sort order, keeping the selected issue selected
+    fn cycle_sort(&mut self) {
+        let selected = self.selected().map(|issue| issue.number);
+        self.sort = self.sort.next();
+        self.sort.sort(&mut self.issues);
+        self.selected_index = selected
+            .and_then(|number| self.issues.iter().position(|issue| issue.number == number))
+            .unwrap_or(0);
+        self.list_state.select(Some(self.selected_index));
+    }
+
+    /// Handle a key event, including modified keys (`Ctrl+Enter`)
+    pub fn handle_key_event(&mut self, key: KeyEvent) -> Option<IssuePickerResult> {
+        if key.code == KeyCode::Enter && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return self.handle_key(KeyCode::Char('P'));
+        }
+        self.handle_key(key.code)
+    }
+
     /// Handle key input
     ///
     /// Returns `Some(result)` if an action should be taken,
     /// `None` if the picker should continue.
     pub fn handle_key(&mut self, key: KeyCode) -> Option<IssuePickerResult> {
+        let previous = self.selected_index;
+        let result = self.handle_key_inner(key);
+        if self.selected_index != previous {
+            self.detail_scroll = 0;
+        }
+        result
+    }
+
+    fn handle_key_inner(&mut self, key: KeyCode) -> Option<IssuePickerResult> {
         match key {
             KeyCode::Up | KeyCode::Char('k') => {
                 self.move_up();
@@ -106,8 +207,33 @@ impl IssuePicker {
             KeyCode::Enter => {
                 self.selected().cloned().map(IssuePickerResult::Selected)
             }
+            // Spawn as a plan, falling back to a single worker without a checklist
+            KeyCode::Char('p') => self.selected().cloned().map(|issue| {
+                if subtask_count(&issue) > 0 {
+                    IssuePickerResult::SelectedAsPlan(issue)
+                } else {
+                    IssuePickerResult::Selected(issue)
+                }
+            }),
+            // Full lifecycle plan; `P` for terminals that can't send Ctrl+Enter
+            KeyCode::Char('P') => self.selected().cloned().map(IssuePickerResult::SelectedAsFullPlan),
+            KeyCode::Char('J') => {
+                self.detail_scroll = self.detail_scroll.saturating_add(1);
+                None
+            }
+            KeyCode::Char('K') => {
+                self.detail_scroll = self.detail_scroll.saturating_sub(1);
+                None
+            }
             KeyCode::Esc | KeyCode::Char('q') => Some(IssuePickerResult::Cancel),
             KeyCode::Char('r') => Some(IssuePickerResult::Refresh),
+            KeyCode::Char('o') => self
+                .selected()
+                .map(|issue| IssuePickerResult::OpenInBrowser(issue.html_url.clone())),
+            KeyCode::Char('s') => {
+                self.cycle_sort();
+                None
+            }
             KeyCode::Home => {
                 self.selected_index = 0;
                 self.list_state.select(Some(0));
@@ -155,6 +281,9 @@ impl IssuePicker {
 
     /// Render the issue picker
     pub fn render(&mut self, f: &mut Frame, area: Rect) {
+        if is_degenerate(area) {
+            return;
+        }
         let t = theme();
 
         // Clear background
@@ -193,47 +322,62 @@ impl IssuePicker {
         }
 
         // Calculate areas
-        let list_height = inner_area.height.saturating_sub(2);
-        let list_area = Rect {
+        let content_height = inner_area.height.saturating_sub(2);
+        let content_area = Rect {
             x: inner_area.x,
             y: inner_area.y,
             width: inner_area.width,
-            height: list_height,
+            height: content_height,
         };
         let help_area = Rect {
             x: inner_area.x,
-            y: inner_area.y + list_height,
+            y: inner_area.y + content_height,
             width: inner_area.width,
-            height: 2,
+            height: 2.min(inner_area.height),
+        };
+
+        // List and detail side by side, or stacked on narrow terminals
+        let direction = if content_area.width >= SIDE_BY_SIDE_MIN_WIDTH {
+            Direction::Horizontal
+        } else {
+            Direction::Vertical
         };
+        let panes = Layout::default()
+            .direction(direction)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(content_area);
+        let list_area = panes[0];
+        let detail_area = panes[1];
 
         // Render issue list
+        let now = Utc::now();
         let items: Vec<ListItem> = self
             .issues
             .iter()
             .map(|issue| {
-                let labels = if issue.labels.is_empty() {
-                    String::new()
-                } else {
-                    format!(
-                        "[{}] ",
-                        issue
-                            .labels
-                            .iter()
-                            .map(|l| l.name.as_str())
-                            .collect::<Vec<_>>()
-                            .join(", ")
-                    )
-                };
-
-                let line = Line::from(vec![
-                    Span::styled(
-                        format!("#{:<5} ", issue.number),
-                        Style::default().fg(t.issue_number()),
-                    ),
-                    Span::styled(labels, Style::default().fg(t.issue_label())),
-                    Span::raw(&issue.title),
-                ]);
+                let mut spans = vec![Span::styled(
+                    format!("#{:<5} ", issue.number),
+                    Style::default().fg(t.issue_number()),
+                )];
+                if !issue.labels.is_empty() {
+                    spans.push(Span::styled("[", t.style_text_muted()));
+                    for (i, label) in issue.labels.iter().enumerate() {
+                        if i > 0 {
+                            spans.push(Span::styled(", ", t.style_text_muted()));
+                        }
+                        spans.push(Span::styled(
+                            label.name.as_str(),
+                            Style::default().fg(adapt_color(label_color(&label.color))),
+                        ));
+                    }
+                    spans.push(Span::styled("] ", t.style_text_muted()));
+                }
+                spans.push(Span::raw(&issue.title));
+                if let Some(age) = issue.age(now) {
+                    spans.push(Span::styled(format!("  {age}"), t.style_text_muted()));
+                }
+
+                let line = Line::from(spans);
 
                 ListItem::new(line)
             })
@@ -245,20 +389,57 @@ impl IssuePicker {
 
         f.render_stateful_widget(list, list_area, &mut self.list_state);
 
+        // Render detail pane for the highlighted issue
+        if let Some(issue) = self.issues.get(self.selected_index) {
+            let max_scroll = detail_line_count(issue, detail_area.width, now).saturating_sub(1);
+            self.detail_scroll = self.detail_scroll.min(max_scroll as u16);
+            let detail = Paragraph::new(detail_lines(issue, now))
+                .block(
+                    Block::default()
+                        .borders(if direction == Direction::Horizontal {
+                            Borders::LEFT
+                        } else {
+                            Borders::TOP
+                        })
+                        .border_style(t.style_border_muted()),
+                )
+                .wrap(Wrap { trim: false })
+                .scroll((self.detail_scroll, 0));
+            f.render_widget(detail, detail_area);
+        }
+
         // Render help text
-        let help_text = Line::from(vec![
+        let subtasks = self.selected().map_or(0, subtask_count);
+        let mut help_spans = vec![
             Span::styled("[", t.style_text_muted()),
             Span::styled("Up/Down", t.style_key()),
             Span::styled("] Navigate  [", t.style_text_muted()),
             Span::styled("Enter", t.style_key()),
             Span::styled("] Select  [", t.style_text_muted()),
+            Span::styled("Ctrl+Enter/P", t.style_key()),
+            Span::styled("] Full plan  [", t.style_text_muted()),
+        ];
+        if subtasks > 0 {
+            help_spans.push(Span::styled("p", t.style_key()));
+            help_spans.push(Span::styled(
+                format!("] Plan ({subtasks} sub-tasks)  ["),
+                t.style_text_muted(),
+            ));
+        }
+        help_spans.extend([
+            Span::styled("J/K", t.style_key()),
+            Span::styled("] Scroll detail  [", t.style_text_muted()),
+            Span::styled("s", t.style_key()),
+            Span::styled(format!("] Sort: {}  [", self.sort.label()), t.style_text_muted()),
+            Span::styled("o", t.style_key()),
+            Span::styled("] Open in browser  [", t.style_text_muted()),
             Span::styled("r", t.style_key()),
             Span::styled("] Refresh  [", t.style_text_muted()),
             Span::styled("Esc", t.style_key()),
             Span::styled("] Cancel", t.style_text_muted()),
         ]);
 
-        let help = Paragraph::new(help_text);
+        let help = Paragraph::new(Line::from(help_spans));
         f.render_widget(help, help_area);
     }
 
@@ -289,19 +470,119 @@ impl Default for IssuePicker {
     }
 }
 
-/// Calculate centered popup area
-pub fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
-    let popup_width = area.width * percent_x / 100;
-    let popup_height = area.height * percent_y / 100;
+/// Build the detail pane lines for an issue: title, labels, URL, activity and body
+fn detail_lines(issue: &Issue, now: DateTime<Utc>) -> Vec<Line<'_>> {
+    let t = theme();
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(
+                format!("#{} ", issue.number),
+                Style::default().fg(t.issue_number()),
+            ),
+            Span::styled(
+                issue.title.as_str(),
+                t.style_text().add_modifier(Modifier::BOLD),
+            ),
+        ]),
+    ];
+
+    if !issue.labels.is_empty() {
+        let mut spans = Vec::new();
+        for label in &issue.labels {
+            let color = label_color(&label.color);
+            spans.push(Span::styled(
+                format!(" {} ", label.name),
+                Style::default().fg(contrast_color(color)).bg(adapt_color(color)),
+            ));
+            spans.push(Span::raw(" "));
+        }
+        lines.push(Line::from(spans));
+    }
 
-    let x = (area.width - popup_width) / 2;
-    let y = (area.height - popup_height) / 2;
+    lines.push(Line::from(Span::styled(
+        issue.html_url.as_str(),
+        t.style_text_muted(),
+    )));
+    if let Some(activity) = activity_summary(issue, now) {
+        lines.push(Line::from(Span::styled(activity, t.style_text_muted())));
+    }
+    lines.push(Line::from(""));
+
+    match issue.body.as_deref().map(normalize_issue_body) {
+        Some(body) if !body.is_empty() => {
+            lines.extend(
+                body.lines()
+                    .map(|l| Line::from(Span::styled(l.to_string(), t.style_text_secondary()))),
+            );
+        }
+        _ => {
+            lines.push(Line::from(Span::styled("(no description)", t.style_text_muted())));
+        }
+    }
+
+    lines
+}
 
-    Rect {
-        x: area.x + x,
-        y: area.y + y,
-        width: popup_width,
-        height: popup_height,
+/// "opened 3d ago · updated 2h ago · 4 comments", or None without timestamps
+fn activity_summary(issue: &Issue, now: DateTime<Utc>) -> Option<String> {
+    let opened = issue.created_at?;
+    let mut parts = vec![format!("opened {}", format_age(opened, now))];
+    if let Some(updated) = issue.updated_at.filter(|&updated| updated > opened) {
+        parts.push(format!("updated {}", format_age(updated, now)));
+    }
+    match issue.comments {
+        0 => {}
+        1 => parts.push("1 comment".to_string()),
+        n => parts.push(format!("{n} comments")),
+    }
+    Some(parts.join(" · "))
+}
+
+/// Number of unchecked sub-tasks in the issue's checklist
+fn subtask_count(issue: &Issue) -> usize {
+    issue.body.as_deref().map_or(0, |body| issue_subtask_items(body).len())
+}
+
+/// Approximate number of wrapped lines in the detail pane (used to clamp scrolling)
+fn detail_line_count(issue: &Issue, width: u16, now: DateTime<Utc>) -> usize {
+    let width = usize::from(width.max(1));
+    detail_lines(issue, now)
+        .iter()
+        .map(|line| line.width().div_ceil(width).max(1))
+        .sum()
+}
+
+/// Convert a GitHub label color (hex without `#`) into a terminal color
+///
+/// Falls back to the theme's label color when the hex string is malformed.
+pub fn label_color(hex: &str) -> Color {
+    parse_hex_color(hex).unwrap_or_else(|| theme().issue_label())
+}
+
+/// Parse a 6-digit hex color (with or without a leading `#`)
+fn parse_hex_color(hex: &str) -> Option<Color> {
+    let hex = hex.trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+    let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+    let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+    Some(Color::Rgb(r, g, b))
+}
+
+/// Pick black or white text for readability on the given background
+fn contrast_color(bg: Color) -> Color {
+    match bg {
+        Color::Rgb(r, g, b) => {
+            let luma = 299 * u32::from(r) + 587 * u32::from(g) + 114 * u32::from(b);
+            if luma > 128_000 {
+                Color::Black
+            } else {
+                Color::White
+            }
+        }
+        _ => Color::Black,
     }
 }
 
@@ -322,6 +603,9 @@ mod tests {
                 }],
                 state: "open".to_string(),
                 html_url: "https://github.com/test/repo/issues/123".to_string(),
+                comments: 0,
+                created_at: None,
+                updated_at: None,
             },
             Issue {
                 number: 456,
@@ -333,6 +617,9 @@ mod tests {
                 }],
                 state: "open".to_string(),
                 html_url: "https://github.com/test/repo/issues/456".to_string(),
+                comments: 0,
+                created_at: None,
+                updated_at: None,
             },
             Issue {
                 number: 789,
@@ -344,6 +631,9 @@ mod tests {
                 }],
                 state: "open".to_string(),
                 html_url: "https://github.com/test/repo/issues/789".to_string(),
+                comments: 0,
+                created_at: None,
+                updated_at: None,
             },
         ]
     }
@@ -418,6 +708,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_select_as_plan() {
+        let mut picker = IssuePicker::new();
+        let mut issues = create_test_issues();
+        issues[0].body = Some("- [ ] one\n- [ ] two".to_string());
+        picker.set_issues(issues);
+
+        let result = picker.handle_key(KeyCode::Char('p'));
+        assert!(matches!(result, Some(IssuePickerResult::SelectedAsPlan(issue)) if issue.number == 123));
+
+        // Without a checklist `p` falls back to a plain selection
+        picker.handle_key(KeyCode::Down);
+        let result = picker.handle_key(KeyCode::Char('p'));
+        assert!(matches!(result, Some(IssuePickerResult::Selected(issue)) if issue.number == 456));
+    }
+
+    #[test]
+    fn test_select_as_full_plan() {
+        let mut picker = IssuePicker::new();
+        picker.set_issues(create_test_issues());
+
+        let ctrl_enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::CONTROL);
+        let result = picker.handle_key_event(ctrl_enter);
+        assert!(matches!(result, Some(IssuePickerResult::SelectedAsFullPlan(issue)) if issue.number == 123));
+
+        picker.handle_key(KeyCode::Down);
+        let result = picker.handle_key(KeyCode::Char('P'));
+        assert!(matches!(result, Some(IssuePickerResult::SelectedAsFullPlan(issue)) if issue.number == 456));
+
+        // Plain Enter still spawns a single worker
+        let result = picker.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(matches!(result, Some(IssuePickerResult::Selected(issue)) if issue.number == 456));
+    }
+
     #[test]
     fn test_cancel() {
         let mut picker = IssuePicker::new();
@@ -439,6 +763,20 @@ mod tests {
         assert!(matches!(result, Some(IssuePickerResult::Refresh)));
     }
 
+    #[test]
+    fn test_open_in_browser() {
+        let mut picker = IssuePicker::new();
+        assert!(picker.handle_key(KeyCode::Char('o')).is_none());
+
+        picker.set_issues(create_test_issues());
+        picker.handle_key(KeyCode::Down);
+        let result = picker.handle_key(KeyCode::Char('o'));
+        assert!(matches!(
+            result,
+            Some(IssuePickerResult::OpenInBrowser(url)) if url == "https://github.com/test/repo/issues/456"
+        ));
+    }
+
     #[test]
     fn test_loading_state() {
         let mut picker = IssuePicker::new();
@@ -477,19 +815,112 @@ mod tests {
     }
 
     #[test]
-    fn test_centered_rect() {
-        let area = Rect {
-            x: 0,
-            y: 0,
-            width: 100,
-            height: 50,
+    fn test_detail_scroll_resets_on_selection_change() {
+        let mut picker = IssuePicker::new();
+        picker.set_issues(create_test_issues());
+
+        picker.handle_key(KeyCode::Char('J'));
+        picker.handle_key(KeyCode::Char('J'));
+        assert_eq!(picker.detail_scroll, 2);
+
+        picker.handle_key(KeyCode::Char('K'));
+        assert_eq!(picker.detail_scroll, 1);
+
+        picker.handle_key(KeyCode::Down);
+        assert_eq!(picker.detail_scroll, 0);
+    }
+
+    #[test]
+    fn test_label_color() {
+        assert_eq!(label_color("d73a4a"), Color::Rgb(0xd7, 0x3a, 0x4a));
+        assert_eq!(label_color("#0075ca"), Color::Rgb(0x00, 0x75, 0xca));
+        assert_eq!(label_color("zzzzzz"), theme().issue_label());
+        assert_eq!(label_color("fff"), theme().issue_label());
+    }
+
+    #[test]
+    fn test_contrast_color() {
+        assert_eq!(contrast_color(Color::Rgb(255, 255, 255)), Color::Black);
+        assert_eq!(contrast_color(Color::Rgb(0, 0, 0)), Color::White);
+    }
+
+    #[test]
+    fn test_detail_line_count() {
+        let issues = create_test_issues();
+        let now = Utc::now();
+        // title + labels + url + blank + 1 body line
+        assert_eq!(detail_line_count(&issues[0], 200, now), 5);
+        // body None renders a placeholder line
+        assert_eq!(detail_line_count(&issues[2], 200, now), 5);
+        // narrow panes wrap long lines
+        assert!(detail_line_count(&issues[0], 10, now) > 5);
+    }
+
+    fn timestamp(s: &str) -> Option<DateTime<Utc>> {
+        Some(s.parse().unwrap())
+    }
+
+    /// 123: oldest, most comments; 456: updated most recently; 789: no timestamps
+    fn dated_issues() -> Vec<Issue> {
+        let mut issues = create_test_issues();
+        issues[0].created_at = timestamp("2024-01-01T00:00:00Z");
+        issues[0].updated_at = timestamp("2024-05-01T00:00:00Z");
+        issues[0].comments = 9;
+        issues[1].created_at = timestamp("2024-06-01T00:00:00Z");
+        issues[1].updated_at = timestamp("2024-06-10T00:00:00Z");
+        issues[1].comments = 2;
+        issues
+    }
+
+    #[test]
+    fn test_sort_orders() {
+        let numbers = |sort: IssueSort| {
+            let mut issues = dated_issues();
+            sort.sort(&mut issues);
+            issues.iter().map(|issue| issue.number).collect::<Vec<_>>()
         };
+        assert_eq!(numbers(IssueSort::Number), vec![123, 456, 789]);
+        assert_eq!(numbers(IssueSort::Recent), vec![456, 123, 789]);
+        assert_eq!(numbers(IssueSort::Comments), vec![123, 456, 789]);
+    }
 
-        let popup = centered_rect(80, 60, area);
+    #[test]
+    fn test_sort_key_cycles_and_keeps_selection() {
+        let mut picker = IssuePicker::new();
+        picker.set_issues(dated_issues());
+        assert_eq!(picker.sort(), IssueSort::Number);
+        assert_eq!(picker.selected().unwrap().number, 123);
 
-        assert_eq!(popup.width, 80);
-        assert_eq!(popup.height, 30);
-        assert_eq!(popup.x, 10);
-        assert_eq!(popup.y, 10);
+        picker.handle_key(KeyCode::Char('s'));
+        assert_eq!(picker.sort(), IssueSort::Recent);
+        assert_eq!(picker.selected().unwrap().number, 123);
+        assert_eq!(picker.selected_index, 1);
+
+        picker.handle_key(KeyCode::Char('s'));
+        assert_eq!(picker.sort(), IssueSort::Comments);
+        assert_eq!(picker.selected_index, 0);
+
+        // A refresh keeps the chosen order
+        picker.set_issues(dated_issues());
+        assert_eq!(picker.selected().unwrap().number, 123);
+
+        picker.handle_key(KeyCode::Char('s'));
+        assert_eq!(picker.sort(), IssueSort::Number);
+    }
+
+    #[test]
+    fn test_activity_summary() {
+        let now = "2024-06-15T12:00:00Z".parse().unwrap();
+        let issues = dated_issues();
+        assert_eq!(
+            activity_summary(&issues[0], now).unwrap(),
+            "opened 5mo ago · updated 1mo ago · 9 comments"
+        );
+        assert_eq!(activity_summary(&issues[2], now), None);
+
+        let mut fresh = issues[1].clone();
+        fresh.updated_at = fresh.created_at;
+        fresh.comments = 1;
+        assert_eq!(activity_summary(&fresh, now).unwrap(), "opened 14d ago · 1 comment");
     }
 }
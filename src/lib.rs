@@ -15,6 +15,7 @@
 //! - [`diffview`] - Diff viewer for reviewing changes
 //! - [`issue_picker`] - GitHub issue selection UI
 //! - [`theme`] - Cyberpunk color theme
+//! - [`text`] - Display-width aware truncation and padding
 //!
 //! ## Git Operations
 //! - [`merge`] - Git merge operations manager
@@ -34,12 +35,19 @@ pub mod stream_parser;
 pub mod debug;
 pub mod lock;
 pub mod mcp;
+pub mod desktop;
+pub mod browser;
+pub mod update_check;
+pub mod control;
+pub mod events;
 
 // UI Components
 pub mod dialog;
 pub mod statusbar;
 pub mod diffview;
 pub mod issue_picker;
+pub mod overlay;
+pub mod text;
 pub mod theme;
 
 // Git Operations
@@ -47,6 +55,7 @@ pub mod merge;
 
 // GitHub Integration
 pub mod config;
+pub mod messages;
 pub mod github;
 pub mod template;
 
@@ -54,20 +63,31 @@ pub mod template;
 pub mod anthropic;
 
 // Re-export commonly used types
-pub use worktree::{WorktreeInfo, WorktreeManager};
-pub use plan::{Plan, PlanManager, Task, TaskAction, TaskResult, TaskStatus};
+pub use worktree::{RemoveOutcome, WorktreeInfo, WorktreeManager, WorktreeNaming};
+pub use plan::{ArchivedPlan, GateStatus, Plan, PlanError, PlanManager, Task, TaskAction, TaskResult, TaskStatus};
 pub use dialog::{DialogResult, InputDialog};
-pub use diffview::DiffView;
-pub use merge::{MergeManager, MergePreview};
+pub use diffview::{DiffSide, DiffView, FileChange, FileStat, LineLocation, LineOverflow};
+pub use merge::{MergeManager, MergePreview, PushError, UpdateOutcome};
 pub use statusbar::{AgentStatusInfo, AgentStatusKind, StatusBar};
-pub use config::{Config, GitHubConfig, AnthropicConfig, KeyBindings};
-pub use github::{GitHubClient, Issue, Label};
+pub use config::{
+    AgentConfig, AnthropicConfig, BuildCommands, Config, ConfigEntry, ConfigSource, ErrorBell,
+    GitHubConfig, KeyBindings, MergeMode, PromptsConfig, PushConfig, SplitDirection, UiConfig,
+    UpdateStrategy,
+};
+pub use github::{
+    BranchProtection, GitHubClient, Issue, Label, ReviewEvent, ReviewLineComment, ReviewSide, validate_repository,
+};
 pub use issue_picker::{IssuePicker, IssuePickerResult};
-pub use template::{TaskTemplate, render_task, suggest_branch_name, suggest_commit_message};
+pub use template::{
+    AGENT_NAME_MAX_WIDTH, AgentNameStyle, SlugOptions, TaskTemplate, WorkerPrompt, agent_name, branch_prefix_for, checklist_plan, fill_placeholders, issue_plan,
+    issue_subtask_items, issue_subtasks, lifecycle_plan, load_pull_request_template, normalize_issue_body, pull_request_body, render_task, suggest_branch_name, suggest_branch_name_unique, suggest_branch_name_with, suggest_commit_message,
+    suggest_commit_subject,
+};
 pub use anthropic::AnthropicClient;
 pub use theme::{
     theme, set_theme, set_theme_by_id, set_theme_from_str, create_theme,
     available_themes, current_theme_id, current_theme_id_str, get_theme_colors,
+    adapt_color, color_depth, degrade_color, rgb_to_ansi16, rgb_to_ansi256, set_color_depth, ColorDepth,
     ColorTheme, ThemeColors, ThemeId,
     CyberpunkTheme, MonokaiTheme, DraculaTheme, NordTheme, ArcticAuroraTheme, MinimalTheme,
     CYBERPUNK, MONOKAI, DRACULA, NORD, ARCTIC_AURORA, MINIMAL,
@@ -76,4 +96,5 @@ pub use theme::{
 pub use theme::Theme;
 pub use stream_parser::{StreamEvent, StreamParser, parse_line as parse_stream_line};
 pub use lock::LockFile;
+pub use text::{display_width, justify_line, pad_to_width, truncate_spans, truncate_to_width};
 pub use mcp::McpServer;
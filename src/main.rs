@@ -13,10 +13,13 @@ use anyhow::Result;
 use cctakt::debug;
 use clap::Parser;
 use cli::{Cli, Commands};
-use commands::{run_init, run_issues, run_mcp, run_plan, run_status, run_tui};
+use commands::{
+    run_config, run_init, run_issues, run_mcp, run_plan, run_plan_command, run_status, run_suggest_commit,
+    run_tui, run_worktree, Verbosity,
+};
 
 fn main() -> Result<()> {
-    // Initialize debug logging (only in debug builds)
+    // Initialize debug logging (CCTAKT_LOG, or on by default in debug builds)
     debug::init();
 
     let cli = Cli::parse();
@@ -24,16 +27,26 @@ fn main() -> Result<()> {
     match cli.command {
         Some(Commands::Init { force }) => run_init(force),
         Some(Commands::Status) => run_status(),
-        Some(Commands::Issues { labels, state }) => run_issues(labels, state),
-        Some(Commands::Run { plan }) => run_plan(plan),
+        Some(Commands::Issues { labels, state }) => run_issues(cli.repo, labels, state),
+        Some(Commands::Run {
+            plan,
+            quiet,
+            verbose,
+            timeout,
+        }) => run_plan(plan, Verbosity::from_flags(quiet, verbose), timeout),
         Some(Commands::Mcp) => run_mcp(),
-        None => run_tui(),
+        Some(Commands::Config { action }) => run_config(action),
+        Some(Commands::Worktree { action }) => run_worktree(action),
+        Some(Commands::Plan { action }) => run_plan_command(cli.repo, action),
+        Some(Commands::SuggestCommit { issue }) => run_suggest_commit(cli.repo, issue),
+        Some(Commands::Attach { branch }) => run_tui(cli.no_confirm, cli.repo, Some(branch)),
+        None => run_tui(cli.no_confirm, cli.repo, None),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::app::types::{AppMode, MergeQueue, Notification, ReviewFocus, ReviewState};
+    use crate::app::types::{AppMode, MergeQueue, Notification, ReviewDiff, ReviewFocus, ReviewState};
     use crate::git_utils::{get_commit_log, get_worker_commits, parse_github_url};
     use cctakt::{
         github::Issue, Config, DiffView, GitHubClient, IssuePicker, IssuePickerResult,
@@ -130,6 +143,9 @@ mod tests {
             labels: vec![],
             state: "open".to_string(),
             html_url: "https://github.com/test/repo/issues/42".to_string(),
+            comments: 0,
+            created_at: None,
+            updated_at: None,
         };
 
         let branch = suggest_branch_name(&issue, "cctakt");
@@ -149,6 +165,9 @@ mod tests {
             labels: vec![],
             state: "open".to_string(),
             html_url: "https://github.com/test/repo/issues/123".to_string(),
+            comments: 0,
+            created_at: None,
+            updated_at: None,
         };
 
         let branch = suggest_branch_name(&issue, "fix");
@@ -182,6 +201,9 @@ mod tests {
                 labels: vec![],
                 state: "open".to_string(),
                 html_url: "https://github.com/test/repo/issues/1".to_string(),
+                comments: 0,
+                created_at: None,
+                updated_at: None,
             },
             Issue {
                 number: 2,
@@ -190,6 +212,9 @@ mod tests {
                 labels: vec![],
                 state: "open".to_string(),
                 html_url: "https://github.com/test/repo/issues/2".to_string(),
+                comments: 0,
+                created_at: None,
+                updated_at: None,
             },
         ];
 
@@ -208,6 +233,9 @@ mod tests {
                 labels: vec![],
                 state: "open".to_string(),
                 html_url: "https://github.com/test/repo/issues/1".to_string(),
+                comments: 0,
+                created_at: None,
+                updated_at: None,
             },
             Issue {
                 number: 2,
@@ -216,6 +244,9 @@ mod tests {
                 labels: vec![],
                 state: "open".to_string(),
                 html_url: "https://github.com/test/repo/issues/2".to_string(),
+                comments: 0,
+                created_at: None,
+                updated_at: None,
             },
         ];
         picker.set_issues(issues);
@@ -258,6 +289,9 @@ mod tests {
             labels: vec![],
             state: "open".to_string(),
             html_url: "https://github.com/test/repo/issues/42".to_string(),
+            comments: 0,
+            created_at: None,
+            updated_at: None,
         }]);
 
         let result = picker.handle_key(KeyCode::Enter);
@@ -306,20 +340,34 @@ mod tests {
     #[test]
     fn test_review_state_creation() {
         let state = ReviewState {
-            agent_index: 0,
+            agent_id: Some(0),
             branch: "feature/test".to_string(),
+            base: "main".to_string(),
             worktree_path: PathBuf::from("/tmp/worktree"),
             diff_view: DiffView::new("+ added line\n- removed line".to_string()),
             commit_log: "abc1234 Initial commit".to_string(),
             files_changed: 5,
             insertions: 100,
             deletions: 20,
+            ahead_by: 0,
+            behind_by: 0,
             conflicts: vec!["src/main.rs".to_string()],
+            uncommitted: vec![],
+            nonconforming_commits: vec![],
             focus: ReviewFocus::default(),
             summary_scroll: 0,
+            commits_view: None,
+            base_dialog: None,
+            search_input: None,
+            reject_dialog: None,
+            comments: Vec::new(),
+            comment_dialog: None,
+            post_review_pr: None,
+            draft_pr: false,
+            diff_kind: ReviewDiff::Branch,
         };
 
-        assert_eq!(state.agent_index, 0);
+        assert_eq!(state.agent_id, Some(0));
         assert_eq!(state.branch, "feature/test");
         assert_eq!(state.files_changed, 5);
         assert_eq!(state.insertions, 100);
@@ -457,6 +505,7 @@ mod tests {
             commits: vec!["abc123 first commit".to_string()],
             pr_number: Some(42),
             pr_url: Some("https://github.com/owner/repo/pull/42".to_string()),
+            output: None,
         };
 
         assert_eq!(result.commits.len(), 1);
@@ -513,17 +562,31 @@ mod tests {
     #[test]
     fn test_review_state_empty_conflicts() {
         let state = ReviewState {
-            agent_index: 0,
+            agent_id: Some(0),
             branch: "test".to_string(),
+            base: "main".to_string(),
             worktree_path: PathBuf::from("/tmp"),
             diff_view: DiffView::new(String::new()),
             commit_log: String::new(),
             files_changed: 0,
             insertions: 0,
             deletions: 0,
+            ahead_by: 0,
+            behind_by: 0,
             conflicts: vec![],
+            uncommitted: vec![],
+            nonconforming_commits: vec![],
             focus: ReviewFocus::default(),
             summary_scroll: 0,
+            commits_view: None,
+            base_dialog: None,
+            search_input: None,
+            reject_dialog: None,
+            comments: Vec::new(),
+            comment_dialog: None,
+            post_review_pr: None,
+            draft_pr: false,
+            diff_kind: ReviewDiff::Branch,
         };
 
         assert!(state.conflicts.is_empty());
@@ -533,21 +596,35 @@ mod tests {
     #[test]
     fn test_review_state_multiple_conflicts() {
         let state = ReviewState {
-            agent_index: 1,
+            agent_id: Some(1),
             branch: "feature".to_string(),
+            base: "main".to_string(),
             worktree_path: PathBuf::from("/worktree"),
             diff_view: DiffView::new("diff".to_string()),
             commit_log: "log".to_string(),
             files_changed: 10,
             insertions: 500,
             deletions: 100,
+            ahead_by: 0,
+            behind_by: 0,
             conflicts: vec![
                 "file1.rs".to_string(),
                 "file2.rs".to_string(),
                 "file3.rs".to_string(),
             ],
+            uncommitted: vec![],
+            nonconforming_commits: vec![],
             focus: ReviewFocus::default(),
             summary_scroll: 0,
+            commits_view: None,
+            base_dialog: None,
+            search_input: None,
+            reject_dialog: None,
+            comments: Vec::new(),
+            comment_dialog: None,
+            post_review_pr: None,
+            draft_pr: false,
+            diff_kind: ReviewDiff::Branch,
         };
 
         assert_eq!(state.conflicts.len(), 3);
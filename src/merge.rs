@@ -4,6 +4,7 @@
 //! back into the main branch.
 
 use anyhow::{Context, Result, bail};
+use crate::config::UpdateStrategy;
 use std::path::PathBuf;
 use std::process::Command;
 
@@ -20,8 +21,70 @@ pub struct MergePreview {
     pub deletions: usize,
     /// List of files with potential conflicts
     pub conflicts: Vec<String>,
+    /// Commits on the branch that aren't on the base
+    pub ahead_by: usize,
+    /// Commits on the base that the branch doesn't have yet
+    pub behind_by: usize,
 }
 
+/// Result of `MergeManager::update_from_base`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpdateOutcome {
+    /// The branch now contains the base
+    Updated,
+    /// The merge or rebase stopped on conflicts in these files; it is left
+    /// in progress with conflict markers in the worktree
+    Conflicts(Vec<String>),
+}
+
+/// Why a `git push` failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PushError {
+    /// The remote rejected our credentials (or none were available)
+    AuthFailed(String),
+    /// The remote has commits we don't; pull or rebase first
+    NonFastForward(String),
+    /// Anything else (no remote, network errors, hooks, ...)
+    Failed(String),
+}
+
+impl PushError {
+    /// Classify `git push` stderr
+    pub fn from_stderr(stderr: &str) -> Self {
+        let message = stderr.trim().to_string();
+        let lower = message.to_lowercase();
+        if lower.contains("authentication failed")
+            || lower.contains("permission denied")
+            || lower.contains("could not read username")
+            || lower.contains("terminal prompts disabled")
+            || lower.contains("error: 403")
+        {
+            PushError::AuthFailed(message)
+        } else if lower.contains("non-fast-forward") || lower.contains("fetch first") {
+            PushError::NonFastForward(message)
+        } else {
+            PushError::Failed(message)
+        }
+    }
+}
+
+impl std::fmt::Display for PushError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PushError::AuthFailed(detail) => {
+                write!(f, "push was not authorized; check your git credentials ({detail})")
+            }
+            PushError::NonFastForward(detail) => write!(
+                f,
+                "push was rejected because origin has newer commits; pull or rebase first ({detail})"
+            ),
+            PushError::Failed(detail) => write!(f, "push failed: {detail}"),
+        }
+    }
+}
+
+impl std::error::Error for PushError {}
+
 /// Manager for git merge operations
 ///
 /// # Example
@@ -29,11 +92,11 @@ pub struct MergePreview {
 /// let merger = MergeManager::new("/path/to/repo");
 ///
 /// // Preview the merge
-/// let preview = merger.preview("feat/auth")?;
+/// let preview = merger.preview("feat/auth", "main")?;
 /// println!("Files changed: {}", preview.files_changed);
 ///
 /// // Get the diff
-/// let diff = merger.diff("feat/auth")?;
+/// let diff = merger.diff("feat/auth", "main")?;
 ///
 /// // Perform the merge
 /// merger.merge("feat/auth", Some("Merge feature branch"))?;
@@ -63,6 +126,28 @@ impl MergeManager {
         &self.main_branch
     }
 
+    /// Resolve the repository's default branch
+    ///
+    /// Uses the remote's `origin/HEAD` when it is set, otherwise the
+    /// configured main branch, or `master` if only that one exists.
+    pub fn default_branch(&self) -> String {
+        self.run_git(&["symbolic-ref", "--short", "refs/remotes/origin/HEAD"])
+            .ok()
+            .and_then(|head| {
+                head.trim()
+                    .strip_prefix("origin/")
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string)
+            })
+            .unwrap_or_else(|| {
+                if !self.branch_exists(&self.main_branch) && self.branch_exists("master") {
+                    "master".to_string()
+                } else {
+                    self.main_branch.clone()
+                }
+            })
+    }
+
     /// Run a git command and return its output
     fn run_git(&self, args: &[&str]) -> Result<String> {
         let output = Command::new("git")
@@ -79,20 +164,37 @@ impl MergeManager {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
 
-    /// Get a preview of what the merge would look like
+    /// Whether git is configured to sign commits (`commit.gpgsign`)
     ///
-    /// Uses `git diff --stat main...<branch>` to gather statistics.
-    pub fn preview(&self, branch: &str) -> Result<MergePreview> {
+    /// A signed merge may prompt for a passphrase, which would hang a merge
+    /// run from inside the TUI.
+    pub fn signing_enabled(&self) -> bool {
+        self.run_git(&["config", "--bool", "commit.gpgsign"])
+            .is_ok_and(|value| value.trim() == "true")
+    }
+
+    /// Get a preview of what merging `branch` into `base` would look like
+    ///
+    /// Uses `git diff --stat <base>...<branch>` to gather statistics and
+    /// `git rev-list --left-right --count <base>...<branch>` for how far the
+    /// branch has diverged from the base.
+    pub fn preview(&self, branch: &str, base: &str) -> Result<MergePreview> {
         // Get diff stats
         let diff_stat = self
-            .run_git(&["diff", "--stat", &format!("{}...{}", self.main_branch, branch)])
+            .run_git(&["diff", "--stat", &format!("{base}...{branch}")])
             .context("Failed to get diff stats")?;
 
         // Parse the stats
         let (files_changed, insertions, deletions) = parse_diff_stat(&diff_stat);
 
         // Check for potential conflicts using merge-tree (dry run)
-        let conflicts = self.check_conflicts(branch)?;
+        let conflicts = self.check_conflicts(branch, base)?;
+
+        let counts = self
+            .run_git(&["rev-list", "--left-right", "--count", &format!("{base}...{branch}")])
+            .context("Failed to count commits")?;
+        let (behind_by, ahead_by) = parse_left_right_count(&counts)
+            .with_context(|| format!("Unexpected git rev-list output: {counts}"))?;
 
         Ok(MergePreview {
             branch: branch.to_string(),
@@ -100,13 +202,15 @@ impl MergeManager {
             insertions,
             deletions,
             conflicts,
+            ahead_by,
+            behind_by,
         })
     }
 
     /// Check for potential merge conflicts
-    fn check_conflicts(&self, branch: &str) -> Result<Vec<String>> {
+    fn check_conflicts(&self, branch: &str, base: &str) -> Result<Vec<String>> {
         // Try to find the merge base
-        let merge_base = self.run_git(&["merge-base", &self.main_branch, branch]);
+        let merge_base = self.run_git(&["merge-base", base, branch]);
 
         if merge_base.is_err() {
             // If there's no merge base, branches are unrelated
@@ -116,7 +220,7 @@ impl MergeManager {
         // Use merge-tree to simulate the merge (available in newer git versions)
         // Fall back to checking if the same files were modified on both sides
         let files_on_main = self
-            .run_git(&["diff", "--name-only", &format!("{}..{}", merge_base.as_ref().unwrap().trim(), &self.main_branch)])
+            .run_git(&["diff", "--name-only", &format!("{}..{}", merge_base.as_ref().unwrap().trim(), base)])
             .unwrap_or_default();
 
         let files_on_branch = self
@@ -135,20 +239,70 @@ impl MergeManager {
         Ok(potential_conflicts)
     }
 
-    /// Get the full diff between main and the branch
+    /// Get the full diff between `base` and the branch
     ///
-    /// Uses `git diff main...<branch>`.
-    pub fn diff(&self, branch: &str) -> Result<String> {
-        self.run_git(&["diff", &format!("{}...{}", self.main_branch, branch)])
+    /// Uses `git diff <base>...<branch>`.
+    pub fn diff(&self, branch: &str, base: &str) -> Result<String> {
+        self.run_git(&["diff", &format!("{base}...{branch}")])
             .context("Failed to get diff")
     }
 
-    /// Get a file-specific diff
-    pub fn diff_file(&self, branch: &str, file: &str) -> Result<String> {
-        self.run_git(&["diff", &format!("{}...{}", self.main_branch, branch), "--", file])
+    /// Get the diff between `base` and the result of merging the branch into it
+    ///
+    /// This is the net change the merge brings to `base`, computed with
+    /// `git merge-tree --write-tree` (git 2.38+) without touching a worktree
+    /// or the index. It differs from `diff` once `base` has moved on: changes
+    /// `base` already has drop out, and conflicting hunks show up with their
+    /// conflict markers.
+    pub fn merge_result_diff(&self, branch: &str, base: &str) -> Result<String> {
+        let output = Command::new("git")
+            .current_dir(&self.repo_path)
+            .args(["merge-tree", "--write-tree", base, branch])
+            .output()
+            .context("Failed to execute git merge-tree")?;
+        // Exit status 1 means conflicts; the tree is still written, with markers
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let tree = stdout.lines().next().unwrap_or_default().trim();
+        if output.status.code().is_none_or(|code| code > 1) || tree.is_empty() {
+            bail!(
+                "Failed to compute the merge result: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        self.run_git(&["diff", base, tree])
+            .context("Failed to get the merge result diff")
+    }
+
+    /// Get a file-specific diff against `base`
+    pub fn diff_file(&self, branch: &str, base: &str, file: &str) -> Result<String> {
+        self.run_git(&["diff", &format!("{base}...{branch}"), "--", file])
             .context("Failed to get file diff")
     }
 
+    /// Push `branch` to origin, optionally setting it as the upstream
+    ///
+    /// Credential prompts are disabled so a push can't hang waiting for
+    /// input; failures are returned as a [`PushError`].
+    pub fn push_branch(&self, branch: &str, set_upstream: bool) -> Result<()> {
+        let mut args = vec!["push"];
+        if set_upstream {
+            args.push("--set-upstream");
+        }
+        args.extend(["origin", branch]);
+
+        let output = Command::new("git")
+            .args(&args)
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .current_dir(&self.repo_path)
+            .output()
+            .context("Failed to execute git command")?;
+        if !output.status.success() {
+            let error = PushError::from_stderr(&String::from_utf8_lossy(&output.stderr));
+            return Err(anyhow::Error::new(error).context(format!("Failed to push {branch}")));
+        }
+        Ok(())
+    }
+
     /// Perform the merge
     ///
     /// Uses `git merge <branch> -m "<message>"`.
@@ -173,6 +327,33 @@ impl MergeManager {
         Ok(())
     }
 
+    /// Bring the checked-out branch up to date with `base`
+    ///
+    /// Merges `base` into it (`git merge --no-edit`) or rebases it onto `base`.
+    /// On conflicts the operation is left in progress so they can be resolved
+    /// in place; any other failure is aborted and returned as an error.
+    pub fn update_from_base(&self, base: &str, strategy: UpdateStrategy) -> Result<UpdateOutcome> {
+        let (args, abort): (&[&str], &[&str]) = match strategy {
+            UpdateStrategy::Merge => (&["merge", "--no-edit", base], &["merge", "--abort"]),
+            UpdateStrategy::Rebase => (&["rebase", base], &["rebase", "--abort"]),
+        };
+        let Err(e) = self.run_git(args) else {
+            return Ok(UpdateOutcome::Updated);
+        };
+        let conflicts = self.conflicted_files().unwrap_or_default();
+        if !conflicts.is_empty() {
+            return Ok(UpdateOutcome::Conflicts(conflicts));
+        }
+        let _ = self.run_git(abort);
+        Err(e).with_context(|| format!("Failed to update from {base}"))
+    }
+
+    /// Files with unresolved conflicts (`git diff --name-only --diff-filter=U`)
+    pub fn conflicted_files(&self) -> Result<Vec<String>> {
+        let output = self.run_git(&["diff", "--name-only", "--diff-filter=U"])?;
+        Ok(output.lines().filter(|l| !l.is_empty()).map(str::to_string).collect())
+    }
+
     /// Abort an in-progress merge
     ///
     /// Uses `git merge --abort`.
@@ -215,11 +396,53 @@ impl MergeManager {
         Ok(output.trim().to_string())
     }
 
+    /// Number of commits on `branch` that aren't on `base` (`git rev-list --count base..branch`)
+    pub fn commits_ahead(&self, branch: &str, base: &str) -> Result<usize> {
+        let output = self
+            .run_git(&["rev-list", "--count", &format!("{base}..{branch}")])
+            .with_context(|| format!("Failed to count commits of {branch} ahead of {base}"))?;
+        output
+            .trim()
+            .parse()
+            .with_context(|| format!("Unexpected git rev-list output: {output}"))
+    }
+
     /// Check if a branch exists
     pub fn branch_exists(&self, branch: &str) -> bool {
         self.run_git(&["rev-parse", "--verify", branch]).is_ok()
     }
 
+    /// Resolve a revision (branch, tag, `HEAD`, ...) to a commit hash
+    pub fn rev_parse(&self, rev: &str) -> Result<String> {
+        let output = self
+            .run_git(&["rev-parse", "--verify", &format!("{rev}^{{commit}}")])
+            .with_context(|| format!("Failed to resolve {rev}"))?;
+        Ok(output.trim().to_string())
+    }
+
+    /// Check whether `branch` has been merged into the main branch
+    ///
+    /// True when the branch tip is reachable from main
+    /// (`git merge-base --is-ancestor <branch> main`), so it doesn't depend
+    /// on the merge commit message.
+    pub fn is_merged(&self, branch: &str) -> Result<bool> {
+        let output = Command::new("git")
+            .args(["merge-base", "--is-ancestor", branch, &self.main_branch])
+            .current_dir(&self.repo_path)
+            .output()
+            .context("Failed to execute git command")?;
+
+        // Exit code 1 means "not an ancestor"; anything else is an error
+        match output.status.code() {
+            Some(0) => Ok(true),
+            Some(1) => Ok(false),
+            _ => bail!(
+                "Git command failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        }
+    }
+
     /// Checkout a branch
     pub fn checkout(&self, branch: &str) -> Result<()> {
         self.run_git(&["checkout", branch])
@@ -228,6 +451,15 @@ impl MergeManager {
     }
 }
 
+/// Parse `git rev-list --left-right --count` output into (left, right)
+fn parse_left_right_count(output: &str) -> Option<(usize, usize)> {
+    let mut counts = output.split_whitespace().map(str::parse);
+    match (counts.next(), counts.next(), counts.next()) {
+        (Some(Ok(left)), Some(Ok(right)), None) => Some((left, right)),
+        _ => None,
+    }
+}
+
 /// Parse the output of `git diff --stat` to extract statistics
 fn parse_diff_stat(stat: &str) -> (usize, usize, usize) {
     let mut files_changed = 0;
@@ -339,6 +571,8 @@ mod tests {
             insertions: 100,
             deletions: 20,
             conflicts: vec!["src/main.rs".to_string()],
+            ahead_by: 3,
+            behind_by: 0,
         };
 
         assert_eq!(preview.branch, "feat/test");
@@ -347,4 +581,270 @@ mod tests {
         assert_eq!(preview.deletions, 20);
         assert_eq!(preview.conflicts.len(), 1);
     }
+
+    // ==================== is_merged tests ====================
+
+    fn git(dir: &std::path::Path, args: &[&str]) {
+        let output = Command::new("git")
+            .current_dir(dir)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?}: {output:?}");
+    }
+
+    fn setup_repo() -> (tempfile::TempDir, MergeManager) {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dir = temp.path();
+        git(dir, &["init", "-b", "main"]);
+        git(dir, &["config", "user.email", "test@test.com"]);
+        git(dir, &["config", "user.name", "Test User"]);
+        git(dir, &["commit", "--allow-empty", "-m", "init", "--no-gpg-sign"]);
+        git(dir, &["checkout", "-b", "feat"]);
+        git(dir, &["commit", "--allow-empty", "-m", "work", "--no-gpg-sign"]);
+        git(dir, &["checkout", "main"]);
+        let manager = MergeManager::new(dir);
+        (temp, manager)
+    }
+
+    #[test]
+    fn test_is_merged_with_custom_message() {
+        let (temp, manager) = setup_repo();
+        let before = manager.rev_parse("main").unwrap();
+        assert!(!manager.is_merged("feat").unwrap());
+
+        git(
+            temp.path(),
+            &["merge", "--no-ff", "feat", "-m", "Land the feature", "--no-gpg-sign"],
+        );
+        assert!(manager.is_merged("feat").unwrap());
+        assert_ne!(manager.rev_parse("main").unwrap(), before);
+    }
+
+    #[test]
+    fn test_is_merged_fast_forward() {
+        let (temp, manager) = setup_repo();
+        git(temp.path(), &["merge", "--ff-only", "feat"]);
+        assert!(manager.is_merged("feat").unwrap());
+    }
+
+    #[test]
+    fn test_is_merged_unknown_branch_is_error() {
+        let (_temp, manager) = setup_repo();
+        assert!(manager.is_merged("no-such-branch").is_err());
+        assert!(manager.rev_parse("no-such-branch").is_err());
+    }
+
+    #[test]
+    fn test_parse_left_right_count() {
+        assert_eq!(parse_left_right_count("2\t5\n"), Some((2, 5)));
+        assert_eq!(parse_left_right_count("0\t0"), Some((0, 0)));
+        assert_eq!(parse_left_right_count(""), None);
+        assert_eq!(parse_left_right_count("1"), None);
+        assert_eq!(parse_left_right_count("x\t1"), None);
+    }
+
+    #[test]
+    fn test_preview_ahead_and_behind() {
+        let (temp, manager) = setup_repo();
+        let preview = manager.preview("feat", "main").unwrap();
+        assert_eq!((preview.ahead_by, preview.behind_by), (1, 0));
+
+        git(temp.path(), &["commit", "--allow-empty", "-m", "main 1", "--no-gpg-sign"]);
+        git(temp.path(), &["commit", "--allow-empty", "-m", "main 2", "--no-gpg-sign"]);
+        let preview = manager.preview("feat", "main").unwrap();
+        assert_eq!((preview.ahead_by, preview.behind_by), (1, 2));
+    }
+
+    #[test]
+    fn test_commits_ahead() {
+        let (_temp, manager) = setup_repo();
+        assert_eq!(manager.commits_ahead("feat", "main").unwrap(), 1);
+        assert_eq!(manager.commits_ahead("main", "feat").unwrap(), 0);
+        assert!(manager.commits_ahead("no-such-branch", "main").is_err());
+    }
+
+    // ==================== update_from_base tests ====================
+
+    /// `setup_repo` with `feat` checked out and main one commit ahead, both
+    /// sides writing `file` when `conflicting`
+    fn behind_repo(conflicting: bool) -> (tempfile::TempDir, MergeManager) {
+        let (temp, manager) = setup_repo();
+        let dir = temp.path();
+        git(dir, &["config", "commit.gpgsign", "false"]);
+        std::fs::write(dir.join("file"), "main\n").unwrap();
+        git(dir, &["add", "file"]);
+        git(dir, &["commit", "-m", "main work", "--no-gpg-sign"]);
+        git(dir, &["checkout", "feat"]);
+        if conflicting {
+            std::fs::write(dir.join("file"), "feat\n").unwrap();
+            git(dir, &["add", "file"]);
+            git(dir, &["commit", "-m", "feat work", "--no-gpg-sign"]);
+        }
+        (temp, manager)
+    }
+
+    #[test]
+    fn test_update_from_base_merge_and_rebase() {
+        for strategy in [UpdateStrategy::Merge, UpdateStrategy::Rebase] {
+            let (_temp, manager) = behind_repo(false);
+            assert_eq!(manager.commits_ahead("main", "feat").unwrap(), 1);
+            assert_eq!(
+                manager.update_from_base("main", strategy).unwrap(),
+                UpdateOutcome::Updated
+            );
+            assert_eq!(manager.commits_ahead("main", "feat").unwrap(), 0, "{strategy:?}");
+            assert_eq!(manager.current_branch().unwrap(), "feat");
+        }
+    }
+
+    #[test]
+    fn test_update_from_base_conflicts_are_left_in_place() {
+        for strategy in [UpdateStrategy::Merge, UpdateStrategy::Rebase] {
+            let (temp, manager) = behind_repo(true);
+            assert_eq!(
+                manager.update_from_base("main", strategy).unwrap(),
+                UpdateOutcome::Conflicts(vec!["file".to_string()])
+            );
+            let content = std::fs::read_to_string(temp.path().join("file")).unwrap();
+            assert!(content.contains("<<<<<<<"), "{strategy:?}: {content}");
+        }
+    }
+
+    #[test]
+    fn test_update_from_unknown_base_is_error() {
+        let (_temp, manager) = behind_repo(false);
+        assert!(manager.update_from_base("no-such-base", UpdateStrategy::Merge).is_err());
+        assert!(manager.conflicted_files().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_merge_result_diff_leaves_out_changes_base_has() {
+        let (temp, manager) = setup_repo();
+        let dir = temp.path();
+        git(dir, &["config", "commit.gpgsign", "false"]);
+        std::fs::write(dir.join("shared.txt"), "same\n").unwrap();
+        git(dir, &["add", "shared.txt"]);
+        git(dir, &["commit", "-m", "shared on main"]);
+        git(dir, &["checkout", "feat"]);
+        std::fs::write(dir.join("shared.txt"), "same\n").unwrap();
+        std::fs::write(dir.join("own.txt"), "own\n").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-m", "shared and own"]);
+
+        let branch = manager.diff("feat", "main").unwrap();
+        assert!(branch.contains("shared.txt") && branch.contains("own.txt"));
+
+        let merged = manager.merge_result_diff("feat", "main").unwrap();
+        assert!(!merged.contains("shared.txt") && merged.contains("own.txt"), "{merged}");
+        // Nothing was checked out or merged
+        assert_eq!(manager.current_branch().unwrap(), "feat");
+        assert!(!manager.is_merged("feat").unwrap());
+    }
+
+    #[test]
+    fn test_merge_result_diff_shows_conflict_markers() {
+        let (_temp, manager) = behind_repo(true);
+        let merged = manager.merge_result_diff("feat", "main").unwrap();
+        assert!(merged.contains("+<<<<<<< main"), "{merged}");
+        assert!(manager.conflicted_files().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_merge_result_diff_unknown_branch_is_error() {
+        let (_temp, manager) = setup_repo();
+        assert!(manager.merge_result_diff("no-such-branch", "main").is_err());
+    }
+
+    // ==================== base branch tests ====================
+
+    #[test]
+    fn test_diff_against_stacked_base() {
+        let (temp, manager) = setup_repo();
+        let dir = temp.path();
+        git(dir, &["checkout", "feat"]);
+        std::fs::write(dir.join("a.txt"), "a\n").unwrap();
+        git(dir, &["add", "a.txt"]);
+        git(dir, &["commit", "-m", "add a", "--no-gpg-sign"]);
+        git(dir, &["checkout", "-b", "feat-b"]);
+        std::fs::write(dir.join("b.txt"), "b\n").unwrap();
+        git(dir, &["add", "b.txt"]);
+        git(dir, &["commit", "-m", "add b", "--no-gpg-sign"]);
+
+        let against_main = manager.diff("feat-b", "main").unwrap();
+        assert!(against_main.contains("a.txt") && against_main.contains("b.txt"));
+
+        let against_feat = manager.diff("feat-b", "feat").unwrap();
+        assert!(!against_feat.contains("a.txt") && against_feat.contains("b.txt"));
+        assert_eq!(manager.preview("feat-b", "feat").unwrap().files_changed, 1);
+    }
+
+    #[test]
+    fn test_default_branch_falls_back_to_main_branch() {
+        let (_temp, manager) = setup_repo();
+        assert_eq!(manager.default_branch(), "main");
+        assert_eq!(manager.with_main_branch("trunk").default_branch(), "trunk");
+    }
+
+    #[test]
+    fn test_default_branch_finds_master() {
+        let (temp, manager) = setup_repo();
+        git(temp.path(), &["branch", "-m", "main", "master"]);
+        assert_eq!(manager.default_branch(), "master");
+    }
+
+    #[test]
+    fn test_signing_enabled_reads_git_config() {
+        let (temp, manager) = setup_repo();
+        git(temp.path(), &["config", "commit.gpgsign", "false"]);
+        assert!(!manager.signing_enabled());
+
+        git(temp.path(), &["config", "commit.gpgsign", "true"]);
+        assert!(manager.signing_enabled());
+    }
+
+    // ==================== push tests ====================
+
+    #[test]
+    fn test_push_error_classification() {
+        let auth = "fatal: Authentication failed for 'https://github.com/o/r.git/'";
+        assert!(matches!(PushError::from_stderr(auth), PushError::AuthFailed(_)));
+        let prompt = "fatal: could not read Username for 'https://github.com': terminal prompts disabled";
+        assert!(matches!(PushError::from_stderr(prompt), PushError::AuthFailed(_)));
+
+        let rejected = " ! [rejected]        main -> main (non-fast-forward)\nerror: failed to push some refs";
+        assert!(matches!(PushError::from_stderr(rejected), PushError::NonFastForward(_)));
+        let fetch_first = " ! [rejected]        main -> main (fetch first)";
+        assert!(matches!(PushError::from_stderr(fetch_first), PushError::NonFastForward(_)));
+
+        let other = "fatal: 'origin' does not appear to be a git repository";
+        assert!(matches!(PushError::from_stderr(other), PushError::Failed(_)));
+    }
+
+    #[test]
+    fn test_push_branch_to_bare_remote() {
+        let (temp, manager) = setup_repo();
+        let remote = tempfile::TempDir::new().unwrap();
+        git(remote.path(), &["init", "--bare"]);
+        git(temp.path(), &["remote", "add", "origin", remote.path().to_str().unwrap()]);
+
+        manager.push_branch("feat", true).unwrap();
+        git(remote.path(), &["rev-parse", "--verify", "refs/heads/feat"]);
+    }
+
+    #[test]
+    fn test_push_non_fast_forward_is_reported() {
+        let (temp, manager) = setup_repo();
+        let remote = tempfile::TempDir::new().unwrap();
+        git(remote.path(), &["init", "--bare"]);
+        git(temp.path(), &["remote", "add", "origin", remote.path().to_str().unwrap()]);
+        manager.push_branch("feat", false).unwrap();
+        git(temp.path(), &["branch", "-f", "feat", "main"]);
+
+        let error = manager.push_branch("feat", false).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<PushError>(),
+            Some(PushError::NonFastForward(_))
+        ));
+    }
 }
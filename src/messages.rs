@@ -0,0 +1,245 @@
+//! User-facing message catalog
+//!
+//! Worker prompts and UI strings in English and Japanese. Prompts use the
+//! `language` config key and default to English; UI text also falls back to
+//! the `LANG` / `LC_ALL` / `LC_MESSAGES` environment.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Supported message languages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    Ja,
+}
+
+/// Locale used for UI text (set once at startup)
+static UI_LOCALE: AtomicU8 = AtomicU8::new(0);
+
+impl Locale {
+    /// Parse a language setting such as "en", "ja" or "ja_JP.UTF-8"
+    pub fn parse(name: &str) -> Option<Self> {
+        let lang = name
+            .trim()
+            .split(['_', '-', '.'])
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        match lang.as_str() {
+            "en" => Some(Locale::En),
+            "ja" => Some(Locale::Ja),
+            _ => None,
+        }
+    }
+
+    /// Locale from the environment (`LC_ALL`, `LC_MESSAGES`, then `LANG`)
+    pub fn from_env() -> Option<Self> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::parse(&value))
+    }
+
+    /// Locale for worker prompts: the configured language, else English
+    pub fn for_prompts(config_language: Option<&str>) -> Self {
+        config_language.and_then(Self::parse).unwrap_or_default()
+    }
+
+    /// Locale for UI text: the configured language, else the environment,
+    /// else English
+    pub fn for_ui(config_language: Option<&str>) -> Self {
+        config_language
+            .and_then(Self::parse)
+            .or_else(Self::from_env)
+            .unwrap_or_default()
+    }
+}
+
+/// Set the locale used by `ui_locale()`
+pub fn set_ui_locale(locale: Locale) {
+    UI_LOCALE.store(locale as u8, Ordering::Relaxed);
+}
+
+/// Current UI locale
+pub fn ui_locale() -> Locale {
+    match UI_LOCALE.load(Ordering::Relaxed) {
+        1 => Locale::Ja,
+        _ => Locale::En,
+    }
+}
+
+/// Message keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    /// Theme picker dialog title
+    ThemePickerTitle,
+    /// Shown instead of the UI when the terminal is too small (`{size}`, `{min}`)
+    TerminalTooSmall,
+    /// Footer label for navigation mode
+    ModeNavigation,
+    /// Footer label for input mode
+    ModeInput,
+    /// MergeWorker instructions (`{branch}`)
+    MergeWorkerTask,
+    /// BuildWorker instructions (`{steps}`)
+    BuildWorkerTask,
+    /// BuildWorker step: run the build (`{command}`)
+    BuildStepRun,
+    /// BuildWorker step: fix build errors
+    BuildStepFix,
+    /// BuildWorker step: run the tests (`{command}`)
+    TestStepRun,
+    /// BuildWorker step: fix failing tests
+    TestStepFix,
+    /// Appended to plan worker prompts
+    WorkerCommitReminder,
+    /// Follow-up task for a worker whose branch was rejected in review
+    /// (`{branch}`, `{feedback}`)
+    ReviewFeedbackTask,
+    /// Heading for line comments in review feedback
+    ReviewLineComments,
+}
+
+/// Message text for a key and locale
+pub fn text(locale: Locale, msg: Msg) -> &'static str {
+    match (msg, locale) {
+        (Msg::ThemePickerTitle, Locale::En) => " Select Theme ",
+        (Msg::ThemePickerTitle, Locale::Ja) => " テーマを選択 ",
+        (Msg::TerminalTooSmall, Locale::En) => "Terminal too small ({size}, need {min})",
+        (Msg::TerminalTooSmall, Locale::Ja) => "端末が小さすぎます ({size}、{min} 以上必要)",
+        (Msg::ModeNavigation, Locale::En) => "NAV(i:input ::cmd)",
+        (Msg::ModeNavigation, Locale::Ja) => "NAV(i:入力 ::cmd)",
+        (Msg::ModeInput, Locale::En) => "INS(Esc:navigate)",
+        (Msg::ModeInput, Locale::Ja) => "INS(Esc:移動)",
+        (Msg::MergeWorkerTask, Locale::En) => {
+            "Merge {branch} into {target}.\n\n\
+             Steps:\n\
+             1. git checkout {target}\n\
+             2. git pull origin {target} (get the latest changes)\n\
+             3. git merge --no-ff {branch}\n\
+             4. If there are conflicts, resolve them and commit\n\n\
+             Important: always create a merge commit."
+        }
+        (Msg::MergeWorkerTask, Locale::Ja) => {
+            "{target} ブランチに {branch} をマージしてください。\n\n\
+             手順:\n\
+             1. git checkout {target}\n\
+             2. git pull origin {target} (最新を取得)\n\
+             3. git merge --no-ff {branch}\n\
+             4. コンフリクトがあれば解決してコミット\n\n\
+             重要: マージコミットを必ず作成してください。"
+        }
+        (Msg::BuildWorkerTask, Locale::En) => {
+            "Run the post-merge build check.\n\n\
+             Steps:\n\
+             {steps}\n\n\
+             You are done when the build succeeds."
+        }
+        (Msg::BuildWorkerTask, Locale::Ja) => {
+            "マージ後のビルドチェックを実行してください。\n\n\
+             手順:\n\
+             {steps}\n\n\
+             ビルドが成功したら完了です。"
+        }
+        (Msg::BuildStepRun, Locale::En) => "Run {command}",
+        (Msg::BuildStepRun, Locale::Ja) => "{command} を実行",
+        (Msg::BuildStepFix, Locale::En) => "If there are errors, fix them and commit",
+        (Msg::BuildStepFix, Locale::Ja) => "エラーがあれば修正してコミット",
+        (Msg::TestStepRun, Locale::En) => "Run {command} (optional)",
+        (Msg::TestStepRun, Locale::Ja) => "{command} を実行（オプション）",
+        (Msg::TestStepFix, Locale::En) => "If any tests fail, fix them and commit",
+        (Msg::TestStepFix, Locale::Ja) => "失敗したテストがあれば修正してコミット",
+        (Msg::WorkerCommitReminder, Locale::En) => {
+            "Important: when you are done, always run git add and git commit.\n\
+             Changes are lost if you exit without committing."
+        }
+        (Msg::WorkerCommitReminder, Locale::Ja) => {
+            "重要: 作業完了後は必ず git add と git commit を実行してコミットしてください。\n\
+             コミットせずに終了すると変更が失われます。"
+        }
+        (Msg::ReviewFeedbackTask, Locale::En) => {
+            "The changes on {branch} were reviewed and need more work before they can be merged.\n\n\
+             Review feedback:\n\
+             {feedback}\n\n\
+             Address the feedback on top of the existing commits in this worktree."
+        }
+        (Msg::ReviewFeedbackTask, Locale::Ja) => {
+            "{branch} の変更をレビューしました。マージする前に修正が必要です。\n\n\
+             レビューコメント:\n\
+             {feedback}\n\n\
+             このworktreeの既存のコミットの上で、指摘に対応してください。"
+        }
+        (Msg::ReviewLineComments, Locale::En) => "Comments on specific lines:",
+        (Msg::ReviewLineComments, Locale::Ja) => "行ごとのコメント:",
+    }
+}
+
+/// Message text with `{name}` placeholders filled in
+pub fn format(locale: Locale, msg: Msg, args: &[(&str, &str)]) -> String {
+    args.iter()
+        .fold(text(locale, msg).to_string(), |acc, (name, value)| {
+            acc.replace(&format!("{{{name}}}"), value)
+        })
+}
+
+/// Localized description for a theme id (see `available_themes`)
+pub fn theme_description(locale: Locale, id: &str) -> Option<&'static str> {
+    let description = match (id, locale) {
+        ("cyberpunk", Locale::En) => "Neon cyberpunk colors",
+        ("cyberpunk", Locale::Ja) => "ネオンカラーのサイバーパンク風",
+        ("monokai", Locale::En) => "Classic editor colors",
+        ("monokai", Locale::Ja) => "クラシックなエディタカラー",
+        ("dracula", Locale::En) => "Popular dark theme",
+        ("dracula", Locale::Ja) => "人気のダークテーマ",
+        ("nord", Locale::En) => "Bluish Nordic palette",
+        ("nord", Locale::Ja) => "北欧の青みのあるパレット",
+        ("arctic", Locale::En) => "Dreamy aurora colors",
+        ("arctic", Locale::Ja) => "オーロラ風の幻想的なテーマ",
+        ("minimal", Locale::En) => "Understated and professional",
+        ("minimal", Locale::Ja) => "控えめでプロフェッショナル",
+        _ => return None,
+    };
+    Some(description)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_parse() {
+        assert_eq!(Locale::parse("en"), Some(Locale::En));
+        assert_eq!(Locale::parse("ja_JP.UTF-8"), Some(Locale::Ja));
+        assert_eq!(Locale::parse("JA"), Some(Locale::Ja));
+        assert_eq!(Locale::parse("C"), None);
+        assert_eq!(Locale::parse("fr_FR"), None);
+    }
+
+    #[test]
+    fn test_prompt_locale_defaults_to_english() {
+        assert_eq!(Locale::for_prompts(None), Locale::En);
+        assert_eq!(Locale::for_prompts(Some("de")), Locale::En);
+        assert_eq!(Locale::for_prompts(Some("ja")), Locale::Ja);
+    }
+
+    #[test]
+    fn test_format_fills_placeholders() {
+        let en = format(Locale::En, Msg::MergeWorkerTask, &[("branch", "feat/x"), ("target", "main")]);
+        assert!(en.starts_with("Merge feat/x into main."));
+        assert!(en.contains("git merge --no-ff feat/x"));
+        assert!(!en.contains("{branch}") && !en.contains("{target}"));
+
+        let ja = format(Locale::Ja, Msg::BuildStepRun, &[("command", "cargo build")]);
+        assert_eq!(ja, "cargo build を実行");
+    }
+
+    #[test]
+    fn test_theme_descriptions_cover_all_themes() {
+        for (id, _, _) in crate::theme::available_themes() {
+            assert!(theme_description(Locale::En, id).is_some(), "{id}");
+            assert!(theme_description(Locale::Ja, id).is_some(), "{id}");
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Centered popups drawn over the rest of the screen
+//!
+//! Overlays share one look: a bordered block on the dialog background with
+//! a bold title on top and the keys they take along the bottom border.
+
+use crate::theme::theme;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// Whether `area` is too small to hold a border around at least one cell
+pub fn is_degenerate(area: Rect) -> bool {
+    area.width < 3 || area.height < 3
+}
+
+/// A `width` x `height` popup centered in `area`, shrunk to fit
+pub fn centered_modal(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// A popup taking `percent_x` / `percent_y` percent of `area`, centered
+pub fn centered_percent(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let scale = |length: u16, percent: u16| (u32::from(length) * u32::from(percent.min(100)) / 100) as u16;
+    centered_modal(area, scale(area.width, percent_x), scale(area.height, percent_y))
+}
+
+/// Title in the overlay style
+pub fn modal_title(title: impl Into<String>) -> Span<'static> {
+    Span::styled(
+        title.into(),
+        Style::default()
+            .fg(theme().neon_cyan())
+            .add_modifier(Modifier::BOLD),
+    )
+}
+
+/// Key hints such as ` j/k: Scroll  Esc: Close `, for the bottom border
+pub fn key_hints(hints: &[(&str, &str)]) -> Line<'static> {
+    let t = theme();
+    let mut spans = Vec::with_capacity(hints.len() * 2);
+    for (i, (keys, description)) in hints.iter().enumerate() {
+        let key = if i == 0 { format!(" {keys}") } else { keys.to_string() };
+        let gap = if i + 1 == hints.len() { " " } else { "  " };
+        spans.push(Span::styled(key, t.style_key()));
+        spans.push(Span::styled(format!(": {description}{gap}"), t.style_key_desc()));
+    }
+    Line::from(spans)
+}
+
+/// Bordered block in the overlay style, with `footer` along the bottom border
+pub fn modal_block<'a>(title: Span<'a>, footer: Option<Line<'a>>) -> Block<'a> {
+    let t = theme();
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(t.style_dialog_border())
+        .style(t.style_dialog_bg());
+    match footer {
+        Some(footer) => block.title_bottom(footer),
+        None => block,
+    }
+}
+
+/// Clear `area` and draw a popup with `lines` in it
+pub fn render_modal<'a>(
+    f: &mut Frame,
+    area: Rect,
+    title: Span<'a>,
+    lines: Vec<Line<'a>>,
+    footer: Option<Line<'a>>,
+) {
+    render_modal_scrolled(f, area, title, lines, footer, 0);
+}
+
+/// Like [`render_modal`], with the content scrolled down by `scroll` lines
+pub fn render_modal_scrolled<'a>(
+    f: &mut Frame,
+    area: Rect,
+    title: Span<'a>,
+    lines: Vec<Line<'a>>,
+    footer: Option<Line<'a>>,
+    scroll: u16,
+) {
+    if is_degenerate(area) {
+        return;
+    }
+    f.render_widget(Clear, area);
+    let paragraph = Paragraph::new(lines)
+        .block(modal_block(title, footer))
+        .scroll((scroll, 0));
+    f.render_widget(paragraph, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_centered_modal() {
+        let area = Rect::new(10, 5, 100, 40);
+        assert_eq!(centered_modal(area, 40, 10), Rect::new(40, 20, 40, 10));
+        // Larger than the area: shrunk to fit
+        assert_eq!(centered_modal(area, 200, 100), area);
+        assert_eq!(centered_modal(Rect::default(), 40, 10), Rect::default());
+    }
+
+    #[test]
+    fn test_centered_percent() {
+        let area = Rect::new(0, 0, 100, 50);
+        assert_eq!(centered_percent(area, 80, 60), Rect::new(10, 10, 80, 30));
+        // No overflow on wide terminals
+        let wide = Rect::new(0, 0, 1000, 300);
+        assert_eq!(centered_percent(wide, 90, 50).width, 900);
+        assert_eq!(centered_percent(area, 150, 100), area);
+    }
+
+    #[test]
+    fn test_is_degenerate() {
+        assert!(is_degenerate(Rect::default()));
+        assert!(is_degenerate(Rect::new(0, 0, 80, 2)));
+        assert!(is_degenerate(Rect::new(0, 0, 2, 24)));
+        assert!(!is_degenerate(Rect::new(0, 0, 3, 3)));
+    }
+
+    #[test]
+    fn test_key_hints() {
+        let line = key_hints(&[("j/k", "Scroll"), ("Esc", "Close")]);
+        let text: String = line.spans.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(text, " j/k: Scroll  Esc: Close ");
+    }
+}
@@ -4,8 +4,10 @@
 //! and cctakt. The orchestrator writes plans to `.cctakt/plan.json`, and cctakt
 //! watches and executes them.
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
@@ -77,6 +79,10 @@ pub struct TaskResult {
     /// PR URL if a PR was created
     #[serde(default)]
     pub pr_url: Option<String>,
+
+    /// Captured output of a RunCommand task
+    #[serde(default)]
+    pub output: Option<String>,
 }
 
 /// Task action types
@@ -127,11 +133,17 @@ pub enum TaskAction {
     },
 
     /// Run a command in a worktree
+    ///
+    /// A failing command blocks later merge/PR/review tasks for the same branch
+    /// unless `allow_failure` is set.
     RunCommand {
         /// Worktree path or branch name
         worktree: String,
         /// Command to run
         command: String,
+        /// Treat failure as advisory (task completes with a warning)
+        #[serde(default)]
+        allow_failure: bool,
     },
 
     /// Notify/message (no action, just for logging)
@@ -154,7 +166,7 @@ pub enum TaskAction {
 }
 
 /// Notification level
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum NotifyLevel {
     #[default]
@@ -207,6 +219,17 @@ impl Plan {
         self.tasks.push(task);
     }
 
+    /// Generate a task ID that is not used by any task in the plan
+    ///
+    /// IDs take the form `{prefix}-{n}`, counting up from 1. Using a prefix
+    /// the orchestrator doesn't use (e.g. `tui`) keeps IDs from clashing.
+    pub fn next_task_id(&self, prefix: &str) -> String {
+        (1..)
+            .map(|n| format!("{prefix}-{n}"))
+            .find(|id| self.get_task(id).is_none())
+            .expect("unbounded range always yields an unused id")
+    }
+
     /// Get next pending task
     pub fn next_pending(&self) -> Option<&Task> {
         self.tasks.iter().find(|t| t.status == TaskStatus::Pending)
@@ -245,6 +268,39 @@ impl Plan {
         }
     }
 
+    /// Put a task back to pending, clearing its error and result, so it runs again
+    pub fn reset_task(&mut self, id: &str) -> bool {
+        if let Some(task) = self.get_task_mut(id) {
+            task.status = TaskStatus::Pending;
+            task.error = None;
+            task.result = None;
+            task.updated_at = Some(current_timestamp());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Mark a task as skipped along with the pending tasks that (transitively)
+    /// wait for it; returns the ids that were skipped
+    pub fn skip_with_dependents(&mut self, id: &str) -> Vec<String> {
+        let mut skipped = Vec::new();
+        let mut queue = vec![id.to_string()];
+        while let Some(id) = queue.pop() {
+            if !self.update_status(&id, TaskStatus::Skipped) {
+                continue;
+            }
+            queue.extend(
+                self.tasks
+                    .iter()
+                    .filter(|t| t.status == TaskStatus::Pending && t.depends_on() == Some(id.as_str()))
+                    .map(|t| t.id.clone()),
+            );
+            skipped.push(id);
+        }
+        skipped
+    }
+
     /// Mark task as completed with result
     pub fn mark_completed(&mut self, id: &str, result: TaskResult) -> bool {
         if let Some(task) = self.get_task_mut(id) {
@@ -257,6 +313,179 @@ impl Plan {
         }
     }
 
+    /// Check RunCommand gates that apply to a branch
+    ///
+    /// Only non-advisory RunCommand tasks that precede `task_id` in the plan and
+    /// target the branch (by branch name or worktree directory name) act as gates.
+    pub fn gate_status(&self, branch: &str, task_id: &str) -> GateStatus {
+        let dir_name = branch.replace('/', "-");
+        let mut waiting = None;
+
+        for task in self.tasks.iter().take_while(|t| t.id != task_id) {
+            let TaskAction::RunCommand {
+                worktree,
+                allow_failure: false,
+                ..
+            } = &task.action
+            else {
+                continue;
+            };
+            let target = Path::new(worktree)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or(worktree);
+            if worktree != branch && target != dir_name {
+                continue;
+            }
+
+            match task.status {
+                TaskStatus::Failed => return GateStatus::Blocked(task.id.clone()),
+                TaskStatus::Pending | TaskStatus::Running => {
+                    waiting.get_or_insert_with(|| task.id.clone());
+                }
+                TaskStatus::Completed | TaskStatus::Skipped => {}
+            }
+        }
+
+        waiting.map_or(GateStatus::Clear, GateStatus::Waiting)
+    }
+
+    /// Record the outcome of a RunCommand task
+    ///
+    /// A failed command marks the task `Failed` unless `allow_failure` is set,
+    /// in which case it is `Completed` and the failure is only advisory.
+    /// Returns false if the task does not exist.
+    pub fn complete_run_command(&mut self, id: &str, outcome: &CommandOutcome) -> bool {
+        let allow_failure = matches!(
+            self.get_task(id).map(|t| &t.action),
+            Some(TaskAction::RunCommand { allow_failure: true, .. })
+        );
+        let result = TaskResult {
+            commits: Vec::new(),
+            pr_number: None,
+            pr_url: None,
+            output: Some(outcome.output.clone()),
+        };
+
+        if outcome.success || allow_failure {
+            if !self.mark_completed(id, result) {
+                return false;
+            }
+            if !outcome.success
+                && let Some(task) = self.get_task_mut(id)
+            {
+                task.error = Some(format!(
+                    "Command failed (exit {}), allowed to fail",
+                    exit_code_label(outcome.exit_code)
+                ));
+            }
+            true
+        } else {
+            let error = format!(
+                "Command failed (exit {}): {}",
+                exit_code_label(outcome.exit_code),
+                output_tail(&outcome.output, 5)
+            );
+            if !self.mark_failed(id, error) {
+                return false;
+            }
+            if let Some(task) = self.get_task_mut(id) {
+                task.result = Some(result);
+            }
+            true
+        }
+    }
+
+    /// Check the plan's internal consistency, returning every problem found
+    ///
+    /// Task ids must be unique, dependencies must name existing tasks and
+    /// not form a cycle, and branch names must be legal. A merge or review of
+    /// a branch no `create_worker` task creates is reported as a warning
+    /// (`PlanError::is_warning`), since the branch may already exist.
+    pub fn validate(&self) -> std::result::Result<(), Vec<PlanError>> {
+        let mut problems = Vec::new();
+
+        let mut ids = HashSet::new();
+        for task in &self.tasks {
+            if !ids.insert(task.id.as_str()) {
+                problems.push(PlanError::DuplicateId(task.id.clone()));
+            }
+        }
+
+        let mut depends_on = HashMap::new();
+        for task in &self.tasks {
+            if let Some(dependency) = task.depends_on() {
+                if ids.contains(dependency) {
+                    depends_on.insert(task.id.as_str(), dependency);
+                } else {
+                    problems.push(PlanError::UnknownDependency {
+                        task: task.id.clone(),
+                        depends_on: dependency.to_string(),
+                    });
+                }
+            }
+        }
+
+        // Each task waits for at most one other, so a cycle is found by
+        // following the chain until it comes back around
+        let mut in_cycle = HashSet::new();
+        for task in &self.tasks {
+            let start = task.id.as_str();
+            if in_cycle.contains(start) {
+                continue;
+            }
+            let mut chain = vec![start];
+            let mut current = start;
+            while let Some(&next) = depends_on.get(current) {
+                if next == start {
+                    in_cycle.extend(chain.iter().copied());
+                    chain.push(start);
+                    problems.push(PlanError::DependencyCycle(
+                        chain.iter().map(|id| id.to_string()).collect(),
+                    ));
+                    break;
+                }
+                if chain.contains(&next) {
+                    // A cycle further down the chain, reported from its own start
+                    break;
+                }
+                chain.push(next);
+                current = next;
+            }
+        }
+
+        let worker_branches: HashSet<&str> = self
+            .tasks
+            .iter()
+            .filter_map(|task| match &task.action {
+                TaskAction::CreateWorker { branch, .. } => Some(branch.as_str()),
+                _ => None,
+            })
+            .collect();
+        for task in &self.tasks {
+            for branch in task.action.branch_names() {
+                if let Err(reason) = check_branch_name(branch) {
+                    problems.push(PlanError::InvalidBranch {
+                        task: task.id.clone(),
+                        branch: branch.to_string(),
+                        reason,
+                    });
+                }
+            }
+            if let TaskAction::MergeBranch { branch, .. } | TaskAction::RequestReview { branch, .. } =
+                &task.action
+                && !worker_branches.contains(branch.as_str())
+            {
+                problems.push(PlanError::UnknownBranch {
+                    task: task.id.clone(),
+                    branch: branch.clone(),
+                });
+            }
+        }
+
+        if problems.is_empty() { Ok(()) } else { Err(problems) }
+    }
+
     /// Check if all tasks are completed (or failed/skipped)
     pub fn is_complete(&self) -> bool {
         self.tasks.iter().all(|t| {
@@ -275,6 +504,70 @@ impl Plan {
         let failed = self.tasks.iter().filter(|t| t.status == TaskStatus::Failed).count();
         (pending, running, completed, failed)
     }
+
+    /// Environment variables describing the plan outcome, for the
+    /// `on_plan_complete` hook
+    pub fn completion_env(&self) -> Vec<(&'static str, String)> {
+        let (_, _, completed, failed) = self.count_by_status();
+        let skipped = self
+            .tasks
+            .iter()
+            .filter(|t| t.status == TaskStatus::Skipped)
+            .count();
+        vec![
+            ("CCTAKT_PLAN_DESCRIPTION", self.description.clone().unwrap_or_default()),
+            ("CCTAKT_PLAN_TOTAL", self.tasks.len().to_string()),
+            ("CCTAKT_PLAN_COMPLETED", completed.to_string()),
+            ("CCTAKT_PLAN_FAILED", failed.to_string()),
+            ("CCTAKT_PLAN_SKIPPED", skipped.to_string()),
+        ]
+    }
+
+    /// Fraction of tasks that are done (completed or skipped), in 0.0..=1.0
+    pub fn progress_ratio(&self) -> f64 {
+        if self.tasks.is_empty() {
+            return 0.0;
+        }
+        self.done_count() as f64 / self.tasks.len() as f64
+    }
+
+    /// Rough estimate of seconds until the remaining tasks are done
+    ///
+    /// Uses the average wall-clock time per done task since the plan was
+    /// created, based on `updated_at`. Returns None when there's nothing
+    /// to base an estimate on or nothing left to do.
+    pub fn estimated_remaining_secs(&self) -> Option<u64> {
+        let done = self.done_count() as u64;
+        let remaining = self
+            .tasks
+            .iter()
+            .filter(|t| matches!(t.status, TaskStatus::Pending | TaskStatus::Running))
+            .count() as u64;
+        if done == 0 || remaining == 0 {
+            return None;
+        }
+
+        let last_done = self
+            .tasks
+            .iter()
+            .filter(|t| matches!(t.status, TaskStatus::Completed | TaskStatus::Skipped))
+            .filter_map(|t| t.updated_at)
+            .max()?;
+        let elapsed = last_done.checked_sub(self.created_at)?;
+        if elapsed == 0 {
+            return None;
+        }
+
+        Some(elapsed * remaining / done)
+    }
+
+    /// Number of completed or skipped tasks
+    fn done_count(&self) -> usize {
+        self.tasks
+            .iter()
+            .filter(|t| matches!(t.status, TaskStatus::Completed | TaskStatus::Skipped))
+            .count()
+    }
 }
 
 impl Default for Plan {
@@ -284,6 +577,14 @@ impl Default for Plan {
 }
 
 impl Task {
+    /// Id of the task this one waits for (a review's `after_task`)
+    pub fn depends_on(&self) -> Option<&str> {
+        match &self.action {
+            TaskAction::RequestReview { after_task, .. } => after_task.as_deref(),
+            _ => None,
+        }
+    }
+
     /// Create a new task with action
     pub fn new(id: impl Into<String>, action: TaskAction) -> Self {
         Self {
@@ -330,6 +631,31 @@ impl Task {
         )
     }
 
+    /// Create a branch merge task
+    pub fn merge_branch(
+        id: impl Into<String>,
+        branch: impl Into<String>,
+        target: Option<String>,
+    ) -> Self {
+        Self::new(
+            id,
+            TaskAction::MergeBranch {
+                branch: branch.into(),
+                target,
+            },
+        )
+    }
+
+    /// Create a worktree cleanup task
+    pub fn cleanup_worktree(id: impl Into<String>, worktree: impl Into<String>) -> Self {
+        Self::new(
+            id,
+            TaskAction::CleanupWorktree {
+                worktree: worktree.into(),
+            },
+        )
+    }
+
     /// Create a notification task
     pub fn notify(id: impl Into<String>, message: impl Into<String>) -> Self {
         Self::new(
@@ -357,6 +683,170 @@ impl Task {
     }
 }
 
+impl TaskAction {
+    /// Every branch the action names, including base and target branches
+    pub fn branch_names(&self) -> Vec<&str> {
+        let (branch, other) = match self {
+            TaskAction::CreateWorker { branch, base_branch, .. } => (branch, base_branch),
+            TaskAction::CreatePr { branch, base, .. } => (branch, base),
+            TaskAction::MergeBranch { branch, target } => (branch, target),
+            TaskAction::RequestReview { branch, .. } => (branch, &None),
+            TaskAction::CleanupWorktree { .. }
+            | TaskAction::RunCommand { .. }
+            | TaskAction::Notify { .. } => return Vec::new(),
+        };
+        std::iter::once(branch.as_str()).chain(other.as_deref()).collect()
+    }
+
+    /// One-line description for compact listings, e.g. `merge feat/x → main`
+    pub fn summary(&self) -> String {
+        match self {
+            TaskAction::CreateWorker {
+                branch, base_branch, ..
+            } => match base_branch {
+                Some(base) => format!("worker {branch} (from {base})"),
+                None => format!("worker {branch}"),
+            },
+            TaskAction::CreatePr {
+                branch,
+                title,
+                base,
+                draft,
+                ..
+            } => {
+                let draft = if *draft { " (draft)" } else { "" };
+                format!(
+                    "pr {branch} → {}: {title}{draft}",
+                    base.as_deref().unwrap_or("main")
+                )
+            }
+            TaskAction::MergeBranch { branch, target } => {
+                format!("merge {branch} → {}", target.as_deref().unwrap_or("main"))
+            }
+            TaskAction::CleanupWorktree { worktree } => format!("cleanup {worktree}"),
+            TaskAction::RunCommand {
+                worktree,
+                command,
+                allow_failure,
+            } => {
+                let allowed = if *allow_failure { " (may fail)" } else { "" };
+                format!("run `{command}` in {worktree}{allowed}")
+            }
+            TaskAction::Notify { message, .. } => format!("notify: {message}"),
+            TaskAction::RequestReview { branch, .. } => format!("review {branch}"),
+        }
+    }
+}
+
+/// A consistency problem found by `Plan::validate`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanError {
+    /// Two tasks share an id
+    DuplicateId(String),
+    /// A task waits for a task that isn't in the plan
+    UnknownDependency { task: String, depends_on: String },
+    /// Tasks wait for each other in a loop (the first id is repeated at the end)
+    DependencyCycle(Vec<String>),
+    /// A branch name git would reject
+    InvalidBranch {
+        task: String,
+        branch: String,
+        reason: &'static str,
+    },
+    /// A merge or review of a branch no `create_worker` task creates
+    UnknownBranch { task: String, branch: String },
+}
+
+impl PlanError {
+    /// Worth mentioning, but the plan can still run
+    pub fn is_warning(&self) -> bool {
+        matches!(self, PlanError::UnknownBranch { .. })
+    }
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanError::DuplicateId(id) => write!(f, "duplicate task id '{id}'"),
+            PlanError::UnknownDependency { task, depends_on } => {
+                write!(f, "task '{task}' waits for unknown task '{depends_on}'")
+            }
+            PlanError::DependencyCycle(ids) => write!(f, "dependency cycle: {}", ids.join(" -> ")),
+            PlanError::InvalidBranch { task, branch, reason } => {
+                write!(f, "task '{task}': '{branch}' is not a valid branch name ({reason})")
+            }
+            PlanError::UnknownBranch { task, branch } => write!(
+                f,
+                "task '{task}' uses branch '{branch}', which no create_worker task creates"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PlanError {}
+
+/// Check that `branch` is a name git accepts for a branch
+fn check_branch_name(branch: &str) -> std::result::Result<(), &'static str> {
+    if branch.starts_with('-') {
+        return Err("must not start with '-'");
+    }
+    if branch == "HEAD" || branch == "@" {
+        return Err("is reserved by git");
+    }
+    crate::config::check_ref_prefix(branch)
+}
+
+/// Whether a task may proceed given the RunCommand gates for its branch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GateStatus {
+    /// No blocking command for this branch
+    Clear,
+    /// A gating command for this branch has not finished yet
+    Waiting(String),
+    /// A gating command for this branch failed
+    Blocked(String),
+}
+
+/// Outcome of running a RunCommand task's command
+#[derive(Debug, Clone)]
+pub struct CommandOutcome {
+    /// Whether the command exited successfully
+    pub success: bool,
+    /// Exit code (None if terminated by a signal)
+    pub exit_code: Option<i32>,
+    /// Combined stdout and stderr
+    pub output: String,
+}
+
+/// Run a shell command in the given directory, capturing its output
+pub fn run_command_in(dir: &Path, command: &str) -> Result<CommandOutcome> {
+    run_command_with_env(dir, command, &[])
+}
+
+/// Run a shell command with extra environment variables, capturing its output
+pub fn run_command_with_env(
+    dir: &Path,
+    command: &str,
+    env: &[(&str, String)],
+) -> Result<CommandOutcome> {
+    let output = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(dir)
+        .envs(env.iter().map(|(name, value)| (name, value)))
+        .output()
+        .with_context(|| format!("Failed to run command: {command}"))?;
+
+    let mut combined = String::from_utf8_lossy(&output.stdout).into_owned();
+    combined.push_str(&String::from_utf8_lossy(&output.stderr));
+
+    Ok(CommandOutcome {
+        success: output.status.success(),
+        exit_code: output.status.code(),
+        output: combined,
+    })
+}
+
 /// Plan file manager
 pub struct PlanManager {
     /// Plan directory path
@@ -404,8 +894,7 @@ impl PlanManager {
         let content = fs::read_to_string(&path)
             .with_context(|| format!("Failed to read plan file: {path:?}"))?;
 
-        let plan: Plan = serde_json::from_str(&content)
-            .with_context(|| "Failed to parse plan file")?;
+        let plan = parse_plan(&content)?;
 
         // Update last modified time
         if let Ok(metadata) = fs::metadata(&path) {
@@ -478,6 +967,129 @@ impl PlanManager {
         self.last_modified = None;
         Ok(Some(archive_path))
     }
+
+    /// Archived plans, newest first
+    ///
+    /// Older schema versions are migrated as on load; a file that can't be
+    /// read or parsed is still listed, with its error.
+    pub fn history(&self) -> Result<Vec<ArchivedPlan>> {
+        let entries = match fs::read_dir(&self.plan_dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read {}", self.plan_dir.display()));
+            }
+        };
+        let mut plans: Vec<ArchivedPlan> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let path = entry.path();
+                let archived_at = archive_timestamp(path.file_name()?.to_str()?)?;
+                let plan = fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))
+                    .and_then(|content| parse_plan(&content));
+                Some(ArchivedPlan { path, archived_at, plan })
+            })
+            .collect();
+        plans.sort_by(|a, b| b.archived_at.cmp(&a.archived_at).then_with(|| b.path.cmp(&a.path)));
+        Ok(plans)
+    }
+}
+
+/// A plan moved aside by `PlanManager::archive`
+#[derive(Debug)]
+pub struct ArchivedPlan {
+    /// The `plan_<timestamp>.json` file
+    pub path: PathBuf,
+    /// When it was archived (Unix epoch, from the file name)
+    pub archived_at: u64,
+    /// The plan, or why it couldn't be read
+    pub plan: Result<Plan>,
+}
+
+/// Timestamp in an archived plan's file name (`plan_<timestamp>.json`)
+fn archive_timestamp(name: &str) -> Option<u64> {
+    name.strip_prefix("plan_")?.strip_suffix(".json")?.parse().ok()
+}
+
+/// Parse plan JSON, checking the schema version and migrating older plans
+pub fn parse_plan(content: &str) -> Result<Plan> {
+    let json: serde_json::Value =
+        serde_json::from_str(content).with_context(|| "Failed to parse plan file")?;
+
+    // Plans written before versioning was introduced have no version field
+    let version = match json.get("version") {
+        None => 0,
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .context("Plan version must be a non-negative integer")?,
+    };
+
+    if version > PLAN_VERSION {
+        bail!(
+            "Plan version {version} is newer than supported version {PLAN_VERSION}; \
+             please upgrade cctakt"
+        );
+    }
+
+    let json = migrate_plan(json, version)?;
+    serde_json::from_value(json).with_context(|| "Failed to parse plan file")
+}
+
+/// Migrate plan JSON from `from_version` to the current schema version
+///
+/// Each step upgrades the document by exactly one version, so a plan of any
+/// older version is brought up to date by running the steps in sequence.
+pub fn migrate_plan(mut json: serde_json::Value, from_version: u32) -> Result<serde_json::Value> {
+    if from_version > PLAN_VERSION {
+        bail!("Cannot migrate plan from unsupported version {from_version}");
+    }
+
+    for version in from_version..PLAN_VERSION {
+        match version {
+            0 => migrate_v0_to_v1(&mut json)?,
+            _ => bail!("No migration registered for plan version {version}"),
+        }
+    }
+
+    Ok(json)
+}
+
+/// v0 → v1: add the `version` field and rename `create_worker.task`
+/// to `create_worker.task_description`
+fn migrate_v0_to_v1(json: &mut serde_json::Value) -> Result<()> {
+    let plan = json
+        .as_object_mut()
+        .context("Plan must be a JSON object")?;
+
+    if let Some(tasks) = plan.get_mut("tasks").and_then(|t| t.as_array_mut()) {
+        for task in tasks {
+            let Some(action) = task.get_mut("action").and_then(|a| a.as_object_mut()) else {
+                continue;
+            };
+            if action.get("type").and_then(|t| t.as_str()) == Some("create_worker")
+                && !action.contains_key("task_description")
+                && let Some(description) = action.remove("task")
+            {
+                action.insert("task_description".to_string(), description);
+            }
+        }
+    }
+
+    plan.insert("version".to_string(), serde_json::Value::from(1));
+    Ok(())
+}
+
+/// Format an exit code for messages
+fn exit_code_label(code: Option<i32>) -> String {
+    code.map_or_else(|| "signal".to_string(), |c| c.to_string())
+}
+
+/// Last `n` non-empty lines of command output, joined with " | "
+pub fn output_tail(output: &str, n: usize) -> String {
+    let lines: Vec<&str> = output.lines().filter(|l| !l.trim().is_empty()).collect();
+    lines[lines.len().saturating_sub(n)..].join(" | ")
 }
 
 /// Get current Unix timestamp
@@ -594,6 +1206,45 @@ mod tests {
         assert!(plan.is_complete());
     }
 
+    #[test]
+    fn test_plan_progress_ratio() {
+        let mut plan = Plan::new();
+        assert_eq!(plan.progress_ratio(), 0.0);
+
+        plan.add_task(Task::notify("t-1", "msg"));
+        plan.add_task(Task::notify("t-2", "msg"));
+        plan.add_task(Task::notify("t-3", "msg"));
+        plan.add_task(Task::notify("t-4", "msg"));
+        plan.tasks[0].status = TaskStatus::Completed;
+        plan.tasks[1].status = TaskStatus::Skipped;
+        plan.tasks[2].status = TaskStatus::Failed;
+
+        assert_eq!(plan.progress_ratio(), 0.5);
+    }
+
+    #[test]
+    fn test_plan_estimated_remaining_secs() {
+        let mut plan = Plan::new();
+        plan.created_at = 1000;
+        for i in 1..=4 {
+            plan.add_task(Task::notify(format!("t-{i}"), "msg"));
+        }
+        // Nothing done yet: no estimate
+        assert_eq!(plan.estimated_remaining_secs(), None);
+
+        // Two tasks done in 120s -> 60s each, two remaining
+        plan.tasks[0].status = TaskStatus::Completed;
+        plan.tasks[0].updated_at = Some(1050);
+        plan.tasks[1].status = TaskStatus::Completed;
+        plan.tasks[1].updated_at = Some(1120);
+        assert_eq!(plan.estimated_remaining_secs(), Some(120));
+
+        // Everything finished: no estimate
+        plan.tasks[2].status = TaskStatus::Skipped;
+        plan.tasks[3].status = TaskStatus::Failed;
+        assert_eq!(plan.estimated_remaining_secs(), None);
+    }
+
     #[test]
     fn test_plan_count_by_status() {
         let mut plan = Plan::new();
@@ -611,6 +1262,25 @@ mod tests {
         assert_eq!((pending, running, completed, failed), (1, 1, 1, 0));
     }
 
+    #[test]
+    fn test_plan_completion_env() {
+        let mut plan = Plan::with_description("Nightly");
+        plan.add_task(Task::notify("t-1", "Test"));
+        plan.add_task(Task::notify("t-2", "Test"));
+        plan.add_task(Task::notify("t-3", "Test"));
+        plan.update_status("t-1", TaskStatus::Completed);
+        plan.mark_failed("t-2", "boom");
+        plan.update_status("t-3", TaskStatus::Skipped);
+
+        let env = plan.completion_env();
+        let get = |name: &str| env.iter().find(|(n, _)| *n == name).map(|(_, v)| v.as_str());
+        assert_eq!(get("CCTAKT_PLAN_DESCRIPTION"), Some("Nightly"));
+        assert_eq!(get("CCTAKT_PLAN_TOTAL"), Some("3"));
+        assert_eq!(get("CCTAKT_PLAN_COMPLETED"), Some("1"));
+        assert_eq!(get("CCTAKT_PLAN_FAILED"), Some("1"));
+        assert_eq!(get("CCTAKT_PLAN_SKIPPED"), Some("1"));
+    }
+
     #[test]
     fn test_plan_manager_save_load() {
         let temp_dir = TempDir::new().unwrap();
@@ -728,6 +1398,7 @@ mod tests {
             ],
             pr_number: None,
             pr_url: None,
+            output: None,
         };
         assert_eq!(result.commits.len(), 2);
         assert!(result.commits[0].contains("abc1234"));
@@ -739,6 +1410,7 @@ mod tests {
             commits: Vec::new(),
             pr_number: Some(42),
             pr_url: Some("https://github.com/owner/repo/pull/42".to_string()),
+            output: None,
         };
         assert_eq!(result.pr_number, Some(42));
         assert!(result.pr_url.as_ref().unwrap().contains("pull/42"));
@@ -750,6 +1422,7 @@ mod tests {
             commits: vec!["abc1234 test commit".to_string()],
             pr_number: Some(123),
             pr_url: Some("https://example.com/pr/123".to_string()),
+            output: None,
         };
         let json = serde_json::to_string(&result).unwrap();
         assert!(json.contains("\"commits\""));
@@ -797,6 +1470,7 @@ mod tests {
             commits: vec!["abc123 test".to_string()],
             pr_number: None,
             pr_url: None,
+            output: None,
         };
 
         assert!(plan.mark_completed("t-1", result));
@@ -836,6 +1510,7 @@ mod tests {
             commits: vec!["abc123 done".to_string()],
             pr_number: None,
             pr_url: None,
+            output: None,
         });
 
         let json = serde_json::to_string(&task).unwrap();
@@ -897,6 +1572,39 @@ mod tests {
         assert!(archive.to_string_lossy().contains("plan_"));
     }
 
+    #[test]
+    fn test_plan_manager_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = PlanManager::new(temp_dir.path());
+        assert!(manager.history().unwrap().is_empty());
+
+        let dir = temp_dir.path().join(PLAN_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        let mut plan = Plan::with_description("Newer");
+        plan.add_task(Task::notify("n1", "hi"));
+        fs::write(dir.join("plan_200.json"), serde_json::to_string(&plan).unwrap()).unwrap();
+        // Written before versioning, migrated on read
+        fs::write(
+            dir.join("plan_100.json"),
+            r#"{"created_at":0,"description":"Older","tasks":[]}"#,
+        )
+        .unwrap();
+        fs::write(dir.join("plan_300.json"), "not json").unwrap();
+        // Not archives
+        fs::write(dir.join("plan.json"), "{}").unwrap();
+        fs::write(dir.join("plan_x.json"), "{}").unwrap();
+
+        let history = manager.history().unwrap();
+        let stamps: Vec<u64> = history.iter().map(|archived| archived.archived_at).collect();
+        assert_eq!(stamps, [300, 200, 100]);
+        assert!(history[0].plan.is_err());
+        let newer = history[1].plan.as_ref().unwrap();
+        assert_eq!(newer.description.as_deref(), Some("Newer"));
+        assert_eq!(newer.tasks.len(), 1);
+        let older = history[2].plan.as_ref().unwrap();
+        assert_eq!(older.description.as_deref(), Some("Older"));
+    }
+
     #[test]
     fn test_plan_manager_archive_no_file() {
         let temp_dir = TempDir::new().unwrap();
@@ -934,12 +1642,136 @@ mod tests {
         let action = TaskAction::RunCommand {
             worktree: "feat/test".to_string(),
             command: "cargo test".to_string(),
+            allow_failure: false,
         };
         let json = serde_json::to_string(&action).unwrap();
         assert!(json.contains("\"type\":\"run_command\""));
         assert!(json.contains("cargo test"));
     }
 
+    #[test]
+    fn test_task_action_run_command_allow_failure_default() {
+        let json = r#"{"type": "run_command", "worktree": "feat/test", "command": "cargo test"}"#;
+        let action: TaskAction = serde_json::from_str(json).unwrap();
+        match action {
+            TaskAction::RunCommand { allow_failure, .. } => assert!(!allow_failure),
+            _ => panic!("Wrong action type"),
+        }
+    }
+
+    // ==================== RunCommand gate tests ====================
+
+    fn run_command_task(id: &str, worktree: &str, command: &str, allow_failure: bool) -> Task {
+        Task::new(
+            id,
+            TaskAction::RunCommand {
+                worktree: worktree.to_string(),
+                command: command.to_string(),
+                allow_failure,
+            },
+        )
+    }
+
+    #[test]
+    fn test_run_command_in_success() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("marker.txt"), "hello").unwrap();
+
+        let outcome = run_command_in(temp_dir.path(), "cat marker.txt").unwrap();
+        assert!(outcome.success);
+        assert_eq!(outcome.exit_code, Some(0));
+        assert_eq!(outcome.output, "hello");
+    }
+
+    #[test]
+    fn test_run_command_in_failure_captures_stderr() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let outcome = run_command_in(temp_dir.path(), "echo boom >&2; exit 3").unwrap();
+        assert!(!outcome.success);
+        assert_eq!(outcome.exit_code, Some(3));
+        assert!(outcome.output.contains("boom"));
+    }
+
+    #[test]
+    fn test_run_command_with_env() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let env = [("CCTAKT_PLAN_FAILED", "2".to_string())];
+        let outcome = run_command_with_env(temp_dir.path(), "printf %s \"$CCTAKT_PLAN_FAILED\"", &env).unwrap();
+        assert!(outcome.success);
+        assert_eq!(outcome.output, "2");
+    }
+
+    #[test]
+    fn test_failed_command_blocks_dependent_tasks() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut plan = Plan::new();
+        plan.add_task(run_command_task("test-1", "feat/x", "echo FAIL; exit 1", false));
+        plan.add_task(Task::merge_branch("merge-1", "feat/x", None));
+
+        assert_eq!(
+            plan.gate_status("feat/x", "merge-1"),
+            GateStatus::Waiting("test-1".to_string())
+        );
+
+        let outcome = run_command_in(temp_dir.path(), "echo FAIL; exit 1").unwrap();
+        assert!(plan.complete_run_command("test-1", &outcome));
+
+        let task = plan.get_task("test-1").unwrap();
+        assert_eq!(task.status, TaskStatus::Failed);
+        assert!(task.error.as_ref().unwrap().contains("FAIL"));
+        assert_eq!(
+            plan.gate_status("feat/x", "merge-1"),
+            GateStatus::Blocked("test-1".to_string())
+        );
+        // Other branches are unaffected
+        assert_eq!(plan.gate_status("feat/y", "merge-2"), GateStatus::Clear);
+        // Gates only apply to tasks that come after them
+        assert_eq!(plan.gate_status("feat/x", "test-1"), GateStatus::Clear);
+    }
+
+    #[test]
+    fn test_allowed_failure_completes_with_warning() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut plan = Plan::new();
+        plan.add_task(run_command_task("lint-1", "feat/x", "exit 1", true));
+        plan.add_task(Task::merge_branch("merge-1", "feat/x", None));
+
+        // Advisory commands never gate
+        assert_eq!(plan.gate_status("feat/x", "merge-1"), GateStatus::Clear);
+
+        let outcome = run_command_in(temp_dir.path(), "exit 1").unwrap();
+        assert!(plan.complete_run_command("lint-1", &outcome));
+
+        let task = plan.get_task("lint-1").unwrap();
+        assert_eq!(task.status, TaskStatus::Completed);
+        assert!(task.error.as_ref().unwrap().contains("allowed to fail"));
+        assert_eq!(plan.gate_status("feat/x", "merge-1"), GateStatus::Clear);
+    }
+
+    #[test]
+    fn test_successful_command_clears_gate() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut plan = Plan::new();
+        plan.add_task(run_command_task("test-1", ".worktrees/feat-x", "true", false));
+
+        let outcome = run_command_in(temp_dir.path(), "true").unwrap();
+        plan.complete_run_command("test-1", &outcome);
+
+        let task = plan.get_task("test-1").unwrap();
+        assert_eq!(task.status, TaskStatus::Completed);
+        assert!(task.error.is_none());
+        assert_eq!(plan.gate_status("feat/x", "merge-1"), GateStatus::Clear);
+    }
+
+    #[test]
+    fn test_output_tail() {
+        assert_eq!(output_tail("a\nb\n\nc\nd", 2), "c | d");
+        assert_eq!(output_tail("only", 5), "only");
+        assert_eq!(output_tail("", 5), "");
+    }
+
     #[test]
     fn test_task_action_notify_levels() {
         let levels = [
@@ -971,6 +1803,133 @@ mod tests {
         assert!(json.contains("\"after_task\":\"w-1\""));
     }
 
+    #[test]
+    fn test_task_action_summary() {
+        assert_eq!(
+            Task::create_worker("w", "feat/a", "Do it").action.summary(),
+            "worker feat/a"
+        );
+        assert_eq!(
+            Task::merge_branch("m", "feat/a", Some("develop".to_string())).action.summary(),
+            "merge feat/a → develop"
+        );
+        assert_eq!(
+            Task::request_review("r", "feat/a", Some("w".to_string())).action.summary(),
+            "review feat/a"
+        );
+        let run = TaskAction::RunCommand {
+            worktree: "feat/a".to_string(),
+            command: "cargo test".to_string(),
+            allow_failure: true,
+        };
+        assert_eq!(run.summary(), "run `cargo test` in feat/a (may fail)");
+    }
+
+    #[test]
+    fn test_reset_task() {
+        let mut plan = Plan::new();
+        plan.add_task(Task::create_worker("w1", "feat/a", "Do it"));
+        plan.mark_failed("w1", "token expired");
+
+        assert!(plan.reset_task("w1"));
+        let task = plan.get_task("w1").unwrap();
+        assert_eq!(task.status, TaskStatus::Pending);
+        assert_eq!(task.error, None);
+        assert!(!plan.reset_task("missing"));
+    }
+
+    #[test]
+    fn test_skip_with_dependents() {
+        let mut plan = Plan::new();
+        plan.add_task(Task::create_worker("w1", "feat/a", "Do a"));
+        plan.add_task(Task::request_review("w1-review", "feat/a", Some("w1".to_string())));
+        plan.add_task(Task::request_review("w1-again", "feat/a", Some("w1-review".to_string())));
+        plan.add_task(Task::create_worker("w2", "feat/b", "Do b"));
+        plan.mark_failed("w1", "boom");
+
+        let skipped = plan.skip_with_dependents("w1");
+        assert_eq!(skipped, ["w1", "w1-review", "w1-again"]);
+        assert_eq!(plan.get_task("w2").unwrap().status, TaskStatus::Pending);
+        assert!(plan.skip_with_dependents("missing").is_empty());
+    }
+
+    #[test]
+    fn test_validate_accepts_consistent_plan() {
+        let mut plan = Plan::new();
+        plan.add_task(Task::create_worker("w1", "feat/a", "Do a"));
+        plan.add_task(Task::request_review("w1-review", "feat/a", Some("w1".to_string())));
+        plan.add_task(Task::merge_branch("w1-merge", "feat/a", Some("main".to_string())));
+        assert_eq!(plan.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_reports_every_problem() {
+        let mut plan = Plan::new();
+        plan.add_task(Task::create_worker("w1", "feat/a", "Do a"));
+        plan.add_task(Task::create_worker("w1", "feat/b", "Do b"));
+        plan.add_task(Task::request_review("r1", "feat/a", Some("w9".to_string())));
+        plan.add_task(Task::create_worker("w2", "feat/bad name", "Do c"));
+        plan.add_task(Task::merge_branch("m1", "feat/old", None));
+
+        let problems = plan.validate().unwrap_err();
+        assert_eq!(
+            problems,
+            vec![
+                PlanError::DuplicateId("w1".to_string()),
+                PlanError::UnknownDependency {
+                    task: "r1".to_string(),
+                    depends_on: "w9".to_string(),
+                },
+                PlanError::InvalidBranch {
+                    task: "w2".to_string(),
+                    branch: "feat/bad name".to_string(),
+                    reason: "must not contain spaces, control characters or any of ~^:?*[\\",
+                },
+                PlanError::UnknownBranch {
+                    task: "m1".to_string(),
+                    branch: "feat/old".to_string(),
+                },
+            ]
+        );
+        assert!(!problems[0].is_warning());
+        assert!(problems[3].is_warning());
+        assert_eq!(problems[1].to_string(), "task 'r1' waits for unknown task 'w9'");
+    }
+
+    #[test]
+    fn test_validate_finds_dependency_cycles() {
+        let mut plan = Plan::new();
+        plan.add_task(Task::create_worker("w1", "feat/a", "Do a"));
+        plan.add_task(Task::request_review("a", "feat/a", Some("b".to_string())));
+        plan.add_task(Task::request_review("b", "feat/a", Some("a".to_string())));
+        plan.add_task(Task::request_review("c", "feat/a", Some("a".to_string())));
+        plan.add_task(Task::request_review("self", "feat/a", Some("self".to_string())));
+
+        let problems = plan.validate().unwrap_err();
+        let cycles: Vec<String> = problems.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            cycles,
+            ["dependency cycle: a -> b -> a", "dependency cycle: self -> self"]
+        );
+    }
+
+    #[test]
+    fn test_check_branch_name() {
+        for valid in ["feat/a", "fix/issue-12", "main"] {
+            assert!(check_branch_name(valid).is_ok(), "{valid}");
+        }
+        for invalid in ["-x", "HEAD", "feat/", "a..b", "feat/x.lock", "a b", ""] {
+            assert!(check_branch_name(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_task_depends_on() {
+        assert_eq!(Task::request_review("r", "feat/a", Some("w".to_string())).depends_on(), Some("w"));
+        assert_eq!(Task::request_review("r", "feat/a", None).depends_on(), None);
+        assert_eq!(Task::create_worker("w", "feat/a", "Do it").depends_on(), None);
+    }
+
     #[test]
     fn test_task_action_request_review_no_after_task() {
         let action = TaskAction::RequestReview {
@@ -1014,6 +1973,128 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_task_merge_branch_helper() {
+        let task = Task::merge_branch("m-1", "feat/test", None);
+        assert_eq!(task.id, "m-1");
+        match task.action {
+            TaskAction::MergeBranch { branch, target } => {
+                assert_eq!(branch, "feat/test");
+                assert!(target.is_none());
+            }
+            _ => panic!("Wrong action type"),
+        }
+    }
+
+    #[test]
+    fn test_task_cleanup_worktree_helper() {
+        let task = Task::cleanup_worktree("c-1", "feat-test");
+        match task.action {
+            TaskAction::CleanupWorktree { worktree } => assert_eq!(worktree, "feat-test"),
+            _ => panic!("Wrong action type"),
+        }
+    }
+
+    // ==================== Schema version / migration ====================
+
+    #[test]
+    fn test_parse_plan_current_version() {
+        let json = r#"{"version": 1, "tasks": []}"#;
+        let plan = parse_plan(json).unwrap();
+        assert_eq!(plan.version, 1);
+    }
+
+    #[test]
+    fn test_parse_plan_rejects_future_version() {
+        let json = r#"{"version": 99, "tasks": []}"#;
+        let err = parse_plan(json).unwrap_err().to_string();
+        assert!(err.contains("99"));
+        assert!(err.contains("newer than supported"));
+    }
+
+    #[test]
+    fn test_parse_plan_rejects_invalid_version() {
+        let json = r#"{"version": "one", "tasks": []}"#;
+        assert!(parse_plan(json).is_err());
+    }
+
+    #[test]
+    fn test_migrate_plan_v0_to_v1() {
+        let json = serde_json::json!({
+            "tasks": [{
+                "id": "w-1",
+                "action": {"type": "create_worker", "branch": "feat/x", "task": "Do it"}
+            }]
+        });
+
+        let migrated = migrate_plan(json, 0).unwrap();
+        assert_eq!(migrated["version"], 1);
+        assert_eq!(migrated["tasks"][0]["action"]["task_description"], "Do it");
+        assert!(migrated["tasks"][0]["action"].get("task").is_none());
+    }
+
+    #[test]
+    fn test_parse_plan_upgrades_unversioned_plan() {
+        let json = r#"{
+            "tasks": [{
+                "id": "w-1",
+                "action": {"type": "create_worker", "branch": "feat/x", "task": "Do it"}
+            }]
+        }"#;
+
+        let plan = parse_plan(json).unwrap();
+        assert_eq!(plan.version, PLAN_VERSION);
+        match &plan.tasks[0].action {
+            TaskAction::CreateWorker { task_description, .. } => {
+                assert_eq!(task_description, "Do it");
+            }
+            _ => panic!("Wrong action type"),
+        }
+    }
+
+    #[test]
+    fn test_migrate_plan_current_version_is_noop() {
+        let json = serde_json::json!({"version": 1, "tasks": []});
+        assert_eq!(migrate_plan(json.clone(), PLAN_VERSION).unwrap(), json);
+    }
+
+    #[test]
+    fn test_migrate_plan_future_version_errors() {
+        let json = serde_json::json!({"version": 2, "tasks": []});
+        assert!(migrate_plan(json, PLAN_VERSION + 1).is_err());
+    }
+
+    #[test]
+    fn test_plan_manager_load_rejects_future_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = PlanManager::new(temp_dir.path());
+        manager.ensure_dir().unwrap();
+        fs::write(manager.plan_file(), r#"{"version": 2, "tasks": []}"#).unwrap();
+
+        assert!(manager.load().is_err());
+    }
+
+    // ==================== Task ID generation ====================
+
+    #[test]
+    fn test_next_task_id_empty_plan() {
+        let plan = Plan::new();
+        assert_eq!(plan.next_task_id("tui"), "tui-1");
+    }
+
+    #[test]
+    fn test_next_task_id_skips_existing() {
+        let mut plan = Plan::new();
+        plan.add_task(Task::notify("tui-1", "a"));
+        plan.add_task(Task::notify("tui-3", "b"));
+        plan.add_task(Task::notify("w-2", "c"));
+
+        assert_eq!(plan.next_task_id("tui"), "tui-2");
+        plan.add_task(Task::notify(plan.next_task_id("tui"), "d"));
+        assert_eq!(plan.next_task_id("tui"), "tui-4");
+        assert_eq!(plan.next_task_id("w"), "w-1");
+    }
+
     // ==================== Plan edge cases ====================
 
     #[test]
@@ -1133,6 +2214,7 @@ mod tests {
             commits: vec!["commit1".to_string(), "commit2".to_string()],
             pr_number: Some(100),
             pr_url: Some("https://example.com/pr/100".to_string()),
+            output: None,
         });
         plan.mark_failed("n-1", "Test error");
 
@@ -1198,6 +2280,7 @@ mod proptests {
                 commits,
                 pr_number,
                 pr_url,
+                output: None,
             })
     }
 
@@ -1226,7 +2309,11 @@ mod proptests {
                 .prop_map(|(branch, target)| TaskAction::MergeBranch { branch, target }),
             "[a-z]+/[a-z-]+".prop_map(|worktree| TaskAction::CleanupWorktree { worktree }),
             ("[a-z]+/[a-z-]+", "[a-z]+ [a-z]+")
-                .prop_map(|(worktree, command)| TaskAction::RunCommand { worktree, command }),
+                .prop_map(|(worktree, command)| TaskAction::RunCommand {
+                    worktree,
+                    command,
+                    allow_failure: false,
+                }),
             (".{1,100}", arb_notify_level())
                 .prop_map(|(message, level)| TaskAction::Notify { message, level }),
         ]
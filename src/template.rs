@@ -3,6 +3,12 @@
 //! Generates task instructions from GitHub issues using templates.
 
 use crate::github::Issue;
+use crate::plan::{Plan, Task, TaskAction};
+use crate::text::{display_width, truncate_to_width};
+use crate::worktree::WorktreeNaming;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
 
 /// Default task template
 const DEFAULT_TEMPLATE: &str = r#"
@@ -18,7 +24,10 @@ Please work on the following GitHub issue:
 3. Implement the required changes
 4. Write tests if applicable
 5. Run tests to verify your changes work correctly
-6. Commit with message referencing the issue (e.g., "Fix #{{number}}: ...")
+6. Commit with a message referencing the issue, e.g.:
+   {{commit_subject}}
+
+   Closes #{{number}}
 
 IMPORTANT: Continue working until all tasks are complete. If you encounter errors, fix them. After committing, verify everything works and report completion status.
 "#;
@@ -36,10 +45,11 @@ impl TaskTemplate {
     /// Available placeholders:
     /// - `{{number}}` - Issue number
     /// - `{{title}}` - Issue title
-    /// - `{{body}}` - Issue body/description
+    /// - `{{body}}` - Issue body/description (see `normalize_issue_body`)
     /// - `{{url}}` - Issue URL
     /// - `{{labels}}` - Comma-separated label names
     /// - `{{state}}` - Issue state (open/closed)
+    /// - `{{commit_subject}}` - Suggested commit subject (see `suggest_commit_message`)
     pub fn new(template: &str) -> Self {
         Self {
             template: template.to_string(),
@@ -53,16 +63,26 @@ impl TaskTemplate {
 
     /// Render the template with issue data
     pub fn render(&self, issue: &Issue) -> String {
-        let body = issue.body.clone().unwrap_or_else(|| "(No description provided)".to_string());
+        let body = issue
+            .body
+            .as_deref()
+            .map(normalize_issue_body)
+            .filter(|body| !body.is_empty())
+            .unwrap_or_else(|| "(No description provided)".to_string());
         let labels = issue.label_names();
 
-        self.template
-            .replace("{{number}}", &issue.number.to_string())
-            .replace("{{title}}", &issue.title)
-            .replace("{{body}}", &body)
-            .replace("{{url}}", &issue.html_url)
-            .replace("{{labels}}", &labels)
-            .replace("{{state}}", &issue.state)
+        fill_placeholders(
+            &self.template,
+            &[
+                ("number", &issue.number.to_string()),
+                ("title", &issue.title),
+                ("body", &body),
+                ("url", &issue.html_url),
+                ("labels", &labels),
+                ("state", &issue.state),
+                ("commit_subject", &suggest_commit_subject(issue)),
+            ],
+        )
     }
 
     /// Get the raw template string
@@ -77,42 +97,846 @@ impl Default for TaskTemplate {
     }
 }
 
+/// Replace `{{name}}` placeholders; unknown placeholders are left as-is
+pub fn fill_placeholders(template: &str, values: &[(&str, &str)]) -> String {
+    values
+        .iter()
+        .fold(template.to_string(), |acc, (name, value)| {
+            acc.replace(&format!("{{{{{name}}}}}"), value)
+        })
+}
+
+/// Prompts for the built-in workers that teams can customize
+///
+/// A template is read from `.cctakt/<kind>_worker_prompt.md` if present,
+/// otherwise from the `[prompts]` config section; with neither, callers use
+/// the built-in default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkerPrompt {
+    /// MergeWorker: `{{branch}}`, `{{main_branch}}`
+    Merge,
+    /// BuildWorker: `{{branch}}`, `{{build_command}}`, `{{test_command}}`, `{{steps}}`
+    Build,
+}
+
+impl WorkerPrompt {
+    /// Template file name inside `.cctakt/`
+    pub fn file_name(self) -> &'static str {
+        match self {
+            WorkerPrompt::Merge => "merge_worker_prompt.md",
+            WorkerPrompt::Build => "build_worker_prompt.md",
+        }
+    }
+
+    /// Custom template from `cctakt_dir` or `configured`, if any
+    pub fn load(self, configured: Option<&str>, cctakt_dir: &Path) -> Option<String> {
+        std::fs::read_to_string(cctakt_dir.join(self.file_name()))
+            .ok()
+            .filter(|content| !content.trim().is_empty())
+            .or_else(|| configured.map(str::to_string))
+    }
+}
+
+/// Where GitHub looks for a pull request template, relative to the repository root
+pub const PULL_REQUEST_TEMPLATE_PATHS: &[&str] = &[
+    ".github/pull_request_template.md",
+    ".github/PULL_REQUEST_TEMPLATE.md",
+    "pull_request_template.md",
+    "PULL_REQUEST_TEMPLATE.md",
+    "docs/pull_request_template.md",
+    "docs/PULL_REQUEST_TEMPLATE.md",
+];
+
+/// The repository's pull request template, if it has a non-empty one
+pub fn load_pull_request_template(repo_root: &Path) -> Option<String> {
+    PULL_REQUEST_TEMPLATE_PATHS.iter().find_map(|path| {
+        std::fs::read_to_string(repo_root.join(path))
+            .ok()
+            .filter(|content| !content.trim().is_empty())
+    })
+}
+
+/// Pull request body: `body`, else the template, plus `Closes #N` for the issue
+///
+/// The closing line is left out if the text already mentions `#N`.
+/// Returns None when there is nothing to say.
+pub fn pull_request_body(
+    body: Option<&str>,
+    template: Option<&str>,
+    issue: Option<u64>,
+) -> Option<String> {
+    let mut text = body.or(template).unwrap_or_default().trim_end().to_string();
+    if let Some(number) = issue {
+        let reference = format!("#{number}");
+        let mentioned = text
+            .match_indices(&reference)
+            .any(|(i, _)| !text[i + reference.len()..].starts_with(|c: char| c.is_ascii_digit()));
+        if !mentioned {
+            if !text.is_empty() {
+                text.push_str("\n\n");
+            }
+            text.push_str(&format!("Closes {reference}"));
+        }
+    }
+    Some(text).filter(|text| !text.is_empty())
+}
+
 /// Quick template for simple task generation
 pub fn render_task(issue: &Issue) -> String {
     TaskTemplate::default().render(issue)
 }
 
+/// Plain-text version of an issue body for prompts and previews
+///
+/// Strips HTML comments, images and `<details>`/`<summary>` tags, flattens
+/// headings, renders task-list items as `[ ]` / `[x]` and turns links into
+/// `text (url)`, resolving reference-style links. Fenced code blocks and
+/// inline code are kept verbatim.
+pub fn normalize_issue_body(body: &str) -> String {
+    let references = link_references(body);
+    // (text, inside a code block)
+    let mut lines: Vec<(String, bool)> = Vec::new();
+    let mut fence: Option<&str> = None;
+    let mut in_comment = false;
+
+    for line in body.lines() {
+        if let Some(marker) = fence {
+            lines.push((line.to_string(), true));
+            if line.trim_start().starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if !in_comment && let Some(marker) = fence_marker(line) {
+            fence = Some(marker);
+            lines.push((line.to_string(), true));
+            continue;
+        }
+
+        let text = strip_html_comments(line, &mut in_comment);
+        if text.trim().is_empty() {
+            // Keep real blank lines, drop lines that only held markup
+            if line.trim().is_empty() {
+                lines.push((String::new(), false));
+            }
+            continue;
+        }
+        if link_definition(&text).is_some() {
+            continue;
+        }
+
+        let text = strip_details_tags(&text);
+        if text.trim().is_empty() {
+            continue;
+        }
+        let text = flatten_heading(&text)
+            .or_else(|| task_list_item(&text))
+            .unwrap_or(text);
+        let text = render_inline(&text, &references);
+        if !text.trim().is_empty() {
+            lines.push((text.trim_end().to_string(), false));
+        }
+    }
+
+    // Collapse runs of blank lines left by removed markup
+    let mut normalized = String::new();
+    let mut blank = true;
+    for (line, code) in &lines {
+        if line.is_empty() && !code {
+            if !blank {
+                normalized.push('\n');
+            }
+            blank = true;
+            continue;
+        }
+        normalized.push_str(line);
+        normalized.push('\n');
+        blank = false;
+    }
+    normalized.trim_end().to_string()
+}
+
+/// Unchecked task-list items (`- [ ] ...`) of an issue body, in order
+///
+/// Checked items are already done and are skipped, as are items inside
+/// code blocks. Item text is normalized like `normalize_issue_body`.
+pub fn issue_subtask_items(body: &str) -> Vec<String> {
+    normalize_issue_body(body)
+        .lines()
+        .scan(None::<&str>, |fence, line| {
+            if let Some(marker) = *fence {
+                if line.trim_start().starts_with(marker) {
+                    *fence = None;
+                }
+                return Some(None);
+            }
+            *fence = fence_marker(line);
+            Some(
+                line.trim_start()
+                    .strip_prefix("[ ] ")
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty() && fence.is_none())
+                    .map(str::to_string),
+            )
+        })
+        .flatten()
+        .collect()
+}
+
+/// One `CreateWorker` plan task per unchecked sub-task of `issue`
+///
+/// Tasks get `issue-<N>-<k>` ids and `<prefix>/issue-<N>-<k>-<slug>`
+/// branches; each worker is told its sub-task and given the whole issue for
+/// context. Empty when the issue has no checklist.
+pub fn issue_subtasks(issue: &Issue, prefix: &str, options: &SlugOptions) -> Vec<Task> {
+    let body = issue.body.as_deref().unwrap_or_default();
+    let items = issue_subtask_items(body);
+    let context = normalize_issue_body(body);
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let k = i + 1;
+            let slug = options.slugify(item);
+            let branch = if slug.is_empty() {
+                format!("{prefix}/issue-{}-{k}", issue.number)
+            } else {
+                format!("{prefix}/issue-{}-{k}-{slug}", issue.number)
+            };
+            let description = format!(
+                "Work on sub-task {k} of {} from GitHub issue #{}: {}\n\n\
+                 Sub-task: {item}\n\n\
+                 Only do this sub-task; the others are handled by separate workers.\n\n\
+                 ## Issue\n\n{context}\n\n\
+                 Commit with a message referencing the issue, e.g.:\n   {}",
+                items.len(),
+                issue.number,
+                issue.title,
+                suggest_commit_subject(issue),
+            );
+            Task::create_worker(format!("issue-{}-{k}", issue.number), branch, description)
+        })
+        .collect()
+}
+
+/// Plan taking each issue through worker → review → merge → cleanup
+///
+/// Workers get `issue-<N>` ids and branches from `suggest_branch_name_with`,
+/// each with the prefix from `branch_prefix_for`; see `lifecycle_plan` for
+/// the rest of the chain.
+pub fn issue_plan(
+    issues: &[Issue],
+    prefix: &str,
+    label_prefixes: &HashMap<String, String>,
+    options: &SlugOptions,
+    naming: WorktreeNaming,
+) -> Plan {
+    let description = match issues {
+        [issue] => format!("Issue #{}: {}", issue.number, issue.title),
+        _ => format!(
+            "Issues {}",
+            issues
+                .iter()
+                .map(|issue| format!("#{}", issue.number))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    let workers = issues
+        .iter()
+        .map(|issue| {
+            Task::create_worker(
+                format!("issue-{}", issue.number),
+                suggest_branch_name_with(
+                    issue,
+                    branch_prefix_for(issue, prefix, label_prefixes),
+                    options,
+                ),
+                render_task(issue),
+            )
+        })
+        .collect();
+    lifecycle_plan(description, workers, naming)
+}
+
+/// Like `issue_plan`, but with one worker per unchecked checklist item of
+/// `issue` (see `issue_subtasks`); the plan has no tasks if there are none
+pub fn checklist_plan(
+    issue: &Issue,
+    prefix: &str,
+    options: &SlugOptions,
+    naming: WorktreeNaming,
+) -> Plan {
+    lifecycle_plan(
+        format!("Issue #{}: {}", issue.number, issue.title),
+        issue_subtasks(issue, prefix, options),
+        naming,
+    )
+}
+
+/// Plan with the given `CreateWorker` tasks, each followed by review, merge
+/// and cleanup tasks (`<id>-review`, `<id>-merge`, `<id>-cleanup`)
+///
+/// All workers come first so they run side by side; each review waits for
+/// its worker through `after_task`, and the plan runs the rest in order.
+pub fn lifecycle_plan(
+    description: impl Into<String>,
+    workers: Vec<Task>,
+    naming: WorktreeNaming,
+) -> Plan {
+    let follow_ups: Vec<Task> = workers
+        .iter()
+        .filter_map(|worker| match &worker.action {
+            TaskAction::CreateWorker { branch, .. } => Some((worker.id.clone(), branch.clone())),
+            _ => None,
+        })
+        .flat_map(|(id, branch)| {
+            let worktree = naming.dir_name(&branch).display().to_string();
+            [
+                Task::request_review(format!("{id}-review"), &branch, Some(id.clone())),
+                Task::merge_branch(format!("{id}-merge"), &branch, None),
+                Task::cleanup_worktree(format!("{id}-cleanup"), worktree),
+            ]
+        })
+        .collect();
+
+    let mut plan = Plan::with_description(description);
+    for task in workers.into_iter().chain(follow_ups) {
+        plan.add_task(task);
+    }
+    plan
+}
+
+/// Opening code fence marker (three backticks or tildes) of `line`, if any
+fn fence_marker(line: &str) -> Option<&'static str> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("```") {
+        Some("```")
+    } else if trimmed.starts_with("~~~") {
+        Some("~~~")
+    } else {
+        None
+    }
+}
+
+/// Remove `<!-- ... -->` from a line; `in_comment` carries open comments across lines
+fn strip_html_comments(line: &str, in_comment: &mut bool) -> String {
+    let mut text = String::new();
+    let mut rest = line;
+    loop {
+        if *in_comment {
+            match rest.find("-->") {
+                Some(end) => {
+                    rest = &rest[end + 3..];
+                    *in_comment = false;
+                }
+                None => return text,
+            }
+        }
+        match rest.find("<!--") {
+            Some(start) => {
+                text.push_str(&rest[..start]);
+                rest = &rest[start + 4..];
+                *in_comment = true;
+            }
+            None => {
+                text.push_str(rest);
+                return text;
+            }
+        }
+    }
+}
+
+/// `[label]: url` reference definition, as `(label, url)`
+fn link_definition(line: &str) -> Option<(String, String)> {
+    let rest = line.trim_start().strip_prefix('[')?;
+    let (label, rest) = rest.split_once("]:")?;
+    let url = rest.split_whitespace().next()?;
+    (!label.is_empty()).then(|| (label.to_lowercase(), url.to_string()))
+}
+
+/// Reference definitions outside code blocks, keyed by lowercase label
+fn link_references(body: &str) -> Vec<(String, String)> {
+    let mut fence: Option<&str> = None;
+    body.lines()
+        .filter_map(|line| {
+            if let Some(marker) = fence {
+                if line.trim_start().starts_with(marker) {
+                    fence = None;
+                }
+                return None;
+            }
+            fence = fence_marker(line);
+            if fence.is_some() {
+                return None;
+            }
+            link_definition(line)
+        })
+        .collect()
+}
+
+/// Drop `<details>` tags and unwrap `<summary>` text
+fn strip_details_tags(line: &str) -> String {
+    let mut text = line.to_string();
+    for tag in ["</details>", "</summary>", "<summary>"] {
+        text = text.replace(tag, "");
+    }
+    while let Some(start) = text.find("<details") {
+        match text[start..].find('>') {
+            Some(end) => text.replace_range(start..start + end + 1, ""),
+            None => break,
+        }
+    }
+    text
+}
+
+/// Heading text without the leading `#`s, if `line` is an ATX heading
+fn flatten_heading(line: &str) -> Option<String> {
+    let trimmed = line.trim_start();
+    let hashes = trimmed.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&hashes) {
+        return None;
+    }
+    let title = trimmed[hashes..].strip_prefix(' ')?;
+    Some(title.trim().trim_end_matches('#').trim_end().to_string())
+}
+
+/// `- [ ] item` / `* [X] item` as `[ ] item` / `[x] item`, keeping indentation
+fn task_list_item(line: &str) -> Option<String> {
+    let indent = &line[..line.len() - line.trim_start().len()];
+    let rest = line.trim_start();
+    let rest = rest
+        .strip_prefix(['-', '*', '+'])
+        .or_else(|| {
+            let digits = rest.chars().take_while(char::is_ascii_digit).count();
+            (digits > 0).then(|| rest[digits..].strip_prefix(['.', ')'])).flatten()
+        })?
+        .strip_prefix(' ')?;
+    let (checked, item) = if let Some(item) = rest.strip_prefix("[ ]") {
+        (false, item)
+    } else if let Some(item) = rest.strip_prefix("[x]").or_else(|| rest.strip_prefix("[X]")) {
+        (true, item)
+    } else {
+        return None;
+    };
+    let mark = if checked { "[x]" } else { "[ ]" };
+    Some(format!("{indent}{mark}{item}"))
+}
+
+/// Drop images and render links as `text (url)`, leaving inline code untouched
+fn render_inline(line: &str, references: &[(String, String)]) -> String {
+    line.split('`')
+        .enumerate()
+        .map(|(i, part)| {
+            if i % 2 == 1 {
+                part.to_string()
+            } else {
+                render_links(part, references)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("`")
+}
+
+/// Render the images and links of a code-free span of text
+fn render_links(text: &str, references: &[(String, String)]) -> String {
+    let lookup = |label: &str| {
+        let label = label.to_lowercase();
+        references
+            .iter()
+            .find(|(name, _)| *name == label)
+            .map(|(_, url)| url.clone())
+    };
+
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(open) = rest.find('[') {
+        let image = rest[..open].ends_with('!');
+        let Some(close) = rest[open..].find(']').map(|i| open + i) else {
+            break;
+        };
+        let label = &rest[open + 1..close];
+        let after = &rest[close + 1..];
+
+        // [text](url), [text][ref], [text][] or a defined [ref]
+        let (url, consumed) = if let Some(inner) = after.strip_prefix('(')
+            && let Some(end) = inner.find(')')
+        {
+            let url = inner[..end].split_whitespace().next().unwrap_or("");
+            (Some(url.to_string()), end + 2)
+        } else if let Some(inner) = after.strip_prefix('[')
+            && let Some(end) = inner.find(']')
+        {
+            let name = if end == 0 { label } else { &inner[..end] };
+            (lookup(name), end + 2)
+        } else {
+            (lookup(label), 0)
+        };
+
+        let Some(url) = url else {
+            out.push_str(&rest[..=close]);
+            rest = after;
+            continue;
+        };
+
+        if image {
+            out.push_str(&rest[..open - 1]);
+        } else {
+            out.push_str(&rest[..open]);
+            if label == url || label.is_empty() {
+                out.push_str(&url);
+            } else {
+                out.push_str(&format!("{label} ({url})"));
+            }
+        }
+        rest = &after[consumed..];
+        if image && (out.is_empty() || out.ends_with(' ')) {
+            rest = rest.trim_start_matches(' ');
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Maximum length of a suggested commit subject line
+pub const COMMIT_SUBJECT_MAX: usize = 72;
+
+/// Wrap width for the suggested commit body
+const COMMIT_BODY_WIDTH: usize = 72;
+
 /// Template for commit message suggestion
+///
+/// Conventional-commit style: a `type: summary (#N)` subject of at most
+/// `COMMIT_SUBJECT_MAX` characters, then the first paragraph of the issue
+/// body (wrapped) and a `Closes #N` trailer.
 pub fn suggest_commit_message(issue: &Issue) -> String {
-    format!("Fix #{}: {}", issue.number, issue.title)
+    let mut message = suggest_commit_subject(issue);
+    message.push_str("\n\n");
+
+    if let Some(paragraph) = issue
+        .body
+        .as_deref()
+        .and_then(|body| body.split("\n\n").map(str::trim).find(|p| !p.is_empty()))
+    {
+        for line in wrap_words(paragraph, COMMIT_BODY_WIDTH) {
+            message.push_str(&line);
+            message.push('\n');
+        }
+        message.push('\n');
+    }
+
+    message.push_str(&format!("Closes #{}", issue.number));
+    message
 }
 
-/// Template for branch name suggestion
-pub fn suggest_branch_name(issue: &Issue, prefix: &str) -> String {
-    let sanitized_title = issue
+/// Subject line of the suggested commit message
+pub fn suggest_commit_subject(issue: &Issue) -> String {
+    let kind = commit_type(issue);
+    let suffix = format!(" (#{})", issue.number);
+
+    let title = strip_kind_word(issue.title.trim().trim_end_matches('.'), kind);
+    let mut chars = title.chars();
+    let summary = match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect::<String>(),
+        None => "update".to_string(),
+    };
+
+    let budget = COMMIT_SUBJECT_MAX
+        .saturating_sub(kind.len() + 2 + suffix.chars().count());
+    let summary = if summary.chars().count() > budget {
+        let cut: String = summary.chars().take(budget.saturating_sub(1)).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        summary
+    };
+
+    format!("{kind}: {summary}{suffix}")
+}
+
+/// Conventional-commit type, from labels first and then the title's leading word
+fn commit_type(issue: &Issue) -> &'static str {
+    for label in &issue.labels {
+        match label.name.to_lowercase().as_str() {
+            "bug" | "fix" | "regression" => return "fix",
+            "documentation" | "docs" => return "docs",
+            "refactor" | "refactoring" => return "refactor",
+            "test" | "tests" | "testing" => return "test",
+            "enhancement" | "feature" => return "feat",
+            _ => {}
+        }
+    }
+
+    let first_word = issue
         .title
-        .to_lowercase()
-        .chars()
-        .map(|c| {
-            if c.is_alphanumeric() || c == '-' || c == '_' {
-                c
-            } else if c.is_whitespace() {
-                '-'
-            } else {
-                '_'
-            }
+        .split_whitespace()
+        .next()
+        .unwrap_or("")
+        .trim_end_matches(':')
+        .to_lowercase();
+    match first_word.as_str() {
+        "fix" | "fixes" | "bug" => "fix",
+        "doc" | "docs" | "document" => "docs",
+        "refactor" | "cleanup" => "refactor",
+        "test" | "tests" => "test",
+        _ => "feat",
+    }
+}
+
+/// `title` without a leading word that just repeats `kind` ("Fix crash" under
+/// `fix` becomes "crash"), so subjects don't read "fix: fix crash"
+fn strip_kind_word<'a>(title: &'a str, kind: &str) -> &'a str {
+    let Some((first, rest)) = title.split_once(char::is_whitespace) else {
+        return title;
+    };
+    let word_kind = match first.trim_end_matches(':').to_lowercase().as_str() {
+        "fix" | "fixes" | "fixed" => "fix",
+        "doc" | "docs" | "document" => "docs",
+        "refactor" => "refactor",
+        "test" | "tests" => "test",
+        "feat" => "feat",
+        _ => return title,
+    };
+    let rest = rest.trim_start();
+    if word_kind == kind && !rest.is_empty() {
+        rest
+    } else {
+        title
+    }
+}
+
+/// Greedy word wrap (words longer than `width` get a line of their own)
+fn wrap_words(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// Maximum length of the title slug in suggested branch names
+const MAX_SLUG_LEN: usize = 40;
+
+/// Separators allowed between slug words
+pub const SLUG_SEPARATORS: &[&str] = &["-", "_", "."];
+
+/// How issue titles are turned into branch name slugs
+///
+/// The defaults give lowercase, dash-separated words with no token limit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SlugOptions {
+    /// Keep the title's letter case instead of lowercasing
+    #[serde(default)]
+    pub preserve_case: bool,
+
+    /// Keep at most this many words from the title
+    #[serde(default)]
+    pub max_tokens: Option<usize>,
+
+    /// Separator between words: "-", "_" or "."
+    #[serde(default = "default_slug_separator")]
+    pub separator: String,
+
+    /// Keep a ticket key such as `ABC-123` from the title verbatim, first
+    #[serde(default)]
+    pub keep_ticket_prefix: bool,
+}
+
+fn default_slug_separator() -> String {
+    "-".to_string()
+}
+
+impl Default for SlugOptions {
+    fn default() -> Self {
+        Self {
+            preserve_case: false,
+            max_tokens: None,
+            separator: default_slug_separator(),
+            keep_ticket_prefix: false,
+        }
+    }
+}
+
+impl SlugOptions {
+    /// Slug for a title: ASCII `[A-Za-z0-9_]` words joined by the separator,
+    /// capped at `MAX_SLUG_LEN` characters
+    pub fn slugify(&self, title: &str) -> String {
+        let ticket = if self.keep_ticket_prefix {
+            find_ticket_key(title)
+        } else {
+            None
+        };
+        let rest = match ticket {
+            Some(key) => title.replacen(key, " ", 1),
+            None => title.to_string(),
+        };
+
+        let words = rest
+            .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .filter(|w| !w.is_empty())
+            .map(|w| {
+                if self.preserve_case {
+                    w.to_string()
+                } else {
+                    w.to_ascii_lowercase()
+                }
+            })
+            .take(self.max_tokens.unwrap_or(usize::MAX));
+
+        let mut slug = ticket
+            .map(str::to_string)
+            .into_iter()
+            .chain(words)
+            .collect::<Vec<_>>()
+            .join(&self.separator);
+
+        // Everything is ASCII here, so byte and char lengths agree
+        if slug.len() > MAX_SLUG_LEN {
+            slug.truncate(MAX_SLUG_LEN);
+        }
+        let trim: Vec<char> = self.separator.chars().chain(['-', '_']).collect();
+        slug.trim_end_matches(trim.as_slice()).to_string()
+    }
+}
+
+/// First ticket key (letters, a dash, digits; e.g. `ABC-123`) in a title
+fn find_ticket_key(title: &str) -> Option<&str> {
+    title
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .find(|word| {
+            let Some((key, number)) = word.split_once('-') else {
+                return false;
+            };
+            key.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+                && key.chars().all(|c| c.is_ascii_alphanumeric())
+                && !number.is_empty()
+                && number.chars().all(|c| c.is_ascii_digit())
         })
-        .collect::<String>();
+}
+
+/// Template for branch name suggestion
+///
+/// Produces `prefix/issue-N-slug` with the default `SlugOptions`. Non-ASCII
+/// characters are dropped; if nothing is left the name is `prefix/issue-N`.
+pub fn suggest_branch_name(issue: &Issue, prefix: &str) -> String {
+    suggest_branch_name_with(issue, prefix, &SlugOptions::default())
+}
+
+/// Branch prefix for `issue`: the one `label_prefixes` maps its first
+/// mapped label to, else `default`
+///
+/// Labels are tried in the order the issue lists them, so the result
+/// doesn't depend on the map's iteration order.
+pub fn branch_prefix_for<'a>(
+    issue: &Issue,
+    default: &'a str,
+    label_prefixes: &'a HashMap<String, String>,
+) -> &'a str {
+    issue
+        .labels
+        .iter()
+        .find_map(|label| label_prefixes.get(&label.name))
+        .map_or(default, String::as_str)
+}
 
-    // Limit title length in branch name
-    let max_title_len = 40;
-    let truncated_title = if sanitized_title.len() > max_title_len {
-        &sanitized_title[..max_title_len]
+/// Branch name suggestion with custom slug options
+pub fn suggest_branch_name_with(issue: &Issue, prefix: &str, options: &SlugOptions) -> String {
+    let slug = options.slugify(&issue.title);
+    if slug.is_empty() {
+        format!("{}/issue-{}", prefix, issue.number)
     } else {
-        &sanitized_title
+        format!("{}/issue-{}-{}", prefix, issue.number, slug)
+    }
+}
+
+/// `base`, or the first of `base-2`, `base-3`, ... not in `existing_branches`
+///
+/// `WorktreeManager` names new branches the same way, so a name picked here
+/// is the one `create` ends up using.
+pub fn with_unique_suffix(base: &str, existing_branches: &[String]) -> String {
+    let taken = |name: &str| existing_branches.iter().any(|b| b == name);
+    if !taken(base) {
+        return base.to_string();
+    }
+    (2..)
+        .map(|n| format!("{base}-{n}"))
+        .find(|candidate| !taken(candidate))
+        .expect("unbounded suffix search")
+}
+
+/// Like `suggest_branch_name_with`, but appends `-2`, `-3`, ... if the name
+/// is already in `existing_branches`
+pub fn suggest_branch_name_unique(
+    issue: &Issue,
+    prefix: &str,
+    options: &SlugOptions,
+    existing_branches: &[String],
+) -> String {
+    with_unique_suffix(&suggest_branch_name_with(issue, prefix, options), existing_branches)
+}
+
+/// Widest `number_slug` agent name (in terminal cells) so tabs stay readable
+pub const AGENT_NAME_MAX_WIDTH: usize = 32;
+
+/// How agents are named in tabs and notifications
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentNameStyle {
+    /// Issue number, e.g. `#42`
+    #[default]
+    Number,
+    /// Issue number and a short slug of its title, e.g. `#42 add-auth`
+    NumberSlug,
+    /// Branch name
+    Branch,
+}
+
+/// Name for an agent working on `branch`, for `issue` if it came from one
+///
+/// Workers without an issue (plan workers) are named after their branch in
+/// every style. Branch names are kept whole; a title slug is cut to fit
+/// [`AGENT_NAME_MAX_WIDTH`], dropping whole words first.
+pub fn agent_name(style: AgentNameStyle, issue: Option<&Issue>, branch: &str, options: &SlugOptions) -> String {
+    let Some(issue) = issue.filter(|_| style != AgentNameStyle::Branch) else {
+        return branch.to_string();
     };
+    let number = format!("#{}", issue.number);
+    if style == AgentNameStyle::Number {
+        return number;
+    }
 
-    format!("{}/issue-{}-{}", prefix, issue.number, truncated_title)
+    let budget = AGENT_NAME_MAX_WIDTH.saturating_sub(display_width(&number) + 1);
+    let slug = options.slugify(&issue.title);
+    let mut short = String::new();
+    for word in slug.split(options.separator.as_str()) {
+        let candidate = if short.is_empty() {
+            word.to_string()
+        } else {
+            format!("{short}{}{word}", options.separator)
+        };
+        if display_width(&candidate) > budget {
+            break;
+        }
+        short = candidate;
+    }
+    if short.is_empty() {
+        short = truncate_to_width(&slug, budget);
+    }
+    if short.is_empty() {
+        number
+    } else {
+        format!("{number} {short}")
+    }
 }
 
 #[cfg(test)]
@@ -137,6 +961,9 @@ mod tests {
             ],
             state: "open".to_string(),
             html_url: "https://github.com/test/repo/issues/42".to_string(),
+            comments: 0,
+            created_at: None,
+            updated_at: None,
         }
     }
 
@@ -149,7 +976,8 @@ mod tests {
 
         assert!(result.contains("Issue #42: Add user authentication"));
         assert!(result.contains("JWT-based authentication"));
-        assert!(result.contains("Fix #42"));
+        assert!(result.contains("feat: add user authentication (#42)"));
+        assert!(result.contains("Closes #42"));
     }
 
     #[test]
@@ -165,6 +993,240 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_normalize_issue_body_task_lists_and_headings() {
+        let body = "## Summary ##\n\nDo the thing.\n\n### Tasks\n- [ ] first\n  * [X] nested done\n1. [x] numbered\n- plain item";
+        assert_eq!(
+            normalize_issue_body(body),
+            "Summary\n\nDo the thing.\n\nTasks\n[ ] first\n  [x] nested done\n[x] numbered\n- plain item"
+        );
+    }
+
+    #[test]
+    fn test_normalize_issue_body_strips_comments_images_and_details() {
+        let body = "<!-- Please describe the bug -->\nCrash on start.\n\n\
+                    <!--\nmulti-line\ntemplate hint\n-->\n\
+                    ![screenshot](https://example.com/a.png) See above.\n\n\
+                    <details>\n<summary>Stack trace</summary>\n\npanic at main.rs:3\n</details>";
+        assert_eq!(
+            normalize_issue_body(body),
+            "Crash on start.\n\nSee above.\n\nStack trace\n\npanic at main.rs:3"
+        );
+    }
+
+    #[test]
+    fn test_normalize_issue_body_links() {
+        let body = "See [the docs][docs], [RFC][] and [spec](https://example.com/spec \"Spec\").\n\
+                    Raw [brackets] stay.\n\n\
+                    [docs]: https://example.com/docs\n[rfc]: https://example.com/rfc";
+        assert_eq!(
+            normalize_issue_body(body),
+            "See the docs (https://example.com/docs), RFC (https://example.com/rfc) and spec (https://example.com/spec).\nRaw [brackets] stay."
+        );
+    }
+
+    #[test]
+    fn test_normalize_issue_body_keeps_code_verbatim() {
+        let body = "Run `[x](y)` first.\n\n```md\n## not a heading\n- [ ] not a task\n<!-- kept -->\n\n\n[ref]: kept\n```\n\n~~~\n![img](a.png)\n~~~";
+        assert_eq!(
+            normalize_issue_body(body),
+            "Run `[x](y)` first.\n\n```md\n## not a heading\n- [ ] not a task\n<!-- kept -->\n\n\n[ref]: kept\n```\n\n~~~\n![img](a.png)\n~~~"
+        );
+    }
+
+    #[test]
+    fn test_issue_subtask_items_skips_checked_and_code() {
+        let body = "Intro\n\n- [ ] Add the parser\n- [x] Write the RFC\n  - [ ] **Nested** item\n\n```\n- [ ] not a task\n```";
+        assert_eq!(issue_subtask_items(body), vec!["Add the parser", "**Nested** item"]);
+        assert!(issue_subtask_items("No checklist here").is_empty());
+    }
+
+    #[test]
+    fn test_issue_subtasks_builds_worker_tasks() {
+        let issue = Issue {
+            number: 9,
+            title: "Improve search".to_string(),
+            body: Some("- [ ] Add an index\n- [ ] Rank results".to_string()),
+            labels: vec![],
+            state: "open".to_string(),
+            html_url: "https://github.com/test/repo/issues/9".to_string(),
+            comments: 0,
+            created_at: None,
+            updated_at: None,
+        };
+
+        let tasks = issue_subtasks(&issue, "feat", &SlugOptions::default());
+
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[1].id, "issue-9-2");
+        match &tasks[1].action {
+            crate::plan::TaskAction::CreateWorker { branch, task_description, .. } => {
+                assert_eq!(branch, "feat/issue-9-2-rank-results");
+                assert!(task_description.contains("sub-task 2 of 2 from GitHub issue #9"));
+                assert!(task_description.contains("Sub-task: Rank results"));
+                assert!(task_description.contains("[ ] Add an index"));
+            }
+            other => panic!("unexpected action: {other:?}"),
+        }
+
+        let issue = Issue { body: None, ..issue };
+        assert!(issue_subtasks(&issue, "feat", &SlugOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_issue_plan_chains_each_issue() {
+        let second = Issue {
+            number: 7,
+            title: "Fix login".to_string(),
+            ..create_test_issue()
+        };
+        let plan = issue_plan(
+            &[create_test_issue(), second],
+            "feat",
+            &HashMap::new(),
+            &SlugOptions::default(),
+            WorktreeNaming::Flat,
+        );
+
+        assert_eq!(plan.description.as_deref(), Some("Issues #42, #7"));
+        let ids: Vec<&str> = plan.tasks.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "issue-42",
+                "issue-7",
+                "issue-42-review",
+                "issue-42-merge",
+                "issue-42-cleanup",
+                "issue-7-review",
+                "issue-7-merge",
+                "issue-7-cleanup",
+            ]
+        );
+        match &plan.tasks[5].action {
+            TaskAction::RequestReview { branch, after_task } => {
+                assert_eq!(branch, "feat/issue-7-fix-login");
+                assert_eq!(after_task.as_deref(), Some("issue-7"));
+            }
+            other => panic!("unexpected action: {other:?}"),
+        }
+        match &plan.tasks[7].action {
+            TaskAction::CleanupWorktree { worktree } => assert_eq!(worktree, "feat-issue-7-fix-login"),
+            other => panic!("unexpected action: {other:?}"),
+        }
+    }
+
+    fn labeled_issue(labels: &[&str]) -> Issue {
+        Issue {
+            labels: labels
+                .iter()
+                .map(|name| Label {
+                    name: name.to_string(),
+                    color: String::new(),
+                })
+                .collect(),
+            ..create_test_issue()
+        }
+    }
+
+    #[test]
+    fn test_branch_prefix_for_no_match() {
+        let prefixes = HashMap::from([("bug".to_string(), "fix".to_string())]);
+        assert_eq!(branch_prefix_for(&labeled_issue(&[]), "cctakt", &prefixes), "cctakt");
+        assert_eq!(branch_prefix_for(&labeled_issue(&["question"]), "cctakt", &prefixes), "cctakt");
+        assert_eq!(branch_prefix_for(&labeled_issue(&["bug"]), "cctakt", &HashMap::new()), "cctakt");
+    }
+
+    #[test]
+    fn test_branch_prefix_for_single_match() {
+        let prefixes = HashMap::from([
+            ("bug".to_string(), "fix".to_string()),
+            ("enhancement".to_string(), "feat".to_string()),
+        ]);
+        let issue = labeled_issue(&["question", "bug"]);
+        assert_eq!(branch_prefix_for(&issue, "cctakt", &prefixes), "fix");
+        assert_eq!(
+            suggest_branch_name(&issue, branch_prefix_for(&issue, "cctakt", &prefixes)),
+            "fix/issue-42-add-user-authentication"
+        );
+    }
+
+    #[test]
+    fn test_branch_prefix_for_first_label_wins() {
+        let prefixes = HashMap::from([
+            ("bug".to_string(), "fix".to_string()),
+            ("enhancement".to_string(), "feat".to_string()),
+            ("chore".to_string(), "chore".to_string()),
+        ]);
+        assert_eq!(branch_prefix_for(&labeled_issue(&["enhancement", "bug"]), "cctakt", &prefixes), "feat");
+        assert_eq!(branch_prefix_for(&labeled_issue(&["bug", "enhancement"]), "cctakt", &prefixes), "fix");
+        assert_eq!(branch_prefix_for(&labeled_issue(&["chore", "bug"]), "cctakt", &prefixes), "chore");
+    }
+
+    #[test]
+    fn test_issue_plan_uses_label_prefixes() {
+        let prefixes = HashMap::from([("bug".to_string(), "fix".to_string())]);
+        let plan = issue_plan(
+            &[labeled_issue(&["bug"])],
+            "feat",
+            &prefixes,
+            &SlugOptions::default(),
+            WorktreeNaming::Flat,
+        );
+        match &plan.tasks[0].action {
+            TaskAction::CreateWorker { branch, .. } => {
+                assert_eq!(branch, "fix/issue-42-add-user-authentication")
+            }
+            other => panic!("unexpected action: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_checklist_plan() {
+        let issue = Issue {
+            body: Some("- [ ] Add an index\n- [x] Done already".to_string()),
+            ..create_test_issue()
+        };
+        let plan = checklist_plan(&issue, "feat", &SlugOptions::default(), WorktreeNaming::Nested);
+        let ids: Vec<&str> = plan.tasks.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, ["issue-42-1", "issue-42-1-review", "issue-42-1-merge", "issue-42-1-cleanup"]);
+        match &plan.tasks[3].action {
+            TaskAction::CleanupWorktree { worktree } => {
+                assert_eq!(worktree, "feat/issue-42-1-add-an-index");
+            }
+            other => panic!("unexpected action: {other:?}"),
+        }
+
+        let issue = Issue { body: None, ..issue };
+        assert!(checklist_plan(&issue, "feat", &SlugOptions::default(), WorktreeNaming::Flat).tasks.is_empty());
+    }
+
+    #[test]
+    fn test_render_normalizes_body() {
+        let issue = Issue {
+            number: 1,
+            title: "Test".to_string(),
+            body: Some("<!-- template -->\n# Goal\n- [ ] step".to_string()),
+            labels: vec![],
+            state: "open".to_string(),
+            html_url: "https://github.com/test/repo/issues/1".to_string(),
+            comments: 0,
+            created_at: None,
+            updated_at: None,
+        };
+
+        let result = TaskTemplate::default().render(&issue);
+
+        assert!(result.contains("Goal\n[ ] step"));
+        assert!(!result.contains("<!--"));
+
+        let issue = Issue {
+            body: Some("<!-- only a template -->".to_string()),
+            ..issue
+        };
+        assert!(TaskTemplate::default().render(&issue).contains("(No description provided)"));
+    }
+
     #[test]
     fn test_render_with_no_body() {
         let template = TaskTemplate::default();
@@ -175,6 +1237,9 @@ mod tests {
             labels: vec![],
             state: "open".to_string(),
             html_url: "https://github.com/test/repo/issues/1".to_string(),
+            comments: 0,
+            created_at: None,
+            updated_at: None,
         };
 
         let result = template.render(&issue);
@@ -216,7 +1281,86 @@ mod tests {
         let issue = create_test_issue();
         let message = suggest_commit_message(&issue);
 
-        assert_eq!(message, "Fix #42: Add user authentication");
+        assert_eq!(
+            message,
+            "feat: add user authentication (#42)\n\nWe need to add JWT-based authentication.\n\nCloses #42"
+        );
+    }
+
+    #[test]
+    fn test_suggest_commit_message_without_body() {
+        let issue = Issue {
+            number: 7,
+            title: "Crash on empty plan".to_string(),
+            body: None,
+            labels: vec![Label {
+                name: "bug".to_string(),
+                color: "d73a4a".to_string(),
+            }],
+            state: "open".to_string(),
+            html_url: "https://github.com/test/repo/issues/7".to_string(),
+            comments: 0,
+            created_at: None,
+            updated_at: None,
+        };
+
+        assert_eq!(
+            suggest_commit_message(&issue),
+            "fix: crash on empty plan (#7)\n\nCloses #7"
+        );
+    }
+
+    #[test]
+    fn test_suggest_commit_subject_drops_repeated_kind() {
+        let subject = |title: &str, label: Option<&str>| {
+            suggest_commit_subject(&Issue {
+                number: 7,
+                title: title.to_string(),
+                labels: label
+                    .map(|name| vec![Label { name: name.to_string(), color: String::new() }])
+                    .unwrap_or_default(),
+                ..create_test_issue()
+            })
+        };
+
+        assert_eq!(subject("Fix crash on empty plan", None), "fix: crash on empty plan (#7)");
+        assert_eq!(subject("fix: crash on empty plan", None), "fix: crash on empty plan (#7)");
+        assert_eq!(subject("Docs: explain the merge queue", None), "docs: explain the merge queue (#7)");
+        // Only a word matching the chosen type is dropped
+        assert_eq!(subject("Docs are wrong", Some("bug")), "fix: docs are wrong (#7)");
+        assert_eq!(subject("Fix", None), "fix: fix (#7)");
+    }
+
+    #[test]
+    fn test_suggest_commit_subject_truncates_long_titles() {
+        let issue = Issue {
+            number: 12345,
+            title: "Fix ".to_string() + &"very long words ".repeat(10),
+            body: None,
+            labels: vec![],
+            state: "open".to_string(),
+            html_url: String::new(),
+            comments: 0,
+            created_at: None,
+            updated_at: None,
+        };
+
+        let subject = suggest_commit_subject(&issue);
+        assert!(subject.chars().count() <= COMMIT_SUBJECT_MAX, "{subject}");
+        assert!(subject.starts_with("fix: very long"), "{subject}");
+        assert!(subject.ends_with("… (#12345)"));
+    }
+
+    #[test]
+    fn test_suggest_commit_message_wraps_body() {
+        let issue = Issue {
+            body: Some("word ".repeat(40)),
+            ..create_test_issue()
+        };
+
+        let message = suggest_commit_message(&issue);
+        assert!(message.lines().all(|l| l.chars().count() <= 72));
+        assert!(message.lines().count() > 4);
     }
 
     #[test]
@@ -236,6 +1380,9 @@ mod tests {
             labels: vec![],
             state: "open".to_string(),
             html_url: "https://github.com/test/repo/issues/123".to_string(),
+            comments: 0,
+            created_at: None,
+            updated_at: None,
         };
 
         let branch = suggest_branch_name(&issue, "feature");
@@ -256,6 +1403,9 @@ mod tests {
             labels: vec![],
             state: "open".to_string(),
             html_url: "https://github.com/test/repo/issues/1".to_string(),
+            comments: 0,
+            created_at: None,
+            updated_at: None,
         };
 
         let branch = suggest_branch_name(&issue, "fix");
@@ -264,6 +1414,231 @@ mod tests {
         assert!(branch.len() < 80);
     }
 
+    #[test]
+    fn test_branch_name_slug_length_is_capped() {
+        let issue = Issue {
+            title: "Refactor ".repeat(20),
+            ..create_test_issue()
+        };
+
+        let branch = suggest_branch_name(&issue, "cctakt");
+        let slug = branch.strip_prefix("cctakt/issue-42-").unwrap();
+        assert!(slug.len() <= MAX_SLUG_LEN);
+        assert!(!slug.ends_with('-'));
+    }
+
+    #[test]
+    fn test_branch_name_unicode_title() {
+        let issue = Issue {
+            title: "ログイン画面の修正 – fix login 🚀".to_string(),
+            ..create_test_issue()
+        };
+        assert_eq!(suggest_branch_name(&issue, "cctakt"), "cctakt/issue-42-fix-login");
+
+        let issue = Issue {
+            title: "日本語のみ".to_string(),
+            ..create_test_issue()
+        };
+        assert_eq!(suggest_branch_name(&issue, "cctakt"), "cctakt/issue-42");
+    }
+
+    #[test]
+    fn test_branch_name_unique_appends_suffix() {
+        let issue = create_test_issue();
+        let base = "cctakt/issue-42-add-user-authentication".to_string();
+
+        let options = SlugOptions::default();
+        assert_eq!(suggest_branch_name_unique(&issue, "cctakt", &options, &[]), base);
+
+        let existing = vec![base.clone(), format!("{base}-2"), "main".to_string()];
+        assert_eq!(
+            suggest_branch_name_unique(&issue, "cctakt", &options, &existing),
+            format!("{base}-3")
+        );
+    }
+
+    #[test]
+    fn test_unique_suffix_fills_the_first_gap() {
+        let existing = vec!["feat".to_string(), "feat-3".to_string(), "feat-2-x".to_string()];
+        assert_eq!(with_unique_suffix("feat", &existing), "feat-2");
+        assert_eq!(with_unique_suffix("other", &existing), "other");
+    }
+
+    #[test]
+    fn test_agent_name_styles() {
+        let issue = create_test_issue();
+        let branch = "cctakt/issue-42-add-user-authentication";
+        let options = SlugOptions::default();
+        let name = |style| agent_name(style, Some(&issue), branch, &options);
+
+        assert_eq!(name(AgentNameStyle::Number), "#42");
+        assert_eq!(name(AgentNameStyle::NumberSlug), "#42 add-user-authentication");
+        assert_eq!(name(AgentNameStyle::Branch), "cctakt/issue-42-add-user-authentication");
+    }
+
+    #[test]
+    fn test_agent_name_slug_drops_whole_words() {
+        let issue = Issue {
+            title: "Refactor the authentication middleware for multiple providers".to_string(),
+            ..create_test_issue()
+        };
+        let name = agent_name(AgentNameStyle::NumberSlug, Some(&issue), "b", &SlugOptions::default());
+        assert_eq!(name, "#42 refactor-the-authentication");
+        assert!(display_width(&name) <= AGENT_NAME_MAX_WIDTH);
+
+        // No ASCII words to slug: just the number
+        let issue = Issue {
+            title: "日本語のみ".to_string(),
+            ..create_test_issue()
+        };
+        assert_eq!(agent_name(AgentNameStyle::NumberSlug, Some(&issue), "b", &SlugOptions::default()), "#42");
+    }
+
+    #[test]
+    fn test_agent_name_without_issue_uses_branch() {
+        let options = SlugOptions::default();
+        for style in [AgentNameStyle::Number, AgentNameStyle::NumberSlug, AgentNameStyle::Branch] {
+            assert_eq!(agent_name(style, None, "feat/login", &options), "feat/login");
+        }
+        let long = "cctakt/issue-7-1-write-the-migration-guide-for-v2";
+        assert_eq!(agent_name(AgentNameStyle::Branch, None, long, &options), long);
+    }
+
+    #[test]
+    fn test_slug_options_default_lowercases() {
+        let options = SlugOptions::default();
+        assert_eq!(options.slugify("Fix ABC-123 Login Bug"), "fix-abc-123-login-bug");
+    }
+
+    #[test]
+    fn test_slug_options_preserve_case_and_ticket() {
+        let options = SlugOptions {
+            preserve_case: true,
+            keep_ticket_prefix: true,
+            ..SlugOptions::default()
+        };
+        assert_eq!(options.slugify("Fix login bug (PROJ-42)"), "PROJ-42-Fix-login-bug");
+
+        // Without a ticket key nothing is moved
+        assert_eq!(options.slugify("Fix Login"), "Fix-Login");
+    }
+
+    #[test]
+    fn test_slug_options_separator_and_max_tokens() {
+        let options = SlugOptions {
+            separator: "_".to_string(),
+            max_tokens: Some(2),
+            keep_ticket_prefix: true,
+            ..SlugOptions::default()
+        };
+        assert_eq!(
+            options.slugify("ABC-7 add very long feature name"),
+            "ABC-7_add_very"
+        );
+    }
+
+    #[test]
+    fn test_slug_options_emoji_and_cjk() {
+        let options = SlugOptions {
+            preserve_case: true,
+            keep_ticket_prefix: true,
+            ..SlugOptions::default()
+        };
+        assert_eq!(options.slugify("🚀 新機能 JIRA-9 Dark Mode ✨"), "JIRA-9-Dark-Mode");
+        assert_eq!(options.slugify("絵文字だけ 🎉🎉"), "");
+
+        // Truncation never splits a multi-byte character
+        let long = "é".repeat(100) + &"Word ".repeat(20);
+        assert!(options.slugify(&long).len() <= MAX_SLUG_LEN);
+    }
+
+    #[test]
+    fn test_branch_name_with_options() {
+        let issue = Issue {
+            title: "Support SSO for ACME-12".to_string(),
+            ..create_test_issue()
+        };
+        let options = SlugOptions {
+            keep_ticket_prefix: true,
+            ..SlugOptions::default()
+        };
+        assert_eq!(
+            suggest_branch_name_with(&issue, "team", &options),
+            "team/issue-42-ACME-12-support-sso-for"
+        );
+    }
+
+    #[test]
+    fn test_fill_placeholders_keeps_unknown() {
+        let filled = fill_placeholders(
+            "Merge {{branch}} into {{main_branch}}, then {{unknown}}",
+            &[("branch", "feat/x"), ("main_branch", "main")],
+        );
+        assert_eq!(filled, "Merge feat/x into main, then {{unknown}}");
+    }
+
+    #[test]
+    fn test_load_pull_request_template() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert_eq!(load_pull_request_template(dir.path()), None);
+
+        std::fs::write(dir.path().join("PULL_REQUEST_TEMPLATE.md"), "root").unwrap();
+        assert_eq!(load_pull_request_template(dir.path()).as_deref(), Some("root"));
+
+        // .github/ wins, but an empty template is skipped
+        std::fs::create_dir(dir.path().join(".github")).unwrap();
+        std::fs::write(dir.path().join(".github/pull_request_template.md"), " \n").unwrap();
+        assert_eq!(load_pull_request_template(dir.path()).as_deref(), Some("root"));
+        std::fs::write(dir.path().join(".github/pull_request_template.md"), "## Summary\n").unwrap();
+        assert_eq!(load_pull_request_template(dir.path()).as_deref(), Some("## Summary\n"));
+    }
+
+    #[test]
+    fn test_pull_request_body() {
+        let template = "## Summary\n\n## Testing\n";
+        assert_eq!(pull_request_body(None, None, None), None);
+        assert_eq!(
+            pull_request_body(None, Some(template), None).as_deref(),
+            Some("## Summary\n\n## Testing")
+        );
+        assert_eq!(
+            pull_request_body(Some("Explicit"), Some(template), Some(42)).as_deref(),
+            Some("Explicit\n\nCloses #42")
+        );
+        assert_eq!(pull_request_body(None, None, Some(42)).as_deref(), Some("Closes #42"));
+        // Already linked (but #420 is another issue)
+        assert_eq!(
+            pull_request_body(Some("Fixes #42"), None, Some(42)).as_deref(),
+            Some("Fixes #42")
+        );
+        assert_eq!(
+            pull_request_body(Some("See #420"), None, Some(42)).as_deref(),
+            Some("See #420\n\nCloses #42")
+        );
+    }
+
+    #[test]
+    fn test_worker_prompt_load_precedence() {
+        let dir = tempfile::TempDir::new().unwrap();
+
+        // Nothing configured: no custom prompt, and no error
+        assert_eq!(WorkerPrompt::Merge.load(None, dir.path()), None);
+
+        // Config value is used when there's no file
+        assert_eq!(
+            WorkerPrompt::Merge.load(Some("from config"), dir.path()),
+            Some("from config".to_string())
+        );
+
+        // The file wins over config
+        std::fs::write(dir.path().join("merge_worker_prompt.md"), "from file").unwrap();
+        assert_eq!(
+            WorkerPrompt::Merge.load(Some("from config"), dir.path()),
+            Some("from file".to_string())
+        );
+        assert_eq!(WorkerPrompt::Build.load(None, dir.path()), None);
+    }
+
     #[test]
     fn test_template_string() {
         let template = TaskTemplate::new("Hello {{title}}");
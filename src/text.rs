@@ -0,0 +1,175 @@
+//! Display-width aware text helpers
+//!
+//! Terminal layout is measured in cells, not bytes or chars: CJK and most
+//! emoji take two cells and combining marks take none. Anything that pads,
+//! aligns or truncates text for the screen should go through these.
+
+use ratatui::text::{Line, Span};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Marker appended to truncated text
+pub const ELLIPSIS: &str = "…";
+
+/// Number of terminal cells `text` takes up
+pub fn display_width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+/// Shorten `text` to at most `max_width` cells, ending in [`ELLIPSIS`] if cut
+///
+/// Never splits a character or drops its combining marks, and backs off to
+/// the last space when that keeps at least half the width, so words aren't
+/// cut in the middle. Text without spaces (e.g. Japanese) is cut anywhere.
+pub fn truncate_to_width(text: &str, max_width: usize) -> String {
+    if display_width(text) <= max_width {
+        return text.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+    let budget = max_width.saturating_sub(display_width(ELLIPSIS));
+    let mut end = 0;
+    let mut used = 0;
+    for (index, c) in text.char_indices() {
+        let width = c.width().unwrap_or(0);
+        if used + width > budget {
+            break;
+        }
+        used += width;
+        end = index + c.len_utf8();
+    }
+
+    let mut kept = &text[..end];
+    let mid_word = text[end..].starts_with(|c: char| !c.is_whitespace());
+    if mid_word
+        && let Some(space) = kept.rfind(char::is_whitespace)
+        && display_width(&kept[..space]) * 2 >= budget
+    {
+        kept = &kept[..space];
+    }
+    format!("{}{ELLIPSIS}", kept.trim_end())
+}
+
+/// `text` followed by spaces up to `width` cells (unchanged if already wider)
+pub fn pad_to_width(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(text));
+    format!("{text}{}", " ".repeat(padding))
+}
+
+/// Clip `spans` to at most `max_width` cells, truncating the span that crosses it
+pub fn truncate_spans(spans: Vec<Span<'_>>, max_width: usize) -> Vec<Span<'_>> {
+    let mut remaining = max_width;
+    let mut clipped = Vec::with_capacity(spans.len());
+    for span in spans {
+        let width = display_width(&span.content);
+        if width <= remaining {
+            remaining -= width;
+            clipped.push(span);
+            continue;
+        }
+        // A lone ellipsis says nothing, leave the rest out instead
+        let truncated = truncate_to_width(&span.content, remaining);
+        if !truncated.is_empty() && truncated != ELLIPSIS {
+            clipped.push(Span::styled(truncated, span.style));
+        }
+        break;
+    }
+    clipped
+}
+
+/// One line of exactly `width` cells: `left` at the start, `right` at the end
+///
+/// The gap is filled with spaces. When both don't fit, `left` wins and
+/// `right` is truncated (or dropped).
+pub fn justify_line<'a>(left: Vec<Span<'a>>, right: Vec<Span<'a>>, width: usize) -> Line<'a> {
+    let spans_width = |spans: &[Span]| spans.iter().map(|span| display_width(&span.content)).sum::<usize>();
+    let mut spans = truncate_spans(left, width);
+    let left_width = spans_width(&spans);
+    let right = truncate_spans(right, width - left_width);
+    let padding = width - left_width - spans_width(&right);
+    if padding > 0 {
+        spans.push(Span::raw(" ".repeat(padding)));
+    }
+    spans.extend(right);
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_width() {
+        assert_eq!(display_width("abc"), 3);
+        assert_eq!(display_width("日本語"), 6);
+        // Combining acute accent takes no cell
+        assert_eq!(display_width("e\u{301}"), 1);
+        assert_eq!(display_width(""), 0);
+    }
+
+    #[test]
+    fn test_truncate_to_width_fits() {
+        assert_eq!(truncate_to_width("short", 80), "short");
+        assert_eq!(truncate_to_width("日本語", 6), "日本語");
+    }
+
+    #[test]
+    fn test_truncate_to_width_wide_chars() {
+        assert_eq!(truncate_to_width("日本語テキスト", 7), "日本語…");
+        // A double-width char that doesn't fit is left out entirely
+        assert_eq!(truncate_to_width("日本語テキスト", 6), "日本…");
+        assert!(display_width(&truncate_to_width("日本語テキスト", 6)) <= 6);
+    }
+
+    #[test]
+    fn test_truncate_to_width_word_boundary() {
+        assert_eq!(truncate_to_width("fix the parser bug", 12), "fix the…");
+        // A single long word is cut where it must be
+        assert_eq!(truncate_to_width("internationalization", 8), "interna…");
+    }
+
+    #[test]
+    fn test_truncate_to_width_keeps_combining_marks() {
+        assert_eq!(truncate_to_width("cafe\u{301} noir", 6), "cafe\u{301}…");
+    }
+
+    #[test]
+    fn test_truncate_to_width_tiny() {
+        assert_eq!(truncate_to_width("abc", 1), "…");
+        assert_eq!(truncate_to_width("abc", 0), "");
+    }
+
+    #[test]
+    fn test_justify_line_fills_width() {
+        let line = justify_line(vec![Span::raw("left")], vec![Span::raw("right")], 20);
+        assert_eq!(line.width(), 20);
+        assert_eq!(line.spans.last().unwrap().content, "right");
+    }
+
+    #[test]
+    fn test_justify_line_wide_chars() {
+        let left = vec![Span::raw(" エージェント: 3 "), Span::raw("🚀 Running"), Span::raw(" [←]")];
+        let right = vec![Span::raw("計画: 1/4 "), Span::raw("██░░ 50% ")];
+        for width in [80, 40, 30, 20, 7, 1, 0] {
+            let line = justify_line(left.clone(), right.clone(), width);
+            assert_eq!(line.width(), width, "width {width}");
+        }
+    }
+
+    #[test]
+    fn test_justify_line_left_wins() {
+        let line = justify_line(vec![Span::raw("status ")], vec![Span::raw("plan: 1/4")], 11);
+        let text: String = line.spans.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(text, "status pla…");
+        let line = justify_line(vec![Span::raw("status too long")], vec![Span::raw("plan")], 8);
+        let text: String = line.spans.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(text, "status… ");
+    }
+
+    #[test]
+    fn test_pad_to_width() {
+        assert_eq!(pad_to_width("ab", 4), "ab  ");
+        assert_eq!(pad_to_width("日本", 5), "日本 ");
+        assert_eq!(pad_to_width("toolong", 3), "toolong");
+    }
+}
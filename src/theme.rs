@@ -167,209 +167,209 @@ impl ThemeColors {
 
     /// Primary accent color
     pub fn neon_pink(&self) -> Color {
-        self.neon_pink
+        adapt_color(self.neon_pink)
     }
 
     /// Secondary accent color
     pub fn neon_cyan(&self) -> Color {
-        self.neon_cyan
+        adapt_color(self.neon_cyan)
     }
 
     /// Tertiary accent color
     pub fn neon_purple(&self) -> Color {
-        self.neon_purple
+        adapt_color(self.neon_purple)
     }
 
     /// Success/active color
     pub fn neon_green(&self) -> Color {
-        self.neon_green
+        adapt_color(self.neon_green)
     }
 
     /// Warning color
     pub fn neon_yellow(&self) -> Color {
-        self.neon_yellow
+        adapt_color(self.neon_yellow)
     }
 
     /// Highlight color
     pub fn neon_orange(&self) -> Color {
-        self.neon_orange
+        adapt_color(self.neon_orange)
     }
 
     /// Info color
     pub fn neon_blue(&self) -> Color {
-        self.neon_blue
+        adapt_color(self.neon_blue)
     }
 
     /// Main background color
     pub fn bg_dark(&self) -> Color {
-        self.bg_dark
+        adapt_color(self.bg_dark)
     }
 
     /// Panel background color
     pub fn bg_panel(&self) -> Color {
-        self.bg_panel
+        adapt_color(self.bg_panel)
     }
 
     /// Surface/elevated background color
     pub fn bg_surface(&self) -> Color {
-        self.bg_surface
+        adapt_color(self.bg_surface)
     }
 
     /// Highlight background color
     pub fn bg_highlight(&self) -> Color {
-        self.bg_highlight
+        adapt_color(self.bg_highlight)
     }
 
     /// Primary text color
     pub fn text_primary(&self) -> Color {
-        self.text_primary
+        adapt_color(self.text_primary)
     }
 
     /// Secondary text color
     pub fn text_secondary(&self) -> Color {
-        self.text_secondary
+        adapt_color(self.text_secondary)
     }
 
     /// Muted text color
     pub fn text_muted(&self) -> Color {
-        self.text_muted
+        adapt_color(self.text_muted)
     }
 
     /// Error color
     pub fn error(&self) -> Color {
-        self.error
+        adapt_color(self.error)
     }
 
     /// Status ended color
     pub fn status_ended(&self) -> Color {
-        self.status_ended
+        adapt_color(self.status_ended)
     }
 
     /// Secondary border color
     pub fn border_secondary(&self) -> Color {
-        self.border_secondary
+        adapt_color(self.border_secondary)
     }
 
     /// Addition background color
     pub fn diff_add_bg(&self) -> Color {
-        self.diff_add_bg
+        adapt_color(self.diff_add_bg)
     }
 
     /// Deletion background color
     pub fn diff_del_bg(&self) -> Color {
-        self.diff_del_bg
+        adapt_color(self.diff_del_bg)
     }
 
     // ==================== Computed Colors ====================
 
     /// Success color (defaults to neon_green)
     pub fn success(&self) -> Color {
-        self.neon_green
+        adapt_color(self.neon_green)
     }
 
     /// Warning color (defaults to neon_yellow, can be overridden)
     pub fn warning(&self) -> Color {
-        self.warning_override.unwrap_or(self.neon_yellow)
+        adapt_color(self.warning_override.unwrap_or(self.neon_yellow))
     }
 
     /// Info color (defaults to neon_cyan)
     pub fn info(&self) -> Color {
-        self.neon_cyan
+        adapt_color(self.neon_cyan)
     }
 
     /// Running status color
     pub fn status_running(&self) -> Color {
-        self.neon_green
+        adapt_color(self.neon_green)
     }
 
     /// Idle status color
     pub fn status_idle(&self) -> Color {
-        self.neon_yellow
+        adapt_color(self.neon_yellow)
     }
 
     /// Error status color
     pub fn status_error(&self) -> Color {
-        self.error
+        adapt_color(self.error)
     }
 
     /// Primary border color (defaults to neon_cyan, can be overridden)
     pub fn border_primary(&self) -> Color {
-        self.border_primary_override.unwrap_or(self.neon_cyan)
+        adapt_color(self.border_primary_override.unwrap_or(self.neon_cyan))
     }
 
     /// Active/focused border color
     pub fn border_active(&self) -> Color {
-        self.neon_pink
+        adapt_color(self.neon_pink)
     }
 
     /// Addition text color
     pub fn diff_addition(&self) -> Color {
-        self.neon_green
+        adapt_color(self.neon_green)
     }
 
     /// Deletion text color
     pub fn diff_deletion(&self) -> Color {
-        self.error
+        adapt_color(self.error)
     }
 
     /// Context line color
     pub fn diff_context(&self) -> Color {
-        self.text_primary
+        adapt_color(self.text_primary)
     }
 
     /// Hunk header color
     pub fn diff_hunk_header(&self) -> Color {
-        self.neon_cyan
+        adapt_color(self.neon_cyan)
     }
 
     /// File header color
     pub fn diff_file_header(&self) -> Color {
-        self.neon_yellow
+        adapt_color(self.neon_yellow)
     }
 
     /// Active tab background (defaults to neon_cyan, can be overridden)
     pub fn tab_active_bg(&self) -> Color {
-        self.tab_active_bg_override.unwrap_or(self.neon_cyan)
+        adapt_color(self.tab_active_bg_override.unwrap_or(self.neon_cyan))
     }
 
     /// Active tab foreground
     pub fn tab_active_fg(&self) -> Color {
-        Color::Rgb(0, 0, 0)
+        adapt_color(Color::Rgb(0, 0, 0))
     }
 
     /// Selected item background
     pub fn selected_bg(&self) -> Color {
-        self.bg_highlight
+        adapt_color(self.bg_highlight)
     }
 
     /// Cursor background
     pub fn cursor_bg(&self) -> Color {
-        self.neon_cyan
+        adapt_color(self.neon_cyan)
     }
 
     /// Cursor foreground
     pub fn cursor_fg(&self) -> Color {
-        Color::Rgb(0, 0, 0)
+        adapt_color(Color::Rgb(0, 0, 0))
     }
 
     /// Key binding color
     pub fn key_binding(&self) -> Color {
-        self.neon_cyan
+        adapt_color(self.neon_cyan)
     }
 
     /// Key description color
     pub fn key_description(&self) -> Color {
-        self.text_muted
+        adapt_color(self.text_muted)
     }
 
     /// Issue number color
     pub fn issue_number(&self) -> Color {
-        self.neon_yellow
+        adapt_color(self.neon_yellow)
     }
 
     /// Issue label color
     pub fn issue_label(&self) -> Color {
-        self.neon_purple
+        adapt_color(self.neon_purple)
     }
 
     // ==================== Style Methods ====================
@@ -385,8 +385,8 @@ impl ThemeColors {
     /// Style for inactive tab
     pub fn style_tab_inactive(&self) -> Style {
         Style::default()
-            .fg(self.text_secondary)
-            .bg(self.bg_surface)
+            .fg(self.text_secondary())
+            .bg(self.bg_surface())
     }
 
     /// Style for primary border
@@ -396,12 +396,12 @@ impl ThemeColors {
 
     /// Style for secondary (muted) border
     pub fn style_border_muted(&self) -> Style {
-        Style::default().fg(self.border_secondary)
+        Style::default().fg(self.border_secondary())
     }
 
     /// Style for dialog border
     pub fn style_dialog_border(&self) -> Style {
-        Style::default().fg(self.neon_cyan)
+        Style::default().fg(self.neon_cyan())
     }
 
     /// Style for success text
@@ -414,7 +414,7 @@ impl ThemeColors {
     /// Style for error text
     pub fn style_error(&self) -> Style {
         Style::default()
-            .fg(self.error)
+            .fg(self.error())
             .add_modifier(Modifier::BOLD)
     }
 
@@ -430,17 +430,17 @@ impl ThemeColors {
 
     /// Style for primary text
     pub fn style_text(&self) -> Style {
-        Style::default().fg(self.text_primary)
+        Style::default().fg(self.text_primary())
     }
 
     /// Style for secondary text
     pub fn style_text_secondary(&self) -> Style {
-        Style::default().fg(self.text_secondary)
+        Style::default().fg(self.text_secondary())
     }
 
     /// Style for muted text
     pub fn style_text_muted(&self) -> Style {
-        Style::default().fg(self.text_muted)
+        Style::default().fg(self.text_muted())
     }
 
     /// Style for key bindings
@@ -471,17 +471,17 @@ impl ThemeColors {
 
     /// Style for input text
     pub fn style_input(&self) -> Style {
-        Style::default().fg(self.neon_yellow)
+        Style::default().fg(self.neon_yellow())
     }
 
     /// Style for loading indicator
     pub fn style_loading(&self) -> Style {
-        Style::default().fg(self.neon_yellow)
+        Style::default().fg(self.neon_yellow())
     }
 
     /// Style for dialog background
     pub fn style_dialog_bg(&self) -> Style {
-        Style::default().bg(self.bg_dark)
+        Style::default().bg(self.bg_dark())
     }
 }
 
@@ -725,6 +725,176 @@ pub fn available_themes() -> &'static [(&'static str, &'static str, &'static str
     ]
 }
 
+// ==================== Color Degradation ====================
+
+/// How many colors the terminal can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum ColorDepth {
+    /// 24-bit RGB
+    #[default]
+    #[serde(rename = "truecolor")]
+    TrueColor,
+    /// The xterm 256-color palette
+    #[serde(rename = "256")]
+    Ansi256,
+    /// The 16 ANSI colors
+    #[serde(rename = "16")]
+    Ansi16,
+}
+
+impl ColorDepth {
+    /// Detect the color depth from `COLORTERM` and `TERM`
+    ///
+    /// `COLORTERM=truecolor` (or `24bit`) and `TERM` values like
+    /// `xterm-direct` mean truecolor; basic terminals such as `linux` or
+    /// `vt100` get 16 colors; any other `TERM` gets 256. Without a `TERM`
+    /// (e.g. Windows Terminal) truecolor is assumed.
+    pub fn detect(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        let colorterm = colorterm.unwrap_or_default().trim().to_ascii_lowercase();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorDepth::TrueColor;
+        }
+        let term = term.unwrap_or_default().trim().to_ascii_lowercase();
+        if term.is_empty() || ["direct", "truecolor", "24bit"].iter().any(|t| term.contains(t)) {
+            ColorDepth::TrueColor
+        } else if term.contains("256") {
+            ColorDepth::Ansi256
+        } else if ["linux", "vt100", "vt220", "ansi", "cons25", "dumb"]
+            .iter()
+            .any(|basic| term == *basic)
+        {
+            ColorDepth::Ansi16
+        } else {
+            ColorDepth::Ansi256
+        }
+    }
+
+    /// Detect the color depth of the terminal cctakt runs in
+    pub fn from_env() -> Self {
+        Self::detect(
+            std::env::var("COLORTERM").ok().as_deref(),
+            std::env::var("TERM").ok().as_deref(),
+        )
+    }
+}
+
+/// Color depth theme colors are adapted to
+static COLOR_DEPTH: RwLock<ColorDepth> = RwLock::new(ColorDepth::TrueColor);
+
+/// Set the color depth theme colors are adapted to
+pub fn set_color_depth(depth: ColorDepth) {
+    *COLOR_DEPTH.write().unwrap_or_else(|e| e.into_inner()) = depth;
+}
+
+/// Get the color depth theme colors are adapted to
+pub fn color_depth() -> ColorDepth {
+    *COLOR_DEPTH.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// `color` as the terminal can show it at the current color depth
+pub fn adapt_color(color: Color) -> Color {
+    degrade_color(color, color_depth())
+}
+
+/// `color` as a terminal with `depth` colors can show it
+///
+/// RGB colors become their nearest palette entry; with 16 colors, 256-color
+/// palette entries are mapped down as well.
+pub fn degrade_color(color: Color, depth: ColorDepth) -> Color {
+    match (color, depth) {
+        (_, ColorDepth::TrueColor) => color,
+        (Color::Rgb(r, g, b), ColorDepth::Ansi256) => Color::Indexed(rgb_to_ansi256(r, g, b)),
+        (Color::Rgb(r, g, b), ColorDepth::Ansi16) => rgb_to_ansi16(r, g, b),
+        (Color::Indexed(index), ColorDepth::Ansi16) if index >= 16 => {
+            let (r, g, b) = ansi256_to_rgb(index);
+            rgb_to_ansi16(r, g, b)
+        }
+        (other, _) => other,
+    }
+}
+
+/// Levels of the 6x6x6 color cube in the 256-color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Squared distance between two RGB colors
+fn rgb_distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    [(r1, r2), (g1, g2), (b1, b2)]
+        .iter()
+        .map(|&(a, b)| u32::from(a.abs_diff(b)).pow(2))
+        .sum()
+}
+
+/// Nearest entry of the 256-color palette for an RGB color
+///
+/// Picks the closer of the 6x6x6 color cube (16-231) and the grayscale
+/// ramp (232-255); the 16 system colors are skipped since terminals
+/// redefine them freely.
+pub fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let nearest_level = |value: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| CUBE_LEVELS[i].abs_diff(value))
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (nearest_level(r), nearest_level(g), nearest_level(b));
+    let cube = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+    let cube_index = 16 + 36 * ri + 6 * gi + bi;
+
+    // Gray ramp: 8, 18, ..., 238
+    let average = (u32::from(r) + u32::from(g) + u32::from(b)) / 3;
+    let gray_step = (average.saturating_sub(3) / 10).min(23);
+    let gray_level = (8 + 10 * gray_step) as u8;
+    let gray_index = 232 + gray_step as usize;
+
+    if rgb_distance((r, g, b), (gray_level, gray_level, gray_level)) < rgb_distance((r, g, b), cube) {
+        gray_index as u8
+    } else {
+        cube_index as u8
+    }
+}
+
+/// RGB value of a 256-color palette entry (xterm defaults for 0-15)
+fn ansi256_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI16[usize::from(index)].1,
+        16..=231 => {
+            let i = usize::from(index - 16);
+            (CUBE_LEVELS[i / 36], CUBE_LEVELS[i / 6 % 6], CUBE_LEVELS[i % 6])
+        }
+        _ => {
+            let level = 8 + 10 * (index - 232);
+            (level, level, level)
+        }
+    }
+}
+
+/// The 16 ANSI colors with xterm's default RGB values
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Nearest of the 16 ANSI colors for an RGB color
+pub fn rgb_to_ansi16(r: u8, g: u8, b: u8) -> Color {
+    ANSI16
+        .iter()
+        .min_by_key(|(_, rgb)| rgb_distance((r, g, b), *rgb))
+        .map_or(Color::White, |(color, _)| *color)
+}
+
 // ==================== Legacy API Compatibility ====================
 
 /// ColorTheme trait for backwards compatibility
@@ -1227,4 +1397,64 @@ mod tests {
         assert_eq!(themes[4].0, "arctic");
         assert_eq!(themes[5].0, "minimal");
     }
+
+    #[test]
+    fn test_color_depth_detect() {
+        assert_eq!(ColorDepth::detect(Some("truecolor"), Some("xterm-256color")), ColorDepth::TrueColor);
+        assert_eq!(ColorDepth::detect(Some("24bit"), Some("linux")), ColorDepth::TrueColor);
+        assert_eq!(ColorDepth::detect(None, Some("xterm-direct")), ColorDepth::TrueColor);
+        assert_eq!(ColorDepth::detect(None, Some("xterm-256color")), ColorDepth::Ansi256);
+        assert_eq!(ColorDepth::detect(Some(""), Some("screen")), ColorDepth::Ansi256);
+        assert_eq!(ColorDepth::detect(None, Some("linux")), ColorDepth::Ansi16);
+        assert_eq!(ColorDepth::detect(None, Some("vt100")), ColorDepth::Ansi16);
+        assert_eq!(ColorDepth::detect(None, None), ColorDepth::TrueColor);
+    }
+
+    #[test]
+    fn test_color_depth_parse() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            depth: ColorDepth,
+        }
+        let parse = |value: &str| toml::from_str::<Wrapper>(&format!("depth = \"{value}\"")).map(|w| w.depth);
+        assert_eq!(parse("truecolor").unwrap(), ColorDepth::TrueColor);
+        assert_eq!(parse("256").unwrap(), ColorDepth::Ansi256);
+        assert_eq!(parse("16").unwrap(), ColorDepth::Ansi16);
+        assert!(parse("88").is_err());
+    }
+
+    #[test]
+    fn test_rgb_to_ansi256() {
+        // Cube corners
+        assert_eq!(rgb_to_ansi256(0, 0, 0), 16);
+        assert_eq!(rgb_to_ansi256(255, 255, 255), 231);
+        assert_eq!(rgb_to_ansi256(255, 0, 0), 196);
+        assert_eq!(rgb_to_ansi256(0, 255, 255), 51);
+        // Near-cube colors snap to the closest level
+        assert_eq!(rgb_to_ansi256(255, 0, 128), 198);
+        // Grays go to the grayscale ramp
+        assert_eq!(rgb_to_ansi256(97, 97, 97), 241);
+        assert_eq!(rgb_to_ansi256(18, 18, 18), 233);
+    }
+
+    #[test]
+    fn test_rgb_to_ansi16() {
+        assert_eq!(rgb_to_ansi16(255, 0, 128), Color::Magenta);
+        assert_eq!(rgb_to_ansi16(0, 255, 255), Color::LightCyan);
+        assert_eq!(rgb_to_ansi16(13, 13, 26), Color::Black);
+        assert_eq!(rgb_to_ansi16(158, 158, 158), Color::DarkGray);
+    }
+
+    #[test]
+    fn test_degrade_color() {
+        let red = Color::Rgb(255, 0, 0);
+        assert_eq!(degrade_color(red, ColorDepth::TrueColor), red);
+        assert_eq!(degrade_color(red, ColorDepth::Ansi256), Color::Indexed(196));
+        assert_eq!(degrade_color(red, ColorDepth::Ansi16), Color::LightRed);
+        // Palette entries only need mapping down to 16 colors
+        assert_eq!(degrade_color(Color::Indexed(196), ColorDepth::Ansi256), Color::Indexed(196));
+        assert_eq!(degrade_color(Color::Indexed(196), ColorDepth::Ansi16), Color::LightRed);
+        assert_eq!(degrade_color(Color::Indexed(3), ColorDepth::Ansi16), Color::Indexed(3));
+        assert_eq!(degrade_color(Color::Cyan, ColorDepth::Ansi16), Color::Cyan);
+    }
 }
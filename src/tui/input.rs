@@ -1,75 +1,365 @@
 //! Input handling for TUI
 
-use crate::app::{App, AppMode, FocusedPane, InputMode};
-use cctakt::{available_themes, plan::NotifyLevel};
-use crossterm::event::{KeyCode, KeyModifiers};
+use crate::agent::AgentMode;
+use crate::app::{App, AppMode, FocusedPane, InputMode, ReviewFocus};
+use crate::tui::keymap::GlobalAction;
+use crate::tui::output::{OutputFilter, OutputKind};
+use crate::tui::render::{grid_cells, tab_at_column};
+use cctakt::{available_themes, plan::NotifyLevel, DialogResult, ReviewEvent};
+use crossterm::event::{KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
 
-/// Handle special keybindings, returns true if handled
+/// Lines moved per mouse wheel step
+const MOUSE_SCROLL_LINES: u16 = 3;
+
+/// Columns moved per Left/Right press in the review diff's scroll mode
+const DIFF_COLUMN_STEP: u16 = 8;
+
+/// How much `-` / `+` move the split between orchestrator and workers
+const SPLIT_STEP: f32 = 0.05;
+
+/// Handle global keybindings (see `Keymap`), returns true if handled
 pub fn handle_keybinding(app: &mut App, modifiers: KeyModifiers, code: KeyCode) -> bool {
-    match (modifiers, code) {
-        // Ctrl+Q: Quit
-        (KeyModifiers::CONTROL, KeyCode::Char('q' | 'Q')) => {
+    // Ctrl+1-9 / Alt+1-9: Switch to tab by number
+    if let KeyCode::Char(c @ '1'..='9') = code
+        && (modifiers == KeyModifiers::CONTROL || modifiers == KeyModifiers::ALT)
+    {
+        let index = (c as usize) - ('1' as usize);
+        app.agent_manager.switch_to(index);
+        return true;
+    }
+
+    let Some(action) = app.keymap.lookup(modifiers, code) else {
+        return false;
+    };
+    match action {
+        GlobalAction::Quit => app.request_quit(),
+        GlobalAction::ThemePicker => app.open_theme_picker(),
+        GlobalAction::IssuePicker => app.open_issue_picker(),
+        GlobalAction::CloseAgent => app.close_active_agent(),
+        GlobalAction::NextTab => app.agent_manager.next(),
+        GlobalAction::PrevTab => app.agent_manager.prev(),
+        GlobalAction::RestartConductor => match app.restart_conductor() {
+            Ok(()) => {
+                app.add_notification("Conductor restarted".to_string(), NotifyLevel::Success);
+            }
+            Err(e) => {
+                app.add_notification(
+                    format!("Failed to restart conductor: {e}"),
+                    NotifyLevel::Error,
+                );
+            }
+        },
+        GlobalAction::LogViewer => {
+            app.log_viewer.reset();
+            app.log_viewer.refresh();
+            app.mode = AppMode::LogViewer;
+        }
+        GlobalAction::Help => app.open_help(),
+    }
+    true
+}
+
+/// Handle help overlay input
+pub fn handle_help_input(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Esc | KeyCode::Char('?') | KeyCode::Char('q') | KeyCode::F(1) => {
+            app.close_help();
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            app.help_scroll = app.help_scroll.saturating_sub(1);
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            app.help_scroll = app.help_scroll.saturating_add(1);
+        }
+        _ => {}
+    }
+}
+
+/// Handle plan overlay input
+pub fn handle_plan_view_input(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('p') => app.mode = AppMode::Normal,
+        KeyCode::Up | KeyCode::Char('k') => app.move_plan_selection(-1),
+        KeyCode::Down | KeyCode::Char('j') => app.move_plan_selection(1),
+        KeyCode::PageUp | KeyCode::Char('u') => app.move_plan_selection(-10),
+        KeyCode::PageDown | KeyCode::Char('d') => app.move_plan_selection(10),
+        KeyCode::Home | KeyCode::Char('g') => app.move_plan_selection(isize::MIN),
+        KeyCode::End | KeyCode::Char('G') => app.move_plan_selection(isize::MAX),
+        KeyCode::Char('r') => {
+            if let Some(task_id) = app.selected_plan_task() {
+                app.retry_task(&task_id);
+            }
+        }
+        KeyCode::Char('s') => {
+            if let Some(task_id) = app.selected_plan_task() {
+                app.skip_task(&task_id);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Handle input while the review commit details overlay is open
+pub fn handle_review_commits_input(app: &mut App, code: KeyCode) {
+    let Some(view) = app
+        .review_state
+        .as_mut()
+        .and_then(|state| state.commits_view.as_mut())
+    else {
+        return;
+    };
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('c') => {
+            app.close_review_commits();
+        }
+        KeyCode::Up | KeyCode::Char('k') => view.scroll = view.scroll.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => view.scroll = view.scroll.saturating_add(1),
+        KeyCode::PageUp | KeyCode::Char('u') => view.scroll = view.scroll.saturating_sub(20),
+        KeyCode::PageDown | KeyCode::Char('d') => view.scroll = view.scroll.saturating_add(20),
+        KeyCode::Home | KeyCode::Char('g') => view.scroll = 0,
+        KeyCode::End | KeyCode::Char('G') => view.scroll = u16::MAX,
+        _ => {}
+    }
+}
+
+/// Handle input while the review base branch prompt is open
+pub fn handle_review_base_input(app: &mut App, code: KeyCode) {
+    let Some(result) = app
+        .review_state
+        .as_mut()
+        .and_then(|state| state.base_dialog.as_mut())
+        .and_then(|dialog| dialog.handle_key(code))
+    else {
+        return;
+    };
+    if let Some(ref mut state) = app.review_state {
+        state.base_dialog = None;
+    }
+    if let DialogResult::Submit(base) = result
+        && !base.trim().is_empty()
+    {
+        app.change_review_base(base.trim());
+    }
+}
+
+/// Handle the reject feedback dialog in review mode
+pub fn handle_review_reject_input(app: &mut App, code: KeyCode) {
+    let Some(result) = app
+        .review_state
+        .as_mut()
+        .and_then(|state| state.reject_dialog.as_mut())
+        .and_then(|dialog| dialog.handle_key(code))
+    else {
+        return;
+    };
+    if let Some(ref mut state) = app.review_state {
+        state.reject_dialog = None;
+    }
+    // Line comments alone are enough feedback
+    let has_comments = app
+        .review_state
+        .as_ref()
+        .is_some_and(|state| !state.comments.is_empty());
+    if let DialogResult::Submit(feedback) = result
+        && (!feedback.trim().is_empty() || has_comments)
+    {
+        app.reject_review(feedback.trim());
+    }
+}
+
+/// Handle the review verdict prompt (after pressing `p`)
+pub fn handle_review_post_input(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Char('a') => app.post_review(ReviewEvent::Approve),
+        KeyCode::Char('r') => app.post_review(ReviewEvent::RequestChanges),
+        KeyCode::Char('c') => app.post_review(ReviewEvent::Comment),
+        KeyCode::Esc | KeyCode::Char('q') => app.cancel_review_post(),
+        _ => {}
+    }
+}
+
+/// Handle the line comment prompt in review mode
+pub fn handle_review_comment_input(app: &mut App, code: KeyCode) {
+    let Some(result) = app
+        .review_state
+        .as_mut()
+        .and_then(|state| state.comment_dialog.as_mut())
+        .and_then(|(_, dialog)| dialog.handle_key(code))
+    else {
+        return;
+    };
+    let Some((location, _)) = app
+        .review_state
+        .as_mut()
+        .and_then(|state| state.comment_dialog.take())
+    else {
+        return;
+    };
+    if let DialogResult::Submit(text) = result {
+        app.set_review_comment(location, &text);
+    }
+}
+
+/// Handle the review diff search prompt (after pressing `/`)
+pub fn handle_review_search_input(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Enter => app.run_review_search(),
+        KeyCode::Esc => app.clear_review_search(),
+        // Toggle case-sensitive matching
+        KeyCode::Tab => {
+            if let Some(ref mut state) = app.review_state {
+                state.diff_view.toggle_search_case();
+            }
+        }
+        KeyCode::Backspace => {
+            if let Some(query) = app
+                .review_state
+                .as_mut()
+                .and_then(|state| state.search_input.as_mut())
+            {
+                query.pop();
+            }
+        }
+        KeyCode::Char(c) => {
+            if let Some(query) = app
+                .review_state
+                .as_mut()
+                .and_then(|state| state.search_input.as_mut())
+            {
+                query.push(c);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Handle debug log viewer input
+pub fn handle_log_viewer_input(app: &mut App, code: KeyCode) {
+    let viewer = &mut app.log_viewer;
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::F(12) => {
+            app.mode = AppMode::Normal;
+        }
+        KeyCode::Up | KeyCode::Char('k') => viewer.scroll_up(1),
+        KeyCode::Down | KeyCode::Char('j') => viewer.scroll_down(1),
+        KeyCode::PageUp | KeyCode::Char('u') => viewer.scroll_up(20),
+        KeyCode::PageDown | KeyCode::Char('d') => viewer.scroll_down(20),
+        KeyCode::Home | KeyCode::Char('g') => viewer.scroll_to_top(),
+        KeyCode::End | KeyCode::Char('G') => viewer.follow(),
+        KeyCode::Char('v') => {
+            // Toggle verbose (trace) debug logging
+            let message = match cctakt::debug::toggle_verbose() {
+                Some(level) => format!("Debug logging: {level:?}"),
+                None => "Debug logging: off".to_string(),
+            };
+            app.add_notification(message, NotifyLevel::Info);
+        }
+        _ => {}
+    }
+}
+
+/// Handle input while the quit confirmation dialog is shown
+///
+/// `y` or pressing the quit key again quits; `n` or Esc cancels. Other keys
+/// are ignored.
+pub fn handle_quit_confirm(app: &mut App, modifiers: KeyModifiers, code: KeyCode) {
+    let quit_key = app.keymap.lookup(modifiers, code) == Some(GlobalAction::Quit);
+    match code {
+        KeyCode::Char('y' | 'Y') => {
+            app.quit_prompt = None;
             app.should_quit = true;
-            true
-        }
-        // Ctrl+T: Open theme picker
-        (KeyModifiers::CONTROL, KeyCode::Char('t' | 'T')) => {
-            app.open_theme_picker();
-            true
-        }
-        // Ctrl+I or F2: Open issue picker
-        (KeyModifiers::CONTROL, KeyCode::Char('i' | 'I')) | (_, KeyCode::F(2)) => {
-            app.open_issue_picker();
-            true
-        }
-        // Ctrl+W: Close active agent
-        (KeyModifiers::CONTROL, KeyCode::Char('w' | 'W')) => {
-            app.close_active_agent();
-            true
-        }
-        // Ctrl+Tab or plain Tab (when no agent focused): Next tab
-        // Note: Ctrl+Tab may not work in all terminals, so we use Ctrl+N as alternative
-        (KeyModifiers::CONTROL, KeyCode::Char('n' | 'N')) => {
-            app.agent_manager.next();
-            true
-        }
-        // Ctrl+P: Previous tab
-        (KeyModifiers::CONTROL, KeyCode::Char('p' | 'P')) => {
-            app.agent_manager.prev();
-            true
-        }
-        // Ctrl+R: Restart conductor (orchestrator)
-        (KeyModifiers::CONTROL, KeyCode::Char('r' | 'R')) => {
-            match app.restart_conductor() {
-                Ok(()) => {
-                    app.add_notification(
-                        "Conductor restarted".to_string(),
-                        NotifyLevel::Success,
-                    );
+        }
+        _ if quit_key => {
+            app.quit_prompt = None;
+            app.should_quit = true;
+        }
+        KeyCode::Char('n' | 'N') | KeyCode::Esc => {
+            app.quit_prompt = None;
+        }
+        _ => {}
+    }
+}
+
+/// Handle mouse input (only delivered when `ui.mouse` is enabled)
+///
+/// Clicking a header tab switches to it, clicking a pane focuses it, and the
+/// wheel scrolls the diff in review mode or the worker output otherwise.
+pub fn handle_mouse(app: &mut App, mouse: MouseEvent) {
+    if app.mode == AppMode::LogViewer {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => app.log_viewer.scroll_up(MOUSE_SCROLL_LINES as usize),
+            MouseEventKind::ScrollDown => app.log_viewer.scroll_down(MOUSE_SCROLL_LINES as usize),
+            _ => {}
+        }
+        return;
+    }
+    if app.mode == AppMode::PlanView {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => app.move_plan_selection(-(MOUSE_SCROLL_LINES as isize)),
+            MouseEventKind::ScrollDown => app.move_plan_selection(MOUSE_SCROLL_LINES as isize),
+            _ => {}
+        }
+        return;
+    }
+    if matches!(
+        app.mode,
+        AppMode::IssuePicker | AppMode::ThemePicker | AppMode::Help
+    ) {
+        return;
+    }
+    let pos = Position::new(mouse.column, mouse.row);
+    let in_rect = |rect: Option<Rect>| rect.is_some_and(|r| r.contains(pos));
+
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            if app.regions.header.contains(pos) {
+                let column = mouse.column - app.regions.header.x;
+                if let Some(index) = tab_at_column(app, column) {
+                    app.agent_manager.switch_to(index);
+                    if let Some(agent) = app.agent_manager.get(index) {
+                        app.focused_pane = match agent.mode {
+                            AgentMode::Interactive => FocusedPane::Left,
+                            AgentMode::NonInteractive => FocusedPane::Right,
+                        };
+                    }
                 }
-                Err(e) => {
-                    app.add_notification(
-                        format!("Failed to restart conductor: {e}"),
-                        NotifyLevel::Error,
-                    );
+            } else if in_rect(app.regions.left_pane) {
+                app.focused_pane = FocusedPane::Left;
+            } else if in_rect(app.regions.right_pane) {
+                app.focused_pane = FocusedPane::Right;
+                // Clicking a grid cell selects its worker
+                let workers = app.grid_workers();
+                if app.mode != AppMode::ReviewMerge
+                    && let Some(pane) = app.regions.right_pane
+                    && let Some(cell) = grid_cells(pane, workers.len())
+                        .iter()
+                        .position(|cell| cell.contains(pos))
+                {
+                    app.agent_manager.switch_to(workers[cell]);
                 }
             }
-            true
-        }
-        // Ctrl+1-9: Switch to tab by number
-        (KeyModifiers::CONTROL, KeyCode::Char(c)) if ('1'..='9').contains(&c) => {
-            let index = (c as usize) - ('1' as usize);
-            app.agent_manager.switch_to(index);
-            true
         }
-        // Alt+1-9: Also switch to tab by number (more compatible)
-        (KeyModifiers::ALT, KeyCode::Char(c)) if ('1'..='9').contains(&c) => {
-            let index = (c as usize) - ('1' as usize);
-            app.agent_manager.switch_to(index);
-            true
+        MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+            let up = mouse.kind == MouseEventKind::ScrollUp;
+            if app.mode == AppMode::ReviewMerge {
+                if let Some(ref mut state) = app.review_state {
+                    if up {
+                        state.diff_view.scroll_up(MOUSE_SCROLL_LINES);
+                    } else {
+                        state.diff_view.scroll_down(MOUSE_SCROLL_LINES);
+                    }
+                }
+            } else if in_rect(app.regions.right_pane) {
+                let scroll = &mut app.output_filter.scroll;
+                *scroll = if up {
+                    scroll.saturating_add(MOUSE_SCROLL_LINES as usize)
+                } else {
+                    scroll.saturating_sub(MOUSE_SCROLL_LINES as usize)
+                };
+            }
         }
-        // Note: hjkl pane navigation is handled in Navigation mode (see AppMode::Normal)
-        _ => false,
+        _ => {}
     }
 }
 
@@ -110,9 +400,158 @@ pub fn handle_theme_picker_input(app: &mut App, code: KeyCode) {
     }
 }
 
+/// Handle a key in review mode, returns true if handled
+///
+/// Both input modes share the review actions listed in the help; navigation
+/// mode adds `i` / `h` / `l`, and Esc clears the search there instead of
+/// leaving input mode.
+pub fn handle_review_input(app: &mut App, code: KeyCode) -> bool {
+    let summary_open = app
+        .review_state
+        .as_ref()
+        .is_some_and(|s| s.focus == ReviewFocus::Diff && s.diff_view.is_summary());
+    match (app.input_mode, code) {
+        // Enter on the file summary jumps to that file's diff
+        (_, KeyCode::Enter) if summary_open => {
+            if let Some(ref mut state) = app.review_state {
+                state.diff_view.open_selected_file();
+            }
+        }
+        (InputMode::Navigation, KeyCode::Char('i') | KeyCode::Enter) => {
+            app.input_mode = InputMode::Input;
+        }
+        // Clear an active diff search
+        (InputMode::Navigation, KeyCode::Esc) => app.clear_review_search(),
+        // Pane navigation with h/l
+        (InputMode::Navigation, KeyCode::Char('h')) => app.focused_pane = FocusedPane::Left,
+        (InputMode::Navigation, KeyCode::Char('l')) => app.focused_pane = FocusedPane::Right,
+        (_, KeyCode::Esc) => app.input_mode = InputMode::Navigation,
+        // Command mode is not used in review
+        (InputMode::Command, _) => return false,
+        (_, KeyCode::Enter) => {}
+        _ => return handle_review_action(app, code),
+    }
+    true
+}
+
+/// Review actions and scrolling shared by both input modes
+fn handle_review_action(app: &mut App, code: KeyCode) -> bool {
+    match code {
+        // Merge, or open a PR in pull_request merge mode
+        KeyCode::Char('m') | KeyCode::Char('M') => app.approve_review(),
+        // Full commit details overlay
+        KeyCode::Char('c') => app.open_review_commits(),
+        // Comment on the diff line at the top of the view
+        KeyCode::Char('C') => app.open_review_comment_prompt(),
+        KeyCode::Char('?') => app.open_help(),
+        // Cancel review (skips to the next one in the review queue)
+        KeyCode::Char('q') => app.cancel_review(),
+        // Cancel review and the rest of the review queue
+        KeyCode::Char('Q') => app.stop_review_queue(),
+        // Toggle per-file stat summary
+        KeyCode::Char('s') | KeyCode::Char('S') => {
+            if let Some(ref mut state) = app.review_state {
+                state.diff_view.toggle_summary();
+                state.focus = ReviewFocus::Diff;
+            }
+        }
+        // Add the reviewed branch to the plan as a MergeBranch task
+        KeyCode::Char('a') | KeyCode::Char('A') => app.queue_review_as_task(),
+        // Toggle word-level diff highlighting
+        KeyCode::Char('w') | KeyCode::Char('W') => {
+            if let Some(ref mut state) = app.review_state {
+                state.diff_view.toggle_word_diff();
+            }
+        }
+        // Toggle the old/new line number gutter
+        KeyCode::Char('#') => {
+            if let Some(ref mut state) = app.review_state {
+                state.diff_view.toggle_line_numbers();
+            }
+        }
+        // Branch changes / net merge result against the base
+        KeyCode::Char('v') | KeyCode::Char('V') => app.toggle_review_merge_result(),
+        // Compare against a different base branch
+        KeyCode::Char('b') | KeyCode::Char('B') => app.open_review_base_prompt(),
+        // Merge or rebase the base into the branch
+        KeyCode::Char('U') => app.update_review_branch(),
+        // Open the PR as a draft (pull_request merge mode)
+        KeyCode::Char('D') => app.toggle_review_draft(),
+        // Reject with feedback for the worker
+        KeyCode::Char('x') | KeyCode::Char('X') => app.open_review_reject_prompt(),
+        // Post the line comments as a review on the branch's PR
+        KeyCode::Char('p') | KeyCode::Char('P') => app.open_review_post_prompt(),
+        // Search the diff; n / N cycle through matches
+        KeyCode::Char('/') => app.open_review_search(),
+        KeyCode::Char('n') => app.review_search_step(true),
+        KeyCode::Char('N') => app.review_search_step(false),
+        // Cycle long-line mode: truncate → wrap → scroll
+        KeyCode::Char('z') | KeyCode::Char('Z') => {
+            if let Some(ref mut state) = app.review_state {
+                state.diff_view.cycle_overflow();
+            }
+        }
+        KeyCode::Left => {
+            if let Some(ref mut state) = app.review_state {
+                state.diff_view.scroll_left(DIFF_COLUMN_STEP);
+            }
+        }
+        KeyCode::Right => {
+            if let Some(ref mut state) = app.review_state {
+                state.diff_view.scroll_right(DIFF_COLUMN_STEP);
+            }
+        }
+        // Focus switching between Summary/Diff with Tab
+        KeyCode::Tab => {
+            if let Some(ref mut state) = app.review_state {
+                state.focus = match state.focus {
+                    ReviewFocus::Summary => ReviewFocus::Diff,
+                    ReviewFocus::Diff => ReviewFocus::Summary,
+                };
+            }
+        }
+        _ => return scroll_review(app, code),
+    }
+    true
+}
+
+/// Scroll the focused review pane (commit summary or diff)
+fn scroll_review(app: &mut App, code: KeyCode) -> bool {
+    let Some(ref mut state) = app.review_state else {
+        return false;
+    };
+    let max_summary = state.commit_log.lines().count().saturating_sub(1) as u16;
+    let summary = &mut state.summary_scroll;
+    let diff = &mut state.diff_view;
+    let on_summary = state.focus == ReviewFocus::Summary;
+    match code {
+        KeyCode::Char('k') | KeyCode::Up if on_summary => *summary = summary.saturating_sub(1),
+        KeyCode::Char('k') | KeyCode::Up => diff.scroll_up(1),
+        KeyCode::Char('j') | KeyCode::Down if on_summary => *summary = (*summary + 1).min(max_summary),
+        KeyCode::Char('j') | KeyCode::Down => diff.scroll_down(1),
+        KeyCode::Char('u') | KeyCode::PageUp if on_summary => *summary = summary.saturating_sub(10),
+        KeyCode::Char('u') | KeyCode::PageUp => diff.page_up(20),
+        KeyCode::Char('d') | KeyCode::PageDown if on_summary => *summary = (*summary + 10).min(max_summary),
+        KeyCode::Char('d') | KeyCode::PageDown => diff.page_down(20),
+        KeyCode::Char('g') | KeyCode::Home if on_summary => *summary = 0,
+        KeyCode::Char('g') | KeyCode::Home => diff.scroll_to_top(),
+        KeyCode::Char('G') | KeyCode::End if on_summary => *summary = max_summary,
+        KeyCode::Char('G') | KeyCode::End => diff.scroll_to_bottom(),
+        _ => return false,
+    }
+    true
+}
+
 /// Handle navigation mode keys (hjkl)
 pub fn handle_navigation_mode(app: &mut App, code: KeyCode) {
+    let in_grid = app.worker_grid && app.focused_pane == FocusedPane::Right;
     match code {
+        // Move among the cells of the worker grid
+        KeyCode::Char('h') if in_grid => app.move_grid_focus(-1, 0),
+        KeyCode::Char('l') if in_grid => app.move_grid_focus(1, 0),
+        KeyCode::Char('j') if in_grid => app.move_grid_focus(0, 1),
+        KeyCode::Char('k') if in_grid => app.move_grid_focus(0, -1),
+        KeyCode::Char('g') => app.toggle_worker_grid(),
         KeyCode::Char('h') => {
             app.focused_pane = FocusedPane::Left;
         }
@@ -133,11 +572,71 @@ pub fn handle_navigation_mode(app: &mut App, code: KeyCode) {
             // Switch to input mode
             app.input_mode = InputMode::Input;
         }
+        KeyCode::Char('?') => {
+            app.open_help();
+        }
+        KeyCode::Char('o') => {
+            app.open_in_editor();
+        }
+        KeyCode::Char('O') => app.open_agent_in_browser(),
+        KeyCode::Char('p') => app.open_plan_view(),
+        KeyCode::Char('R') => app.review_all_pending(),
+        KeyCode::Char('!') => app.jump_to_attention(),
+        // Reorder tabs
+        KeyCode::Char('<') => app.move_active_agent(-1),
+        KeyCode::Char('>') => app.move_active_agent(1),
+        // Resize the split between orchestrator and workers
+        KeyCode::Char('-') => app.nudge_split(-SPLIT_STEP),
+        KeyCode::Char('+') | KeyCode::Char('=') => app.nudge_split(SPLIT_STEP),
         KeyCode::Char(':') => {
             // Enter command mode
             app.command_buffer.clear();
             app.input_mode = InputMode::Command;
         }
+        // Worker output filters (right pane)
+        KeyCode::Char('/') if app.focused_pane == FocusedPane::Right => {
+            app.output_filter.search.clear();
+            app.output_filter.editing_search = true;
+        }
+        KeyCode::Char('s') if app.focused_pane == FocusedPane::Right => {
+            app.output_filter.toggle(OutputKind::System);
+        }
+        KeyCode::Char('a') if app.focused_pane == FocusedPane::Right => {
+            app.output_filter.toggle(OutputKind::Assistant);
+        }
+        KeyCode::Char('e') if app.focused_pane == FocusedPane::Right => {
+            app.output_filter.toggle(OutputKind::Error);
+        }
+        KeyCode::Char('r') if app.focused_pane == FocusedPane::Right => {
+            app.output_filter.toggle(OutputKind::Result);
+        }
+        KeyCode::Char('t') if app.focused_pane == FocusedPane::Right => {
+            app.output_filter.toggle(OutputKind::Tool);
+        }
+        KeyCode::Char('x') if app.focused_pane == FocusedPane::Right => {
+            // Reset filters and search (tool use visibility is kept)
+            app.output_filter = OutputFilter::with_tools(app.output_filter.show_tools);
+        }
+        _ => {}
+    }
+}
+
+/// Handle worker output search input (after pressing `/`)
+pub fn handle_output_search_input(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Enter => {
+            app.output_filter.editing_search = false;
+        }
+        KeyCode::Esc => {
+            app.output_filter.search.clear();
+            app.output_filter.editing_search = false;
+        }
+        KeyCode::Backspace => {
+            app.output_filter.search.pop();
+        }
+        KeyCode::Char(c) => {
+            app.output_filter.search.push(c);
+        }
         _ => {}
     }
 }
@@ -151,16 +650,36 @@ pub fn handle_command_mode(app: &mut App, code: KeyCode) {
             app.input_mode = InputMode::Navigation;
         }
         KeyCode::Enter => {
-            // Execute command
-            let cmd = app.command_buffer.trim().to_lowercase();
+            // Execute command (arguments keep their case, e.g. branch names)
+            let input = app.command_buffer.trim().to_string();
+            let (cmd, arg) = match input.split_once(char::is_whitespace) {
+                Some((cmd, arg)) => (cmd.to_lowercase(), arg.trim()),
+                None => (input.to_lowercase(), ""),
+            };
             match cmd.as_str() {
                 "q" | "quit" | "exit" => {
+                    app.request_quit();
+                }
+                "q!" | "quit!" => {
+                    // Quit without confirmation
                     app.should_quit = true;
                 }
                 "w" => {
                     // Close active agent (like :w in vim... but we use it for close)
                     app.close_active_agent();
                 }
+                "merge" if !arg.is_empty() => {
+                    // Append a MergeBranch task to the plan
+                    app.queue_merge_task(arg);
+                }
+                "cleanup" if !arg.is_empty() => {
+                    // Append a CleanupWorktree task to the plan
+                    app.queue_cleanup_task(arg);
+                }
+                "attach" if !arg.is_empty() => {
+                    // Review an existing branch (input mode is reset below)
+                    app.attach_branch(arg);
+                }
                 _ => {
                     // Unknown command - show notification
                     if !cmd.is_empty() {
@@ -0,0 +1,411 @@
+//! Key bindings
+//!
+//! Global bindings are data-driven: `Keymap` is built from the `[keybindings]`
+//! config and is consulted both by the dispatcher and by the help overlay.
+//! Mode-specific keys are listed in the `*_HELP` tables next to it.
+
+use cctakt::KeyBindings;
+use crossterm::event::{KeyCode, KeyModifiers};
+
+/// Actions reachable from any mode via a global key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlobalAction {
+    Quit,
+    ThemePicker,
+    IssuePicker,
+    CloseAgent,
+    NextTab,
+    PrevTab,
+    RestartConductor,
+    LogViewer,
+    Help,
+}
+
+impl GlobalAction {
+    /// Description shown in the help overlay
+    pub fn description(self) -> &'static str {
+        match self {
+            GlobalAction::Quit => "Quit (confirms if work is in flight)",
+            GlobalAction::ThemePicker => "Open theme picker",
+            GlobalAction::IssuePicker => "Open issue picker",
+            GlobalAction::CloseAgent => "Close active agent",
+            GlobalAction::NextTab => "Next tab",
+            GlobalAction::PrevTab => "Previous tab",
+            GlobalAction::RestartConductor => "Restart conductor",
+            GlobalAction::LogViewer => "Open debug log viewer",
+            GlobalAction::Help => "Show this help",
+        }
+    }
+}
+
+/// A key with modifiers, e.g. `ctrl+q`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    pub modifiers: KeyModifiers,
+    pub code: KeyCode,
+}
+
+impl KeyChord {
+    pub const fn new(modifiers: KeyModifiers, code: KeyCode) -> Self {
+        Self { modifiers, code }
+    }
+
+    /// Parse a binding like "ctrl+q", "alt+x", "shift+tab", "f2" or "?"
+    pub fn parse(spec: &str) -> Option<Self> {
+        let spec = spec.trim().to_ascii_lowercase();
+        let (mods, key) = match spec.rsplit_once('+') {
+            // "ctrl++" binds the plus key
+            Some((mods, "")) => (mods.strip_suffix('+').unwrap_or(mods), "+"),
+            Some((mods, key)) => (mods, key),
+            None => ("", spec.as_str()),
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for m in mods.split('+').filter(|m| !m.is_empty()) {
+            modifiers |= match m {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return None,
+            };
+        }
+
+        let code = match key {
+            "tab" if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "space" => KeyCode::Char(' '),
+            "backspace" => KeyCode::Backspace,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            _ if key.len() > 1 && key.starts_with('f') => KeyCode::F(key[1..].parse().ok()?),
+            _ => {
+                let mut chars = key.chars();
+                let c = chars.next()?;
+                if chars.next().is_some() {
+                    return None;
+                }
+                KeyCode::Char(c)
+            }
+        };
+
+        Some(Self { modifiers, code })
+    }
+
+    /// Whether a key event matches this chord
+    ///
+    /// Letters match case-insensitively. Chords without modifiers match
+    /// function and special keys regardless of modifiers, and characters
+    /// only when typed without Ctrl/Alt.
+    pub fn matches(&self, modifiers: KeyModifiers, code: KeyCode) -> bool {
+        let code_matches = match (self.code, code) {
+            (KeyCode::Char(a), KeyCode::Char(b)) => a.eq_ignore_ascii_case(&b),
+            (a, b) => a == b,
+        };
+        if !code_matches {
+            return false;
+        }
+
+        if self.modifiers.is_empty() {
+            match code {
+                KeyCode::Char(_) => !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT),
+                _ => true,
+            }
+        } else {
+            modifiers == self.modifiers
+        }
+    }
+
+    /// Display label, e.g. "Ctrl+Q", "F2", "Shift+Tab"
+    pub fn label(&self) -> String {
+        let mut parts = Vec::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            parts.push("Ctrl".to_string());
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            parts.push("Alt".to_string());
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            parts.push("Shift".to_string());
+        }
+        parts.push(match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) if !self.modifiers.is_empty() => c.to_ascii_uppercase().to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::F(n) => format!("F{n}"),
+            KeyCode::BackTab => "Shift+Tab".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::Enter => "Enter".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            other => format!("{other:?}"),
+        });
+        parts.join("+")
+    }
+}
+
+/// Global key bindings, with user overrides applied
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    global: Vec<(Vec<KeyChord>, GlobalAction)>,
+}
+
+impl Keymap {
+    /// Build the keymap, taking quit/close/tab bindings from config
+    ///
+    /// A config value that can't be parsed, or that would steal a key meant
+    /// for the agent (no Ctrl/Alt and not a function key), keeps the default.
+    pub fn from_config(bindings: &KeyBindings) -> Self {
+        let ctrl = |c| KeyChord::new(KeyModifiers::CONTROL, KeyCode::Char(c));
+        let key = |code| KeyChord::new(KeyModifiers::NONE, code);
+        let configured = |spec: &str, default: KeyChord| {
+            let chord = KeyChord::parse(spec).filter(|c| {
+                c.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+                    || matches!(c.code, KeyCode::F(_))
+            });
+            vec![chord.unwrap_or(default)]
+        };
+
+        Self {
+            global: vec![
+                (configured(&bindings.quit, ctrl('q')), GlobalAction::Quit),
+                (vec![ctrl('t')], GlobalAction::ThemePicker),
+                (vec![ctrl('i'), key(KeyCode::F(2))], GlobalAction::IssuePicker),
+                (configured(&bindings.close_agent, ctrl('w')), GlobalAction::CloseAgent),
+                (configured(&bindings.next_tab, ctrl('n')), GlobalAction::NextTab),
+                (configured(&bindings.prev_tab, ctrl('p')), GlobalAction::PrevTab),
+                (vec![ctrl('r')], GlobalAction::RestartConductor),
+                (vec![key(KeyCode::F(12))], GlobalAction::LogViewer),
+                (vec![key(KeyCode::F(1))], GlobalAction::Help),
+            ],
+        }
+    }
+
+    /// Global action bound to a key event, if any
+    pub fn lookup(&self, modifiers: KeyModifiers, code: KeyCode) -> Option<GlobalAction> {
+        self.global
+            .iter()
+            .find(|(chords, _)| chords.iter().any(|c| c.matches(modifiers, code)))
+            .map(|(_, action)| *action)
+    }
+
+    /// Help overlay sections: global bindings from this keymap, then per-mode tables
+    pub fn help_sections(&self) -> Vec<(&'static str, Vec<(String, &'static str)>)> {
+        let mut global: Vec<(String, &'static str)> = self
+            .global
+            .iter()
+            .map(|(chords, action)| {
+                let keys: Vec<String> = chords.iter().map(KeyChord::label).collect();
+                (keys.join(" / "), action.description())
+            })
+            .collect();
+        global.push(("Ctrl+1-9 / Alt+1-9".to_string(), "Switch tab by number"));
+
+        let table = |rows: &[(&str, &'static str)]| {
+            rows.iter()
+                .map(|(k, d)| (k.to_string(), *d))
+                .collect::<Vec<_>>()
+        };
+
+        vec![
+            ("Global", global),
+            ("Navigation", table(NAVIGATION_HELP)),
+            ("Input", table(INPUT_HELP)),
+            ("Review", table(REVIEW_HELP)),
+            ("Issue Picker", table(ISSUE_PICKER_HELP)),
+            ("Theme Picker", table(THEME_PICKER_HELP)),
+        ]
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_config(&KeyBindings::default())
+    }
+}
+
+/// Navigation mode keys (see `handle_navigation_mode`)
+const NAVIGATION_HELP: &[(&str, &str)] = &[
+    ("h / l", "Focus left (conductor) / right (workers) pane"),
+    ("j / k", "Next / previous worker (right pane)"),
+    ("g", "Toggle the worker grid (hjkl move between cells)"),
+    ("i / Enter", "Switch to input mode"),
+    (":", "Command mode (:q, :q!, :w, :merge <branch>, :cleanup <worktree>, :attach <branch>)"),
+    ("o", "Open the focused agent's worktree in the editor"),
+    ("O", "Open the focused agent's pull request or issue in the browser"),
+    ("p", "Show the plan's tasks and status (j/k select, r retry failed, s skip)"),
+    ("R", "Review all finished workers one after another"),
+    ("!", "Jump to the first failed task, failed agent or conflicting branch"),
+    ("< / >", "Move the active tab left / right"),
+    ("- / +", "Shrink / grow the conductor pane in the split view"),
+    ("/", "Search worker output"),
+    ("s / a / e / r", "Toggle system / assistant / error / result lines"),
+    ("t", "Show / hide tool use lines"),
+    ("x", "Reset worker output filters"),
+    ("?", "Show this help"),
+];
+
+/// Input mode keys
+const INPUT_HELP: &[(&str, &str)] = &[
+    ("Esc", "Return to navigation mode"),
+    ("Any key", "Send input to agent"),
+];
+
+/// Review mode keys (see `handle_review_input`)
+pub(crate) const REVIEW_HELP: &[(&str, &str)] = &[
+    ("j / k / ↓ / ↑", "Scroll down / up"),
+    ("d / u / PgDn / PgUp", "Page down / up"),
+    ("g / G / Home / End", "Go to top / bottom"),
+    ("Tab", "Switch focus between the commit summary and the diff"),
+    ("s", "Toggle per-file summary (Enter opens file)"),
+    ("w", "Toggle word-level diff"),
+    ("#", "Toggle old / new line numbers"),
+    ("/", "Search the diff (Tab toggles case, Esc clears)"),
+    ("n / N", "Next / previous search match"),
+    ("z", "Cycle long lines: truncate / wrap / horizontal scroll"),
+    ("← / →", "Scroll diff columns (horizontal scroll mode)"),
+    ("c", "View full commit details"),
+    ("C", "Comment on the diff line at the top of the view (empty removes it)"),
+    ("b", "Change the base branch to compare against"),
+    ("v", "Toggle the diff: branch changes / net result of merging into the base"),
+    ("m", "Execute merge (or open a PR when merge_mode = \"pull_request\")"),
+    ("D", "Toggle opening the PR as a draft (merge_mode = \"pull_request\")"),
+    ("U", "Update the branch from the base (merge or rebase per update_strategy)"),
+    ("x", "Reject: send feedback and line comments to the worker and reopen it"),
+    ("p", "Post line comments as a review on the branch's PR (a / r / c: approve / request changes / comment)"),
+    ("a", "Add branch to the plan as a merge task"),
+    ("q", "Cancel review (skips to the next one when reviewing all)"),
+    ("Q", "Cancel review and stop reviewing all"),
+    ("?", "Show this help"),
+];
+
+/// Issue picker keys (see `IssuePicker::handle_key`)
+const ISSUE_PICKER_HELP: &[(&str, &str)] = &[
+    ("j / k / ↓ / ↑", "Next / previous issue"),
+    ("J / K", "Scroll issue details"),
+    ("Home / End / PgUp / PgDn", "Jump in list"),
+    ("Enter", "Start agent for issue"),
+    ("p", "Spawn as plan: one worker task per unchecked checklist item"),
+    ("Ctrl+Enter / P", "Plan the issue's worker → review → merge → cleanup"),
+    ("s", "Sort by number / recently updated / comments"),
+    ("o", "Open issue in browser"),
+    ("r", "Refresh issues"),
+    ("Esc / q", "Cancel"),
+];
+
+/// Theme picker keys (see `handle_theme_picker_input`)
+const THEME_PICKER_HELP: &[(&str, &str)] = &[
+    ("j / k / ↓ / ↑", "Next / previous theme"),
+    ("Enter", "Apply theme"),
+    ("Esc / q", "Cancel"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chords() {
+        assert_eq!(
+            KeyChord::parse("ctrl+q"),
+            Some(KeyChord::new(KeyModifiers::CONTROL, KeyCode::Char('q')))
+        );
+        assert_eq!(
+            KeyChord::parse("Shift+Tab"),
+            Some(KeyChord::new(KeyModifiers::NONE, KeyCode::BackTab))
+        );
+        assert_eq!(
+            KeyChord::parse("f2"),
+            Some(KeyChord::new(KeyModifiers::NONE, KeyCode::F(2)))
+        );
+        assert_eq!(
+            KeyChord::parse("alt+ctrl+x"),
+            Some(KeyChord::new(
+                KeyModifiers::ALT | KeyModifiers::CONTROL,
+                KeyCode::Char('x')
+            ))
+        );
+        assert_eq!(KeyChord::parse("hyper+x"), None);
+        assert_eq!(KeyChord::parse("ctrl+xy"), None);
+    }
+
+    #[test]
+    fn test_chord_matches() {
+        let ctrl_q = KeyChord::parse("ctrl+q").unwrap();
+        assert!(ctrl_q.matches(KeyModifiers::CONTROL, KeyCode::Char('q')));
+        assert!(ctrl_q.matches(KeyModifiers::CONTROL, KeyCode::Char('Q')));
+        assert!(!ctrl_q.matches(KeyModifiers::NONE, KeyCode::Char('q')));
+
+        let f12 = KeyChord::parse("f12").unwrap();
+        assert!(f12.matches(KeyModifiers::SHIFT, KeyCode::F(12)));
+
+        let question = KeyChord::parse("?").unwrap();
+        assert!(question.matches(KeyModifiers::SHIFT, KeyCode::Char('?')));
+        assert!(!question.matches(KeyModifiers::CONTROL, KeyCode::Char('?')));
+    }
+
+    #[test]
+    fn test_chord_label() {
+        assert_eq!(KeyChord::parse("ctrl+q").unwrap().label(), "Ctrl+Q");
+        assert_eq!(KeyChord::parse("shift+tab").unwrap().label(), "Shift+Tab");
+        assert_eq!(KeyChord::parse("f2").unwrap().label(), "F2");
+    }
+
+    #[test]
+    fn test_keymap_defaults() {
+        let keymap = Keymap::default();
+        assert_eq!(
+            keymap.lookup(KeyModifiers::CONTROL, KeyCode::Char('q')),
+            Some(GlobalAction::Quit)
+        );
+        assert_eq!(
+            keymap.lookup(KeyModifiers::NONE, KeyCode::F(2)),
+            Some(GlobalAction::IssuePicker)
+        );
+        assert_eq!(keymap.lookup(KeyModifiers::NONE, KeyCode::Char('q')), None);
+        assert_eq!(keymap.lookup(KeyModifiers::NONE, KeyCode::Tab), None);
+    }
+
+    #[test]
+    fn test_keymap_ignores_bindings_that_steal_agent_input() {
+        let bindings = KeyBindings {
+            next_tab: "tab".to_string(),
+            prev_tab: "shift+tab".to_string(),
+            ..KeyBindings::default()
+        };
+        let keymap = Keymap::from_config(&bindings);
+
+        assert_eq!(keymap.lookup(KeyModifiers::NONE, KeyCode::Tab), None);
+        assert_eq!(keymap.lookup(KeyModifiers::SHIFT, KeyCode::BackTab), None);
+        assert_eq!(
+            keymap.lookup(KeyModifiers::CONTROL, KeyCode::Char('n')),
+            Some(GlobalAction::NextTab)
+        );
+    }
+
+    #[test]
+    fn test_keymap_user_override_reflected_in_help() {
+        let bindings = KeyBindings {
+            quit: "ctrl+x".to_string(),
+            ..KeyBindings::default()
+        };
+        let keymap = Keymap::from_config(&bindings);
+
+        assert_eq!(
+            keymap.lookup(KeyModifiers::CONTROL, KeyCode::Char('x')),
+            Some(GlobalAction::Quit)
+        );
+        assert_eq!(keymap.lookup(KeyModifiers::CONTROL, KeyCode::Char('q')), None);
+
+        let sections = keymap.help_sections();
+        let (title, global) = &sections[0];
+        assert_eq!(*title, "Global");
+        assert!(global.contains(&("Ctrl+X".to_string(), GlobalAction::Quit.description())));
+    }
+}
@@ -0,0 +1,277 @@
+//! In-app debug log viewer
+//!
+//! Tails `.cctakt/debug.log` in an overlay. Follows new lines unless the
+//! user has scrolled up, and only ever reads the end of large files.
+
+use cctakt::debug;
+use cctakt::overlay::{centered_percent, key_hints, modal_title, render_modal};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    Frame,
+};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Bytes read from the end of the log file
+const MAX_TAIL_BYTES: u64 = 256 * 1024;
+
+/// Lines kept for scrollback
+const MAX_LINES: usize = 2000;
+
+/// Log viewer state
+pub struct LogViewer {
+    path: PathBuf,
+    lines: Vec<String>,
+    /// Lines scrolled back from the bottom (0 follows new lines)
+    scroll: usize,
+    /// File length at the last refresh (None if the file didn't exist)
+    last_len: Option<u64>,
+}
+
+impl LogViewer {
+    /// Create a viewer for the debug log file
+    pub fn new() -> Self {
+        Self::with_path(debug::log_file_path())
+    }
+
+    /// Create a viewer for a specific file
+    pub fn with_path(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            lines: Vec::new(),
+            scroll: 0,
+            last_len: None,
+        }
+    }
+
+    /// Re-read the tail of the file if it changed since the last refresh
+    pub fn refresh(&mut self) {
+        let len = std::fs::metadata(&self.path).ok().map(|m| m.len());
+        if len == self.last_len {
+            return;
+        }
+        self.last_len = len;
+
+        let old_count = self.lines.len();
+        self.lines = match len {
+            Some(_) => read_tail(&self.path, MAX_TAIL_BYTES, MAX_LINES).unwrap_or_default(),
+            None => Vec::new(),
+        };
+
+        // Keep the view still while scrolled back
+        if self.scroll > 0 {
+            let added = self.lines.len().saturating_sub(old_count);
+            self.scroll = (self.scroll + added).min(self.lines.len());
+        }
+    }
+
+    /// Forget cached contents so the next refresh re-reads the file
+    pub fn reset(&mut self) {
+        self.last_len = None;
+        self.lines.clear();
+        self.scroll = 0;
+    }
+
+    pub fn scroll_up(&mut self, amount: usize) {
+        self.scroll = (self.scroll + amount).min(self.lines.len().saturating_sub(1));
+    }
+
+    pub fn scroll_down(&mut self, amount: usize) {
+        self.scroll = self.scroll.saturating_sub(amount);
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll = self.lines.len().saturating_sub(1);
+    }
+
+    /// Jump to the end and follow new lines
+    pub fn follow(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn is_following(&self) -> bool {
+        self.scroll == 0
+    }
+
+    /// Lines visible in a viewport of `height` rows
+    fn visible(&self, height: usize) -> &[String] {
+        let end = self.lines.len().saturating_sub(self.scroll);
+        let start = end.saturating_sub(height);
+        &self.lines[start..end]
+    }
+
+    /// Render the viewer as an overlay covering most of `area`
+    pub fn render(&self, f: &mut Frame, area: Rect) {
+        let t = cctakt::theme();
+        let popup = centered_percent(area, 90, 80);
+
+        let height = popup.height.saturating_sub(2) as usize;
+        let lines: Vec<Line> = if self.last_len.is_none() {
+            vec![Line::from(Span::styled(
+                format!(
+                    " {} doesn't exist yet. Set CCTAKT_LOG or log_level to enable logging.",
+                    self.path.display()
+                ),
+                t.style_text_muted(),
+            ))]
+        } else {
+            self.visible(height)
+                .iter()
+                .map(|l| Line::from(Span::styled(l.clone(), level_style(l))))
+                .collect()
+        };
+
+        let level = match debug::level() {
+            Some(level) => format!("{level:?}"),
+            None => "off".to_string(),
+        };
+        let position = if self.is_following() {
+            "following".to_string()
+        } else {
+            format!("↑{}", self.scroll)
+        };
+        let footer = key_hints(&[
+            ("j/k", "Scroll"),
+            ("g/G", "Top/Follow"),
+            ("v", "Verbose"),
+            ("Esc", "Close"),
+        ]);
+        render_modal(
+            f,
+            popup,
+            modal_title(format!(" Debug log [{level}] {position} ")),
+            lines,
+            Some(footer),
+        );
+    }
+}
+
+impl Default for LogViewer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Read up to `max_lines` lines from the last `max_bytes` of a file
+///
+/// A line cut in half by the byte limit is dropped.
+fn read_tail(path: &Path, max_bytes: u64, max_lines: usize) -> std::io::Result<Vec<String>> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(max_bytes);
+    // Read one byte early to tell whether `start` falls on a line boundary
+    file.seek(SeekFrom::Start(start.saturating_sub(1)))?;
+
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)?;
+    let buf = if start == 0 {
+        &buf[..]
+    } else {
+        match buf.iter().position(|&b| b == b'\n') {
+            Some(newline) => &buf[newline + 1..],
+            None => &[][..],
+        }
+    };
+    let text = String::from_utf8_lossy(buf);
+
+    let lines: Vec<&str> = text.lines().collect();
+    let skip = lines.len().saturating_sub(max_lines);
+    Ok(lines[skip..].iter().map(|l| l.to_string()).collect())
+}
+
+/// Color a log line by its level tag
+fn level_style(line: &str) -> Style {
+    // Lines look like "[12:00:00.000] INFO  task: ..."
+    let level = line
+        .split_once("] ")
+        .map(|(_, rest)| rest.split_whitespace().next().unwrap_or_default())
+        .unwrap_or_default();
+    match level {
+        "WARN" => Style::default().fg(Color::Yellow),
+        "INFO" => Style::default().fg(Color::Cyan),
+        "DEBUG" => Style::default().fg(Color::Gray),
+        "TRACE" => Style::default().fg(Color::DarkGray),
+        _ => Style::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn write_lines(path: &Path, count: usize) {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        for i in 0..count {
+            writeln!(file, "[00:00:00.000] INFO  test: line {i}").unwrap();
+        }
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let temp = TempDir::new().unwrap();
+        let mut viewer = LogViewer::with_path(temp.path().join("debug.log"));
+        viewer.refresh();
+        assert!(viewer.lines.is_empty());
+        assert!(viewer.last_len.is_none());
+    }
+
+    #[test]
+    fn test_read_tail_drops_partial_first_line() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("debug.log");
+        std::fs::write(&path, "first line\nsecond\nthird\n").unwrap();
+
+        // Starts exactly at "second"
+        let lines = read_tail(&path, 13, 100).unwrap();
+        assert_eq!(lines, vec!["second", "third"]);
+
+        // Starts mid-way through "second"
+        let lines = read_tail(&path, 10, 100).unwrap();
+        assert_eq!(lines, vec!["third"]);
+
+        let lines = read_tail(&path, 1000, 1).unwrap();
+        assert_eq!(lines, vec!["third"]);
+    }
+
+    #[test]
+    fn test_follow_and_scroll_back() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("debug.log");
+        write_lines(&path, 10);
+
+        let mut viewer = LogViewer::with_path(&path);
+        viewer.refresh();
+        assert!(viewer.is_following());
+        assert_eq!(viewer.visible(3).last().unwrap(), "[00:00:00.000] INFO  test: line 9");
+
+        // Scrolled back, new lines don't move the view
+        viewer.scroll_up(2);
+        let before = viewer.visible(3).to_vec();
+        write_lines(&path, 5);
+        viewer.refresh();
+        assert_eq!(viewer.visible(3), before.as_slice());
+
+        // Following again shows the newest line
+        viewer.follow();
+        assert_eq!(viewer.lines.len(), 15);
+        assert!(viewer.visible(3).last().unwrap().ends_with("line 4"));
+    }
+
+    #[test]
+    fn test_level_style() {
+        assert_eq!(
+            level_style("[00:00:00.000] WARN  x: y").fg,
+            Some(Color::Yellow)
+        );
+        assert_eq!(level_style("no level here").fg, None);
+    }
+}
@@ -1,7 +1,16 @@
 //! TUI module - rendering and input handling
 
 pub mod input;
+pub mod keymap;
+pub mod log_viewer;
+pub mod output;
 pub mod render;
 
-pub use input::{handle_command_mode, handle_keybinding, handle_navigation_mode, handle_theme_picker_input};
+pub use input::{
+    handle_command_mode, handle_help_input, handle_keybinding, handle_log_viewer_input, handle_mouse, handle_navigation_mode,
+    handle_plan_view_input,
+    handle_review_base_input, handle_review_comment_input, handle_review_commits_input, handle_review_input,
+    handle_review_post_input, handle_review_reject_input, handle_review_search_input,
+    handle_output_search_input, handle_quit_confirm, handle_theme_picker_input,
+};
 pub use render::ui;
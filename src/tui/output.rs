@@ -0,0 +1,676 @@
+//! Worker output parsing and filtering for the non-interactive view
+
+use cctakt::truncate_to_width;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+/// Kind of a formatted worker output line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputKind {
+    /// System events (init, model info)
+    System,
+    /// Assistant text
+    Assistant,
+    /// Failed tool results and error results
+    Error,
+    /// Final result
+    Result,
+    /// Tool use by the assistant (hidden unless enabled)
+    Tool,
+    /// Non-JSON output
+    Raw,
+}
+
+/// A formatted output line tagged with its event kind
+#[derive(Debug, Clone)]
+pub struct OutputLine {
+    pub kind: OutputKind,
+    pub line: Line<'static>,
+}
+
+/// Filter state for the worker output view
+#[derive(Debug, Clone)]
+pub struct OutputFilter {
+    /// Show system events
+    pub show_system: bool,
+    /// Show assistant text
+    pub show_assistant: bool,
+    /// Show errors and failed tool results
+    pub show_errors: bool,
+    /// Show final results
+    pub show_result: bool,
+    /// Show non-JSON output
+    pub show_raw: bool,
+    /// Show tool use lines (opt-in)
+    pub show_tools: bool,
+    /// Lines scrolled back from the bottom (0 follows new output)
+    pub scroll: usize,
+    /// Search query (empty = no search)
+    pub search: String,
+    /// Whether the search query is being edited
+    pub editing_search: bool,
+}
+
+impl Default for OutputFilter {
+    fn default() -> Self {
+        Self {
+            show_system: true,
+            show_assistant: true,
+            show_errors: true,
+            show_result: true,
+            show_raw: true,
+            show_tools: false,
+            scroll: 0,
+            search: String::new(),
+            editing_search: false,
+        }
+    }
+}
+
+impl OutputFilter {
+    /// Create a filter with tool use lines shown or hidden
+    pub fn with_tools(show_tools: bool) -> Self {
+        Self {
+            show_tools,
+            ..Self::default()
+        }
+    }
+
+    /// Check if lines of the given kind are visible
+    pub fn shows(&self, kind: OutputKind) -> bool {
+        match kind {
+            OutputKind::System => self.show_system,
+            OutputKind::Assistant => self.show_assistant,
+            OutputKind::Error => self.show_errors,
+            OutputKind::Result => self.show_result,
+            OutputKind::Tool => self.show_tools,
+            OutputKind::Raw => self.show_raw,
+        }
+    }
+
+    /// Toggle visibility of a kind
+    pub fn toggle(&mut self, kind: OutputKind) {
+        let flag = match kind {
+            OutputKind::System => &mut self.show_system,
+            OutputKind::Assistant => &mut self.show_assistant,
+            OutputKind::Error => &mut self.show_errors,
+            OutputKind::Result => &mut self.show_result,
+            OutputKind::Tool => &mut self.show_tools,
+            OutputKind::Raw => &mut self.show_raw,
+        };
+        *flag = !*flag;
+    }
+
+    /// Whether any filtering or search is active (tool visibility is a display mode, not a filter)
+    pub fn is_active(&self) -> bool {
+        let shows_all = self.show_system
+            && self.show_assistant
+            && self.show_errors
+            && self.show_result
+            && self.show_raw;
+        !shows_all || !self.search.is_empty()
+    }
+
+    /// Short description for the view title, e.g. "AI,ERR /build"
+    pub fn summary(&self) -> String {
+        let kinds: Vec<&str> = [
+            (self.show_system, "SYS"),
+            (self.show_assistant, "AI"),
+            (self.show_errors, "ERR"),
+            (self.show_result, "DONE"),
+            (self.show_raw, "RAW"),
+        ]
+        .iter()
+        .filter(|(shown, _)| *shown)
+        .map(|(_, name)| *name)
+        .collect();
+
+        let mut summary = if kinds.len() == 5 {
+            "all".to_string()
+        } else if kinds.is_empty() {
+            "none".to_string()
+        } else {
+            kinds.join(",")
+        };
+        if !self.search.is_empty() {
+            summary.push_str(&format!(" /{}", self.search));
+        }
+        summary
+    }
+
+    /// The last `height` filtered lines, ending `scroll` lines back,
+    /// with search matches highlighted
+    ///
+    /// Walks back from the newest line and stops once the viewport is
+    /// filled, so the work follows the viewport and scroll position rather
+    /// than the size of the output. Returns the lines oldest first, and the
+    /// scroll actually applied (less than `scroll` near the top).
+    pub fn tail(&self, lines: &[OutputLine], height: usize) -> (Vec<Line<'static>>, usize) {
+        let query = self.search.to_lowercase();
+        let newest_first: Vec<&OutputLine> = lines
+            .iter()
+            .rev()
+            .filter(|l| self.matches(l, &query))
+            .take(self.scroll.saturating_add(height))
+            .collect();
+        let scroll = self.scroll.min(newest_first.len().saturating_sub(height));
+        let visible = newest_first[scroll..]
+            .iter()
+            .take(height)
+            .rev()
+            .map(|l| highlight(l.line.clone(), &query))
+            .collect();
+        (visible, scroll)
+    }
+
+    /// Whether a line passes the kind filter and the (lowercased) search query
+    fn matches(&self, line: &OutputLine, query: &str) -> bool {
+        self.shows(line.kind)
+            && (query.is_empty() || line_text(&line.line).to_lowercase().contains(query))
+    }
+}
+
+/// Worker output parsed so far
+///
+/// Parsing the whole output every frame costs O(output) per frame while a
+/// worker streams; the cache parses each complete line once as it arrives.
+/// Offsets are positions in everything the worker has written, matching
+/// `Agent::output_since`.
+#[derive(Debug, Default)]
+pub struct OutputCache {
+    /// Offset parsed up to, always just after a line break
+    consumed: usize,
+    lines: Vec<OutputLine>,
+    /// Offset of the raw line each entry of `lines` came from
+    starts: Vec<usize>,
+    /// Leading entries whose raw output was discarded, removed in batches
+    stale: usize,
+}
+
+impl OutputCache {
+    /// Offset parsed up to
+    pub fn consumed(&self) -> usize {
+        self.consumed
+    }
+
+    /// Parse `new_output`, which starts at offset `start`
+    ///
+    /// `start` is past `consumed()` when output was discarded before it
+    /// could be read. A trailing partial line is left for the next call.
+    pub fn extend(&mut self, start: usize, new_output: &str) {
+        let mut offset = start.max(self.consumed);
+        for line in new_output.split_inclusive('\n') {
+            if !line.ends_with('\n') {
+                break;
+            }
+            for parsed in parse_output_line(line.trim_end_matches('\n')) {
+                self.lines.push(parsed);
+                self.starts.push(offset);
+            }
+            offset += line.len();
+        }
+        self.consumed = offset;
+    }
+
+    /// Forget lines from raw output before `offset`
+    pub fn discard_before(&mut self, offset: usize) {
+        self.stale = self.starts.partition_point(|&start| start < offset);
+        // Shifting the whole list on every new line would make each refresh
+        // O(output) again, so wait until half of it is stale
+        if self.stale * 2 > self.lines.len() {
+            self.lines.drain(..self.stale);
+            self.starts.drain(..self.stale);
+            self.stale = 0;
+        }
+    }
+
+    /// Parsed lines, oldest first
+    pub fn lines(&self) -> &[OutputLine] {
+        &self.lines[self.stale..]
+    }
+}
+
+/// Parse a single output line; JSON events are formatted, other text is kept raw
+///
+/// An assistant event yields its text, if any, followed by a line per tool use.
+pub fn parse_output_line(line: &str) -> Vec<OutputLine> {
+    if let Ok(json) = serde_json::from_str::<serde_json::Value>(line) {
+        let mut lines: Vec<OutputLine> = format_json_event(&json).into_iter().collect();
+        lines.extend(format_tool_uses(&json));
+        lines
+    } else if !line.trim().is_empty() {
+        vec![OutputLine {
+            kind: OutputKind::Raw,
+            line: Line::from(Span::raw(line.to_string())),
+        }]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Format the tool_use blocks of an assistant event, one compact line each
+fn format_tool_uses(json: &serde_json::Value) -> Vec<OutputLine> {
+    if json.get("type").and_then(|t| t.as_str()) != Some("assistant") {
+        return Vec::new();
+    }
+
+    content_blocks(json)
+        .filter(|block| block.get("type").and_then(|t| t.as_str()) == Some("tool_use"))
+        .map(|block| {
+            let name = block.get("name").and_then(|n| n.as_str()).unwrap_or("unknown");
+            let input = block.get("input").unwrap_or(&serde_json::Value::Null);
+            let (tag, target) = describe_tool_use(name, input);
+            let mut spans = vec![Span::styled(
+                format!("[{tag}] "),
+                Style::default().fg(Color::Magenta),
+            )];
+            if tag == "TOOL" {
+                spans.push(Span::styled(
+                    format!("{name} "),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ));
+            }
+            spans.push(Span::raw(truncate(&target, 80)));
+            OutputLine {
+                kind: OutputKind::Tool,
+                line: Line::from(spans),
+            }
+        })
+        .collect()
+}
+
+/// Pick a display tag and the most relevant input field for a tool
+///
+/// Unknown tools get the generic `TOOL` tag and no target.
+fn describe_tool_use(name: &str, input: &serde_json::Value) -> (&'static str, String) {
+    let field = |key: &str| {
+        input
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    match name {
+        "Bash" => ("BASH", field("command")),
+        "Read" | "Write" | "Edit" | "MultiEdit" => ("TOOL", field("file_path")),
+        "NotebookEdit" => ("TOOL", field("notebook_path")),
+        "Grep" | "Glob" => ("TOOL", field("pattern")),
+        "WebFetch" => ("TOOL", field("url")),
+        "WebSearch" => ("TOOL", field("query")),
+        "Task" => ("TOOL", field("description")),
+        _ => ("TOOL", String::new()),
+    }
+}
+
+/// Format a stream-json event for display
+fn format_json_event(json: &serde_json::Value) -> Option<OutputLine> {
+    let event_type = json.get("type").and_then(|v| v.as_str()).unwrap_or("unknown");
+
+    match event_type {
+        "system" => {
+            let subtype = json.get("subtype").and_then(|v| v.as_str()).unwrap_or("");
+            Some(OutputLine {
+                kind: OutputKind::System,
+                line: Line::from(vec![
+                    Span::styled("[SYS] ", Style::default().fg(Color::Blue)),
+                    Span::raw(subtype.to_string()),
+                ]),
+            })
+        }
+        "user" => {
+            // Echo of input is not useful; only surface failed tool results
+            let error = content_blocks(json)
+                .find(|block| {
+                    block.get("type").and_then(|t| t.as_str()) == Some("tool_result")
+                        && block.get("is_error").and_then(|e| e.as_bool()) == Some(true)
+                })
+                .map(tool_result_text)?;
+
+            Some(OutputLine {
+                kind: OutputKind::Error,
+                line: Line::from(vec![
+                    Span::styled("[ERR] ", Style::default().fg(Color::Red)),
+                    Span::raw(truncate(&error, 80)),
+                ]),
+            })
+        }
+        "assistant" => {
+            // Extract only text content (skip tool_use which is not informative)
+            let text: String = content_blocks(json)
+                .filter_map(|block| {
+                    if block.get("type").and_then(|t| t.as_str()) == Some("text") {
+                        block.get("text").and_then(|t| t.as_str())
+                    } else {
+                        None // Skip tool_use, tool_result, etc.
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            // Skip if no text content (only tool calls)
+            if text.trim().is_empty() {
+                return None;
+            }
+
+            Some(OutputLine {
+                kind: OutputKind::Assistant,
+                line: Line::from(vec![
+                    Span::styled("[AI] ", Style::default().fg(Color::Cyan)),
+                    Span::raw(truncate(&text, 80)),
+                ]),
+            })
+        }
+        "result" => {
+            let subtype = json.get("subtype").and_then(|v| v.as_str()).unwrap_or("");
+            let is_error = subtype != "success"
+                || json.get("is_error").and_then(|e| e.as_bool()) == Some(true);
+            let style = if is_error {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default().fg(Color::Green)
+            };
+            Some(OutputLine {
+                kind: if is_error {
+                    OutputKind::Error
+                } else {
+                    OutputKind::Result
+                },
+                line: Line::from(vec![
+                    Span::styled("[DONE] ", style),
+                    Span::raw(subtype.to_string()),
+                ]),
+            })
+        }
+        _ => None, // Skip unknown event types
+    }
+}
+
+/// Iterate over `message.content` blocks of an event
+fn content_blocks(json: &serde_json::Value) -> impl Iterator<Item = &serde_json::Value> {
+    json.get("message")
+        .and_then(|m| m.get("content"))
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten()
+}
+
+/// Text of a tool_result block (content may be a string or a list of text blocks)
+fn tool_result_text(block: &serde_json::Value) -> String {
+    match block.get("content") {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(serde_json::Value::Array(parts)) => parts
+            .iter()
+            .filter_map(|p| p.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => "tool error".to_string(),
+    }
+}
+
+/// Flatten text to one line of at most `max` cells
+fn truncate(text: &str, max: usize) -> String {
+    truncate_to_width(&text.replace('\n', " "), max)
+}
+
+/// Plain text of a line
+fn line_text(line: &Line) -> String {
+    line.spans.iter().map(|s| s.content.as_ref()).collect()
+}
+
+/// Highlight matches of a (lowercased) search query, if any
+fn highlight(line: Line<'static>, query: &str) -> Line<'static> {
+    if query.is_empty() {
+        line
+    } else {
+        highlight_matches(line, query)
+    }
+}
+
+/// Split spans so that case-insensitive matches of `query` (already lowercased) are highlighted
+fn highlight_matches(line: Line<'static>, query: &str) -> Line<'static> {
+    let highlight = Style::default()
+        .fg(Color::Black)
+        .bg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+
+    let mut spans = Vec::new();
+    for span in line.spans {
+        let text = span.content.to_string();
+        let lower = text.to_lowercase();
+        // Lowercasing can change byte lengths for some scripts; skip highlighting then
+        if lower.len() != text.len() {
+            spans.push(span);
+            continue;
+        }
+
+        let mut pos = 0;
+        while let Some(found) = lower[pos..].find(query) {
+            let start = pos + found;
+            let end = start + query.len();
+            if start > pos {
+                spans.push(Span::styled(text[pos..start].to_string(), span.style));
+            }
+            spans.push(Span::styled(text[start..end].to_string(), span.style.patch(highlight)));
+            pos = end;
+        }
+        if pos < text.len() {
+            spans.push(Span::styled(text[pos..].to_string(), span.style));
+        }
+    }
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse raw worker output (stream-json lines) into tagged display lines
+    fn parse_output(output: &str) -> Vec<OutputLine> {
+        output.lines().flat_map(parse_output_line).collect()
+    }
+
+    /// Every line that passes the filter
+    fn apply(filter: &OutputFilter, lines: Vec<OutputLine>) -> Vec<Line<'static>> {
+        filter.tail(&lines, usize::MAX).0
+    }
+
+    const SAMPLE: &str = concat!(
+        r#"{"type":"system","subtype":"init"}"#,
+        "\n",
+        r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Running the build now"}]}}"#,
+        "\n",
+        r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"1","content":"error: build failed","is_error":true}]}}"#,
+        "\n",
+        r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"2","content":"ok"}]}}"#,
+        "\n",
+        "plain stderr line\n",
+        r#"{"type":"result","subtype":"success","session_id":"s"}"#,
+    );
+
+    #[test]
+    fn test_parse_output_tags_kinds() {
+        let kinds: Vec<OutputKind> = parse_output(SAMPLE).iter().map(|l| l.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                OutputKind::System,
+                OutputKind::Assistant,
+                OutputKind::Error,
+                OutputKind::Raw,
+                OutputKind::Result,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_error_result_is_tagged_error() {
+        let lines = parse_output_line(r#"{"type":"result","subtype":"error","session_id":"s"}"#);
+        assert_eq!(lines[0].kind, OutputKind::Error);
+    }
+
+    #[test]
+    fn test_tool_use_lines() {
+        let event = r#"{"type":"assistant","message":{"content":[
+            {"type":"tool_use","id":"1","name":"Bash","input":{"command":"cargo build"}},
+            {"type":"tool_use","id":"2","name":"Edit","input":{"file_path":"src/main.rs","old_string":"a"}},
+            {"type":"tool_use","id":"3","name":"FancyNewTool","input":{"x":1}},
+            {"type":"text","text":"Done"}
+        ]}}"#;
+
+        let lines = parse_output_line(event);
+        let texts: Vec<String> = lines.iter().map(|l| line_text(&l.line)).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "[AI] Done",
+                "[BASH] cargo build",
+                "[TOOL] Edit src/main.rs",
+                "[TOOL] FancyNewTool ",
+            ]
+        );
+        assert_eq!(lines[0].kind, OutputKind::Assistant);
+        assert_eq!(lines[1].kind, OutputKind::Tool);
+    }
+
+    #[test]
+    fn test_tool_lines_hidden_by_default() {
+        let event = r#"{"type":"assistant","message":{"content":[{"type":"tool_use","id":"1","name":"Bash","input":{"command":"ls"}}]}}"#;
+
+        let filter = OutputFilter::default();
+        assert!(apply(&filter, parse_output(event)).is_empty());
+        assert!(!filter.is_active());
+
+        let filter = OutputFilter::with_tools(true);
+        assert_eq!(apply(&filter, parse_output(event)).len(), 1);
+    }
+
+    #[test]
+    fn test_filter_by_kind() {
+        let mut filter = OutputFilter::default();
+        assert!(!filter.is_active());
+        assert_eq!(apply(&filter, parse_output(SAMPLE)).len(), 5);
+
+        filter.toggle(OutputKind::System);
+        filter.toggle(OutputKind::Raw);
+        assert!(filter.is_active());
+        assert_eq!(apply(&filter, parse_output(SAMPLE)).len(), 3);
+        assert_eq!(filter.summary(), "AI,ERR,DONE");
+    }
+
+    #[test]
+    fn test_search_filters_and_highlights() {
+        let filter = OutputFilter {
+            search: "build".to_string(),
+            ..Default::default()
+        };
+
+        let lines = apply(&filter, parse_output(SAMPLE));
+        assert_eq!(lines.len(), 2);
+        assert!(line_text(&lines[0]).contains("Running the build now"));
+
+        let highlighted: Vec<&str> = lines[0]
+            .spans
+            .iter()
+            .filter(|s| s.style.bg == Some(Color::Yellow))
+            .map(|s| s.content.as_ref())
+            .collect();
+        assert_eq!(highlighted, vec!["build"]);
+    }
+
+    #[test]
+    fn test_search_is_case_insensitive() {
+        let line = Line::from("Build BUILD build");
+        let highlighted = highlight_matches(line, "build");
+        assert_eq!(highlighted.spans.len(), 5);
+        assert_eq!(line_text(&highlighted), "Build BUILD build");
+    }
+
+    #[test]
+    fn test_tail_takes_the_viewport_and_clamps_scroll() {
+        let lines = parse_output(SAMPLE);
+        let texts = |lines: &[Line]| lines.iter().map(line_text).collect::<Vec<_>>();
+
+        let (visible, scroll) = OutputFilter::default().tail(&lines, 2);
+        assert_eq!(texts(&visible), ["plain stderr line", "[DONE] success"]);
+        assert_eq!(scroll, 0);
+
+        let filter = OutputFilter {
+            scroll: 100,
+            ..Default::default()
+        };
+        let (visible, scroll) = filter.tail(&lines, 2);
+        assert_eq!(scroll, 3);
+        assert_eq!(texts(&visible), ["[SYS] init", "[AI] Running the build now"]);
+    }
+
+    #[test]
+    fn test_output_cache_parses_complete_lines_once() {
+        let mut cache = OutputCache::default();
+        let partial = r#"{"type":"system","subtype":"init"}"#;
+        cache.extend(0, partial);
+        assert_eq!(cache.consumed(), 0);
+        assert!(cache.lines().is_empty());
+
+        let output = format!("{partial}\nraw line\n{partial}");
+        cache.extend(0, &output[cache.consumed()..]);
+        assert_eq!(cache.lines().len(), 2);
+        assert_eq!(cache.consumed(), partial.len() + "\nraw line\n".len());
+
+        let consumed = cache.consumed();
+        cache.extend(consumed, &format!("{}\n", &output[consumed..]));
+        assert_eq!(cache.lines().len(), 3);
+    }
+
+    #[test]
+    fn test_output_cache_follows_discarded_output() {
+        let mut cache = OutputCache::default();
+        cache.extend(0, "one\ntwo\nthree\n");
+        assert_eq!(cache.lines().len(), 3);
+
+        // "one\n" was discarded before this read; the rest is already parsed
+        cache.extend(4, "");
+        cache.discard_before(4);
+        let texts: Vec<String> = cache.lines().iter().map(|l| line_text(&l.line)).collect();
+        assert_eq!(texts, ["two", "three"]);
+
+        // Output discarded before it was ever read
+        cache.extend(20, "five\n");
+        cache.discard_before(20);
+        let texts: Vec<String> = cache.lines().iter().map(|l| line_text(&l.line)).collect();
+        assert_eq!(texts, ["five"]);
+        assert_eq!(cache.consumed(), 25);
+    }
+
+    #[test]
+    fn test_large_output_is_parsed_incrementally() {
+        let event = r#"{"type":"assistant","message":{"content":[{"type":"text","text":"step"}]}}"#;
+        let mut output = String::new();
+        let mut cache = OutputCache::default();
+        for chunk in 0..20 {
+            for i in 0..1_000 {
+                output.push_str(&event.replace("step", &format!("step {}", chunk * 1_000 + i)));
+                output.push('\n');
+            }
+            // Each refresh hands over only what was written since the last one
+            let new = &output[cache.consumed()..];
+            assert_eq!(new.lines().count(), 1_000);
+            cache.extend(cache.consumed(), new);
+        }
+        assert_eq!(cache.lines().len(), 20_000);
+        assert_eq!(cache.consumed(), output.len());
+
+        let (visible, _) = OutputFilter::default().tail(cache.lines(), 20);
+        assert_eq!(visible.len(), 20);
+        assert_eq!(line_text(&visible[19]), "[AI] step 19999");
+        assert_eq!(line_text(&visible[0]), "[AI] step 19980");
+    }
+
+    #[test]
+    fn test_truncate_is_char_safe() {
+        assert_eq!(truncate("日本語テキスト", 7), "日本語…");
+        assert_eq!(truncate("short", 80), "short");
+    }
+}
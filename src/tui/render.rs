@@ -1,18 +1,35 @@
 //! TUI rendering functions
 
 use crate::agent::{Agent, AgentMode, AgentStatus, WorkState};
-use crate::app::{App, AppMode, FocusedPane, InputMode, ReviewFocus};
-use cctakt::{available_themes, current_theme_id, issue_picker::centered_rect, theme};
+use crate::app::{display_path, App, AppMode, FocusedPane, InputMode, ReviewFocus, ScreenRegions};
+use crate::git_utils::{CommitKind, OnelineCommit};
+use crate::tui::output::{OutputFilter, OutputLine};
+use cctakt::messages::{self, Msg, ui_locale};
+use cctakt::{
+    adapt_color, available_themes, current_theme_id, display_width, justify_line, pad_to_width, truncate_to_width, get_theme_colors, ThemeColors, ThemeId, overlay::{centered_modal, centered_percent, key_hints, modal_title, render_modal, render_modal_scrolled}, theme, LineOverflow,
+    SplitDirection, TaskStatus,
+};
 use ratatui::{
-    layout::{Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph},
     Frame,
 };
 
+/// Smallest terminal the UI is drawn in (header, footer and a usable pane)
+const MIN_TERMINAL_WIDTH: u16 = 40;
+const MIN_TERMINAL_HEIGHT: u16 = 10;
+
 /// Main UI rendering function
 pub fn ui(f: &mut Frame, app: &mut App) {
+    let area = f.area();
+    if area.width < MIN_TERMINAL_WIDTH || area.height < MIN_TERMINAL_HEIGHT {
+        app.regions = ScreenRegions::default();
+        render_too_small(f, area);
+        return;
+    }
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -20,9 +37,13 @@ pub fn ui(f: &mut Frame, app: &mut App) {
             Constraint::Min(0),    // Main area
             Constraint::Length(2), // Footer with status and keymaps
         ])
-        .split(f.area());
+        .split(area);
 
     // Header with tabs
+    app.regions = ScreenRegions {
+        header: chunks[0],
+        ..ScreenRegions::default()
+    };
     render_header(f, app, chunks[0]);
 
     // Footer with status
@@ -35,22 +56,243 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         render_split_pane_main_area(f, app, chunks[1]);
     }
 
+    // Error flash: redraw the main area's outer border in the error color
+    if app.error_flash_active() {
+        let flash = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme().style_error());
+        f.render_widget(flash, chunks[1]);
+    }
+
     // Render overlays based on mode
     match app.mode {
         AppMode::IssuePicker => {
-            let popup_area = centered_rect(80, 70, f.area());
+            let popup_area = centered_percent(area, 80, 70);
             app.issue_picker.render(f, popup_area);
         }
         AppMode::ThemePicker => {
-            render_theme_picker(f, app, f.area());
+            render_theme_picker(f, app, area);
+        }
+        AppMode::LogViewer => {
+            app.log_viewer.refresh();
+            app.log_viewer.render(f, area);
+        }
+        AppMode::Help => {
+            render_help(f, app, area);
+        }
+        AppMode::PlanView => {
+            render_plan_view(f, app, area);
         }
         AppMode::ReviewMerge | AppMode::Normal => {}
     }
 
+    // Quit confirmation goes above everything else
+    if let Some(ref message) = app.quit_prompt {
+        render_quit_confirm(f, message, area);
+    }
+
     // Render notifications at the bottom
     if !app.notifications.is_empty() {
-        render_notifications(f, app, f.area());
+        render_notifications(f, app, area);
+    }
+}
+
+/// Ask for a bigger terminal instead of drawing a squashed UI
+fn render_too_small(f: &mut Frame, area: Rect) {
+    let t = theme();
+    let message = messages::format(
+        ui_locale(),
+        Msg::TerminalTooSmall,
+        &[
+            ("size", &format!("{}x{}", area.width, area.height)),
+            ("min", &format!("{MIN_TERMINAL_WIDTH}x{MIN_TERMINAL_HEIGHT}")),
+        ],
+    );
+    let lines = vec![Line::from(Span::styled(
+        truncate_to_width(&message, area.width as usize),
+        t.style_warning(),
+    ))];
+    let row = Rect { y: area.y + area.height / 2, height: area.height.min(1), ..area };
+    f.render_widget(Paragraph::new("").style(Style::default().bg(t.bg_surface())), area);
+    f.render_widget(Paragraph::new(lines).alignment(Alignment::Center), row);
+}
+
+/// Help section matching the mode the overlay was opened from
+fn current_help_section(app: &App) -> &'static str {
+    match app.help_return_mode {
+        AppMode::ReviewMerge => "Review",
+        AppMode::IssuePicker => "Issue Picker",
+        AppMode::ThemePicker => "Theme Picker",
+        _ => match app.input_mode {
+            InputMode::Input => "Input",
+            _ => "Navigation",
+        },
+    }
+}
+
+/// Render the key binding help overlay
+fn render_help(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let t = theme();
+    let popup_area = centered_percent(area, 70, 80);
+
+    // Current context first, then global keys, then the remaining modes
+    let current = current_help_section(app);
+    let mut sections = app.keymap.help_sections();
+    if let Some(pos) = sections.iter().position(|(title, _)| *title == current) {
+        let section = sections.remove(pos);
+        sections.insert(0, section);
     }
+
+    let key_width = sections
+        .iter()
+        .flat_map(|(_, rows)| rows.iter().map(|(keys, _)| display_width(keys)))
+        .max()
+        .unwrap_or(0);
+
+    let mut lines: Vec<Line> = Vec::new();
+    for (title, rows) in &sections {
+        let marker = if *title == current { " (current)" } else { "" };
+        lines.push(Line::from(Span::styled(
+            format!(" {title}{marker}"),
+            Style::default()
+                .fg(t.neon_cyan())
+                .add_modifier(Modifier::BOLD),
+        )));
+        for (keys, description) in rows {
+            lines.push(Line::from(vec![
+                Span::styled(format!("   {}", pad_to_width(keys, key_width)), t.style_key()),
+                Span::styled(format!("  {description}"), t.style_key_desc()),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    render_modal_scrolled(
+        f,
+        popup_area,
+        modal_title(" Key Bindings "),
+        lines,
+        Some(key_hints(&[("j/k", "Scroll"), ("Esc/?", "Close")])),
+        app.help_scroll,
+    );
+}
+
+/// Render the plan overlay: one line per task with its status, action and
+/// dependency, plus the error under failed tasks
+///
+/// The selected task is highlighted and scrolled into view.
+fn render_plan_view(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
+    let t = theme();
+    let popup_area = centered_percent(area, 80, 80);
+    f.render_widget(Clear, popup_area);
+
+    let Some(ref plan) = app.current_plan else {
+        return;
+    };
+    let status_of = |id: &str| plan.get_task(id).map(|task| task.status.clone());
+    let id_width = plan.tasks.iter().map(|task| display_width(&task.id)).max().unwrap_or(0);
+
+    app.plan_view_selected = app.plan_view_selected.min(plan.tasks.len().saturating_sub(1));
+    let mut lines: Vec<Line> = Vec::new();
+    let (mut selected_line, mut selected_end) = (0, 0);
+    for (index, task) in plan.tasks.iter().enumerate() {
+        let (icon, style) = match task.status {
+            TaskStatus::Pending => ("○", t.style_text_muted()),
+            TaskStatus::Running => ("●", t.style_info()),
+            TaskStatus::Completed => ("✓", t.style_success()),
+            TaskStatus::Failed => ("✗", t.style_error()),
+            TaskStatus::Skipped => ("-", t.style_text_muted()),
+        };
+        let mut spans = vec![
+            Span::styled(format!(" {icon} "), style),
+            Span::styled(pad_to_width(&task.id, id_width), style.add_modifier(Modifier::BOLD)),
+            Span::styled(format!("  {}", task.action.summary()), t.style_text()),
+        ];
+        if let Some(dependency) = task.depends_on() {
+            spans.push(Span::styled(format!("  ← {dependency}"), t.style_text_secondary()));
+            // Say why a pending task isn't starting
+            if task.status == TaskStatus::Pending {
+                match status_of(dependency) {
+                    Some(TaskStatus::Completed) => {}
+                    Some(TaskStatus::Failed | TaskStatus::Skipped) => spans.push(Span::styled(
+                        " (blocked: dependency did not complete)",
+                        t.style_error(),
+                    )),
+                    Some(_) => spans.push(Span::styled(" (waiting)", t.style_warning())),
+                    None => spans.push(Span::styled(" (unknown task)", t.style_error())),
+                }
+            }
+        }
+        let mut line = Line::from(spans);
+        if index == app.plan_view_selected {
+            selected_line = lines.len();
+            line = line.patch_style(t.style_selected());
+        }
+        lines.push(line);
+
+        if task.status == TaskStatus::Failed
+            && let Some(ref error) = task.error
+        {
+            let indent = " ".repeat(id_width + 5);
+            for error_line in error.lines() {
+                lines.push(Line::from(Span::styled(
+                    format!("{indent}{error_line}"),
+                    t.style_error(),
+                )));
+            }
+        }
+        if index == app.plan_view_selected {
+            selected_end = lines.len() - 1;
+        }
+    }
+
+    // Scroll just enough to keep the selected task (and its error) in view
+    let visible = popup_area.height.saturating_sub(2).max(1) as usize;
+    let mut scroll = app.plan_view_scroll as usize;
+    if selected_end >= scroll + visible {
+        scroll = selected_end + 1 - visible;
+    }
+    scroll = scroll.min(selected_line);
+    app.plan_view_scroll = scroll as u16;
+
+    let (_, _, completed, _) = plan.count_by_status();
+    let title = match plan.description {
+        Some(ref description) => format!(" Plan: {description} ({completed}/{}) ", plan.tasks.len()),
+        None => format!(" Plan ({completed}/{}) ", plan.tasks.len()),
+    };
+    let footer = key_hints(&[
+        ("j/k", "Select"),
+        ("r", "Retry failed"),
+        ("s", "Skip"),
+        ("Esc/p", "Close"),
+    ]);
+    render_modal_scrolled(f, popup_area, modal_title(title), lines, Some(footer), app.plan_view_scroll);
+}
+
+/// Render the quit confirmation dialog
+pub fn render_quit_confirm(f: &mut Frame, message: &str, area: ratatui::layout::Rect) {
+    let t = theme();
+
+    let popup_width = (display_width(message) as u16 + 4).max(30);
+    let popup_area = centered_modal(area, popup_width, 5);
+
+    let lines = vec![
+        Line::from(Span::styled(format!(" {message}"), t.style_text())),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(" y", t.style_key()),
+            Span::styled("/", t.style_key_desc()),
+            Span::styled("^Q", t.style_key()),
+            Span::styled(": Quit  ", t.style_key_desc()),
+            Span::styled("n", t.style_key()),
+            Span::styled("/", t.style_key_desc()),
+            Span::styled("Esc", t.style_key()),
+            Span::styled(": Cancel", t.style_key_desc()),
+        ]),
+    ];
+
+    let title = Span::styled(" Quit cctakt? ", t.style_warning().add_modifier(Modifier::BOLD));
+    render_modal(f, popup_area, title, lines, None);
 }
 
 /// Render notifications at the bottom of the screen
@@ -104,23 +346,8 @@ pub fn render_theme_picker(f: &mut Frame, app: &App, area: ratatui::layout::Rect
     let themes = available_themes();
     let current_theme_id_str = current_theme_id().id();
 
-    // Calculate popup size
-    let popup_width = 40u16;
-    let popup_height = (themes.len() as u16) + 6; // title + items + footer + borders
-
-    // Center the popup
-    let popup_x = area.x + (area.width.saturating_sub(popup_width)) / 2;
-    let popup_y = area.y + (area.height.saturating_sub(popup_height)) / 2;
-
-    let popup_area = ratatui::layout::Rect {
-        x: popup_x,
-        y: popup_y,
-        width: popup_width.min(area.width),
-        height: popup_height.min(area.height),
-    };
-
-    // Clear the popup area
-    f.render_widget(Clear, popup_area);
+    // Items + description + preview + blank lines + borders
+    let popup_area = centered_modal(area, 40, themes.len() as u16 + 7);
 
     // Build theme list
     let mut lines: Vec<Line> = vec![Line::from("")];
@@ -148,42 +375,85 @@ pub fn render_theme_picker(f: &mut Frame, app: &App, area: ratatui::layout::Rect
             Span::styled(suffix, Style::default().fg(t.neon_green())),
         ]));
 
-        // Show description for selected item
+        // Show description and a color preview for selected item
         if is_selected {
             lines.push(Line::from(vec![
                 Span::raw("     "),
-                Span::styled(*description, t.style_text_muted()),
+                Span::styled(
+                    messages::theme_description(ui_locale(), id).unwrap_or(description),
+                    t.style_text_muted(),
+                ),
             ]));
+            if let Ok(theme_id) = id.parse::<ThemeId>() {
+                lines.extend(theme_preview(get_theme_colors(theme_id)));
+            }
         }
     }
 
     lines.push(Line::from(""));
 
-    // Footer
-    lines.push(Line::from(vec![
-        Span::styled(" Enter", t.style_key()),
-        Span::styled(": Select  ", t.style_key_desc()),
-        Span::styled("Esc", t.style_key()),
-        Span::styled(": Cancel", t.style_key_desc()),
-    ]));
+    render_modal(
+        f,
+        popup_area,
+        modal_title(messages::text(ui_locale(), Msg::ThemePickerTitle)),
+        lines,
+        Some(key_hints(&[("Enter", "Select"), ("Esc", "Cancel")])),
+    );
+}
+
+/// Swatches of a theme's key colors and a sample line styled with it
+///
+/// The accessors adapt the colors to the terminal's color depth, so the
+/// preview shows them as they would look once applied.
+fn theme_preview(colors: &ThemeColors) -> Vec<Line<'static>> {
+    let swatches = [
+        colors.neon_pink(),
+        colors.neon_cyan(),
+        colors.neon_purple(),
+        colors.neon_green(),
+        colors.neon_yellow(),
+        colors.bg_dark(),
+        colors.bg_panel(),
+        colors.text_primary(),
+        colors.text_muted(),
+    ];
+    let mut strip = vec![Span::raw("     ")];
+    strip.extend(
+        swatches
+            .into_iter()
+            .map(|c| Span::styled("   ", Style::default().bg(c))),
+    );
 
-    let block = Block::default()
-        .title(Span::styled(
-            " テーマを選択 ",
+    let background = colors.bg_dark();
+    let on_background = |fg| Style::default().fg(fg).bg(background);
+    let sample = vec![
+        Span::raw("     "),
+        Span::styled(
+            " tab ",
             Style::default()
-                .fg(t.neon_cyan())
+                .fg(colors.tab_active_fg())
+                .bg(colors.tab_active_bg())
                 .add_modifier(Modifier::BOLD),
-        ))
-        .borders(Borders::ALL)
-        .border_style(t.style_dialog_border())
-        .style(t.style_dialog_bg());
+        ),
+        Span::styled(" text", on_background(colors.text_primary())),
+        Span::styled(" +add", on_background(colors.diff_addition())),
+        Span::styled(" -del", on_background(colors.diff_deletion())),
+        Span::styled(" muted ", on_background(colors.text_muted())),
+    ];
 
-    let paragraph = Paragraph::new(lines).block(block);
-    f.render_widget(paragraph, popup_area);
+    vec![Line::from(strip), Line::from(sample)]
 }
 
 /// Render review merge screen with split panes (summary on top, diff on bottom)
 pub fn render_review_merge(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
+    let draft = app.review_state.as_ref().is_some_and(|state| state.draft_pr);
+    let approve_label = match app.config.merge_mode {
+        cctakt::MergeMode::Local => " Merge  ",
+        cctakt::MergeMode::PullRequest if draft => " Open draft PR  ",
+        cctakt::MergeMode::PullRequest => " Open PR  ",
+    };
+    let enforce_convention = app.config.enforce_commit_convention;
+    let progress = app.review_queue.progress();
     let Some(ref mut state) = app.review_state else {
         return;
     };
@@ -219,27 +489,194 @@ pub fn render_review_merge(f: &mut Frame, app: &mut App, area: ratatui::layout::
     };
 
     // === Summary pane (top) ===
-    render_summary_pane(f, state, chunks[0], summary_border_color);
+    render_summary_pane(f, state, chunks[0], summary_border_color, enforce_convention, progress);
 
     // === Diff pane (bottom) ===
+    let diff_title = if state.diff_view.is_summary() {
+        format!(" Files: {} → {} ", state.branch, state.base)
+    } else {
+        match state.diff_view.overflow() {
+            LineOverflow::Truncate => format!(" Diff: {} → {} ", state.branch, state.base),
+            LineOverflow::Wrap => format!(" Diff: {} → {} [wrap] ", state.branch, state.base),
+            LineOverflow::Scroll => format!(
+                " Diff: {} → {} [col {}] ",
+                state.branch,
+                state.base,
+                state.diff_view.column_offset() + 1
+            ),
+        }
+    };
+    let view = &state.diff_view;
+    let diff_title = if view.search_query().is_empty() {
+        diff_title
+    } else {
+        let position = view.current_match().map_or(0, |i| i + 1);
+        format!(
+            "{diff_title}[/{} {position}/{}] ",
+            view.search_query(),
+            view.search_matches().len()
+        )
+    };
+    let diff_title = match state.comments.len() {
+        0 => diff_title,
+        1 => format!("{diff_title}[1 comment] "),
+        n => format!("{diff_title}[{n} comments] "),
+    };
     let diff_block = Block::default()
-        .title(format!(" Diff: {} → main ", state.branch))
+        .title(diff_title)
         .borders(Borders::ALL)
         .border_style(Style::default().fg(diff_border_color));
     state.diff_view.render_with_block(f, chunks[1], diff_block);
 
-    // Footer with help
+    // Search prompt replaces the footer while typing
+    if let Some(ref query) = state.search_input {
+        let case = if state.diff_view.search_case_sensitive() {
+            " Case-sensitive  "
+        } else {
+            " Ignore case  "
+        };
+        let prompt = Paragraph::new(Line::from(vec![
+            Span::styled(format!("/{query}"), t.style_text()),
+            Span::styled("█  ", t.style_key()),
+            Span::styled("[Tab]", t.style_key()),
+            Span::styled(case, t.style_text_muted()),
+            Span::styled("[Enter]", t.style_key()),
+            Span::styled(" Search  ", t.style_text_muted()),
+            Span::styled("[Esc]", t.style_key()),
+            Span::styled(" Clear", t.style_text_muted()),
+        ]));
+        f.render_widget(prompt, chunks[2]);
+    } else if let Some(number) = state.post_review_pr {
+        let prompt = Paragraph::new(Line::from(vec![
+            Span::styled(format!("Post review on PR #{number}: "), t.style_text()),
+            Span::styled("[a]", t.style_key()),
+            Span::styled(" Approve  ", t.style_text_muted()),
+            Span::styled("[r]", t.style_key()),
+            Span::styled(" Request changes  ", t.style_text_muted()),
+            Span::styled("[c]", t.style_key()),
+            Span::styled(" Comment  ", t.style_text_muted()),
+            Span::styled("[Esc]", t.style_key()),
+            Span::styled(" Cancel", t.style_text_muted()),
+        ]));
+        f.render_widget(prompt, chunks[2]);
+    } else {
+        render_review_footer(f, approve_label, chunks[2]);
+    }
+
+    if let Some(ref mut view) = state.commits_view {
+        render_review_commits(f, view, area);
+    }
+    if let Some(ref dialog) = state.base_dialog {
+        dialog.render(f, area);
+    }
+    if let Some(ref dialog) = state.reject_dialog {
+        dialog.render(f, area);
+    }
+    if let Some((_, ref dialog)) = state.comment_dialog {
+        dialog.render(f, area);
+    }
+}
+
+/// Render the key help footer of the review screen
+fn render_review_footer(f: &mut Frame, approve_label: &str, area: ratatui::layout::Rect) {
+    let t = theme();
     let footer = Paragraph::new(Line::from(vec![
         Span::styled("[i/Enter]", t.style_key()),
         Span::styled(" Focus  ", t.style_text_muted()),
         Span::styled("[j/k]", t.style_key()),
         Span::styled(" Scroll  ", t.style_text_muted()),
+        Span::styled("[s]", t.style_key()),
+        Span::styled(" Files  ", t.style_text_muted()),
+        Span::styled("[w]", t.style_key()),
+        Span::styled(" Word diff  ", t.style_text_muted()),
+        Span::styled("[/]", t.style_key()),
+        Span::styled(" Search  ", t.style_text_muted()),
+        Span::styled("[c]", t.style_key()),
+        Span::styled(" Commits  ", t.style_text_muted()),
+        Span::styled("[b]", t.style_key()),
+        Span::styled(" Base  ", t.style_text_muted()),
         Span::styled("[M]", t.style_success()),
-        Span::styled(" Merge  ", t.style_text_muted()),
-        Span::styled("[Q/C]", t.style_error()),
+        Span::styled(approve_label, t.style_text_muted()),
+        Span::styled("[C]", t.style_key()),
+        Span::styled(" Comment  ", t.style_text_muted()),
+        Span::styled("[x]", t.style_key()),
+        Span::styled(" Reject  ", t.style_text_muted()),
+        Span::styled("[a]", t.style_key()),
+        Span::styled(" Add to plan  ", t.style_text_muted()),
+        Span::styled("[q]", t.style_error()),
         Span::styled(" Cancel", t.style_text_muted()),
     ]));
-    f.render_widget(footer, chunks[2]);
+    f.render_widget(footer, area);
+}
+
+/// Render the full commit details overlay over the review screen
+fn render_review_commits(
+    f: &mut Frame,
+    view: &mut crate::app::CommitsView,
+    area: ratatui::layout::Rect,
+) {
+    let t = theme();
+    let popup_area = centered_percent(area, 80, 80);
+
+    let mut lines: Vec<Line> = Vec::new();
+    if view.commits.is_empty() {
+        lines.push(Line::from(Span::styled(
+            " No commits on this branch",
+            t.style_text_muted(),
+        )));
+    }
+    for commit in &view.commits {
+        lines.push(Line::from(vec![
+            Span::styled(" commit ", t.style_text_muted()),
+            Span::styled(&commit.hash, Style::default().fg(t.neon_yellow())),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled(" Author: ", t.style_text_muted()),
+            Span::styled(&commit.author, t.style_text()),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled(" Date:   ", t.style_text_muted()),
+            Span::styled(&commit.date, t.style_text()),
+        ]));
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("     {}", commit.subject),
+            t.style_text().add_modifier(Modifier::BOLD),
+        )));
+        if !commit.body.is_empty() {
+            lines.push(Line::from(""));
+            for body_line in commit.body.lines() {
+                lines.push(Line::from(Span::styled(
+                    format!("     {body_line}"),
+                    t.style_text_secondary(),
+                )));
+            }
+        }
+        if !commit.files.is_empty() {
+            lines.push(Line::from(""));
+            for file in &commit.files {
+                lines.push(Line::from(Span::styled(
+                    format!("   {file}"),
+                    Style::default().fg(t.neon_cyan()),
+                )));
+            }
+        }
+        lines.push(Line::from(""));
+    }
+
+    // Clamp here so G / End can scroll "to the bottom" without knowing the height
+    let visible = popup_area.height.saturating_sub(2) as usize;
+    let max_scroll = lines.len().saturating_sub(visible) as u16;
+    view.scroll = view.scroll.min(max_scroll);
+
+    render_modal_scrolled(
+        f,
+        popup_area,
+        modal_title(format!(" Commits ({}) ", view.commits.len())),
+        lines,
+        Some(key_hints(&[("j/k", "Scroll"), ("g/G", "Top/Bottom"), ("Esc", "Back to diff")])),
+        view.scroll,
+    );
 }
 
 /// Render the summary pane showing commit log and stats
@@ -248,6 +685,8 @@ fn render_summary_pane(
     state: &crate::app::types::ReviewState,
     area: ratatui::layout::Rect,
     border_color: Color,
+    enforce_convention: bool,
+    progress: Option<(usize, usize)>,
 ) {
     let t = theme();
 
@@ -255,7 +694,7 @@ fn render_summary_pane(
     let mut lines: Vec<Line> = vec![];
 
     // Title line
-    lines.push(Line::from(vec![
+    let mut title = vec![
         Span::styled(
             " Review Merge: ",
             Style::default()
@@ -264,8 +703,12 @@ fn render_summary_pane(
         ),
         Span::styled(&state.branch, Style::default().fg(t.neon_yellow())),
         Span::raw(" → "),
-        Span::styled("main", Style::default().fg(t.success())),
-    ]));
+        Span::styled(&state.base, Style::default().fg(t.success())),
+    ];
+    if let Some((position, total)) = progress {
+        title.push(Span::styled(format!("  (reviewing {position}/{total})"), t.style_text_muted()));
+    }
+    lines.push(Line::from(title));
 
     lines.push(Line::from(""));
 
@@ -283,8 +726,69 @@ fn render_summary_pane(
             format!("-{}", state.deletions),
             Style::default().fg(t.error()),
         ),
+        Span::raw(", "),
+        Span::styled(
+            format!("{} behind", state.behind_by),
+            if state.behind_by > 0 { t.style_warning() } else { t.style_text() },
+        ),
+        Span::raw(", "),
+        Span::styled(format!("{} ahead", state.ahead_by), t.style_text()),
     ]));
 
+    // A branch behind its base is a common source of conflicts
+    if state.behind_by > 0 {
+        lines.push(Line::from(vec![Span::styled(
+            format!(
+                " ↻ {} has {} commit(s) the branch doesn't; press U to update it first",
+                state.base, state.behind_by
+            ),
+            t.style_warning(),
+        )]));
+    }
+
+    // Uncommitted changes would be lost on cleanup; merging is blocked
+    if !state.uncommitted.is_empty() {
+        let error_bold = Style::default()
+            .fg(t.error())
+            .add_modifier(Modifier::BOLD);
+        lines.push(Line::from(vec![Span::styled(
+            format!(
+                " ⚠ {} UNCOMMITTED change(s) in worktree — commit before merging:",
+                state.uncommitted.len()
+            ),
+            error_bold,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            format!("   {}", state.uncommitted.join(", ")),
+            t.style_error(),
+        )]));
+    }
+
+    // Commits breaking the commit convention; merging is blocked only when enforced
+    if !state.nonconforming_commits.is_empty() {
+        let (style, suffix) = if enforce_convention {
+            (
+                Style::default().fg(t.error()).add_modifier(Modifier::BOLD),
+                " — reword before merging",
+            )
+        } else {
+            (t.style_warning(), "")
+        };
+        lines.push(Line::from(vec![Span::styled(
+            format!(
+                " ⚠ {} commit(s) don't match the commit convention{suffix}:",
+                state.nonconforming_commits.len()
+            ),
+            style,
+        )]));
+        for commit in &state.nonconforming_commits {
+            lines.push(Line::from(vec![Span::styled(
+                format!("   {commit}"),
+                style.remove_modifier(Modifier::BOLD),
+            )]));
+        }
+    }
+
     // Show conflicts warning if any
     if !state.conflicts.is_empty() {
         lines.push(Line::from(vec![
@@ -321,19 +825,22 @@ fn render_summary_pane(
                 t.style_text_secondary(),
             ))
         } else if log_line.contains(' ') {
-            // Commit hash and title
-            let parts: Vec<&str> = log_line.splitn(2, ' ').collect();
-            if parts.len() == 2 {
-                Line::from(vec![
-                    Span::styled(
-                        format!(" {} ", parts[0]),
-                        Style::default().fg(t.neon_yellow()),
-                    ),
-                    Span::styled(parts[1].to_string(), t.style_text()),
-                ])
-            } else {
-                Line::from(Span::styled(format!(" {log_line}"), t.style_text()))
+            // Commit hash, conventional type prefix and title
+            let commit = OnelineCommit::parse(log_line);
+            let mut spans = vec![Span::styled(
+                format!(" {} ", commit.hash),
+                Style::default().fg(t.neon_yellow()),
+            )];
+            if let (Some(prefix), Some(kind)) = (commit.prefix, commit.kind()) {
+                let color = match kind {
+                    CommitKind::Feature => t.success(),
+                    CommitKind::Fix => t.warning(),
+                    CommitKind::Other => t.neon_cyan(),
+                };
+                spans.push(Span::styled(format!("{prefix} "), Style::default().fg(color)));
             }
+            spans.push(Span::styled(commit.subject.to_string(), t.style_text()));
+            Line::from(spans)
         } else {
             Line::from(Span::styled(format!(" {log_line}"), t.style_text()))
         };
@@ -351,6 +858,25 @@ fn render_summary_pane(
 
 /// Render header with tabs
 pub fn render_header(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let mut spans = header_spans(app);
+    if app.config.ui.show_worktree_path
+        && let Some(dir) = app.agent_dir(app.agent_manager.active_index())
+    {
+        let home = std::env::var_os("HOME").map(std::path::PathBuf::from);
+        spans.push(Span::styled(
+            format!(" {}", display_path(&dir, home.as_deref())),
+            theme().style_text_muted(),
+        ));
+    }
+    let header = Paragraph::new(Line::from(spans));
+    f.render_widget(header, area);
+}
+
+/// Number of header spans before the first tab (logo and version)
+const HEADER_PREFIX_SPANS: usize = 2;
+
+/// Build the header spans: logo, version, then one span per agent tab
+fn header_spans(app: &App) -> Vec<Span<'static>> {
     let t = theme();
     let mut spans: Vec<Span> = vec![
         Span::styled(
@@ -386,8 +912,31 @@ pub fn render_header(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
         spans.push(Span::styled(tab_content, style));
     }
 
-    let header = Paragraph::new(Line::from(spans));
-    f.render_widget(header, area);
+    spans
+}
+
+/// Agent index of the header tab at column `x` (relative to the header's left edge)
+pub fn tab_at_column(app: &App, x: u16) -> Option<usize> {
+    let mut start = 0usize;
+    for (i, span) in header_spans(app).iter().enumerate() {
+        let end = start + span.width();
+        if (start..end).contains(&(x as usize)) {
+            return i.checked_sub(HEADER_PREFIX_SPANS);
+        }
+        start = end;
+    }
+    None
+}
+
+/// Braille spinner frames for in-progress states
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Main-loop frames per spinner step (~10 steps per second at 60fps)
+const FRAMES_PER_SPINNER_STEP: u64 = 6;
+
+/// Spinner glyph for main-loop frame `frame`
+fn spinner(frame: u64) -> &'static str {
+    SPINNER_FRAMES[(frame / FRAMES_PER_SPINNER_STEP) as usize % SPINNER_FRAMES.len()]
 }
 
 /// Render footer with agent status and key bindings
@@ -419,7 +968,11 @@ pub fn render_footer(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
             t.style_text_muted(),
         ));
         left_spans.push(Span::styled(
-            format!("Running: {running_count}"),
+            if running_count > 0 {
+                format!("{} Running: {running_count}", spinner(app.frame))
+            } else {
+                format!("Running: {running_count}")
+            },
             if running_count > 0 {
                 t.style_warning()
             } else {
@@ -479,8 +1032,11 @@ pub fn render_footer(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     // Add input mode indicator
     left_spans.push(Span::styled(" | ", t.style_text_muted()));
     let (mode_text, mode_style) = match app.input_mode {
-        InputMode::Navigation => ("NAV(i:入力 ::cmd)", t.style_warning()),
-        InputMode::Input => ("INS(Esc:移動)", t.style_success()),
+        InputMode::Navigation => (
+            messages::text(ui_locale(), Msg::ModeNavigation),
+            t.style_warning(),
+        ),
+        InputMode::Input => (messages::text(ui_locale(), Msg::ModeInput), t.style_success()),
         InputMode::Command => {
             // Show command buffer
             let cmd_display = format!(":{}▌", app.command_buffer);
@@ -519,23 +1075,26 @@ pub fn render_footer(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
         ));
         // Mark pending as unused to suppress warning
         let _ = pending;
-    }
 
-    // Calculate widths for left/right alignment (line 1: status)
-    let left_text: String = left_spans.iter().map(|s| s.content.as_ref()).collect();
-    let right_text: String = right_spans.iter().map(|s| s.content.as_ref()).collect();
-    let left_width = left_text.len();
-    let right_width = right_text.len();
-    let available_width = area.width as usize;
-
-    // Build line 1 (status) with padding
-    let mut line1_spans = left_spans;
-    let padding = available_width.saturating_sub(left_width + right_width);
-    if padding > 0 {
-        line1_spans.push(Span::raw(" ".repeat(padding)));
+        // Progress gauge (completed and skipped count as done)
+        let ratio = plan.progress_ratio();
+        let (filled, empty) = progress_bar_cells(ratio, PROGRESS_BAR_WIDTH);
+        right_spans.push(Span::styled("█".repeat(filled), plan_style));
+        right_spans.push(Span::styled("░".repeat(empty), t.style_text_muted()));
+        right_spans.push(Span::styled(
+            format!(" {:.0}% ", ratio * 100.0),
+            plan_style,
+        ));
+        if let Some(secs) = plan.estimated_remaining_secs() {
+            right_spans.push(Span::styled(
+                format!("ETA {} ", format_eta(secs)),
+                t.style_text_muted(),
+            ));
+        }
     }
-    line1_spans.extend(right_spans);
-    let line1 = Line::from(line1_spans);
+
+    // Line 1 (status): left/right aligned, never wider than the footer
+    let line1 = justify_line(left_spans, right_spans, area.width as usize);
 
     // Build line 2 (keymaps)
     let keymap_spans = vec![Span::styled(
@@ -548,42 +1107,218 @@ pub fn render_footer(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     f.render_widget(footer, area);
 }
 
-/// Render menu when no agents exist
+/// Width of the plan progress gauge in the footer
+const PROGRESS_BAR_WIDTH: usize = 10;
+
+/// Split a progress bar of `width` cells into (filled, empty) for `ratio`
+fn progress_bar_cells(ratio: f64, width: usize) -> (usize, usize) {
+    let filled = ((ratio.clamp(0.0, 1.0) * width as f64).round() as usize).min(width);
+    (filled, width - filled)
+}
+
+/// Format a rough remaining-time estimate, e.g. "~45s", "~4m", "~1h05m"
+fn format_eta(secs: u64) -> String {
+    if secs < 60 {
+        format!("~{secs}s")
+    } else if secs < 3600 {
+        format!("~{}m", secs.div_ceil(60))
+    } else {
+        format!("~{}h{:02}m", secs / 3600, (secs % 3600) / 60)
+    }
+}
+
+/// Inner (cols, rows) of a full-width agent pane in a `cols` x `rows` terminal
+///
+/// Mirrors `ui`: header (1 row) and footer (2 rows) around the main area,
+/// and the pane's border (2 columns, 2 rows).
+pub fn pane_content_size(cols: u16, rows: u16) -> (u16, u16) {
+    (cols.saturating_sub(2), rows.saturating_sub(5))
+}
+
+/// Inner (cols, rows) of the orchestrator and worker panes of `split_panes`
+/// when a full-size pane is `content_cols` x `content_rows` inside its border
+pub fn split_pane_content_sizes(
+    content_cols: u16,
+    content_rows: u16,
+    ratio: f32,
+    direction: SplitDirection,
+) -> ((u16, u16), (u16, u16)) {
+    let area = Rect::new(0, 0, content_cols.saturating_add(2), content_rows.saturating_add(2));
+    let [first, _, second] = split_panes(area, ratio, direction);
+    let inner = |r: Rect| (r.width.saturating_sub(2), r.height.saturating_sub(2));
+    (inner(first), inner(second))
+}
+
+/// Smallest pane (border included) `split_panes` keeps on each side while
+/// the area allows it
+const MIN_SPLIT_PANE: u16 = 3;
+
+/// Split the main area into the orchestrator pane, a 1-cell separator and
+/// the worker pane
+///
+/// `ratio` is the orchestrator's share of the space left after the
+/// separator. The halves are measured here rather than by `Layout` so the
+/// PTY sizes in `update_agent_sizes` match what is drawn.
+fn split_panes(area: Rect, ratio: f32, direction: SplitDirection) -> [Rect; 3] {
+    let total = match direction {
+        SplitDirection::Horizontal => area.width,
+        SplitDirection::Vertical => area.height,
+    };
+    let separator = total.min(1);
+    let available = total - separator;
+    let min = MIN_SPLIT_PANE.min(available / 2);
+    let first = ((available as f32 * ratio).round() as u16).clamp(min, available - min);
+    let second = available - first;
+
+    match direction {
+        SplitDirection::Horizontal => [
+            Rect { width: first, ..area },
+            Rect { x: area.x + first, width: separator, ..area },
+            Rect { x: area.x + first + separator, width: second, ..area },
+        ],
+        SplitDirection::Vertical => [
+            Rect { height: first, ..area },
+            Rect { y: area.y + first, height: separator, ..area },
+            Rect { y: area.y + first + separator, height: second, ..area },
+        ],
+    }
+}
+
+/// Most workers tiled at once in the worker grid
+pub const GRID_CAPACITY: usize = 4;
+
+/// Columns and rows of a grid of `count` panes
+///
+/// Rows fill first since the worker pane is usually tall and narrow: two
+/// workers stack, three or four make a 2x2 grid.
+pub fn grid_dims(count: usize) -> (usize, usize) {
+    let rows = (1..).find(|rows| rows * rows >= count).unwrap_or(1);
+    (count.div_ceil(rows).max(1), rows)
+}
+
+/// Cells of a grid of `count` panes in `area`, row by row
+///
+/// A short last row leaves its remaining cells empty so columns line up.
+pub fn grid_cells(area: Rect, count: usize) -> Vec<Rect> {
+    let (cols, rows) = grid_dims(count);
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Ratio(1, rows as u32); rows])
+        .split(area)
+        .iter()
+        .flat_map(|row| {
+            Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(vec![Constraint::Ratio(1, cols as u32); cols])
+                .split(*row)
+                .to_vec()
+        })
+        .take(count)
+        .collect()
+}
+
+/// Draw the worker pane: the active worker, or the workers in the grid
+fn render_worker_pane(f: &mut Frame, app: &App, area: Rect, focus_color: Option<Color>) {
+    let active_id = app.agent_manager.get_active_non_interactive().map(|a| a.id);
+    let grid = app.grid_workers();
+    if grid.is_empty() {
+        if let Some(worker) = app.agent_manager.get_active_non_interactive() {
+            let output = app.output_lines(worker.id);
+            render_worker(f, worker, area, focus_color, &app.output_filter, output, app.frame);
+        }
+        return;
+    }
+
+    // Only the focused cell follows the scroll position
+    let mut unfocused_filter = app.output_filter.clone();
+    unfocused_filter.scroll = 0;
+    for (index, cell) in grid.iter().zip(grid_cells(area, grid.len())) {
+        let Some(worker) = app.agent_manager.get(*index) else {
+            continue;
+        };
+        let output = app.output_lines(worker.id);
+        if Some(worker.id) == active_id {
+            render_worker(f, worker, cell, focus_color, &app.output_filter, output, app.frame);
+        } else {
+            render_worker(f, worker, cell, None, &unfocused_filter, output, app.frame);
+        }
+    }
+}
+
+/// Draw one worker, running or ended
+fn render_worker(
+    f: &mut Frame,
+    worker: &Agent,
+    area: Rect,
+    focus_color: Option<Color>,
+    filter: &OutputFilter,
+    output: &[OutputLine],
+    frame: u64,
+) {
+    if worker.status == AgentStatus::Ended {
+        render_ended_agent(f, worker, area, focus_color);
+    } else {
+        render_agent_screen(f, worker, area, focus_color, filter, output, frame);
+    }
+}
+
+/// Draw the line between the split panes
+fn render_split_separator(f: &mut Frame, area: Rect, direction: SplitDirection, color: Color) {
+    let lines: Vec<Line> = match direction {
+        SplitDirection::Horizontal => (0..area.height).map(|_| Line::from("│")).collect(),
+        SplitDirection::Vertical => vec![Line::from("─".repeat(area.width as usize))],
+    };
+    f.render_widget(Paragraph::new(lines).style(Style::default().fg(color)), area);
+}
+
 /// Render the main area with split panes for Interactive (left) and NonInteractive (right) agents
 pub fn render_split_pane_main_area(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
+    let is_review_mode = app.mode == AppMode::ReviewMerge;
+    let (ratio, direction) = (app.config.ui.split_ratio, app.config.ui.split_direction);
+
+    // Remember where each pane is drawn for mouse hit-testing
+    let has_interactive = app.agent_manager.get_interactive().is_some();
+    let has_worker = app.agent_manager.get_active_non_interactive().is_some();
+    (app.regions.left_pane, app.regions.right_pane) = match (has_interactive, has_worker) {
+        (true, _) if is_review_mode => {
+            let chunks = split_panes(area, ratio, direction);
+            (Some(chunks[0]), Some(chunks[2]))
+        }
+        (true, true) => {
+            let chunks = split_panes(area, ratio, direction);
+            (Some(chunks[0]), Some(chunks[2]))
+        }
+        (true, false) => (Some(area), None),
+        (false, _) => (None, Some(area)),
+    };
+
     let interactive = app.agent_manager.get_interactive();
     let active_worker = app.agent_manager.get_active_non_interactive();
-    let is_review_mode = app.mode == AppMode::ReviewMerge;
 
     match (interactive, active_worker, is_review_mode) {
         // ReviewMerge mode with orchestrator: show orchestrator on left, review UI on right
         (Some(orchestrator), _, true) => {
             let t = theme();
 
-            // Split horizontally: left 50% for orchestrator, 1 column for border, right 50% for review
-            let main_chunks = Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints([
-                    Constraint::Percentage(50),
-                    Constraint::Length(1), // vertical separator
-                    Constraint::Percentage(50),
-                ])
-                .split(area);
+            // Orchestrator, separator, review UI
+            let main_chunks = split_panes(area, ratio, direction);
 
             // Left pane: Interactive (orchestrator) - no focus color in review mode
             if orchestrator.status == AgentStatus::Ended {
                 render_ended_agent(f, orchestrator, main_chunks[0], None);
             } else {
-                render_agent_screen(f, orchestrator, main_chunks[0], None);
+                render_agent_screen(
+                    f,
+                    orchestrator,
+                    main_chunks[0],
+                    None,
+                    &app.output_filter,
+                    app.output_lines(orchestrator.id),
+                    app.frame,
+                );
             }
 
-            // Vertical separator
-            let separator_lines: Vec<Line> = (0..main_chunks[1].height)
-                .map(|_| Line::from("│"))
-                .collect();
-            let separator =
-                Paragraph::new(separator_lines).style(Style::default().fg(t.border_secondary()));
-            f.render_widget(separator, main_chunks[1]);
+            render_split_separator(f, main_chunks[1], direction, t.border_secondary());
 
             // Right pane: Review UI
             render_review_merge(f, app, main_chunks[2]);
@@ -593,7 +1328,7 @@ pub fn render_split_pane_main_area(f: &mut Frame, app: &mut App, area: ratatui::
             render_review_merge(f, app, area);
         }
         // Both Interactive and NonInteractive agents exist: split pane layout
-        (Some(orchestrator), Some(worker), false) => {
+        (Some(orchestrator), Some(_), false) => {
             let t = theme();
             let left_focused = app.focused_pane == FocusedPane::Left;
             let right_focused = app.focused_pane == FocusedPane::Right;
@@ -610,24 +1345,25 @@ pub fn render_split_pane_main_area(f: &mut Frame, app: &mut App, area: ratatui::
                 None
             };
 
-            // Split horizontally: left 50% for orchestrator, 1 column for border, right 50% for worker
-            let main_chunks = Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints([
-                    Constraint::Percentage(50),
-                    Constraint::Length(1), // vertical separator
-                    Constraint::Percentage(50),
-                ])
-                .split(area);
+            // Orchestrator, separator, worker
+            let main_chunks = split_panes(area, ratio, direction);
 
             // Left pane: Interactive (orchestrator)
             if orchestrator.status == AgentStatus::Ended {
                 render_ended_agent(f, orchestrator, main_chunks[0], left_focus_color);
             } else {
-                render_agent_screen(f, orchestrator, main_chunks[0], left_focus_color);
+                render_agent_screen(
+                    f,
+                    orchestrator,
+                    main_chunks[0],
+                    left_focus_color,
+                    &app.output_filter,
+                    app.output_lines(orchestrator.id),
+                    app.frame,
+                );
             }
 
-            // Vertical separator - highlight based on focus
+            // Separator - highlight based on focus
             let separator_color = if left_focused || right_focused {
                 if left_focused {
                     t.neon_cyan()
@@ -637,19 +1373,10 @@ pub fn render_split_pane_main_area(f: &mut Frame, app: &mut App, area: ratatui::
             } else {
                 t.border_secondary()
             };
-            let separator_lines: Vec<Line> = (0..main_chunks[1].height)
-                .map(|_| Line::from("│"))
-                .collect();
-            let separator =
-                Paragraph::new(separator_lines).style(Style::default().fg(separator_color));
-            f.render_widget(separator, main_chunks[1]);
-
-            // Right pane: NonInteractive (worker)
-            if worker.status == AgentStatus::Ended {
-                render_ended_agent(f, worker, main_chunks[2], right_focus_color);
-            } else {
-                render_agent_screen(f, worker, main_chunks[2], right_focus_color);
-            }
+            render_split_separator(f, main_chunks[1], direction, separator_color);
+
+            // Right pane: NonInteractive (worker or grid of workers)
+            render_worker_pane(f, app, main_chunks[2], right_focus_color);
         }
         // Only Interactive agent: full width for orchestrator (always highlighted as single pane)
         (Some(orchestrator), None, false) => {
@@ -658,18 +1385,21 @@ pub fn render_split_pane_main_area(f: &mut Frame, app: &mut App, area: ratatui::
             if orchestrator.status == AgentStatus::Ended {
                 render_ended_agent(f, orchestrator, area, focus_color);
             } else {
-                render_agent_screen(f, orchestrator, area, focus_color);
+                render_agent_screen(
+                    f,
+                    orchestrator,
+                    area,
+                    focus_color,
+                    &app.output_filter,
+                    app.output_lines(orchestrator.id),
+                    app.frame,
+                );
             }
         }
-        // Only NonInteractive agents: full width for worker (always highlighted as single pane)
-        (None, Some(worker), false) => {
+        // Only NonInteractive agents: full width for workers (always highlighted as single pane)
+        (None, Some(_), false) => {
             let t = theme();
-            let focus_color = Some(t.neon_pink());
-            if worker.status == AgentStatus::Ended {
-                render_ended_agent(f, worker, area, focus_color);
-            } else {
-                render_agent_screen(f, worker, area, focus_color);
-            }
+            render_worker_pane(f, app, area, Some(t.neon_pink()));
         }
         // No agents (shouldn't happen, but handle gracefully)
         (None, None, false) => {
@@ -678,6 +1408,7 @@ pub fn render_split_pane_main_area(f: &mut Frame, app: &mut App, area: ratatui::
     }
 }
 
+/// Render menu when no agents exist
 pub fn render_no_agent_menu(f: &mut Frame, area: ratatui::layout::Rect) {
     let t = theme();
     let menu = Paragraph::new(vec![
@@ -762,13 +1493,16 @@ pub fn render_agent_screen(
     agent: &Agent,
     area: ratatui::layout::Rect,
     focus_color: Option<Color>,
+    filter: &OutputFilter,
+    output: &[OutputLine],
+    frame: u64,
 ) {
     match agent.mode {
         AgentMode::Interactive => {
             render_agent_screen_interactive(f, agent, area, focus_color);
         }
         AgentMode::NonInteractive => {
-            render_agent_screen_non_interactive(f, agent, area, focus_color);
+            render_agent_screen_non_interactive(f, agent, area, focus_color, filter, output, frame);
         }
     }
 }
@@ -844,11 +1578,16 @@ pub fn render_agent_screen_interactive(
 
 /// Render non-interactive agent screen (JSON stream output)
 /// `focus_color`: Some(Color) to highlight border with that color, None for muted border
+/// `output`: the worker's parsed output (see `OutputCache`)
+/// `frame`: main-loop frame counter, animates the spinner while working
 pub fn render_agent_screen_non_interactive(
     f: &mut Frame,
     agent: &Agent,
     area: ratatui::layout::Rect,
     focus_color: Option<Color>,
+    filter: &OutputFilter,
+    output: &[OutputLine],
+    frame: u64,
 ) {
     let t = theme();
     let border_style = match focus_color {
@@ -856,26 +1595,9 @@ pub fn render_agent_screen_non_interactive(
         None => t.style_border_muted(),
     };
     let content_height = area.height.saturating_sub(2) as usize;
-    let output = agent.screen_text();
-
-    // Parse and filter JSON events (skip uninteresting ones)
-    let all_lines: Vec<Line> = output
-        .lines()
-        .filter_map(|line| {
-            // Parse JSON for prettier display
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(line) {
-                format_json_event(&json)
-            } else if !line.trim().is_empty() {
-                Some(Line::from(Span::raw(line.to_string())))
-            } else {
-                None
-            }
-        })
-        .collect();
 
-    // Get the last N lines to fit in the viewport
-    let start = all_lines.len().saturating_sub(content_height);
-    let visible_lines: Vec<Line> = all_lines[start..].to_vec();
+    // Only the filtered lines that fit in the viewport, minus any scrollback
+    let (visible_lines, scroll) = filter.tail(output, content_height);
 
     // Show status indicator
     let status_style = match agent.work_state {
@@ -903,11 +1625,31 @@ pub fn render_agent_screen_non_interactive(
         }
     };
 
+    let mut title = if matches!(agent.work_state, WorkState::Starting | WorkState::Working) {
+        vec![
+            Span::styled(format!(" {}", spinner(frame)), t.style_warning()),
+            Span::styled(format!(" {status_text} "), status_style),
+        ]
+    } else {
+        vec![Span::styled(format!(" {status_text} "), status_style)]
+    };
+    if scroll > 0 {
+        title.push(Span::styled(format!("[↑{scroll}] "), t.style_text_muted()));
+    }
+    if filter.editing_search {
+        title.push(Span::styled(format!("/{}_ ", filter.search), t.style_input()));
+    } else if filter.is_active() {
+        title.push(Span::styled(
+            format!("[filter: {}] ", filter.summary()),
+            t.style_warning(),
+        ));
+    }
+
     let terminal_widget = Paragraph::new(visible_lines).block(
         Block::default()
             .borders(Borders::ALL)
             .border_style(border_style)
-            .title(Span::styled(format!(" {status_text} "), status_style)),
+            .title(Line::from(title)),
     );
     f.render_widget(terminal_widget, area);
 }
@@ -919,13 +1661,13 @@ fn cell_to_style(cell: &vt100::Cell) -> Style {
     // Foreground color
     let fg = cell.fgcolor();
     if !matches!(fg, vt100::Color::Default) {
-        style = style.fg(vt100_color_to_ratatui(fg));
+        style = style.fg(adapt_color(vt100_color_to_ratatui(fg)));
     }
 
     // Background color
     let bg = cell.bgcolor();
     if !matches!(bg, vt100::Color::Default) {
-        style = style.bg(vt100_color_to_ratatui(bg));
+        style = style.bg(adapt_color(vt100_color_to_ratatui(bg)));
     }
 
     // Attributes
@@ -969,73 +1711,3 @@ fn vt100_color_to_ratatui(color: vt100::Color) -> Color {
         vt100::Color::Rgb(r, g, b) => Color::Rgb(r, g, b),
     }
 }
-
-/// Format a JSON stream event for display
-/// Returns None if the event should be skipped
-fn format_json_event(json: &serde_json::Value) -> Option<Line<'static>> {
-    let event_type = json.get("type").and_then(|v| v.as_str()).unwrap_or("unknown");
-
-    match event_type {
-        "system" => {
-            let subtype = json.get("subtype").and_then(|v| v.as_str()).unwrap_or("");
-            Some(Line::from(vec![
-                Span::styled("[SYS] ", Style::default().fg(Color::Blue)),
-                Span::raw(subtype.to_string()),
-            ]))
-        }
-        "user" => {
-            // Skip user events (echo of input, not useful to display)
-            None
-        }
-        "assistant" => {
-            // Extract only text content (skip tool_use which is not informative)
-            let text: String = json
-                .get("message")
-                .and_then(|m| m.get("content"))
-                .and_then(|c| c.as_array())
-                .map(|arr| {
-                    arr.iter()
-                        .filter_map(|block| {
-                            if block.get("type").and_then(|t| t.as_str()) == Some("text") {
-                                block.get("text").and_then(|t| t.as_str())
-                            } else {
-                                None // Skip tool_use, tool_result, etc.
-                            }
-                        })
-                        .collect::<Vec<_>>()
-                        .join(" ")
-                })
-                .unwrap_or_default();
-
-            // Skip if no text content (only tool calls)
-            if text.trim().is_empty() {
-                return None;
-            }
-
-            // Truncate long text (char-safe for UTF-8)
-            let display_text: String = if text.chars().count() > 80 {
-                format!("{}...", text.chars().take(80).collect::<String>())
-            } else {
-                text
-            };
-
-            Some(Line::from(vec![
-                Span::styled("[AI] ", Style::default().fg(Color::Cyan)),
-                Span::raw(display_text),
-            ]))
-        }
-        "result" => {
-            let subtype = json.get("subtype").and_then(|v| v.as_str()).unwrap_or("");
-            let style = if subtype == "success" {
-                Style::default().fg(Color::Green)
-            } else {
-                Style::default().fg(Color::Red)
-            };
-            Some(Line::from(vec![
-                Span::styled("[DONE] ", style),
-                Span::raw(subtype.to_string()),
-            ]))
-        }
-        _ => None, // Skip unknown event types
-    }
-}
@@ -0,0 +1,221 @@
+//! Optional check for a newer cctakt release
+//!
+//! Off unless `check_updates` is set. Asks crates.io for the newest
+//! published version at most once per `CHECK_INTERVAL_SECS`, remembering
+//! the answer in `.cctakt/update_check.json`. Any failure (offline, rate
+//! limited, unexpected response) is logged at debug level and otherwise
+//! ignored.
+
+use crate::debug;
+use crate::github::{HttpClient, UreqHttpClient};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Where the last check is remembered
+pub const CACHE_FILE: &str = ".cctakt/update_check.json";
+
+/// Seconds between network checks (a day)
+pub const CHECK_INTERVAL_SECS: u64 = 24 * 60 * 60;
+
+/// crates.io API endpoint for this crate
+const CRATES_IO_URL: &str = "https://crates.io/api/v1/crates/cctakt";
+
+/// Result of the last network check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CheckCache {
+    /// Unix timestamp of the request
+    checked_at: u64,
+    /// Newest stable version, if the check succeeded
+    latest: Option<String>,
+}
+
+/// Newest published version if it is newer than the running one
+///
+/// Runs the network request, so call it off the UI thread (see `spawn`).
+pub fn newer_version() -> Option<String> {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let result = check(
+        &UreqHttpClient::new(),
+        Path::new(CACHE_FILE),
+        env!("CARGO_PKG_VERSION"),
+        now,
+    );
+    result.unwrap_or_else(|e| {
+        debug::log_category("update", &format!("update check failed: {e:#}"));
+        None
+    })
+}
+
+/// Run `newer_version` on a background thread
+pub fn spawn() -> std::sync::mpsc::Receiver<Option<String>> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(newer_version());
+    });
+    receiver
+}
+
+/// Newest version newer than `current`, using the cache at `cache_path`
+/// when it is younger than `CHECK_INTERVAL_SECS` at Unix time `now`
+///
+/// A failed request is cached too, so being offline doesn't mean a request
+/// on every launch.
+pub fn check(
+    http: &dyn HttpClient,
+    cache_path: &Path,
+    current: &str,
+    now: u64,
+) -> Result<Option<String>> {
+    let cached = fs::read_to_string(cache_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<CheckCache>(&content).ok())
+        .filter(|cache| now.saturating_sub(cache.checked_at) < CHECK_INTERVAL_SECS);
+
+    let latest = match cached {
+        Some(cache) => cache.latest,
+        None => {
+            let fetched = fetch_latest(http);
+            let cache = CheckCache {
+                checked_at: now,
+                latest: fetched.as_ref().ok().cloned(),
+            };
+            if let Some(parent) = cache_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(cache_path, serde_json::to_string_pretty(&cache)?)
+                .with_context(|| format!("Failed to write {}", cache_path.display()))?;
+            Some(fetched?)
+        }
+    };
+
+    Ok(latest.filter(|latest| is_newer(latest, current)))
+}
+
+/// Ask crates.io for the newest stable version
+fn fetch_latest(http: &dyn HttpClient) -> Result<String> {
+    // crates.io rejects requests without an identifying User-Agent
+    let user_agent = format!(
+        "cctakt/{} ({})",
+        env!("CARGO_PKG_VERSION"),
+        env!("CARGO_PKG_REPOSITORY")
+    );
+    let response = http.get(CRATES_IO_URL, vec![("User-Agent".to_string(), user_agent)])?;
+    if response.status != 200 {
+        anyhow::bail!("crates.io returned status {}", response.status);
+    }
+    let json: serde_json::Value =
+        serde_json::from_str(&response.body).context("Failed to parse crates.io response")?;
+    json.pointer("/crate/max_stable_version")
+        .or_else(|| json.pointer("/crate/max_version"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .context("crates.io response has no version")
+}
+
+/// Parse `major.minor.patch`, ignoring a leading `v` and any pre-release
+/// or build suffix
+pub fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version
+        .trim()
+        .trim_start_matches('v')
+        .split(['-', '+'])
+        .next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    let version = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(version)
+}
+
+/// Whether `latest` is a higher version than `current` (false if either
+/// can't be parsed)
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    match (parse_version(latest), parse_version(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::{HttpResponse, MockHttpClient};
+    use tempfile::TempDir;
+
+    fn crates_io(version: &str) -> MockHttpClient {
+        let body = format!(r#"{{"crate":{{"max_version":"{version}","max_stable_version":"{version}"}}}}"#);
+        let mut http = MockHttpClient::new();
+        http.expect_get()
+            .times(1)
+            .returning(move |_, _| Ok(HttpResponse { status: 200, body: body.clone() }));
+        http
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("0.1.1"), Some((0, 1, 1)));
+        assert_eq!(parse_version("v1.2.3"), Some((1, 2, 3)));
+        assert_eq!(parse_version("1.2.3-beta.1"), Some((1, 2, 3)));
+        assert_eq!(parse_version("1.2"), None);
+        assert_eq!(parse_version("1.2.3.4"), None);
+        assert_eq!(parse_version("latest"), None);
+    }
+
+    #[test]
+    fn test_is_newer_compares_numerically() {
+        assert!(is_newer("0.1.10", "0.1.9"));
+        assert!(is_newer("1.0.0", "0.9.9"));
+        assert!(!is_newer("0.1.1", "0.1.1"));
+        assert!(!is_newer("0.1.0", "0.1.1"));
+        assert!(!is_newer("garbage", "0.1.1"));
+    }
+
+    #[test]
+    fn test_check_reports_newer_version_and_caches_it() {
+        let dir = TempDir::new().unwrap();
+        let cache = dir.path().join(".cctakt/update_check.json");
+        let now = 1_700_000_000;
+
+        let latest = check(&crates_io("0.2.0"), &cache, "0.1.1", now).unwrap();
+        assert_eq!(latest.as_deref(), Some("0.2.0"));
+
+        // Within the interval the cache answers; the mock would panic on a
+        // second request
+        let mut offline = MockHttpClient::new();
+        offline.expect_get().times(0);
+        let latest = check(&offline, &cache, "0.1.1", now + 3600).unwrap();
+        assert_eq!(latest.as_deref(), Some("0.2.0"));
+    }
+
+    #[test]
+    fn test_check_is_quiet_when_up_to_date() {
+        let dir = TempDir::new().unwrap();
+        let cache = dir.path().join("update_check.json");
+        assert_eq!(check(&crates_io("0.1.1"), &cache, "0.1.1", 1_700_000_000).unwrap(), None);
+    }
+
+    #[test]
+    fn test_failed_check_is_cached() {
+        let dir = TempDir::new().unwrap();
+        let cache = dir.path().join("update_check.json");
+        let now = 1_700_000_000;
+
+        let mut http = MockHttpClient::new();
+        http.expect_get()
+            .times(1)
+            .returning(|_, _| Err(anyhow::anyhow!("offline")));
+        assert!(check(&http, &cache, "0.1.1", now).is_err());
+
+        let mut unused = MockHttpClient::new();
+        unused.expect_get().times(0);
+        assert_eq!(check(&unused, &cache, "0.1.1", now + 2 * 3600).unwrap(), None);
+
+        // Once the interval has passed it asks again
+        let latest = check(&crates_io("0.3.0"), &cache, "0.1.1", now + CHECK_INTERVAL_SECS + 1).unwrap();
+        assert_eq!(latest.as_deref(), Some("0.3.0"));
+    }
+}
@@ -2,21 +2,77 @@
 //!
 //! Git Worktreeの作成・削除・一覧を管理する独立モジュール。
 
-use anyhow::{Context, Result};
+use crate::debug;
+use crate::template::with_unique_suffix;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+/// ブランチ↔パスの対応を記録するインデックスファイル名（git共通ディレクトリ内）
+const INDEX_FILE_NAME: &str = "cctakt-worktrees.json";
+
+/// Worktreeディレクトリの命名方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorktreeNaming {
+    /// `/` を `-` に置換したフラットなディレクトリ（cctakt/task -> cctakt-task）
+    #[default]
+    Flat,
+    /// ブランチ名どおりにネストしたディレクトリ（cctakt/task -> cctakt/task）
+    Nested,
+    /// ブランチ名の短いハッシュ（cctakt/task -> 8桁の16進数）
+    Hash,
+}
+
+impl WorktreeNaming {
+    /// ブランチ名からbase_dir配下のディレクトリ名を決定
+    pub fn dir_name(self, branch: &str) -> PathBuf {
+        match self {
+            WorktreeNaming::Flat => PathBuf::from(branch.replace('/', "-")),
+            WorktreeNaming::Nested => branch.split('/').filter(|c| !c.is_empty()).collect(),
+            WorktreeNaming::Hash => PathBuf::from(format!("{:08x}", fnv1a(branch))),
+        }
+    }
+}
+
+/// インデックスの1エントリ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexEntry {
+    /// Worktreeのパス
+    path: PathBuf,
+    /// 作成時のベースディレクトリ（削除時に空ディレクトリを片付ける上限）
+    base_dir: PathBuf,
+}
+
 /// Worktreeの情報
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct WorktreeInfo {
     pub path: PathBuf,
     pub branch: String,
+    /// チェックアウト中のコミットハッシュ
+    pub head: String,
     pub is_main: bool,
+    /// `git worktree lock` でロックされているか
+    pub locked: bool,
+}
+
+/// Worktree削除の結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoveOutcome {
+    /// Worktreeを削除した
+    Removed,
+    /// ディレクトリが既に存在しなかった（管理情報のみpruneした）
+    AlreadyGone,
+    /// 未コミットの変更があるため削除しなかった（`git status --porcelain` の各行）
+    RefusedDirty(Vec<String>),
 }
 
 /// Git Worktree Manager
 pub struct WorktreeManager {
     repo_path: PathBuf,
+    naming: WorktreeNaming,
 }
 
 impl WorktreeManager {
@@ -38,7 +94,16 @@ impl WorktreeManager {
             ));
         }
 
-        Ok(Self { repo_path })
+        Ok(Self {
+            repo_path,
+            naming: WorktreeNaming::default(),
+        })
+    }
+
+    /// Worktreeディレクトリの命名方式を指定
+    pub fn with_naming(mut self, naming: WorktreeNaming) -> Self {
+        self.naming = naming;
+        self
     }
 
     /// 現在のディレクトリからリポジトリを検出
@@ -64,38 +129,79 @@ impl WorktreeManager {
     /// - base_dir: Worktreeを作成するベースディレクトリ（例: .worktrees/）
     /// - 戻り値: 作成されたWorktreeのパス
     pub fn create(&self, branch: &str, base_dir: &Path) -> Result<PathBuf> {
-        // 1. ブランチ名をサニタイズ
-        let safe_branch = sanitize_branch_name(branch);
+        // ブランチ名をサニタイズし、ユニークな名前を確保
+        let unique_branch = self.unique_branch_name(branch)?;
 
-        // 2. ユニークなブランチ名を確保
-        let unique_branch = self.generate_unique_branch(&safe_branch)?;
+        self.add_worktree(&unique_branch, base_dir, true)
+    }
 
-        // 3. Worktreeのパスを決定（base_dirが相対パスの場合はrepo_pathからの相対）
-        let base_path = if base_dir.is_absolute() {
-            base_dir.to_path_buf()
-        } else {
-            self.repo_path.join(base_dir)
-        };
+    /// 既存ブランチをチェックアウトしたWorktreeを作成
+    /// - branch: 既存のブランチ名
+    /// - base_dir: Worktreeを作成するベースディレクトリ（例: .worktrees/）
+    /// - 戻り値: 作成されたWorktreeのパス
+    pub fn checkout(&self, branch: &str, base_dir: &Path) -> Result<PathBuf> {
+        if !self.branch_exists(branch)? {
+            return Err(anyhow::anyhow!("Branch '{branch}' does not exist"));
+        }
+        self.add_worktree(branch, base_dir, false)
+    }
+
+    /// Worktreeを使わず、メインのチェックアウトで新しいブランチを作成してチェックアウト
+    /// - ブランチ名は `create` と同じくサニタイズして一意にする
+    /// - 追跡中のファイルに未コミットの変更があれば拒否（新しいブランチに持ち込まないため）
+    /// - 未追跡のファイル（`.cctakt/plan.json` など）は無視する
+    /// - 戻り値: リポジトリのルート（エージェントの作業ディレクトリ）
+    pub fn create_in_place(&self, branch: &str) -> Result<PathBuf> {
+        let root = self.repo_root()?;
+        let dirty = Self::status_lines(&root, &["--untracked-files=no"])?;
+        if !dirty.is_empty() {
+            bail!(
+                "The main checkout has {} uncommitted change(s); commit or stash them first",
+                dirty.len()
+            );
+        }
 
-        // base_dirが存在しない場合は作成
-        if !base_path.exists() {
-            std::fs::create_dir_all(&base_path)
-                .with_context(|| format!("Failed to create directory: {}", base_path.display()))?;
+        let unique_branch = self.unique_branch_name(branch)?;
+        let output = Command::new("git")
+            .current_dir(&root)
+            .args(["checkout", "-b", &unique_branch])
+            .output()
+            .context("Failed to execute git checkout")?;
+        if !output.status.success() {
+            bail!(
+                "Failed to create branch {unique_branch}: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
         }
+        Ok(root)
+    }
 
-        let worktree_name = unique_branch.replace('/', "-");
-        let worktree_path = base_path.join(&worktree_name);
+    /// メインのチェックアウト（`git worktree add` で作ったものではない作業ツリー）か
+    pub fn is_main_worktree(&self, path: &Path) -> bool {
+        self.repo_root().is_ok_and(|root| same_path(&root, path))
+    }
 
-        // 4. git worktree add -b <branch> <path> を実行
+    /// `git worktree add` を実行してインデックスに記録
+    /// - new_branch: trueなら `-b` でブランチを新規作成
+    fn add_worktree(&self, branch: &str, base_dir: &Path, new_branch: bool) -> Result<PathBuf> {
+        // 1. Worktreeのパスを決定
+        let base_path = self.resolve_base_dir(base_dir)?;
+        let worktree_path = base_path.join(self.naming.dir_name(branch));
+        if let Some(parent) = worktree_path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+
+        // 2. git worktree add -b <branch> <path>（既存ブランチなら git worktree add <path> <branch>）
+        let path_str = worktree_path.to_str().context("Invalid path")?;
+        let args: &[&str] = if new_branch {
+            &["worktree", "add", "-b", branch, path_str]
+        } else {
+            &["worktree", "add", path_str, branch]
+        };
         let output = Command::new("git")
             .current_dir(&self.repo_path)
-            .args([
-                "worktree",
-                "add",
-                "-b",
-                &unique_branch,
-                worktree_path.to_str().context("Invalid path")?,
-            ])
+            .args(args)
             .output()
             .context("Failed to execute git worktree add")?;
 
@@ -106,20 +212,69 @@ impl WorktreeManager {
             ));
         }
 
+        // 3. ブランチ↔パスの対応を記録
+        let mut index = self.load_index();
+        index.insert(
+            branch.to_string(),
+            IndexEntry {
+                path: worktree_path.clone(),
+                base_dir: base_path,
+            },
+        );
+        self.save_index(&index)?;
+
         Ok(worktree_path)
     }
 
     /// Worktreeを削除
-    pub fn remove(&self, path: &Path) -> Result<()> {
-        // 1. git worktree remove --force <path>
+    /// - force: 未コミットの変更があっても削除する
+    /// - ディレクトリが既に無い場合は `git worktree prune` のみ実行
+    /// - ロックされている場合はロックを解除してから削除
+    pub fn remove(&self, path: &Path, force: bool) -> Result<RemoveOutcome> {
+        // 1. ディレクトリが既に無ければ管理情報だけ削除
+        if !path.exists() {
+            self.prune()?;
+            return Ok(RemoveOutcome::AlreadyGone);
+        }
+        // メインのチェックアウトは削除できない（Worktreeを使わないワーカーの作業場所）
+        if self.is_main_worktree(path) {
+            bail!("{} is the main checkout, not a worktree", path.display());
+        }
+
+        // 2. 未コミットの変更があればforce指定時以外は削除しない
+        if !force {
+            let dirty = Self::dirty_files(path)?;
+            if !dirty.is_empty() {
+                return Ok(RemoveOutcome::RefusedDirty(dirty));
+            }
+        }
+
+        let path_str = path.to_str().context("Invalid path")?;
+        // 削除後はパスを解決できないので先にインデックスを引いておく
+        let indexed = self.indexed_branch(path);
+
+        // 3. ロックされていれば解除
+        if self.is_locked(path)? {
+            let output = Command::new("git")
+                .current_dir(&self.repo_path)
+                .args(["worktree", "unlock", path_str])
+                .output()
+                .context("Failed to execute git worktree unlock")?;
+
+            if !output.status.success() {
+                return Err(anyhow::anyhow!(
+                    "Worktree {} is locked and could not be unlocked: {}",
+                    path.display(),
+                    String::from_utf8_lossy(&output.stderr)
+                ));
+            }
+        }
+
+        // 4. git worktree remove --force <path>
+        // （変更の有無は確認済み。未追跡の無視ファイルやサブモジュールがあっても削除する）
         let output = Command::new("git")
             .current_dir(&self.repo_path)
-            .args([
-                "worktree",
-                "remove",
-                "--force",
-                path.to_str().context("Invalid path")?,
-            ])
+            .args(["worktree", "remove", "--force", path_str])
             .output()
             .context("Failed to execute git worktree remove")?;
 
@@ -130,20 +285,189 @@ impl WorktreeManager {
             ));
         }
 
-        // 2. 親ディレクトリが空なら削除
-        if let Some(parent) = path.parent() {
-            if parent.exists() {
-                if let Ok(mut entries) = parent.read_dir() {
-                    if entries.next().is_none() {
-                        let _ = std::fs::remove_dir(parent);
-                    }
-                }
+        // 5. 空になった親ディレクトリを削除（ベースディレクトリまで。記録が無ければ直上のみ）
+        let (removed_path, limit) = match indexed {
+            Some((branch, entry)) => {
+                let mut index = self.load_index();
+                index.remove(&branch);
+                let _ = self.save_index(&index);
+                (entry.path, Some(entry.base_dir))
+            }
+            None => (path.to_path_buf(), path.parent().map(Path::to_path_buf)),
+        };
+        let mut dir = removed_path.parent();
+        while let (Some(parent), Some(limit)) = (dir, limit.as_deref()) {
+            if !parent.starts_with(limit) || !is_empty_dir(parent) {
+                break;
             }
+            let _ = std::fs::remove_dir(parent);
+            if parent == limit {
+                break;
+            }
+            dir = parent.parent();
+        }
+
+        Ok(RemoveOutcome::Removed)
+    }
+
+    /// ブランチのWorktreeのパス（インデックスを優先し、無ければ `git worktree list` から検索）
+    pub fn path_for_branch(&self, branch: &str) -> Result<Option<PathBuf>> {
+        if let Some(entry) = self.load_index().remove(branch)
+            && entry.path.is_dir()
+        {
+            return Ok(Some(entry.path));
+        }
+        Ok(self.find_by_branch(branch)?.map(|wt| wt.path))
+    }
+
+    /// Worktreeのパスからブランチ名を取得（インデックスを優先し、無ければ `git worktree list` から検索）
+    pub fn branch_for_path(&self, path: &Path) -> Result<Option<String>> {
+        if let Some((branch, _)) = self.indexed_branch(path) {
+            return Ok(Some(branch));
         }
+        Ok(self
+            .list()?
+            .into_iter()
+            .find(|wt| !wt.branch.is_empty() && same_path(&wt.path, path))
+            .map(|wt| wt.branch))
+    }
+
+    /// Worktreeを作成するベースディレクトリを検証し、正規化した絶対パスを返す
+    /// - 相対パスはrepo_pathからの相対、存在しなければ親ディレクトリごと作成
+    /// - `.git` 配下や別リポジトリの中（ネストしたリポジトリになる）は拒否
+    /// - 作成前に存在する最も近い祖先で検証するので、拒否したときは何も作らない
+    /// - リポジトリのルート外かどうかは `is_outside_repo` で確認できる（`../worktrees` などは正当な設定）
+    fn resolve_base_dir(&self, base_dir: &Path) -> Result<PathBuf> {
+        let base_path = if base_dir.is_absolute() {
+            base_dir.to_path_buf()
+        } else {
+            self.repo_path.join(base_dir)
+        };
 
+        // 途中にファイルがあると create_dir_all のエラーが分かりにくいので先に確認
+        if let Some(file) = base_path.ancestors().find(|p| p.exists() && !p.is_dir()) {
+            bail!(
+                "Invalid worktree directory {}: {} is not a directory",
+                base_path.display(),
+                file.display()
+            );
+        }
+        let git_dir = self.git_common_dir()?.canonicalize()?;
+        if let Some(existing) = base_path.ancestors().find(|p| p.exists())
+            && let Ok(existing) = existing.canonicalize()
+        {
+            self.check_base_dir(&base_path, &existing, &git_dir)?;
+        }
+
+        std::fs::create_dir_all(&base_path)
+            .with_context(|| format!("Failed to create worktree directory: {}", base_path.display()))?;
+        let base_path = base_path
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve worktree directory: {}", base_path.display()))?;
+        // `..` を含むパスは祖先から辿った先が変わりうるので、作成後にも確認
+        self.check_base_dir(&base_path, &base_path, &git_dir)?;
+
+        if self.is_outside_repo(&base_path) {
+            debug::log_fields(
+                debug::Level::Warn,
+                "worktree",
+                "worktree directory is outside the repository",
+                &[("dir", &base_path.display())],
+            );
+        }
+        Ok(base_path)
+    }
+
+    /// `dir`（`base_path` かその既存の祖先、正規化済み）がWorktreeの置き場所として使えるか
+    fn check_base_dir(&self, base_path: &Path, dir: &Path, git_dir: &Path) -> Result<()> {
+        if dir.starts_with(git_dir) {
+            bail!(
+                "Invalid worktree directory {}: it is inside the git directory {}",
+                base_path.display(),
+                git_dir.display()
+            );
+        }
+        // ベースディレクトリが属するリポジトリ（このリポジトリ自身やそのWorktreeなら問題なし）
+        if let Some(other) = git_output(dir, &["rev-parse", "--git-common-dir"])
+            .map(|common| dir.join(common))
+            .and_then(|common| common.canonicalize().ok())
+            .filter(|common| common != git_dir)
+        {
+            bail!(
+                "Invalid worktree directory {}: it is inside another git repository ({}), \
+                 so the worktrees would be nested in it",
+                base_path.display(),
+                other.display()
+            );
+        }
         Ok(())
     }
 
+    /// `path` がリポジトリのルートの外にあるか（判定できなければfalse）
+    pub fn is_outside_repo(&self, path: &Path) -> bool {
+        let Ok(root) = self.repo_root() else {
+            return false;
+        };
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        !path.starts_with(root)
+    }
+
+    /// git共通ディレクトリ（全Worktreeで共有される `.git`）
+    fn git_common_dir(&self) -> Result<PathBuf> {
+        let output = Command::new("git")
+            .current_dir(&self.repo_path)
+            .args(["rev-parse", "--git-common-dir"])
+            .output()
+            .context("Failed to execute git rev-parse")?;
+
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "Failed to locate git directory: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        let git_dir = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+        Ok(self.repo_path.join(git_dir))
+    }
+
+    /// インデックスファイルのパス（全Worktreeで共有されるgit共通ディレクトリ内）
+    fn index_path(&self) -> Result<PathBuf> {
+        Ok(self.git_common_dir()?.join(INDEX_FILE_NAME))
+    }
+
+    /// インデックスを読み込み（無い・壊れている場合は空）
+    fn load_index(&self) -> BTreeMap<String, IndexEntry> {
+        self.index_path()
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// インデックスを保存
+    fn save_index(&self, index: &BTreeMap<String, IndexEntry>) -> Result<()> {
+        let path = self.index_path()?;
+        let content = serde_json::to_string_pretty(index)?;
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write worktree index: {}", path.display()))
+    }
+
+    /// パスに対応するインデックスのエントリ（ブランチ名と共に）
+    fn indexed_branch(&self, path: &Path) -> Option<(String, IndexEntry)> {
+        self.load_index()
+            .into_iter()
+            .find(|(_, entry)| same_path(&entry.path, path))
+    }
+
+    /// Worktreeがロックされているかチェック
+    fn is_locked(&self, path: &Path) -> Result<bool> {
+        Ok(self
+            .list()?
+            .into_iter()
+            .any(|wt| wt.locked && same_path(&wt.path, path)))
+    }
+
     /// 全Worktreeを一覧
     pub fn list(&self) -> Result<Vec<WorktreeInfo>> {
         let output = Command::new("git")
@@ -160,33 +484,99 @@ impl WorktreeManager {
         }
 
         let stdout = String::from_utf8_lossy(&output.stdout);
-        let mut worktrees = Vec::new();
-        let mut current_path: Option<PathBuf> = None;
-        let mut current_branch: Option<String> = None;
-
-        for line in stdout.lines() {
-            if let Some(path_str) = line.strip_prefix("worktree ") {
-                current_path = Some(PathBuf::from(path_str));
-            } else if let Some(branch_str) = line.strip_prefix("branch refs/heads/") {
-                current_branch = Some(branch_str.to_string());
-            } else if line.is_empty() {
-                if let Some(path) = current_path.take() {
-                    let branch = current_branch.take().unwrap_or_default();
-                    let is_main = path == self.repo_path;
-                    worktrees.push(WorktreeInfo { path, branch, is_main });
-                }
-                current_branch = None;
-            }
+        Ok(parse_worktree_list(&stdout, &self.repo_path))
+    }
+
+    /// ブランチ名でWorktreeを検索
+    pub fn find_by_branch(&self, branch: &str) -> Result<Option<WorktreeInfo>> {
+        Ok(self.list()?.into_iter().find(|wt| wt.branch == branch))
+    }
+
+    /// Worktreeの未コミットの変更（`git status --porcelain` の各行、未追跡ファイルを含む）
+    pub fn dirty_files(path: &Path) -> Result<Vec<String>> {
+        Self::status_lines(path, &[])
+    }
+
+    /// `git status --porcelain` の各行（`extra` は追加の引数）
+    fn status_lines(path: &Path, extra: &[&str]) -> Result<Vec<String>> {
+        let output = Command::new("git")
+            .current_dir(path)
+            .args(["status", "--porcelain"])
+            .args(extra)
+            .output()
+            .context("Failed to execute git status")?;
+
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "Failed to get status of {}: {}",
+                path.display(),
+                String::from_utf8_lossy(&output.stderr)
+            ));
         }
 
-        // 最後のエントリを処理（末尾に空行がない場合）
-        if let Some(path) = current_path.take() {
-            let branch = current_branch.take().unwrap_or_default();
-            let is_main = path == self.repo_path;
-            worktrees.push(WorktreeInfo { path, branch, is_main });
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.to_string())
+            .collect())
+    }
+
+    /// Worktreeで現在チェックアウトされているブランチ（detached HEADならNone）
+    pub fn current_branch(path: &Path) -> Result<Option<String>> {
+        let output = Command::new("git")
+            .current_dir(path)
+            .args(["branch", "--show-current"])
+            .output()
+            .context("Failed to execute git branch")?;
+
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "Failed to get the branch of {}: {}",
+                path.display(),
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(Some(branch).filter(|b| !b.is_empty()))
+    }
+
+    /// Worktreeに未コミットの変更があるかチェック
+    pub fn is_dirty(path: &Path) -> Result<bool> {
+        Ok(!Self::dirty_files(path)?.is_empty())
+    }
+
+    /// 存在しなくなったWorktreeの管理情報を削除（git worktree prune）
+    /// - 戻り値: 削除された項目の説明（git の出力）
+    pub fn prune(&self) -> Result<Vec<String>> {
+        let output = Command::new("git")
+            .current_dir(&self.repo_path)
+            .args(["worktree", "prune", "--verbose"])
+            .output()
+            .context("Failed to execute git worktree prune")?;
+
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "Failed to prune worktrees: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        // 存在しないWorktreeのエントリをインデックスから削除
+        let mut index = self.load_index();
+        let before = index.len();
+        index.retain(|_, entry| entry.path.exists());
+        if index.len() != before {
+            self.save_index(&index)?;
         }
 
-        Ok(worktrees)
+        // --verbose の出力は stderr に出る
+        Ok(String::from_utf8_lossy(&output.stderr)
+            .lines()
+            .chain(String::from_utf8_lossy(&output.stdout).lines())
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.to_string())
+            .collect())
     }
 
     /// ブランチが既に存在するかチェック
@@ -200,34 +590,154 @@ impl WorktreeManager {
         Ok(output.status.success())
     }
 
-    /// ユニークなブランチ名を生成
-    /// 例: "cctakt/task" -> "cctakt/task", "cctakt/task-2", "cctakt/task-3"
-    pub fn generate_unique_branch(&self, base_name: &str) -> Result<String> {
-        if !self.branch_exists(base_name)? {
-            return Ok(base_name.to_string());
+    /// ローカルブランチを削除（`git branch -D`、マージされていなくても削除する）
+    pub fn delete_branch(&self, branch: &str) -> Result<()> {
+        let output = Command::new("git")
+            .current_dir(&self.repo_path)
+            .args(["branch", "-D", branch])
+            .output()
+            .context("Failed to execute git branch -D")?;
+
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "Failed to delete branch {branch}: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
         }
+        Ok(())
+    }
 
-        let mut counter = 2;
-        loop {
-            let candidate = format!("{base_name}-{counter}");
-            if !self.branch_exists(&candidate)? {
-                return Ok(candidate);
-            }
-            counter += 1;
+    /// ローカルブランチ名を一覧
+    pub fn list_branches(&self) -> Result<Vec<String>> {
+        let output = Command::new("git")
+            .current_dir(&self.repo_path)
+            .args(["for-each-ref", "--format=%(refname:short)", "refs/heads/"])
+            .output()
+            .context("Failed to execute git for-each-ref")?;
 
-            // 無限ループ防止
-            if counter > 1000 {
-                return Err(anyhow::anyhow!(
-                    "Failed to generate unique branch name for: {base_name}"
-                ));
-            }
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "Failed to list branches: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
         }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect())
+    }
+
+    /// `create` / `create_in_place` が実際に使うブランチ名
+    /// - サニタイズした上で、既存のブランチと重ならないよう `-2`, `-3`, ... を付ける
+    pub fn unique_branch_name(&self, branch: &str) -> Result<String> {
+        self.generate_unique_branch(&sanitize_branch_name(branch))
+    }
+
+    /// ユニークなブランチ名を生成
+    /// 例: "cctakt/task" -> "cctakt/task", "cctakt/task-2", "cctakt/task-3"
+    /// - 付け方は `template::with_unique_suffix` と同じ（Issueからのブランチ名と一致させる）
+    pub fn generate_unique_branch(&self, base_name: &str) -> Result<String> {
+        Ok(with_unique_suffix(base_name, &self.list_branches()?))
     }
 
     /// リポジトリのパスを取得
     pub fn repo_path(&self) -> &Path {
         &self.repo_path
     }
+
+    /// リポジトリのルート（作業ツリーの最上位、正規化済み）
+    ///
+    /// `repo_path` はリポジトリ内のサブディレクトリでもよいため、
+    /// Worktreeの作成先の検証などにはこちらを使う。
+    pub fn repo_root(&self) -> Result<PathBuf> {
+        let root = git_output(&self.repo_path, &["rev-parse", "--show-toplevel"])
+            .with_context(|| format!("Failed to locate the root of {}", self.repo_path.display()))?;
+        PathBuf::from(root)
+            .canonicalize()
+            .context("Failed to resolve the repository root")
+    }
+}
+
+/// `dir` で git を実行し、成功すれば標準出力（前後の空白を除く）を返す
+fn git_output(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git").current_dir(dir).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// `git worktree list --porcelain` の出力をパース
+fn parse_worktree_list(stdout: &str, repo_path: &Path) -> Vec<WorktreeInfo> {
+    let mut worktrees = Vec::new();
+    let mut current_path: Option<PathBuf> = None;
+    let mut current_branch: Option<String> = None;
+    let mut current_head: Option<String> = None;
+    let mut current_locked = false;
+
+    let mut flush = |path: Option<PathBuf>, branch: Option<String>, head: Option<String>, locked| {
+        if let Some(path) = path {
+            let is_main = path == repo_path;
+            worktrees.push(WorktreeInfo {
+                path,
+                branch: branch.unwrap_or_default(),
+                head: head.unwrap_or_default(),
+                is_main,
+                locked,
+            });
+        }
+    };
+
+    for line in stdout.lines() {
+        if let Some(path_str) = line.strip_prefix("worktree ") {
+            current_path = Some(PathBuf::from(path_str));
+        } else if let Some(head) = line.strip_prefix("HEAD ") {
+            current_head = Some(head.to_string());
+        } else if let Some(branch_str) = line.strip_prefix("branch refs/heads/") {
+            current_branch = Some(branch_str.to_string());
+        } else if line == "locked" || line.starts_with("locked ") {
+            current_locked = true;
+        } else if line.is_empty() {
+            flush(
+                current_path.take(),
+                current_branch.take(),
+                current_head.take(),
+                std::mem::take(&mut current_locked),
+            );
+        }
+    }
+
+    // 最後のエントリを処理（末尾に空行がない場合）
+    flush(
+        current_path.take(),
+        current_branch.take(),
+        current_head.take(),
+        current_locked,
+    );
+
+    worktrees
+}
+
+/// 2つのパスが同じ場所を指すか（シンボリックリンクを解決して比較）
+fn same_path(a: &Path, b: &Path) -> bool {
+    let canonical = |p: &Path| p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+    a == b || canonical(a) == canonical(b)
+}
+
+/// 空のディレクトリかどうか
+fn is_empty_dir(path: &Path) -> bool {
+    path.read_dir()
+        .map(|mut entries| entries.next().is_none())
+        .unwrap_or(false)
+}
+
+/// 32bit FNV-1a ハッシュ（Rustのバージョンに依存しない安定した値）
+fn fnv1a(s: &str) -> u32 {
+    s.bytes().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
 }
 
 /// ブランチ名をサニタイズ
@@ -332,6 +842,25 @@ mod tests {
         // 同じ名前を要求すると -2 が付く
         let branch2 = manager.generate_unique_branch("feature").unwrap();
         assert_eq!(branch2, "feature-2");
+
+        // create と同じ名前になる
+        assert_eq!(manager.unique_branch_name("feature").unwrap(), "feature-2");
+        // 作成したブランチが一覧に含まれる
+        assert!(manager.list_branches().unwrap().contains(&"feature".to_string()));
+    }
+
+    #[test]
+    fn test_delete_branch() {
+        let (temp, manager) = setup_test_repo();
+
+        let wt_path = manager.create("feature", temp.path()).unwrap();
+        // チェックアウト中のブランチは削除できない
+        assert!(manager.delete_branch("feature").is_err());
+
+        manager.remove(&wt_path, true).unwrap();
+        manager.delete_branch("feature").unwrap();
+        assert!(!manager.branch_exists("feature").unwrap());
+        assert_eq!(manager.generate_unique_branch("feature").unwrap(), "feature");
     }
 
     #[test]
@@ -356,6 +885,108 @@ mod tests {
         assert_eq!(created_wt.branch, "test-branch");
     }
 
+    #[test]
+    fn test_repo_root_from_subdirectory() {
+        let (temp, _) = setup_test_repo();
+        let sub = temp.path().join("src");
+        std::fs::create_dir(&sub).unwrap();
+
+        let manager = WorktreeManager::new(&sub).unwrap();
+        assert_eq!(manager.repo_root().unwrap(), temp.path().canonicalize().unwrap());
+    }
+
+    #[test]
+    fn test_create_resolves_relative_base_dir() {
+        let (temp, manager) = setup_test_repo();
+
+        // 相対パスはリポジトリからの相対で、親ディレクトリごと作成される
+        let wt_path = manager.create("feat", Path::new(".worktrees/deep")).unwrap();
+        let expected = temp.path().canonicalize().unwrap().join(".worktrees/deep/feat");
+        assert_eq!(wt_path, expected);
+        assert!(wt_path.exists());
+
+        assert!(!manager.is_outside_repo(&wt_path));
+
+        // リポジトリ外も作成できる（呼び出し側が警告する）
+        let outside = TempDir::new().unwrap();
+        let wt_path = manager.create("other", outside.path()).unwrap();
+        assert!(wt_path.starts_with(outside.path().canonicalize().unwrap()));
+        assert!(manager.is_outside_repo(&wt_path));
+    }
+
+    #[test]
+    fn test_create_rejects_invalid_base_dir() {
+        let (temp, manager) = setup_test_repo();
+
+        // .git 配下
+        let err = manager.create("a", &temp.path().join(".git/worktrees-here")).unwrap_err();
+        assert!(err.to_string().contains("inside the git directory"), "{err}");
+
+        // 途中にファイルがある
+        std::fs::write(temp.path().join("file"), "").unwrap();
+        let err = manager.create("b", &temp.path().join("file/sub")).unwrap_err();
+        assert!(err.to_string().contains("is not a directory"), "{err}");
+
+        // 別のリポジトリの中（ネストしたリポジトリになる）
+        let (other, _) = setup_test_repo();
+        let err = manager.create("c", &other.path().join("wt")).unwrap_err();
+        assert!(err.to_string().contains("inside another git repository"), "{err}");
+
+        // どれもWorktreeは作られていない
+        assert_eq!(manager.list().unwrap().len(), 1);
+        // 拒否したディレクトリは作成されない
+        assert!(!temp.path().join(".git/worktrees-here").exists());
+        assert!(!other.path().join("wt").exists());
+    }
+
+    #[test]
+    fn test_create_in_place() {
+        let (temp, manager) = setup_test_repo();
+
+        let root = manager.create_in_place("feat/in place").unwrap();
+        assert_eq!(root, temp.path().canonicalize().unwrap());
+        assert!(manager.is_main_worktree(temp.path()));
+        assert_eq!(
+            WorktreeManager::current_branch(temp.path()).unwrap().as_deref(),
+            Some("feat/in-place")
+        );
+        // Worktreeは作らない
+        assert_eq!(manager.list().unwrap().len(), 1);
+
+        // メインのチェックアウトは削除できない
+        let err = manager.remove(temp.path(), true).unwrap_err();
+        assert!(err.to_string().contains("is the main checkout"), "{err}");
+
+        // 未コミットの変更があれば拒否
+        let git = |args: &[&str]| {
+            Command::new("git").current_dir(temp.path()).args(args).output().unwrap();
+        };
+        std::fs::write(temp.path().join("file"), "x").unwrap();
+        git(&["add", "file"]);
+        git(&["commit", "-m", "add file", "--no-gpg-sign"]);
+        std::fs::write(temp.path().join("file"), "y").unwrap();
+        let err = manager.create_in_place("feat/other").unwrap_err();
+        assert!(err.to_string().contains("uncommitted change"), "{err}");
+        assert!(!manager.branch_exists("feat/other").unwrap());
+    }
+
+    #[test]
+    fn test_create_in_place_ignores_untracked_files() {
+        let (temp, manager) = setup_test_repo();
+
+        // オーケストレーターが書く未追跡ファイルでは拒否しない
+        std::fs::create_dir_all(temp.path().join(".cctakt")).unwrap();
+        std::fs::write(temp.path().join(".cctakt/plan.json"), "{}").unwrap();
+        std::fs::write(temp.path().join(".cctakt/debug.log"), "log").unwrap();
+
+        manager.create_in_place("feat/plan").unwrap();
+        assert_eq!(
+            WorktreeManager::current_branch(temp.path()).unwrap().as_deref(),
+            Some("feat/plan")
+        );
+        assert!(temp.path().join(".cctakt/plan.json").exists());
+    }
+
     #[test]
     fn test_create_and_remove_worktree() {
         let (temp, manager) = setup_test_repo();
@@ -366,13 +997,217 @@ mod tests {
         let list = manager.list().unwrap();
         assert_eq!(list.len(), 2);
 
-        manager.remove(&wt_path).unwrap();
+        assert_eq!(manager.remove(&wt_path, false).unwrap(), RemoveOutcome::Removed);
         assert!(!wt_path.exists());
 
         let list_after = manager.list().unwrap();
         assert_eq!(list_after.len(), 1);
     }
 
+    #[test]
+    fn test_worktree_naming_dir_name() {
+        assert_eq!(
+            WorktreeNaming::Flat.dir_name("cctakt/task"),
+            PathBuf::from("cctakt-task")
+        );
+        assert_eq!(
+            WorktreeNaming::Nested.dir_name("cctakt/task"),
+            Path::new("cctakt").join("task")
+        );
+        let hashed = WorktreeNaming::Hash.dir_name("cctakt/task");
+        assert_eq!(hashed.to_str().unwrap().len(), 8);
+        assert_eq!(hashed, WorktreeNaming::Hash.dir_name("cctakt/task"));
+        assert_ne!(hashed, WorktreeNaming::Hash.dir_name("cctakt/other"));
+    }
+
+    #[test]
+    fn test_nested_naming_records_branch() {
+        let (temp, manager) = setup_test_repo();
+        let manager = manager.with_naming(WorktreeNaming::Nested);
+        let base = temp.path().join(".worktrees");
+
+        let wt_path = manager.create("feat/login", &base).unwrap();
+        assert_eq!(wt_path, base.join("feat").join("login"));
+        assert_eq!(
+            manager.branch_for_path(&wt_path).unwrap().as_deref(),
+            Some("feat/login")
+        );
+        assert_eq!(manager.path_for_branch("feat/login").unwrap(), Some(wt_path.clone()));
+
+        // 空になった中間ディレクトリとベースディレクトリも片付ける
+        manager.remove(&wt_path, false).unwrap();
+        assert!(!base.exists());
+        assert_eq!(manager.path_for_branch("feat/login").unwrap(), None);
+    }
+
+    #[test]
+    fn test_hash_naming_lookup_by_branch() {
+        let (temp, manager) = setup_test_repo();
+        let manager = manager.with_naming(WorktreeNaming::Hash);
+
+        let wt_path = manager.create("cctakt/task", temp.path()).unwrap();
+        assert!(!wt_path.to_str().unwrap().contains("task"));
+        assert_eq!(manager.path_for_branch("cctakt/task").unwrap(), Some(wt_path.clone()));
+        assert_eq!(
+            manager.branch_for_path(&wt_path).unwrap().as_deref(),
+            Some("cctakt/task")
+        );
+    }
+
+    #[test]
+    fn test_lookup_falls_back_to_git_without_index() {
+        let (temp, manager) = setup_test_repo();
+
+        let wt_path = temp.path().join("manual");
+        Command::new("git")
+            .current_dir(temp.path())
+            .args(["worktree", "add", "-b", "manual/branch", wt_path.to_str().unwrap()])
+            .output()
+            .unwrap();
+
+        assert_eq!(
+            manager.branch_for_path(&wt_path).unwrap().as_deref(),
+            Some("manual/branch")
+        );
+        assert!(manager.path_for_branch("manual/branch").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_checkout_existing_branch() {
+        let (temp, manager) = setup_test_repo();
+        Command::new("git")
+            .current_dir(temp.path())
+            .args(["branch", "existing"])
+            .output()
+            .unwrap();
+
+        let wt_path = manager.checkout("existing", temp.path()).unwrap();
+        assert!(wt_path.exists());
+        assert_eq!(manager.path_for_branch("existing").unwrap(), Some(wt_path));
+        assert!(!manager.branch_exists("existing-2").unwrap());
+
+        assert!(manager.checkout("missing", temp.path()).is_err());
+    }
+
+    #[test]
+    fn test_remove_missing_worktree_prunes() {
+        let (temp, manager) = setup_test_repo();
+
+        let wt_path = manager.create("gone", temp.path()).unwrap();
+        std::fs::remove_dir_all(&wt_path).unwrap();
+
+        assert_eq!(
+            manager.remove(&wt_path, false).unwrap(),
+            RemoveOutcome::AlreadyGone
+        );
+        assert_eq!(manager.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_remove_dirty_worktree_requires_force() {
+        let (temp, manager) = setup_test_repo();
+
+        let wt_path = manager.create("dirty", temp.path()).unwrap();
+        std::fs::write(wt_path.join("wip.txt"), "x").unwrap();
+
+        assert_eq!(
+            manager.remove(&wt_path, false).unwrap(),
+            RemoveOutcome::RefusedDirty(vec!["?? wip.txt".to_string()])
+        );
+        assert!(wt_path.exists());
+
+        assert_eq!(manager.remove(&wt_path, true).unwrap(), RemoveOutcome::Removed);
+        assert!(!wt_path.exists());
+    }
+
+    #[test]
+    fn test_remove_locked_worktree() {
+        let (temp, manager) = setup_test_repo();
+
+        let wt_path = manager.create("locked", temp.path()).unwrap();
+        Command::new("git")
+            .current_dir(temp.path())
+            .args(["worktree", "lock", "--reason", "testing", wt_path.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(manager.find_by_branch("locked").unwrap().unwrap().locked);
+
+        assert_eq!(manager.remove(&wt_path, false).unwrap(), RemoveOutcome::Removed);
+        assert!(!wt_path.exists());
+        assert_eq!(manager.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_worktree_list() {
+        let output = "worktree /repo\nHEAD abc123\nbranch refs/heads/main\n\nworktree /repo/.worktrees/feat\nHEAD def456\nbranch refs/heads/feat\nlocked busy\n\nworktree /repo/.worktrees/detached\nHEAD 789abc\ndetached\n";
+        let list = parse_worktree_list(output, Path::new("/repo"));
+
+        assert_eq!(list.len(), 3);
+        assert!(list[0].is_main);
+        assert_eq!(list[0].head, "abc123");
+        assert_eq!(list[1].branch, "feat");
+        assert_eq!(list[1].head, "def456");
+        assert!(!list[1].is_main);
+        assert!(list[1].locked);
+        assert!(!list[0].locked);
+        assert_eq!(list[2].branch, "");
+        assert_eq!(list[2].head, "789abc");
+    }
+
+    #[test]
+    fn test_find_by_branch_and_is_dirty() {
+        let (temp, manager) = setup_test_repo();
+
+        let wt_path = manager.create("dirty", temp.path()).unwrap();
+        let found = manager.find_by_branch("dirty").unwrap().unwrap();
+        assert!(!found.head.is_empty());
+        assert!(manager.find_by_branch("missing").unwrap().is_none());
+
+        assert!(!WorktreeManager::is_dirty(&wt_path).unwrap());
+        std::fs::write(wt_path.join("new.txt"), "x").unwrap();
+        assert!(WorktreeManager::is_dirty(&wt_path).unwrap());
+        assert_eq!(
+            WorktreeManager::dirty_files(&wt_path).unwrap(),
+            vec!["?? new.txt".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_current_branch() {
+        let (temp, manager) = setup_test_repo();
+
+        let wt_path = manager.create("feat/current", temp.path()).unwrap();
+        assert_eq!(
+            WorktreeManager::current_branch(&wt_path).unwrap().as_deref(),
+            Some("feat/current")
+        );
+
+        // detached HEADではNone
+        Command::new("git")
+            .current_dir(&wt_path)
+            .args(["checkout", "--detach"])
+            .output()
+            .unwrap();
+        assert_eq!(WorktreeManager::current_branch(&wt_path).unwrap(), None);
+
+        // gitリポジトリでなければエラー
+        let outside = TempDir::new().unwrap();
+        assert!(WorktreeManager::current_branch(outside.path()).is_err());
+    }
+
+    #[test]
+    fn test_prune_removes_stale_worktree() {
+        let (temp, manager) = setup_test_repo();
+
+        let wt_path = manager.create("stale", temp.path()).unwrap();
+        std::fs::remove_dir_all(&wt_path).unwrap();
+        assert_eq!(manager.list().unwrap().len(), 2);
+
+        let pruned = manager.prune().unwrap();
+        assert!(!pruned.is_empty());
+        assert_eq!(manager.list().unwrap().len(), 1);
+    }
+
     #[test]
     fn test_create_with_relative_base_dir() {
         let (_temp, manager) = setup_test_repo();
@@ -386,6 +1221,6 @@ mod tests {
         assert!(wt_path.to_str().unwrap().contains("feature-new"));
 
         // クリーンアップ
-        manager.remove(&wt_path).unwrap();
+        manager.remove(&wt_path, false).unwrap();
     }
 }